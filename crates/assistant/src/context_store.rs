@@ -1,4 +1,4 @@
-use crate::slash_command::context_server_command;
+use crate::slash_command::{context_server_command, context_server_resource_command};
 use crate::SlashCommandId;
 use crate::{
     prompts::PromptBuilder, slash_command_working_set::SlashCommandWorkingSet, Context,
@@ -854,6 +854,24 @@ impl ContextStore {
                                 }
                             }
 
+                            if protocol.capable(context_server::protocol::ServerCapability::Resources) {
+                                log::info!("registering context server resource command: {:?}", server_id);
+                                let slash_command_id = slash_command_working_set.insert(Arc::new(
+                                    context_server_resource_command::ContextServerResourceSlashCommand::new(
+                                        context_server_manager.clone(),
+                                        &server,
+                                    ),
+                                ));
+
+                                this.update(&mut cx, |this, _cx| {
+                                    this.context_server_slash_command_ids
+                                        .entry(server_id.clone())
+                                        .or_default()
+                                        .push(slash_command_id);
+                                })
+                                .log_err();
+                            }
+
                             if protocol.capable(context_server::protocol::ServerCapability::Tools) {
                                 if let Some(tools) = protocol.list_tools().await.log_err() {
                                     let tool_ids = tools.tools.into_iter().map(|tool| {
@@ -892,6 +910,7 @@ impl ContextStore {
                     tool_working_set.remove(&tool_ids);
                 }
             }
+            context_server::manager::Event::ResourceUpdated { .. } => {}
         }
     }
 }
@@ -0,0 +1,136 @@
+use anyhow::{anyhow, Result};
+use assistant_slash_command::{
+    AfterCompletion, ArgumentCompletion, SlashCommand, SlashCommandOutput,
+    SlashCommandOutputSection, SlashCommandResult,
+};
+use context_server::manager::{ContextServer, ContextServerManager};
+use gpui::{Model, Task, WeakView, WindowContext};
+use language::{BufferSnapshot, CodeLabel, LspAdapterDelegate};
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use text::LineEnding;
+use ui::{IconName, SharedString};
+use workspace::Workspace;
+
+/// Inserts the contents of one of the resources of a context server.
+pub struct ContextServerResourceSlashCommand {
+    server_manager: Model<ContextServerManager>,
+    server_id: Arc<str>,
+}
+
+impl ContextServerResourceSlashCommand {
+    pub fn new(server_manager: Model<ContextServerManager>, server: &Arc<ContextServer>) -> Self {
+        Self {
+            server_id: server.id(),
+            server_manager,
+        }
+    }
+}
+
+impl SlashCommand for ContextServerResourceSlashCommand {
+    fn name(&self) -> String {
+        format!("{}-resource", self.server_id)
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::FileText
+    }
+
+    fn description(&self) -> String {
+        format!("Insert a resource from {}", self.server_id)
+    }
+
+    fn menu_text(&self) -> String {
+        self.description()
+    }
+
+    fn requires_argument(&self) -> bool {
+        true
+    }
+
+    fn complete_argument(
+        self: Arc<Self>,
+        arguments: &[String],
+        _cancel: Arc<AtomicBool>,
+        _workspace: Option<WeakView<Workspace>>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<Vec<ArgumentCompletion>>> {
+        let query = arguments.join(" ").to_lowercase();
+
+        if let Some(server) = self.server_manager.read(cx).get_server(&self.server_id) {
+            cx.foreground_executor().spawn(async move {
+                let Some(protocol) = server.client() else {
+                    return Err(anyhow!("Context server not initialized"));
+                };
+
+                let completions = protocol
+                    .list_resources()
+                    .await?
+                    .resources
+                    .into_iter()
+                    .filter(|resource| {
+                        resource.name.to_lowercase().contains(&query)
+                            || resource.uri.as_str().to_lowercase().contains(&query)
+                    })
+                    .map(|resource| ArgumentCompletion {
+                        label: CodeLabel::plain(resource.name, None),
+                        new_text: resource.uri.to_string(),
+                        after_completion: AfterCompletion::Run,
+                        replace_previous_arguments: true,
+                    })
+                    .collect();
+                Ok(completions)
+            })
+        } else {
+            Task::ready(Err(anyhow!("Context server not found")))
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        arguments: &[String],
+        _context_slash_command_output_sections: &[SlashCommandOutputSection<language::Anchor>],
+        _context_buffer: BufferSnapshot,
+        _workspace: WeakView<Workspace>,
+        _delegate: Option<Arc<dyn LspAdapterDelegate>>,
+        cx: &mut WindowContext,
+    ) -> Task<SlashCommandResult> {
+        let Some(uri) = arguments.first().cloned() else {
+            return Task::ready(Err(anyhow!("Missing resource URI")));
+        };
+
+        if let Some(server) = self.server_manager.read(cx).get_server(&self.server_id) {
+            cx.foreground_executor().spawn(async move {
+                let Some(protocol) = server.client() else {
+                    return Err(anyhow!("Context server not initialized"));
+                };
+
+                let resource = protocol
+                    .list_resources()
+                    .await?
+                    .resources
+                    .into_iter()
+                    .find(|resource| resource.uri.as_str() == uri)
+                    .ok_or_else(|| anyhow!("Resource {uri} not found"))?;
+                let mut text = protocol.read_resource(resource.uri).await?.text();
+
+                // We must normalize the line endings here, since servers might return CR characters.
+                LineEnding::normalize(&mut text);
+
+                Ok(SlashCommandOutput {
+                    sections: vec![SlashCommandOutputSection {
+                        range: 0..text.len(),
+                        icon: IconName::FileText,
+                        label: SharedString::from(resource.name),
+                        metadata: None,
+                    }],
+                    text,
+                    run_commands_in_text: false,
+                }
+                .to_event_stream())
+            })
+        } else {
+            Task::ready(Err(anyhow!("Context server not found")))
+        }
+    }
+}
@@ -1,10 +1,13 @@
 use std::sync::Arc;
 
+use anyhow::anyhow;
 use assistant_tool::ToolWorkingSet;
 use collections::HashMap;
+use futures::future::Shared;
+use futures::FutureExt as _;
 use gpui::{
-    list, AnyElement, AppContext, Empty, ListAlignment, ListState, Model, StyleRefinement,
-    Subscription, TextStyleRefinement, View, WeakView,
+    list, AnyElement, AppContext, Empty, ListAlignment, ListState, Model, PromptLevel,
+    StyleRefinement, Subscription, Task, TextStyleRefinement, View, WeakView,
 };
 use language::LanguageRegistry;
 use language_model::Role;
@@ -67,6 +70,10 @@ impl ActiveThread {
         this
     }
 
+    pub fn thread(&self) -> &Model<Thread> {
+        &self.thread
+    }
+
     pub fn is_empty(&self) -> bool {
         self.messages.is_empty()
     }
@@ -174,9 +181,34 @@ impl ActiveThread {
                     .cloned()
                     .collect::<Vec<_>>();
 
+                let tool_uses_needing_confirmation = pending_tool_uses
+                    .iter()
+                    .filter_map(|tool_use| {
+                        let tool = self.tools.tool(&tool_use.name, cx)?;
+                        tool.needs_confirmation()
+                            .then(|| tool.confirmation_message(&tool_use.input))
+                    })
+                    .collect::<Vec<_>>();
+                let confirmation = (!tool_uses_needing_confirmation.is_empty())
+                    .then(|| self.confirm_tool_uses(&tool_uses_needing_confirmation, cx));
+
                 for tool_use in pending_tool_uses {
                     if let Some(tool) = self.tools.tool(&tool_use.name, cx) {
-                        let task = tool.run(tool_use.input, self.workspace.clone(), cx);
+                        let task = match confirmation.clone() {
+                            Some(confirmation) if tool.needs_confirmation() => {
+                                let workspace = self.workspace.clone();
+                                cx.spawn(|_, mut cx| async move {
+                                    if !confirmation.await {
+                                        return Err(anyhow!(
+                                            "The user declined to let the tool run"
+                                        ));
+                                    }
+                                    cx.update(|cx| tool.run(tool_use.input, workspace, cx))?
+                                        .await
+                                })
+                            }
+                            _ => tool.run(tool_use.input, self.workspace.clone(), cx),
+                        };
 
                         self.thread.update(cx, |thread, cx| {
                             thread.insert_tool_output(
@@ -193,6 +225,24 @@ impl ActiveThread {
         }
     }
 
+    /// Asks the user once whether the tools that modify the project or run commands may run,
+    /// listing what each use does, and resolves to whether they allowed it.
+    fn confirm_tool_uses(
+        &self,
+        messages: &[String],
+        cx: &mut ViewContext<Self>,
+    ) -> Shared<Task<bool>> {
+        let answer = cx.prompt(
+            PromptLevel::Warning,
+            "Allow the assistant to use these tools?",
+            Some(&messages.join("\n")),
+            &["Allow", "Deny"],
+        );
+        cx.background_executor()
+            .spawn(async move { answer.await == Ok(0) })
+            .shared()
+    }
+
     fn render_message(&self, ix: usize, cx: &mut ViewContext<Self>) -> AnyElement {
         let message_id = self.messages[ix];
         let Some(message) = self.thread.read(cx).message(message_id) else {
@@ -39,6 +39,7 @@ actions!(
         ToggleContextPicker,
         ToggleModelSelector,
         OpenHistory,
+        ExportThreadToMarkdown,
         Chat,
         CycleNextInlineAssist,
         CyclePreviousInlineAssist
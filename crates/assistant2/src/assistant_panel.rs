@@ -3,6 +3,7 @@ use std::sync::Arc;
 use anyhow::Result;
 use assistant_tool::ToolWorkingSet;
 use client::zed_urls;
+use editor::{Editor, MultiBuffer};
 use fs::Fs;
 use gpui::{
     prelude::*, px, svg, Action, AnyElement, AppContext, AsyncWindowContext, EventEmitter,
@@ -13,6 +14,7 @@ use language::LanguageRegistry;
 use settings::Settings;
 use time::UtcOffset;
 use ui::{prelude::*, KeyBinding, Tab, Tooltip};
+use util::ResultExt as _;
 use workspace::dock::{DockPosition, Panel, PanelEvent};
 use workspace::Workspace;
 
@@ -22,7 +24,7 @@ use crate::message_editor::MessageEditor;
 use crate::thread::{ThreadError, ThreadId};
 use crate::thread_history::{PastThread, ThreadHistory};
 use crate::thread_store::ThreadStore;
-use crate::{NewThread, OpenHistory, ToggleFocus};
+use crate::{ExportThreadToMarkdown, NewThread, OpenHistory, ToggleFocus};
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(
@@ -42,6 +44,11 @@ pub fn init(cx: &mut AppContext) {
                         workspace.focus_panel::<AssistantPanel>(cx);
                         panel.update(cx, |panel, cx| panel.open_history(cx));
                     }
+                })
+                .register_action(|workspace, _: &ExportThreadToMarkdown, cx| {
+                    if let Some(panel) = workspace.panel::<AssistantPanel>(cx) {
+                        panel.update(cx, |panel, cx| panel.export_thread_to_markdown(cx));
+                    }
                 });
         },
     )
@@ -206,6 +213,44 @@ impl AssistantPanel {
         self.message_editor.focus_handle(cx).focus(cx);
     }
 
+    /// Opens the active thread as Markdown in a new buffer, from which it can be saved or shared.
+    fn export_thread_to_markdown(&mut self, cx: &mut ViewContext<Self>) {
+        let thread = self.thread.read(cx).thread().read(cx);
+        if thread.is_empty() {
+            return;
+        }
+        let markdown = thread.to_markdown();
+        let title: SharedString = thread.summary().unwrap_or("New Thread".into());
+        let language = self.language_registry.language_for_name("Markdown");
+        let workspace = self.workspace.clone();
+
+        cx.spawn(|_, mut cx| async move {
+            let language = language.await.log_err();
+            workspace
+                .update(&mut cx, |workspace, cx| {
+                    workspace.with_local_workspace(cx, |workspace, cx| {
+                        let project = workspace.project().clone();
+                        let buffer = project.update(cx, |project, cx| {
+                            project.create_local_buffer(&markdown, language, cx)
+                        });
+                        let buffer = cx.new_model(|cx| {
+                            MultiBuffer::singleton(buffer, cx).with_title(title.to_string())
+                        });
+                        workspace.add_item_to_active_pane(
+                            Box::new(cx.new_view(|cx| {
+                                Editor::for_multibuffer(buffer, Some(project), true, cx)
+                            })),
+                            None,
+                            true,
+                            cx,
+                        );
+                    })
+                })?
+                .await
+        })
+        .detach_and_log_err(cx);
+    }
+
     pub(crate) fn delete_thread(&mut self, thread_id: &ThreadId, cx: &mut ViewContext<Self>) {
         self.thread_store
             .update(cx, |this, cx| this.delete_thread(thread_id, cx));
@@ -347,6 +392,26 @@ impl AssistantPanel {
                                 cx.dispatch_action(OpenHistory.boxed_clone());
                             }),
                     )
+                    .child(
+                        IconButton::new("export-thread", IconName::FileText)
+                            .icon_size(IconSize::Small)
+                            .style(ButtonStyle::Subtle)
+                            .disabled(self.thread.read(cx).is_empty())
+                            .tooltip({
+                                let focus_handle = focus_handle.clone();
+                                move |cx| {
+                                    Tooltip::for_action_in(
+                                        "Export Thread to Markdown",
+                                        &ExportThreadToMarkdown,
+                                        &focus_handle,
+                                        cx,
+                                    )
+                                }
+                            })
+                            .on_click(move |_event, cx| {
+                                cx.dispatch_action(ExportThreadToMarkdown.boxed_clone());
+                            }),
+                    )
                     .child(
                         IconButton::new("configure-assistant", IconName::Settings)
                             .icon_size(IconSize::Small)
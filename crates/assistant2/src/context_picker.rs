@@ -1,6 +1,7 @@
 mod directory_context_picker;
 mod fetch_context_picker;
 mod file_context_picker;
+mod resource_context_picker;
 mod thread_context_picker;
 
 use std::sync::Arc;
@@ -18,6 +19,7 @@ use crate::context::ContextKind;
 use crate::context_picker::directory_context_picker::DirectoryContextPicker;
 use crate::context_picker::fetch_context_picker::FetchContextPicker;
 use crate::context_picker::file_context_picker::FileContextPicker;
+use crate::context_picker::resource_context_picker::ResourceContextPicker;
 use crate::context_picker::thread_context_picker::ThreadContextPicker;
 use crate::context_store::ContextStore;
 use crate::thread_store::ThreadStore;
@@ -29,6 +31,7 @@ enum ContextPickerMode {
     Directory(View<DirectoryContextPicker>),
     Fetch(View<FetchContextPicker>),
     Thread(View<ThreadContextPicker>),
+    Resource(View<ResourceContextPicker>),
 }
 
 pub(super) struct ContextPicker {
@@ -67,6 +70,11 @@ impl ContextPicker {
                 kind: ContextKind::Thread,
                 icon: IconName::MessageCircle,
             });
+            entries.push(ContextPickerEntry {
+                name: "Resource".into(),
+                kind: ContextKind::Resource,
+                icon: IconName::FileText,
+            });
         }
 
         let delegate = ContextPickerDelegate {
@@ -103,6 +111,7 @@ impl FocusableView for ContextPicker {
             ContextPickerMode::Directory(directory_picker) => directory_picker.focus_handle(cx),
             ContextPickerMode::Fetch(fetch_picker) => fetch_picker.focus_handle(cx),
             ContextPickerMode::Thread(thread_picker) => thread_picker.focus_handle(cx),
+            ContextPickerMode::Resource(resource_picker) => resource_picker.focus_handle(cx),
         }
     }
 }
@@ -120,6 +129,9 @@ impl Render for ContextPicker {
                 }
                 ContextPickerMode::Fetch(fetch_picker) => parent.child(fetch_picker.clone()),
                 ContextPickerMode::Thread(thread_picker) => parent.child(thread_picker.clone()),
+                ContextPickerMode::Resource(resource_picker) => {
+                    parent.child(resource_picker.clone())
+                }
             })
     }
 }
@@ -211,6 +223,18 @@ impl PickerDelegate for ContextPickerDelegate {
                                 }));
                             }
                         }
+                        ContextKind::Resource => {
+                            if let Some(thread_store) = self.thread_store.as_ref() {
+                                this.mode = ContextPickerMode::Resource(cx.new_view(|cx| {
+                                    ResourceContextPicker::new(
+                                        thread_store.clone(),
+                                        self.context_picker.clone(),
+                                        self.context_store.clone(),
+                                        cx,
+                                    )
+                                }));
+                            }
+                        }
                     }
 
                     cx.focus_self();
@@ -226,7 +250,8 @@ impl PickerDelegate for ContextPickerDelegate {
                 ContextPickerMode::File(_)
                 | ContextPickerMode::Directory(_)
                 | ContextPickerMode::Fetch(_)
-                | ContextPickerMode::Thread(_) => {}
+                | ContextPickerMode::Thread(_)
+                | ContextPickerMode::Resource(_) => {}
             })
             .log_err();
     }
@@ -0,0 +1,253 @@
+use std::sync::Arc;
+
+use context_server::protocol::ServerCapability;
+use context_server::types::Resource;
+use fuzzy::StringMatchCandidate;
+use gpui::{AppContext, DismissEvent, FocusHandle, FocusableView, Task, View, WeakModel, WeakView};
+use picker::{Picker, PickerDelegate};
+use ui::{prelude::*, ListItem};
+use util::ResultExt as _;
+
+use crate::context_picker::ContextPicker;
+use crate::context_store::ContextStore;
+use crate::thread_store::ThreadStore;
+
+pub struct ResourceContextPicker {
+    picker: View<Picker<ResourceContextPickerDelegate>>,
+}
+
+impl ResourceContextPicker {
+    pub fn new(
+        thread_store: WeakModel<ThreadStore>,
+        context_picker: WeakView<ContextPicker>,
+        context_store: WeakModel<ContextStore>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let delegate =
+            ResourceContextPickerDelegate::new(thread_store.clone(), context_picker, context_store);
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        Self::load_resources(&thread_store, picker.downgrade(), cx);
+
+        ResourceContextPicker { picker }
+    }
+
+    /// Lists the resources of all the context servers that provide some, refreshing the picker
+    /// once they are known.
+    fn load_resources(
+        thread_store: &WeakModel<ThreadStore>,
+        picker: WeakView<Picker<ResourceContextPickerDelegate>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(thread_store) = thread_store.upgrade() else {
+            return;
+        };
+        let servers = thread_store
+            .read(cx)
+            .context_server_manager()
+            .read(cx)
+            .servers();
+
+        cx.spawn(|_, mut cx| async move {
+            let mut resources = Vec::new();
+            for server in servers {
+                let Some(protocol) = server
+                    .client()
+                    .filter(|protocol| protocol.capable(ServerCapability::Resources))
+                else {
+                    continue;
+                };
+                if let Some(response) = protocol.list_resources().await.log_err() {
+                    resources.extend(response.resources.into_iter().map(|resource| {
+                        ResourceContextEntry {
+                            server_id: server.id(),
+                            resource,
+                        }
+                    }));
+                }
+            }
+
+            picker.update(&mut cx, |picker, cx| {
+                picker.delegate.resources = resources;
+                picker.refresh(cx);
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+}
+
+impl FocusableView for ResourceContextPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for ResourceContextPicker {
+    fn render(&mut self, _cx: &mut ViewContext<Self>) -> impl IntoElement {
+        self.picker.clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct ResourceContextEntry {
+    server_id: Arc<str>,
+    resource: Resource,
+}
+
+pub struct ResourceContextPickerDelegate {
+    thread_store: WeakModel<ThreadStore>,
+    context_picker: WeakView<ContextPicker>,
+    context_store: WeakModel<ContextStore>,
+    resources: Vec<ResourceContextEntry>,
+    matches: Vec<ResourceContextEntry>,
+    selected_index: usize,
+}
+
+impl ResourceContextPickerDelegate {
+    pub fn new(
+        thread_store: WeakModel<ThreadStore>,
+        context_picker: WeakView<ContextPicker>,
+        context_store: WeakModel<ContextStore>,
+    ) -> Self {
+        ResourceContextPickerDelegate {
+            thread_store,
+            context_picker,
+            context_store,
+            resources: Vec::new(),
+            matches: Vec::new(),
+            selected_index: 0,
+        }
+    }
+}
+
+impl PickerDelegate for ResourceContextPickerDelegate {
+    type ListItem = ListItem;
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _cx: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn placeholder_text(&self, _cx: &mut WindowContext) -> Arc<str> {
+        "Search resources…".into()
+    }
+
+    fn no_matches_text(&self, _cx: &mut WindowContext) -> SharedString {
+        "No context server resources".into()
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let resources = self.resources.clone();
+
+        let executor = cx.background_executor().clone();
+        let search_task = cx.background_executor().spawn(async move {
+            if query.is_empty() {
+                resources
+            } else {
+                let candidates = resources
+                    .iter()
+                    .enumerate()
+                    .map(|(id, entry)| StringMatchCandidate::new(id, &entry.resource.name))
+                    .collect::<Vec<_>>();
+                let matches = fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    false,
+                    100,
+                    &Default::default(),
+                    executor,
+                )
+                .await;
+
+                matches
+                    .into_iter()
+                    .map(|mat| resources[mat.candidate_id].clone())
+                    .collect()
+            }
+        });
+
+        cx.spawn(|this, mut cx| async move {
+            let matches = search_task.await;
+            this.update(&mut cx, |this, cx| {
+                this.delegate.matches = matches;
+                this.delegate.selected_index = 0;
+                cx.notify();
+            })
+            .ok();
+        })
+    }
+
+    fn confirm(&mut self, _secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(entry) = self.matches.get(self.selected_index).cloned() else {
+            return;
+        };
+
+        let Some(thread_store) = self.thread_store.upgrade() else {
+            return;
+        };
+        let context_server_manager = thread_store.read(cx).context_server_manager();
+        let Some(protocol) = context_server_manager
+            .read(cx)
+            .get_server(&entry.server_id)
+            .and_then(|server| server.client())
+        else {
+            return;
+        };
+
+        let context_store = self.context_store.clone();
+        cx.spawn(|_, mut cx| async move {
+            let text = protocol
+                .read_resource(entry.resource.uri.clone())
+                .await?
+                .text();
+
+            context_store.update(&mut cx, |context_store, cx| {
+                context_store.insert_resource_context(
+                    context_server_manager,
+                    entry.server_id,
+                    entry.resource,
+                    text,
+                    cx,
+                );
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        self.context_picker
+            .update(cx, |this, cx| {
+                this.reset_mode();
+                cx.emit(DismissEvent);
+            })
+            .ok();
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _cx: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let entry = &self.matches[ix];
+
+        Some(
+            ListItem::new(ix).inset(true).toggle_state(selected).child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(entry.resource.name.clone()).single_line())
+                    .child(
+                        Label::new(entry.server_id.to_string())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted),
+                    ),
+            ),
+        )
+    }
+}
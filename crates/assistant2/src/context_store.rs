@@ -1,10 +1,26 @@
-use gpui::SharedString;
+use std::sync::Arc;
+
+use collections::HashMap;
+use context_server::manager::{ContextServerManager, Event as ContextServerEvent};
+use context_server::types::Resource;
+use gpui::{Model, ModelContext, SharedString, Subscription};
+use url::Url;
 
 use crate::context::{Context, ContextId, ContextKind};
 
 pub struct ContextStore {
     context: Vec<Context>,
     next_context_id: ContextId,
+    resource_subscriptions: HashMap<ContextId, ResourceSubscription>,
+}
+
+/// Keeps the text of a resource context up to date with the updates the context server
+/// reports for it.
+struct ResourceSubscription {
+    context_server_manager: Model<ContextServerManager>,
+    server_id: Arc<str>,
+    uri: Url,
+    _subscription: Subscription,
 }
 
 impl ContextStore {
@@ -12,6 +28,7 @@ impl ContextStore {
         Self {
             context: Vec::new(),
             next_context_id: ContextId(0),
+            resource_subscriptions: HashMap::default(),
         }
     }
 
@@ -19,11 +36,13 @@ impl ContextStore {
         &self.context
     }
 
-    pub fn drain(&mut self) -> Vec<Context> {
+    pub fn drain(&mut self, cx: &mut ModelContext<Self>) -> Vec<Context> {
+        self.unsubscribe_from_all_resources(cx);
         self.context.drain(..).collect()
     }
 
-    pub fn clear(&mut self) {
+    pub fn clear(&mut self, cx: &mut ModelContext<Self>) {
+        self.unsubscribe_from_all_resources(cx);
         self.context.clear();
     }
 
@@ -32,16 +51,143 @@ impl ContextStore {
         kind: ContextKind,
         name: impl Into<SharedString>,
         text: impl Into<SharedString>,
-    ) {
+    ) -> ContextId {
+        let id = self.next_context_id.post_inc();
         self.context.push(Context {
-            id: self.next_context_id.post_inc(),
+            id,
             name: name.into(),
             kind,
             text: text.into(),
         });
+        id
     }
 
-    pub fn remove_context(&mut self, id: &ContextId) {
+    /// Inserts the contents of a resource of a context server, and subscribes to its updates so
+    /// that the context reflects the latest contents until it is sent.
+    pub fn insert_resource_context(
+        &mut self,
+        context_server_manager: Model<ContextServerManager>,
+        server_id: Arc<str>,
+        resource: Resource,
+        text: impl Into<SharedString>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let id = self.insert_context(ContextKind::Resource, resource.name, text);
+        let uri = resource.uri;
+
+        let subscription = cx.subscribe(&context_server_manager, {
+            let server_id = server_id.clone();
+            let uri = uri.clone();
+            move |this, context_server_manager, event, cx| {
+                if let ContextServerEvent::ResourceUpdated {
+                    server_id: updated_server_id,
+                    uri: updated_uri,
+                } = event
+                {
+                    if *updated_server_id == server_id && *updated_uri == uri {
+                        this.reload_resource(id, context_server_manager, &server_id, &uri, cx);
+                    }
+                }
+            }
+        });
+
+        let already_subscribed = self
+            .resource_subscriptions
+            .values()
+            .any(|subscription| subscription.server_id == server_id && subscription.uri == uri);
+        if !already_subscribed {
+            if let Some(protocol) = context_server_manager
+                .read(cx)
+                .get_server(&server_id)
+                .and_then(|server| server.client())
+                .filter(|protocol| protocol.can_subscribe_to_resources())
+            {
+                let uri = uri.clone();
+                cx.spawn(|_, _| async move { protocol.subscribe_to_resource(uri).await })
+                    .detach_and_log_err(cx);
+            }
+        }
+
+        self.resource_subscriptions.insert(
+            id,
+            ResourceSubscription {
+                context_server_manager,
+                server_id,
+                uri,
+                _subscription: subscription,
+            },
+        );
+    }
+
+    pub fn remove_context(&mut self, id: &ContextId, cx: &mut ModelContext<Self>) {
         self.context.retain(|context| context.id != *id);
+        self.unsubscribe_from_resource(id, cx);
+    }
+
+    fn reload_resource(
+        &mut self,
+        id: ContextId,
+        context_server_manager: Model<ContextServerManager>,
+        server_id: &str,
+        uri: &Url,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(protocol) = context_server_manager
+            .read(cx)
+            .get_server(server_id)
+            .and_then(|server| server.client())
+        else {
+            return;
+        };
+
+        let uri = uri.clone();
+        cx.spawn(|this, mut cx| async move {
+            let text = protocol.read_resource(uri).await?.text();
+            this.update(&mut cx, |this, cx| {
+                if let Some(context) = this.context.iter_mut().find(|context| context.id == id) {
+                    context.text = text.into();
+                    cx.notify();
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn unsubscribe_from_all_resources(&mut self, cx: &mut ModelContext<Self>) {
+        let ids = self
+            .resource_subscriptions
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        for id in ids {
+            self.unsubscribe_from_resource(&id, cx);
+        }
+    }
+
+    /// Drops the subscription of a resource context, telling the server once no other context
+    /// needs the updates of the resource.
+    fn unsubscribe_from_resource(&mut self, id: &ContextId, cx: &mut ModelContext<Self>) {
+        let Some(subscription) = self.resource_subscriptions.remove(id) else {
+            return;
+        };
+
+        let still_subscribed = self.resource_subscriptions.values().any(|other| {
+            other.server_id == subscription.server_id && other.uri == subscription.uri
+        });
+        if still_subscribed {
+            return;
+        }
+
+        if let Some(protocol) = subscription
+            .context_server_manager
+            .read(cx)
+            .get_server(&subscription.server_id)
+            .and_then(|server| server.client())
+            .filter(|protocol| protocol.can_subscribe_to_resources())
+        {
+            let uri = subscription.uri;
+            cx.spawn(|_, _| async move { protocol.unsubscribe_from_resource(uri).await })
+                .detach_and_log_err(cx);
+        }
     }
 }
@@ -1,3 +1,4 @@
+use std::fmt::Write as _;
 use std::sync::Arc;
 
 use anyhow::Result;
@@ -92,6 +93,148 @@ impl Thread {
         }
     }
 
+    /// Restores a thread stored on disk.
+    pub fn deserialize(
+        serialized: SerializedThread,
+        tools: Arc<ToolWorkingSet>,
+        _cx: &mut ModelContext<Self>,
+    ) -> Self {
+        let next_message_id = serialized
+            .messages
+            .iter()
+            .map(|message| message.id.0 + 1)
+            .max()
+            .unwrap_or(0);
+
+        let mut messages = Vec::with_capacity(serialized.messages.len());
+        let mut context_by_message = HashMap::default();
+        let mut tool_uses_by_message = HashMap::default();
+        let mut tool_results_by_message = HashMap::default();
+        for message in serialized.messages {
+            if !message.context.is_empty() {
+                context_by_message.insert(message.id, message.context);
+            }
+            if !message.tool_uses.is_empty() {
+                tool_uses_by_message.insert(message.id, message.tool_uses);
+            }
+            if !message.tool_results.is_empty() {
+                tool_results_by_message.insert(message.id, message.tool_results);
+            }
+            messages.push(Message {
+                id: message.id,
+                role: message.role,
+                text: message.text,
+            });
+        }
+
+        Self {
+            id: serialized.id,
+            updated_at: serialized.updated_at,
+            summary: serialized.summary,
+            pending_summary: Task::ready(None),
+            messages,
+            next_message_id: MessageId(next_message_id),
+            context_by_message,
+            completion_count: 0,
+            pending_completions: Vec::new(),
+            tools,
+            tool_uses_by_message,
+            tool_results_by_message,
+            pending_tool_uses_by_id: HashMap::default(),
+        }
+    }
+
+    pub fn serialize(&self) -> SerializedThread {
+        SerializedThread {
+            id: self.id.clone(),
+            summary: self.summary.clone(),
+            updated_at: self.updated_at,
+            messages: self
+                .messages
+                .iter()
+                .map(|message| SerializedMessage {
+                    id: message.id,
+                    role: message.role,
+                    text: message.text.clone(),
+                    context: self
+                        .context_by_message
+                        .get(&message.id)
+                        .cloned()
+                        .unwrap_or_default(),
+                    tool_uses: self
+                        .tool_uses_by_message
+                        .get(&message.id)
+                        .cloned()
+                        .unwrap_or_default(),
+                    tool_results: self
+                        .tool_results_by_message
+                        .get(&message.id)
+                        .cloned()
+                        .unwrap_or_default(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Renders the thread as a Markdown document, for sharing it outside of Zed.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::new();
+        let summary = self.summary.as_deref().unwrap_or("New Thread");
+        writeln!(markdown, "# {summary}\n").ok();
+
+        for message in &self.messages {
+            let role = match message.role {
+                Role::User => "User",
+                Role::Assistant => "Assistant",
+                Role::System => "System",
+            };
+            writeln!(markdown, "## {role}\n").ok();
+
+            if let Some(context) = self.context_by_message.get(&message.id) {
+                let names = context
+                    .iter()
+                    .map(|context| format!("`{}`", context.name))
+                    .collect::<Vec<_>>();
+                writeln!(markdown, "Context: {}\n", names.join(", ")).ok();
+            }
+
+            if let Some(tool_results) = self.tool_results_by_message.get(&message.id) {
+                for tool_result in tool_results {
+                    let label = if tool_result.is_error {
+                        "Tool error"
+                    } else {
+                        "Tool result"
+                    };
+                    writeln!(
+                        markdown,
+                        "**{label}** (`{}`):\n\n```\n{}\n```\n",
+                        tool_result.tool_use_id,
+                        tool_result.content.trim_end()
+                    )
+                    .ok();
+                }
+            }
+
+            if !message.text.is_empty() {
+                writeln!(markdown, "{}\n", message.text.trim_end()).ok();
+            }
+
+            if let Some(tool_uses) = self.tool_uses_by_message.get(&message.id) {
+                for tool_use in tool_uses {
+                    let input = serde_json::to_string_pretty(&tool_use.input).unwrap_or_default();
+                    writeln!(
+                        markdown,
+                        "**Tool use** `{}` (`{}`):\n\n```json\n{input}\n```\n",
+                        tool_use.name, tool_use.id
+                    )
+                    .ok();
+                }
+            }
+        }
+
+        markdown
+    }
+
     pub fn id(&self) -> &ThreadId {
         &self.id
     }
@@ -448,6 +591,29 @@ impl Thread {
     }
 }
 
+/// A [`Thread`] as it is stored on disk.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SerializedThread {
+    pub id: ThreadId,
+    #[serde(default)]
+    pub summary: Option<SharedString>,
+    pub updated_at: DateTime<Utc>,
+    pub messages: Vec<SerializedMessage>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SerializedMessage {
+    pub id: MessageId,
+    pub role: Role,
+    pub text: String,
+    #[serde(default)]
+    pub context: Vec<Context>,
+    #[serde(default)]
+    pub tool_uses: Vec<LanguageModelToolUse>,
+    #[serde(default)]
+    pub tool_results: Vec<LanguageModelToolResult>,
+}
+
 #[derive(Debug, Clone)]
 pub enum ThreadError {
     PaymentRequired,
@@ -498,3 +664,121 @@ impl PendingToolUseStatus {
         matches!(self, PendingToolUseStatus::Idle)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::context::{ContextId, ContextKind};
+    use gpui::{Context as _, TestAppContext};
+    use indoc::indoc;
+    use serde_json::json;
+
+    #[gpui::test]
+    fn test_serialization_and_markdown(cx: &mut TestAppContext) {
+        let tools = Arc::new(ToolWorkingSet::default());
+        let thread = cx.new_model(|cx| {
+            let mut thread = Thread::new(tools.clone(), cx);
+            thread.set_summary("Reading a file", cx);
+            thread.insert_user_message(
+                "What does main.rs do?",
+                vec![Context {
+                    id: ContextId(0),
+                    name: "main.rs".into(),
+                    kind: ContextKind::File,
+                    text: "fn main() {}".into(),
+                }],
+                cx,
+            );
+            let assistant_message = thread.insert_message(Role::Assistant, "Let me read it.", cx);
+            thread.tool_uses_by_message.insert(
+                assistant_message,
+                vec![LanguageModelToolUse {
+                    id: "tool-use-1".into(),
+                    name: "read_file".into(),
+                    input: json!({ "path": "root/main.rs" }),
+                }],
+            );
+            let user_message = thread.insert_message(Role::User, "", cx);
+            thread.tool_results_by_message.insert(
+                user_message,
+                vec![LanguageModelToolResult {
+                    tool_use_id: "tool-use-1".into(),
+                    is_error: false,
+                    content: "fn main() {}\n".into(),
+                }],
+            );
+            thread
+        });
+
+        let json = thread.read_with(cx, |thread, _| {
+            serde_json::to_string(&thread.serialize()).unwrap()
+        });
+        let serialized: SerializedThread = serde_json::from_str(&json).unwrap();
+        let restored = cx.new_model(|cx| Thread::deserialize(serialized, tools, cx));
+
+        let markdown = thread.read_with(cx, |thread, _| thread.to_markdown());
+        restored.read_with(cx, |restored, cx| {
+            let thread = thread.read(cx);
+            assert_eq!(restored.id(), thread.id());
+            assert_eq!(restored.summary(), thread.summary());
+            assert_eq!(restored.updated_at(), thread.updated_at());
+            assert_eq!(
+                restored
+                    .messages()
+                    .map(|message| (message.id, message.role, message.text.clone()))
+                    .collect::<Vec<_>>(),
+                thread
+                    .messages()
+                    .map(|message| (message.id, message.role, message.text.clone()))
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(restored.tool_uses_by_message, thread.tool_uses_by_message);
+            assert_eq!(
+                restored.tool_results_by_message,
+                thread.tool_results_by_message
+            );
+            assert_eq!(
+                restored
+                    .context_for_message(MessageId(0))
+                    .map(|context| context.iter().map(|context| context.name.clone()).collect()),
+                Some(vec![SharedString::from("main.rs")])
+            );
+            assert_eq!(restored.next_message_id, MessageId(3));
+            assert_eq!(restored.to_markdown(), markdown);
+        });
+
+        assert_eq!(
+            markdown,
+            indoc! {r#"
+                # Reading a file
+
+                ## User
+
+                Context: `main.rs`
+
+                What does main.rs do?
+
+                ## Assistant
+
+                Let me read it.
+
+                **Tool use** `read_file` (`tool-use-1`):
+
+                ```json
+                {
+                  "path": "root/main.rs"
+                }
+                ```
+
+                ## User
+
+                **Tool result** (`tool-use-1`):
+
+                ```
+                fn main() {}
+                ```
+
+            "#}
+        );
+    }
+}
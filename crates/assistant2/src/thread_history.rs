@@ -1,5 +1,7 @@
+use editor::{Editor, EditorEvent};
 use gpui::{
-    uniform_list, AppContext, FocusHandle, FocusableView, Model, UniformListScrollHandle, WeakView,
+    uniform_list, AppContext, FocusHandle, FocusableView, Model, Subscription, Task,
+    UniformListScrollHandle, View, WeakView,
 };
 use time::{OffsetDateTime, UtcOffset};
 use ui::{prelude::*, IconButtonShape, ListItem, ListItemSpacing, Tooltip};
@@ -12,7 +14,11 @@ pub struct ThreadHistory {
     focus_handle: FocusHandle,
     assistant_panel: WeakView<AssistantPanel>,
     thread_store: Model<ThreadStore>,
+    search_editor: View<Editor>,
+    matches: Vec<Model<Thread>>,
     scroll_handle: UniformListScrollHandle,
+    _search_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl ThreadHistory {
@@ -21,12 +27,47 @@ impl ThreadHistory {
         thread_store: Model<ThreadStore>,
         cx: &mut ViewContext<Self>,
     ) -> Self {
-        Self {
+        let search_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Search threads...", cx);
+            editor
+        });
+        let subscriptions = vec![
+            cx.subscribe(&search_editor, |this, _, event, cx| {
+                if let EditorEvent::Edited { .. } = event {
+                    this.update_search(cx);
+                }
+            }),
+            cx.observe(&thread_store, |this, _, cx| this.update_search(cx)),
+        ];
+
+        let mut this = Self {
             focus_handle: cx.focus_handle(),
             assistant_panel,
             thread_store,
+            search_editor,
+            matches: Vec::new(),
             scroll_handle: UniformListScrollHandle::default(),
-        }
+            _search_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.update_search(cx);
+        this
+    }
+
+    fn update_search(&mut self, cx: &mut ViewContext<Self>) {
+        let query = self.search_editor.read(cx).text(cx);
+        let search = self
+            .thread_store
+            .update(cx, |thread_store, cx| thread_store.search(query, cx));
+        self._search_task = cx.spawn(|this, mut cx| async move {
+            let matches = search.await;
+            this.update(&mut cx, |this, cx| {
+                this.matches = matches;
+                cx.notify();
+            })
+            .ok();
+        });
     }
 }
 
@@ -38,7 +79,8 @@ impl FocusableView for ThreadHistory {
 
 impl Render for ThreadHistory {
     fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
-        let threads = self.thread_store.update(cx, |this, cx| this.threads(cx));
+        let threads = self.matches.clone();
+        let is_searching = !self.search_editor.read(cx).text(cx).is_empty();
 
         v_flex()
             .id("thread-history-container")
@@ -46,16 +88,28 @@ impl Render for ThreadHistory {
             .overflow_y_scroll()
             .size_full()
             .p_1()
+            .child(
+                h_flex()
+                    .w_full()
+                    .px_1()
+                    .pb_1()
+                    .child(self.search_editor.clone()),
+            )
             .map(|history| {
                 if threads.is_empty() {
-                    history
-                        .justify_center()
-                        .child(
-                            h_flex().w_full().justify_center().child(
-                                Label::new("You don't have any past threads yet.")
-                                    .size(LabelSize::Small),
-                            ),
-                        )
+                    let message = if is_searching {
+                        "No threads match your search."
+                    } else {
+                        "You don't have any past threads yet."
+                    };
+                    history.child(
+                        v_flex().flex_grow().justify_center().child(
+                            h_flex()
+                                .w_full()
+                                .justify_center()
+                                .child(Label::new(message).size(LabelSize::Small)),
+                        ),
+                    )
                 } else {
                     history.child(
                         uniform_list(
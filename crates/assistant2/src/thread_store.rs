@@ -1,24 +1,38 @@
+use std::ffi::OsStr;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::Result;
 use assistant_tool::{ToolId, ToolWorkingSet};
 use collections::HashMap;
 use context_server::manager::ContextServerManager;
 use context_server::{ContextServerFactoryRegistry, ContextServerTool};
-use gpui::{prelude::*, AppContext, Model, ModelContext, Task};
+use fs::{Fs, RemoveOptions};
+use futures::StreamExt as _;
+use fuzzy::StringMatchCandidate;
+use gpui::{prelude::*, AppContext, Model, ModelContext, SharedString, Subscription, Task};
+use paths::threads_dir;
 use project::Project;
-use unindent::Unindent;
 use util::ResultExt as _;
 
-use crate::thread::{Thread, ThreadId};
+use crate::thread::{SerializedThread, Thread, ThreadEvent, ThreadId};
+
+/// How long a thread has to stay unchanged before it is saved, so that a streaming completion
+/// doesn't write it to disk on every chunk.
+const SAVE_THREAD_DEBOUNCE: Duration = Duration::from_millis(500);
 
 pub struct ThreadStore {
     #[allow(unused)]
     project: Model<Project>,
+    fs: Arc<dyn Fs>,
     tools: Arc<ToolWorkingSet>,
     context_server_manager: Model<ContextServerManager>,
     context_server_tool_ids: HashMap<Arc<str>, Vec<ToolId>>,
     threads: Vec<Model<Thread>>,
+    /// Saves the threads when they change, until they are deleted.
+    thread_subscriptions: HashMap<ThreadId, Subscription>,
+    pending_saves: HashMap<ThreadId, Task<()>>,
 }
 
 impl ThreadStore {
@@ -27,7 +41,9 @@ impl ThreadStore {
         tools: Arc<ToolWorkingSet>,
         cx: &mut AppContext,
     ) -> Task<Result<Model<Self>>> {
+        let fs = project.read(cx).fs().clone();
         cx.spawn(|mut cx| async move {
+            let serialized_threads = Self::load_threads(fs.as_ref()).await;
             let this = cx.new_model(|cx: &mut ModelContext<Self>| {
                 let context_server_factory_registry =
                     ContextServerFactoryRegistry::default_global(cx);
@@ -37,12 +53,20 @@ impl ThreadStore {
 
                 let mut this = Self {
                     project,
+                    fs,
                     tools,
                     context_server_manager,
                     context_server_tool_ids: HashMap::default(),
                     threads: Vec::new(),
+                    thread_subscriptions: HashMap::default(),
+                    pending_saves: HashMap::default(),
                 };
-                this.mock_recent_threads(cx);
+                for serialized_thread in serialized_threads {
+                    let thread = cx.new_model(|cx| {
+                        Thread::deserialize(serialized_thread, this.tools.clone(), cx)
+                    });
+                    this.insert_thread(thread, cx);
+                }
                 this.register_context_server_handlers(cx);
 
                 this
@@ -67,12 +91,54 @@ impl ThreadStore {
         self.threads(cx).into_iter().take(limit).collect()
     }
 
+    /// Fuzzy-searches the summaries of the threads, returning the matching threads from the best
+    /// match to the worst, or all of them from the most recent when the query is empty.
+    pub fn search(&self, query: String, cx: &ModelContext<Self>) -> Task<Vec<Model<Thread>>> {
+        let threads = self.threads(cx);
+        if query.is_empty() {
+            return Task::ready(threads);
+        }
+
+        let candidates = threads
+            .iter()
+            .enumerate()
+            .map(|(id, thread)| {
+                let summary = thread.read(cx).summary().unwrap_or_default();
+                StringMatchCandidate::new(id, &summary)
+            })
+            .collect::<Vec<_>>();
+        let executor = cx.background_executor().clone();
+        cx.background_executor().spawn(async move {
+            let matches = fuzzy::match_strings(
+                &candidates,
+                &query,
+                false,
+                100,
+                &Default::default(),
+                executor,
+            )
+            .await;
+            matches
+                .into_iter()
+                .map(|mat| threads[mat.candidate_id].clone())
+                .collect()
+        })
+    }
+
     pub fn create_thread(&mut self, cx: &mut ModelContext<Self>) -> Model<Thread> {
         let thread = cx.new_model(|cx| Thread::new(self.tools.clone(), cx));
-        self.threads.push(thread.clone());
+        self.insert_thread(thread.clone(), cx);
         thread
     }
 
+    fn insert_thread(&mut self, thread: Model<Thread>, cx: &mut ModelContext<Self>) {
+        let id = thread.read(cx).id().clone();
+        self.thread_subscriptions
+            .insert(id, cx.subscribe(&thread, Self::handle_thread_event));
+        self.threads.push(thread);
+        cx.notify();
+    }
+
     pub fn open_thread(&self, id: &ThreadId, cx: &mut ModelContext<Self>) -> Option<Model<Thread>> {
         self.threads
             .iter()
@@ -82,6 +148,101 @@ impl ThreadStore {
 
     pub fn delete_thread(&mut self, id: &ThreadId, cx: &mut ModelContext<Self>) {
         self.threads.retain(|thread| thread.read(cx).id() != id);
+        self.thread_subscriptions.remove(id);
+        self.pending_saves.remove(id);
+        cx.notify();
+
+        let fs = self.fs.clone();
+        let path = thread_path(id);
+        cx.background_executor()
+            .spawn(async move {
+                fs.remove_file(
+                    &path,
+                    RemoveOptions {
+                        ignore_if_not_exists: true,
+                        ..Default::default()
+                    },
+                )
+                .await
+            })
+            .detach_and_log_err(cx);
+    }
+
+    fn handle_thread_event(
+        &mut self,
+        thread: Model<Thread>,
+        event: &ThreadEvent,
+        cx: &mut ModelContext<Self>,
+    ) {
+        match event {
+            ThreadEvent::MessageAdded(_)
+            | ThreadEvent::StreamedCompletion
+            | ThreadEvent::SummaryChanged => {
+                self.save_thread(&thread, cx);
+                cx.notify();
+            }
+            ThreadEvent::ShowError(_)
+            | ThreadEvent::StreamedAssistantText(_, _)
+            | ThreadEvent::UsePendingTools
+            | ThreadEvent::ToolFinished { .. } => {}
+        }
+    }
+
+    /// Saves the thread to disk once it stops changing for a moment. Empty threads aren't saved.
+    fn save_thread(&mut self, thread: &Model<Thread>, cx: &mut ModelContext<Self>) {
+        let id = thread.read(cx).id().clone();
+        let fs = self.fs.clone();
+        let thread = thread.downgrade();
+        let save = cx.spawn(|_, cx| async move {
+            cx.background_executor().timer(SAVE_THREAD_DEBOUNCE).await;
+            let Some(serialized_thread) = thread
+                .read_with(&cx, |thread, _| {
+                    (!thread.is_empty()).then(|| thread.serialize())
+                })
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+
+            async move {
+                let json = serde_json::to_string(&serialized_thread)?;
+                fs.create_dir(threads_dir()).await?;
+                fs.atomic_write(thread_path(&serialized_thread.id), json)
+                    .await
+            }
+            .await
+            .log_err();
+        });
+        self.pending_saves.insert(id, save);
+    }
+
+    /// Loads the threads saved in the threads directory, skipping the ones that can't be read.
+    async fn load_threads(fs: &dyn Fs) -> Vec<SerializedThread> {
+        let mut threads = Vec::new();
+        let Some(mut paths) = fs.read_dir(threads_dir()).await.ok() else {
+            return threads;
+        };
+        while let Some(path) = paths.next().await {
+            let Some(path) = path.log_err() else {
+                continue;
+            };
+            if path.extension() != Some(OsStr::new("json")) {
+                continue;
+            }
+            let thread = async {
+                let json = fs.load(&path).await?;
+                anyhow::Ok(serde_json::from_str::<SerializedThread>(&json)?)
+            };
+            if let Some(thread) = thread.await.log_err() {
+                threads.push(thread);
+            }
+        }
+        threads
+    }
+
+    pub fn context_server_manager(&self) -> Model<ContextServerManager> {
+        self.context_server_manager.clone()
     }
 
     fn register_context_server_handlers(&self, cx: &mut ModelContext<Self>) {
@@ -147,96 +308,11 @@ impl ThreadStore {
                     tool_working_set.remove(&tool_ids);
                 }
             }
+            context_server::manager::Event::ResourceUpdated { .. } => {}
         }
     }
 }
 
-impl ThreadStore {
-    /// Creates some mocked recent threads for testing purposes.
-    fn mock_recent_threads(&mut self, cx: &mut ModelContext<Self>) {
-        use language_model::Role;
-
-        self.threads.push(cx.new_model(|cx| {
-            let mut thread = Thread::new(self.tools.clone(), cx);
-            thread.set_summary("Introduction to quantum computing", cx);
-            thread.insert_user_message("Hello! Can you help me understand quantum computing?", Vec::new(), cx);
-            thread.insert_message(Role::Assistant, "Of course! I'd be happy to help you understand quantum computing. Quantum computing is a fascinating field that uses the principles of quantum mechanics to process information. Unlike classical computers that use bits (0s and 1s), quantum computers use quantum bits or 'qubits'. These qubits can exist in multiple states simultaneously, a property called superposition. This allows quantum computers to perform certain calculations much faster than classical computers. What specific aspect of quantum computing would you like to know more about?", cx);
-            thread.insert_user_message("That's interesting! Can you explain how quantum entanglement is used in quantum computing?", Vec::new(), cx);
-            thread.insert_message(Role::Assistant, "Certainly! Quantum entanglement is a key principle used in quantum computing. When two qubits become entangled, the state of one qubit is directly related to the state of the other, regardless of the distance between them. This property is used in quantum computing to create complex quantum states and to perform operations on multiple qubits simultaneously. Entanglement allows quantum computers to process information in ways that classical computers cannot, potentially solving certain problems much more efficiently. For example, it's crucial in quantum error correction and in algorithms like quantum teleportation, which is important for quantum communication.", cx);
-            thread
-        }));
-
-        self.threads.push(cx.new_model(|cx| {
-            let mut thread = Thread::new(self.tools.clone(), cx);
-            thread.set_summary("Rust web development and async programming", cx);
-            thread.insert_user_message("Can you show me an example of Rust code for a simple web server?", Vec::new(), cx);
-            thread.insert_message(Role::Assistant, "Certainly! Here's an example of a simple web server in Rust using the `actix-web` framework:
-
-        ```rust
-        use actix_web::{web, App, HttpResponse, HttpServer, Responder};
-
-        async fn hello() -> impl Responder {
-            HttpResponse::Ok().body(\"Hello, World!\")
-        }
-
-        #[actix_web::main]
-        async fn main() -> std::io::Result<()> {
-            HttpServer::new(|| {
-                App::new()
-                    .route(\"/\", web::get().to(hello))
-            })
-            .bind(\"127.0.0.1:8080\")?
-            .run()
-            .await
-        }
-        ```
-
-        This code creates a basic web server that responds with 'Hello, World!' when you access the root URL. Here's a breakdown of what's happening:
-
-        1. We import necessary items from the `actix-web` crate.
-        2. We define an async `hello` function that returns a simple HTTP response.
-        3. In the `main` function, we set up the server to listen on `127.0.0.1:8080`.
-        4. We configure the app to respond to GET requests on the root path with our `hello` function.
-
-        To run this, you'd need to add `actix-web` to your `Cargo.toml` dependencies:
-
-        ```toml
-        [dependencies]
-        actix-web = \"4.0\"
-        ```
-
-        Then you can run the server with `cargo run` and access it at `http://localhost:8080`.".unindent(), cx);
-            thread.insert_user_message("That's great! Can you explain more about async functions in Rust?", Vec::new(), cx);
-            thread.insert_message(Role::Assistant, "Certainly! Async functions are a key feature in Rust for writing efficient, non-blocking code, especially for I/O-bound operations. Here's an overview:
-
-        1. **Syntax**: Async functions are declared using the `async` keyword:
-
-           ```rust
-           async fn my_async_function() -> Result<(), Error> {
-               // Asynchronous code here
-           }
-           ```
-
-        2. **Futures**: Async functions return a `Future`. A `Future` represents a value that may not be available yet but will be at some point.
-
-        3. **Await**: Inside an async function, you can use the `.await` syntax to wait for other async operations to complete:
-
-           ```rust
-           async fn fetch_data() -> Result<String, Error> {
-               let response = make_http_request().await?;
-               let data = process_response(response).await?;
-               Ok(data)
-           }
-           ```
-
-        4. **Non-blocking**: Async functions allow the runtime to work on other tasks while waiting for I/O or other operations to complete, making efficient use of system resources.
-
-        5. **Runtime**: To execute async code, you need a runtime like `tokio` or `async-std`. Actix-web, which we used in the previous example, includes its own runtime.
-
-        6. **Error Handling**: Async functions work well with Rust's `?` operator for error handling.
-
-        Async programming in Rust provides a powerful way to write concurrent code that's both safe and efficient. It's particularly useful for servers, network programming, and any application that deals with many concurrent operations.".unindent(), cx);
-            thread
-        }));
-    }
+fn thread_path(id: &ThreadId) -> PathBuf {
+    threads_dir().join(format!("{id}.json"))
 }
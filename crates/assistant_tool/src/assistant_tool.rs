@@ -27,6 +27,17 @@ pub trait Tool: 'static + Send + Sync {
         serde_json::Value::Object(serde_json::Map::default())
     }
 
+    /// Returns whether the user has to confirm each use of the tool before it runs, which is the
+    /// case for tools that modify the project or run commands.
+    fn needs_confirmation(&self) -> bool {
+        false
+    }
+
+    /// Describes what a use of the tool with the provided input does, for the user to confirm it.
+    fn confirmation_message(&self, _input: &serde_json::Value) -> String {
+        self.name()
+    }
+
     /// Runs the tool with the provided input.
     fn run(
         self: Arc<Self>,
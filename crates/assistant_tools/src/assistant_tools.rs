@@ -1,13 +1,240 @@
+mod diagnostics_tool;
+mod edit_file_tool;
+mod list_directory_tool;
 mod now_tool;
+mod read_file_tool;
+mod regex_search_tool;
+mod run_task_tool;
 
+use std::path::Path;
+
+use anyhow::{anyhow, Result};
 use assistant_tool::ToolRegistry;
-use gpui::AppContext;
+use gpui::{AppContext, Model, WeakView};
+use project::{Project, ProjectPath};
+use workspace::Workspace;
 
+use crate::diagnostics_tool::DiagnosticsTool;
+use crate::edit_file_tool::EditFileTool;
+use crate::list_directory_tool::ListDirectoryTool;
 use crate::now_tool::NowTool;
+use crate::read_file_tool::ReadFileTool;
+use crate::regex_search_tool::RegexSearchTool;
+use crate::run_task_tool::RunTaskTool;
 
 pub fn init(cx: &mut AppContext) {
     assistant_tool::init(cx);
 
     let registry = ToolRegistry::global(cx);
     registry.register_tool(NowTool);
+    registry.register_tool(ReadFileTool);
+    registry.register_tool(ListDirectoryTool);
+    registry.register_tool(RegexSearchTool);
+    registry.register_tool(EditFileTool);
+    registry.register_tool(DiagnosticsTool);
+    registry.register_tool(RunTaskTool);
+}
+
+/// Resolves a path given to a tool, which starts with the name of one of the root directories of
+/// the project, to the project of the workspace and a path in it.
+fn project_path_for_input(
+    workspace: &WeakView<Workspace>,
+    path: &Path,
+    cx: &AppContext,
+) -> Result<(Model<Project>, ProjectPath)> {
+    let workspace = workspace
+        .upgrade()
+        .ok_or_else(|| anyhow!("The workspace was closed"))?;
+    let project = workspace.read(cx).project().clone();
+    let project_path = project
+        .read(cx)
+        .find_project_path(path, cx)
+        .ok_or_else(|| anyhow!("Path {} not found in the project", path.display()))?;
+    Ok((project, project_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fs::{FakeFs, Fs as _};
+    use futures::StreamExt as _;
+    use gpui::{TestAppContext, WindowHandle};
+    use language_model::{
+        fake_provider::FakeLanguageModel, LanguageModel as _, LanguageModelCompletionEvent,
+        LanguageModelRequest, LanguageModelRequestTool, LanguageModelToolUse,
+    };
+    use serde_json::json;
+    use settings::SettingsStore;
+
+    fn init_test(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            let settings_store = SettingsStore::test(cx);
+            cx.set_global(settings_store);
+            theme::init(theme::LoadThemes::JustBase, cx);
+            language::init(cx);
+            workspace::init_settings(cx);
+            Project::init_settings(cx);
+            crate::init(cx);
+        });
+    }
+
+    /// Has the fake model request a tool use with the given input, and runs the tool it names
+    /// like the assistant does.
+    async fn use_tool(
+        model: &FakeLanguageModel,
+        name: &str,
+        input: serde_json::Value,
+        window: WindowHandle<Workspace>,
+        cx: &mut TestAppContext,
+    ) -> Result<String> {
+        let tools = cx.update(|cx| ToolRegistry::global(cx).tools());
+        let request = LanguageModelRequest {
+            messages: Vec::new(),
+            tools: tools
+                .iter()
+                .map(|tool| LanguageModelRequestTool {
+                    name: tool.name(),
+                    description: tool.description(),
+                    input_schema: tool.input_schema(),
+                })
+                .collect(),
+            stop: Vec::new(),
+            temperature: None,
+        };
+        let events = model.stream_completion(request, &cx.to_async());
+        model.send_last_completion_event(LanguageModelCompletionEvent::ToolUse(
+            LanguageModelToolUse {
+                id: "tool-use-1".into(),
+                name: name.into(),
+                input,
+            },
+        ));
+        model.end_last_completion_stream();
+
+        let mut events = events.await.unwrap();
+        let Some(Ok(LanguageModelCompletionEvent::ToolUse(tool_use))) = events.next().await else {
+            panic!("expected a tool use");
+        };
+        let tool = cx
+            .update(|cx| ToolRegistry::global(cx).tool(&tool_use.name))
+            .unwrap();
+        let workspace = window.root(cx).unwrap().downgrade();
+        window
+            .update(cx, |_, cx| tool.run(tool_use.input, workspace, cx))
+            .unwrap()
+            .await
+    }
+
+    #[gpui::test]
+    async fn test_project_tools(cx: &mut TestAppContext) {
+        init_test(cx);
+        let fs = FakeFs::new(cx.executor());
+        fs.insert_tree(
+            "/root",
+            json!({
+                "README.md": "# Greeter\n",
+                "src": {
+                    "main.rs": "fn main() {\n    greet(\"world\");\n}\n",
+                    "lib.rs": "pub fn greet(name: &str) {\n    println!(\"Hello, {name}!\");\n}\n",
+                },
+            }),
+        )
+        .await;
+        let project = Project::test(fs.clone(), ["/root".as_ref()], cx).await;
+        let window = cx.add_window(|cx| Workspace::test_new(project.clone(), cx));
+        let model = FakeLanguageModel::default();
+
+        let output = use_tool(
+            &model,
+            "list_directory",
+            json!({ "path": "root" }),
+            window,
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(output, "root/README.md\nroot/src/\n");
+
+        let output = use_tool(
+            &model,
+            "read_file",
+            json!({ "path": "root/src/main.rs", "start_line": 2, "end_line": 2 }),
+            window,
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(output, "    greet(\"world\");");
+
+        let output = use_tool(
+            &model,
+            "regex_search",
+            json!({ "regex": "greet\\(" }),
+            window,
+            cx,
+        )
+        .await
+        .unwrap();
+        let mut sections = output.split("## ").skip(1).collect::<Vec<_>>();
+        sections.sort();
+        assert_eq!(
+            sections,
+            [
+                "root/src/lib.rs\n1: pub fn greet(name: &str) {\n",
+                "root/src/main.rs\n2:     greet(\"world\");\n",
+            ]
+        );
+
+        let output = use_tool(
+            &model,
+            "edit_file",
+            json!({
+                "path": "root/src/main.rs",
+                "edits": [{ "old_text": "\"world\"", "new_text": "\"assistant\"" }],
+            }),
+            window,
+            cx,
+        )
+        .await
+        .unwrap();
+        assert_eq!(output, "Edited root/src/main.rs");
+        assert_eq!(
+            fs.load("/root/src/main.rs".as_ref()).await.unwrap(),
+            "fn main() {\n    greet(\"assistant\");\n}\n"
+        );
+
+        let output = use_tool(&model, "diagnostics", json!({}), window, cx)
+            .await
+            .unwrap();
+        assert_eq!(output, "The project has no errors or warnings");
+
+        let error = use_tool(
+            &model,
+            "read_file",
+            json!({ "path": "other/src/main.rs" }),
+            window,
+            cx,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Path other/src/main.rs not found in the project"
+        );
+    }
+
+    #[gpui::test]
+    fn test_confirmation_policy(cx: &mut TestAppContext) {
+        init_test(cx);
+        let mut tools_needing_confirmation = cx.update(|cx| {
+            ToolRegistry::global(cx)
+                .tools()
+                .into_iter()
+                .filter(|tool| tool.needs_confirmation())
+                .map(|tool| tool.name())
+                .collect::<Vec<_>>()
+        });
+        tools_needing_confirmation.sort();
+        assert_eq!(tools_needing_confirmation, ["edit_file", "run_task"]);
+    }
 }
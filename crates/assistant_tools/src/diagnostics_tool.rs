@@ -0,0 +1,120 @@
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use assistant_tool::Tool;
+use gpui::{Task, WeakView, WindowContext};
+use language::{DiagnosticSeverity, Point};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use workspace::Workspace;
+
+use crate::project_path_for_input;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct DiagnosticsToolInput {
+    /// The path of a file to list the errors and warnings of, starting with the name of one of
+    /// the root directories of the project. Without a path, the number of errors and warnings of
+    /// each file of the project is returned instead.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+}
+
+pub struct DiagnosticsTool;
+
+impl Tool for DiagnosticsTool {
+    fn name(&self) -> String {
+        "diagnostics".into()
+    }
+
+    fn description(&self) -> String {
+        "Returns the errors and warnings reported by the language servers for a file of the project, or a summary of them for the whole project. Use this tool after editing code to check that it compiles.".into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let schema = schemars::schema_for!(DiagnosticsToolInput);
+        serde_json::to_value(&schema).unwrap()
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input: DiagnosticsToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))),
+        };
+        match input.path {
+            Some(path) => file_diagnostics(&workspace, path, cx),
+            None => Task::ready(project_diagnostics(&workspace, cx)),
+        }
+    }
+}
+
+fn file_diagnostics(
+    workspace: &WeakView<Workspace>,
+    path: PathBuf,
+    cx: &mut WindowContext,
+) -> Task<Result<String>> {
+    let (project, project_path) = match project_path_for_input(workspace, &path, cx) {
+        Ok(project_path) => project_path,
+        Err(err) => return Task::ready(Err(err)),
+    };
+
+    let buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+    cx.spawn(|cx| async move {
+        let buffer = buffer.await?;
+        let snapshot = buffer.read_with(&cx, |buffer, _| buffer.snapshot())?;
+        let mut output = String::new();
+        for entry in snapshot.diagnostics_in_range::<_, Point>(0..snapshot.len(), false) {
+            if !entry.diagnostic.is_primary {
+                continue;
+            }
+            let severity = match entry.diagnostic.severity {
+                DiagnosticSeverity::ERROR => "error",
+                DiagnosticSeverity::WARNING => "warning",
+                _ => continue,
+            };
+            writeln!(
+                output,
+                "{}:{}: {severity}: {}",
+                entry.range.start.row + 1,
+                entry.range.start.column + 1,
+                entry.diagnostic.message
+            )?;
+        }
+        if output.is_empty() {
+            output = format!("{} has no errors or warnings", path.display());
+        }
+        Ok(output)
+    })
+}
+
+fn project_diagnostics(workspace: &WeakView<Workspace>, cx: &WindowContext) -> Result<String> {
+    let workspace = workspace
+        .upgrade()
+        .ok_or_else(|| anyhow!("The workspace was closed"))?;
+    let project = workspace.read(cx).project().read(cx);
+
+    let mut output = String::new();
+    for (project_path, _, summary) in project.diagnostic_summaries(false, cx) {
+        let Some(worktree) = project.worktree_for_id(project_path.worktree_id, cx) else {
+            continue;
+        };
+        let path = Path::new(worktree.read(cx).root_name()).join(&project_path.path);
+        writeln!(
+            output,
+            "{}: {} errors, {} warnings",
+            path.display(),
+            summary.error_count,
+            summary.warning_count
+        )?;
+    }
+    if output.is_empty() {
+        output = "The project has no errors or warnings".to_string();
+    }
+    Ok(output)
+}
@@ -0,0 +1,184 @@
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use assistant_tool::Tool;
+use gpui::{Task, WeakView, WindowContext};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use workspace::Workspace;
+
+use crate::project_path_for_input;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct EditFileToolInput {
+    /// The path of the file to edit, starting with the name of one of the root directories of
+    /// the project, e.g. `zed/crates/gpui/src/gpui.rs` in a project with a `zed` root directory.
+    pub path: PathBuf,
+    /// The replacements to make in the file. They are all applied at once, so each one refers to
+    /// the contents of the file before any of them are made.
+    pub edits: Vec<Edit>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct Edit {
+    /// The text to replace, which must occur exactly once in the file. Include enough
+    /// surrounding lines to make it unique.
+    pub old_text: String,
+    /// The text to replace it with.
+    pub new_text: String,
+}
+
+pub struct EditFileTool;
+
+impl Tool for EditFileTool {
+    fn name(&self) -> String {
+        "edit_file".into()
+    }
+
+    fn description(&self) -> String {
+        "Edits a file in the project by replacing pieces of its text, then saves it. Read the file first, and make sure each replaced text occurs exactly once in it.".into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let schema = schemars::schema_for!(EditFileToolInput);
+        serde_json::to_value(&schema).unwrap()
+    }
+
+    fn needs_confirmation(&self) -> bool {
+        true
+    }
+
+    fn confirmation_message(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<EditFileToolInput>(input.clone()) {
+            Ok(input) if input.edits.len() == 1 => format!("Edit {}", input.path.display()),
+            Ok(input) => format!(
+                "Make {} edits to {}",
+                input.edits.len(),
+                input.path.display()
+            ),
+            Err(_) => self.name(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input: EditFileToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))),
+        };
+        let (project, project_path) = match project_path_for_input(&workspace, &input.path, cx) {
+            Ok(project_path) => project_path,
+            Err(err) => return Task::ready(Err(err)),
+        };
+
+        let buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+        cx.spawn(|mut cx| async move {
+            let buffer = buffer.await?;
+            buffer.update(&mut cx, |buffer, cx| -> Result<()> {
+                let edits = resolve_edits(&buffer.text(), &input.edits)?;
+                buffer.edit(edits, None, cx);
+                Ok(())
+            })??;
+            project
+                .update(&mut cx, |project, cx| project.save_buffer(buffer, cx))?
+                .await?;
+
+            Ok(format!("Edited {}", input.path.display()))
+        })
+    }
+}
+
+/// Finds the range each edit replaces in `text`, failing unless each replaced text occurs exactly
+/// once and the edits don't overlap.
+fn resolve_edits(text: &str, edits: &[Edit]) -> Result<Vec<(Range<usize>, String)>> {
+    let mut resolved_edits = Vec::with_capacity(edits.len());
+    for edit in edits {
+        if edit.old_text.is_empty() {
+            return Err(anyhow!("The text to replace can't be empty"));
+        }
+        let mut matches = text.match_indices(&edit.old_text);
+        let Some((start, _)) = matches.next() else {
+            return Err(anyhow!("{:?} does not occur in the file", edit.old_text));
+        };
+        if matches.next().is_some() {
+            return Err(anyhow!(
+                "{:?} occurs more than once in the file, include more of its surrounding text",
+                edit.old_text
+            ));
+        }
+        resolved_edits.push((start..start + edit.old_text.len(), edit.new_text.clone()));
+    }
+
+    resolved_edits.sort_by_key(|(range, _)| range.start);
+    for pair in resolved_edits.windows(2) {
+        if pair[0].0.end > pair[1].0.start {
+            return Err(anyhow!("The edits overlap"));
+        }
+    }
+    Ok(resolved_edits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(old_text: &str, new_text: &str) -> Edit {
+        Edit {
+            old_text: old_text.into(),
+            new_text: new_text.into(),
+        }
+    }
+
+    #[test]
+    fn test_resolve_edits() {
+        let text = "fn one() {}\nfn two() {}\nfn three() {}\n";
+        assert_eq!(
+            resolve_edits(
+                text,
+                &[
+                    edit("fn three", "fn tres"),
+                    edit("fn one() {}", "fn uno() {}")
+                ]
+            )
+            .unwrap(),
+            [
+                (0..11, "fn uno() {}".to_string()),
+                (24..32, "fn tres".to_string())
+            ]
+        );
+
+        assert!(resolve_edits(text, &[edit("fn four", "fn cuatro")]).is_err());
+        assert!(resolve_edits(text, &[edit("() {}", "() { todo!() }")]).is_err());
+        assert!(resolve_edits(text, &[edit("", "fn zero() {}\n")]).is_err());
+        assert!(resolve_edits(
+            text,
+            &[edit("one() {}\nfn two", "uno"), edit("two() {}", "dos")]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_confirmation_message() {
+        let input = serde_json::json!({
+            "path": "root/main.rs",
+            "edits": [
+                { "old_text": "one", "new_text": "uno" },
+                { "old_text": "two", "new_text": "dos" },
+            ],
+        });
+        assert_eq!(
+            EditFileTool.confirmation_message(&input),
+            "Make 2 edits to root/main.rs"
+        );
+        assert_eq!(
+            EditFileTool.confirmation_message(&serde_json::json!({ "path": 1 })),
+            "edit_file"
+        );
+    }
+}
@@ -0,0 +1,81 @@
+use std::fmt::Write as _;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use assistant_tool::Tool;
+use gpui::{Task, WeakView, WindowContext};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use workspace::Workspace;
+
+use crate::project_path_for_input;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ListDirectoryToolInput {
+    /// The path of the directory to list, starting with the name of one of the root directories
+    /// of the project, e.g. `zed/crates` in a project with a `zed` root directory. The name of a
+    /// root directory alone lists that directory.
+    pub path: PathBuf,
+}
+
+pub struct ListDirectoryTool;
+
+impl Tool for ListDirectoryTool {
+    fn name(&self) -> String {
+        "list_directory".into()
+    }
+
+    fn description(&self) -> String {
+        "Lists the files and directories directly inside a directory of the project. Directories are listed with a trailing slash.".into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let schema = schemars::schema_for!(ListDirectoryToolInput);
+        serde_json::to_value(&schema).unwrap()
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input: ListDirectoryToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))),
+        };
+        Task::ready(list_directory(&workspace, input, cx))
+    }
+}
+
+fn list_directory(
+    workspace: &WeakView<Workspace>,
+    input: ListDirectoryToolInput,
+    cx: &WindowContext,
+) -> Result<String> {
+    let (project, project_path) = project_path_for_input(workspace, &input.path, cx)?;
+    let worktree = project
+        .read(cx)
+        .worktree_for_id(project_path.worktree_id, cx)
+        .ok_or_else(|| anyhow!("Path {} not found in the project", input.path.display()))?;
+    let worktree = worktree.read(cx);
+    let entry = worktree
+        .entry_for_path(&project_path.path)
+        .ok_or_else(|| anyhow!("Path {} not found in the project", input.path.display()))?;
+    if !entry.is_dir() {
+        return Err(anyhow!("{} is not a directory", input.path.display()));
+    }
+
+    let root_name = worktree.root_name();
+    let mut output = String::new();
+    for entry in worktree.child_entries(&project_path.path) {
+        let path = PathBuf::from(root_name).join(&entry.path);
+        let suffix = if entry.is_dir() { "/" } else { "" };
+        writeln!(output, "{}{suffix}", path.display())?;
+    }
+    if output.is_empty() {
+        output = format!("{} is empty", input.path.display());
+    }
+    Ok(output)
+}
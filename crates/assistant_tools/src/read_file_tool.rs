@@ -0,0 +1,108 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use assistant_tool::Tool;
+use gpui::{Task, WeakView, WindowContext};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use workspace::Workspace;
+
+use crate::project_path_for_input;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ReadFileToolInput {
+    /// The path of the file to read, starting with the name of one of the root directories of
+    /// the project, e.g. `zed/crates/gpui/src/gpui.rs` in a project with a `zed` root directory.
+    pub path: PathBuf,
+    /// The line to start reading at, counting from 1. Defaults to the first line.
+    #[serde(default)]
+    pub start_line: Option<usize>,
+    /// The last line to read, counting from 1. Defaults to the last line of the file.
+    #[serde(default)]
+    pub end_line: Option<usize>,
+}
+
+pub struct ReadFileTool;
+
+impl Tool for ReadFileTool {
+    fn name(&self) -> String {
+        "read_file".into()
+    }
+
+    fn description(&self) -> String {
+        "Reads the contents of a file in the project, or only the given range of lines of it. Use this tool to look at code before suggesting changes to it.".into()
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let schema = schemars::schema_for!(ReadFileToolInput);
+        serde_json::to_value(&schema).unwrap()
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input: ReadFileToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))),
+        };
+        let (project, project_path) = match project_path_for_input(&workspace, &input.path, cx) {
+            Ok(project_path) => project_path,
+            Err(err) => return Task::ready(Err(err)),
+        };
+
+        let buffer = project.update(cx, |project, cx| project.open_buffer(project_path, cx));
+        cx.spawn(|cx| async move {
+            let buffer = buffer.await?;
+            let text = buffer.read_with(&cx, |buffer, _| buffer.text())?;
+            line_range(&text, input.start_line, input.end_line)
+        })
+    }
+}
+
+/// Returns the lines of `text` from `start_line` to `end_line`, both counting from 1 and
+/// inclusive.
+fn line_range(text: &str, start_line: Option<usize>, end_line: Option<usize>) -> Result<String> {
+    if start_line.is_none() && end_line.is_none() {
+        return Ok(text.to_string());
+    }
+
+    let line_count = text.lines().count();
+    let start_line = start_line.unwrap_or(1).max(1);
+    let end_line = end_line.unwrap_or(line_count).min(line_count);
+    if start_line > end_line {
+        return Err(anyhow!(
+            "Invalid line range {start_line}-{end_line}: the file has {line_count} lines"
+        ));
+    }
+
+    let lines = text
+        .lines()
+        .skip(start_line - 1)
+        .take(end_line + 1 - start_line)
+        .collect::<Vec<_>>();
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_range() {
+        let text = "one\ntwo\nthree\nfour\n";
+        assert_eq!(line_range(text, None, None).unwrap(), text);
+        assert_eq!(line_range(text, Some(2), Some(3)).unwrap(), "two\nthree");
+        assert_eq!(line_range(text, Some(3), None).unwrap(), "three\nfour");
+        assert_eq!(line_range(text, None, Some(1)).unwrap(), "one");
+        assert_eq!(
+            line_range(text, Some(0), Some(10)).unwrap(),
+            "one\ntwo\nthree\nfour"
+        );
+        assert!(line_range(text, Some(5), None).is_err());
+        assert!(line_range(text, Some(3), Some(2)).is_err());
+    }
+}
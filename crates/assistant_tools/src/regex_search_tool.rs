@@ -0,0 +1,127 @@
+use std::fmt::Write as _;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use assistant_tool::Tool;
+use gpui::{Task, WeakView, WindowContext};
+use language::{Point, ToPoint as _};
+use project::search::{SearchQuery, SearchResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use util::paths::PathMatcher;
+use workspace::Workspace;
+
+/// The maximum number of matching lines returned, so that searching for a common pattern doesn't
+/// flood the context of the model.
+const MAX_MATCHES: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RegexSearchToolInput {
+    /// The regular expression to search for, e.g. `fn \w+_test\(`.
+    pub regex: String,
+    /// Whether the search is case sensitive. Defaults to false.
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Glob patterns of the paths to search in, e.g. `zed/crates/**/*.rs`. Defaults to all the
+    /// files of the project.
+    #[serde(default)]
+    pub include: Vec<String>,
+}
+
+pub struct RegexSearchTool;
+
+impl Tool for RegexSearchTool {
+    fn name(&self) -> String {
+        "regex_search".into()
+    }
+
+    fn description(&self) -> String {
+        format!("Searches the files of the project for a regular expression, and returns each matching line with its path and line number. At most {MAX_MATCHES} matching lines are returned, so prefer specific patterns.")
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let schema = schemars::schema_for!(RegexSearchToolInput);
+        serde_json::to_value(&schema).unwrap()
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input: RegexSearchToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))),
+        };
+        let Some(project) = workspace
+            .upgrade()
+            .map(|workspace| workspace.read(cx).project().clone())
+        else {
+            return Task::ready(Err(anyhow!("The workspace was closed")));
+        };
+        let query = PathMatcher::new(&input.include)
+            .map_err(anyhow::Error::from)
+            .and_then(|files_to_include| {
+                SearchQuery::regex(
+                    &input.regex,
+                    false,
+                    input.case_sensitive,
+                    false,
+                    files_to_include,
+                    PathMatcher::default(),
+                    None,
+                )
+            });
+        let query = match query {
+            Ok(query) => query,
+            Err(err) => return Task::ready(Err(err)),
+        };
+
+        let results = project.update(cx, |project, cx| project.search(query, cx));
+        cx.spawn(|cx| async move {
+            let mut output = String::new();
+            let mut match_count = 0;
+            while let Ok(result) = results.recv().await {
+                let SearchResult::Buffer { buffer, ranges } = result else {
+                    break;
+                };
+                buffer.read_with(&cx, |buffer, cx| -> Result<()> {
+                    let Some(file) = buffer.file() else {
+                        return Ok(());
+                    };
+                    writeln!(output, "## {}", file.full_path(cx).display())?;
+
+                    let snapshot = buffer.snapshot();
+                    let mut last_row = None;
+                    for range in ranges {
+                        let row = range.start.to_point(&snapshot).row;
+                        if last_row == Some(row) || match_count == MAX_MATCHES {
+                            continue;
+                        }
+                        last_row = Some(row);
+                        match_count += 1;
+
+                        let line = snapshot
+                            .text_for_range(
+                                Point::new(row, 0)..Point::new(row, snapshot.line_len(row)),
+                            )
+                            .collect::<String>();
+                        writeln!(output, "{}: {}", row + 1, line.trim_end())?;
+                    }
+                    Ok(())
+                })??;
+
+                if match_count == MAX_MATCHES {
+                    writeln!(output, "\nThe search stopped after {MAX_MATCHES} matches.")?;
+                    break;
+                }
+            }
+
+            if output.is_empty() {
+                output = "No matches found".to_string();
+            }
+            Ok(output)
+        })
+    }
+}
@@ -0,0 +1,155 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context as _, Result};
+use assistant_tool::Tool;
+use futures::future::{self, Either};
+use gpui::{Task, WeakView, WindowContext};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use task::{RevealStrategy, TaskContext, TaskTemplate};
+use terminal::TaskStatus;
+use terminal_view::terminal_panel::TerminalPanel;
+use workspace::Workspace;
+
+use crate::project_path_for_input;
+
+/// The number of lines at the end of the output of a task that are returned to the model.
+const MAX_OUTPUT_LINES: usize = 200;
+
+/// How long a task may run before it is interrupted.
+const TASK_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct RunTaskToolInput {
+    /// The shell command to run, e.g. `cargo test -p editor`.
+    pub command: String,
+    /// The directory to run the command in, starting with the name of one of the root directories
+    /// of the project. Defaults to the root directory of the active file.
+    #[serde(default)]
+    pub directory: Option<PathBuf>,
+}
+
+pub struct RunTaskTool;
+
+impl Tool for RunTaskTool {
+    fn name(&self) -> String {
+        "run_task".into()
+    }
+
+    fn description(&self) -> String {
+        format!("Runs a shell command as a task in a terminal, waits for it to finish and returns whether it succeeded along with the last {MAX_OUTPUT_LINES} lines of its output. The command is interrupted after {} minutes. Use this tool to build the project or run its tests.", TASK_TIMEOUT.as_secs() / 60)
+    }
+
+    fn input_schema(&self) -> serde_json::Value {
+        let schema = schemars::schema_for!(RunTaskToolInput);
+        serde_json::to_value(&schema).unwrap()
+    }
+
+    fn needs_confirmation(&self) -> bool {
+        true
+    }
+
+    fn confirmation_message(&self, input: &serde_json::Value) -> String {
+        match serde_json::from_value::<RunTaskToolInput>(input.clone()) {
+            Ok(RunTaskToolInput {
+                command,
+                directory: Some(directory),
+            }) => format!("Run `{command}` in {}", directory.display()),
+            Ok(RunTaskToolInput { command, .. }) => format!("Run `{command}`"),
+            Err(_) => self.name(),
+        }
+    }
+
+    fn run(
+        self: Arc<Self>,
+        input: serde_json::Value,
+        workspace: WeakView<Workspace>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<String>> {
+        let input: RunTaskToolInput = match serde_json::from_value(input) {
+            Ok(input) => input,
+            Err(err) => return Task::ready(Err(anyhow!(err))),
+        };
+        let cwd = match input.directory {
+            Some(directory) => {
+                match project_path_for_input(&workspace, &directory, cx).and_then(
+                    |(project, project_path)| {
+                        project
+                            .read(cx)
+                            .absolute_path(&project_path, cx)
+                            .context("Failed to resolve the directory")
+                    },
+                ) {
+                    Ok(cwd) => Some(cwd),
+                    Err(err) => return Task::ready(Err(err)),
+                }
+            }
+            None => None,
+        };
+        let Some(workspace) = workspace.upgrade() else {
+            return Task::ready(Err(anyhow!("The workspace was closed")));
+        };
+        let Some(terminal_panel) = workspace.read(cx).panel::<TerminalPanel>(cx) else {
+            return Task::ready(Err(anyhow!("The terminal panel is not available")));
+        };
+        let is_local = workspace.read(cx).project().read(cx).is_local();
+
+        let template = TaskTemplate {
+            label: input.command.clone(),
+            command: input.command.clone(),
+            cwd: cwd.map(|cwd| cwd.to_string_lossy().into_owned()),
+            reveal: RevealStrategy::NoFocus,
+            ..Default::default()
+        };
+        let Some(spawn_in_terminal) = template
+            .resolve_task("assistant", &TaskContext::default())
+            .and_then(|task| task.resolved)
+        else {
+            return Task::ready(Err(anyhow!("Invalid command {:?}", input.command)));
+        };
+
+        let mut spawn_task = spawn_in_terminal.clone();
+        if TerminalPanel::fill_command(is_local, &spawn_in_terminal, &mut spawn_task).is_break() {
+            return Task::ready(Err(anyhow!("No shell was found to run the command in")));
+        }
+
+        let terminal = terminal_panel.update(cx, |terminal_panel, cx| {
+            terminal_panel.spawn_in_new_terminal(spawn_task, cx)
+        });
+        cx.spawn(|mut cx| async move {
+            // The terminal is owned by its view in the terminal panel, so that closing it cancels
+            // the task.
+            let terminal = terminal.await?.downgrade();
+            let completed =
+                terminal.update(&mut cx, |terminal, cx| terminal.wait_for_completed_task(cx))?;
+            let timeout = cx.background_executor().timer(TASK_TIMEOUT);
+            let timed_out = match future::select(completed, timeout).await {
+                Either::Left(_) => false,
+                Either::Right(_) => {
+                    terminal
+                        .update(&mut cx, |terminal, _| terminal.input("\x03".to_string()))
+                        .ok();
+                    true
+                }
+            };
+
+            terminal
+                .update(&mut cx, |terminal, _| {
+                    let status = match terminal.task().map(|task| task.status) {
+                        _ if timed_out => "was interrupted after running for too long",
+                        Some(TaskStatus::Completed { success: true }) => "succeeded",
+                        Some(TaskStatus::Completed { success: false }) => "failed",
+                        _ => "stopped without reporting its exit status",
+                    };
+                    let output = terminal.last_n_non_empty_lines(MAX_OUTPUT_LINES).join("\n");
+                    format!(
+                        "The command `{}` {status}. Its output was:\n\n{output}",
+                        input.command
+                    )
+                })
+                .context("The command was cancelled by closing its terminal")
+        })
+    }
+}
@@ -34,6 +34,9 @@ trait InstalledApp {
 )]
 struct Args {
     /// Wait for all of the given paths to be opened/closed before exiting.
+    ///
+    /// This lets Zed be used as the editor of other programs, e.g. with
+    /// `GIT_SEQUENCE_EDITOR="zed --wait"`, interactive rebases are edited in Zed's rebase editor.
     #[arg(short, long)]
     wait: bool,
     /// Add files to the currently open workspace
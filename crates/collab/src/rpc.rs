@@ -299,6 +299,12 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::GetDefinition>)
             .add_request_handler(forward_read_only_project_request::<proto::GetTypeDefinition>)
             .add_request_handler(forward_read_only_project_request::<proto::GetReferences>)
+            .add_request_handler(forward_read_only_project_request::<proto::PrepareCallHierarchy>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetIncomingCalls>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetOutgoingCalls>)
+            .add_request_handler(forward_read_only_project_request::<proto::PrepareTypeHierarchy>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSupertypes>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSubtypes>)
             .add_request_handler(forward_find_search_candidates_request)
             .add_request_handler(forward_read_only_project_request::<proto::GetDocumentHighlights>)
             .add_request_handler(forward_read_only_project_request::<proto::GetProjectSymbols>)
@@ -307,6 +313,12 @@ impl Server {
             .add_request_handler(forward_read_only_project_request::<proto::SynchronizeBuffers>)
             .add_request_handler(forward_read_only_project_request::<proto::InlayHints>)
             .add_request_handler(forward_read_only_project_request::<proto::ResolveInlayHint>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetSemanticTokens>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetCodeLens>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetFoldingRanges>)
+            .add_request_handler(forward_read_only_project_request::<proto::ResolveCodeLens>)
+            .add_request_handler(forward_read_only_project_request::<proto::GetDocumentLinks>)
+            .add_request_handler(forward_read_only_project_request::<proto::ResolveDocumentLink>)
             .add_request_handler(forward_read_only_project_request::<proto::OpenBufferByPath>)
             .add_request_handler(forward_read_only_project_request::<proto::GitBranches>)
             .add_request_handler(forward_read_only_project_request::<proto::GetStagedText>)
@@ -314,6 +326,27 @@ impl Server {
                 forward_mutating_project_request::<proto::RegisterBufferWithLanguageServers>,
             )
             .add_request_handler(forward_mutating_project_request::<proto::UpdateGitBranch>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitStagedPaths>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStage>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitUnstage>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitDiscard>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitCommit>)
+            .add_request_handler(forward_mutating_project_request::<proto::SetIndexText>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitLog>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitCommitDiff>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitCommitMessage>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitRevisionText>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitChangedPaths>)
+            .add_request_handler(reject_guest_request::<proto::GitRemoteOperation>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitStashList>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStashPush>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitStashAction>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitRebaseTodo>)
+            .add_request_handler(reject_guest_request::<proto::GitRebase>)
+            .add_request_handler(forward_mutating_project_request::<proto::GitRebaseControl>)
+            .add_request_handler(forward_read_only_project_request::<proto::GitWorktreeList>)
+            .add_request_handler(reject_guest_request::<proto::GitCreateWorktree>)
+            .add_request_handler(reject_guest_request::<proto::GitRemoveWorktree>)
             .add_request_handler(forward_mutating_project_request::<proto::GetCompletions>)
             .add_request_handler(
                 forward_mutating_project_request::<proto::ApplyCompletionAdditionalEdits>,
@@ -342,6 +375,10 @@ impl Server {
             .add_message_handler(create_buffer_for_peer)
             .add_request_handler(update_buffer)
             .add_message_handler(broadcast_project_message_from_host::<proto::RefreshInlayHints>)
+            .add_message_handler(broadcast_project_message_from_host::<proto::RefreshCodeLens>)
+            .add_message_handler(
+                broadcast_project_message_from_host::<proto::RefreshSemanticTokens>,
+            )
             .add_message_handler(broadcast_project_message_from_host::<proto::UpdateBufferFile>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferReloaded>)
             .add_message_handler(broadcast_project_message_from_host::<proto::BufferSaved>)
@@ -2159,6 +2196,16 @@ where
     Ok(())
 }
 
+/// Rejects a project request that would let guests act on the host's machine beyond the project,
+/// such as pushing with the host's credentials, running the commands of a rebase, or creating and
+/// removing worktrees at any path. These requests are only served to the host's own SSH clients.
+async fn reject_guest_request<T>(_: T, _: Response<T>, _: Session) -> Result<()>
+where
+    T: EntityMessage + RequestMessage,
+{
+    Err(anyhow!("{} is not available to collaborators", T::NAME))?
+}
+
 /// Notify other participants that a new buffer has been created
 async fn create_buffer_for_peer(
     request: proto::CreateBufferForPeer,
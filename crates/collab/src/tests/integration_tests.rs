@@ -2858,6 +2858,89 @@ async fn test_git_branch_name(
     });
 }
 
+#[gpui::test]
+async fn test_host_only_git_operations(
+    executor: BackgroundExecutor,
+    cx_a: &mut TestAppContext,
+    cx_b: &mut TestAppContext,
+) {
+    let mut server = TestServer::start(executor.clone()).await;
+    let client_a = server.create_client(cx_a, "user_a").await;
+    let client_b = server.create_client(cx_b, "user_b").await;
+    server
+        .create_room(&mut [(&client_a, cx_a), (&client_b, cx_b)])
+        .await;
+    let active_call_a = cx_a.read(ActiveCall::global);
+
+    client_a
+        .fs()
+        .insert_tree("/dir", json!({ ".git": {}, "a.txt": "a" }))
+        .await;
+    client_a
+        .fs()
+        .insert_branches(Path::new("/dir/.git"), &["main"]);
+
+    let (project_a, worktree_id) = client_a.build_local_project("/dir", cx_a).await;
+    let project_id = active_call_a
+        .update(cx_a, |call, cx| call.share_project(project_a.clone(), cx))
+        .await
+        .unwrap();
+    let project_b = client_b.join_remote_project(project_id, cx_b).await;
+    executor.run_until_parked();
+
+    struct NoCredentials;
+    impl remote::AskPassDelegate for NoCredentials {
+        fn ask_password(
+            &self,
+            _: String,
+            _: &mut gpui::AsyncAppContext,
+        ) -> futures::channel::oneshot::Receiver<Result<String>> {
+            panic!("no credentials should be requested");
+        }
+    }
+
+    // Guests can't push with the host's credentials, nor run rebases or create and remove
+    // worktrees on the host's machine.
+    let repository = ProjectPath::root_path(worktree_id);
+    let push = project_b.update(cx_b, |project, cx| {
+        project.git_remote_operation(
+            repository.clone(),
+            git::repository::RemoteOperation::Push { force: true },
+            Arc::new(NoCredentials),
+            cx,
+        )
+    });
+    assert!(push.await.is_err());
+
+    let create_worktree = project_b.update(cx_b, |project, cx| {
+        project.create_git_worktree(
+            repository.clone(),
+            PathBuf::from("/etc/worktree"),
+            "main".into(),
+            None,
+            cx,
+        )
+    });
+    assert!(create_worktree.await.is_err());
+
+    let remove_worktree = project_b.update(cx_b, |project, cx| {
+        project.remove_git_worktree(repository.clone(), PathBuf::from("/dir-linked"), true, cx)
+    });
+    assert!(remove_worktree.await.is_err());
+
+    let rebase = project_b.update(cx_b, |project, cx| {
+        project.rebase(
+            repository.clone(),
+            "main".into(),
+            vec![git::rebase::RebaseTodoItem::command(
+                "exec touch /tmp/pwned".into(),
+            )],
+            cx,
+        )
+    });
+    assert!(rebase.await.is_err());
+}
+
 #[gpui::test]
 async fn test_git_status_sync(
     executor: BackgroundExecutor,
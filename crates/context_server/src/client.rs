@@ -2,6 +2,7 @@ use anyhow::{anyhow, Context, Result};
 use collections::HashMap;
 use futures::{channel::oneshot, io::BufWriter, select, AsyncRead, AsyncWrite, FutureExt};
 use gpui::{AsyncAppContext, BackgroundExecutor, Task};
+use http_client::HttpClient;
 use parking_lot::Mutex;
 use postage::barrier;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
@@ -20,7 +21,9 @@ use std::{
     },
     time::{Duration, Instant},
 };
-use util::TryFutureExt;
+use util::{ResultExt as _, TryFutureExt};
+
+use crate::http_transport::{self, ModelContextServerEndpoint};
 
 const JSON_RPC_VERSION: &str = "2.0";
 const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
@@ -34,6 +37,7 @@ pub const INTERNAL_ERROR: i32 = -32603;
 
 type ResponseHandler = Box<dyn Send + FnOnce(Result<String, Error>)>;
 type NotificationHandler = Box<dyn Send + FnMut(Value, AsyncAppContext)>;
+type RequestHandler = Box<dyn Send + FnMut(Value, AsyncAppContext) -> Task<Result<Value>>>;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[serde(untagged)]
@@ -48,6 +52,7 @@ pub struct Client {
     outbound_tx: channel::Sender<String>,
     name: Arc<str>,
     notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
     response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
     #[allow(clippy::type_complexity)]
     #[allow(dead_code)]
@@ -79,6 +84,15 @@ struct Request<'a, T> {
     params: T,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+struct AnyRequest<'a> {
+    jsonrpc: &'a str,
+    id: RequestId,
+    method: String,
+    #[serde(default)]
+    params: Option<Value>,
+}
+
 #[derive(Serialize, Deserialize)]
 struct AnyResponse<'a> {
     jsonrpc: &'a str,
@@ -123,8 +137,20 @@ struct AnyNotification<'a> {
     params: Option<Value>,
 }
 
+#[derive(Serialize)]
+struct OutgoingResponse {
+    jsonrpc: &'static str,
+    id: RequestId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<Error>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct Error {
+    #[serde(default)]
+    code: i32,
     message: String,
 }
 
@@ -177,14 +203,25 @@ impl Client {
 
         let notification_handlers =
             Arc::new(Mutex::new(HashMap::<_, NotificationHandler>::default()));
+        let request_handlers = Arc::new(Mutex::new(HashMap::<_, RequestHandler>::default()));
         let response_handlers =
             Arc::new(Mutex::new(Some(HashMap::<_, ResponseHandler>::default())));
 
         let stdout_input_task = cx.spawn({
             let notification_handlers = notification_handlers.clone();
+            let request_handlers = request_handlers.clone();
             let response_handlers = response_handlers.clone();
+            let outbound_tx = outbound_tx.clone();
             move |cx| {
-                Self::handle_input(stdout, notification_handlers, response_handlers, cx).log_err()
+                Self::handle_input(
+                    stdout,
+                    notification_handlers,
+                    request_handlers,
+                    response_handlers,
+                    outbound_tx,
+                    cx,
+                )
+                .log_err()
             }
         });
         let stderr_input_task = cx.spawn(|_| Self::handle_stderr(stderr).log_err());
@@ -205,6 +242,7 @@ impl Client {
         let mut context_server = Self {
             server_id,
             notification_handlers,
+            request_handlers,
             response_handlers,
             name: "".into(),
             next_id: Default::default(),
@@ -222,16 +260,92 @@ impl Client {
         Ok(context_server)
     }
 
+    /// Creates a new Client instance for a context server that runs as an HTTP service.
+    ///
+    /// Messages are exchanged over the HTTP transport of the endpoint, and the messages received
+    /// are dispatched to the same handlers as the ones of a server spawned as a child process.
+    pub fn new_http(
+        server_id: ContextServerId,
+        endpoint: ModelContextServerEndpoint,
+        http_client: Arc<dyn HttpClient>,
+        cx: AsyncAppContext,
+    ) -> Result<Self> {
+        log::info!(
+            "connecting to context server (url={}, transport={:?})",
+            endpoint.url,
+            endpoint.transport
+        );
+
+        let name = endpoint.url.host_str().unwrap_or_default().into();
+        let (outbound_tx, outbound_rx) = channel::unbounded::<String>();
+        let (inbound_tx, inbound_rx) = channel::unbounded::<String>();
+        let (output_done_tx, output_done_rx) = barrier::channel();
+
+        let notification_handlers =
+            Arc::new(Mutex::new(HashMap::<_, NotificationHandler>::default()));
+        let request_handlers = Arc::new(Mutex::new(HashMap::<_, RequestHandler>::default()));
+        let response_handlers =
+            Arc::new(Mutex::new(Some(HashMap::<_, ResponseHandler>::default())));
+
+        let input_task = cx.spawn({
+            let notification_handlers = notification_handlers.clone();
+            let request_handlers = request_handlers.clone();
+            let response_handlers = response_handlers.clone();
+            let outbound_tx = outbound_tx.clone();
+            move |cx| {
+                Self::handle_inbound_messages(
+                    inbound_rx,
+                    notification_handlers,
+                    request_handlers,
+                    response_handlers,
+                    outbound_tx,
+                    cx,
+                )
+                .log_err()
+            }
+        });
+        let output_task = cx.background_executor().spawn({
+            let response_handlers = response_handlers.clone();
+            async move {
+                let _clear_response_handlers = util::defer(move || {
+                    response_handlers.lock().take();
+                });
+                let result =
+                    http_transport::run(endpoint, http_client, outbound_rx, inbound_tx).await;
+                drop(output_done_tx);
+                result
+            }
+            .log_err()
+        });
+
+        Ok(Self {
+            server_id,
+            notification_handlers,
+            request_handlers,
+            response_handlers,
+            name,
+            next_id: Default::default(),
+            outbound_tx,
+            executor: cx.background_executor().clone(),
+            io_tasks: Mutex::new(Some((input_task, output_task))),
+            output_done_rx: Mutex::new(Some(output_done_rx)),
+            server: Arc::new(Mutex::new(None)),
+        })
+    }
+
     /// Handles input from the server's stdout.
     ///
     /// This function continuously reads lines from the provided stdout stream,
-    /// parses them as JSON-RPC responses or notifications, and dispatches them
-    /// to the appropriate handlers. It processes both responses (which are matched
-    /// to pending requests) and notifications (which trigger registered handlers).
+    /// parses them as JSON-RPC requests, responses or notifications, and dispatches them
+    /// to the appropriate handlers. It processes requests (which are answered by registered
+    /// handlers), responses (which are matched to pending requests) and notifications
+    /// (which trigger registered handlers).
     async fn handle_input<Stdout>(
         stdout: Stdout,
         notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+        request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
         response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+        outbound_tx: channel::Sender<String>,
         cx: AsyncAppContext,
     ) -> anyhow::Result<()>
     where
@@ -246,29 +360,125 @@ impl Client {
                 return Ok(());
             }
 
-            let content = buffer.trim();
+            Self::handle_message(
+                buffer.trim(),
+                &notification_handlers,
+                &request_handlers,
+                &response_handlers,
+                &outbound_tx,
+                &cx,
+            );
 
-            if !content.is_empty() {
-                if let Ok(response) = serde_json::from_str::<AnyResponse>(content) {
-                    if let Some(handlers) = response_handlers.lock().as_mut() {
-                        if let Some(handler) = handlers.remove(&response.id) {
-                            handler(Ok(content.to_string()));
-                        }
-                    }
-                } else if let Ok(notification) = serde_json::from_str::<AnyNotification>(content) {
-                    let mut notification_handlers = notification_handlers.lock();
-                    if let Some(handler) =
-                        notification_handlers.get_mut(notification.method.as_str())
-                    {
-                        handler(notification.params.unwrap_or(Value::Null), cx.clone());
-                    }
+            smol::future::yield_now().await;
+        }
+    }
+
+    /// Handles the messages received over an HTTP transport, dispatching them like the lines
+    /// of a server's stdout.
+    async fn handle_inbound_messages(
+        inbound_rx: channel::Receiver<String>,
+        notification_handlers: Arc<Mutex<HashMap<&'static str, NotificationHandler>>>,
+        request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
+        response_handlers: Arc<Mutex<Option<HashMap<RequestId, ResponseHandler>>>>,
+        outbound_tx: channel::Sender<String>,
+        cx: AsyncAppContext,
+    ) -> anyhow::Result<()> {
+        while let Ok(message) = inbound_rx.recv().await {
+            Self::handle_message(
+                message.trim(),
+                &notification_handlers,
+                &request_handlers,
+                &response_handlers,
+                &outbound_tx,
+                &cx,
+            );
+        }
+        Ok(())
+    }
+
+    /// Dispatches a message received from the server, either to the handler of the request it
+    /// makes, to the handler of the request it responds to, or to the handler of the
+    /// notification.
+    fn handle_message(
+        content: &str,
+        notification_handlers: &Mutex<HashMap<&'static str, NotificationHandler>>,
+        request_handlers: &Mutex<HashMap<&'static str, RequestHandler>>,
+        response_handlers: &Mutex<Option<HashMap<RequestId, ResponseHandler>>>,
+        outbound_tx: &channel::Sender<String>,
+        cx: &AsyncAppContext,
+    ) {
+        if content.is_empty() {
+            return;
+        }
+
+        if let Ok(request) = serde_json::from_str::<AnyRequest>(content) {
+            Self::handle_request(request, request_handlers, outbound_tx, cx);
+        } else if let Ok(response) = serde_json::from_str::<AnyResponse>(content) {
+            if let Some(handlers) = response_handlers.lock().as_mut() {
+                if let Some(handler) = handlers.remove(&response.id) {
+                    handler(Ok(content.to_string()));
                 }
             }
-
-            smol::future::yield_now().await;
+        } else if let Ok(notification) = serde_json::from_str::<AnyNotification>(content) {
+            let mut notification_handlers = notification_handlers.lock();
+            if let Some(handler) = notification_handlers.get_mut(notification.method.as_str()) {
+                handler(notification.params.unwrap_or(Value::Null), cx.clone());
+            }
         }
     }
 
+    /// Runs the handler registered for a request made by the server, and sends its result back
+    /// to the server. Requests without a handler are answered with a "method not found" error.
+    fn handle_request(
+        request: AnyRequest,
+        request_handlers: &Mutex<HashMap<&'static str, RequestHandler>>,
+        outbound_tx: &channel::Sender<String>,
+        cx: &AsyncAppContext,
+    ) {
+        let params = request.params.unwrap_or(Value::Null);
+        let result = request_handlers
+            .lock()
+            .get_mut(request.method.as_str())
+            .map(|handler| handler(params, cx.clone()));
+        let id = request.id;
+        let method = request.method;
+        let outbound_tx = outbound_tx.clone();
+        cx.background_executor()
+            .spawn(async move {
+                let (result, error) = match result {
+                    Some(result) => match result.await {
+                        Ok(result) => (Some(result), None),
+                        Err(error) => (
+                            None,
+                            Some(Error {
+                                code: INTERNAL_ERROR,
+                                message: error.to_string(),
+                            }),
+                        ),
+                    },
+                    None => (
+                        None,
+                        Some(Error {
+                            code: METHOD_NOT_FOUND,
+                            message: format!("method not found: {method}"),
+                        }),
+                    ),
+                };
+                let response = serde_json::to_string(&OutgoingResponse {
+                    jsonrpc: JSON_RPC_VERSION,
+                    id,
+                    result,
+                    error,
+                })
+                .unwrap();
+                outbound_tx
+                    .try_send(response)
+                    .context("failed to send the response to the context server")
+                    .log_err();
+            })
+            .detach();
+    }
+
     /// Handles the stderr output from the context server.
     /// Continuously reads and logs any error messages from the server.
     async fn handle_stderr<Stderr>(stderr: Stderr) -> anyhow::Result<()>
@@ -352,7 +562,7 @@ impl Client {
         let send = self
             .outbound_tx
             .try_send(request)
-            .context("failed to write to context server's stdin");
+            .context("failed to send the message to the context server");
 
         let executor = self.executor.clone();
         let started = Instant::now();
@@ -407,6 +617,15 @@ impl Client {
             .insert(method, Box::new(f));
     }
 
+    /// Registers the handler of a request the server makes to the client. The task it returns
+    /// resolves to the result of the request, or to the error the server is answered with.
+    pub fn on_request<F>(&self, method: &'static str, f: F)
+    where
+        F: 'static + Send + FnMut(Value, AsyncAppContext) -> Task<Result<Value>>,
+    {
+        self.request_handlers.lock().insert(method, Box::new(f));
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -438,3 +657,80 @@ impl fmt::Debug for Client {
             .finish_non_exhaustive()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::TestAppContext;
+    use serde_json::json;
+
+    #[gpui::test]
+    async fn test_handle_server_request(cx: &mut TestAppContext) {
+        let notification_handlers = Mutex::new(HashMap::<_, NotificationHandler>::default());
+        let request_handlers = Mutex::new(HashMap::<_, RequestHandler>::default());
+        let response_handlers = Mutex::new(Some(HashMap::<_, ResponseHandler>::default()));
+        let (outbound_tx, outbound_rx) = channel::unbounded::<String>();
+        let async_cx = cx.to_async();
+
+        let handler: RequestHandler =
+            Box::new(|params, _cx| Task::ready(Ok(json!({ "echo": params }))));
+        request_handlers
+            .lock()
+            .insert("sampling/createMessage", handler);
+        let handler: RequestHandler = Box::new(|_params, _cx| Task::ready(Err(anyhow!("denied"))));
+        request_handlers.lock().insert("roots/list", handler);
+
+        let handle_message = |content: &str| {
+            Client::handle_message(
+                content,
+                &notification_handlers,
+                &request_handlers,
+                &response_handlers,
+                &outbound_tx,
+                &async_cx,
+            )
+        };
+        let next_response =
+            || serde_json::from_str::<Value>(&outbound_rx.try_recv().unwrap()).unwrap();
+
+        handle_message(
+            r#"{"jsonrpc":"2.0","id":7,"method":"sampling/createMessage","params":{"maxTokens":10}}"#,
+        );
+        cx.run_until_parked();
+        assert_eq!(
+            next_response(),
+            json!({ "jsonrpc": "2.0", "id": 7, "result": { "echo": { "maxTokens": 10 } } })
+        );
+
+        handle_message(r#"{"jsonrpc":"2.0","id":"a","method":"roots/list"}"#);
+        cx.run_until_parked();
+        assert_eq!(
+            next_response(),
+            json!({ "jsonrpc": "2.0", "id": "a", "error": { "code": INTERNAL_ERROR, "message": "denied" } })
+        );
+
+        handle_message(r#"{"jsonrpc":"2.0","id":8,"method":"elicitation/create","params":{}}"#);
+        cx.run_until_parked();
+        assert_eq!(
+            next_response(),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 8,
+                "error": { "code": METHOD_NOT_FOUND, "message": "method not found: elicitation/create" }
+            })
+        );
+
+        // Responses to the client's own requests are still routed to their handlers.
+        let (tx, rx) = oneshot::channel();
+        response_handlers.lock().as_mut().unwrap().insert(
+            RequestId::Int(1),
+            Box::new(move |result| {
+                tx.send(result).ok();
+            }),
+        );
+        handle_message(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#);
+        cx.run_until_parked();
+        assert!(rx.await.unwrap().is_ok());
+        assert!(outbound_rx.try_recv().is_err());
+    }
+}
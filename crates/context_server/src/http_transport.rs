@@ -0,0 +1,606 @@
+//! This module implements the HTTP transports of the Model Context Protocol, for context servers
+//! that run as HTTP services rather than as child processes.
+//!
+//! Two transports are supported:
+//! - The streamable HTTP transport, where each message is POSTed to the server's URL, which
+//!   answers with either a JSON body or a stream of server-sent events. Once the session is
+//!   initialized, the server may also send messages on a stream of its own, opened with a GET.
+//! - The older HTTP with SSE transport, where the client listens to a stream of server-sent
+//!   events, the first of which tells it where to POST its messages.
+//!
+//! Both exchange the same JSON-RPC messages as the stdio transport: the messages to send are
+//! received from one channel, and the messages received are forwarded to another, from which
+//! the [`Client`](crate::client::Client) dispatches them.
+
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use collections::HashMap;
+use futures::{
+    io::BufReader, select, stream::FuturesUnordered, AsyncBufReadExt as _, AsyncRead,
+    AsyncReadExt as _, FutureExt as _, StreamExt as _,
+};
+use http_client::{http::header, AsyncBody, HttpClient, Method, Request, Response, StatusCode};
+use parking_lot::Mutex;
+use serde_json::Value;
+use smol::channel;
+use url::Url;
+use util::ResultExt as _;
+
+use crate::client::INTERNAL_ERROR;
+
+/// The header in which a server using the streamable HTTP transport identifies the session.
+const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HttpTransport {
+    StreamableHttp,
+    Sse,
+}
+
+/// Where to reach a context server that runs as an HTTP service.
+#[derive(Debug, Clone)]
+pub struct ModelContextServerEndpoint {
+    pub url: Url,
+    pub headers: HashMap<String, String>,
+    pub transport: HttpTransport,
+}
+
+/// Exchanges messages with the server until either side closes the connection.
+pub(crate) async fn run(
+    endpoint: ModelContextServerEndpoint,
+    http_client: Arc<dyn HttpClient>,
+    outbound_rx: channel::Receiver<String>,
+    inbound_tx: channel::Sender<String>,
+) -> Result<()> {
+    match endpoint.transport {
+        HttpTransport::StreamableHttp => {
+            run_streamable_http(endpoint, http_client, outbound_rx, inbound_tx).await
+        }
+        HttpTransport::Sse => run_sse(endpoint, http_client, outbound_rx, inbound_tx).await,
+    }
+}
+
+async fn run_streamable_http(
+    endpoint: ModelContextServerEndpoint,
+    http_client: Arc<dyn HttpClient>,
+    outbound_rx: channel::Receiver<String>,
+    inbound_tx: channel::Sender<String>,
+) -> Result<()> {
+    let endpoint = &endpoint;
+    let session_id = Arc::new(Mutex::new(None));
+    let mut pending_posts = FuturesUnordered::new();
+
+    // Responses may be streamed for as long as the server works on a request, so each message
+    // is posted concurrently with the others.
+    loop {
+        select! {
+            message = outbound_rx.recv().fuse() => {
+                let Ok(message) = message else {
+                    return Ok(());
+                };
+                log::trace!("outgoing message: {}", message);
+
+                // Once the session is initialized, the server may send messages outside of the
+                // responses to the posted ones, such as the notifications about resources.
+                let method = serde_json::from_str::<Value>(&message)
+                    .ok()
+                    .and_then(|message| Some(message.get("method")?.as_str()?.to_string()));
+                if method.as_deref() == Some("notifications/initialized") {
+                    pending_posts.push(
+                        listen_streamable_http(
+                            endpoint,
+                            http_client.clone(),
+                            session_id.clone(),
+                            inbound_tx.clone(),
+                        )
+                        .boxed(),
+                    );
+                }
+
+                pending_posts.push(
+                    post_streamable_http_message(
+                        endpoint,
+                        http_client.clone(),
+                        session_id.clone(),
+                        message,
+                        inbound_tx.clone(),
+                    )
+                    .boxed(),
+                );
+            }
+            result = pending_posts.select_next_some() => {
+                result.log_err();
+            }
+        }
+    }
+}
+
+/// Forwards the messages the server sends on the stream it offers for the session, until it
+/// closes it. Servers aren't required to offer one.
+async fn listen_streamable_http(
+    endpoint: &ModelContextServerEndpoint,
+    http_client: Arc<dyn HttpClient>,
+    session_id: Arc<Mutex<Option<String>>>,
+    inbound_tx: channel::Sender<String>,
+) -> Result<()> {
+    let mut request = Request::builder()
+        .method(Method::GET)
+        .uri(endpoint.url.as_str())
+        .header(header::ACCEPT, "text/event-stream");
+    for (name, value) in &endpoint.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    if let Some(session_id) = session_id.lock().clone() {
+        request = request.header(SESSION_ID_HEADER, session_id);
+    }
+
+    let response = http_client.send(request.body(AsyncBody::empty())?).await?;
+    if response.status() == StatusCode::METHOD_NOT_ALLOWED {
+        log::debug!("{} doesn't offer a stream of messages", endpoint.url);
+        return Ok(());
+    }
+    let response = check_response(response).await?;
+    forward_sse_messages(response.into_body(), &inbound_tx).await
+}
+
+/// Posts a message to the server. A request that can't be posted is answered with the error,
+/// rather than being left to time out.
+async fn post_streamable_http_message(
+    endpoint: &ModelContextServerEndpoint,
+    http_client: Arc<dyn HttpClient>,
+    session_id: Arc<Mutex<Option<String>>>,
+    message: String,
+    inbound_tx: channel::Sender<String>,
+) -> Result<()> {
+    let request_id = serde_json::from_str::<Value>(&message)
+        .ok()
+        .filter(|message| message.get("method").is_some())
+        .and_then(|message| message.get("id").cloned());
+
+    let result =
+        send_streamable_http_message(endpoint, http_client, session_id, message, &inbound_tx).await;
+    if let (Err(error), Some(id)) = (&result, request_id) {
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": INTERNAL_ERROR, "message": format!("{error:#}") },
+        });
+        inbound_tx.send(response.to_string()).await.ok();
+    }
+    result
+}
+
+async fn send_streamable_http_message(
+    endpoint: &ModelContextServerEndpoint,
+    http_client: Arc<dyn HttpClient>,
+    session_id: Arc<Mutex<Option<String>>>,
+    message: String,
+    inbound_tx: &channel::Sender<String>,
+) -> Result<()> {
+    let mut request = Request::builder()
+        .method(Method::POST)
+        .uri(endpoint.url.as_str())
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::ACCEPT, "application/json, text/event-stream");
+    for (name, value) in &endpoint.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    if let Some(session_id) = session_id.lock().clone() {
+        request = request.header(SESSION_ID_HEADER, session_id);
+    }
+
+    let response = http_client
+        .send(request.body(AsyncBody::from(message))?)
+        .await?;
+    let mut response = check_response(response).await?;
+    if let Some(id) = response
+        .headers()
+        .get(SESSION_ID_HEADER)
+        .and_then(|id| id.to_str().ok())
+    {
+        *session_id.lock() = Some(id.to_string());
+    }
+
+    if content_type(&response).starts_with("text/event-stream") {
+        forward_sse_messages(response.into_body(), inbound_tx).await
+    } else {
+        let mut body = String::new();
+        response.body_mut().read_to_string(&mut body).await?;
+        if body.trim().is_empty() {
+            return Ok(());
+        }
+
+        // The server may answer with a batch of messages.
+        match serde_json::from_str::<Value>(&body)? {
+            Value::Array(messages) => {
+                for message in messages {
+                    inbound_tx.send(message.to_string()).await?;
+                }
+            }
+            _ => inbound_tx.send(body).await?,
+        }
+        Ok(())
+    }
+}
+
+async fn run_sse(
+    endpoint: ModelContextServerEndpoint,
+    http_client: Arc<dyn HttpClient>,
+    outbound_rx: channel::Receiver<String>,
+    inbound_tx: channel::Sender<String>,
+) -> Result<()> {
+    let mut request = Request::builder()
+        .method(Method::GET)
+        .uri(endpoint.url.as_str())
+        .header(header::ACCEPT, "text/event-stream");
+    for (name, value) in &endpoint.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    let response = http_client.send(request.body(AsyncBody::empty())?).await?;
+    let response = check_response(response).await?;
+
+    let mut lines = BufReader::new(response.into_body()).lines();
+    let mut parser = SseParser::default();
+
+    // The server first tells where to post the messages, which may be relative to its URL.
+    let messages_url = loop {
+        let line = lines
+            .next()
+            .await
+            .ok_or_else(|| anyhow!("the server closed the stream before sending its endpoint"))??;
+        if let Some(event) = parser.feed_line(&line) {
+            if event.event.as_deref() == Some("endpoint") {
+                break endpoint
+                    .url
+                    .join(event.data.trim())
+                    .context("invalid endpoint sent by the server")?;
+            }
+        }
+    };
+    log::debug!("posting messages for {} to {}", endpoint.url, messages_url);
+
+    let receive = async {
+        while let Some(line) = lines.next().await {
+            if let Some(event) = parser.feed_line(&line?) {
+                if is_message_event(&event) {
+                    inbound_tx.send(event.data).await?;
+                }
+            }
+        }
+        anyhow::Ok(())
+    };
+    let send = async {
+        while let Ok(message) = outbound_rx.recv().await {
+            log::trace!("outgoing message: {}", message);
+            let mut request = Request::builder()
+                .method(Method::POST)
+                .uri(messages_url.as_str())
+                .header(header::CONTENT_TYPE, "application/json");
+            for (name, value) in &endpoint.headers {
+                request = request.header(name.as_str(), value.as_str());
+            }
+            let response = http_client
+                .send(request.body(AsyncBody::from(message))?)
+                .await?;
+            check_response(response).await?;
+        }
+        anyhow::Ok(())
+    };
+
+    select! {
+        result = receive.fuse() => result,
+        result = send.fuse() => result,
+    }
+}
+
+async fn check_response(mut response: Response<AsyncBody>) -> Result<Response<AsyncBody>> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await.ok();
+    bail!(
+        "context server responded with {}: {}",
+        response.status(),
+        body
+    )
+}
+
+fn content_type(response: &Response<AsyncBody>) -> &str {
+    response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .unwrap_or_default()
+}
+
+/// Forwards the JSON-RPC messages in a stream of server-sent events until it ends.
+async fn forward_sse_messages(
+    body: impl AsyncRead + Unpin,
+    inbound_tx: &channel::Sender<String>,
+) -> Result<()> {
+    let mut lines = BufReader::new(body).lines();
+    let mut parser = SseParser::default();
+    while let Some(line) = lines.next().await {
+        if let Some(event) = parser.feed_line(&line?) {
+            if is_message_event(&event) {
+                inbound_tx.send(event.data).await?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn is_message_event(event: &SseEvent) -> bool {
+    matches!(event.event.as_deref(), None | Some("message")) && !event.data.is_empty()
+}
+
+#[derive(Debug, PartialEq)]
+struct SseEvent {
+    event: Option<String>,
+    data: String,
+}
+
+/// Parses the lines of a stream of server-sent events into events.
+#[derive(Default)]
+struct SseParser {
+    event: Option<String>,
+    data: Vec<String>,
+}
+
+impl SseParser {
+    /// Feeds the next line of the stream, returning an event when the line ends one.
+    fn feed_line(&mut self, line: &str) -> Option<SseEvent> {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        if line.is_empty() {
+            if self.data.is_empty() {
+                self.event = None;
+                return None;
+            }
+            return Some(SseEvent {
+                event: self.event.take(),
+                data: std::mem::take(&mut self.data).join("\n"),
+            });
+        }
+        if line.starts_with(':') {
+            return None;
+        }
+
+        let (field, value) = line.split_once(':').unwrap_or((line, ""));
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match field {
+            "event" => self.event = Some(value.to_string()),
+            "data" => self.data.push(value.to_string()),
+            _ => {}
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{Client, ContextServerId};
+    use futures::{channel::mpsc, AsyncReadExt as _, TryStreamExt as _};
+    use gpui::TestAppContext;
+    use http_client::FakeHttpClient;
+    use serde_json::json;
+
+    #[test]
+    fn test_sse_parser() {
+        let mut parser = SseParser::default();
+        let events = [
+            ": a comment",
+            "event: endpoint",
+            "data: /messages?session_id=1",
+            "",
+            "",
+            "data: {\"jsonrpc\":",
+            "data:\"2.0\"}\r",
+            "\r",
+        ]
+        .into_iter()
+        .filter_map(|line| parser.feed_line(line))
+        .collect::<Vec<_>>();
+
+        assert_eq!(
+            events,
+            [
+                SseEvent {
+                    event: Some("endpoint".into()),
+                    data: "/messages?session_id=1".into(),
+                },
+                SseEvent {
+                    event: None,
+                    data: "{\"jsonrpc\":\n\"2.0\"}".into(),
+                },
+            ]
+        );
+    }
+
+    fn response(id: &Value, result: Value) -> String {
+        json!({ "jsonrpc": "2.0", "id": id, "result": result }).to_string()
+    }
+
+    #[gpui::test]
+    async fn test_streamable_http_transport(cx: &mut TestAppContext) {
+        let http_client = FakeHttpClient::create(|request| async move {
+            assert_eq!(request.uri(), "http://localhost:3000/mcp");
+            assert_eq!(request.headers()["Authorization"], "Bearer token");
+            let session_id = request
+                .headers()
+                .get(SESSION_ID_HEADER)
+                .map(|id| id.to_str().unwrap().to_string());
+
+            // Notifies the client on the stream of the session.
+            if request.method() == Method::GET {
+                assert_eq!(session_id.as_deref(), Some("session-1"));
+                let notification = json!({
+                    "jsonrpc": "2.0",
+                    "method": "notifications/resources/updated",
+                    "params": { "uri": "file:///notes.txt" },
+                });
+                return Ok(Response::builder()
+                    .header(header::CONTENT_TYPE, "text/event-stream")
+                    .body(format!("data: {notification}\n\n").into())?);
+            }
+
+            let mut body = String::new();
+            request.into_body().read_to_string(&mut body).await?;
+            let message: Value = serde_json::from_str(&body)?;
+            let Some(id) = message.get("id") else {
+                // Notifications are only acknowledged.
+                assert_eq!(session_id.as_deref(), Some("session-1"));
+                return Ok(Response::builder().status(202).body(AsyncBody::empty())?);
+            };
+
+            match message["method"].as_str() {
+                // Answers the first request with a stream, which starts the session.
+                Some("initialize") => {
+                    assert_eq!(session_id, None);
+                    let body = format!(
+                        "event: message\ndata: {}\n\n",
+                        response(id, json!({ "protocolVersion": "2024-11-05" }))
+                    );
+                    Ok(Response::builder()
+                        .header(header::CONTENT_TYPE, "text/event-stream")
+                        .header(SESSION_ID_HEADER, "session-1")
+                        .body(body.into())?)
+                }
+                Some("tools/call") => Ok(Response::builder()
+                    .status(500)
+                    .body("the tool crashed".into())?),
+                _ => {
+                    assert_eq!(session_id.as_deref(), Some("session-1"));
+                    Ok(Response::builder()
+                        .header(header::CONTENT_TYPE, "application/json")
+                        .body(response(id, json!({ "echo": message["params"] })).into())?)
+                }
+            }
+        });
+
+        let client = Client::new_http(
+            ContextServerId("remote".into()),
+            ModelContextServerEndpoint {
+                url: "http://localhost:3000/mcp".parse().unwrap(),
+                headers: HashMap::from_iter([("Authorization".into(), "Bearer token".into())]),
+                transport: HttpTransport::StreamableHttp,
+            },
+            http_client,
+            cx.to_async(),
+        )
+        .unwrap();
+
+        let notifications = Arc::new(Mutex::new(Vec::new()));
+        client.on_notification("notifications/resources/updated", {
+            let notifications = notifications.clone();
+            move |params, _| notifications.lock().push(params)
+        });
+
+        let result = client
+            .request::<Value>("initialize", json!({}))
+            .await
+            .unwrap();
+        assert_eq!(result, json!({ "protocolVersion": "2024-11-05" }));
+        client
+            .notify("notifications/initialized", json!({}))
+            .unwrap();
+        let result = client
+            .request::<Value>("tools/list", json!({ "cursor": "1" }))
+            .await
+            .unwrap();
+        assert_eq!(result, json!({ "echo": { "cursor": "1" } }));
+
+        // A request that fails to be posted fails right away, rather than timing out.
+        let error = client
+            .request::<Value>("tools/call", json!({}))
+            .await
+            .unwrap_err();
+        assert!(
+            error.to_string().contains("the tool crashed"),
+            "unexpected error: {error}"
+        );
+
+        cx.run_until_parked();
+        assert_eq!(
+            *notifications.lock(),
+            [json!({ "uri": "file:///notes.txt" })]
+        );
+    }
+
+    #[gpui::test]
+    async fn test_sse_transport(cx: &mut TestAppContext) {
+        let (events_tx, events_rx) = mpsc::unbounded::<std::io::Result<Vec<u8>>>();
+        let events_rx = Arc::new(Mutex::new(Some(events_rx)));
+        let http_client = FakeHttpClient::create(move |request| {
+            let events_tx = events_tx.clone();
+            let events_rx = events_rx.clone();
+            async move {
+                if request.method() == Method::GET {
+                    assert_eq!(request.uri(), "http://localhost:3000/sse");
+                    let events_rx = events_rx.lock().take().expect("connected twice");
+                    events_tx.unbounded_send(Ok(
+                        b"event: endpoint\ndata: /messages?session_id=1\n\n".to_vec(),
+                    ))?;
+                    return Ok(Response::builder()
+                        .header(header::CONTENT_TYPE, "text/event-stream")
+                        .body(AsyncBody::from_reader(events_rx.into_async_read()))?);
+                }
+
+                assert_eq!(request.uri(), "http://localhost:3000/messages?session_id=1");
+                let mut body = String::new();
+                request.into_body().read_to_string(&mut body).await?;
+                let message: Value = serde_json::from_str(&body)?;
+                if let Some(id) = message.get("id") {
+                    // Notifies the client before answering its request, over the event stream.
+                    let notification = json!({
+                        "jsonrpc": "2.0",
+                        "method": "notifications/message",
+                        "params": { "data": "working" },
+                    });
+                    let event = format!(
+                        "data: {notification}\n\nevent: message\ndata: {}\n\n",
+                        response(id, json!({ "method": message["method"] }))
+                    );
+                    events_tx.unbounded_send(Ok(event.into_bytes()))?;
+                }
+                Ok(Response::builder().status(202).body(AsyncBody::empty())?)
+            }
+        });
+
+        let client = Client::new_http(
+            ContextServerId("remote".into()),
+            ModelContextServerEndpoint {
+                url: "http://localhost:3000/sse".parse().unwrap(),
+                headers: HashMap::default(),
+                transport: HttpTransport::Sse,
+            },
+            http_client,
+            cx.to_async(),
+        )
+        .unwrap();
+
+        let notifications = Arc::new(Mutex::new(Vec::new()));
+        client.on_notification("notifications/message", {
+            let notifications = notifications.clone();
+            move |params, _| notifications.lock().push(params)
+        });
+
+        let result = client
+            .request::<Value>("initialize", json!({}))
+            .await
+            .unwrap();
+        assert_eq!(result, json!({ "method": "initialize" }));
+        let result = client
+            .request::<Value>("prompts/list", json!({}))
+            .await
+            .unwrap();
+        assert_eq!(result, json!({ "method": "prompts/list" }));
+        assert_eq!(
+            *notifications.lock(),
+            [json!({ "data": "working" }), json!({ "data": "working" })]
+        );
+    }
+}
@@ -17,7 +17,7 @@
 use std::path::Path;
 use std::sync::Arc;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context as _, Result};
 use collections::HashMap;
 use command_palette_hooks::CommandPaletteFilter;
 use gpui::{AsyncAppContext, EventEmitter, Model, ModelContext, Subscription, Task, WeakModel};
@@ -25,9 +25,11 @@ use log;
 use parking_lot::RwLock;
 use project::Project;
 use settings::{Settings, SettingsStore};
+use url::Url;
 use util::ResultExt as _;
 
-use crate::{ContextServerSettings, ServerConfig};
+use crate::http_transport::{HttpTransport, ModelContextServerEndpoint};
+use crate::{ContextServerSettings, ServerConfig, ServerTransport};
 
 use crate::{
     client::{self, Client},
@@ -63,18 +65,37 @@ impl ContextServer {
 
     pub async fn start(self: Arc<Self>, cx: &AsyncAppContext) -> Result<()> {
         log::info!("starting context server {}", self.id);
-        let Some(command) = &self.config.command else {
-            bail!("no command specified for server {}", self.id);
+        let client = if let Some(url) = &self.config.url {
+            let url = url
+                .parse::<url::Url>()
+                .with_context(|| format!("invalid URL for server {}", self.id))?;
+            let transport = match self.config.transport.unwrap_or_default() {
+                ServerTransport::StreamableHttp => HttpTransport::StreamableHttp,
+                ServerTransport::Sse => HttpTransport::Sse,
+            };
+            Client::new_http(
+                client::ContextServerId(self.id.clone()),
+                ModelContextServerEndpoint {
+                    url,
+                    headers: self.config.headers.clone().unwrap_or_default(),
+                    transport,
+                },
+                cx.update(|cx| cx.http_client())?,
+                cx.clone(),
+            )?
+        } else if let Some(command) = &self.config.command {
+            Client::new(
+                client::ContextServerId(self.id.clone()),
+                client::ModelContextServerBinary {
+                    executable: Path::new(&command.path).to_path_buf(),
+                    args: command.args.clone(),
+                    env: command.env.clone(),
+                },
+                cx.clone(),
+            )?
+        } else {
+            bail!("no command or URL specified for server {}", self.id);
         };
-        let client = Client::new(
-            client::ContextServerId(self.id.clone()),
-            client::ModelContextServerBinary {
-                executable: Path::new(&command.path).to_path_buf(),
-                args: command.args.clone(),
-                env: command.env.clone(),
-            },
-            cx.clone(),
-        )?;
 
         let protocol = crate::protocol::ModelContextProtocol::new(client);
         let client_info = types::Implementation {
@@ -82,6 +103,7 @@ impl ContextServer {
             version: env!("CARGO_PKG_VERSION").to_string(),
         };
         let initialized_protocol = protocol.initialize(client_info).await?;
+        crate::sampling::register_handler(&initialized_protocol, self.id.clone());
 
         log::debug!(
             "context server {} initialized: {:?}",
@@ -114,6 +136,7 @@ pub struct ContextServerManager {
 pub enum Event {
     ServerStarted { server_id: Arc<str> },
     ServerStopped { server_id: Arc<str> },
+    ResourceUpdated { server_id: Arc<str>, uri: Url },
 }
 
 impl EventEmitter<Event> for ContextServerManager {}
@@ -192,6 +215,7 @@ impl ContextServerManager {
                 let config = server.config();
                 let new_server = Arc::new(ContextServer::new(id.clone(), config));
                 new_server.clone().start(&cx).await?;
+                Self::observe_resource_updates(this.clone(), &new_server);
                 this.update(&mut cx, |this, cx| {
                     this.servers.insert(id.clone(), new_server);
                     cx.emit(Event::ServerStopped {
@@ -214,6 +238,33 @@ impl ContextServerManager {
             .collect()
     }
 
+    /// Reports the updates of the resources the clients subscribe to on a started server as
+    /// [`Event::ResourceUpdated`] events.
+    fn observe_resource_updates(this: WeakModel<Self>, server: &ContextServer) {
+        let Some(protocol) = server.client() else {
+            return;
+        };
+        let server_id = server.id();
+        protocol.on_notification(
+            types::NotificationType::ResourcesUpdated.as_str(),
+            move |params, mut cx| {
+                let Some(params) =
+                    serde_json::from_value::<types::ResourcesUpdatedParams>(params).log_err()
+                else {
+                    return;
+                };
+                let server_id = server_id.clone();
+                this.update(&mut cx, |_, cx| {
+                    cx.emit(Event::ResourceUpdated {
+                        server_id,
+                        uri: params.uri,
+                    })
+                })
+                .ok();
+            },
+        );
+    }
+
     async fn maintain_servers(this: WeakModel<Self>, mut cx: AsyncAppContext) -> Result<()> {
         let mut desired_servers = HashMap::default();
 
@@ -234,7 +285,7 @@ impl ContextServerManager {
             registry.read_with(&cx, |registry, _| registry.context_server_factories())?
         {
             let config = desired_servers.entry(id).or_default();
-            if config.command.is_none() {
+            if config.command.is_none() && config.url.is_none() {
                 if let Some(extension_command) = factory(project.clone(), &cx).await.log_err() {
                     config.command = Some(extension_command);
                 }
@@ -276,7 +327,8 @@ impl ContextServerManager {
         }
 
         for (id, server) in servers_to_start {
-            if server.start(&cx).await.log_err().is_some() {
+            if server.clone().start(&cx).await.log_err().is_some() {
+                Self::observe_resource_updates(this.clone(), &server);
                 this.update(&mut cx, |_, cx| {
                     cx.emit(Event::ServerStarted { server_id: id })
                 })?;
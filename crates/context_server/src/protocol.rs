@@ -7,6 +7,8 @@
 
 use anyhow::Result;
 use collections::HashMap;
+use gpui::{AsyncAppContext, Task};
+use url::Url;
 
 use crate::client::Client;
 use crate::types;
@@ -34,7 +36,7 @@ impl ModelContextProtocol {
             protocol_version: types::ProtocolVersion(types::LATEST_PROTOCOL_VERSION.to_string()),
             capabilities: types::ClientCapabilities {
                 experimental: None,
-                sampling: None,
+                sampling: Some(serde_json::json!({})),
                 roots: None,
             },
             meta: None,
@@ -136,6 +138,57 @@ impl InitializedContextServerProtocol {
         Ok(response)
     }
 
+    /// Reads the contents of an MCP resource.
+    pub async fn read_resource(&self, uri: Url) -> Result<types::ResourcesReadResponse> {
+        self.check_capability(ServerCapability::Resources)?;
+
+        let params = types::ResourcesReadParams { uri, meta: None };
+
+        let response: types::ResourcesReadResponse = self
+            .inner
+            .request(types::RequestType::ResourcesRead.as_str(), params)
+            .await?;
+
+        Ok(response)
+    }
+
+    /// Whether the server notifies the client about updates of the resources it subscribes to.
+    pub fn can_subscribe_to_resources(&self) -> bool {
+        self.initialize
+            .capabilities
+            .resources
+            .as_ref()
+            .and_then(|resources| resources.subscribe)
+            .unwrap_or(false)
+    }
+
+    /// Subscribes to the updates of an MCP resource, which the server then reports with
+    /// `notifications/resources/updated`.
+    pub async fn subscribe_to_resource(&self, uri: Url) -> Result<()> {
+        self.check_capability(ServerCapability::Resources)?;
+
+        let params = types::ResourcesSubscribeParams { uri, meta: None };
+
+        self.inner
+            .request::<serde_json::Value>(types::RequestType::ResourcesSubscribe.as_str(), params)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Cancels a subscription to the updates of an MCP resource.
+    pub async fn unsubscribe_from_resource(&self, uri: Url) -> Result<()> {
+        self.check_capability(ServerCapability::Resources)?;
+
+        let params = types::ResourcesUnsubscribeParams { uri, meta: None };
+
+        self.inner
+            .request::<serde_json::Value>(types::RequestType::ResourcesUnsubscribe.as_str(), params)
+            .await?;
+
+        Ok(())
+    }
+
     /// Executes a prompt with the given arguments and returns the result.
     pub async fn run_prompt<P: AsRef<str>>(
         &self,
@@ -231,4 +284,20 @@ impl InitializedContextServerProtocol {
     ) -> Result<R> {
         self.inner.request(method, params).await
     }
+
+    pub fn on_notification<F>(&self, method: &'static str, f: F)
+    where
+        F: 'static + Send + FnMut(serde_json::Value, AsyncAppContext),
+    {
+        self.inner.on_notification(method, f)
+    }
+
+    pub fn on_request<F>(&self, method: &'static str, f: F)
+    where
+        F: 'static
+            + Send
+            + FnMut(serde_json::Value, AsyncAppContext) -> Task<Result<serde_json::Value>>,
+    {
+        self.inner.on_request(method, f)
+    }
 }
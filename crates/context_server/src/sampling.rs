@@ -0,0 +1,257 @@
+//! Answers the `sampling/createMessage` requests of context servers.
+//!
+//! A server can ask the client to generate a message for it. The request is shown to the user,
+//! and once they allow it, it is completed with the active language model of the assistant.
+
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Context as _, Result};
+use futures::StreamExt as _;
+use gpui::{AsyncAppContext, PromptLevel};
+use language_model::{
+    LanguageModel, LanguageModelRegistry, LanguageModelRequest, LanguageModelRequestMessage,
+};
+
+use crate::protocol::InitializedContextServerProtocol;
+use crate::types;
+
+/// The number of characters of the request that are shown to the user when asking for approval.
+const MAX_APPROVAL_DETAIL_CHARS: usize = 2000;
+
+/// The number of characters counted as a token when enforcing the `maxTokens` of a request, as
+/// the models don't report the tokens of a completion while it streams.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Registers the handler of the sampling requests of a server.
+pub(crate) fn register_handler(protocol: &InitializedContextServerProtocol, server_id: Arc<str>) {
+    protocol.on_request(
+        types::RequestType::CreateMessage.as_str(),
+        move |params, cx| {
+            let server_id = server_id.clone();
+            cx.spawn(|cx| async move {
+                let request: types::CreateMessageRequest = serde_json::from_value(params)?;
+                let result = create_message(&server_id, request, cx).await?;
+                Ok(serde_json::to_value(result)?)
+            })
+        },
+    );
+}
+
+async fn create_message(
+    server_id: &str,
+    request: types::CreateMessageRequest,
+    mut cx: AsyncAppContext,
+) -> Result<types::CreateMessageResult> {
+    let model = cx
+        .update(|cx| LanguageModelRegistry::read_global(cx).active_model())?
+        .context("no language model is configured")?;
+    let request = to_sampling_request(request)?;
+
+    if !request_approved(server_id, &request, model.as_ref(), &mut cx).await? {
+        bail!("the user declined the request");
+    }
+
+    log::info!(
+        "completing sampling request of context server {server_id} with {}, which prefers {:?}",
+        model.name().0,
+        request.model_hints
+    );
+    let max_len = request.max_tokens as usize * CHARS_PER_TOKEN;
+    let mut stream = model
+        .stream_completion_text(request.request, &cx)
+        .await?
+        .stream;
+    let mut text = String::new();
+    let mut stop_reason = "endTurn";
+    while let Some(chunk) = stream.next().await {
+        text.push_str(&chunk?);
+        if text.len() >= max_len {
+            let mut end = max_len;
+            while !text.is_char_boundary(end) {
+                end -= 1;
+            }
+            text.truncate(end);
+            stop_reason = "maxTokens";
+            break;
+        }
+    }
+
+    Ok(types::CreateMessageResult {
+        role: types::Role::Assistant,
+        content: types::MessageContent::Text {
+            text,
+            annotations: None,
+        },
+        model: model.id().0.to_string(),
+        stop_reason: Some(stop_reason.to_string()),
+    })
+}
+
+/// Asks the user, in the active window, whether the server may use the model to complete the
+/// request.
+async fn request_approved(
+    server_id: &str,
+    request: &SamplingRequest,
+    model: &dyn LanguageModel,
+    cx: &mut AsyncAppContext,
+) -> Result<bool> {
+    let window = cx
+        .update(|cx| cx.active_window())?
+        .context("no window to ask for approval in")?;
+
+    let message = format!(
+        "The {server_id} context server wants to generate a message of up to {} tokens with {}",
+        request.max_tokens,
+        model.name().0
+    );
+    let mut detail = request
+        .request
+        .messages
+        .iter()
+        .map(|message| format!("{}: {}", message.role, message.string_contents()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    if !request.model_hints.is_empty() {
+        detail = format!(
+            "The server prefers {}.\n\n{detail}",
+            request.model_hints.join(", ")
+        );
+    }
+    let detail = util::truncate_and_trailoff(&detail, MAX_APPROVAL_DETAIL_CHARS);
+
+    let answer = window.update(cx, |_, cx| {
+        cx.prompt(
+            PromptLevel::Info,
+            &message,
+            Some(&detail),
+            &["Allow", "Deny"],
+        )
+    })?;
+    Ok(answer.await? == 0)
+}
+
+/// A sampling request of a server, as completed with a language model.
+struct SamplingRequest {
+    request: LanguageModelRequest,
+    /// The number of tokens the completion is limited to.
+    max_tokens: u32,
+    /// The names of the models the server prefers. The request is completed with the active
+    /// model regardless, so they are only shown to the user.
+    model_hints: Vec<String>,
+}
+
+fn to_sampling_request(request: types::CreateMessageRequest) -> Result<SamplingRequest> {
+    let mut messages = Vec::with_capacity(request.messages.len() + 1);
+    if let Some(system_prompt) = request.system_prompt {
+        messages.push(LanguageModelRequestMessage {
+            role: language_model::Role::System,
+            content: vec![system_prompt.into()],
+            cache: false,
+        });
+    }
+
+    for message in request.messages {
+        let role = match message.role {
+            types::Role::User => language_model::Role::User,
+            types::Role::Assistant => language_model::Role::Assistant,
+        };
+        let types::MessageContent::Text { text, .. } = message.content else {
+            return Err(anyhow!("only text messages can be sampled"));
+        };
+        messages.push(LanguageModelRequestMessage {
+            role,
+            content: vec![text.into()],
+            cache: false,
+        });
+    }
+
+    let model_hints = request
+        .model_preferences
+        .and_then(|preferences| preferences.hints)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|hint| hint.name)
+        .collect();
+
+    Ok(SamplingRequest {
+        request: LanguageModelRequest {
+            messages,
+            tools: Vec::new(),
+            stop: request.stop_sequences.unwrap_or_default(),
+            temperature: request.temperature.map(|temperature| temperature as f32),
+        },
+        max_tokens: request.max_tokens,
+        model_hints,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use language_model::MessageContent;
+    use serde_json::json;
+
+    #[test]
+    fn test_to_sampling_request() {
+        let request: types::CreateMessageRequest = serde_json::from_value(json!({
+            "messages": [
+                { "role": "user", "content": { "type": "text", "text": "What is 2 + 2?" } },
+                { "role": "assistant", "content": { "type": "text", "text": "4" } },
+                { "role": "user", "content": { "type": "text", "text": "And 3 + 3?" } }
+            ],
+            "systemPrompt": "You are a calculator.",
+            "temperature": 0.5,
+            "maxTokens": 100,
+            "stopSequences": ["\n"],
+            "modelPreferences": {
+                "hints": [{ "name": "claude-3-sonnet" }, {}, { "name": "gpt-4o" }],
+                "speedPriority": 0.8
+            }
+        }))
+        .unwrap();
+
+        let request = to_sampling_request(request).unwrap();
+        assert_eq!(request.max_tokens, 100);
+        assert_eq!(request.model_hints, ["claude-3-sonnet", "gpt-4o"]);
+        let request = request.request;
+        assert_eq!(
+            request
+                .messages
+                .iter()
+                .map(|message| (message.role, message.content.clone()))
+                .collect::<Vec<_>>(),
+            [
+                (
+                    language_model::Role::System,
+                    vec![MessageContent::Text("You are a calculator.".into())]
+                ),
+                (
+                    language_model::Role::User,
+                    vec![MessageContent::Text("What is 2 + 2?".into())]
+                ),
+                (
+                    language_model::Role::Assistant,
+                    vec![MessageContent::Text("4".into())]
+                ),
+                (
+                    language_model::Role::User,
+                    vec![MessageContent::Text("And 3 + 3?".into())]
+                ),
+            ]
+        );
+        assert_eq!(request.stop, ["\n"]);
+        assert_eq!(request.temperature, Some(0.5));
+
+        let request: types::CreateMessageRequest = serde_json::from_value(json!({
+            "messages": [
+                {
+                    "role": "user",
+                    "content": { "type": "resource", "resource": { "uri": "file:///notes.txt" } }
+                }
+            ],
+            "maxTokens": 100
+        }))
+        .unwrap();
+        assert!(to_sampling_request(request).is_err());
+    }
+}
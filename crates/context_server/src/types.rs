@@ -19,6 +19,7 @@ pub enum RequestType {
     ListTools,
     ListResourceTemplates,
     ListRoots,
+    CreateMessage,
 }
 
 impl RequestType {
@@ -38,6 +39,7 @@ impl RequestType {
             RequestType::ListTools => "tools/list",
             RequestType::ListResourceTemplates => "resources/templates/list",
             RequestType::ListRoots => "roots/list",
+            RequestType::CreateMessage => "sampling/createMessage",
         }
     }
 }
@@ -179,6 +181,28 @@ pub enum ResourceContentsType {
     Blob(BlobResourceContents),
 }
 
+impl ResourcesReadResponse {
+    /// Returns the text of the resource contents, describing the binary ones instead of
+    /// including them.
+    pub fn text(&self) -> String {
+        self.contents
+            .iter()
+            .map(|contents| match contents {
+                ResourceContentsType::Text(contents) => contents.text.clone(),
+                ResourceContentsType::Blob(contents) => format!(
+                    "[binary contents of {} ({})]",
+                    contents.uri,
+                    contents
+                        .mime_type
+                        .as_deref()
+                        .unwrap_or("application/octet-stream")
+                ),
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ResourcesListResponse {
@@ -196,7 +220,7 @@ pub struct SamplingMessage {
     pub content: MessageContent,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateMessageRequest {
     pub messages: Vec<SamplingMessage>,
@@ -215,7 +239,7 @@ pub struct CreateMessageRequest {
     pub metadata: Option<serde_json::Value>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreateMessageResult {
     pub role: Role,
@@ -404,7 +428,7 @@ pub struct Implementation {
     pub version: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Resource {
     pub uri: Url,
@@ -513,6 +537,12 @@ impl NotificationType {
     }
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcesUpdatedParams {
+    pub uri: Url,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
 pub enum ClientNotification {
@@ -18,6 +18,18 @@ pub struct ServerConfig {
     ///
     /// This will override the command set by an extension.
     pub command: Option<ServerCommand>,
+    /// The URL of the context server, for servers that run as an HTTP service
+    /// rather than as a command.
+    ///
+    /// This takes precedence over `command`.
+    pub url: Option<String>,
+    /// The headers to send along with each request to the `url` of the
+    /// context server, e.g. to authenticate.
+    pub headers: Option<HashMap<String, String>>,
+    /// The transport to use to reach the `url` of the context server.
+    ///
+    /// Default: streamable_http
+    pub transport: Option<ServerTransport>,
     /// The settings for this context server.
     ///
     /// Consult the documentation for the context server to see what settings
@@ -40,6 +52,17 @@ pub struct ServerCommand {
     pub env: Option<HashMap<String, String>>,
 }
 
+#[derive(Deserialize, Serialize, Clone, Copy, PartialEq, Eq, JsonSchema, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerTransport {
+    /// The streamable HTTP transport, where messages are posted to the URL of the server.
+    #[default]
+    StreamableHttp,
+    /// The HTTP with SSE transport of older servers, where the server streams its messages
+    /// from its URL.
+    Sse,
+}
+
 #[derive(Deserialize, Serialize, Default, Clone, PartialEq, Eq, JsonSchema, Debug)]
 pub struct ContextServerSettings {
     /// Settings for context servers used in the Assistant.
@@ -0,0 +1,847 @@
+use crate::transport::{self, Event, Message, Request, Response};
+use crate::{DebugAdapterBinary, DebugAdapterName};
+use anyhow::{anyhow, Context as _, Result};
+use collections::HashMap;
+use dap_types::{
+    events, requests, Capabilities, DisconnectArguments, InitializeRequestArguments,
+    InitializeRequestArgumentsPathFormat,
+};
+use futures::{channel::oneshot, io::BufWriter, select, AsyncRead, AsyncWrite, Future, FutureExt};
+use gpui::{AsyncAppContext, BackgroundExecutor, Task};
+use parking_lot::{Mutex, RwLock};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use smol::{
+    channel,
+    io::{AsyncBufReadExt, BufReader},
+    process::Child,
+};
+use std::{
+    fmt,
+    path::Path,
+    process::Stdio,
+    sync::{
+        atomic::{AtomicU64, Ordering::SeqCst},
+        Arc,
+    },
+    time::Duration,
+};
+use util::ResultExt;
+
+const DAP_REQUEST_TIMEOUT: Duration = Duration::from_secs(60 * 2);
+const ADAPTER_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Handlers are shared so that they can be called without holding the lock on the handler map,
+// which would deadlock when a handler drops a subscription.
+type EventHandler = Arc<Mutex<dyn Send + FnMut(Option<Value>, AsyncAppContext)>>;
+type RequestHandler = Arc<Mutex<dyn Send + FnMut(u64, Option<Value>, AsyncAppContext)>>;
+type ResponseHandler = Box<dyn Send + FnOnce(Response)>;
+
+/// Identifies a running debug adapter.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct DebugAdapterClientId(pub usize);
+
+impl DebugAdapterClientId {
+    pub fn from_proto(id: u64) -> Self {
+        Self(id as usize)
+    }
+
+    pub fn to_proto(self) -> u64 {
+        self.0 as u64
+    }
+}
+
+impl fmt::Display for DebugAdapterClientId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A running debug adapter process, spoken to over the Debug Adapter Protocol.
+pub struct DebugAdapterClient {
+    id: DebugAdapterClientId,
+    name: DebugAdapterName,
+    next_seq: Arc<AtomicU64>,
+    outbound_tx: channel::Sender<Message>,
+    capabilities: RwLock<Capabilities>,
+    event_handlers: Arc<Mutex<HashMap<&'static str, EventHandler>>>,
+    request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
+    response_handlers: Arc<Mutex<Option<HashMap<u64, ResponseHandler>>>>,
+    executor: BackgroundExecutor,
+    #[allow(clippy::type_complexity)]
+    io_tasks: Mutex<Option<(Task<Option<()>>, Task<Option<()>>)>>,
+    process: Arc<Mutex<Option<Child>>>,
+}
+
+/// Handle to a debug adapter event or reverse request subscription.
+pub enum Subscription {
+    Event {
+        event: &'static str,
+        event_handlers: Option<Arc<Mutex<HashMap<&'static str, EventHandler>>>>,
+    },
+    Request {
+        command: &'static str,
+        request_handlers: Option<Arc<Mutex<HashMap<&'static str, RequestHandler>>>>,
+    },
+}
+
+impl DebugAdapterClient {
+    /// Starts a debug adapter process.
+    pub fn new(
+        id: DebugAdapterClientId,
+        name: DebugAdapterName,
+        binary: DebugAdapterBinary,
+        working_dir: &Path,
+        cx: AsyncAppContext,
+    ) -> Result<Self> {
+        log::info!(
+            "starting debug adapter process. command: {:?}, working directory: {:?}, args: {:?}",
+            binary.command,
+            working_dir,
+            &binary.arguments
+        );
+
+        let mut process = util::command::new_smol_command(&binary.command)
+            .current_dir(working_dir)
+            .args(&binary.arguments)
+            .envs(binary.env.unwrap_or_default())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| {
+                format!(
+                    "failed to spawn debug adapter. command: {:?}, working directory: {:?}, args: {:?}",
+                    binary.command, working_dir, &binary.arguments
+                )
+            })?;
+
+        let stdin = process.stdin.take().unwrap();
+        let stdout = process.stdout.take().unwrap();
+        let stderr = process.stderr.take().unwrap();
+        Ok(Self::new_internal(
+            id,
+            name,
+            stdin,
+            stdout,
+            Some(stderr),
+            Some(process),
+            cx,
+        ))
+    }
+
+    fn new_internal<Stdin, Stdout, Stderr>(
+        id: DebugAdapterClientId,
+        name: DebugAdapterName,
+        stdin: Stdin,
+        stdout: Stdout,
+        stderr: Option<Stderr>,
+        process: Option<Child>,
+        cx: AsyncAppContext,
+    ) -> Self
+    where
+        Stdin: AsyncWrite + Unpin + Send + 'static,
+        Stdout: AsyncRead + Unpin + Send + 'static,
+        Stderr: AsyncRead + Unpin + Send + 'static,
+    {
+        let (outbound_tx, outbound_rx) = channel::unbounded::<Message>();
+        let (inbound_tx, inbound_rx) = channel::unbounded::<Message>();
+        let next_seq = Arc::new(AtomicU64::new(1));
+        let event_handlers = Arc::new(Mutex::new(HashMap::<_, EventHandler>::default()));
+        let request_handlers = Arc::new(Mutex::new(HashMap::<_, RequestHandler>::default()));
+        let response_handlers =
+            Arc::new(Mutex::new(Some(HashMap::<_, ResponseHandler>::default())));
+
+        let stdout_task = cx
+            .background_executor()
+            .spawn(Self::handle_input(stdout, inbound_tx, response_handlers.clone()).log_err());
+        let stderr_task = stderr
+            .map(|stderr| {
+                let name = name.clone();
+                cx.background_executor()
+                    .spawn(Self::handle_stderr(stderr, name).log_err())
+            })
+            .unwrap_or_else(|| Task::ready(None));
+        let dispatch_task = cx.spawn({
+            let event_handlers = event_handlers.clone();
+            let request_handlers = request_handlers.clone();
+            let outbound_tx = outbound_tx.clone();
+            let next_seq = next_seq.clone();
+            let name = name.clone();
+            move |cx| {
+                Self::dispatch_incoming(
+                    name,
+                    inbound_rx,
+                    event_handlers,
+                    request_handlers,
+                    outbound_tx,
+                    next_seq,
+                    cx,
+                )
+            }
+        });
+        let input_task = cx.spawn(|_| async move {
+            let (stdout, stderr, _) = futures::join!(stdout_task, stderr_task, dispatch_task);
+            stdout.or(stderr)
+        });
+        let output_task = cx
+            .background_executor()
+            .spawn(Self::handle_output(stdin, outbound_rx, response_handlers.clone()).log_err());
+
+        Self {
+            id,
+            name,
+            next_seq,
+            outbound_tx,
+            capabilities: RwLock::new(Capabilities::default()),
+            event_handlers,
+            request_handlers,
+            response_handlers,
+            executor: cx.background_executor().clone(),
+            io_tasks: Mutex::new(Some((input_task, output_task))),
+            process: Arc::new(Mutex::new(process)),
+        }
+    }
+
+    async fn handle_input<Stdout>(
+        stdout: Stdout,
+        inbound_tx: channel::Sender<Message>,
+        response_handlers: Arc<Mutex<Option<HashMap<u64, ResponseHandler>>>>,
+    ) -> Result<()>
+    where
+        Stdout: AsyncRead + Unpin + Send + 'static,
+    {
+        let _clear_response_handlers = util::defer({
+            let response_handlers = response_handlers.clone();
+            move || {
+                response_handlers.lock().take();
+            }
+        });
+        let mut stdout = BufReader::new(stdout);
+        let mut buffer = Vec::new();
+        loop {
+            let message = transport::read_message(&mut stdout, &mut buffer).await?;
+            log::trace!("incoming DAP message: {message}");
+            match serde_json::from_str::<Message>(&message) {
+                Ok(Message::Response(response)) => {
+                    let handler = response_handlers
+                        .lock()
+                        .as_mut()
+                        .and_then(|handlers| handlers.remove(&response.request_seq));
+                    if let Some(handler) = handler {
+                        handler(response);
+                    } else {
+                        log::warn!("received DAP response for unknown request {response:?}");
+                    }
+                }
+                Ok(message) => inbound_tx.send(message).await?,
+                Err(error) => log::warn!("failed to deserialize DAP message: {error}\n{message}"),
+            }
+        }
+    }
+
+    async fn handle_stderr<Stderr>(stderr: Stderr, name: DebugAdapterName) -> Result<()>
+    where
+        Stderr: AsyncRead + Unpin + Send + 'static,
+    {
+        let mut stderr = BufReader::new(stderr);
+        let mut buffer = Vec::new();
+        loop {
+            buffer.clear();
+            if stderr.read_until(b'\n', &mut buffer).await? == 0 {
+                return Ok(());
+            }
+            if let Ok(message) = std::str::from_utf8(&buffer) {
+                log::debug!("debug adapter {name} stderr: {}", message.trim_end());
+            }
+        }
+    }
+
+    async fn handle_output<Stdin>(
+        stdin: Stdin,
+        outbound_rx: channel::Receiver<Message>,
+        response_handlers: Arc<Mutex<Option<HashMap<u64, ResponseHandler>>>>,
+    ) -> Result<()>
+    where
+        Stdin: AsyncWrite + Unpin + Send + 'static,
+    {
+        let _clear_response_handlers = util::defer({
+            let response_handlers = response_handlers.clone();
+            move || {
+                response_handlers.lock().take();
+            }
+        });
+        let mut stdin = BufWriter::new(stdin);
+        let mut content_len_buffer = Vec::new();
+        while let Ok(message) = outbound_rx.recv().await {
+            let message = serde_json::to_string(&message)?;
+            log::trace!("outgoing DAP message: {message}");
+            transport::write_message(&mut stdin, &message, &mut content_len_buffer).await?;
+        }
+        Ok(())
+    }
+
+    async fn dispatch_incoming(
+        name: DebugAdapterName,
+        inbound_rx: channel::Receiver<Message>,
+        event_handlers: Arc<Mutex<HashMap<&'static str, EventHandler>>>,
+        request_handlers: Arc<Mutex<HashMap<&'static str, RequestHandler>>>,
+        outbound_tx: channel::Sender<Message>,
+        next_seq: Arc<AtomicU64>,
+        cx: AsyncAppContext,
+    ) {
+        while let Ok(message) = inbound_rx.recv().await {
+            match message {
+                Message::Event(Event { event, body, .. }) => {
+                    let handler = event_handlers.lock().get(event.as_str()).cloned();
+                    if let Some(handler) = handler {
+                        (&mut *handler.lock())(body, cx.clone());
+                    } else {
+                        log::debug!("debug adapter {name} sent unhandled event {event}");
+                    }
+                }
+                Message::Request(Request {
+                    seq,
+                    command,
+                    arguments,
+                }) => {
+                    let handler = request_handlers.lock().get(command.as_str()).cloned();
+                    if let Some(handler) = handler {
+                        (&mut *handler.lock())(seq, arguments, cx.clone());
+                    } else {
+                        log::warn!("debug adapter {name} sent unsupported request {command}");
+                        outbound_tx
+                            .try_send(Message::Response(Response {
+                                seq: next_seq.fetch_add(1, SeqCst),
+                                request_seq: seq,
+                                success: false,
+                                message: Some(format!("unsupported request {command}")),
+                                command,
+                                body: None,
+                            }))
+                            .ok();
+                    }
+                }
+                Message::Response(_) => {}
+            }
+
+            // Don't starve the main thread when receiving lots of messages at once.
+            smol::future::yield_now().await;
+        }
+    }
+
+    /// Sends the `initialize` request and records the adapter's capabilities.
+    ///
+    /// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Requests_Initialize)
+    pub fn initialize(&self) -> impl Future<Output = Result<()>> + '_ {
+        let request = self.request::<requests::Initialize>(InitializeRequestArguments {
+            client_id: Some("zed".to_string()),
+            client_name: Some("Zed".to_string()),
+            adapter_id: self.name.to_string(),
+            locale: Some("en-US".to_string()),
+            lines_start_at1: Some(true),
+            columns_start_at1: Some(true),
+            path_format: Some(InitializeRequestArgumentsPathFormat::Path),
+            supports_variable_type: Some(true),
+            supports_variable_paging: Some(false),
+            supports_run_in_terminal_request: Some(false),
+            supports_memory_references: Some(false),
+            supports_progress_reporting: Some(false),
+            supports_invalidated_event: Some(false),
+            supports_memory_event: Some(false),
+            supports_args_can_be_interpreted_by_shell: Some(false),
+            supports_start_debugging_request: Some(false),
+        });
+        async move {
+            let capabilities = request.await?;
+            *self.capabilities.write() = capabilities;
+            Ok(())
+        }
+    }
+
+    /// Sends a `disconnect` request to the adapter and prepares the [`DebugAdapterClient`] to be dropped.
+    pub fn shutdown(&self) -> Option<impl 'static + Send + Future<Output = Option<()>>> {
+        let tasks = self.io_tasks.lock().take()?;
+        let disconnect = self.request::<requests::Disconnect>(DisconnectArguments {
+            restart: Some(false),
+            terminate_debuggee: Some(true),
+            suspend_debuggee: Some(false),
+        });
+        let outbound_tx = self.outbound_tx.clone();
+        let response_handlers = self.response_handlers.clone();
+        let process = self.process.clone();
+        let name = self.name.clone();
+        let mut timer = self.executor.timer(ADAPTER_SHUTDOWN_TIMEOUT).fuse();
+        Some(
+            async move {
+                log::debug!("debug adapter shutdown started");
+
+                select! {
+                    result = disconnect.fuse() => {
+                        result.log_err();
+                    }
+
+                    _ = timer => {
+                        log::info!("timeout waiting for debug adapter {name} to disconnect");
+                    },
+                }
+
+                outbound_tx.close();
+                response_handlers.lock().take();
+                process.lock().take().map(|mut child| child.kill());
+                log::debug!("debug adapter shutdown finished");
+
+                drop(tasks);
+                anyhow::Ok(())
+            }
+            .log_err(),
+        )
+    }
+
+    /// Register a handler for events of the given type sent by the adapter.
+    ///
+    /// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Base_Protocol_Event)
+    #[must_use]
+    pub fn on_event<E, F>(&self, mut f: F) -> Subscription
+    where
+        E: events::Event,
+        F: 'static + Send + FnMut(E::Body, AsyncAppContext),
+    {
+        let prev_handler = self.event_handlers.lock().insert(
+            E::EVENT,
+            Arc::new(Mutex::new(
+                move |body: Option<Value>, cx: AsyncAppContext| {
+                    if let Some(body) = deserialize_body::<E::Body>(body)
+                        .with_context(|| format!("deserializing {} event", E::EVENT))
+                        .log_err()
+                    {
+                        f(body, cx);
+                    }
+                },
+            )),
+        );
+        assert!(
+            prev_handler.is_none(),
+            "registered multiple handlers for the same DAP event"
+        );
+        Subscription::Event {
+            event: E::EVENT,
+            event_handlers: Some(self.event_handlers.clone()),
+        }
+    }
+
+    /// Register a handler for reverse requests of the given type sent by the adapter.
+    ///
+    /// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Reverse_Requests)
+    #[must_use]
+    pub fn on_request<R, F, Fut>(&self, mut f: F) -> Subscription
+    where
+        R: requests::Request,
+        F: 'static + Send + FnMut(R::Arguments, AsyncAppContext) -> Fut,
+        Fut: 'static + Future<Output = Result<R::Response>>,
+    {
+        let outbound_tx = self.outbound_tx.clone();
+        let next_seq = self.next_seq.clone();
+        let prev_handler = self.request_handlers.lock().insert(
+            R::COMMAND,
+            Arc::new(Mutex::new(
+                move |seq: u64, arguments: Option<Value>, cx: AsyncAppContext| {
+                    let response = deserialize_body::<R::Arguments>(arguments)
+                        .with_context(|| format!("deserializing {} request", R::COMMAND))
+                        .map(|arguments| f(arguments, cx.clone()));
+                    let outbound_tx = outbound_tx.clone();
+                    let next_seq = next_seq.clone();
+                    cx.foreground_executor()
+                        .spawn(async move {
+                            let result = match response {
+                                Ok(response) => response.await.and_then(|body| {
+                                    serde_json::to_value(body).context("serializing response")
+                                }),
+                                Err(error) => Err(error),
+                            };
+                            let (success, message, body) = match result {
+                                Ok(body) => (true, None, Some(body).filter(|body| !body.is_null())),
+                                Err(error) => (false, Some(error.to_string()), None),
+                            };
+                            outbound_tx
+                                .try_send(Message::Response(Response {
+                                    seq: next_seq.fetch_add(1, SeqCst),
+                                    request_seq: seq,
+                                    success,
+                                    command: R::COMMAND.to_string(),
+                                    message,
+                                    body,
+                                }))
+                                .ok();
+                        })
+                        .detach();
+                },
+            )),
+        );
+        assert!(
+            prev_handler.is_none(),
+            "registered multiple handlers for the same DAP request"
+        );
+        Subscription::Request {
+            command: R::COMMAND,
+            request_handlers: Some(self.request_handlers.clone()),
+        }
+    }
+
+    /// Removes a request handler registered via [`Self::on_request`].
+    pub fn remove_request_handler<R: requests::Request>(&self) {
+        self.request_handlers.lock().remove(R::COMMAND);
+    }
+
+    /// Removes an event handler registered via [`Self::on_event`].
+    pub fn remove_event_handler<E: events::Event>(&self) {
+        self.event_handlers.lock().remove(E::EVENT);
+    }
+
+    pub fn id(&self) -> DebugAdapterClientId {
+        self.id
+    }
+
+    pub fn name(&self) -> DebugAdapterName {
+        self.name.clone()
+    }
+
+    /// Get the capabilities reported by the adapter in response to `initialize`.
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities.read().clone()
+    }
+
+    /// Sends a request to the debug adapter.
+    ///
+    /// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Base_Protocol_Request)
+    pub fn request<R: requests::Request>(
+        &self,
+        arguments: R::Arguments,
+    ) -> impl 'static + Send + Future<Output = Result<R::Response>>
+    where
+        R::Response: 'static + Send,
+    {
+        let seq = self.next_seq.fetch_add(1, SeqCst);
+        let (tx, rx) = oneshot::channel();
+        let handle_response = self
+            .response_handlers
+            .lock()
+            .as_mut()
+            .ok_or_else(|| anyhow!("debug adapter shut down"))
+            .map(|handlers| {
+                handlers.insert(
+                    seq,
+                    Box::new(move |response| {
+                        _ = tx.send(response);
+                    }),
+                );
+            });
+
+        let send = serde_json::to_value(arguments)
+            .context("failed to serialize DAP request arguments")
+            .and_then(|arguments| {
+                self.outbound_tx
+                    .try_send(Message::Request(Request {
+                        seq,
+                        command: R::COMMAND.to_string(),
+                        arguments: Some(arguments).filter(|arguments| !arguments.is_null()),
+                    }))
+                    .context("failed to write to debug adapter's stdin")
+            });
+
+        let mut timeout = self.executor.timer(DAP_REQUEST_TIMEOUT).fuse();
+        async move {
+            handle_response?;
+            send?;
+
+            let command = R::COMMAND;
+            select! {
+                response = rx.fuse() => {
+                    let response = response.map_err(|_| anyhow!("debug adapter shut down"))?;
+                    if response.success {
+                        deserialize_body(response.body)
+                            .with_context(|| format!("failed to deserialize {command:?} response"))
+                    } else {
+                        Err(anyhow!(
+                            "{}",
+                            response
+                                .message
+                                .unwrap_or_else(|| format!("{command:?} request failed"))
+                        ))
+                    }
+                }
+
+                _ = timeout => {
+                    log::error!("cancelled DAP request {command:?} seq {seq} which took over {DAP_REQUEST_TIMEOUT:?}");
+                    anyhow::bail!("DAP request timeout");
+                }
+            }
+        }
+    }
+
+    #[cfg(any(test, feature = "test-support"))]
+    fn send_event<E: events::Event>(&self, body: E::Body) -> Result<()> {
+        let body = serde_json::to_value(body)?;
+        self.outbound_tx.try_send(Message::Event(Event {
+            seq: self.next_seq.fetch_add(1, SeqCst),
+            event: E::EVENT.to_string(),
+            body: Some(body).filter(|body| !body.is_null()),
+        }))?;
+        Ok(())
+    }
+}
+
+/// Adapters disagree on how to encode an empty payload: some omit the body,
+/// some send `null` and some send an empty object, so accept all of them.
+fn deserialize_body<T: DeserializeOwned>(body: Option<Value>) -> Result<T> {
+    let body = body.unwrap_or(Value::Null);
+    match serde_json::from_value(body.clone()) {
+        Ok(value) => Ok(value),
+        Err(error) => {
+            let fallback = if body.is_null() {
+                json!({})
+            } else if body == json!({}) {
+                Value::Null
+            } else {
+                return Err(error.into());
+            };
+            serde_json::from_value(fallback).map_err(|_| error.into())
+        }
+    }
+}
+
+impl Drop for DebugAdapterClient {
+    fn drop(&mut self) {
+        if let Some(shutdown) = self.shutdown() {
+            self.executor.spawn(shutdown).detach();
+        }
+    }
+}
+
+impl fmt::Debug for DebugAdapterClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DebugAdapterClient")
+            .field("id", &self.id.0)
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Subscription {
+    /// Detaching a subscription handle prevents it from unsubscribing on drop.
+    pub fn detach(&mut self) {
+        match self {
+            Subscription::Event { event_handlers, .. } => *event_handlers = None,
+            Subscription::Request {
+                request_handlers, ..
+            } => *request_handlers = None,
+        }
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        match self {
+            Subscription::Event {
+                event,
+                event_handlers,
+            } => {
+                if let Some(handlers) = event_handlers {
+                    handlers.lock().remove(event);
+                }
+            }
+            Subscription::Request {
+                command,
+                request_handlers,
+            } => {
+                if let Some(handlers) = request_handlers {
+                    handlers.lock().remove(command);
+                }
+            }
+        }
+    }
+}
+
+/// Mock debug adapter for use in tests.
+#[cfg(any(test, feature = "test-support"))]
+#[derive(Clone)]
+pub struct FakeDebugAdapter {
+    pub adapter: Arc<DebugAdapterClient>,
+}
+
+#[cfg(any(test, feature = "test-support"))]
+impl FakeDebugAdapter {
+    /// Construct a fake debug adapter, returning the client that talks to it.
+    pub fn new(
+        id: DebugAdapterClientId,
+        name: DebugAdapterName,
+        capabilities: Capabilities,
+        cx: AsyncAppContext,
+    ) -> (DebugAdapterClient, FakeDebugAdapter) {
+        let (stdin_writer, stdin_reader) = async_pipe::pipe();
+        let (stdout_writer, stdout_reader) = async_pipe::pipe();
+
+        let client = DebugAdapterClient::new_internal(
+            id,
+            name.clone(),
+            stdin_writer,
+            stdout_reader,
+            None::<async_pipe::PipeReader>,
+            None,
+            cx.clone(),
+        );
+        let fake = FakeDebugAdapter {
+            adapter: Arc::new(DebugAdapterClient::new_internal(
+                id,
+                name,
+                stdout_writer,
+                stdin_reader,
+                None::<async_pipe::PipeReader>,
+                None,
+                cx,
+            )),
+        };
+
+        fake.handle_request::<requests::Initialize, _, _>(move |_, _| {
+            let capabilities = capabilities.clone();
+            async move { Ok(capabilities) }
+        });
+        fake.handle_request::<requests::Launch, _, _>(|_, _| async move { Ok(()) });
+        fake.handle_request::<requests::Attach, _, _>(|_, _| async move { Ok(()) });
+        fake.handle_request::<requests::ConfigurationDone, _, _>(|_, _| async move { Ok(()) });
+        fake.handle_request::<requests::SetExceptionBreakpoints, _, _>(|_, _| async move {
+            Ok(dap_types::SetExceptionBreakpointsResponse { breakpoints: None })
+        });
+        fake.handle_request::<requests::Disconnect, _, _>(|_, _| async move { Ok(()) });
+
+        (client, fake)
+    }
+
+    /// Sends an event from the fake adapter to the client.
+    pub fn send_event<E: events::Event>(&self, body: E::Body) {
+        self.adapter.send_event::<E>(body).ok();
+    }
+
+    /// See [`DebugAdapterClient::request`].
+    pub async fn request<R>(&self, arguments: R::Arguments) -> Result<R::Response>
+    where
+        R: requests::Request,
+        R::Response: 'static + Send,
+    {
+        self.adapter.executor.start_waiting();
+        self.adapter.request::<R>(arguments).await
+    }
+
+    /// Registers a handler for a specific kind of request. Removes any existing handler for specified request type.
+    pub fn handle_request<R, F, Fut>(
+        &self,
+        mut handler: F,
+    ) -> futures::channel::mpsc::UnboundedReceiver<()>
+    where
+        R: 'static + requests::Request,
+        F: 'static + Send + FnMut(R::Arguments, AsyncAppContext) -> Fut,
+        Fut: 'static + Send + Future<Output = Result<R::Response>>,
+    {
+        let (responded_tx, responded_rx) = futures::channel::mpsc::unbounded();
+        self.adapter.remove_request_handler::<R>();
+        self.adapter
+            .on_request::<R, _, _>(move |arguments, cx| {
+                let result = handler(arguments, cx.clone());
+                let responded_tx = responded_tx.clone();
+                let executor = cx.background_executor().clone();
+                async move {
+                    executor.simulate_random_delay().await;
+                    let result = result.await;
+                    responded_tx.unbounded_send(()).ok();
+                    result
+                }
+            })
+            .detach();
+        responded_rx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dap_types::{StoppedEvent, StoppedEventReason, Thread, ThreadsResponse};
+    use gpui::TestAppContext;
+
+    #[ctor::ctor]
+    fn init_logger() {
+        if std::env::var("RUST_LOG").is_ok() {
+            env_logger::init();
+        }
+    }
+
+    #[gpui::test]
+    async fn test_fake_adapter(cx: &mut TestAppContext) {
+        let (client, fake) = FakeDebugAdapter::new(
+            DebugAdapterClientId(0),
+            "fake-adapter".into(),
+            Capabilities {
+                supports_configuration_done_request: Some(true),
+                ..Default::default()
+            },
+            cx.to_async(),
+        );
+
+        let (stopped_tx, stopped_rx) = channel::unbounded();
+        client
+            .on_event::<events::Stopped, _>(move |event, _| {
+                stopped_tx.try_send(event).unwrap();
+            })
+            .detach();
+
+        client.initialize().await.unwrap();
+        assert_eq!(
+            client.capabilities().supports_configuration_done_request,
+            Some(true)
+        );
+
+        fake.handle_request::<requests::Threads, _, _>(|_, _| async move {
+            Ok(ThreadsResponse {
+                threads: vec![Thread {
+                    id: 1,
+                    name: "main".into(),
+                }],
+            })
+        });
+        let threads = client.request::<requests::Threads>(()).await.unwrap();
+        assert_eq!(threads.threads.len(), 1);
+        assert_eq!(threads.threads[0].name, "main");
+
+        // Requests without a handler in the adapter fail instead of hanging.
+        fake.adapter.remove_request_handler::<requests::Launch>();
+        assert!(client
+            .request::<requests::Launch>(dap_types::LaunchRequestArguments {
+                raw: json!({ "program": "a.out" }),
+            })
+            .await
+            .is_err());
+
+        fake.send_event::<events::Stopped>(StoppedEvent {
+            reason: StoppedEventReason::Breakpoint,
+            description: None,
+            thread_id: Some(1),
+            preserve_focus_hint: None,
+            text: None,
+            all_threads_stopped: Some(true),
+            hit_breakpoint_ids: None,
+        });
+        let event = stopped_rx.recv().await.unwrap();
+        assert_eq!(event.reason, StoppedEventReason::Breakpoint);
+        assert_eq!(event.thread_id, Some(1));
+    }
+
+    #[test]
+    fn test_deserialize_empty_body() {
+        deserialize_body::<()>(None).unwrap();
+        deserialize_body::<()>(Some(json!({}))).unwrap();
+        let capabilities = deserialize_body::<Capabilities>(None).unwrap();
+        assert_eq!(capabilities.supports_configuration_done_request, None);
+        assert!(deserialize_body::<Thread>(Some(json!({ "id": "x" }))).is_err());
+    }
+}
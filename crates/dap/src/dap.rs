@@ -0,0 +1,94 @@
+pub mod client;
+pub mod transport;
+
+pub use dap_types::*;
+
+use collections::HashMap;
+use gpui::SharedString;
+use schemars::{
+    gen::SchemaGenerator,
+    schema::{InstanceType, Schema, SchemaObject},
+    JsonSchema,
+};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Describes how to launch a debug adapter process.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct DebugAdapterBinary {
+    /// The command used to start the debug adapter.
+    pub command: String,
+    /// Arguments passed to the debug adapter command.
+    #[serde(default)]
+    pub arguments: Vec<String>,
+    /// Environment variables set for the debug adapter process.
+    #[serde(default)]
+    pub env: Option<HashMap<String, String>>,
+}
+
+/// A name of a debug adapter, as used in the `dap.adapters` setting.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub struct DebugAdapterName(pub SharedString);
+
+impl std::fmt::Display for DebugAdapterName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl AsRef<str> for DebugAdapterName {
+    fn as_ref(&self) -> &str {
+        self.0.as_ref()
+    }
+}
+
+impl<'a> From<&'a str> for DebugAdapterName {
+    fn from(str: &'a str) -> DebugAdapterName {
+        DebugAdapterName(str.to_string().into())
+    }
+}
+
+impl JsonSchema for DebugAdapterName {
+    fn schema_name() -> String {
+        "DebugAdapterName".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            ..Default::default()
+        }
+        .into()
+    }
+}
+
+/// Whether a debug session starts the debuggee or attaches to a running process.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DebugRequestType {
+    #[default]
+    Launch,
+    Attach,
+}
+
+/// A user-defined configuration for starting a debug session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DebugAdapterConfig {
+    /// The name of the configuration, shown in the debugger panel.
+    pub label: String,
+    /// The debug adapter to use, as declared in `dap.adapters`.
+    pub adapter: DebugAdapterName,
+    /// Whether to launch a new process or attach to an existing one.
+    ///
+    /// Default: launch
+    #[serde(default)]
+    pub request: DebugRequestType,
+    /// Adapter-specific arguments sent with the `launch` or `attach` request,
+    /// e.g. `{ "program": "target/debug/app" }`.
+    #[serde(default)]
+    pub arguments: serde_json::Value,
+    /// The working directory of the debug adapter process.
+    /// Defaults to the root of the first worktree.
+    #[serde(default)]
+    pub cwd: Option<PathBuf>,
+}
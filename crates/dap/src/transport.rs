@@ -0,0 +1,167 @@
+use anyhow::{anyhow, Result};
+use futures::{AsyncBufReadExt, AsyncRead, AsyncReadExt as _, AsyncWrite, AsyncWriteExt as _};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use smol::io::BufReader;
+use std::io::Write as _;
+
+const CONTENT_LEN_HEADER: &str = "Content-Length: ";
+const HEADER_DELIMITER: &[u8; 4] = b"\r\n\r\n";
+
+/// A single message exchanged with a debug adapter.
+///
+/// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Base_Protocol_ProtocolMessage)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum Message {
+    Request(Request),
+    Response(Response),
+    Event(Event),
+}
+
+/// A request sent either by the client or, for reverse requests, by the adapter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    pub seq: u64,
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub arguments: Option<Value>,
+}
+
+/// A response to a previously sent [`Request`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Response {
+    pub seq: u64,
+    pub request_seq: u64,
+    pub success: bool,
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+/// An event sent by the debug adapter.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub seq: u64,
+    pub event: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub body: Option<Value>,
+}
+
+async fn read_headers<Input>(reader: &mut BufReader<Input>, buffer: &mut Vec<u8>) -> Result<()>
+where
+    Input: AsyncRead + Unpin + Send + 'static,
+{
+    loop {
+        if buffer.len() >= HEADER_DELIMITER.len()
+            && buffer[(buffer.len() - HEADER_DELIMITER.len())..] == HEADER_DELIMITER[..]
+        {
+            return Ok(());
+        }
+
+        if reader.read_until(b'\n', buffer).await? == 0 {
+            return Err(anyhow!("cannot read DAP message headers"));
+        }
+    }
+}
+
+/// Reads the next `Content-Length` framed message from the adapter's output.
+///
+/// Returns the raw message text, so that it can be logged before being parsed.
+pub(crate) async fn read_message<Input>(
+    reader: &mut BufReader<Input>,
+    buffer: &mut Vec<u8>,
+) -> Result<String>
+where
+    Input: AsyncRead + Unpin + Send + 'static,
+{
+    buffer.clear();
+    read_headers(reader, buffer).await?;
+
+    let headers = std::str::from_utf8(buffer)?;
+    let message_len = headers
+        .split('\n')
+        .find(|line| line.starts_with(CONTENT_LEN_HEADER))
+        .and_then(|line| line.strip_prefix(CONTENT_LEN_HEADER))
+        .ok_or_else(|| anyhow!("invalid DAP message header {headers:?}"))?
+        .trim_end()
+        .parse()?;
+
+    buffer.resize(message_len, 0);
+    reader.read_exact(buffer).await?;
+    Ok(String::from_utf8(buffer.clone())?)
+}
+
+/// Writes a single message to the adapter's input using `Content-Length` framing.
+pub(crate) async fn write_message<Output>(
+    writer: &mut Output,
+    message: &str,
+    content_len_buffer: &mut Vec<u8>,
+) -> Result<()>
+where
+    Output: AsyncWrite + Unpin + Send + 'static,
+{
+    content_len_buffer.clear();
+    write!(content_len_buffer, "{}", message.len()).unwrap();
+    writer.write_all(CONTENT_LEN_HEADER.as_bytes()).await?;
+    writer.write_all(content_len_buffer).await?;
+    writer.write_all(HEADER_DELIMITER).await?;
+    writer.write_all(message.as_bytes()).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    async fn test_read_message() {
+        let mut buffer = Vec::new();
+        let mut reader = smol::io::BufReader::new(
+            b"Content-Length: 42\r\n\r\n{\"seq\":1,\"type\":\"event\",\"event\":\"stopped\"}trailing" as &[u8],
+        );
+        let message = read_message(&mut reader, &mut buffer).await.unwrap();
+        assert_eq!(
+            message,
+            "{\"seq\":1,\"type\":\"event\",\"event\":\"stopped\"}"
+        );
+
+        let mut reader = smol::io::BufReader::new(
+            b"Content-Type: application/vscode-jsonrpc\r\nContent-Length: 2\r\n\r\n{}" as &[u8],
+        );
+        let message = read_message(&mut reader, &mut buffer).await.unwrap();
+        assert_eq!(message, "{}");
+    }
+
+    #[test]
+    fn test_message_serialization() {
+        let request = Message::Request(Request {
+            seq: 1,
+            command: "threads".into(),
+            arguments: None,
+        });
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"type":"request","seq":1,"command":"threads"}"#
+        );
+
+        let response: Message = serde_json::from_str(
+            r#"{"seq":2,"type":"response","request_seq":1,"success":true,"command":"threads","body":{"threads":[]}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            response,
+            Message::Response(Response {
+                seq: 2,
+                request_seq: 1,
+                success: true,
+                command: "threads".into(),
+                message: None,
+                body: Some(serde_json::json!({ "threads": [] })),
+            })
+        );
+    }
+}
@@ -0,0 +1,866 @@
+use anyhow::Result;
+use collections::{HashMap, HashSet};
+use dap::{
+    client::DebugAdapterClientId, requests, DebugAdapterConfig, Scope, ScopesArguments, StackFrame,
+    StackTraceArguments, Variable, VariablesArguments,
+};
+use db::kvp::KEY_VALUE_STORE;
+use editor::{scroll::Autoscroll, Editor};
+use gpui::{
+    actions, Action, AppContext, AsyncWindowContext, Corner, EventEmitter, FocusHandle,
+    FocusableView, Model, Pixels, Subscription, Task, View, WeakView,
+};
+use project::{
+    dap_store::{DapStore, DapStoreEvent, DebugSessionStatus},
+    project_settings::ProjectSettings,
+    Fs,
+};
+use serde::{Deserialize, Serialize};
+use settings::Settings as _;
+use std::sync::Arc;
+use ui::{
+    prelude::*, ContextMenu, Divider, ListHeader, ListItem, ListItemSpacing, PopoverMenu, Tooltip,
+};
+use util::ResultExt as _;
+use workspace::{
+    dock::{DockPosition, Panel, PanelEvent},
+    Workspace,
+};
+
+use crate::debugger_panel_settings::DebuggerPanelSettings;
+
+actions!(
+    debugger,
+    [
+        ToggleFocus,
+        Continue,
+        Pause,
+        StepOver,
+        StepIn,
+        StepOut,
+        Stop
+    ]
+);
+
+const DEBUGGER_PANEL_KEY: &str = "DebuggerPanel";
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+            workspace.register_action(|workspace, _: &ToggleFocus, cx| {
+                workspace.toggle_panel_focus::<DebugPanel>(cx);
+            });
+            workspace.register_action(|workspace, action: &Continue, cx| {
+                DebugPanel::dispatch(workspace, cx, |panel, cx| panel.continue_thread(action, cx));
+            });
+            workspace.register_action(|workspace, action: &Pause, cx| {
+                DebugPanel::dispatch(workspace, cx, |panel, cx| panel.pause(action, cx));
+            });
+            workspace.register_action(|workspace, action: &StepOver, cx| {
+                DebugPanel::dispatch(workspace, cx, |panel, cx| panel.step_over(action, cx));
+            });
+            workspace.register_action(|workspace, action: &StepIn, cx| {
+                DebugPanel::dispatch(workspace, cx, |panel, cx| panel.step_in(action, cx));
+            });
+            workspace.register_action(|workspace, action: &StepOut, cx| {
+                DebugPanel::dispatch(workspace, cx, |panel, cx| panel.step_out(action, cx));
+            });
+            workspace.register_action(|workspace, action: &Stop, cx| {
+                DebugPanel::dispatch(workspace, cx, |panel, cx| panel.stop(action, cx));
+            });
+        },
+    )
+    .detach();
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedDebugPanel {
+    height: Option<Pixels>,
+}
+
+/// Shows the state of the active debug session: its threads, the call stack of the
+/// selected thread and the variables in scope of the selected stack frame.
+pub struct DebugPanel {
+    workspace: WeakView<Workspace>,
+    dap_store: Model<DapStore>,
+    fs: Arc<dyn Fs>,
+    focus_handle: FocusHandle,
+    height: Option<Pixels>,
+    pending_serialization: Task<Option<()>>,
+    active_session: Option<DebugAdapterClientId>,
+    selected_thread: Option<u64>,
+    stack_frames: Vec<StackFrame>,
+    selected_frame: Option<usize>,
+    scopes: Vec<Scope>,
+    variables: HashMap<u64, Vec<Variable>>,
+    expanded_variables: HashSet<u64>,
+    fetch_stack_frames_task: Task<()>,
+    fetch_scopes_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DebugPanel {
+    pub fn load(
+        workspace: WeakView<Workspace>,
+        cx: AsyncWindowContext,
+    ) -> Task<Result<View<Self>>> {
+        cx.spawn(|mut cx| async move {
+            let serialized_panel = cx
+                .background_executor()
+                .spawn(async move { KEY_VALUE_STORE.read_kvp(DEBUGGER_PANEL_KEY) })
+                .await
+                .log_err()
+                .flatten()
+                .and_then(|panel| serde_json::from_str::<SerializedDebugPanel>(&panel).log_err());
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let panel = Self::new(workspace, cx);
+                if let Some(serialized_panel) = serialized_panel {
+                    panel.update(cx, |panel, cx| {
+                        panel.height = serialized_panel.height.map(|height| height.round());
+                        cx.notify();
+                    });
+                }
+                panel
+            })
+        })
+    }
+
+    pub fn new(workspace: &mut Workspace, cx: &mut ViewContext<Workspace>) -> View<Self> {
+        let fs = workspace.app_state().fs.clone();
+        let project = workspace.project().clone();
+        let dap_store = project.read(cx).dap_store().clone();
+        let weak_workspace = workspace.weak_handle();
+
+        cx.new_view(|cx: &mut ViewContext<Self>| {
+            let subscriptions = vec![
+                cx.subscribe(&dap_store, Self::on_dap_store_event),
+                cx.observe_global::<settings::SettingsStore>(|_, cx| cx.notify()),
+            ];
+            let active_session = dap_store.read(cx).sessions().next().map(|(id, _)| id);
+
+            Self {
+                workspace: weak_workspace,
+                dap_store,
+                fs,
+                focus_handle: cx.focus_handle(),
+                height: None,
+                pending_serialization: Task::ready(None),
+                active_session,
+                selected_thread: None,
+                stack_frames: Vec::new(),
+                selected_frame: None,
+                scopes: Vec::new(),
+                variables: HashMap::default(),
+                expanded_variables: HashSet::default(),
+                fetch_stack_frames_task: Task::ready(()),
+                fetch_scopes_task: Task::ready(()),
+                _subscriptions: subscriptions,
+            }
+        })
+    }
+
+    fn dispatch(
+        workspace: &mut Workspace,
+        cx: &mut ViewContext<Workspace>,
+        f: impl FnOnce(&mut Self, &mut ViewContext<Self>),
+    ) {
+        if let Some(panel) = workspace.panel::<Self>(cx) {
+            panel.update(cx, f);
+        }
+    }
+
+    fn serialize(&mut self, cx: &mut ViewContext<Self>) {
+        let height = self.height;
+        self.pending_serialization = cx.background_executor().spawn(
+            async move {
+                KEY_VALUE_STORE
+                    .write_kvp(
+                        DEBUGGER_PANEL_KEY.into(),
+                        serde_json::to_string(&SerializedDebugPanel { height })?,
+                    )
+                    .await?;
+                anyhow::Ok(())
+            }
+            .log_err(),
+        );
+    }
+
+    fn on_dap_store_event(
+        &mut self,
+        _: Model<DapStore>,
+        event: &DapStoreEvent,
+        cx: &mut ViewContext<Self>,
+    ) {
+        match event {
+            DapStoreEvent::SessionStarted(client_id) => {
+                if self.active_session.is_none() {
+                    self.set_active_session(Some(*client_id), cx);
+                }
+            }
+            DapStoreEvent::SessionStopped(client_id) => {
+                if self.active_session == Some(*client_id) {
+                    let next_session = self.dap_store.read(cx).sessions().next().map(|(id, _)| id);
+                    self.set_active_session(next_session, cx);
+                }
+            }
+            DapStoreEvent::Stopped {
+                client_id,
+                thread_id,
+                ..
+            } => {
+                if self.active_session.is_none() || self.active_session == Some(*client_id) {
+                    self.active_session = Some(*client_id);
+                    self.selected_thread = *thread_id;
+                    self.fetch_stack_frames(cx);
+                }
+            }
+            DapStoreEvent::Continued(client_id) => {
+                if self.active_session == Some(*client_id) {
+                    self.clear_frames();
+                }
+            }
+            DapStoreEvent::ThreadsChanged(client_id) => {
+                if self.active_session == Some(*client_id) && self.selected_thread.is_none() {
+                    self.selected_thread = self
+                        .dap_store
+                        .read(cx)
+                        .session(*client_id)
+                        .and_then(|session| session.threads().first())
+                        .map(|thread| thread.id);
+                }
+            }
+            DapStoreEvent::Output(_)
+            | DapStoreEvent::BreakpointsChanged(_)
+            | DapStoreEvent::ActiveLocationChanged => {}
+        }
+        cx.notify();
+    }
+
+    fn set_active_session(
+        &mut self,
+        client_id: Option<DebugAdapterClientId>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.active_session = client_id;
+        self.selected_thread = None;
+        self.clear_frames();
+        if let Some(DebugSessionStatus::Stopped { thread_id }) = client_id
+            .and_then(|client_id| self.dap_store.read(cx).session(client_id))
+            .map(|session| session.status())
+        {
+            self.selected_thread = thread_id;
+            self.fetch_stack_frames(cx);
+        }
+        cx.notify();
+    }
+
+    fn clear_frames(&mut self) {
+        self.stack_frames.clear();
+        self.selected_frame = None;
+        self.scopes.clear();
+        self.variables.clear();
+        self.expanded_variables.clear();
+        self.fetch_stack_frames_task = Task::ready(());
+        self.fetch_scopes_task = Task::ready(());
+    }
+
+    fn active_thread(&self, cx: &AppContext) -> Option<(DebugAdapterClientId, u64)> {
+        let client_id = self.active_session?;
+        let thread_id = self.selected_thread.or_else(|| {
+            self.dap_store
+                .read(cx)
+                .session(client_id)?
+                .threads()
+                .first()
+                .map(|thread| thread.id)
+        })?;
+        Some((client_id, thread_id))
+    }
+
+    fn fetch_stack_frames(&mut self, cx: &mut ViewContext<Self>) {
+        self.clear_frames();
+        let Some((client_id, thread_id)) = self.active_thread(cx) else {
+            return;
+        };
+        let stack_trace = self.dap_store.read(cx).request::<requests::StackTrace>(
+            client_id,
+            StackTraceArguments {
+                thread_id,
+                start_frame: None,
+                levels: None,
+                format: None,
+            },
+            cx,
+        );
+        self.fetch_stack_frames_task = cx.spawn(|this, mut cx| async move {
+            let Some(stack_trace) = stack_trace.await.log_err() else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                this.stack_frames = stack_trace.stack_frames;
+                if !this.stack_frames.is_empty() {
+                    this.select_stack_frame(0, false, cx);
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn select_stack_frame(&mut self, ix: usize, open_source: bool, cx: &mut ViewContext<Self>) {
+        let (Some(client_id), Some(stack_frame)) =
+            (self.active_session, self.stack_frames.get(ix).cloned())
+        else {
+            return;
+        };
+        self.selected_frame = Some(ix);
+        self.scopes.clear();
+        self.variables.clear();
+        self.expanded_variables.clear();
+        self.dap_store.update(cx, |dap_store, cx| {
+            dap_store.set_active_stack_frame(client_id, &stack_frame, cx);
+        });
+        if open_source {
+            self.open_stack_frame(&stack_frame, cx);
+        }
+
+        let scopes = self.dap_store.read(cx).request::<requests::Scopes>(
+            client_id,
+            ScopesArguments {
+                frame_id: stack_frame.id,
+            },
+            cx,
+        );
+        self.fetch_scopes_task = cx.spawn(|this, mut cx| async move {
+            let Some(scopes) = scopes.await.log_err() else {
+                return;
+            };
+            this.update(&mut cx, |this, cx| {
+                this.scopes = scopes.scopes;
+                // Expand the cheap scopes right away, as they usually hold the locals.
+                let references = this
+                    .scopes
+                    .iter()
+                    .filter(|scope| !scope.expensive)
+                    .map(|scope| scope.variables_reference)
+                    .collect::<Vec<_>>();
+                for reference in references {
+                    this.toggle_variables(reference, cx);
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+        cx.notify();
+    }
+
+    fn open_stack_frame(&self, stack_frame: &StackFrame, cx: &mut ViewContext<Self>) {
+        let Some(project_path) = stack_frame
+            .source
+            .as_ref()
+            .and_then(|source| self.dap_store.read(cx).project_path_for_source(source, cx))
+        else {
+            return;
+        };
+        let row = stack_frame.line.saturating_sub(1) as u32;
+        let column = stack_frame.column.saturating_sub(1) as u32;
+        let Some(open_path) = self
+            .workspace
+            .update(cx, |workspace, cx| {
+                workspace.open_path(project_path, None, true, cx)
+            })
+            .log_err()
+        else {
+            return;
+        };
+        cx.spawn(|_, mut cx| async move {
+            let item = open_path.await?;
+            if let Some(editor) = item.downcast::<Editor>() {
+                editor.update(&mut cx, |editor, cx| {
+                    let point = language::Point::new(row, column);
+                    editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                        selections.select_ranges([point..point])
+                    });
+                })?;
+            }
+            anyhow::Ok(())
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn toggle_variables(&mut self, variables_reference: u64, cx: &mut ViewContext<Self>) {
+        if !self.expanded_variables.insert(variables_reference) {
+            self.expanded_variables.remove(&variables_reference);
+            cx.notify();
+            return;
+        }
+        if self.variables.contains_key(&variables_reference) {
+            cx.notify();
+            return;
+        }
+        let Some(client_id) = self.active_session else {
+            return;
+        };
+        let variables = self.dap_store.read(cx).request::<requests::Variables>(
+            client_id,
+            VariablesArguments {
+                variables_reference,
+                filter: None,
+                start: None,
+                count: None,
+                format: None,
+            },
+            cx,
+        );
+        cx.spawn(|this, mut cx| async move {
+            let variables = variables.await?;
+            this.update(&mut cx, |this, cx| {
+                this.variables
+                    .insert(variables_reference, variables.variables);
+                cx.notify();
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn start_session(&mut self, config: DebugAdapterConfig, cx: &mut ViewContext<Self>) {
+        let start = self
+            .dap_store
+            .update(cx, |dap_store, cx| dap_store.start_session(config, cx));
+        let workspace = self.workspace.clone();
+        cx.spawn(|_, mut cx| async move {
+            if let Err(error) = start.await {
+                workspace
+                    .update(&mut cx, |workspace, cx| {
+                        workspace.show_error(&error, cx);
+                    })
+                    .ok();
+            }
+        })
+        .detach();
+    }
+
+    fn continue_thread(&mut self, _: &Continue, cx: &mut ViewContext<Self>) {
+        if let Some((client_id, thread_id)) = self.active_thread(cx) {
+            self.dap_store
+                .update(cx, |dap_store, cx| {
+                    dap_store.continue_thread(client_id, thread_id, cx)
+                })
+                .detach_and_log_err(cx);
+        }
+    }
+
+    fn pause(&mut self, _: &Pause, cx: &mut ViewContext<Self>) {
+        if let Some((client_id, thread_id)) = self.active_thread(cx) {
+            self.dap_store
+                .read(cx)
+                .pause(client_id, thread_id, cx)
+                .detach_and_log_err(cx);
+        }
+    }
+
+    fn step_over(&mut self, _: &StepOver, cx: &mut ViewContext<Self>) {
+        if let Some((client_id, thread_id)) = self.active_thread(cx) {
+            self.dap_store
+                .update(cx, |dap_store, cx| {
+                    dap_store.step_over(client_id, thread_id, cx)
+                })
+                .detach_and_log_err(cx);
+        }
+    }
+
+    fn step_in(&mut self, _: &StepIn, cx: &mut ViewContext<Self>) {
+        if let Some((client_id, thread_id)) = self.active_thread(cx) {
+            self.dap_store
+                .update(cx, |dap_store, cx| {
+                    dap_store.step_in(client_id, thread_id, cx)
+                })
+                .detach_and_log_err(cx);
+        }
+    }
+
+    fn step_out(&mut self, _: &StepOut, cx: &mut ViewContext<Self>) {
+        if let Some((client_id, thread_id)) = self.active_thread(cx) {
+            self.dap_store
+                .update(cx, |dap_store, cx| {
+                    dap_store.step_out(client_id, thread_id, cx)
+                })
+                .detach_and_log_err(cx);
+        }
+    }
+
+    fn stop(&mut self, _: &Stop, cx: &mut ViewContext<Self>) {
+        if let Some(client_id) = self.active_session {
+            self.dap_store
+                .update(cx, |dap_store, cx| dap_store.stop_session(client_id, cx))
+                .detach();
+        }
+    }
+
+    fn render_toolbar(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let dap_store = self.dap_store.read(cx);
+        let session = self
+            .active_session
+            .and_then(|client_id| dap_store.session(client_id));
+        let status = session.map(|session| session.status());
+        let is_stopped = matches!(status, Some(DebugSessionStatus::Stopped { .. }));
+        let is_running = matches!(status, Some(DebugSessionStatus::Running));
+        let configurations = ProjectSettings::get_global(cx).dap.configurations.clone();
+        let is_local = dap_store.is_local();
+
+        let tool_button = |id: &'static str,
+                           icon: IconName,
+                           tooltip: &'static str,
+                           action: Box<dyn Action>,
+                           enabled: bool| {
+            IconButton::new(id, icon)
+                .icon_size(IconSize::Small)
+                .disabled(!enabled)
+                .tooltip({
+                    let action = action.boxed_clone();
+                    move |cx| Tooltip::for_action(tooltip, action.as_ref(), cx)
+                })
+                .on_click(move |_, cx| cx.dispatch_action(action.boxed_clone()))
+        };
+
+        h_flex()
+            .px_2()
+            .py_1()
+            .gap_1()
+            .border_b_1()
+            .border_color(cx.theme().colors().border)
+            .child(
+                Label::new(
+                    session
+                        .map(|session| SharedString::from(session.config().label.clone()))
+                        .unwrap_or_else(|| "No debug session".into()),
+                )
+                .size(LabelSize::Small),
+            )
+            .when_some(status, |this, status| {
+                this.child(
+                    Label::new(match status {
+                        DebugSessionStatus::Starting => "Starting",
+                        DebugSessionStatus::Running => "Running",
+                        DebugSessionStatus::Stopped { .. } => "Paused",
+                        DebugSessionStatus::Exited => "Exited",
+                    })
+                    .size(LabelSize::Small)
+                    .color(Color::Muted),
+                )
+            })
+            .child(div().flex_1())
+            .when(session.is_some(), |this| {
+                this.child(if is_stopped {
+                    tool_button(
+                        "debug-continue",
+                        IconName::DebugContinue,
+                        "Continue",
+                        Box::new(Continue),
+                        true,
+                    )
+                } else {
+                    tool_button(
+                        "debug-pause",
+                        IconName::DebugPause,
+                        "Pause",
+                        Box::new(Pause),
+                        is_running,
+                    )
+                })
+                .child(tool_button(
+                    "debug-step-over",
+                    IconName::DebugStepOver,
+                    "Step Over",
+                    Box::new(StepOver),
+                    is_stopped,
+                ))
+                .child(tool_button(
+                    "debug-step-in",
+                    IconName::DebugStepInto,
+                    "Step In",
+                    Box::new(StepIn),
+                    is_stopped,
+                ))
+                .child(tool_button(
+                    "debug-step-out",
+                    IconName::DebugStepOut,
+                    "Step Out",
+                    Box::new(StepOut),
+                    is_stopped,
+                ))
+                .child(tool_button(
+                    "debug-stop",
+                    IconName::Stop,
+                    "Stop",
+                    Box::new(Stop),
+                    true,
+                ))
+            })
+            .child({
+                let panel = cx.view().downgrade();
+                PopoverMenu::new("debug-start-menu")
+                    .trigger(
+                        IconButton::new("debug-start", IconName::Play)
+                            .icon_size(IconSize::Small)
+                            .disabled(!is_local)
+                            .tooltip(|cx| Tooltip::text("Start Debugging…", cx)),
+                    )
+                    .anchor(Corner::TopRight)
+                    .menu(move |cx| {
+                        let panel = panel.clone();
+                        let configurations = configurations.clone();
+                        Some(ContextMenu::build(cx, move |mut menu, _| {
+                            if configurations.is_empty() {
+                                return menu.header("No debug configurations in settings");
+                            }
+                            for config in configurations {
+                                let panel = panel.clone();
+                                menu = menu.entry(config.label.clone(), None, move |cx| {
+                                    panel
+                                        .update(cx, |panel, cx| {
+                                            panel.start_session(config.clone(), cx)
+                                        })
+                                        .ok();
+                                });
+                            }
+                            menu
+                        }))
+                    })
+            })
+    }
+
+    fn render_threads(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let threads = self
+            .active_session
+            .and_then(|client_id| self.dap_store.read(cx).session(client_id))
+            .map(|session| session.threads().to_vec())
+            .unwrap_or_default();
+        let selected_thread = self.selected_thread;
+
+        v_flex()
+            .child(ListHeader::new("Threads"))
+            .children(threads.into_iter().map(|thread| {
+                let thread_id = thread.id;
+                ListItem::new(("debug-thread", thread_id as usize))
+                    .spacing(ListItemSpacing::Dense)
+                    .toggle_state(selected_thread == Some(thread_id))
+                    .child(Label::new(thread.name).size(LabelSize::Small))
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.selected_thread = Some(thread_id);
+                        this.fetch_stack_frames(cx);
+                    }))
+            }))
+    }
+
+    fn render_stack_frames(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().child(ListHeader::new("Call Stack")).children(
+            self.stack_frames.iter().enumerate().map(|(ix, frame)| {
+                let location = frame
+                    .source
+                    .as_ref()
+                    .and_then(|source| source.name.clone().or_else(|| source.path.clone()))
+                    .map(|name| format!("{name}:{}", frame.line));
+                ListItem::new(("debug-stack-frame", ix))
+                    .spacing(ListItemSpacing::Dense)
+                    .toggle_state(self.selected_frame == Some(ix))
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .child(Label::new(frame.name.clone()).size(LabelSize::Small))
+                            .when_some(location, |this, location| {
+                                this.child(
+                                    Label::new(location)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Muted),
+                                )
+                            }),
+                    )
+                    .on_click(cx.listener(move |this, _, cx| {
+                        this.select_stack_frame(ix, true, cx);
+                    }))
+            }),
+        )
+    }
+
+    fn render_variables(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let mut items = Vec::new();
+        for scope in &self.scopes {
+            let reference = scope.variables_reference;
+            let expanded = self.expanded_variables.contains(&reference);
+            items.push(
+                ListItem::new(("debug-scope", items.len()))
+                    .spacing(ListItemSpacing::Dense)
+                    .toggle(expanded)
+                    .on_toggle(cx.listener(move |this, _, cx| this.toggle_variables(reference, cx)))
+                    .child(Label::new(scope.name.clone()).size(LabelSize::Small))
+                    .into_any_element(),
+            );
+            if expanded {
+                self.render_variable_children(reference, 1, &mut items, cx);
+            }
+        }
+
+        v_flex().child(ListHeader::new("Variables")).children(items)
+    }
+
+    fn render_variable_children(
+        &self,
+        variables_reference: u64,
+        depth: usize,
+        items: &mut Vec<AnyElement>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(variables) = self.variables.get(&variables_reference) else {
+            return;
+        };
+        for variable in variables {
+            let reference = variable.variables_reference;
+            let has_children = reference > 0;
+            let expanded = has_children && self.expanded_variables.contains(&reference);
+            items.push(
+                ListItem::new(("debug-variable", items.len()))
+                    .spacing(ListItemSpacing::Dense)
+                    .indent_level(depth)
+                    .toggle(has_children.then_some(expanded))
+                    .on_toggle(cx.listener(move |this, _, cx| this.toggle_variables(reference, cx)))
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .child(Label::new(format!("{}:", variable.name)).size(LabelSize::Small))
+                            .child(
+                                Label::new(variable.value.clone())
+                                    .size(LabelSize::Small)
+                                    .color(Color::Muted),
+                            )
+                            .when_some(variable.type_.clone(), |this, type_| {
+                                this.child(
+                                    Label::new(type_)
+                                        .size(LabelSize::Small)
+                                        .color(Color::Placeholder),
+                                )
+                            }),
+                    )
+                    .into_any_element(),
+            );
+            // Adapters may report the same reference for recursive structures, so cap the depth.
+            if expanded && depth < 32 {
+                self.render_variable_children(reference, depth + 1, items, cx);
+            }
+        }
+    }
+
+    fn render_output(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let output = self
+            .active_session
+            .and_then(|client_id| self.dap_store.read(cx).session(client_id))
+            .map(|session| session.output().to_vec())
+            .unwrap_or_default();
+
+        v_flex()
+            .child(ListHeader::new("Output"))
+            .children(output.into_iter().map(|line| {
+                div()
+                    .px_2()
+                    .font_buffer(cx)
+                    .child(Label::new(line).size(LabelSize::Small))
+            }))
+    }
+}
+
+impl Render for DebugPanel {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context("DebugPanel")
+            .track_focus(&self.focus_handle(cx))
+            .size_full()
+            .child(self.render_toolbar(cx))
+            .child(
+                h_flex()
+                    .flex_1()
+                    .items_start()
+                    .overflow_hidden()
+                    .child(
+                        v_flex()
+                            .id("debug-threads-and-stack")
+                            .w_1_3()
+                            .h_full()
+                            .overflow_y_scroll()
+                            .child(self.render_threads(cx))
+                            .child(self.render_stack_frames(cx)),
+                    )
+                    .child(Divider::vertical())
+                    .child(
+                        v_flex()
+                            .id("debug-variables")
+                            .w_1_3()
+                            .h_full()
+                            .overflow_y_scroll()
+                            .child(self.render_variables(cx)),
+                    )
+                    .child(Divider::vertical())
+                    .child(
+                        v_flex()
+                            .id("debug-output")
+                            .flex_1()
+                            .h_full()
+                            .overflow_y_scroll()
+                            .child(self.render_output(cx)),
+                    ),
+            )
+    }
+}
+
+impl FocusableView for DebugPanel {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<PanelEvent> for DebugPanel {}
+
+impl Panel for DebugPanel {
+    fn persistent_name() -> &'static str {
+        "DebugPanel"
+    }
+
+    fn position(&self, cx: &WindowContext) -> DockPosition {
+        DebuggerPanelSettings::get_global(cx).dock
+    }
+
+    fn position_is_valid(&self, _: DockPosition) -> bool {
+        true
+    }
+
+    fn set_position(&mut self, position: DockPosition, cx: &mut ViewContext<Self>) {
+        settings::update_settings_file::<DebuggerPanelSettings>(
+            self.fs.clone(),
+            cx,
+            move |settings, _| settings.dock = Some(position),
+        );
+    }
+
+    fn size(&self, cx: &WindowContext) -> Pixels {
+        self.height
+            .unwrap_or_else(|| DebuggerPanelSettings::get_global(cx).default_height)
+    }
+
+    fn set_size(&mut self, size: Option<Pixels>, cx: &mut ViewContext<Self>) {
+        self.height = size;
+        self.serialize(cx);
+        cx.notify();
+    }
+
+    fn icon(&self, cx: &WindowContext) -> Option<IconName> {
+        Some(IconName::DebugBreakpoint).filter(|_| DebuggerPanelSettings::get_global(cx).button)
+    }
+
+    fn icon_tooltip(&self, _cx: &WindowContext) -> Option<&'static str> {
+        Some("Debugger Panel")
+    }
+
+    fn toggle_action(&self) -> Box<dyn Action> {
+        Box::new(ToggleFocus)
+    }
+}
@@ -0,0 +1,41 @@
+use gpui::Pixels;
+use schemars::JsonSchema;
+use serde_derive::{Deserialize, Serialize};
+use settings::{Settings, SettingsSources};
+use workspace::dock::DockPosition;
+
+#[derive(Deserialize, Debug)]
+pub struct DebuggerPanelSettings {
+    pub button: bool,
+    pub dock: DockPosition,
+    pub default_height: Pixels,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, JsonSchema, Debug)]
+pub struct DebuggerPanelSettingsContent {
+    /// Whether to show the panel button in the status bar.
+    ///
+    /// Default: true
+    pub button: Option<bool>,
+    /// Where to dock the panel.
+    ///
+    /// Default: bottom
+    pub dock: Option<DockPosition>,
+    /// Default height of the panel in pixels.
+    ///
+    /// Default: 320
+    pub default_height: Option<f32>,
+}
+
+impl Settings for DebuggerPanelSettings {
+    const KEY: Option<&'static str> = Some("debugger_panel");
+
+    type FileContent = DebuggerPanelSettingsContent;
+
+    fn load(
+        sources: SettingsSources<Self::FileContent>,
+        _: &mut gpui::AppContext,
+    ) -> anyhow::Result<Self> {
+        sources.json_merge()
+    }
+}
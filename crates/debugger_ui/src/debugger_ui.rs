@@ -0,0 +1,11 @@
+use ::settings::Settings;
+use debugger_panel_settings::DebuggerPanelSettings;
+use gpui::AppContext;
+
+pub mod debugger_panel;
+mod debugger_panel_settings;
+
+pub fn init(cx: &mut AppContext) {
+    DebuggerPanelSettings::register(cx);
+    debugger_panel::init(cx);
+}
@@ -264,6 +264,7 @@ gpui::actions!(
         ToggleFoldRecursive,
         Format,
         FormatSelections,
+        GoToConflict,
         GoToDeclaration,
         GoToDeclarationSplit,
         GoToDefinition,
@@ -272,6 +273,7 @@ gpui::actions!(
         GoToHunk,
         GoToImplementation,
         GoToImplementationSplit,
+        GoToPrevConflict,
         GoToPrevDiagnostic,
         GoToPrevHunk,
         GoToTypeDefinition,
@@ -311,6 +313,7 @@ gpui::actions!(
         OpenExcerpts,
         OpenExcerptsSplit,
         OpenProposedChangesEditor,
+        OpenSplitDiff,
         OpenFile,
         OpenDocs,
         OpenPermalinkToLine,
@@ -356,16 +359,23 @@ gpui::actions!(
         SelectToStartOfParagraph,
         SelectUp,
         ShowCharacterPalette,
+        ShowIncomingCalls,
         ShowInlineCompletion,
+        ShowOutgoingCalls,
         ShowSignatureHelp,
+        ShowSubtypes,
+        ShowSupertypes,
         ShuffleLines,
         SortLinesCaseInsensitive,
         SortLinesCaseSensitive,
         SplitSelectionIntoLines,
+        StageHunk,
+        StageSelectedLines,
         SwitchSourceHeader,
         Tab,
         TabPrev,
         ToggleAutoSignatureHelp,
+        ToggleBreakpoint,
         ToggleGitBlame,
         ToggleGitBlameInline,
         ToggleHunkDiff,
@@ -385,6 +395,7 @@ gpui::actions!(
         UnfoldRecursive,
         UniqueLinesCaseInsensitive,
         UniqueLinesCaseSensitive,
+        UnstageHunk,
     ]
 );
 
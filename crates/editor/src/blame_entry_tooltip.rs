@@ -6,11 +6,12 @@ use gpui::{
     StatefulInteractiveElement, WeakView,
 };
 use settings::Settings;
-use std::hash::Hash;
+use std::{hash::Hash, path::PathBuf};
 use theme::ThemeSettings;
 use time::UtcOffset;
-use ui::{prelude::*, tooltip_container, Avatar, Divider, IconButtonShape};
+use ui::{prelude::*, tooltip_container, Avatar, Divider, IconButtonShape, Tooltip};
 use workspace::Workspace;
+use zed_actions::git::ViewCommit;
 
 use crate::git::blame::{CommitDetails, GitRemote};
 use crate::EditorStyle;
@@ -92,6 +93,8 @@ pub(crate) struct BlameEntryTooltip {
     details: Option<CommitDetails>,
     editor_style: EditorStyle,
     workspace: Option<WeakView<Workspace>>,
+    /// The absolute path of the blamed file, to open the commit in its repository.
+    abs_path: Option<PathBuf>,
     scroll_handle: ScrollHandle,
 }
 
@@ -101,12 +104,14 @@ impl BlameEntryTooltip {
         details: Option<CommitDetails>,
         style: &EditorStyle,
         workspace: Option<WeakView<Workspace>>,
+        abs_path: Option<PathBuf>,
     ) -> Self {
         Self {
             editor_style: style.clone(),
             blame_entry,
             details,
             workspace,
+            abs_path,
             scroll_handle: ScrollHandle::new(),
         }
     }
@@ -147,6 +152,10 @@ impl Render for BlameEntryTooltip {
             .details
             .as_ref()
             .and_then(|details| details.pull_request.clone());
+        let view_commit = self.abs_path.clone().map(|path| ViewCommit {
+            sha: full_sha.clone(),
+            path,
+        });
 
         let ui_font_size = ThemeSettings::get_global(cx).ui_font_size;
         let message_max_height = cx.line_height() * 12 + (ui_font_size / 0.4);
@@ -242,6 +251,24 @@ impl Render for BlameEntryTooltip {
                                                 },
                                             ),
                                         )
+                                        .when_some(view_commit, |this, view_commit| {
+                                            this.child(
+                                                IconButton::new(
+                                                    "view-commit-button",
+                                                    IconName::Eye,
+                                                )
+                                                .shape(IconButtonShape::Square)
+                                                .icon_size(IconSize::Small)
+                                                .icon_color(Color::Muted)
+                                                .tooltip(|cx| Tooltip::text("View Commit", cx))
+                                                .on_click(move |_, cx| {
+                                                    cx.stop_propagation();
+                                                    cx.dispatch_action(Box::new(
+                                                        view_commit.clone(),
+                                                    ))
+                                                }),
+                                            )
+                                        })
                                         .child(
                                             IconButton::new("copy-sha-button", IconName::Copy)
                                                 .shape(IconButtonShape::Square)
@@ -0,0 +1,390 @@
+use std::{ops::Range, sync::Arc, time::Duration};
+
+use collections::{BTreeMap, HashMap, HashSet};
+use futures::future::join_all;
+use gpui::{div, AnyElement, Model, Task, ViewContext, WeakView};
+use language::{Buffer, Point, ToOffset as _, ToPoint as _};
+use multi_buffer::{Anchor, ToPoint as _};
+use project::CodeLens;
+use settings::Settings;
+use text::BufferId;
+use ui::{h_flex, prelude::*};
+use util::ResultExt;
+use workspace::notifications::NotifyTaskExt;
+
+use crate::{
+    display_map::{
+        BlockContext, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, RenderBlock,
+    },
+    Bias, Editor, EditorMode, EditorSettings,
+};
+
+const CODE_LENS_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// The code lenses of an editor's buffers and the blocks showing them.
+#[derive(Default)]
+pub(super) struct CodeLensState {
+    fetch_tasks: HashMap<BufferId, Task<()>>,
+    resolve_task: Option<Task<()>>,
+    /// The latest code lenses of each buffer. Their commands are resolved once they are visible.
+    lenses: HashMap<BufferId, Vec<CodeLens>>,
+    blocks: Vec<CodeLensBlock>,
+}
+
+struct CodeLensBlock {
+    position: Anchor,
+    id: CustomBlockId,
+    lenses: Vec<CodeLens>,
+}
+
+/// Requests the code lenses of `buffer`, or of every buffer in the editor when it is `None`, and
+/// shows them in blocks above the lines they refer to. When `debounce` is set, waits a little
+/// first, so that typing does not flood the language server with requests.
+pub(super) fn refresh_code_lens(
+    editor: &mut Editor,
+    buffer: Option<Model<Buffer>>,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    if !EditorSettings::get_global(cx).code_lens {
+        editor.code_lens.fetch_tasks.clear();
+        editor.code_lens.resolve_task = None;
+        editor.code_lens.lenses.clear();
+        update_code_lens_blocks(editor, cx);
+        return;
+    }
+
+    let buffers = match buffer {
+        Some(buffer) => vec![buffer],
+        None => editor.buffer.read(cx).all_buffers().into_iter().collect(),
+    };
+    for buffer in buffers {
+        let buffer_id = buffer.read(cx).remote_id();
+        let project = project.clone();
+        let task = cx.spawn(|editor, mut cx| async move {
+            if debounce {
+                cx.background_executor().timer(CODE_LENS_DEBOUNCE).await;
+            }
+
+            let Some(lenses) = project
+                .update(&mut cx, |project, cx| project.code_lens(&buffer, cx))
+                .log_err()
+            else {
+                return;
+            };
+            let mut lenses = lenses.await.log_err().unwrap_or_default();
+
+            editor
+                .update(&mut cx, |editor, cx| {
+                    // Lenses that did not change keep the commands they were resolved with, so
+                    // that they aren't resolved again.
+                    if let Some(previous_lenses) = editor.code_lens.lenses.get(&buffer_id) {
+                        for lens in &mut lenses {
+                            if lens.lsp_lens.command.is_none() {
+                                lens.lsp_lens.command = previous_lenses
+                                    .iter()
+                                    .find(|previous| same_lens(previous, lens))
+                                    .and_then(|previous| previous.lsp_lens.command.clone());
+                            }
+                        }
+                    }
+                    editor.code_lens.lenses.insert(buffer_id, lenses);
+                    update_code_lens_blocks(editor, cx);
+                    resolve_visible_code_lens(editor, false, cx);
+                })
+                .ok();
+        });
+        editor.code_lens.fetch_tasks.insert(buffer_id, task);
+    }
+}
+
+/// Resolves the commands of the code lenses in the visible part of the editor. When `debounce` is
+/// set, waits a little first, so that scrolling does not flood the language server with requests.
+pub(super) fn resolve_visible_code_lens(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.code_lens.lenses.is_empty() {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+
+    let mut unresolved = Vec::new();
+    for (buffer, visible_range) in visible_buffer_ranges(editor, cx) {
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let Some(lenses) = editor.code_lens.lenses.get(&buffer_snapshot.remote_id()) else {
+            continue;
+        };
+        for lens in lenses {
+            let start = lens.range.start.to_offset(&buffer_snapshot);
+            if lens.lsp_lens.command.is_none()
+                && visible_range.start <= start
+                && start <= visible_range.end
+            {
+                unresolved.push((buffer.clone(), lens.clone()));
+            }
+        }
+    }
+    if unresolved.is_empty() {
+        return;
+    }
+
+    editor.code_lens.resolve_task = Some(cx.spawn(|editor, mut cx| async move {
+        if debounce {
+            cx.background_executor().timer(CODE_LENS_DEBOUNCE).await;
+        }
+
+        let Some(resolves) = project
+            .update(&mut cx, |project, cx| {
+                unresolved
+                    .into_iter()
+                    .map(|(buffer, lens)| {
+                        let buffer_id = buffer.read(cx).remote_id();
+                        let resolve = project.resolve_code_lens(lens.clone(), buffer, cx);
+                        async move { (buffer_id, lens, resolve.await.log_err()) }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .log_err()
+        else {
+            return;
+        };
+        let resolved = join_all(resolves).await;
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                for (buffer_id, lens, resolved_lens) in resolved {
+                    let Some(lenses) = editor.code_lens.lenses.get_mut(&buffer_id) else {
+                        continue;
+                    };
+                    let Some(ix) = lenses.iter().position(|other| same_lens(other, &lens)) else {
+                        continue;
+                    };
+                    // Lenses that can't be resolved to a command have nothing to show.
+                    match resolved_lens {
+                        Some(resolved_lens) if resolved_lens.lsp_lens.command.is_some() => {
+                            lenses[ix] = resolved_lens;
+                        }
+                        _ => {
+                            lenses.remove(ix);
+                        }
+                    }
+                }
+                update_code_lens_blocks(editor, cx);
+            })
+            .ok();
+    }));
+}
+
+/// Whether two code lenses, possibly from different responses, are the same lens of a buffer.
+fn same_lens(a: &CodeLens, b: &CodeLens) -> bool {
+    a.server_id == b.server_id
+        && a.lsp_lens.range == b.lsp_lens.range
+        && a.lsp_lens.data == b.lsp_lens.data
+}
+
+fn visible_buffer_ranges(
+    editor: &Editor,
+    cx: &ViewContext<Editor>,
+) -> Vec<(Model<Buffer>, Range<usize>)> {
+    let multi_buffer = editor.buffer.read(cx);
+    let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+    let visible_start = editor
+        .scroll_manager
+        .anchor()
+        .anchor
+        .to_point(&multi_buffer_snapshot);
+    let visible_end = multi_buffer_snapshot.clip_point(
+        visible_start + Point::new(editor.visible_line_count().unwrap_or(0.).ceil() as u32, 0),
+        Bias::Left,
+    );
+    multi_buffer
+        .range_to_buffer_ranges(visible_start..visible_end, cx)
+        .into_iter()
+        .map(|(buffer, range, _)| (buffer, range))
+        .collect()
+}
+
+/// Shows the resolved code lenses in blocks above the lines they refer to. Blocks whose lenses did
+/// not change are kept, and only the others are replaced.
+pub(super) fn update_code_lens_blocks(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let weak_editor = cx.view().downgrade();
+    let multi_buffer = editor.buffer.read(cx);
+    let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+    editor
+        .code_lens
+        .lenses
+        .retain(|buffer_id, _| multi_buffer.buffer(*buffer_id).is_some());
+
+    let mut new_blocks = HashMap::default();
+    for (buffer_id, lenses) in &editor.code_lens.lenses {
+        let Some(buffer) = multi_buffer.buffer(*buffer_id) else {
+            continue;
+        };
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let mut lenses_by_row = BTreeMap::<u32, Vec<CodeLens>>::new();
+        for lens in lenses {
+            if lens.lsp_lens.command.is_some() {
+                let row = lens.range.start.to_point(&buffer_snapshot).row;
+                lenses_by_row.entry(row).or_default().push(lens.clone());
+            }
+        }
+
+        for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(&buffer, cx) {
+            let excerpt_range = excerpt_range.context.to_point(&buffer_snapshot);
+            for (row, lenses) in lenses_by_row.range(excerpt_range.start.row..) {
+                let line_start = Point::new(*row, 0);
+                if line_start >= excerpt_range.end {
+                    break;
+                }
+                let indent = buffer_snapshot.indent_size_for_line(*row).len;
+                let Some(position) = multi_buffer_snapshot.anchor_in_excerpt(
+                    excerpt_id,
+                    buffer_snapshot.anchor_before(Point::new(*row, indent)),
+                ) else {
+                    continue;
+                };
+                new_blocks.insert(
+                    position.to_point(&multi_buffer_snapshot),
+                    (position, buffer.clone(), lenses.clone()),
+                );
+            }
+        }
+    }
+
+    let mut blocks_to_remove = HashSet::default();
+    let mut kept_blocks = Vec::new();
+    for block in editor.code_lens.blocks.drain(..) {
+        let point = block.position.to_point(&multi_buffer_snapshot);
+        let unchanged = new_blocks.get(&point).map_or(false, |(_, _, lenses)| {
+            lenses.len() == block.lenses.len()
+                && lenses.iter().zip(&block.lenses).all(|(lens, block_lens)| {
+                    same_lens(lens, block_lens)
+                        && lens.lsp_lens.command == block_lens.lsp_lens.command
+                })
+        });
+        if unchanged {
+            new_blocks.remove(&point);
+            kept_blocks.push(block);
+        } else {
+            blocks_to_remove.insert(block.id);
+        }
+    }
+    editor.code_lens.blocks = kept_blocks;
+    if blocks_to_remove.is_empty() && new_blocks.is_empty() {
+        return;
+    }
+
+    if !blocks_to_remove.is_empty() {
+        editor.remove_blocks(blocks_to_remove, None, cx);
+    }
+    let (positions, blocks): (Vec<_>, Vec<_>) = new_blocks
+        .into_values()
+        .map(|(position, buffer, lenses)| {
+            let block = BlockProperties {
+                placement: BlockPlacement::Above(position),
+                height: 1,
+                style: BlockStyle::Flex,
+                render: render_code_lens(weak_editor.clone(), buffer, lenses.clone()),
+                priority: 0,
+            };
+            ((position, lenses), block)
+        })
+        .unzip();
+    let block_ids = editor.insert_blocks(blocks, None, cx);
+    editor
+        .code_lens
+        .blocks
+        .extend(
+            positions
+                .into_iter()
+                .zip(block_ids)
+                .map(|((position, lenses), id)| CodeLensBlock {
+                    position,
+                    id,
+                    lenses,
+                }),
+        );
+    cx.notify();
+}
+
+fn render_code_lens(
+    editor: WeakView<Editor>,
+    buffer: Model<Buffer>,
+    lenses: Vec<CodeLens>,
+) -> RenderBlock {
+    Arc::new(move |cx: &mut BlockContext| {
+        let muted_color = cx.theme().colors().text_muted;
+        let hover_color = cx.theme().colors().text;
+        h_flex()
+            .id(cx.block_id)
+            .block_mouse_down()
+            .pl(cx.anchor_x)
+            .gap_1()
+            .font_family(cx.editor_style.text.font_family.clone())
+            .text_size(cx.editor_style.text.font_size)
+            .text_color(muted_color)
+            .children(lenses.iter().enumerate().map(|(ix, lens)| {
+                let title = lens
+                    .lsp_lens
+                    .command
+                    .as_ref()
+                    .map(|command| command.title.clone())
+                    .unwrap_or_default();
+                let editor = editor.clone();
+                let buffer = buffer.clone();
+                let lens = lens.clone();
+                h_flex().when(ix > 0, |this| this.child("|")).child(
+                    div()
+                        .id(ix)
+                        .cursor_pointer()
+                        .hover(|style| style.text_color(hover_color))
+                        .child(title)
+                        .on_click(move |_, cx| {
+                            editor
+                                .update(cx, |editor, cx| {
+                                    run_code_lens(editor, buffer.clone(), lens.clone(), cx)
+                                })
+                                .ok();
+                        }),
+                )
+            }))
+            .into_any_element()
+    })
+}
+
+fn run_code_lens(
+    editor: &mut Editor,
+    buffer: Model<Buffer>,
+    lens: CodeLens,
+    cx: &mut ViewContext<Editor>,
+) {
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    let Some(workspace) = editor.workspace() else {
+        return;
+    };
+    let title = lens
+        .lsp_lens
+        .command
+        .as_ref()
+        .map(|command| command.title.clone())
+        .unwrap_or_default();
+    let run = project.update(cx, |project, cx| project.run_code_lens(buffer, lens, cx));
+    let workspace = workspace.downgrade();
+    cx.spawn(|editor, cx| async move {
+        let project_transaction = run.await?;
+        Editor::open_project_transaction(&editor, workspace, project_transaction, title, cx).await
+    })
+    .detach_and_notify_err(cx);
+}
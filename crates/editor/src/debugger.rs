@@ -0,0 +1,175 @@
+use collections::HashMap;
+use gpui::{AppContext, Model, ViewContext};
+use language::{Bias, Buffer, Point, ToPoint as _};
+use multi_buffer::Anchor;
+use project::{dap_store::DapStoreEvent, ProjectPath};
+use std::ops::Range;
+use text::OffsetRangeExt as _;
+use ui::{prelude::*, IconButtonShape};
+
+use crate::{
+    DisplayPoint, DisplayRow, Editor, EditorSnapshot, ToDisplayPoint as _, ToggleBreakpoint,
+};
+
+/// Highlights the line the debugger is currently paused at.
+enum DebugCurrentRowHighlight {}
+
+impl Editor {
+    pub(crate) fn on_dap_store_event(&mut self, event: &DapStoreEvent, cx: &mut ViewContext<Self>) {
+        match event {
+            DapStoreEvent::BreakpointsChanged(_) => cx.notify(),
+            DapStoreEvent::ActiveLocationChanged => self.refresh_debug_location(cx),
+            _ => {}
+        }
+    }
+
+    pub fn toggle_breakpoint(&mut self, _: &ToggleBreakpoint, cx: &mut ViewContext<Self>) {
+        let head = self.selections.newest_anchor().head();
+        self.toggle_breakpoint_at(head, cx);
+    }
+
+    fn toggle_breakpoint_at(&mut self, position: Anchor, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.as_ref() else {
+            return;
+        };
+        let dap_store = project.read(cx).dap_store().clone();
+        let Some((buffer, buffer_position)) =
+            self.buffer.read(cx).text_anchor_for_position(position, cx)
+        else {
+            return;
+        };
+        dap_store.update(cx, |dap_store, cx| {
+            dap_store.toggle_breakpoint(&buffer, buffer_position, cx);
+        });
+    }
+
+    /// Returns the display rows within `range` that have a breakpoint set, in every excerpt
+    /// showing the breakpoint's line.
+    pub(crate) fn breakpoint_display_rows(
+        &self,
+        range: Range<DisplayRow>,
+        snapshot: &EditorSnapshot,
+        cx: &AppContext,
+    ) -> Vec<(DisplayRow, Anchor)> {
+        let Some(project) = self.project.as_ref() else {
+            return Vec::new();
+        };
+        let dap_store = project.read(cx).dap_store().read(cx);
+        if dap_store.breakpoints().is_empty() {
+            return Vec::new();
+        }
+
+        let multi_buffer_snapshot = &snapshot.buffer_snapshot;
+        let start = snapshot.clip_point(DisplayPoint::new(range.start, 0), Bias::Left);
+        let end = snapshot.clip_point(DisplayPoint::new(range.end, 0), Bias::Left);
+        let mut rows_by_buffer = HashMap::default();
+        let mut rows = Vec::new();
+        for excerpt in multi_buffer_snapshot
+            .excerpts_for_range(start.to_point(snapshot)..end.to_point(snapshot))
+        {
+            let buffer_snapshot = excerpt.buffer();
+            let buffer_rows = rows_by_buffer
+                .entry(buffer_snapshot.remote_id())
+                .or_insert_with(|| dap_store.breakpoint_rows(buffer_snapshot, cx));
+            let excerpt_range = excerpt.buffer_range().to_point(buffer_snapshot);
+            for row in buffer_rows.iter().copied() {
+                if row < excerpt_range.start.row || row > excerpt_range.end.row {
+                    continue;
+                }
+                let Some(anchor) = multi_buffer_snapshot.anchor_in_excerpt(
+                    excerpt.id(),
+                    buffer_snapshot.anchor_before(Point::new(row, 0)),
+                ) else {
+                    continue;
+                };
+                let display_row = anchor.to_display_point(snapshot).row();
+                if range.contains(&display_row) {
+                    rows.push((display_row, anchor));
+                }
+            }
+        }
+        rows.sort_by_key(|(row, _)| *row);
+        rows.dedup_by_key(|(row, _)| *row);
+        rows
+    }
+
+    fn multibuffer_anchor_for_buffer_row(
+        &self,
+        buffer: &Model<Buffer>,
+        row: u32,
+        cx: &AppContext,
+    ) -> Option<Anchor> {
+        let multi_buffer = self.buffer.read(cx);
+        let buffer_snapshot = buffer.read(cx).snapshot();
+        let position = buffer_snapshot.anchor_before(Point::new(row, 0));
+        let snapshot = multi_buffer.snapshot(cx);
+        multi_buffer
+            .excerpts_for_buffer(buffer, cx)
+            .into_iter()
+            .find(|(_, range)| {
+                let start = range.context.start.to_point(&buffer_snapshot).row;
+                let end = range.context.end.to_point(&buffer_snapshot).row;
+                (start..=end).contains(&row)
+            })
+            .and_then(|(excerpt_id, _)| snapshot.anchor_in_excerpt(excerpt_id, position))
+    }
+
+    pub(crate) fn render_breakpoint_indicator(
+        &self,
+        row: DisplayRow,
+        position: Anchor,
+        cx: &mut ViewContext<Self>,
+    ) -> IconButton {
+        IconButton::new(
+            ("breakpoint_indicator", row.0 as usize),
+            IconName::DebugBreakpoint,
+        )
+        .shape(IconButtonShape::Square)
+        .icon_size(IconSize::XSmall)
+        .icon_color(Color::Error)
+        .on_click(cx.listener(move |editor, _e, cx| {
+            editor.focus(cx);
+            editor.toggle_breakpoint_at(position, cx);
+        }))
+    }
+
+    /// Highlights the line the debugger is paused at, if it's shown in this editor.
+    pub(crate) fn refresh_debug_location(&mut self, cx: &mut ViewContext<Self>) {
+        self.clear_row_highlights::<DebugCurrentRowHighlight>();
+        let Some(project) = self.project.as_ref() else {
+            return;
+        };
+        let Some(location) = project
+            .read(cx)
+            .dap_store()
+            .read(cx)
+            .active_location()
+            .cloned()
+        else {
+            cx.notify();
+            return;
+        };
+
+        let buffer = self
+            .buffer
+            .read(cx)
+            .all_buffers()
+            .into_iter()
+            .find(|buffer| {
+                buffer.read(cx).file().map_or(false, |file| {
+                    ProjectPath::from((file.worktree_id(cx), file.path().clone())) == location.path
+                })
+            });
+        let anchor = buffer
+            .and_then(|buffer| self.multibuffer_anchor_for_buffer_row(&buffer, location.row, cx));
+        if let Some(anchor) = anchor {
+            self.highlight_rows::<DebugCurrentRowHighlight>(
+                anchor..anchor,
+                cx.theme().colors().editor_highlighted_line_background,
+                true,
+                cx,
+            );
+        }
+        cx.notify();
+    }
+}
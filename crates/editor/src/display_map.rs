@@ -66,7 +66,7 @@ use std::{
 };
 use sum_tree::{Bias, TreeMap};
 use tab_map::{TabMap, TabSnapshot};
-use text::LineIndent;
+use text::{BufferId, LineIndent};
 use ui::{px, SharedString, WindowContext};
 use unicode_segmentation::UnicodeSegmentation;
 use wrap_map::{WrapMap, WrapSnapshot};
@@ -85,6 +85,8 @@ pub trait ToDisplayPoint {
 
 type TextHighlights = TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>;
 type InlayHighlights = TreeMap<TypeId, TreeMap<InlayId, (HighlightStyle, InlayHighlight)>>;
+type SemanticTokenHighlights = TreeMap<BufferId, Arc<[(Range<Anchor>, HighlightStyle)]>>;
+type FoldRangeCreases = TreeMap<BufferId, Arc<[(Range<Anchor>, CreaseId)]>>;
 
 /// Decides how text in a [`MultiBuffer`] should be displayed in a buffer, handling inlay hints,
 /// folding, hard tabs, soft wrapping, custom blocks (like diagnostics), and highlighting.
@@ -108,8 +110,13 @@ pub struct DisplayMap {
     text_highlights: TextHighlights,
     /// Regions of inlays that should be highlighted.
     inlay_highlights: InlayHighlights,
+    /// Styles of the semantic tokens reported by language servers, layered over the syntax highlighting.
+    semantic_token_highlights: SemanticTokenHighlights,
     /// A container for explicitly foldable ranges, which supersede indentation based fold range suggestions.
     crease_map: CreaseMap,
+    /// The creases of the buffers whose fold ranges come from a folds query or a language server,
+    /// which are not folded by indentation.
+    fold_range_creases: FoldRangeCreases,
     pub(crate) fold_placeholder: FoldPlaceholder,
     pub clip_at_line_ends: bool,
     pub(crate) masked: bool,
@@ -157,9 +164,11 @@ impl DisplayMap {
             wrap_map,
             block_map,
             crease_map,
+            fold_range_creases: Default::default(),
             fold_placeholder,
             text_highlights: Default::default(),
             inlay_highlights: Default::default(),
+            semantic_token_highlights: Default::default(),
             clip_at_line_ends: false,
             masked: false,
         }
@@ -185,8 +194,10 @@ impl DisplayMap {
             wrap_snapshot,
             block_snapshot,
             crease_snapshot: self.crease_map.snapshot(),
+            fold_range_creases: self.fold_range_creases.clone(),
             text_highlights: self.text_highlights.clone(),
             inlay_highlights: self.inlay_highlights.clone(),
+            semantic_token_highlights: self.semantic_token_highlights.clone(),
             clip_at_line_ends: self.clip_at_line_ends,
             masked: self.masked,
             fold_placeholder: self.fold_placeholder.clone(),
@@ -387,6 +398,62 @@ impl DisplayMap {
         self.crease_map.remove(crease_ids, &snapshot)
     }
 
+    /// Replaces the creases for the fold ranges of the given buffer, keeping the ones whose range
+    /// did not change. Passing `None`, or no ranges, makes the buffer fall back to folding by
+    /// indentation.
+    pub(crate) fn set_fold_ranges(
+        &mut self,
+        buffer_id: BufferId,
+        ranges: Option<Vec<Range<Anchor>>>,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut old_creases = self
+            .fold_range_creases
+            .remove(&buffer_id)
+            .map(|creases| {
+                creases
+                    .iter()
+                    .map(|(range, crease_id)| {
+                        (range.to_offset(&snapshot), (range.clone(), *crease_id))
+                    })
+                    .collect::<HashMap<_, _>>()
+            })
+            .unwrap_or_default();
+
+        let mut creases = Vec::new();
+        let mut new_ranges = Vec::new();
+        for range in ranges.unwrap_or_default() {
+            match old_creases.remove(&range.to_offset(&snapshot)) {
+                Some(crease) => creases.push(crease),
+                None => new_ranges.push(range),
+            }
+        }
+        self.crease_map.remove(
+            old_creases.into_values().map(|(_, crease_id)| crease_id),
+            &snapshot,
+        );
+
+        new_ranges.sort_by(|a, b| AnchorRangeExt::cmp(a, b, &snapshot));
+        let crease_ids = self.crease_map.insert(
+            new_ranges
+                .iter()
+                .map(|range| Crease::simple(range.clone(), self.fold_placeholder.clone())),
+            &snapshot,
+        );
+        creases.extend(new_ranges.into_iter().zip(crease_ids));
+        if !creases.is_empty() {
+            creases.sort_by(|(a, _), (b, _)| AnchorRangeExt::cmp(a, b, &snapshot));
+            self.fold_range_creases.insert(buffer_id, creases.into());
+        }
+    }
+
+    pub(crate) fn fold_range_buffers(&self) -> impl Iterator<Item = BufferId> + '_ {
+        self.fold_range_creases
+            .iter()
+            .map(|(buffer_id, _)| *buffer_id)
+    }
+
     pub fn insert_blocks(
         &mut self,
         blocks: impl IntoIterator<Item = BlockProperties<Anchor>>,
@@ -489,6 +556,31 @@ impl DisplayMap {
         }
     }
 
+    /// Replaces the semantic token styles of the given buffer. The ranges must be sorted and
+    /// non-overlapping.
+    pub(crate) fn set_semantic_token_highlights(
+        &mut self,
+        buffer_id: BufferId,
+        highlights: Vec<(Range<Anchor>, HighlightStyle)>,
+    ) {
+        if highlights.is_empty() {
+            self.semantic_token_highlights.remove(&buffer_id);
+        } else {
+            self.semantic_token_highlights
+                .insert(buffer_id, highlights.into());
+        }
+    }
+
+    pub(crate) fn clear_semantic_token_highlights(&mut self) {
+        self.semantic_token_highlights = Default::default();
+    }
+
+    pub(crate) fn semantic_token_buffers(&self) -> impl Iterator<Item = BufferId> + '_ {
+        self.semantic_token_highlights
+            .iter()
+            .map(|(buffer_id, _)| *buffer_id)
+    }
+
     pub fn text_highlights(&self, type_id: TypeId) -> Option<(HighlightStyle, &[Range<Anchor>])> {
         let highlights = self.text_highlights.get(&type_id)?;
         Some((highlights.0, &highlights.1))
@@ -565,6 +657,7 @@ impl DisplayMap {
 pub(crate) struct Highlights<'a> {
     pub text_highlights: Option<&'a TextHighlights>,
     pub inlay_highlights: Option<&'a InlayHighlights>,
+    pub semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
     pub styles: HighlightStyles,
 }
 
@@ -693,12 +786,14 @@ pub struct DisplaySnapshot {
     pub buffer_snapshot: MultiBufferSnapshot,
     pub fold_snapshot: FoldSnapshot,
     pub crease_snapshot: CreaseSnapshot,
+    fold_range_creases: FoldRangeCreases,
     inlay_snapshot: InlaySnapshot,
     tab_snapshot: TabSnapshot,
     wrap_snapshot: WrapSnapshot,
     block_snapshot: BlockSnapshot,
     text_highlights: TextHighlights,
     inlay_highlights: InlayHighlights,
+    semantic_token_highlights: SemanticTokenHighlights,
     clip_at_line_ends: bool,
     masked: bool,
     pub(crate) fold_placeholder: FoldPlaceholder,
@@ -886,6 +981,7 @@ impl DisplaySnapshot {
             Highlights {
                 text_highlights: Some(&self.text_highlights),
                 inlay_highlights: Some(&self.inlay_highlights),
+                semantic_token_highlights: Some(&self.semantic_token_highlights),
                 styles: highlight_styles,
             },
         )
@@ -1195,6 +1291,18 @@ impl DisplaySnapshot {
             .unwrap_or(false)
     }
 
+    /// Whether the folds of the given row are inferred from indentation, because its buffer has
+    /// no fold ranges from a folds query or a language server.
+    pub fn folds_by_indent(&self, buffer_row: MultiBufferRow) -> bool {
+        self.buffer_snapshot
+            .buffer_line_for_row(buffer_row)
+            .map_or(true, |(buffer, _)| {
+                self.fold_range_creases
+                    .get(&buffer.remote_id())
+                    .map_or(true, |creases| creases.is_empty())
+            })
+    }
+
     pub fn crease_for_buffer_row(&self, buffer_row: MultiBufferRow) -> Option<Crease<Point>> {
         let start = MultiBufferPoint::new(buffer_row.0, self.buffer_snapshot.line_len(buffer_row));
         if let Some(crease) = self
@@ -1231,7 +1339,8 @@ impl DisplaySnapshot {
                     render_toggle: render_toggle.clone(),
                 }),
             }
-        } else if self.starts_indent(MultiBufferRow(start.row))
+        } else if self.folds_by_indent(buffer_row)
+            && self.starts_indent(MultiBufferRow(start.row))
             && !self.is_line_folded(MultiBufferRow(start.row))
         {
             let start_line_indent = self.line_indent_for_buffer_row(buffer_row);
@@ -2629,6 +2738,69 @@ pub mod tests {
         });
     }
 
+    #[gpui::test]
+    fn test_fold_ranges(cx: &mut gpui::AppContext) {
+        init_test(cx, |_| {});
+
+        let text = "aaa\n    bbb\n    ccc\nddd\n    eee\nfff";
+        let buffer = MultiBuffer::build_simple(text, cx);
+        let buffer_id = buffer.read(cx).as_singleton().unwrap().read(cx).remote_id();
+        let map = cx.new_model(|cx| {
+            DisplayMap::new(
+                buffer.clone(),
+                font("Helvetica"),
+                px(14.0),
+                None,
+                true,
+                1,
+                1,
+                0,
+                FoldPlaceholder::test(),
+                cx,
+            )
+        });
+        let snapshot = buffer.read(cx).snapshot(cx);
+        let first_range =
+            snapshot.anchor_before(Point::new(0, 3))..snapshot.anchor_after(Point::new(2, 7));
+        let second_range =
+            snapshot.anchor_before(Point::new(3, 3))..snapshot.anchor_after(Point::new(4, 7));
+        let crease_ids = |map: &Model<DisplayMap>, cx: &mut gpui::AppContext| {
+            map.read(cx)
+                .fold_range_creases
+                .get(&buffer_id)
+                .map(|creases| creases.iter().map(|(_, id)| *id).collect::<Vec<_>>())
+                .unwrap_or_default()
+        };
+
+        // Without any fold ranges, the buffer is folded by indentation.
+        map.update(cx, |map, cx| {
+            map.set_fold_ranges(buffer_id, Some(Vec::new()), cx)
+        });
+        let display_snapshot = map.update(cx, |map, cx| map.snapshot(cx));
+        assert!(display_snapshot.folds_by_indent(MultiBufferRow(0)));
+
+        map.update(cx, |map, cx| {
+            map.set_fold_ranges(buffer_id, Some(vec![first_range.clone()]), cx)
+        });
+        let display_snapshot = map.update(cx, |map, cx| map.snapshot(cx));
+        assert!(!display_snapshot.folds_by_indent(MultiBufferRow(0)));
+        let first_ids = crease_ids(&map, cx);
+        assert_eq!(first_ids.len(), 1);
+
+        // The crease of an unchanged range is kept.
+        map.update(cx, |map, cx| {
+            map.set_fold_ranges(buffer_id, Some(vec![first_range, second_range]), cx)
+        });
+        let second_ids = crease_ids(&map, cx);
+        assert_eq!(second_ids.len(), 2);
+        assert_eq!(second_ids[0], first_ids[0]);
+
+        map.update(cx, |map, cx| map.set_fold_ranges(buffer_id, None, cx));
+        let display_snapshot = map.update(cx, |map, cx| map.snapshot(cx));
+        assert!(display_snapshot.folds_by_indent(MultiBufferRow(0)));
+        assert!(crease_ids(&map, cx).is_empty());
+    }
+
     #[gpui::test]
     fn test_tabs_with_multibyte_chars(cx: &mut gpui::AppContext) {
         init_test(cx, |_| {});
@@ -7,10 +7,11 @@ use std::{
     cmp,
     iter::{self, Peekable},
     ops::Range,
-    sync::Arc,
     vec,
 };
-use sum_tree::TreeMap;
+use util::post_inc;
+
+use super::{SemanticTokenHighlights, TextHighlights};
 
 pub struct CustomHighlightsChunks<'a> {
     buffer_chunks: MultiBufferChunks<'a>,
@@ -19,15 +20,24 @@ pub struct CustomHighlightsChunks<'a> {
     multibuffer_snapshot: &'a MultiBufferSnapshot,
 
     highlight_endpoints: Peekable<vec::IntoIter<HighlightEndpoint>>,
-    active_highlights: BTreeMap<TypeId, HighlightStyle>,
-    text_highlights: Option<&'a TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+    active_highlights: BTreeMap<HighlightKey, HighlightStyle>,
+    text_highlights: Option<&'a TextHighlights>,
+    semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
+}
+
+/// Identifies an active highlight. Semantic tokens sort first, so that other highlights are
+/// layered over them, and are keyed individually, so that adjacent tokens don't end each other.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+enum HighlightKey {
+    SemanticToken(usize),
+    Text(TypeId),
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 struct HighlightEndpoint {
     offset: usize,
     is_start: bool,
-    tag: TypeId,
+    tag: HighlightKey,
     style: HighlightStyle,
 }
 
@@ -35,7 +45,8 @@ impl<'a> CustomHighlightsChunks<'a> {
     pub fn new(
         range: Range<usize>,
         language_aware: bool,
-        text_highlights: Option<&'a TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+        text_highlights: Option<&'a TextHighlights>,
+        semantic_token_highlights: Option<&'a SemanticTokenHighlights>,
         multibuffer_snapshot: &'a MultiBufferSnapshot,
     ) -> Self {
         Self {
@@ -44,9 +55,11 @@ impl<'a> CustomHighlightsChunks<'a> {
             offset: range.start,
 
             text_highlights,
+            semantic_token_highlights,
             highlight_endpoints: create_highlight_endpoints(
                 &range,
                 text_highlights,
+                semantic_token_highlights,
                 multibuffer_snapshot,
             ),
             active_highlights: Default::default(),
@@ -55,8 +68,12 @@ impl<'a> CustomHighlightsChunks<'a> {
     }
 
     pub fn seek(&mut self, new_range: Range<usize>) {
-        self.highlight_endpoints =
-            create_highlight_endpoints(&new_range, self.text_highlights, self.multibuffer_snapshot);
+        self.highlight_endpoints = create_highlight_endpoints(
+            &new_range,
+            self.text_highlights,
+            self.semantic_token_highlights,
+            self.multibuffer_snapshot,
+        );
         self.offset = new_range.start;
         self.buffer_chunks.seek(new_range);
         self.buffer_chunk.take();
@@ -66,13 +83,40 @@ impl<'a> CustomHighlightsChunks<'a> {
 
 fn create_highlight_endpoints(
     range: &Range<usize>,
-    text_highlights: Option<&TreeMap<TypeId, Arc<(HighlightStyle, Vec<Range<Anchor>>)>>>,
+    text_highlights: Option<&TextHighlights>,
+    semantic_token_highlights: Option<&SemanticTokenHighlights>,
     buffer: &MultiBufferSnapshot,
 ) -> iter::Peekable<vec::IntoIter<HighlightEndpoint>> {
     let mut highlight_endpoints = Vec::new();
+    let start = buffer.anchor_after(range.start);
+    let end = buffer.anchor_after(range.end);
+    if let Some(semantic_token_highlights) = semantic_token_highlights {
+        let mut token_ix = 0;
+        for (_, tokens) in semantic_token_highlights.iter() {
+            let start_ix =
+                tokens.partition_point(|(range, _)| range.end.cmp(&start, buffer).is_le());
+            for (range, style) in &tokens[start_ix..] {
+                if range.start.cmp(&end, buffer).is_ge() {
+                    break;
+                }
+
+                let tag = HighlightKey::SemanticToken(post_inc(&mut token_ix));
+                highlight_endpoints.push(HighlightEndpoint {
+                    offset: range.start.to_offset(buffer),
+                    is_start: true,
+                    tag,
+                    style: *style,
+                });
+                highlight_endpoints.push(HighlightEndpoint {
+                    offset: range.end.to_offset(buffer),
+                    is_start: false,
+                    tag,
+                    style: *style,
+                });
+            }
+        }
+    }
     if let Some(text_highlights) = text_highlights {
-        let start = buffer.anchor_after(range.start);
-        let end = buffer.anchor_after(range.end);
         for (&tag, text_highlights) in text_highlights.iter() {
             let style = text_highlights.0;
             let ranges = &text_highlights.1;
@@ -96,19 +140,19 @@ fn create_highlight_endpoints(
                 highlight_endpoints.push(HighlightEndpoint {
                     offset: range.start.to_offset(&buffer),
                     is_start: true,
-                    tag,
+                    tag: HighlightKey::Text(tag),
                     style,
                 });
                 highlight_endpoints.push(HighlightEndpoint {
                     offset: range.end.to_offset(&buffer),
                     is_start: false,
-                    tag,
+                    tag: HighlightKey::Text(tag),
                     style,
                 });
             }
         }
-        highlight_endpoints.sort();
     }
+    highlight_endpoints.sort();
     highlight_endpoints.into_iter().peekable()
 }
 
@@ -17,10 +17,13 @@ mod blame_entry_tooltip;
 mod blink_manager;
 mod clangd_ext;
 mod code_context_menus;
+mod code_lens;
+mod debugger;
 pub mod display_map;
 mod editor_settings;
 mod editor_settings_controls;
 mod element;
+mod folding_ranges;
 mod git;
 mod highlight_matching_bracket;
 mod hover_links;
@@ -31,6 +34,7 @@ mod inlay_hint_cache;
 pub mod items;
 mod linked_editing_ranges;
 mod lsp_ext;
+mod merge_conflicts;
 mod mouse_context_menu;
 pub mod movement;
 mod persistence;
@@ -38,6 +42,7 @@ mod proposed_changes_editor;
 mod rust_analyzer_ext;
 pub mod scroll;
 mod selections_collection;
+mod semantic_tokens;
 pub mod tasks;
 
 #[cfg(test)]
@@ -76,6 +81,7 @@ use code_context_menus::{
     CompletionEntry, CompletionsMenu, ContextMenuOrigin,
 };
 use git::blame::GitBlame;
+pub use git::{project_conflicts, split_diff::SplitDiffView};
 use gpui::{
     div, impl_actions, point, prelude::*, px, relative, size, Action, AnyElement, AppContext,
     AsyncWindowContext, AvailableSpace, Bounds, ClipboardEntry, ClipboardItem, Context,
@@ -119,6 +125,9 @@ use lsp::{
     LanguageServerId, LanguageServerName,
 };
 
+use code_lens::{refresh_code_lens, update_code_lens_blocks, CodeLensState};
+use folding_ranges::{clear_removed_folding_ranges, refresh_folding_ranges};
+use merge_conflicts::{refresh_merge_conflicts, MergeConflict, MergeConflictCodeActions};
 use movement::TextLayoutDetails;
 pub use multi_buffer::{
     Anchor, AnchorRangeExt, ExcerptId, ExcerptRange, MultiBuffer, MultiBufferSnapshot, ToOffset,
@@ -130,8 +139,8 @@ use multi_buffer::{
 use project::{
     lsp_store::{FormatTarget, FormatTrigger, OpenLspBufferHandle},
     project_settings::{GitGutterSetting, ProjectSettings},
-    CodeAction, Completion, CompletionIntent, DocumentHighlight, InlayHint, Location, LocationLink,
-    LspStore, Project, ProjectItem, ProjectTransaction, TaskSourceKind,
+    CodeAction, Completion, CompletionIntent, DocumentHighlight, DocumentLink, InlayHint, Location,
+    LocationLink, LspStore, Project, ProjectItem, ProjectTransaction, TaskSourceKind,
 };
 use rand::prelude::*;
 use rpc::{proto::*, ErrorExt};
@@ -139,6 +148,7 @@ use scroll::{Autoscroll, OngoingScroll, ScrollAnchor, ScrollManager, ScrollbarAu
 use selections_collection::{
     resolve_selections, MutableSelectionsCollection, SelectionsCollection,
 };
+use semantic_tokens::{clear_removed_semantic_tokens, refresh_semantic_tokens};
 use serde::{Deserialize, Serialize};
 use settings::{update_settings_file, Settings, SettingsLocation, SettingsStore};
 use smallvec::SmallVec;
@@ -331,6 +341,7 @@ pub fn init(cx: &mut AppContext) {
         }
     });
     git::project_diff::init(cx);
+    git::project_conflicts::init(cx);
 }
 
 pub struct SearchWithinRange;
@@ -657,6 +668,9 @@ pub struct Editor {
     show_inline_completions_override: Option<bool>,
     inlay_hint_cache: InlayHintCache,
     diff_map: DiffMap,
+    /// The git revision the buffers are compared with, or `None` when they're compared with the
+    /// index.
+    diff_base_revision: Option<String>,
     next_inlay_id: usize,
     _subscriptions: Vec<Subscription>,
     pixel_position_of_newest_cursor: Option<gpui::Point<Pixels>>,
@@ -686,6 +700,11 @@ pub struct Editor {
     expect_bounds_change: Option<Bounds<Pixels>>,
     tasks: BTreeMap<(BufferId, BufferRow), RunnableTasks>,
     tasks_update_task: Option<Task<()>>,
+    semantic_tokens_tasks: HashMap<BufferId, Task<()>>,
+    code_lens: CodeLensState,
+    folding_ranges_tasks: HashMap<BufferId, Task<()>>,
+    merge_conflicts: HashMap<BufferId, Vec<MergeConflict>>,
+    merge_conflicts_task: Option<Task<()>>,
     previous_search_ranges: Option<Arc<[Range<Anchor>]>>,
     breadcrumb_header: Option<String>,
     focused_block: Option<FocusedBlock>,
@@ -1155,6 +1174,14 @@ impl Editor {
                 project_subscriptions.push(cx.subscribe(project, |editor, _, event, cx| {
                     if let project::Event::RefreshInlayHints = event {
                         editor.refresh_inlay_hints(InlayHintRefreshReason::RefreshRequested, cx);
+                    } else if let project::Event::RefreshSemanticTokens = event {
+                        refresh_semantic_tokens(editor, None, false, cx);
+                    } else if let project::Event::RefreshCodeLens = event {
+                        refresh_code_lens(editor, None, false, cx);
+                    } else if let project::Event::LanguageServerAdded(..) = event {
+                        refresh_folding_ranges(editor, None, true, cx);
+                    } else if let project::Event::WorktreeUpdatedGitRepositories(_) = event {
+                        refresh_merge_conflicts(editor, true, cx);
                     } else if let project::Event::SnippetEdit(id, snippet_edits) = event {
                         if let Some(buffer) = editor.buffer.read(cx).buffer(*id) {
                             let focus_handle = editor.focus_handle(cx);
@@ -1171,6 +1198,10 @@ impl Editor {
                         }
                     }
                 }));
+                let dap_store = project.read(cx).dap_store().clone();
+                project_subscriptions.push(cx.subscribe(&dap_store, |editor, _, event, cx| {
+                    editor.on_dap_store_event(event, cx);
+                }));
                 if let Some(task_inventory) = project
                     .read(cx)
                     .task_store()
@@ -1205,8 +1236,10 @@ impl Editor {
 
         let mut code_action_providers = Vec::new();
         if let Some(project) = project.clone() {
-            get_unstaged_changes_for_buffers(&project, buffer.read(cx).all_buffers(), cx);
+            get_change_sets_for_buffers(&project, buffer.read(cx).all_buffers(), None, cx)
+                .detach_and_log_err(cx);
             code_action_providers.push(Rc::new(project) as Rc<_>);
+            code_action_providers.push(Rc::new(MergeConflictCodeActions) as Rc<_>);
         }
 
         let mut this = Self {
@@ -1289,6 +1322,7 @@ impl Editor {
             active_inline_completion: None,
             inlay_hint_cache: InlayHintCache::new(inlay_hint_settings),
             diff_map: DiffMap::default(),
+            diff_base_revision: None,
             gutter_hovered: false,
             pixel_position_of_newest_cursor: None,
             last_bounds: None,
@@ -1332,6 +1366,11 @@ impl Editor {
                 }),
             ],
             tasks_update_task: None,
+            semantic_tokens_tasks: HashMap::default(),
+            code_lens: CodeLensState::default(),
+            folding_ranges_tasks: HashMap::default(),
+            merge_conflicts: HashMap::default(),
+            merge_conflicts_task: None,
             linked_edit_ranges: Default::default(),
             previous_search_ranges: None,
             breadcrumb_header: None,
@@ -1345,6 +1384,9 @@ impl Editor {
         };
         this.tasks_update_task = Some(this.refresh_runnables(cx));
         this._subscriptions.extend(project_subscriptions);
+        if this.mode == EditorMode::Full {
+            this.refresh_debug_location(cx);
+        }
 
         this.end_selection(cx);
         this.scroll_manager.show_scrollbar(cx);
@@ -1368,6 +1410,10 @@ impl Editor {
                         .insert(buffer.read(cx).remote_id(), handle);
                 }
             }
+            refresh_semantic_tokens(&mut this, None, false, cx);
+            refresh_code_lens(&mut this, None, false, cx);
+            refresh_folding_ranges(&mut this, None, false, cx);
+            refresh_merge_conflicts(&mut this, false, cx);
         }
 
         this.report_editor_event("Editor Opened", None, cx);
@@ -12263,6 +12309,10 @@ impl Editor {
                     (telemetry, is_via_ssh)
                 };
                 refresh_linked_ranges(self, cx);
+                refresh_semantic_tokens(self, buffer_edited.clone(), true, cx);
+                refresh_code_lens(self, buffer_edited.clone(), true, cx);
+                refresh_folding_ranges(self, buffer_edited.clone(), true, cx);
+                refresh_merge_conflicts(self, true, cx);
                 telemetry.log_edit_event("editor", is_via_ssh);
             }
             multi_buffer::Event::ExcerptsAdded {
@@ -12274,7 +12324,13 @@ impl Editor {
                 let buffer_id = buffer.read(cx).remote_id();
                 if !self.diff_map.diff_bases.contains_key(&buffer_id) {
                     if let Some(project) = &self.project {
-                        get_unstaged_changes_for_buffers(project, [buffer.clone()], cx);
+                        get_change_sets_for_buffers(
+                            project,
+                            [buffer.clone()],
+                            self.diff_base_revision.clone(),
+                            cx,
+                        )
+                        .detach_and_log_err(cx);
                     }
                 }
                 cx.emit(EditorEvent::ExcerptsAdded {
@@ -12283,9 +12339,17 @@ impl Editor {
                     excerpts: excerpts.clone(),
                 });
                 self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                refresh_semantic_tokens(self, Some(buffer.clone()), false, cx);
+                refresh_code_lens(self, Some(buffer.clone()), false, cx);
+                refresh_folding_ranges(self, Some(buffer.clone()), false, cx);
+                refresh_merge_conflicts(self, false, cx);
             }
             multi_buffer::Event::ExcerptsRemoved { ids } => {
                 self.refresh_inlay_hints(InlayHintRefreshReason::ExcerptsRemoved(ids.clone()), cx);
+                clear_removed_semantic_tokens(self, cx);
+                update_code_lens_blocks(self, cx);
+                clear_removed_folding_ranges(self, cx);
+                refresh_merge_conflicts(self, false, cx);
                 let buffer = self.buffer.read(cx);
                 self.registered_buffers
                     .retain(|buffer_id, _| buffer.buffer(*buffer_id).is_some());
@@ -12299,11 +12363,19 @@ impl Editor {
             }
             multi_buffer::Event::Reparsed(buffer_id) => {
                 self.tasks_update_task = Some(self.refresh_runnables(cx));
+                if let Some(buffer) = self.buffer.read(cx).buffer(*buffer_id) {
+                    refresh_folding_ranges(self, Some(buffer), true, cx);
+                }
 
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
             }
             multi_buffer::Event::LanguageChanged(buffer_id) => {
                 linked_editing_ranges::refresh_linked_ranges(self, cx);
+                if let Some(buffer) = self.buffer.read(cx).buffer(*buffer_id) {
+                    refresh_semantic_tokens(self, Some(buffer.clone()), false, cx);
+                    refresh_code_lens(self, Some(buffer.clone()), false, cx);
+                    refresh_folding_ranges(self, Some(buffer), false, cx);
+                }
                 cx.emit(EditorEvent::Reparsed(*buffer_id));
                 cx.notify();
             }
@@ -12334,6 +12406,8 @@ impl Editor {
     fn settings_changed(&mut self, cx: &mut ViewContext<Self>) {
         self.tasks_update_task = Some(self.refresh_runnables(cx));
         self.refresh_inline_completion(true, false, cx);
+        refresh_semantic_tokens(self, None, false, cx);
+        refresh_code_lens(self, None, false, cx);
         self.refresh_inlay_hints(
             InlayHintRefreshReason::SettingsChange(inlay_hint_settings(
                 self.selections.newest_anchor().head(),
@@ -12975,29 +13049,50 @@ impl Editor {
     }
 }
 
-fn get_unstaged_changes_for_buffers(
+/// Loads the change sets comparing the buffers with the index, or with the given git revision.
+/// Once loaded, a change set is only shown if the editor still compares with its base and doesn't
+/// already show a change set with the same base, e.g. one added with [`Editor::add_change_set`].
+fn get_change_sets_for_buffers(
     project: &Model<Project>,
     buffers: impl IntoIterator<Item = Model<Buffer>>,
+    revision: Option<String>,
     cx: &mut ViewContext<Editor>,
-) {
+) -> Task<Result<()>> {
     let mut tasks = Vec::new();
     project.update(cx, |project, cx| {
         for buffer in buffers {
-            tasks.push(project.open_unstaged_changes(buffer.clone(), cx))
+            tasks.push(match &revision {
+                Some(revision) => {
+                    project.open_changes_since_revision(buffer.clone(), revision.clone(), cx)
+                }
+                None => project.open_unstaged_changes(buffer.clone(), cx),
+            })
         }
     });
     cx.spawn(|this, mut cx| async move {
         let change_sets = futures::future::join_all(tasks).await;
         this.update(&mut cx, |this, cx| {
+            let mut result = Ok(());
             for change_set in change_sets {
-                if let Some(change_set) = change_set.log_err() {
-                    this.diff_map.add_change_set(change_set, cx);
+                match change_set {
+                    Ok(change_set) => {
+                        let base_revision = &change_set.read(cx).base_revision;
+                        if *base_revision == this.diff_base_revision
+                            && !this.diff_map.has_base_revision(
+                                change_set.read(cx).buffer_id,
+                                base_revision,
+                                cx,
+                            )
+                        {
+                            this.diff_map.add_change_set(change_set, cx);
+                        }
+                    }
+                    Err(error) => result = Err(error),
                 }
             }
-        })
-        .ok();
+            result
+        })?
     })
-    .detach();
 }
 
 fn char_len_with_expanded_tabs(offset: usize, text: &str, tab_size: NonZeroU32) -> usize {
@@ -13403,6 +13498,19 @@ pub trait SemanticsProvider {
         cx: &mut AppContext,
     ) -> Option<Task<Result<Vec<LocationLink>>>>;
 
+    fn document_links(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<Vec<DocumentLink>>>>;
+
+    fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer: Model<Buffer>,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<DocumentLink>>>;
+
     fn range_for_rename(
         &self,
         buffer: &Model<Buffer>,
@@ -13765,6 +13873,25 @@ impl SemanticsProvider for Model<Project> {
         }))
     }
 
+    fn document_links(
+        &self,
+        buffer: &Model<Buffer>,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<Vec<DocumentLink>>>> {
+        Some(self.update(cx, |project, cx| project.document_links(buffer, cx)))
+    }
+
+    fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer: Model<Buffer>,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<DocumentLink>>> {
+        Some(self.update(cx, |project, cx| {
+            project.resolve_document_link(link, buffer, cx)
+        }))
+    }
+
     fn supports_inlay_hints(&self, buffer: &Model<Buffer>, cx: &AppContext) -> bool {
         // TODO: make this work for remote projects
         self.read(cx)
@@ -14029,7 +14156,7 @@ impl EditorSnapshot {
             }
         }
 
-        is_foldable |= self.starts_indent(buffer_row);
+        is_foldable |= self.folds_by_indent(buffer_row) && self.starts_indent(buffer_row);
 
         if folded || (is_foldable && (row_contains_cursor || self.gutter_hovered)) {
             Some(
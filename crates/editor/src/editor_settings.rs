@@ -1,3 +1,4 @@
+use collections::HashMap;
 use gpui::AppContext;
 use language::CursorShape;
 use schemars::JsonSchema;
@@ -14,6 +15,8 @@ pub struct EditorSettings {
     pub hover_popover_delay: u64,
     pub toolbar: Toolbar,
     pub scrollbar: Scrollbar,
+    pub minimap: Minimap,
+    pub sticky_scroll: StickyScroll,
     pub gutter: Gutter,
     pub scroll_beyond_last_line: ScrollBeyondLastLine,
     pub vertical_scroll_margin: f32,
@@ -36,6 +39,8 @@ pub struct EditorSettings {
     pub show_signature_help_after_edits: bool,
     pub jupyter: Jupyter,
     pub show_inline_completions_in_menu: bool,
+    pub semantic_tokens: SemanticTokens,
+    pub code_lens: bool,
 }
 
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
@@ -92,6 +97,14 @@ pub struct JupyterContent {
     pub enabled: Option<bool>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct SemanticTokens {
+    pub enabled: bool,
+    pub edit_debounce_ms: u64,
+    pub token_types: HashMap<String, String>,
+    pub token_modifiers: HashMap<String, String>,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Toolbar {
     pub breadcrumbs: bool,
@@ -110,6 +123,20 @@ pub struct Scrollbar {
     pub axes: ScrollbarAxes,
 }
 
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct Minimap {
+    pub show: ShowMinimap,
+    pub max_width_columns: u32,
+    pub search_results: bool,
+    pub diagnostics: bool,
+}
+
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StickyScroll {
+    pub enabled: bool,
+    pub max_depth: usize,
+}
+
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 pub struct Gutter {
     pub line_numbers: bool,
@@ -135,6 +162,20 @@ pub enum ShowScrollbar {
     Never,
 }
 
+/// When to show the minimap in the editor.
+///
+/// Default: never
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ShowMinimap {
+    /// Show the minimap when the document does not fit in the editor.
+    Auto,
+    /// Always show the minimap.
+    Always,
+    /// Never show the minimap.
+    Never,
+}
+
 /// Forcefully enable or disable the scrollbar for each axis
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
@@ -223,6 +264,10 @@ pub struct EditorSettingsContent {
     pub toolbar: Option<ToolbarContent>,
     /// Scrollbar related settings
     pub scrollbar: Option<ScrollbarContent>,
+    /// Minimap related settings
+    pub minimap: Option<MinimapContent>,
+    /// Sticky scroll related settings
+    pub sticky_scroll: Option<StickyScrollContent>,
     /// Gutter related settings
     pub gutter: Option<GutterContent>,
     /// Whether the editor will scroll beyond the last line.
@@ -309,6 +354,33 @@ pub struct EditorSettingsContent {
 
     /// Jupyter REPL settings.
     pub jupyter: Option<JupyterContent>,
+
+    /// Semantic token highlighting settings.
+    pub semantic_tokens: Option<SemanticTokensContent>,
+
+    /// Whether to show the code lenses provided by language servers above their lines.
+    ///
+    /// Default: true
+    pub code_lens: Option<bool>,
+}
+
+/// Semantic token highlighting related settings
+#[derive(Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct SemanticTokensContent {
+    /// Whether to request semantic tokens from language servers and layer them
+    /// over the tree-sitter highlighting.
+    ///
+    /// Default: true
+    pub enabled: Option<bool>,
+    /// Time to wait after editing the buffer, before requesting the tokens.
+    ///
+    /// Default: 150
+    pub edit_debounce_ms: Option<u64>,
+    /// The theme syntax style to use for each semantic token type.
+    /// Tokens whose type has no style keep their tree-sitter highlighting.
+    pub token_types: Option<HashMap<String, String>>,
+    /// The theme syntax style to layer over a token for each semantic token modifier.
+    pub token_modifiers: Option<HashMap<String, String>>,
 }
 
 // Toolbar related settings
@@ -360,6 +432,41 @@ pub struct ScrollbarContent {
     pub axes: Option<ScrollbarAxesContent>,
 }
 
+/// Minimap related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct MinimapContent {
+    /// When to show the minimap in the editor.
+    ///
+    /// Default: never
+    pub show: Option<ShowMinimap>,
+    /// The maximum number of columns of the document shown in the minimap.
+    ///
+    /// Default: 80
+    pub max_width_columns: Option<u32>,
+    /// Whether to show buffer search results in the minimap.
+    ///
+    /// Default: true
+    pub search_results: Option<bool>,
+    /// Whether to show diagnostics in the minimap.
+    ///
+    /// Default: true
+    pub diagnostics: Option<bool>,
+}
+
+/// Sticky scroll related settings
+#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize, JsonSchema, PartialEq, Eq)]
+pub struct StickyScrollContent {
+    /// Whether to pin the first lines of the items enclosing the top of the viewport,
+    /// such as functions and impl blocks, at the top of the editor.
+    ///
+    /// Default: false
+    pub enabled: Option<bool>,
+    /// The maximum number of enclosing items to pin.
+    ///
+    /// Default: 5
+    pub max_depth: Option<usize>,
+}
+
 /// Forcefully enable or disable the scrollbar for each axis
 #[derive(Copy, Clone, Debug, Serialize, Deserialize, JsonSchema, PartialEq)]
 pub struct ScrollbarAxesContent {
@@ -12563,6 +12563,191 @@ async fn test_fold_unfold_diff_hunk(executor: BackgroundExecutor, cx: &mut gpui:
     );
 }
 
+#[gpui::test]
+async fn test_stage_and_unstage_hunks(executor: BackgroundExecutor, cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    let committed_text = "one\ntwo\nthree\nfour\nfive\n";
+    cx.set_state("one\nTWO TWO\nthree\nfour\nˇFIVE\nsix\n");
+    cx.set_head_text(committed_text);
+    cx.set_diff_base(committed_text);
+    executor.run_until_parked();
+
+    let index_text = |cx: &mut EditorTestContext| {
+        cx.update_editor(|editor, cx| {
+            let buffer_id = editor
+                .buffer()
+                .read(cx)
+                .as_singleton()
+                .unwrap()
+                .read(cx)
+                .remote_id();
+            editor.diff_map.diff_bases[&buffer_id]
+                .change_set
+                .read(cx)
+                .base_text_string(cx)
+                .unwrap()
+        })
+    };
+    let staged_rows = |cx: &mut EditorTestContext| {
+        cx.update_editor(|editor, cx| {
+            let snapshot = editor.snapshot(cx);
+            snapshot
+                .diff_map
+                .staged_hunks_in_range(0..snapshot.buffer_snapshot.len(), &snapshot.buffer_snapshot)
+                .map(|hunk| hunk.row_range.start.0..hunk.row_range.end.0)
+                .collect::<Vec<_>>()
+        })
+    };
+
+    // Only the selected line of the hunk is staged.
+    cx.update_editor(|editor, cx| editor.stage_selected_lines(&StageSelectedLines, cx));
+    executor.run_until_parked();
+    assert_eq!(index_text(&mut cx), "one\ntwo\nthree\nfour\nFIVE\n");
+    assert_eq!(staged_rows(&mut cx), Vec::<Range<u32>>::new());
+
+    cx.set_selections_state("one\nTWO TWO\nthree\nfour\nFIVE\nsˇix\n");
+    cx.update_editor(|editor, cx| editor.stage_hunk(&StageHunk, cx));
+    executor.run_until_parked();
+    assert_eq!(index_text(&mut cx), "one\ntwo\nthree\nfour\nFIVE\nsix\n");
+    assert_eq!(staged_rows(&mut cx), vec![4..6]);
+
+    // Unstaging accounts for the unstaged hunk above it, which has a different length in the index.
+    cx.update_editor(|editor, cx| editor.unstage_hunk(&UnstageHunk, cx));
+    executor.run_until_parked();
+    assert_eq!(index_text(&mut cx), committed_text);
+    assert_eq!(staged_rows(&mut cx), Vec::<Range<u32>>::new());
+
+    cx.set_selections_state("one\nTWO ˇTWO\nthree\nfour\nFIVE\nsix\n");
+    cx.update_editor(|editor, cx| editor.stage_hunk(&StageHunk, cx));
+    executor.run_until_parked();
+    assert_eq!(index_text(&mut cx), "one\nTWO TWO\nthree\nfour\nfive\n");
+    assert_eq!(staged_rows(&mut cx), vec![1..2]);
+}
+
+#[gpui::test]
+async fn test_merge_conflicts(executor: BackgroundExecutor, cx: &mut gpui::TestAppContext) {
+    init_test(cx, |_| {});
+
+    let mut cx = EditorTestContext::new(cx).await;
+    cx.set_state(indoc! {"
+        ˇone
+        <<<<<<< HEAD
+        two
+        =======
+        deux
+        >>>>>>> french
+        three
+        <<<<<<< HEAD
+        four
+        =======
+        quatre
+        >>>>>>> french
+    "});
+    executor.advance_clock(Duration::from_millis(200));
+    executor.run_until_parked();
+    // Markers are only recognized once git reports the file as unmerged.
+    cx.update_editor(|editor, _| assert!(editor.merge_conflicts.is_empty()));
+
+    cx.set_git_status(::git::repository::GitFileStatus::Conflict);
+    executor.advance_clock(Duration::from_millis(200));
+    executor.run_until_parked();
+    cx.update_editor(|editor, _| {
+        assert_eq!(
+            editor
+                .merge_conflicts
+                .values()
+                .map(|conflicts| conflicts.len())
+                .sum::<usize>(),
+            2
+        );
+    });
+
+    cx.update_editor(|editor, cx| editor.go_to_next_conflict(&GoToConflict, cx));
+    cx.assert_editor_state(indoc! {"
+        one
+        ˇ<<<<<<< HEAD
+        two
+        =======
+        deux
+        >>>>>>> french
+        three
+        <<<<<<< HEAD
+        four
+        =======
+        quatre
+        >>>>>>> french
+    "});
+    cx.update_editor(|editor, cx| editor.go_to_next_conflict(&GoToConflict, cx));
+    cx.update_editor(|editor, cx| editor.go_to_next_conflict(&GoToConflict, cx));
+    cx.update_editor(|editor, cx| editor.go_to_prev_conflict(&GoToPrevConflict, cx));
+    cx.assert_editor_state(indoc! {"
+        one
+        <<<<<<< HEAD
+        two
+        =======
+        deux
+        >>>>>>> french
+        three
+        ˇ<<<<<<< HEAD
+        four
+        =======
+        quatre
+        >>>>>>> french
+    "});
+
+    let (buffer, code_actions) = cx.update_editor(|editor, cx| {
+        let buffer = editor.buffer().read(cx).as_singleton().unwrap();
+        let position = buffer.read(cx).anchor_before(Point::new(9, 0));
+        let code_actions = MergeConflictCodeActions.code_actions(&buffer, position..position, cx);
+        (buffer, code_actions)
+    });
+    let code_actions = code_actions.await.unwrap();
+    assert_eq!(
+        code_actions
+            .iter()
+            .map(|action| action.lsp_action.title.as_str())
+            .collect::<Vec<_>>(),
+        ["Accept Ours", "Accept Theirs", "Accept Both"]
+    );
+
+    cx.update_editor(|_, cx| {
+        MergeConflictCodeActions.apply_code_action(
+            buffer.clone(),
+            code_actions[2].clone(),
+            ExcerptId::min(),
+            true,
+            cx,
+        )
+    })
+    .await
+    .unwrap();
+    executor.advance_clock(Duration::from_millis(200));
+    executor.run_until_parked();
+    cx.assert_editor_state(indoc! {"
+        one
+        <<<<<<< HEAD
+        two
+        =======
+        deux
+        >>>>>>> french
+        three
+        ˇfour
+        quatre
+    "});
+    cx.update_editor(|editor, _| {
+        assert_eq!(
+            editor
+                .merge_conflicts
+                .values()
+                .map(|conflicts| conflicts.len())
+                .sum::<usize>(),
+            1
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_toggle_diff_expand_in_multi_buffer(cx: &mut gpui::TestAppContext) {
     init_test(cx, |_| {});
@@ -6,7 +6,7 @@ use crate::{
     },
     editor_settings::{
         CurrentLineHighlight, DoubleClickInMultibuffer, MultiCursorModifier, ScrollBeyondLastLine,
-        ShowScrollbar,
+        ShowMinimap, ShowScrollbar,
     },
     git::blame::{CommitDetails, GitBlame},
     hover_popover::{
@@ -16,7 +16,7 @@ use crate::{
     hunk_status,
     items::BufferSearchHighlights,
     mouse_context_menu::{self, MenuPosition, MouseContextMenu},
-    scroll::{axis_pair, scroll_amount::ScrollAmount, AxisPair},
+    scroll::{axis_pair, scroll_amount::ScrollAmount, Autoscroll, AxisPair},
     BlockId, ChunkReplacement, CursorShape, CustomBlockId, DisplayPoint, DisplayRow,
     DocumentHighlightRead, DocumentHighlightWrite, Editor, EditorMode, EditorSettings,
     EditorSnapshot, EditorStyle, ExpandExcerpts, FocusedBlock, GutterDimensions, HalfPageDown,
@@ -65,6 +65,7 @@ use std::{
     fmt::{self, Write},
     iter, mem,
     ops::{Deref, Range},
+    path::PathBuf,
     rc::Rc,
     sync::Arc,
 };
@@ -315,6 +316,8 @@ impl EditorElement {
         register_action(view, cx, Editor::go_to_prev_diagnostic);
         register_action(view, cx, Editor::go_to_next_hunk);
         register_action(view, cx, Editor::go_to_prev_hunk);
+        register_action(view, cx, Editor::go_to_next_conflict);
+        register_action(view, cx, Editor::go_to_prev_conflict);
         register_action(view, cx, |editor, a, cx| {
             editor.go_to_definition(a, cx).detach_and_log_err(cx);
         });
@@ -363,6 +366,7 @@ impl EditorElement {
         register_action(view, cx, Editor::open_excerpts);
         register_action(view, cx, Editor::open_excerpts_in_split);
         register_action(view, cx, Editor::open_proposed_changes_editor);
+        register_action(view, cx, Editor::open_split_diff);
         register_action(view, cx, Editor::toggle_soft_wrap);
         register_action(view, cx, Editor::toggle_tab_bar);
         register_action(view, cx, Editor::toggle_line_numbers);
@@ -381,6 +385,7 @@ impl EditorElement {
         register_action(view, cx, Editor::toggle_git_blame);
         register_action(view, cx, Editor::toggle_git_blame_inline);
         register_action(view, cx, Editor::toggle_hunk_diff);
+        register_action(view, cx, Editor::toggle_breakpoint);
         register_action(view, cx, Editor::expand_all_hunk_diffs);
         register_action(view, cx, |editor, action, cx| {
             if let Some(task) = editor.format(action, cx) {
@@ -458,6 +463,9 @@ impl EditorElement {
         register_action(view, cx, Editor::revert_selected_hunks);
         register_action(view, cx, Editor::apply_all_diff_hunks);
         register_action(view, cx, Editor::apply_selected_diff_hunks);
+        register_action(view, cx, Editor::stage_hunk);
+        register_action(view, cx, Editor::unstage_hunk);
+        register_action(view, cx, Editor::stage_selected_lines);
         register_action(view, cx, Editor::open_active_item_in_terminal);
         register_action(view, cx, Editor::reload_file);
         register_action(view, cx, Editor::spawn_nearest_task);
@@ -1176,6 +1184,322 @@ impl EditorElement {
         cursor_layouts
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_sticky_headers(
+        &self,
+        snapshot: &EditorSnapshot,
+        start_row: DisplayRow,
+        gutter_hitbox: &Hitbox,
+        text_hitbox: &Hitbox,
+        content_origin: gpui::Point<Pixels>,
+        scroll_pixel_position: gpui::Point<Pixels>,
+        line_height: Pixels,
+        editor_width: Pixels,
+        cx: &mut WindowContext,
+    ) -> Option<StickyHeadersLayout> {
+        let sticky_scroll = EditorSettings::get_global(cx).sticky_scroll;
+        if !sticky_scroll.enabled || snapshot.mode != EditorMode::Full {
+            return None;
+        }
+
+        // Each pinned line hides the row below it, so keep pinning the items enclosing the first
+        // row that is still visible, until no more of them have scrolled out of view.
+        let mut pinned_count = 0;
+        let mut items;
+        loop {
+            items = Self::enclosing_item_starts(snapshot, start_row + pinned_count);
+            items.truncate(sticky_scroll.max_depth);
+            if items.len() <= pinned_count as usize {
+                break;
+            }
+            pinned_count = items.len() as u32;
+        }
+        if items.is_empty() {
+            return None;
+        }
+
+        let lines = items
+            .into_iter()
+            .enumerate()
+            .map(|(ix, (row, target))| {
+                let top = text_hitbox.top() + ix as f32 * line_height;
+                let hitbox = cx.insert_hitbox(
+                    Bounds::from_corners(
+                        point(gutter_hitbox.left(), top),
+                        point(text_hitbox.right(), top + line_height),
+                    ),
+                    false,
+                );
+                StickyHeaderLine {
+                    line: layout_line(row, snapshot, &self.style, editor_width, |_| false, cx),
+                    origin: point(content_origin.x - scroll_pixel_position.x, top),
+                    hitbox,
+                    target,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        Some(StickyHeadersLayout {
+            bounds: Bounds::from_corners(
+                gutter_hitbox.origin,
+                point(
+                    text_hitbox.right(),
+                    text_hitbox.top() + lines.len() as f32 * line_height,
+                ),
+            ),
+            lines,
+        })
+    }
+
+    /// Returns the first row and the start of each outline item enclosing the given row that
+    /// starts above it, from the outermost to the innermost.
+    fn enclosing_item_starts(
+        snapshot: &EditorSnapshot,
+        row: DisplayRow,
+    ) -> Vec<(DisplayRow, Anchor)> {
+        if row > snapshot.max_point().row() {
+            return Vec::new();
+        }
+        let position = DisplayPoint::new(row, 0).to_point(snapshot);
+        let Some((_, outline_items)) = snapshot.buffer_snapshot.symbols_containing(position, None)
+        else {
+            return Vec::new();
+        };
+
+        let mut item_starts = Vec::<(DisplayRow, Anchor)>::new();
+        for item in outline_items {
+            let item_row = item.range.start.to_display_point(snapshot).row();
+            if item_row >= row {
+                break;
+            }
+            if item_starts
+                .last()
+                .map_or(true, |(last_row, _)| *last_row < item_row)
+            {
+                item_starts.push((item_row, item.range.start));
+            }
+        }
+        item_starts
+    }
+
+    fn minimap_width(
+        &self,
+        snapshot: &EditorSnapshot,
+        text_width: Pixels,
+        height_in_lines: f32,
+        line_height: Pixels,
+        em_advance: Pixels,
+        cx: &WindowContext,
+    ) -> Option<Pixels> {
+        if snapshot.mode != EditorMode::Full {
+            return None;
+        }
+
+        let minimap_settings = EditorSettings::get_global(cx).minimap;
+        let show_minimap = match minimap_settings.show {
+            ShowMinimap::Auto => snapshot.max_point().row().next_row().as_f32() > height_in_lines,
+            ShowMinimap::Always => true,
+            ShowMinimap::Never => false,
+        };
+        if !show_minimap {
+            return None;
+        }
+
+        let minimap_width = MinimapLayout::column_width(line_height, em_advance)
+            * minimap_settings.max_width_columns as f32;
+        // Don't let the minimap take over narrow editors.
+        (minimap_width + self.style.scrollbar_width <= text_width / 3.).then_some(minimap_width)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn layout_minimap(
+        &self,
+        snapshot: &EditorSnapshot,
+        bounds: Bounds<Pixels>,
+        minimap_width: Pixels,
+        line_height: Pixels,
+        em_advance: Pixels,
+        height_in_lines: f32,
+        scroll_position: gpui::Point<f32>,
+        max_scroll_top: f32,
+        cx: &mut WindowContext,
+    ) -> MinimapLayout {
+        let minimap_settings = EditorSettings::get_global(cx).minimap;
+        let row_height = MinimapLayout::ROW_HEIGHT;
+        let column_width = MinimapLayout::column_width(line_height, em_advance);
+        let minimap_right = self.scrollbar_left(&bounds);
+        let minimap_bounds = Bounds::from_corners(
+            point(minimap_right - minimap_width, bounds.top()),
+            point(minimap_right, bounds.bottom()),
+        );
+        let hitbox = cx.insert_hitbox(minimap_bounds, false);
+
+        // The minimap scrolls along with the editor, so that its viewport slice goes from the top
+        // of the minimap to the bottom while the editor scrolls from the first row to the last.
+        let max_row = snapshot.max_point().row();
+        let row_count = max_row.next_row().as_f32();
+        let track_height = minimap_bounds.size.height.min(row_count * row_height);
+        let thumb_height = (height_in_lines * row_height).min(track_height);
+        let thumb_top = if max_scroll_top > 0. {
+            (scroll_position.y / max_scroll_top).min(1.) * (track_height - thumb_height)
+        } else {
+            Pixels::ZERO
+        };
+        let first_row = (scroll_position.y - thumb_top / row_height).max(0.);
+        let thumb_bounds = Bounds {
+            origin: point(minimap_bounds.left(), minimap_bounds.top() + thumb_top),
+            size: size(minimap_width, thumb_height),
+        };
+
+        let start_row = DisplayRow(first_row as u32);
+        let end_row = DisplayRow(
+            ((first_row + minimap_bounds.size.height / row_height).ceil() as u32)
+                .min(max_row.next_row().0),
+        );
+        let max_columns = minimap_settings.max_width_columns;
+        let column_bounds = |row: DisplayRow, columns: Range<u32>| {
+            if columns.start >= max_columns {
+                return None;
+            }
+            let top = minimap_bounds.top() + (row.as_f32() - first_row) * row_height;
+            Some(Bounds::from_corners(
+                point(
+                    minimap_bounds.left() + columns.start as f32 * column_width,
+                    top,
+                ),
+                point(
+                    minimap_bounds.left() + columns.end.min(max_columns) as f32 * column_width,
+                    top + row_height,
+                ),
+            ))
+        };
+
+        let display_snapshot = &snapshot.display_snapshot;
+        let buffer_snapshot = &display_snapshot.buffer_snapshot;
+        let start_anchor = if start_row == Default::default() {
+            Anchor::min()
+        } else {
+            buffer_snapshot.anchor_before(
+                DisplayPoint::new(start_row, 0).to_offset(display_snapshot, Bias::Left),
+            )
+        };
+        let end_anchor = if end_row > max_row {
+            Anchor::max()
+        } else {
+            buffer_snapshot.anchor_before(
+                DisplayPoint::new(end_row, 0).to_offset(display_snapshot, Bias::Right),
+            )
+        };
+
+        let mut highlighted_ranges = Vec::new();
+        if minimap_settings.search_results {
+            let editor = self.editor.read(cx);
+            if let Some((color_fetcher, ranges)) = editor
+                .background_highlights
+                .get(&TypeId::of::<BufferSearchHighlights>())
+            {
+                let color = color_fetcher(cx.theme().colors());
+                let start_ix = ranges
+                    .partition_point(|range| range.end.cmp(&start_anchor, buffer_snapshot).is_lt());
+                for range in &ranges[start_ix..] {
+                    if range.start.cmp(&end_anchor, buffer_snapshot).is_ge() {
+                        break;
+                    }
+                    let start = range.start.to_display_point(display_snapshot);
+                    let end = range.end.to_display_point(display_snapshot);
+                    highlighted_ranges.push((start..end, color));
+                }
+            }
+        }
+        if minimap_settings.diagnostics {
+            let status_colors = cx.theme().status();
+            let diagnostics = buffer_snapshot
+                .diagnostics_in_range::<_, Point>(start_anchor..end_anchor, false)
+                // Paint the most severe diagnostics last.
+                .sorted_by_key(|diagnostic| std::cmp::Reverse(diagnostic.diagnostic.severity));
+            for diagnostic in diagnostics {
+                let color = match diagnostic.diagnostic.severity {
+                    DiagnosticSeverity::ERROR => status_colors.error,
+                    DiagnosticSeverity::WARNING => status_colors.warning,
+                    DiagnosticSeverity::INFORMATION => status_colors.info,
+                    _ => status_colors.hint,
+                };
+                let start = diagnostic.range.start.to_display_point(display_snapshot);
+                let end = diagnostic.range.end.to_display_point(display_snapshot);
+                highlighted_ranges.push((start..end, color));
+            }
+        }
+
+        let mut quads = Vec::new();
+        for (range, color) in highlighted_ranges {
+            for row in range.start.row().0.max(start_row.0)..=range.end.row().0 {
+                let row = DisplayRow(row);
+                if row >= end_row {
+                    break;
+                }
+                let start_column = if row == range.start.row() {
+                    range.start.column()
+                } else {
+                    0
+                };
+                let end_column = if row == range.end.row() {
+                    range.end.column()
+                } else {
+                    display_snapshot.line_len(row)
+                };
+                if let Some(bounds) =
+                    column_bounds(row, start_column..end_column.max(start_column + 1))
+                {
+                    quads.push(fill(bounds, color));
+                }
+            }
+        }
+
+        // Draw each run of non-whitespace characters sharing a color as a single quad.
+        let mut runs = Vec::<(DisplayRow, Range<u32>, Hsla)>::new();
+        let mut row = start_row;
+        let mut column = 0;
+        for chunk in display_snapshot.highlighted_chunks(start_row..end_row, true, &self.style) {
+            let color = chunk
+                .style
+                .and_then(|style| style.color)
+                .unwrap_or(self.style.text.color);
+            for character in chunk.text.chars() {
+                if character == '\n' {
+                    row.0 += 1;
+                    column = 0;
+                    continue;
+                }
+                if !character.is_whitespace() && column < max_columns {
+                    match runs.last_mut() {
+                        Some((run_row, run_columns, run_color))
+                            if *run_row == row
+                                && run_columns.end == column
+                                && *run_color == color =>
+                        {
+                            run_columns.end += 1;
+                        }
+                        _ => runs.push((row, column..column + 1, color)),
+                    }
+                }
+                column += 1;
+            }
+        }
+        quads.extend(
+            runs.into_iter().filter_map(|(row, columns, color)| {
+                Some(fill(column_bounds(row, columns)?, color))
+            }),
+        );
+
+        MinimapLayout {
+            hitbox,
+            thumb_bounds,
+            track_height,
+            max_scroll_top,
+            quads,
+        }
+    }
+
     fn layout_scrollbars(
         &self,
         snapshot: &EditorSnapshot,
@@ -1528,6 +1852,45 @@ impl EditorElement {
         })
     }
 
+    /// Lays out the hunks whose changes are entirely staged, which are painted as hollow strips
+    /// next to the unstaged ones.
+    fn layout_gutter_staged_hunks(
+        &self,
+        line_height: Pixels,
+        gutter_hitbox: &Hitbox,
+        display_rows: Range<DisplayRow>,
+        snapshot: &EditorSnapshot,
+        cx: &mut WindowContext,
+    ) -> Vec<(DiffHunkStatus, Bounds<Pixels>)> {
+        let git_gutter_setting = ProjectSettings::get_global(cx)
+            .git
+            .git_gutter
+            .unwrap_or_default();
+        if !matches!(git_gutter_setting, GitGutterSetting::TrackedFiles) {
+            return Vec::new();
+        }
+
+        let buffer_start = DisplayPoint::new(display_rows.start, 0).to_point(snapshot);
+        let buffer_end = DisplayPoint::new(display_rows.end, 0).to_point(snapshot);
+        snapshot
+            .diff_map
+            .staged_hunks_in_range(buffer_start..buffer_end, &snapshot.buffer_snapshot)
+            .filter_map(|hunk| {
+                let display_hunk = diff_hunk_to_display(&hunk, snapshot);
+                let DisplayDiffHunk::Unfolded { status, .. } = &display_hunk else {
+                    return None;
+                };
+                let bounds = Self::diff_hunk_bounds(
+                    snapshot,
+                    line_height,
+                    gutter_hitbox.bounds,
+                    &display_hunk,
+                );
+                Some((*status, bounds))
+            })
+            .collect()
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_inline_blame(
         &self,
@@ -1791,6 +2154,7 @@ impl EditorElement {
         gutter_dimensions: &GutterDimensions,
         gutter_hitbox: &Hitbox,
         rows_with_hunk_bounds: &HashMap<DisplayRow, Bounds<Pixels>>,
+        breakpoint_rows: &[(DisplayRow, Anchor)],
         snapshot: &EditorSnapshot,
         cx: &mut WindowContext,
     ) -> Vec<AnyElement> {
@@ -1850,6 +2214,9 @@ impl EditorElement {
                         }
                     }
                     let display_row = multibuffer_point.to_display_point(snapshot).row();
+                    if breakpoint_rows.iter().any(|(row, _)| *row == display_row) {
+                        return None;
+                    }
                     let button = editor.render_run_indicator(
                         &self.style,
                         Some(display_row) == active_task_indicator_row,
@@ -1873,6 +2240,37 @@ impl EditorElement {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn layout_breakpoints(
+        &self,
+        line_height: Pixels,
+        breakpoint_rows: &[(DisplayRow, Anchor)],
+        scroll_pixel_position: gpui::Point<Pixels>,
+        gutter_dimensions: &GutterDimensions,
+        gutter_hitbox: &Hitbox,
+        rows_with_hunk_bounds: &HashMap<DisplayRow, Bounds<Pixels>>,
+        cx: &mut WindowContext,
+    ) -> Vec<AnyElement> {
+        self.editor.update(cx, |editor, cx| {
+            breakpoint_rows
+                .iter()
+                .map(|(display_row, position)| {
+                    let button = editor.render_breakpoint_indicator(*display_row, *position, cx);
+                    prepaint_gutter_button(
+                        button,
+                        *display_row,
+                        line_height,
+                        gutter_dimensions,
+                        scroll_pixel_position,
+                        gutter_hitbox,
+                        rows_with_hunk_bounds,
+                        cx,
+                    )
+                })
+                .collect()
+        })
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn layout_code_actions_indicator(
         &self,
@@ -3781,12 +4179,31 @@ impl EditorElement {
     }
 
     fn paint_diff_hunks(layout: &mut EditorLayout, cx: &mut WindowContext) {
-        if layout.display_hunks.is_empty() {
+        if layout.display_hunks.is_empty() && layout.staged_hunk_bounds.is_empty() {
             return;
         }
 
         let line_height = layout.position_map.line_height;
         cx.paint_layer(layout.gutter_hitbox.bounds, |cx| {
+            for (status, bounds) in &layout.staged_hunk_bounds {
+                let (color, corner_radii) = match status {
+                    DiffHunkStatus::Added => (cx.theme().status().created, Corners::all(px(0.))),
+                    DiffHunkStatus::Modified => {
+                        (cx.theme().status().modified, Corners::all(px(0.)))
+                    }
+                    DiffHunkStatus::Removed => {
+                        (cx.theme().status().deleted, Corners::all(1. * line_height))
+                    }
+                };
+                cx.paint_quad(quad(
+                    *bounds,
+                    corner_radii,
+                    transparent_black(),
+                    Edges::all(px(1.)),
+                    color,
+                ));
+            }
+
             for (hunk, hitbox) in &layout.display_hunks {
                 let hunk_to_paint = match hunk {
                     DisplayDiffHunk::Folded { .. } => {
@@ -3928,6 +4345,10 @@ impl EditorElement {
                 test_indicator.paint(cx);
             }
 
+            for breakpoint in layout.breakpoints.iter_mut() {
+                breakpoint.paint(cx);
+            }
+
             if let Some(indicator) = layout.code_actions_indicator.as_mut() {
                 indicator.paint(cx);
             }
@@ -4435,6 +4856,222 @@ impl EditorElement {
         }
     }
 
+    fn paint_sticky_headers(&self, layout: &EditorLayout, cx: &mut WindowContext) {
+        let Some(sticky_headers) = layout.sticky_headers.as_ref() else {
+            return;
+        };
+        let line_height = layout.position_map.line_height;
+
+        cx.paint_layer(sticky_headers.bounds, |cx| {
+            let gutter_bounds = Bounds::from_corners(
+                sticky_headers.bounds.origin,
+                point(layout.gutter_hitbox.right(), sticky_headers.bounds.bottom()),
+            );
+            cx.paint_quad(fill(
+                gutter_bounds,
+                cx.theme().colors().editor_gutter_background,
+            ));
+            cx.paint_quad(fill(
+                Bounds::from_corners(
+                    gutter_bounds.top_right(),
+                    sticky_headers.bounds.bottom_right(),
+                ),
+                self.style.background,
+            ));
+
+            for line in &sticky_headers.lines {
+                if line.hitbox.is_hovered(cx) {
+                    cx.paint_quad(fill(
+                        line.hitbox.bounds,
+                        cx.theme().colors().editor_active_line_background,
+                    ));
+                }
+                cx.set_cursor_style(CursorStyle::PointingHand, &line.hitbox);
+
+                cx.with_content_mask(
+                    Some(ContentMask {
+                        bounds: layout.text_hitbox.bounds,
+                    }),
+                    |cx| {
+                        let mut fragment_origin = line.origin;
+                        for fragment in &line.line.fragments {
+                            match fragment {
+                                LineFragment::Text(shaped_line) => {
+                                    shaped_line
+                                        .paint(fragment_origin, line_height, cx)
+                                        .log_err();
+                                    fragment_origin.x += shaped_line.width;
+                                }
+                                LineFragment::Element { size, .. } => {
+                                    fragment_origin.x += size.width;
+                                }
+                            }
+                        }
+                    },
+                );
+            }
+
+            cx.paint_quad(fill(
+                Bounds::from_corners(
+                    point(
+                        sticky_headers.bounds.left(),
+                        sticky_headers.bounds.bottom() - px(1.),
+                    ),
+                    sticky_headers.bounds.bottom_right(),
+                ),
+                cx.theme().colors().border_variant,
+            ));
+        });
+
+        for line in &sticky_headers.lines {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                let hitbox = line.hitbox.clone();
+                let target = line.target;
+                move |event: &MouseDownEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture
+                        || event.button != MouseButton::Left
+                        || !hitbox.is_hovered(cx)
+                    {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                            selections.select_anchor_ranges([target..target])
+                        });
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        }
+    }
+
+    fn paint_minimap(&self, layout: &EditorLayout, cx: &mut WindowContext) {
+        let Some(minimap) = layout.minimap.as_ref() else {
+            return;
+        };
+        let hitbox = minimap.hitbox.clone();
+        let thumb_bounds = minimap.thumb_bounds;
+        let is_dragging = self
+            .editor
+            .read(cx)
+            .scroll_manager
+            .minimap_drag_offset()
+            .is_some();
+
+        cx.paint_layer(hitbox.bounds, |cx| {
+            cx.paint_quad(quad(
+                hitbox.bounds,
+                Corners::default(),
+                self.style.background,
+                Edges {
+                    left: MinimapLayout::BORDER_WIDTH,
+                    ..Default::default()
+                },
+                cx.theme().colors().scrollbar_track_border,
+            ));
+            for minimap_quad in &minimap.quads {
+                cx.paint_quad(minimap_quad.clone());
+            }
+            let thumb_color = if is_dragging || hitbox.is_hovered(cx) {
+                cx.theme().colors().scrollbar_thumb_hover_background
+            } else {
+                cx.theme().colors().scrollbar_thumb_background
+            };
+            cx.paint_quad(fill(thumb_bounds, thumb_color));
+        });
+
+        cx.set_cursor_style(CursorStyle::Arrow, &hitbox);
+
+        // Maps the mouse position to the scroll position that puts the top of the viewport
+        // slice `drag_offset` above the mouse.
+        let scroll_top_for_y = {
+            let track_top = hitbox.top();
+            let scrollable_height = minimap.track_height - thumb_bounds.size.height;
+            let max_scroll_top = minimap.max_scroll_top;
+            move |y: Pixels, drag_offset: Pixels| {
+                (scrollable_height > Pixels::ZERO).then(|| {
+                    ((y - drag_offset - track_top) / scrollable_height).clamp(0., 1.)
+                        * max_scroll_top
+                })
+            }
+        };
+
+        cx.on_mouse_event({
+            let editor = self.editor.clone();
+            move |event: &MouseDownEvent, phase, cx| {
+                if phase == DispatchPhase::Capture
+                    || event.button != MouseButton::Left
+                    || !hitbox.is_hovered(cx)
+                {
+                    return;
+                }
+
+                editor.update(cx, |editor, cx| {
+                    let y = event.position.y;
+                    let drag_offset = if (thumb_bounds.top()..thumb_bounds.bottom()).contains(&y) {
+                        y - thumb_bounds.top()
+                    } else {
+                        // Center the viewport slice on the clicked position.
+                        let drag_offset = thumb_bounds.size.height / 2.;
+                        if let Some(scroll_top) = scroll_top_for_y(y, drag_offset) {
+                            let mut position = editor.scroll_position(cx);
+                            position.y = scroll_top;
+                            editor.set_scroll_position(position, cx);
+                        }
+                        drag_offset
+                    };
+                    editor
+                        .scroll_manager
+                        .set_minimap_drag_offset(Some(drag_offset), cx);
+                    cx.stop_propagation();
+                });
+            }
+        });
+
+        cx.on_mouse_event({
+            let editor = self.editor.clone();
+            move |event: &MouseMoveEvent, phase, cx| {
+                if phase == DispatchPhase::Capture {
+                    return;
+                }
+
+                editor.update(cx, |editor, cx| {
+                    let Some(drag_offset) = editor.scroll_manager.minimap_drag_offset() else {
+                        return;
+                    };
+                    if event.pressed_button == Some(MouseButton::Left) {
+                        if let Some(scroll_top) = scroll_top_for_y(event.position.y, drag_offset) {
+                            let mut position = editor.scroll_position(cx);
+                            position.y = scroll_top;
+                            editor.set_scroll_position(position, cx);
+                        }
+                        cx.stop_propagation();
+                    } else {
+                        editor.scroll_manager.set_minimap_drag_offset(None, cx);
+                    }
+                });
+            }
+        });
+
+        if is_dragging {
+            cx.on_mouse_event({
+                let editor = self.editor.clone();
+                move |_: &MouseUpEvent, phase, cx| {
+                    if phase == DispatchPhase::Capture {
+                        return;
+                    }
+
+                    editor.update(cx, |editor, cx| {
+                        editor.scroll_manager.set_minimap_drag_offset(None, cx);
+                        cx.stop_propagation();
+                    });
+                }
+            });
+        }
+    }
+
     fn collect_fast_scrollbar_markers(
         &self,
         layout: &EditorLayout,
@@ -5012,8 +5649,10 @@ fn render_inline_blame_entry(
     };
 
     let details = blame.read(cx).details_for_entry(&blame_entry);
+    let abs_path = blame.read(cx).buffer_abs_path(cx);
 
-    let tooltip = cx.new_view(|_| BlameEntryTooltip::new(blame_entry, details, style, workspace));
+    let tooltip =
+        cx.new_view(|_| BlameEntryTooltip::new(blame_entry, details, style, workspace, abs_path));
 
     h_flex()
         .id("inline-blame")
@@ -5060,11 +5699,21 @@ fn render_blame_entry(
     let name = util::truncate_and_trailoff(author_name, GIT_BLAME_MAX_AUTHOR_CHARS_DISPLAYED);
 
     let details = blame.read(cx).details_for_entry(&blame_entry);
+    let abs_path = blame.read(cx).buffer_abs_path(cx);
 
     let workspace = editor.read(cx).workspace.as_ref().map(|(w, _)| w.clone());
 
-    let tooltip = cx.new_view(|_| {
-        BlameEntryTooltip::new(blame_entry.clone(), details.clone(), style, workspace)
+    let tooltip = cx.new_view({
+        let abs_path = abs_path.clone();
+        |_| {
+            BlameEntryTooltip::new(
+                blame_entry.clone(),
+                details.clone(),
+                style,
+                workspace,
+                abs_path,
+            )
+        }
     });
 
     h_flex()
@@ -5091,6 +5740,7 @@ fn render_blame_entry(
                 deploy_blame_entry_context_menu(
                     &blame_entry,
                     details.as_ref(),
+                    abs_path.clone(),
                     editor.clone(),
                     event.position,
                     cx,
@@ -5115,6 +5765,7 @@ fn render_blame_entry(
 fn deploy_blame_entry_context_menu(
     blame_entry: &BlameEntry,
     details: Option<&CommitDetails>,
+    abs_path: Option<PathBuf>,
     editor: View<Editor>,
     position: gpui::Point<Pixels>,
     cx: &mut WindowContext<'_>,
@@ -5129,6 +5780,20 @@ fn deploy_blame_entry_context_menu(
                 details.and_then(|details| details.permalink.clone()),
                 |this, url| this.entry("Open permalink", None, move |cx| cx.open_url(url.as_str())),
             )
+            .when_some(abs_path, |this, path| {
+                this.separator()
+                    .action(
+                        "View Commit",
+                        Box::new(zed_actions::git::ViewCommit {
+                            sha: blame_entry.sha.to_string(),
+                            path: path.clone(),
+                        }),
+                    )
+                    .action(
+                        "View File History",
+                        Box::new(zed_actions::git::ViewHistory { path: Some(path) }),
+                    )
+            })
     });
 
     editor.update(cx, move |editor, cx| {
@@ -5832,6 +6497,17 @@ impl Element for EditorElement {
                         cx,
                     );
                     let text_width = bounds.size.width - gutter_dimensions.width;
+                    let minimap_width = self.minimap_width(
+                        &snapshot,
+                        text_width,
+                        bounds.size.height / line_height,
+                        line_height,
+                        em_advance,
+                        cx,
+                    );
+                    let text_width = text_width
+                        - minimap_width
+                            .map_or(Pixels::ZERO, |width| width + self.style.scrollbar_width);
 
                     let editor_width = text_width - gutter_dimensions.margin - em_width;
 
@@ -6024,6 +6700,13 @@ impl Element for EditorElement {
                         &snapshot,
                         cx,
                     );
+                    let staged_hunk_bounds = self.layout_gutter_staged_hunks(
+                        line_height,
+                        &gutter_hitbox,
+                        start_row..end_row,
+                        &snapshot,
+                        cx,
+                    );
 
                     let mut max_visible_line_width = Pixels::ZERO;
                     let mut line_layouts = Self::layout_lines(
@@ -6258,6 +6941,32 @@ impl Element for EditorElement {
                         cx,
                     );
 
+                    let sticky_headers = self.layout_sticky_headers(
+                        &snapshot,
+                        start_row,
+                        &gutter_hitbox,
+                        &text_hitbox,
+                        content_origin,
+                        scroll_pixel_position,
+                        line_height,
+                        editor_width,
+                        cx,
+                    );
+
+                    let minimap = minimap_width.map(|minimap_width| {
+                        self.layout_minimap(
+                            &snapshot,
+                            bounds,
+                            minimap_width,
+                            line_height,
+                            em_advance,
+                            height_in_lines,
+                            scroll_position,
+                            max_scroll_top,
+                            cx,
+                        )
+                    });
+
                     let gutter_settings = EditorSettings::get_global(cx).gutter;
 
                     let expanded_add_hunks_by_rows = self.editor.update(cx, |editor, _| {
@@ -6297,6 +7006,12 @@ impl Element for EditorElement {
                                 rows_with_hunk_bounds
                             },
                         );
+                    let breakpoint_rows = self.editor.read(cx).breakpoint_display_rows(
+                        start_row..end_row,
+                        &snapshot,
+                        cx,
+                    );
+
                     let mut code_actions_indicator = None;
                     if let Some(newest_selection_head) = newest_selection_head {
                         if (start_row..end_row).contains(&newest_selection_head.row()) {
@@ -6338,7 +7053,12 @@ impl Element for EditorElement {
                                                 .tasks
                                                 .contains_key(&(buffer_id, row));
 
-                                            if !has_test_indicator {
+                                            let has_breakpoint =
+                                                breakpoint_rows.iter().any(|(row, _)| {
+                                                    *row == newest_selection_display_row
+                                                });
+
+                                            if !has_test_indicator && !has_breakpoint {
                                                 code_actions_indicator = self
                                                     .layout_code_actions_indicator(
                                                         line_height,
@@ -6365,6 +7085,7 @@ impl Element for EditorElement {
                             &gutter_dimensions,
                             &gutter_hitbox,
                             &rows_with_hunk_bounds,
+                            &breakpoint_rows,
                             &snapshot,
                             cx,
                         )
@@ -6372,6 +7093,16 @@ impl Element for EditorElement {
                         Vec::new()
                     };
 
+                    let breakpoints = self.layout_breakpoints(
+                        line_height,
+                        &breakpoint_rows,
+                        scroll_pixel_position,
+                        &gutter_dimensions,
+                        &gutter_hitbox,
+                        &rows_with_hunk_bounds,
+                        cx,
+                    );
+
                     self.layout_signature_help(
                         &hitbox,
                         content_origin,
@@ -6484,8 +7215,11 @@ impl Element for EditorElement {
                         gutter_hitbox,
                         gutter_dimensions,
                         display_hunks,
+                        staged_hunk_bounds,
                         content_origin,
                         scrollbars_layout,
+                        minimap,
+                        sticky_headers,
                         active_rows,
                         highlighted_rows,
                         highlighted_ranges,
@@ -6502,6 +7236,7 @@ impl Element for EditorElement {
                         inline_completion_popover,
                         mouse_context_menu,
                         test_indicators,
+                        breakpoints,
                         code_actions_indicator,
                         crease_toggles,
                         crease_trailers,
@@ -6588,6 +7323,8 @@ impl Element for EditorElement {
                         });
                     }
 
+                    self.paint_sticky_headers(layout, cx);
+                    self.paint_minimap(layout, cx);
                     self.paint_scrollbars(layout, cx);
                     self.paint_inline_completion_popover(layout, cx);
                     self.paint_mouse_context_menu(layout, cx);
@@ -6669,6 +7406,8 @@ pub struct EditorLayout {
     gutter_dimensions: GutterDimensions,
     content_origin: gpui::Point<Pixels>,
     scrollbars_layout: AxisPair<Option<ScrollbarLayout>>,
+    minimap: Option<MinimapLayout>,
+    sticky_headers: Option<StickyHeadersLayout>,
     mode: EditorMode,
     wrap_guides: SmallVec<[(Pixels, bool); 2]>,
     indent_guides: Option<Vec<IndentGuideLayout>>,
@@ -6678,6 +7417,7 @@ pub struct EditorLayout {
     line_elements: SmallVec<[AnyElement; 1]>,
     line_numbers: Vec<Option<ShapedLine>>,
     display_hunks: Vec<(DisplayDiffHunk, Option<Hitbox>)>,
+    staged_hunk_bounds: Vec<(DiffHunkStatus, Bounds<Pixels>)>,
     blamed_display_rows: Option<Vec<AnyElement>>,
     inline_blame: Option<AnyElement>,
     blocks: Vec<BlockLayout>,
@@ -6689,6 +7429,7 @@ pub struct EditorLayout {
     selections: Vec<(PlayerColor, Vec<SelectionLayout>)>,
     code_actions_indicator: Option<AnyElement>,
     test_indicators: Vec<AnyElement>,
+    breakpoints: Vec<AnyElement>,
     crease_toggles: Vec<Option<AnyElement>>,
     crease_trailers: Vec<Option<CreaseTrailerLayout>>,
     inline_completion_popover: Option<AnyElement>,
@@ -6832,6 +7573,36 @@ impl ScrollbarLayout {
     }
 }
 
+struct MinimapLayout {
+    hitbox: Hitbox,
+    thumb_bounds: Bounds<Pixels>,
+    track_height: Pixels,
+    max_scroll_top: f32,
+    quads: Vec<PaintQuad>,
+}
+
+impl MinimapLayout {
+    const BORDER_WIDTH: Pixels = px(1.0);
+    const ROW_HEIGHT: Pixels = px(2.0);
+
+    /// The width of a column in the minimap, scaled down from the editor's along with its lines.
+    fn column_width(line_height: Pixels, em_advance: Pixels) -> Pixels {
+        em_advance * (Self::ROW_HEIGHT / line_height)
+    }
+}
+
+struct StickyHeadersLayout {
+    bounds: Bounds<Pixels>,
+    lines: Vec<StickyHeaderLine>,
+}
+
+struct StickyHeaderLine {
+    line: LineWithInvisibles,
+    origin: gpui::Point<Pixels>,
+    hitbox: Hitbox,
+    target: Anchor,
+}
+
 struct CreaseTrailerLayout {
     element: AnyElement,
     bounds: Bounds<Pixels>,
@@ -7295,12 +8066,14 @@ mod tests {
     use super::*;
     use crate::{
         display_map::{BlockPlacement, BlockProperties},
+        editor_settings::{MinimapContent, StickyScrollContent},
         editor_tests::{init_test, update_test_language_settings},
         Editor, MultiBuffer,
     };
     use gpui::{TestAppContext, VisualTestContext};
-    use language::language_settings;
+    use language::{language_settings, Buffer, Language, LanguageConfig};
     use log::info;
+    use settings::SettingsStore;
     use std::num::NonZeroU32;
     use util::test::sample_text;
 
@@ -7577,6 +8350,121 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    fn test_minimap_layout(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.minimap = Some(MinimapContent {
+                        show: Some(ShowMinimap::Always),
+                        ..Default::default()
+                    });
+                });
+            });
+        });
+
+        let window = cx.add_window(|cx| {
+            let buffer = MultiBuffer::build_simple(&sample_text(100, 6, 'a'), cx);
+            Editor::new(EditorMode::Full, buffer, None, true, cx)
+        });
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style.clone())
+        });
+        let minimap = state.minimap.as_ref().expect("minimap should be shown");
+        assert!(state.text_hitbox.bounds.right() <= minimap.hitbox.bounds.left());
+        assert!(!minimap.quads.is_empty());
+        assert_eq!(minimap.thumb_bounds.top(), minimap.hitbox.bounds.top());
+
+        window
+            .update(cx, |editor, cx| {
+                editor.set_scroll_position(point(0., 1000.), cx);
+            })
+            .unwrap();
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style.clone())
+        });
+        let minimap = state.minimap.as_ref().expect("minimap should be shown");
+        assert!(minimap.thumb_bounds.top() > minimap.hitbox.bounds.top());
+        assert!(minimap.thumb_bounds.bottom() <= minimap.hitbox.bounds.bottom());
+    }
+
+    #[gpui::test]
+    fn test_sticky_headers_layout(cx: &mut TestAppContext) {
+        init_test(cx, |_| {});
+        cx.update(|cx| {
+            cx.update_global::<SettingsStore, _>(|settings, cx| {
+                settings.update_user_settings::<EditorSettings>(cx, |settings| {
+                    settings.sticky_scroll = Some(StickyScrollContent {
+                        enabled: Some(true),
+                        ..Default::default()
+                    });
+                });
+            });
+        });
+
+        let language = Arc::new(
+            Language::new(
+                LanguageConfig {
+                    name: "Rust".into(),
+                    ..Default::default()
+                },
+                Some(tree_sitter_rust::LANGUAGE.into()),
+            )
+            .with_outline_query(
+                r#"
+                (mod_item "mod" @context name: (_) @name) @item
+                (function_item "fn" @context name: (_) @name) @item
+                "#,
+            )
+            .unwrap(),
+        );
+        let text = format!(
+            "mod a {{\n    fn b() {{\n{}    }}\n}}\n",
+            "        let x = 1;\n".repeat(50)
+        );
+        let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(language, cx));
+        let window = cx.add_window(|cx| {
+            let buffer = cx.new_model(|cx| MultiBuffer::singleton(buffer, cx));
+            Editor::new(EditorMode::Full, buffer, None, true, cx)
+        });
+        cx.executor().run_until_parked();
+        let cx = &mut VisualTestContext::from_window(*window, cx);
+        let editor = window.root(cx).unwrap();
+        let style = cx.update(|cx| editor.read(cx).style().unwrap().clone());
+
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style.clone())
+        });
+        assert!(state.sticky_headers.is_none());
+
+        window
+            .update(cx, |editor, cx| {
+                editor.set_scroll_position(point(0., 10.), cx);
+            })
+            .unwrap();
+        let (_, state) = cx.draw(point(px(500.), px(500.)), size(px(500.), px(500.)), |_| {
+            EditorElement::new(&editor, style.clone())
+        });
+        let sticky_headers = state
+            .sticky_headers
+            .as_ref()
+            .expect("headers should be pinned");
+        let snapshot = &state.position_map.snapshot;
+        assert_eq!(
+            sticky_headers
+                .lines
+                .iter()
+                .map(|line| line.target.to_point(&snapshot.buffer_snapshot))
+                .collect::<Vec<_>>(),
+            [Point::new(0, 0), Point::new(1, 4)]
+        );
+    }
+
     #[gpui::test]
     fn test_all_invisibles_drawing(cx: &mut TestAppContext) {
         const TAB_SIZE: u32 = 4;
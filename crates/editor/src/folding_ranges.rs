@@ -0,0 +1,138 @@
+use std::time::Duration;
+
+use gpui::{Model, ViewContext};
+use language::Buffer;
+use util::ResultExt;
+
+use crate::{Editor, EditorMode};
+
+const FOLDING_RANGES_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Computes the fold ranges of `buffer`, or of every buffer in the editor when it is `None`, and
+/// stores them as creases, replacing the folds inferred from indentation. The ranges of the
+/// primary language server are used when it provides them, falling back to the `folds.scm` query
+/// of the buffer's language. Buffers with neither keep folding by indentation.
+pub(super) fn refresh_folding_ranges(
+    editor: &mut Editor,
+    buffer: Option<Model<Buffer>>,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+
+    clear_removed_folding_ranges(editor, cx);
+    let buffers = match buffer {
+        Some(buffer) => vec![buffer],
+        None => editor.buffer.read(cx).all_buffers().into_iter().collect(),
+    };
+    for buffer in buffers {
+        let buffer_id = buffer.read(cx).remote_id();
+        let task = cx.spawn(|editor, mut cx| async move {
+            if debounce {
+                cx.background_executor()
+                    .timer(FOLDING_RANGES_DEBOUNCE)
+                    .await;
+            }
+
+            let Some((lsp_ranges, buffer_snapshot)) = editor
+                .update(&mut cx, |editor, cx| {
+                    let lsp_ranges = editor.project.as_ref().map(|project| {
+                        project.update(cx, |project, cx| project.folding_ranges(&buffer, cx))
+                    });
+                    (lsp_ranges, buffer.read(cx).snapshot())
+                })
+                .ok()
+            else {
+                return;
+            };
+            let mut ranges = match lsp_ranges {
+                Some(lsp_ranges) => lsp_ranges.await.log_err().flatten(),
+                None => None,
+            };
+            // Servers that support folding ranges may still have none for a buffer, in which case
+            // the folds query is used, or indentation when the language has no such query.
+            if ranges.as_ref().map_or(true, |ranges| ranges.is_empty()) {
+                ranges = cx
+                    .background_executor()
+                    .spawn(async move {
+                        let ranges = buffer_snapshot.syntax_fold_ranges()?;
+                        Some(
+                            ranges
+                                .into_iter()
+                                .map(|range| {
+                                    buffer_snapshot.anchor_after(range.start)
+                                        ..buffer_snapshot.anchor_before(range.end)
+                                })
+                                .collect::<Vec<_>>(),
+                        )
+                    })
+                    .await;
+            }
+
+            editor
+                .update(&mut cx, |editor, cx| {
+                    let multi_buffer = editor.buffer.read(cx);
+                    let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+                    let buffer_snapshot = buffer.read(cx).snapshot();
+                    let ranges = ranges.map(|ranges| {
+                        let mut multi_buffer_ranges = Vec::new();
+                        for (excerpt_id, excerpt_range) in
+                            multi_buffer.excerpts_for_buffer(&buffer, cx)
+                        {
+                            let excerpt_range = excerpt_range.context;
+                            for range in &ranges {
+                                if range
+                                    .start
+                                    .cmp(&excerpt_range.start, &buffer_snapshot)
+                                    .is_lt()
+                                    || range.end.cmp(&excerpt_range.end, &buffer_snapshot).is_gt()
+                                {
+                                    continue;
+                                }
+                                let start = multi_buffer_snapshot
+                                    .anchor_in_excerpt(excerpt_id, range.start);
+                                let end =
+                                    multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, range.end);
+                                if let Some((start, end)) = start.zip(end) {
+                                    multi_buffer_ranges.push(start..end);
+                                }
+                            }
+                        }
+                        multi_buffer_ranges
+                    });
+
+                    editor.display_map.update(cx, |display_map, cx| {
+                        display_map.set_fold_ranges(buffer_id, ranges, cx);
+                    });
+                    cx.notify();
+                })
+                .ok();
+        });
+        editor.folding_ranges_tasks.insert(buffer_id, task);
+    }
+}
+
+/// Drops the fold ranges of the buffers that are no longer in the editor.
+pub(super) fn clear_removed_folding_ranges(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let multi_buffer = editor.buffer.read(cx);
+    let removed_buffer_ids = editor
+        .display_map
+        .read(cx)
+        .fold_range_buffers()
+        .filter(|buffer_id| multi_buffer.buffer(*buffer_id).is_none())
+        .collect::<Vec<_>>();
+    editor
+        .folding_ranges_tasks
+        .retain(|buffer_id, _| multi_buffer.buffer(*buffer_id).is_some());
+    if removed_buffer_ids.is_empty() {
+        return;
+    }
+    editor.display_map.update(cx, |display_map, cx| {
+        for buffer_id in removed_buffer_ids {
+            display_map.set_fold_ranges(buffer_id, None, cx);
+        }
+    });
+    cx.notify();
+}
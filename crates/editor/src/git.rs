@@ -1,2 +1,4 @@
 pub mod blame;
+pub mod project_conflicts;
 pub mod project_diff;
+pub mod split_diff;
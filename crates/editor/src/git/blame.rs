@@ -1,4 +1,4 @@
-use std::{sync::Arc, time::Duration};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 use anyhow::Result;
 use collections::HashMap;
@@ -6,7 +6,7 @@ use git::{
     blame::{Blame, BlameEntry},
     parse_git_remote_url, GitHostingProvider, GitHostingProviderRegistry, Oid, PullRequest,
 };
-use gpui::{Model, ModelContext, Subscription, Task};
+use gpui::{AppContext, Model, ModelContext, Subscription, Task};
 use http_client::HttpClient;
 use language::{markdown, Bias, Buffer, BufferSnapshot, Edit, LanguageRegistry, ParsedMarkdown};
 use multi_buffer::MultiBufferRow;
@@ -192,6 +192,12 @@ impl GitBlame {
         self.commit_details.get(&entry.sha).cloned()
     }
 
+    /// Returns the absolute path of the blamed file, which locates its repository.
+    pub fn buffer_abs_path(&self, cx: &AppContext) -> Option<PathBuf> {
+        let project_path = self.buffer.read(cx).project_path(cx)?;
+        self.project.read(cx).absolute_path(&project_path, cx)
+    }
+
     pub fn blame_for_rows<'a>(
         &'a mut self,
         rows: impl 'a + IntoIterator<Item = Option<MultiBufferRow>>,
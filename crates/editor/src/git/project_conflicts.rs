@@ -0,0 +1,373 @@
+use std::{
+    any::{Any, TypeId},
+    collections::{BTreeMap, BTreeSet},
+    time::Duration,
+};
+
+use anyhow::Result;
+use futures::future::join_all;
+use git::repository::GitFileStatus;
+use gpui::{
+    actions, AnyElement, AnyView, AppContext, EventEmitter, FocusHandle, FocusableView,
+    InteractiveElement, Model, Render, Subscription, Task, View,
+};
+use language::Buffer;
+use multi_buffer::{build_excerpt_ranges, ExcerptId, MultiBuffer};
+use project::{Project, ProjectPath};
+use text::ToOffset as _;
+use theme::ActiveTheme;
+use ui::prelude::*;
+use workspace::{
+    item::{BreadcrumbText, Item, ItemEvent, TabContentParams},
+    ItemNavHistory, ToolbarItemLocation, Workspace,
+};
+
+use crate::{merge_conflicts::buffer_conflicts, Editor, EditorEvent, DEFAULT_MULTIBUFFER_CONTEXT};
+
+actions!(project_conflicts, [Deploy]);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(ProjectConflictsEditor::register)
+        .detach();
+}
+
+const UPDATE_DEBOUNCE: Duration = Duration::from_millis(50);
+
+/// Shows the merge conflicts of every unmerged file of the project in a single multibuffer.
+///
+/// Files are added when git reports them as unmerged and removed once they are marked as
+/// resolved. The excerpts of a file are kept while it is being edited, so that resolved conflicts
+/// stay in view until the file is staged.
+struct ProjectConflictsEditor {
+    project: Model<Project>,
+    excerpts: Model<MultiBuffer>,
+    editor: View<Editor>,
+    unmerged_buffers: BTreeMap<ProjectPath, Model<Buffer>>,
+    focus_handle: FocusHandle,
+    update_task: Task<Result<()>>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl ProjectConflictsEditor {
+    fn register(workspace: &mut Workspace, _: &mut ViewContext<Workspace>) {
+        workspace.register_action(Self::deploy);
+    }
+
+    fn deploy(workspace: &mut Workspace, _: &Deploy, cx: &mut ViewContext<Workspace>) {
+        if let Some(existing) = workspace.item_of_type::<Self>(cx) {
+            workspace.activate_item(&existing, true, true, cx);
+        } else {
+            let project_conflicts = cx.new_view(|cx| Self::new(workspace.project().clone(), cx));
+            workspace.add_item_to_active_pane(Box::new(project_conflicts), None, true, cx);
+        }
+    }
+
+    fn new(project: Model<Project>, cx: &mut ViewContext<Self>) -> Self {
+        let focus_handle = cx.focus_handle();
+        let excerpts = cx.new_model(|cx| MultiBuffer::new(project.read(cx).capability()));
+        let editor = cx.new_view(|cx| {
+            Editor::for_multibuffer(excerpts.clone(), Some(project.clone()), true, cx)
+        });
+
+        let subscriptions = vec![
+            cx.subscribe(&project, |this, _, event, cx| match event {
+                project::Event::WorktreeUpdatedGitRepositories(_)
+                | project::Event::WorktreeRemoved(_) => this.schedule_update(cx),
+                _ => {}
+            }),
+            cx.subscribe(&editor, |_, _, event: &EditorEvent, cx| {
+                cx.emit(event.clone());
+            }),
+            cx.on_focus_in(&focus_handle, |this, cx| {
+                if !this.unmerged_buffers.is_empty() {
+                    this.editor.focus_handle(cx).focus(cx);
+                }
+            }),
+        ];
+
+        let mut this = Self {
+            project,
+            excerpts,
+            editor,
+            unmerged_buffers: BTreeMap::default(),
+            focus_handle,
+            update_task: Task::ready(Ok(())),
+            _subscriptions: subscriptions,
+        };
+        this.schedule_update(cx);
+        this
+    }
+
+    fn unmerged_paths(&self, cx: &AppContext) -> BTreeSet<ProjectPath> {
+        let mut paths = BTreeSet::new();
+        for worktree in self.project.read(cx).visible_worktrees(cx) {
+            let worktree = worktree.read(cx);
+            for entry in worktree.entries(false, 0) {
+                if entry.is_file() && entry.git_status == Some(GitFileStatus::Conflict) {
+                    paths.insert(ProjectPath {
+                        worktree_id: worktree.id(),
+                        path: entry.path.clone(),
+                    });
+                }
+            }
+        }
+        paths
+    }
+
+    fn schedule_update(&mut self, cx: &mut ViewContext<Self>) {
+        let project = self.project.clone();
+        self.update_task = cx.spawn(|this, mut cx| async move {
+            cx.background_executor().timer(UPDATE_DEBOUNCE).await;
+
+            let new_paths = this.update(&mut cx, |this, cx| {
+                let unmerged_paths = this.unmerged_paths(cx);
+                let resolved_paths = this
+                    .unmerged_buffers
+                    .keys()
+                    .filter(|path| !unmerged_paths.contains(path))
+                    .cloned()
+                    .collect::<Vec<_>>();
+                for path in resolved_paths {
+                    if let Some(buffer) = this.unmerged_buffers.remove(&path) {
+                        this.excerpts.update(cx, |excerpts, cx| {
+                            let excerpt_ids = excerpts
+                                .excerpts_for_buffer(&buffer, cx)
+                                .into_iter()
+                                .map(|(excerpt_id, _)| excerpt_id);
+                            excerpts.remove_excerpts(excerpt_ids.collect::<Vec<_>>(), cx);
+                        });
+                    }
+                }
+                cx.notify();
+
+                unmerged_paths
+                    .into_iter()
+                    .filter(|path| !this.unmerged_buffers.contains_key(path))
+                    .collect::<Vec<_>>()
+            })?;
+
+            let open_buffers = project.update(&mut cx, |project, cx| {
+                new_paths
+                    .into_iter()
+                    .map(|path| {
+                        let buffer = project.open_buffer(path.clone(), cx);
+                        async move { (path, buffer.await) }
+                    })
+                    .collect::<Vec<_>>()
+            })?;
+            let mut new_buffers = Vec::new();
+            for (path, buffer) in join_all(open_buffers).await {
+                let buffer = buffer?;
+                let snapshot = buffer.update(&mut cx, |buffer, _| buffer.snapshot())?;
+                let excerpt_ranges = cx
+                    .background_executor()
+                    .spawn(async move {
+                        let conflict_ranges = buffer_conflicts(&snapshot)
+                            .into_iter()
+                            .map(|conflict| {
+                                conflict.range.start.to_offset(&snapshot)
+                                    ..conflict.range.end.to_offset(&snapshot)
+                            })
+                            .collect::<Vec<_>>();
+                        build_excerpt_ranges(
+                            &snapshot,
+                            &conflict_ranges,
+                            DEFAULT_MULTIBUFFER_CONTEXT,
+                        )
+                        .0
+                    })
+                    .await;
+                new_buffers.push((path, buffer, excerpt_ranges));
+            }
+
+            this.update(&mut cx, |this, cx| {
+                for (path, buffer, excerpt_ranges) in new_buffers {
+                    let previous_excerpt_id = this
+                        .unmerged_buffers
+                        .range(..&path)
+                        .rev()
+                        .find_map(|(_, buffer)| {
+                            this.excerpts
+                                .read(cx)
+                                .excerpts_for_buffer(buffer, cx)
+                                .last()
+                                .map(|(excerpt_id, _)| *excerpt_id)
+                        })
+                        .unwrap_or_else(ExcerptId::min);
+                    this.excerpts.update(cx, |excerpts, cx| {
+                        excerpts.insert_excerpts_after(
+                            previous_excerpt_id,
+                            buffer.clone(),
+                            excerpt_ranges,
+                            cx,
+                        );
+                    });
+                    this.unmerged_buffers.insert(path, buffer);
+                }
+                cx.notify();
+            })
+        });
+    }
+}
+
+impl EventEmitter<EditorEvent> for ProjectConflictsEditor {}
+
+impl FocusableView for ProjectConflictsEditor {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for ProjectConflictsEditor {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, cx| editor.deactivated(cx));
+    }
+
+    fn navigate(&mut self, data: Box<dyn Any>, cx: &mut ViewContext<Self>) -> bool {
+        self.editor
+            .update(cx, |editor, cx| editor.navigate(data, cx))
+    }
+
+    fn tab_tooltip_text(&self, _: &AppContext) -> Option<SharedString> {
+        Some("Merge Conflicts".into())
+    }
+
+    fn tab_content(&self, params: TabContentParams, _: &WindowContext) -> AnyElement {
+        let color = if params.selected {
+            Color::Default
+        } else {
+            Color::Muted
+        };
+        if self.unmerged_buffers.is_empty() {
+            Label::new("No conflicts").color(color).into_any_element()
+        } else {
+            h_flex()
+                .gap_1()
+                .child(Icon::new(IconName::Warning).color(Color::Conflict))
+                .child(Label::new(self.unmerged_buffers.len().to_string()).color(color))
+                .into_any_element()
+        }
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("project conflicts")
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.editor.for_each_project_item(cx, f)
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        false
+    }
+
+    fn set_nav_history(&mut self, nav_history: ItemNavHistory, cx: &mut ViewContext<Self>) {
+        self.editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn clone_on_split(
+        &self,
+        _workspace_id: Option<workspace::WorkspaceId>,
+        cx: &mut ViewContext<Self>,
+    ) -> Option<View<Self>>
+    where
+        Self: Sized,
+    {
+        Some(cx.new_view(|cx| ProjectConflictsEditor::new(self.project.clone(), cx)))
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.excerpts.read(cx).is_dirty(cx)
+    }
+
+    fn has_conflict(&self, cx: &AppContext) -> bool {
+        self.excerpts.read(cx).has_conflict(cx)
+    }
+
+    fn can_save(&self, _: &AppContext) -> bool {
+        true
+    }
+
+    fn save(
+        &mut self,
+        format: bool,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        self.editor.save(format, project, cx)
+    }
+
+    fn save_as(
+        &mut self,
+        _: Model<Project>,
+        _: ProjectPath,
+        _: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        unreachable!()
+    }
+
+    fn reload(&mut self, project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        self.editor.reload(project, cx)
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn breadcrumb_location(&self, _: &AppContext) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &AppContext) -> Option<Vec<BreadcrumbText>> {
+        self.editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        self.editor
+            .update(cx, |editor, cx| editor.added_to_workspace(workspace, cx));
+    }
+}
+
+impl Render for ProjectConflictsEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let child = if self.unmerged_buffers.is_empty() {
+            div()
+                .bg(cx.theme().colors().editor_background)
+                .flex()
+                .items_center()
+                .justify_center()
+                .size_full()
+                .child(Label::new("No merge conflicts in the workspace"))
+        } else {
+            div().size_full().child(self.editor.clone())
+        };
+
+        div()
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(child)
+    }
+}
@@ -0,0 +1,678 @@
+use std::{
+    any::{Any, TypeId},
+    ops::Range,
+    sync::Arc,
+};
+
+use anyhow::Result;
+use collections::HashSet;
+use gpui::{
+    AnyView, AppContext, EventEmitter, FocusHandle, FocusableView, HighlightStyle, Hsla, Model,
+    Render, Subscription, Task, View,
+};
+use language::{language_settings::SoftWrap, Buffer, Capability, Point};
+use multi_buffer::MultiBuffer;
+use project::{buffer_store::BufferChangeSet, Project, ProjectPath};
+use similar::{ChangeTag, TextDiff};
+use text::{Bias, ToOffset as _};
+use theme::ActiveTheme;
+use ui::prelude::*;
+use workspace::{
+    item::{BreadcrumbText, Item, ItemEvent, ItemHandle as _},
+    ItemNavHistory, ToolbarItemLocation, Workspace,
+};
+
+use crate::{
+    BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, Editor, EditorEvent, OpenSplitDiff,
+};
+
+/// Hunks larger than this, in bytes, aren't diffed word by word.
+const MAX_WORD_DIFF_LEN: usize = 4096;
+
+enum SplitDiffRowHighlight {}
+enum SplitDiffWordHighlight {}
+
+/// Shows the base text of a diff and the current text of a buffer side by side.
+///
+/// Both sides are aligned row by row: padding blocks are inserted where one side has more rows
+/// than the other, so that scrolling either side scrolls the other to the same rows. The words
+/// that changed within modified lines are highlighted.
+pub struct SplitDiffView {
+    change_set: Model<BufferChangeSet>,
+    old_buffer: Model<Buffer>,
+    new_buffer: Model<Buffer>,
+    old_editor: View<Editor>,
+    new_editor: View<Editor>,
+    title: SharedString,
+    old_blocks: HashSet<CustomBlockId>,
+    new_blocks: HashSet<CustomBlockId>,
+    base_text_version: Option<usize>,
+    update_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+/// A hunk of the diff, as byte and row ranges of both texts.
+#[derive(Clone, Debug)]
+struct SplitDiffHunk {
+    old_range: Range<usize>,
+    new_range: Range<usize>,
+    old_rows: Range<u32>,
+    new_rows: Range<u32>,
+}
+
+impl Editor {
+    /// Opens the buffer under the newest cursor in a [`SplitDiffView`], comparing it with the
+    /// base text the editor shows its diff hunks against.
+    pub(crate) fn open_split_diff(&mut self, _: &OpenSplitDiff, cx: &mut ViewContext<Self>) {
+        let Some(workspace) = self.workspace() else {
+            cx.propagate();
+            return;
+        };
+        let multibuffer = self.buffer.read(cx);
+        let Some(buffer) = self
+            .selections
+            .newest_anchor()
+            .head()
+            .buffer_id
+            .and_then(|buffer_id| multibuffer.buffer(buffer_id))
+            .or_else(|| multibuffer.as_singleton())
+        else {
+            return;
+        };
+        let Some(change_set) = self
+            .diff_map
+            .diff_bases
+            .get(&buffer.read(cx).remote_id())
+            .map(|diff_base| diff_base.change_set.clone())
+        else {
+            return;
+        };
+        let title = match buffer.read(cx).file() {
+            Some(file) => file.file_name(cx).to_string_lossy().into_owned(),
+            None => multibuffer.title(cx).into_owned(),
+        };
+        let project = self.project.clone();
+
+        let split_diff = cx.new_view(|cx| {
+            SplitDiffView::new(buffer, change_set, project, format!("{title} (Split)"), cx)
+        });
+        cx.window_context().defer(move |cx| {
+            workspace.update(cx, |workspace, cx| {
+                workspace.add_item_to_active_pane(Box::new(split_diff), None, true, cx);
+            });
+        });
+    }
+}
+
+impl SplitDiffView {
+    /// Compares `buffer` with the base text of `change_set`, which can be the index, a git
+    /// revision or another buffer.
+    pub fn new(
+        buffer: Model<Buffer>,
+        change_set: Model<BufferChangeSet>,
+        project: Option<Model<Project>>,
+        title: String,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let language = buffer.read(cx).language().cloned();
+        let old_buffer = cx.new_model(|cx| {
+            let mut old_buffer = Buffer::local("", cx);
+            old_buffer.set_language(language, cx);
+            old_buffer.set_capability(Capability::ReadOnly, cx);
+            old_buffer
+        });
+
+        // Both sides must have the same rows for the padding blocks to align them.
+        let configure_editor = |editor: &mut Editor, cx: &mut ViewContext<Editor>| {
+            editor.set_soft_wrap_mode(SoftWrap::None, cx);
+            editor.set_show_inline_completions(Some(false), cx);
+        };
+        let old_editor = cx.new_view(|cx| {
+            let multibuffer = cx.new_model(|cx| MultiBuffer::singleton(old_buffer.clone(), cx));
+            let mut editor = Editor::for_multibuffer(multibuffer, None, true, cx);
+            editor.set_read_only(true);
+            configure_editor(&mut editor, cx);
+            editor
+        });
+        let new_editor = cx.new_view(|cx| {
+            let mut editor = Editor::for_buffer(buffer.clone(), project, cx);
+            configure_editor(&mut editor, cx);
+            editor
+        });
+
+        let subscriptions = vec![
+            cx.observe(&change_set, |this, _, cx| this.update_layout(cx)),
+            cx.subscribe(&old_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::ScrollPositionChanged { .. } = event {
+                    this.sync_scroll_position(true, cx);
+                }
+            }),
+            cx.subscribe(&new_editor, |this, _, event: &EditorEvent, cx| {
+                if let EditorEvent::ScrollPositionChanged { .. } = event {
+                    this.sync_scroll_position(false, cx);
+                }
+                cx.emit(event.clone());
+            }),
+        ];
+
+        let mut this = Self {
+            change_set,
+            old_buffer,
+            new_buffer: buffer,
+            old_editor,
+            new_editor,
+            title: title.into(),
+            old_blocks: HashSet::default(),
+            new_blocks: HashSet::default(),
+            base_text_version: None,
+            update_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.update_layout(cx);
+        this
+    }
+
+    /// Scrolls one side vertically to the rows shown by the other. Each side keeps its own
+    /// horizontal scroll position, as their lines have different lengths.
+    fn sync_scroll_position(&mut self, from_old: bool, cx: &mut ViewContext<Self>) {
+        let (source, target) = if from_old {
+            (&self.old_editor, &self.new_editor)
+        } else {
+            (&self.new_editor, &self.old_editor)
+        };
+        let source_y = source.update(cx, |editor, cx| editor.scroll_position(cx).y);
+        target.update(cx, |editor, cx| {
+            let mut position = editor.scroll_position(cx);
+            // Stops the scroll event of the target from bouncing back to the source.
+            if position.y != source_y {
+                position.y = source_y;
+                editor.set_scroll_position(position, cx);
+            }
+        });
+    }
+
+    /// Refreshes the base text, the padding blocks and the highlights after the diff changed.
+    fn update_layout(&mut self, cx: &mut ViewContext<Self>) {
+        let change_set = self.change_set.read(cx);
+        if self.base_text_version != Some(change_set.base_text_version) {
+            self.base_text_version = Some(change_set.base_text_version);
+            let base_text = change_set.base_text_string(cx).unwrap_or_default();
+            self.old_buffer
+                .update(cx, |buffer, cx| buffer.set_text(base_text, cx));
+        }
+
+        let change_set = self.change_set.read(cx);
+        let old_snapshot = self.old_buffer.read(cx).text_snapshot();
+        let new_snapshot = self.new_buffer.read(cx).text_snapshot();
+        let hunks = change_set
+            .diff_hunks_intersecting_range(text::Anchor::MIN..text::Anchor::MAX, &new_snapshot)
+            .map(|hunk| {
+                let old_range = old_snapshot
+                    .clip_offset(hunk.diff_base_byte_range.start, Bias::Left)
+                    ..old_snapshot.clip_offset(hunk.diff_base_byte_range.end, Bias::Left);
+                let new_range = hunk.buffer_range.start.to_offset(&new_snapshot)
+                    ..hunk.buffer_range.end.to_offset(&new_snapshot);
+                SplitDiffHunk {
+                    old_rows: line_rows(&old_snapshot, &old_range),
+                    new_rows: line_rows(&new_snapshot, &new_range),
+                    old_range,
+                    new_range,
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let row_ranges = hunks
+            .iter()
+            .map(|hunk| (hunk.old_rows.clone(), hunk.new_rows.clone()))
+            .collect::<Vec<_>>();
+        let (old_padding, new_padding) = alignment_padding(&row_ranges);
+        let old_blocks = std::mem::take(&mut self.old_blocks);
+        self.old_blocks = update_side(
+            &self.old_editor,
+            old_blocks,
+            &old_padding,
+            hunks.iter().map(|hunk| hunk.old_rows.clone()),
+            deleted_color(cx, 0.8),
+            cx,
+        );
+        let new_blocks = std::mem::take(&mut self.new_blocks);
+        self.new_blocks = update_side(
+            &self.new_editor,
+            new_blocks,
+            &new_padding,
+            hunks.iter().map(|hunk| hunk.new_rows.clone()),
+            created_color(cx, 0.8),
+            cx,
+        );
+
+        let texts = hunks
+            .into_iter()
+            .filter(|hunk| {
+                !hunk.old_range.is_empty()
+                    && !hunk.new_range.is_empty()
+                    && hunk.old_range.len() + hunk.new_range.len() <= MAX_WORD_DIFF_LEN
+            })
+            .map(|hunk| {
+                let old_text = old_snapshot
+                    .text_for_range(hunk.old_range.clone())
+                    .collect::<String>();
+                let new_text = new_snapshot
+                    .text_for_range(hunk.new_range.clone())
+                    .collect::<String>();
+                (hunk, old_text, new_text)
+            })
+            .collect::<Vec<_>>();
+        let word_diffs = cx.background_executor().spawn(async move {
+            let mut old_ranges = Vec::new();
+            let mut new_ranges = Vec::new();
+            for (hunk, old_text, new_text) in texts {
+                let (old_words, new_words) = word_diff(&old_text, &new_text);
+                old_ranges.extend(old_words.into_iter().map(|range| {
+                    hunk.old_range.start + range.start..hunk.old_range.start + range.end
+                }));
+                new_ranges.extend(new_words.into_iter().map(|range| {
+                    hunk.new_range.start + range.start..hunk.new_range.start + range.end
+                }));
+            }
+            (old_ranges, new_ranges)
+        });
+        self.update_task = cx.spawn(|this, mut cx| async move {
+            let (old_ranges, new_ranges) = word_diffs.await;
+            this.update(&mut cx, |this, cx| {
+                highlight_words(&this.old_editor, old_ranges, deleted_color(cx, 0.5), cx);
+                highlight_words(&this.new_editor, new_ranges, created_color(cx, 0.5), cx);
+            })
+            .ok();
+        });
+    }
+}
+
+fn created_color(cx: &AppContext, fade_out: f32) -> Hsla {
+    let mut color = cx.theme().status().created;
+    color.fade_out(fade_out);
+    color
+}
+
+fn deleted_color(cx: &AppContext, fade_out: f32) -> Hsla {
+    let mut color = cx.theme().status().deleted;
+    color.fade_out(fade_out);
+    color
+}
+
+/// The rows spanned by a range of whole lines. The last line may lack a trailing newline.
+fn line_rows(snapshot: &text::BufferSnapshot, range: &Range<usize>) -> Range<u32> {
+    let start = snapshot.offset_to_point(range.start);
+    let end = snapshot.offset_to_point(range.end);
+    start.row..end.row + (end.column > 0) as u32
+}
+
+/// Replaces the padding blocks and row highlights of one side of the view.
+fn update_side(
+    editor: &View<Editor>,
+    old_blocks: HashSet<CustomBlockId>,
+    padding: &[(u32, u32)],
+    changed_rows: impl Iterator<Item = Range<u32>>,
+    row_color: Hsla,
+    cx: &mut ViewContext<SplitDiffView>,
+) -> HashSet<CustomBlockId> {
+    editor.update(cx, |editor, cx| {
+        editor.remove_blocks(old_blocks, None, cx);
+        editor.clear_row_highlights::<SplitDiffRowHighlight>();
+
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let max_point = snapshot.max_point();
+        for rows in changed_rows.filter(|rows| !rows.is_empty()) {
+            let start = snapshot.anchor_before(Point::new(rows.start, 0));
+            let end = if rows.end > max_point.row {
+                snapshot.anchor_after(max_point)
+            } else {
+                snapshot.anchor_before(Point::new(rows.end, 0))
+            };
+            editor.highlight_rows::<SplitDiffRowHighlight>(start..end, row_color, false, cx);
+        }
+
+        let blocks = padding.iter().map(|&(row, height)| {
+            let placement = if row > max_point.row {
+                BlockPlacement::Below(snapshot.anchor_after(max_point))
+            } else {
+                BlockPlacement::Above(snapshot.anchor_before(Point::new(row, 0)))
+            };
+            BlockProperties {
+                placement,
+                height,
+                style: BlockStyle::Sticky,
+                priority: 0,
+                render: Arc::new(|cx| {
+                    div()
+                        .size_full()
+                        .bg(cx.theme().colors().editor_subheader_background)
+                        .into_any_element()
+                }),
+            }
+        });
+        let blocks = editor.insert_blocks(blocks.collect::<Vec<_>>(), None, cx);
+        blocks.into_iter().collect()
+    })
+}
+
+fn highlight_words(
+    editor: &View<Editor>,
+    ranges: Vec<Range<usize>>,
+    color: Hsla,
+    cx: &mut ViewContext<SplitDiffView>,
+) {
+    editor.update(cx, |editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        let ranges = ranges
+            .into_iter()
+            .map(|range| snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end))
+            .collect();
+        editor.highlight_text::<SplitDiffWordHighlight>(
+            ranges,
+            HighlightStyle {
+                background_color: Some(color),
+                ..Default::default()
+            },
+            cx,
+        );
+    });
+}
+
+/// Returns the padding that aligns the rows of both texts, given the row ranges of each hunk in
+/// the old and the new text. The padding of each side is a list of `(row, height)` pairs, each
+/// inserting `height` blank rows above `row`.
+fn alignment_padding(hunks: &[(Range<u32>, Range<u32>)]) -> (Vec<(u32, u32)>, Vec<(u32, u32)>) {
+    let mut old_padding = Vec::new();
+    let mut new_padding = Vec::new();
+    for (old_rows, new_rows) in hunks {
+        let old_len = old_rows.end - old_rows.start;
+        let new_len = new_rows.end - new_rows.start;
+        if old_len > new_len {
+            new_padding.push((new_rows.end, old_len - new_len));
+        } else if new_len > old_len {
+            old_padding.push((old_rows.end, new_len - old_len));
+        }
+    }
+    (old_padding, new_padding)
+}
+
+/// Returns the byte ranges of the words that were removed from `old_text` and inserted in
+/// `new_text`, ignoring changes to whitespace.
+fn word_diff(old_text: &str, new_text: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    fn push_range(ranges: &mut Vec<Range<usize>>, range: Range<usize>) {
+        match ranges.last_mut() {
+            Some(last) if last.end == range.start => last.end = range.end,
+            _ => ranges.push(range),
+        }
+    }
+
+    let old_tokens = word_tokens(old_text);
+    let new_tokens = word_tokens(new_text);
+    let mut old_ranges = Vec::new();
+    let mut new_ranges = Vec::new();
+    let mut old_offset = 0;
+    let mut new_offset = 0;
+    for change in TextDiff::from_slices(&old_tokens, &new_tokens).iter_all_changes() {
+        let len = change.value().len();
+        let is_whitespace = change.value().trim().is_empty();
+        match change.tag() {
+            ChangeTag::Equal => {
+                old_offset += len;
+                new_offset += len;
+            }
+            ChangeTag::Delete => {
+                if !is_whitespace {
+                    push_range(&mut old_ranges, old_offset..old_offset + len);
+                }
+                old_offset += len;
+            }
+            ChangeTag::Insert => {
+                if !is_whitespace {
+                    push_range(&mut new_ranges, new_offset..new_offset + len);
+                }
+                new_offset += len;
+            }
+        }
+    }
+    (old_ranges, new_ranges)
+}
+
+/// Splits a text into runs of word characters, runs of whitespace and single punctuation
+/// characters.
+fn word_tokens(text: &str) -> Vec<&str> {
+    #[derive(PartialEq)]
+    enum Kind {
+        Word,
+        Whitespace,
+        Punctuation,
+    }
+    let kind = |ch: char| {
+        if ch.is_alphanumeric() || ch == '_' {
+            Kind::Word
+        } else if ch.is_whitespace() {
+            Kind::Whitespace
+        } else {
+            Kind::Punctuation
+        }
+    };
+
+    let mut tokens = Vec::new();
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        let token_kind = kind(ch);
+        if token_kind != Kind::Punctuation {
+            while chars
+                .next_if(|(_, next)| kind(*next) == token_kind)
+                .is_some()
+            {}
+        }
+        let end = chars.peek().map_or(text.len(), |(ix, _)| *ix);
+        tokens.push(&text[start..end]);
+    }
+    tokens
+}
+
+impl EventEmitter<EditorEvent> for SplitDiffView {}
+
+impl FocusableView for SplitDiffView {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.new_editor.focus_handle(cx)
+    }
+}
+
+impl Render for SplitDiffView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        h_flex()
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                div()
+                    .flex_1()
+                    .h_full()
+                    .min_w_0()
+                    .border_r_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(self.old_editor.clone()),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .h_full()
+                    .min_w_0()
+                    .child(self.new_editor.clone()),
+            )
+    }
+}
+
+impl Item for SplitDiffView {
+    type Event = EditorEvent;
+
+    fn to_item_events(event: &EditorEvent, f: impl FnMut(ItemEvent)) {
+        Editor::to_item_events(event, f)
+    }
+
+    fn tab_content_text(&self, _: &WindowContext) -> Option<SharedString> {
+        Some(self.title.clone())
+    }
+
+    fn tab_icon(&self, _: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::Diff))
+    }
+
+    fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
+        let change_set = self.change_set.read(cx);
+        let base = change_set
+            .base_revision
+            .as_deref()
+            .unwrap_or("its base text");
+        Some(format!("{} compared with {base}", self.title).into())
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("split diff opened")
+    }
+
+    fn deactivated(&mut self, cx: &mut ViewContext<Self>) {
+        self.new_editor
+            .update(cx, |editor, cx| editor.deactivated(cx));
+    }
+
+    fn navigate(&mut self, data: Box<dyn Any>, cx: &mut ViewContext<Self>) -> bool {
+        self.new_editor
+            .update(cx, |editor, cx| editor.navigate(data, cx))
+    }
+
+    fn set_nav_history(&mut self, nav_history: ItemNavHistory, cx: &mut ViewContext<Self>) {
+        self.new_editor.update(cx, |editor, _| {
+            editor.set_nav_history(Some(nav_history));
+        });
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(gpui::EntityId, &dyn project::ProjectItem),
+    ) {
+        self.new_editor.for_each_project_item(cx, f)
+    }
+
+    fn is_singleton(&self, _: &AppContext) -> bool {
+        false
+    }
+
+    fn is_dirty(&self, cx: &AppContext) -> bool {
+        self.new_buffer.read(cx).is_dirty()
+    }
+
+    fn has_conflict(&self, cx: &AppContext) -> bool {
+        self.new_buffer.read(cx).has_conflict()
+    }
+
+    fn can_save(&self, cx: &AppContext) -> bool {
+        self.new_editor.read(cx).can_save(cx)
+    }
+
+    fn save(
+        &mut self,
+        format: bool,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        self.new_editor
+            .update(cx, |editor, cx| editor.save(format, project, cx))
+    }
+
+    fn save_as(
+        &mut self,
+        _: Model<Project>,
+        _: ProjectPath,
+        _: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        unreachable!()
+    }
+
+    fn reload(&mut self, project: Model<Project>, cx: &mut ViewContext<Self>) -> Task<Result<()>> {
+        self.new_editor
+            .update(cx, |editor, cx| editor.reload(project, cx))
+    }
+
+    fn act_as_type<'a>(
+        &'a self,
+        type_id: TypeId,
+        self_handle: &'a View<Self>,
+        _: &'a AppContext,
+    ) -> Option<AnyView> {
+        if type_id == TypeId::of::<Self>() {
+            Some(self_handle.to_any())
+        } else if type_id == TypeId::of::<Editor>() {
+            Some(self.new_editor.to_any())
+        } else {
+            None
+        }
+    }
+
+    fn breadcrumb_location(&self, _: &AppContext) -> ToolbarItemLocation {
+        ToolbarItemLocation::PrimaryLeft
+    }
+
+    fn breadcrumbs(&self, theme: &theme::Theme, cx: &AppContext) -> Option<Vec<BreadcrumbText>> {
+        self.new_editor.breadcrumbs(theme, cx)
+    }
+
+    fn added_to_workspace(&mut self, workspace: &mut Workspace, cx: &mut ViewContext<Self>) {
+        self.new_editor
+            .update(cx, |editor, cx| editor.added_to_workspace(workspace, cx));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alignment_padding() {
+        let hunks = [
+            // Two rows replaced by three.
+            (2..4, 2..5),
+            // A row deleted.
+            (10..11, 11..11),
+            // A row modified.
+            (15..16, 15..16),
+            // Two rows inserted at the end.
+            (20..20, 20..22),
+        ];
+        let (old_padding, new_padding) = alignment_padding(&hunks);
+        assert_eq!(old_padding, [(4, 1), (20, 2)]);
+        assert_eq!(new_padding, [(11, 1)]);
+    }
+
+    #[test]
+    fn test_word_diff() {
+        let old_text = "let x = compute(a, b);\n";
+        let new_text = "let y = compute(a, c);\n";
+        let (old_ranges, new_ranges) = word_diff(old_text, new_text);
+        assert_eq!(
+            old_ranges
+                .into_iter()
+                .map(|range| &old_text[range])
+                .collect::<Vec<_>>(),
+            ["x", "b"]
+        );
+        assert_eq!(
+            new_ranges
+                .into_iter()
+                .map(|range| &new_text[range])
+                .collect::<Vec<_>>(),
+            ["y", "c"]
+        );
+
+        assert_eq!(
+            word_tokens("foo_bar(1, x)  "),
+            ["foo_bar", "(", "1", ",", " ", "x", ")", "  "]
+        );
+
+        // Changes to whitespace alone aren't highlighted.
+        let (old_ranges, new_ranges) = word_diff("a  b\n", "a b\n");
+        assert_eq!((old_ranges, new_ranges), (vec![], vec![]));
+    }
+}
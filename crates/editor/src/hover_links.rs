@@ -4,6 +4,7 @@ use crate::{
     scroll::ScrollAmount,
     Anchor, Editor, EditorSettings, EditorSnapshot, FindAllReferences, GoToDefinition,
     GoToTypeDefinition, GotoDefinitionKind, InlayId, Navigated, PointForPosition, SelectPhase,
+    SemanticsProvider,
 };
 use gpui::{px, AppContext, AsyncWindowContext, Model, Modifiers, Task, ViewContext};
 use language::{Bias, ToOffset};
@@ -14,7 +15,7 @@ use project::{
     ResolveState, ResolvedPath,
 };
 use settings::Settings;
-use std::ops::Range;
+use std::{ops::Range, rc::Rc};
 use theme::ActiveTheme as _;
 use util::{maybe, ResultExt, TryFutureExt as _};
 
@@ -513,7 +514,19 @@ pub fn show_link_definition(
         async move {
             let result = match &trigger_point {
                 TriggerPoint::Text(_) => {
-                    if let Some((url_range, url)) = find_url(&buffer, buffer_position, cx.clone()) {
+                    if let Some((link_range, link)) =
+                        find_document_link(&buffer, buffer_position, provider.as_ref(), &mut cx)
+                            .await
+                    {
+                        let range = maybe!({
+                            let start = snapshot.anchor_in_excerpt(excerpt_id, link_range.start)?;
+                            let end = snapshot.anchor_in_excerpt(excerpt_id, link_range.end)?;
+                            Some(RangeInEditor::Text(start..end))
+                        });
+                        Some((range, vec![link]))
+                    } else if let Some((url_range, url)) =
+                        find_url(&buffer, buffer_position, cx.clone())
+                    {
                         this.update(&mut cx, |_, _| {
                             let range = maybe!({
                                 let start =
@@ -632,6 +645,48 @@ pub fn show_link_definition(
     editor.hovered_link_state = Some(hovered_link_state);
 }
 
+/// Finds the document link the language server reported at `position`, resolving its target
+/// when the server left it out of the initial response.
+pub(crate) async fn find_document_link(
+    buffer: &Model<language::Buffer>,
+    position: text::Anchor,
+    provider: Option<&Rc<dyn SemanticsProvider>>,
+    cx: &mut AsyncWindowContext,
+) -> Option<(Range<text::Anchor>, HoverLink)> {
+    let provider = provider?;
+    let links = cx
+        .update(|cx| provider.document_links(buffer, cx))
+        .ok()??
+        .await
+        .log_err()?;
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot()).ok()?;
+    let link = links.into_iter().find(|link| {
+        link.range.start.cmp(&position, &snapshot).is_le()
+            && link.range.end.cmp(&position, &snapshot).is_ge()
+    })?;
+    let range = link.range.clone();
+    let link = if link.lsp_link.target.is_some() {
+        link
+    } else {
+        cx.update(|cx| provider.resolve_document_link(link, buffer.clone(), cx))
+            .ok()??
+            .await
+            .log_err()?
+    };
+
+    let target = link.lsp_link.target?;
+    let hover_link = if target.scheme() == "file" {
+        let path = target.to_file_path().ok()?;
+        HoverLink::File(ResolvedPath::AbsPath {
+            path,
+            is_dir: false,
+        })
+    } else {
+        HoverLink::Url(target.to_string())
+    };
+    Some((range, hover_link))
+}
+
 pub(crate) fn find_url(
     buffer: &Model<language::Buffer>,
     position: text::Anchor,
@@ -1424,6 +1479,72 @@ mod tests {
         );
     }
 
+    #[gpui::test]
+    async fn test_document_links(cx: &mut gpui::TestAppContext) {
+        init_test(cx, |_| {});
+        let mut cx = EditorLspTestContext::new_rust(
+            lsp::ServerCapabilities {
+                document_link_provider: Some(lsp::DocumentLinkOptions {
+                    resolve_provider: Some(true),
+                    work_done_progress_options: Default::default(),
+                }),
+                ..Default::default()
+            },
+            cx,
+        )
+        .await;
+
+        cx.set_state(indoc! {"
+            // See issue ˇ1234 for details.
+        "});
+        let link_range = cx.lsp_range(indoc! {"
+            // See «issue 1234» for details.
+        "});
+        let screen_coord = cx.pixel_position(indoc! {"
+            // See issue 12ˇ34 for details.
+        "});
+
+        let mut requests = cx.handle_request::<lsp::request::DocumentLinkRequest, _, _>(
+            move |_, _, _| async move {
+                Ok(Some(vec![lsp::DocumentLink {
+                    range: link_range,
+                    target: None,
+                    tooltip: None,
+                    data: Some(serde_json::json!({ "issue": 1234 })),
+                }]))
+            },
+        );
+        let mut resolves = cx.handle_request::<lsp::request::DocumentLinkResolve, _, _>(
+            move |_, link, _| async move {
+                assert_eq!(link.data, Some(serde_json::json!({ "issue": 1234 })));
+                Ok(lsp::DocumentLink {
+                    target: Some(lsp::Url::parse("https://zed.dev/issues/1234").unwrap()),
+                    ..link
+                })
+            },
+        );
+
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        requests.next().await;
+        resolves.next().await;
+        cx.background_executor.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            // See «issue 1234ˇ» for details.
+        "});
+
+        // The resolved link is cached, so hovering it again doesn't resolve it again.
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::none());
+        cx.simulate_mouse_move(screen_coord, None, Modifiers::secondary_key());
+        cx.background_executor.run_until_parked();
+        cx.assert_editor_text_highlights::<HoveredLinkState>(indoc! {"
+            // See «issue 1234ˇ» for details.
+        "});
+        assert!(resolves.try_next().is_err());
+
+        cx.simulate_click(screen_coord, Modifiers::secondary_key());
+        assert_eq!(cx.opened_url(), Some("https://zed.dev/issues/1234".into()));
+    }
+
     #[gpui::test]
     async fn test_urls_at_beginning_of_buffer(cx: &mut gpui::TestAppContext) {
         init_test(cx, |_| {});
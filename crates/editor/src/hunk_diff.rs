@@ -1,3 +1,4 @@
+use anyhow::{anyhow, Result};
 use collections::{HashMap, HashSet};
 use git::diff::DiffHunkStatus;
 use gpui::{
@@ -11,19 +12,20 @@ use multi_buffer::{
 use project::buffer_store::BufferChangeSet;
 use std::{ops::Range, sync::Arc};
 use sum_tree::TreeMap;
-use text::OffsetRangeExt;
+use text::{Bias, LineEnding, OffsetRangeExt, Rope};
 use ui::{
     prelude::*, ActiveTheme, ContextMenu, IconButtonShape, InteractiveElement, IntoElement,
     ParentElement, PopoverMenu, Styled, Tooltip, ViewContext, VisualContext,
 };
 use util::RangeExt;
-use workspace::Item;
+use workspace::{notifications::NotifyTaskExt, Item};
 
 use crate::{
-    editor_settings::CurrentLineHighlight, hunk_status, hunks_for_selections, ApplyAllDiffHunks,
-    ApplyDiffHunk, BlockPlacement, BlockProperties, BlockStyle, CustomBlockId, DiffRowHighlight,
-    DisplayRow, DisplaySnapshot, Editor, EditorElement, ExpandAllHunkDiffs, GoToHunk, GoToPrevHunk,
-    RevertFile, RevertSelectedHunks, ToDisplayPoint, ToggleHunkDiff,
+    editor_settings::CurrentLineHighlight, get_change_sets_for_buffers, hunk_status,
+    hunks_for_selections, ApplyAllDiffHunks, ApplyDiffHunk, BlockPlacement, BlockProperties,
+    BlockStyle, CustomBlockId, DiffRowHighlight, DisplayRow, DisplaySnapshot, Editor,
+    EditorElement, ExpandAllHunkDiffs, GoToHunk, GoToPrevHunk, RevertFile, RevertSelectedHunks,
+    StageHunk, StageSelectedLines, ToDisplayPoint, ToggleHunkDiff, UnstageHunk,
 };
 
 #[derive(Debug, Clone)]
@@ -52,7 +54,10 @@ pub(super) struct ExpandedHunk {
 }
 
 #[derive(Clone, Debug, Default)]
-pub(crate) struct DiffMapSnapshot(TreeMap<BufferId, git::diff::BufferDiff>);
+pub(crate) struct DiffMapSnapshot {
+    diffs: TreeMap<BufferId, git::diff::BufferDiff>,
+    committed_diffs: TreeMap<BufferId, git::diff::BufferDiff>,
+}
 
 pub(crate) struct DiffBaseState {
     pub(crate) change_set: Model<BufferChangeSet>,
@@ -85,19 +90,13 @@ impl DiffMap {
         cx: &mut ViewContext<Editor>,
     ) {
         let buffer_id = change_set.read(cx).buffer_id;
-        self.snapshot
-            .0
-            .insert(buffer_id, change_set.read(cx).diff_to_buffer.clone());
+        self.snapshot.insert(change_set.read(cx));
         self.diff_bases.insert(
             buffer_id,
             DiffBaseState {
                 last_version: None,
                 _subscription: cx.observe(&change_set, move |editor, change_set, cx| {
-                    editor
-                        .diff_map
-                        .snapshot
-                        .0
-                        .insert(buffer_id, change_set.read(cx).diff_to_buffer.clone());
+                    editor.diff_map.snapshot.insert(change_set.read(cx));
                     Editor::sync_expanded_diff_hunks(&mut editor.diff_map, buffer_id, cx);
                 }),
                 change_set,
@@ -106,6 +105,19 @@ impl DiffMap {
         Editor::sync_expanded_diff_hunks(self, buffer_id, cx);
     }
 
+    /// Whether the buffer is compared with the given git revision, or with the index when
+    /// `revision` is `None`.
+    pub(crate) fn has_base_revision(
+        &self,
+        buffer_id: BufferId,
+        revision: &Option<String>,
+        cx: &AppContext,
+    ) -> bool {
+        self.diff_bases.get(&buffer_id).map_or(false, |diff_base| {
+            diff_base.change_set.read(cx).base_revision == *revision
+        })
+    }
+
     pub fn hunks(&self, include_folded: bool) -> impl Iterator<Item = &ExpandedHunk> {
         self.hunks
             .iter()
@@ -114,8 +126,15 @@ impl DiffMap {
 }
 
 impl DiffMapSnapshot {
+    fn insert(&mut self, change_set: &BufferChangeSet) {
+        self.diffs
+            .insert(change_set.buffer_id, change_set.diff_to_buffer.clone());
+        self.committed_diffs
+            .insert(change_set.buffer_id, change_set.diff_to_committed.clone());
+    }
+
     pub fn is_empty(&self) -> bool {
-        self.0.values().all(|diff| diff.is_empty())
+        self.diffs.values().all(|diff| diff.is_empty())
     }
 
     pub fn diff_hunks<'a>(
@@ -136,7 +155,7 @@ impl DiffMapSnapshot {
             .filter_map(move |excerpt| {
                 let buffer = excerpt.buffer();
                 let buffer_id = buffer.remote_id();
-                let diff = self.0.get(&buffer_id)?;
+                let diff = self.diffs.get(&buffer_id)?;
                 let buffer_range = excerpt.map_range_to_buffer(range.clone());
                 let buffer_range =
                     buffer.anchor_before(buffer_range.start)..buffer.anchor_after(buffer_range.end);
@@ -170,7 +189,7 @@ impl DiffMapSnapshot {
             .filter_map(move |excerpt| {
                 let buffer = excerpt.buffer();
                 let buffer_id = buffer.remote_id();
-                let diff = self.0.get(&buffer_id)?;
+                let diff = self.diffs.get(&buffer_id)?;
                 let buffer_range = excerpt.map_range_to_buffer(range.clone());
                 let buffer_range =
                     buffer.anchor_before(buffer_range.start)..buffer.anchor_after(buffer_range.end);
@@ -194,6 +213,45 @@ impl DiffMapSnapshot {
             })
             .flatten()
     }
+
+    /// Returns the hunks of the diff between `HEAD` and each buffer whose changes are entirely
+    /// staged. These don't appear among [`Self::diff_hunks_in_range`], which diffs against the
+    /// index.
+    pub fn staged_hunks_in_range<'a, T: ToOffset>(
+        &'a self,
+        range: Range<T>,
+        buffer_snapshot: &'a MultiBufferSnapshot,
+    ) -> impl Iterator<Item = MultiBufferDiffHunk> + 'a {
+        let range = range.start.to_offset(buffer_snapshot)..range.end.to_offset(buffer_snapshot);
+        buffer_snapshot
+            .excerpts_for_range(range.clone())
+            .filter_map(move |excerpt| {
+                let buffer = excerpt.buffer();
+                let buffer_id = buffer.remote_id();
+                let diff = self.diffs.get(&buffer_id)?;
+                let committed_diff = self.committed_diffs.get(&buffer_id)?;
+                let buffer_range = excerpt.map_range_to_buffer(range.clone());
+                let buffer_range =
+                    buffer.anchor_before(buffer_range.start)..buffer.anchor_after(buffer_range.end);
+                Some(
+                    committed_diff
+                        .hunks_outside_of(diff, buffer_range, excerpt.buffer())
+                        .map(move |hunk| {
+                            let start =
+                                excerpt.map_point_from_buffer(Point::new(hunk.row_range.start, 0));
+                            let end =
+                                excerpt.map_point_from_buffer(Point::new(hunk.row_range.end, 0));
+                            MultiBufferDiffHunk {
+                                row_range: MultiBufferRow(start.row)..MultiBufferRow(end.row),
+                                buffer_id,
+                                buffer_range: hunk.buffer_range.clone(),
+                                diff_base_byte_range: hunk.diff_base_byte_range.clone(),
+                            }
+                        }),
+                )
+            })
+            .flatten()
+    }
 }
 
 impl Editor {
@@ -201,6 +259,51 @@ impl Editor {
         self.diff_map.expand_all = true;
     }
 
+    /// Shows the hunks of a change set that is not tracked by the project, e.g. one comparing
+    /// the contents of a file before and after a commit.
+    pub fn add_change_set(
+        &mut self,
+        change_set: Model<BufferChangeSet>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        self.diff_map.add_change_set(change_set, cx);
+    }
+
+    /// Compares the buffers of the editor with their contents at the given git revision, such as
+    /// a branch, a tag or a SHA, instead of the index. Passing `None` compares them with the index
+    /// again. Hunks can't be staged while comparing with a revision.
+    ///
+    /// Buffers already compared with the revision, e.g. through [`Self::add_change_set`], are
+    /// left as is.
+    pub fn set_diff_base_revision(
+        &mut self,
+        revision: Option<String>,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(project) = self.project.clone() else {
+            return Task::ready(Err(anyhow!("editor has no project")));
+        };
+        self.diff_base_revision = revision.clone();
+        let buffers = self
+            .buffer
+            .read(cx)
+            .all_buffers()
+            .into_iter()
+            .filter(|buffer| {
+                let buffer = buffer.read(cx);
+                buffer.file().is_some()
+                    && !self
+                        .diff_map
+                        .has_base_revision(buffer.remote_id(), &revision, cx)
+            })
+            .collect::<Vec<_>>();
+        get_change_sets_for_buffers(&project, buffers, revision, cx)
+    }
+
+    pub fn diff_base_revision(&self) -> Option<&str> {
+        self.diff_base_revision.as_deref()
+    }
+
     pub(super) fn toggle_hovered_hunk(
         &mut self,
         hovered_hunk: &HoveredHunk,
@@ -521,6 +624,205 @@ impl Editor {
         }
     }
 
+    pub(crate) fn stage_hunk(&mut self, _: &StageHunk, cx: &mut ViewContext<Self>) {
+        let snapshot = self.snapshot(cx);
+        let hunks = hunks_for_selections(&snapshot, &self.selections.all(cx))
+            .into_iter()
+            .map(|hunk| {
+                let rows = vec![hunk.row_range.clone()];
+                (hunk, rows)
+            })
+            .collect();
+        self.stage_hunks(hunks, cx);
+    }
+
+    fn stage_hovered_hunk(&mut self, hunk: &HoveredHunk, cx: &mut ViewContext<Self>) {
+        let snapshot = self.buffer.read(cx).snapshot(cx);
+        if let Some(hunk) = to_diff_hunk(hunk, &snapshot) {
+            let rows = vec![hunk.row_range.clone()];
+            self.stage_hunks(vec![(hunk, rows)], cx);
+        }
+    }
+
+    /// Stages the added lines of the selected hunks that are within the selections. The removed
+    /// lines of these hunks are staged as a whole, as they can't be selected in the buffer.
+    pub(crate) fn stage_selected_lines(
+        &mut self,
+        _: &StageSelectedLines,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let snapshot = self.snapshot(cx);
+        let selections = self.selections.all::<Point>(cx);
+        let hunks = hunks_for_selections(&snapshot, &selections)
+            .into_iter()
+            .map(|hunk| {
+                let mut rows: Vec<Range<MultiBufferRow>> = Vec::new();
+                for selection in &selections {
+                    let start = MultiBufferRow(selection.start.row).max(hunk.row_range.start);
+                    let end = MultiBufferRow(selection.end.row + 1).min(hunk.row_range.end);
+                    if start >= end {
+                        continue;
+                    }
+                    match rows.last_mut() {
+                        Some(last) if last.end >= start => last.end = last.end.max(end),
+                        _ => rows.push(start..end),
+                    }
+                }
+                (hunk, rows)
+            })
+            .collect();
+        self.stage_hunks(hunks, cx);
+    }
+
+    /// Writes the given hunks to the index, each of them with the multibuffer rows whose lines
+    /// should be staged.
+    fn stage_hunks(
+        &mut self,
+        hunks: Vec<(MultiBufferDiffHunk, Vec<Range<MultiBufferRow>>)>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let multi_buffer_snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut hunks_by_buffer = HashMap::<BufferId, Vec<_>>::default();
+        for (hunk, rows) in hunks {
+            hunks_by_buffer
+                .entry(hunk.buffer_id)
+                .or_default()
+                .push((hunk, rows));
+        }
+
+        for (buffer_id, hunks) in hunks_by_buffer {
+            let Some(buffer) = self.buffer.read(cx).buffer(buffer_id) else {
+                continue;
+            };
+            let Some((index_text, line_ending)) = self.index_text(buffer_id, cx) else {
+                continue;
+            };
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            let edits = hunks
+                .into_iter()
+                .map(|(hunk, rows)| {
+                    let new_text =
+                        text_for_rows(&hunk, &rows, &multi_buffer_snapshot, &buffer_snapshot);
+                    (hunk.diff_base_byte_range, new_text)
+                })
+                .collect();
+            let Some(new_index_text) = splice_text(&index_text, edits, line_ending) else {
+                continue;
+            };
+            project
+                .update(cx, |project, cx| {
+                    project.set_index_text(&buffer, Some(new_index_text), cx)
+                })
+                .detach_and_notify_err(cx);
+        }
+    }
+
+    /// Restores the index contents of the staged hunks within the selections to those at `HEAD`.
+    pub(crate) fn unstage_hunk(&mut self, _: &UnstageHunk, cx: &mut ViewContext<Self>) {
+        let Some(project) = self.project.clone() else {
+            return;
+        };
+        let snapshot = self.snapshot(cx);
+        let mut hunks_by_buffer = HashMap::<BufferId, Vec<MultiBufferDiffHunk>>::default();
+        for selection in self.selections.all::<Point>(cx) {
+            let rows = MultiBufferRow(selection.start.row)..MultiBufferRow(selection.end.row + 1);
+            for hunk in snapshot.diff_map.staged_hunks_in_range(
+                Point::new(rows.start.0, 0)..Point::new(rows.end.0, 0),
+                &snapshot.buffer_snapshot,
+            ) {
+                let related_to_selection = hunk.row_range.overlaps(&rows)
+                    || (hunk.row_range.is_empty()
+                        && (hunk.row_range.start == rows.end || hunk.row_range.end == rows.start));
+                let buffer_hunks = hunks_by_buffer.entry(hunk.buffer_id).or_default();
+                if related_to_selection
+                    && !buffer_hunks
+                        .iter()
+                        .any(|buffer_hunk| buffer_hunk.buffer_range == hunk.buffer_range)
+                {
+                    buffer_hunks.push(hunk);
+                }
+            }
+        }
+
+        for (buffer_id, hunks) in hunks_by_buffer {
+            let Some(buffer) = self.buffer.read(cx).buffer(buffer_id) else {
+                continue;
+            };
+            let Some((index_text, line_ending)) = self.index_text(buffer_id, cx) else {
+                continue;
+            };
+            let Some(change_set) = self
+                .diff_map
+                .diff_bases
+                .get(&buffer_id)
+                .map(|diff_base| diff_base.change_set.read(cx))
+            else {
+                continue;
+            };
+            let Some(committed_text) = change_set.committed_text.clone() else {
+                continue;
+            };
+            let buffer_snapshot = buffer.read(cx).text_snapshot();
+            let edits = hunks
+                .into_iter()
+                .map(|hunk| {
+                    // The staged hunk doesn't overlap any unstaged change, so it is offset in the
+                    // index text only by the unstaged changes preceding it.
+                    let hunk_range = hunk.buffer_range.to_offset(&buffer_snapshot);
+                    let offset_in_index = change_set
+                        .diff_hunks_intersecting_range(
+                            text::Anchor::MIN..hunk.buffer_range.start,
+                            &buffer_snapshot,
+                        )
+                        .map(|unstaged_hunk| {
+                            (
+                                unstaged_hunk.buffer_range.to_offset(&buffer_snapshot),
+                                unstaged_hunk,
+                            )
+                        })
+                        .filter(|(range, _)| range.end <= hunk_range.start)
+                        .fold(0isize, |offset, (range, unstaged_hunk)| {
+                            offset + unstaged_hunk.diff_base_byte_range.len() as isize
+                                - range.len() as isize
+                        });
+                    let index_range = usize::try_from(hunk_range.start as isize + offset_in_index)
+                        .ok()?
+                        ..usize::try_from(hunk_range.end as isize + offset_in_index).ok()?;
+                    let committed_hunk_text = committed_text.get(hunk.diff_base_byte_range)?;
+                    Some((index_range, committed_hunk_text.to_string()))
+                })
+                .collect::<Option<Vec<_>>>();
+            let Some(new_index_text) =
+                edits.and_then(|edits| splice_text(&index_text, edits, line_ending))
+            else {
+                continue;
+            };
+            project
+                .update(cx, |project, cx| {
+                    project.set_index_text(&buffer, Some(new_index_text), cx)
+                })
+                .detach_and_notify_err(cx);
+        }
+    }
+
+    /// Returns the index text of the buffer and its line ending, as long as the diff hunks shown
+    /// for the buffer were computed against the current index and committed texts.
+    fn index_text(&self, buffer_id: BufferId, cx: &AppContext) -> Option<(Rope, LineEnding)> {
+        let diff_base = self.diff_map.diff_bases.get(&buffer_id)?;
+        let change_set = diff_base.change_set.read(cx);
+        if change_set.base_revision.is_some()
+            || change_set.recalculate_diff_task.is_some()
+            || diff_base.last_version != Some(change_set.base_text_version)
+        {
+            return None;
+        }
+        let base_text = change_set.base_text.as_ref()?.read(cx);
+        Some((base_text.as_rope().clone(), base_text.line_ending()))
+    }
+
     fn has_multiple_hunks(&self, cx: &AppContext) -> bool {
         let snapshot = self.buffer.read(cx).snapshot(cx);
         let mut hunks = self.diff_map.snapshot.diff_hunks(&snapshot);
@@ -539,6 +841,7 @@ impl Editor {
             .map_or(false, |(buffer, _, _)| {
                 buffer.read(cx).base_buffer().is_some()
             });
+        let is_read_only = self.read_only(cx);
 
         let border_color = cx.theme().colors().border_variant;
         let bg_color = cx.theme().colors().editor_background;
@@ -655,31 +958,62 @@ impl Editor {
                                                     }),
                                             )
                                         })
-                                        .child(
-                                            IconButton::new("discard", IconName::Undo)
-                                                .shape(IconButtonShape::Square)
-                                                .icon_size(IconSize::Small)
-                                                .tooltip({
-                                                    let focus_handle = editor.focus_handle(cx);
-                                                    move |cx| {
-                                                        Tooltip::for_action_in(
-                                                            "Discard Hunk",
-                                                            &RevertSelectedHunks,
-                                                            &focus_handle,
-                                                            cx,
-                                                        )
-                                                    }
-                                                })
-                                                .on_click({
-                                                    let editor = editor.clone();
-                                                    let hunk = hunk.clone();
-                                                    move |_event, cx| {
-                                                        editor.update(cx, |editor, cx| {
-                                                            editor.revert_hunk(hunk.clone(), cx);
-                                                        });
-                                                    }
-                                                }),
-                                        )
+                                        .when(!is_branch_buffer && !is_read_only, |row| {
+                                            row.child(
+                                                IconButton::new("stage", IconName::Plus)
+                                                    .shape(IconButtonShape::Square)
+                                                    .icon_size(IconSize::Small)
+                                                    .tooltip({
+                                                        let focus_handle = editor.focus_handle(cx);
+                                                        move |cx| {
+                                                            Tooltip::for_action_in(
+                                                                "Stage Hunk",
+                                                                &StageHunk,
+                                                                &focus_handle,
+                                                                cx,
+                                                            )
+                                                        }
+                                                    })
+                                                    .on_click({
+                                                        let editor = editor.clone();
+                                                        let hunk = hunk.clone();
+                                                        move |_event, cx| {
+                                                            editor.update(cx, |editor, cx| {
+                                                                editor
+                                                                    .stage_hovered_hunk(&hunk, cx);
+                                                            });
+                                                        }
+                                                    }),
+                                            )
+                                        })
+                                        .when(!is_read_only, |row| {
+                                            row.child(
+                                                IconButton::new("discard", IconName::Undo)
+                                                    .shape(IconButtonShape::Square)
+                                                    .icon_size(IconSize::Small)
+                                                    .tooltip({
+                                                        let focus_handle = editor.focus_handle(cx);
+                                                        move |cx| {
+                                                            Tooltip::for_action_in(
+                                                                "Discard Hunk",
+                                                                &RevertSelectedHunks,
+                                                                &focus_handle,
+                                                                cx,
+                                                            )
+                                                        }
+                                                    })
+                                                    .on_click({
+                                                        let editor = editor.clone();
+                                                        let hunk = hunk.clone();
+                                                        move |_event, cx| {
+                                                            editor.update(cx, |editor, cx| {
+                                                                editor
+                                                                    .revert_hunk(hunk.clone(), cx);
+                                                            });
+                                                        }
+                                                    }),
+                                            )
+                                        })
                                         .map(|this| {
                                             if is_branch_buffer {
                                                 this.child(
@@ -1228,6 +1562,66 @@ impl DisplayDiffHunk {
     }
 }
 
+/// Returns the text of the given multibuffer rows within the hunk.
+fn text_for_rows(
+    hunk: &MultiBufferDiffHunk,
+    rows: &[Range<MultiBufferRow>],
+    multi_buffer_snapshot: &MultiBufferSnapshot,
+    buffer_snapshot: &language::BufferSnapshot,
+) -> String {
+    let hunk_range = hunk.buffer_range.to_offset(buffer_snapshot);
+    let mut text = String::new();
+    for rows in rows {
+        if rows.is_empty() {
+            continue;
+        }
+        let start = multi_buffer_snapshot.point_to_buffer_offset(Point::new(rows.start.0, 0));
+        let last_row_start =
+            multi_buffer_snapshot.point_to_buffer_offset(Point::new(rows.end.0 - 1, 0));
+        let Some(((_, start), (_, last_row_start))) = start.zip(last_row_start) else {
+            continue;
+        };
+        let last_row = buffer_snapshot.offset_to_point(last_row_start).row;
+        let end = buffer_snapshot
+            .point_to_offset(Point::new(last_row + 1, 0).min(buffer_snapshot.max_point()));
+        text.extend(
+            buffer_snapshot.text_for_range(start.max(hunk_range.start)..end.min(hunk_range.end)),
+        );
+    }
+    text
+}
+
+/// Replaces the given byte ranges of `text`, skipping edits that overlap a previous one, and
+/// writes its line breaks with the given line ending. Returns `None` when a range doesn't fit
+/// the text, as the edits were computed against another version of it.
+fn splice_text(
+    text: &Rope,
+    mut edits: Vec<(Range<usize>, String)>,
+    line_ending: LineEnding,
+) -> Option<String> {
+    edits.sort_by_key(|(range, _)| range.start);
+    let mut result = String::with_capacity(text.len());
+    let mut offset = 0;
+    for (range, new_text) in edits {
+        if range.start < offset {
+            continue;
+        }
+        let is_valid =
+            |offset| offset <= text.len() && text.clip_offset(offset, Bias::Left) == offset;
+        if range.start > range.end || !is_valid(range.start) || !is_valid(range.end) {
+            return None;
+        }
+        result.extend(text.chunks_in_range(offset..range.start));
+        result.push_str(&new_text);
+        offset = range.end;
+    }
+    result.extend(text.chunks_in_range(offset..text.len()));
+    if line_ending == LineEnding::Windows {
+        result = result.replace('\n', line_ending.as_str());
+    }
+    Some(result)
+}
+
 pub fn diff_hunk_to_display(
     hunk: &MultiBufferDiffHunk,
     snapshot: &DisplaySnapshot,
@@ -1494,4 +1888,21 @@ mod tests {
                 .as_slice(),
         );
     }
+
+    #[test]
+    fn test_splice_text() {
+        let text = Rope::from("one\ntwo\nthree\n");
+        assert_eq!(
+            splice_text(&text, vec![(4..8, "TWO\n".into())], LineEnding::Unix).as_deref(),
+            Some("one\nTWO\nthree\n")
+        );
+        assert_eq!(
+            splice_text(&text, vec![(4..8, "TWO\n".into())], LineEnding::Windows).as_deref(),
+            Some("one\r\nTWO\r\nthree\r\n")
+        );
+        assert_eq!(
+            splice_text(&text, vec![(8..20, String::new())], LineEnding::Unix),
+            None
+        );
+    }
 }
@@ -0,0 +1,366 @@
+use std::{ops::Range, time::Duration};
+
+use anyhow::{anyhow, Context as _, Result};
+use futures::future::join_all;
+use git::{
+    conflict::{parse_conflicts, ConflictResolution},
+    repository::GitFileStatus,
+};
+use gpui::{AppContext, Model, Task, ViewContext, WindowContext};
+use language::{Buffer, BufferSnapshot, LanguageServerId, Point};
+use multi_buffer::{Anchor, ExcerptId, ToPoint as _};
+use project::{CodeAction, ProjectTransaction};
+use text::OffsetRangeExt as _;
+use ui::ActiveTheme as _;
+
+use crate::{
+    scroll::Autoscroll, CodeActionProvider, Direction, Editor, EditorMode, GoToConflict,
+    GoToPrevConflict,
+};
+
+const MERGE_CONFLICTS_DEBOUNCE: Duration = Duration::from_millis(100);
+
+struct ConflictMarkerRows;
+struct ConflictOursRows;
+struct ConflictBaseRows;
+struct ConflictTheirsRows;
+
+/// A region of a buffer with conflicting changes left by a merge, anchored so that it follows the
+/// edits made while resolving the other conflicts.
+#[derive(Clone, Debug)]
+pub(crate) struct MergeConflict {
+    pub range: Range<text::Anchor>,
+    pub ours: Range<text::Anchor>,
+    pub base: Option<Range<text::Anchor>>,
+    pub theirs: Range<text::Anchor>,
+}
+
+/// Whether git reports the buffer's file as unmerged, in which case its conflict markers are
+/// recognized.
+fn is_unmerged(buffer: &Buffer, cx: &AppContext) -> bool {
+    let Some(file) = project::File::from_dyn(buffer.file()) else {
+        return false;
+    };
+    file.worktree
+        .read(cx)
+        .entry_for_path(&file.path)
+        .map_or(false, |entry| {
+            entry.git_status == Some(GitFileStatus::Conflict)
+        })
+}
+
+pub(crate) fn buffer_conflicts(snapshot: &BufferSnapshot) -> Vec<MergeConflict> {
+    let anchor_range =
+        |range: Range<usize>| snapshot.anchor_after(range.start)..snapshot.anchor_before(range.end);
+    parse_conflicts(&snapshot.text())
+        .into_iter()
+        .map(|conflict| MergeConflict {
+            range: snapshot.anchor_before(conflict.range.start)
+                ..snapshot.anchor_after(conflict.range.end),
+            ours: anchor_range(conflict.ours),
+            base: conflict.base.map(anchor_range),
+            theirs: anchor_range(conflict.theirs),
+        })
+        .collect()
+}
+
+/// Finds the merge conflicts of the editor's unmerged buffers and highlights their sides. When
+/// `debounce` is set, waits a little first, so that the buffers aren't parsed on every keystroke.
+pub(super) fn refresh_merge_conflicts(
+    editor: &mut Editor,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+
+    let buffers = editor
+        .buffer
+        .read(cx)
+        .all_buffers()
+        .into_iter()
+        .filter(|buffer| is_unmerged(buffer.read(cx), cx))
+        .collect::<Vec<_>>();
+    if buffers.is_empty() && editor.merge_conflicts.is_empty() {
+        return;
+    }
+
+    editor.merge_conflicts_task = Some(cx.spawn(|editor, mut cx| async move {
+        if debounce && !buffers.is_empty() {
+            cx.background_executor()
+                .timer(MERGE_CONFLICTS_DEBOUNCE)
+                .await;
+        }
+
+        let Ok(parses) = editor.update(&mut cx, |_, cx| {
+            buffers
+                .into_iter()
+                .map(|buffer| {
+                    let snapshot = buffer.read(cx).snapshot();
+                    cx.background_executor().spawn(async move {
+                        let conflicts = buffer_conflicts(&snapshot);
+                        (snapshot.remote_id(), conflicts)
+                    })
+                })
+                .collect::<Vec<_>>()
+        }) else {
+            return;
+        };
+        let conflicts = join_all(parses).await;
+
+        editor
+            .update(&mut cx, |editor, cx| {
+                editor.merge_conflicts = conflicts
+                    .into_iter()
+                    .filter(|(_, conflicts)| !conflicts.is_empty())
+                    .collect();
+                highlight_merge_conflicts(editor, cx);
+            })
+            .ok();
+    }));
+}
+
+fn highlight_merge_conflicts(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    editor.clear_row_highlights::<ConflictMarkerRows>();
+    editor.clear_row_highlights::<ConflictOursRows>();
+    editor.clear_row_highlights::<ConflictBaseRows>();
+    editor.clear_row_highlights::<ConflictTheirsRows>();
+
+    let mut marker_rows = Vec::new();
+    let mut ours_rows = Vec::new();
+    let mut base_rows = Vec::new();
+    let mut theirs_rows = Vec::new();
+    let multi_buffer_snapshot = editor.buffer.read(cx).snapshot(cx);
+    for (excerpt_id, buffer_snapshot, conflict) in editor.visible_merge_conflicts(cx) {
+        let rows = |rows: Range<u32>, highlights: &mut Vec<Range<Anchor>>| {
+            if rows.is_empty() {
+                return;
+            }
+            let start = buffer_snapshot.anchor_before(Point::new(rows.start, 0));
+            let last_row = rows.end - 1;
+            let end = buffer_snapshot
+                .anchor_after(Point::new(last_row, buffer_snapshot.line_len(last_row)));
+            if let Some((start, end)) = multi_buffer_snapshot
+                .anchor_in_excerpt(excerpt_id, start)
+                .zip(multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, end))
+            {
+                highlights.push(start..end);
+            }
+        };
+
+        let range = line_rows(&conflict.range, &buffer_snapshot);
+        let ours = line_rows(&conflict.ours, &buffer_snapshot);
+        let base = conflict
+            .base
+            .as_ref()
+            .map(|base| line_rows(base, &buffer_snapshot));
+        let theirs = line_rows(&conflict.theirs, &buffer_snapshot);
+
+        rows(range.start..range.start + 1, &mut marker_rows);
+        rows(ours.clone(), &mut ours_rows);
+        if let Some(base) = base {
+            rows(base.start - 1..base.start, &mut marker_rows);
+            rows(base, &mut base_rows);
+        }
+        rows(theirs.start - 1..theirs.start, &mut marker_rows);
+        rows(theirs, &mut theirs_rows);
+        rows(range.end - 1..range.end, &mut marker_rows);
+    }
+
+    let status = cx.theme().status();
+    let (marker_color, ours_color, base_color, theirs_color) = (
+        status.conflict_background,
+        status.created_background,
+        status.ignored_background,
+        status.modified_background,
+    );
+    for range in marker_rows {
+        editor.highlight_rows::<ConflictMarkerRows>(range, marker_color, false, cx);
+    }
+    for range in ours_rows {
+        editor.highlight_rows::<ConflictOursRows>(range, ours_color, false, cx);
+    }
+    for range in base_rows {
+        editor.highlight_rows::<ConflictBaseRows>(range, base_color, false, cx);
+    }
+    for range in theirs_rows {
+        editor.highlight_rows::<ConflictTheirsRows>(range, theirs_color, false, cx);
+    }
+    cx.notify();
+}
+
+/// Returns the rows of the lines spanned by `range`, which starts at the beginning of a line.
+fn line_rows(range: &Range<text::Anchor>, snapshot: &BufferSnapshot) -> Range<u32> {
+    let range = range.to_point(snapshot);
+    let end_row = if range.end.column == 0 {
+        range.end.row
+    } else {
+        range.end.row + 1
+    };
+    range.start.row..end_row.max(range.start.row)
+}
+
+impl Editor {
+    pub fn go_to_next_conflict(&mut self, _: &GoToConflict, cx: &mut ViewContext<Self>) {
+        self.go_to_conflict(Direction::Next, cx);
+    }
+
+    pub fn go_to_prev_conflict(&mut self, _: &GoToPrevConflict, cx: &mut ViewContext<Self>) {
+        self.go_to_conflict(Direction::Prev, cx);
+    }
+
+    fn go_to_conflict(&mut self, direction: Direction, cx: &mut ViewContext<Self>) {
+        let multi_buffer_snapshot = self.buffer.read(cx).snapshot(cx);
+        let mut conflict_starts = self
+            .visible_merge_conflicts(cx)
+            .into_iter()
+            .filter_map(|(excerpt_id, _, conflict)| {
+                multi_buffer_snapshot.anchor_in_excerpt(excerpt_id, conflict.range.start)
+            })
+            .map(|anchor| anchor.to_point(&multi_buffer_snapshot))
+            .collect::<Vec<_>>();
+        conflict_starts.sort();
+
+        let head = self.selections.newest::<Point>(cx).head();
+        let target = match direction {
+            Direction::Next => conflict_starts
+                .iter()
+                .find(|start| start.row > head.row)
+                .or(conflict_starts.first()),
+            Direction::Prev => conflict_starts
+                .iter()
+                .rev()
+                .find(|start| start.row < head.row)
+                .or(conflict_starts.last()),
+        };
+        if let Some(target) = target.copied() {
+            self.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                selections.select_ranges([target..target]);
+            });
+        }
+    }
+
+    /// Returns the merge conflicts lying entirely within an excerpt of the editor.
+    fn visible_merge_conflicts(
+        &self,
+        cx: &AppContext,
+    ) -> Vec<(ExcerptId, BufferSnapshot, MergeConflict)> {
+        let multi_buffer = self.buffer.read(cx);
+        let mut visible_conflicts = Vec::new();
+        for (buffer_id, conflicts) in &self.merge_conflicts {
+            let Some(buffer) = multi_buffer.buffer(*buffer_id) else {
+                continue;
+            };
+            let buffer_snapshot = buffer.read(cx).snapshot();
+            for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(&buffer, cx) {
+                let context = excerpt_range.context.to_point(&buffer_snapshot);
+                for conflict in conflicts {
+                    let rows = line_rows(&conflict.range, &buffer_snapshot);
+                    if context.start.row <= rows.start && rows.end <= context.end.row + 1 {
+                        visible_conflicts.push((
+                            excerpt_id,
+                            buffer_snapshot.clone(),
+                            conflict.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+        visible_conflicts
+    }
+}
+
+/// Offers to resolve the merge conflicts under the cursor by accepting either side, or both.
+pub(crate) struct MergeConflictCodeActions;
+
+impl MergeConflictCodeActions {
+    const RESOLUTIONS: [(ConflictResolution, &'static str); 3] = [
+        (ConflictResolution::Ours, "Accept Ours"),
+        (ConflictResolution::Theirs, "Accept Theirs"),
+        (ConflictResolution::Both, "Accept Both"),
+    ];
+}
+
+impl CodeActionProvider for MergeConflictCodeActions {
+    fn code_actions(
+        &self,
+        buffer: &Model<Buffer>,
+        range: Range<text::Anchor>,
+        cx: &mut WindowContext,
+    ) -> Task<Result<Vec<CodeAction>>> {
+        let buffer = buffer.read(cx);
+        if !is_unmerged(buffer, cx) {
+            return Task::ready(Ok(Vec::new()));
+        }
+
+        let snapshot = buffer.snapshot();
+        cx.background_executor().spawn(async move {
+            let range = range.to_offset(&snapshot);
+            let mut actions = Vec::new();
+            for conflict in buffer_conflicts(&snapshot) {
+                let conflict_range = conflict.range.to_offset(&snapshot);
+                if conflict_range.start > range.end || conflict_range.end <= range.start {
+                    continue;
+                }
+                for (resolution, title) in Self::RESOLUTIONS {
+                    actions.push(CodeAction {
+                        server_id: LanguageServerId(0),
+                        range: conflict.range.clone(),
+                        lsp_action: lsp::CodeAction {
+                            title: title.into(),
+                            kind: Some(lsp::CodeActionKind::QUICKFIX),
+                            data: Some(serde_json::to_value(resolution)?),
+                            ..Default::default()
+                        },
+                    });
+                }
+            }
+            Ok(actions)
+        })
+    }
+
+    fn apply_code_action(
+        &self,
+        buffer_handle: Model<Buffer>,
+        action: CodeAction,
+        _excerpt_id: ExcerptId,
+        push_to_history: bool,
+        cx: &mut WindowContext,
+    ) -> Task<Result<ProjectTransaction>> {
+        let result = util::maybe!({
+            let resolution: ConflictResolution = serde_json::from_value(
+                action
+                    .lsp_action
+                    .data
+                    .context("missing merge conflict resolution")?,
+            )?;
+            buffer_handle.update(cx, |buffer, cx| {
+                let range = action.range.to_offset(buffer);
+                let text = buffer.text_for_range(range.clone()).collect::<String>();
+                let conflict = parse_conflicts(&text)
+                    .into_iter()
+                    .next()
+                    .filter(|conflict| conflict.range == (0..text.len()))
+                    .ok_or_else(|| anyhow!("the merge conflict was already resolved"))?;
+                let resolved_text = conflict.resolved_text(&text, resolution);
+
+                buffer.finalize_last_transaction();
+                buffer.start_transaction();
+                buffer.edit([(range, resolved_text)], None, cx);
+                let mut project_transaction = ProjectTransaction::default();
+                if buffer.end_transaction(cx).is_some() {
+                    let transaction = buffer.finalize_last_transaction().unwrap().clone();
+                    if !push_to_history {
+                        buffer.forget_transaction(transaction.id);
+                    }
+                    project_transaction
+                        .0
+                        .insert(buffer_handle.clone(), transaction);
+                }
+                anyhow::Ok(project_transaction)
+            })
+        });
+        Task::ready(result)
+    }
+}
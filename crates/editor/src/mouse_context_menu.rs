@@ -3,7 +3,8 @@ use crate::{
     actions::Format, selections_collection::SelectionsCollection, Copy, CopyPermalinkToLine, Cut,
     DisplayPoint, DisplaySnapshot, Editor, EditorMode, FindAllReferences, GoToDeclaration,
     GoToDefinition, GoToImplementation, GoToTypeDefinition, Paste, Rename, RevealInFileManager,
-    SelectMode, ToDisplayPoint, ToggleCodeActions,
+    SelectMode, ShowIncomingCalls, ShowOutgoingCalls, ShowSubtypes, ShowSupertypes, ToDisplayPoint,
+    ToggleCodeActions,
 };
 use gpui::prelude::FluentBuilder;
 use gpui::{DismissEvent, Pixels, Point, Subscription, View, ViewContext};
@@ -164,6 +165,10 @@ pub fn deploy_context_menu(
                 .action("Go to Type Definition", Box::new(GoToTypeDefinition))
                 .action("Go to Implementation", Box::new(GoToImplementation))
                 .action("Find All References", Box::new(FindAllReferences))
+                .action("Show Incoming Calls", Box::new(ShowIncomingCalls))
+                .action("Show Outgoing Calls", Box::new(ShowOutgoingCalls))
+                .action("Show Supertypes", Box::new(ShowSupertypes))
+                .action("Show Subtypes", Box::new(ShowSubtypes))
                 .separator()
                 .action("Rename Symbol", Box::new(Rename))
                 .action("Format Buffer", Box::new(Format))
@@ -2,6 +2,7 @@ mod actions;
 pub(crate) mod autoscroll;
 pub(crate) mod scroll_amount;
 
+use crate::code_lens::resolve_visible_code_lens;
 use crate::editor_settings::{ScrollBeyondLastLine, ScrollbarAxes};
 use crate::{
     display_map::{DisplaySnapshot, ToDisplayPoint},
@@ -183,6 +184,7 @@ pub struct ScrollManager {
     show_scrollbars: bool,
     hide_scrollbar_task: Option<Task<()>>,
     dragging_scrollbar: AxisPair<bool>,
+    minimap_drag_offset: Option<Pixels>,
     visible_line_count: Option<f32>,
     forbid_vertical_scroll: bool,
 }
@@ -197,6 +199,7 @@ impl ScrollManager {
             show_scrollbars: true,
             hide_scrollbar_task: None,
             dragging_scrollbar: axis_pair(false, false),
+            minimap_drag_offset: None,
             last_autoscroll: None,
             visible_line_count: None,
             forbid_vertical_scroll: false,
@@ -371,6 +374,21 @@ impl ScrollManager {
         cx.notify();
     }
 
+    /// The distance between the top of the minimap's viewport slice and the mouse, while the
+    /// slice is being dragged.
+    pub fn minimap_drag_offset(&self) -> Option<Pixels> {
+        self.minimap_drag_offset
+    }
+
+    pub fn set_minimap_drag_offset(
+        &mut self,
+        offset: Option<Pixels>,
+        cx: &mut ViewContext<Editor>,
+    ) {
+        self.minimap_drag_offset = offset;
+        cx.notify();
+    }
+
     pub fn clamp_scroll_left(&mut self, max: f32) -> bool {
         if max < self.anchor.offset.x {
             self.anchor.offset.x = max;
@@ -415,7 +433,8 @@ impl Editor {
             cx.spawn(|editor, mut cx| async move {
                 editor
                     .update(&mut cx, |editor, cx| {
-                        editor.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx)
+                        editor.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+                        resolve_visible_code_lens(editor, false, cx);
                     })
                     .ok()
             })
@@ -479,6 +498,7 @@ impl Editor {
         );
 
         self.refresh_inlay_hints(InlayHintRefreshReason::NewLinesShown, cx);
+        resolve_visible_code_lens(self, true, cx);
     }
 
     pub fn scroll_position(&self, cx: &mut ViewContext<Self>) -> gpui::Point<f32> {
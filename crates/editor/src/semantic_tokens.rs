@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use collections::HashMap;
+use gpui::{HighlightStyle, Model, ViewContext};
+use language::Buffer;
+use project::SemanticTokens;
+use settings::Settings;
+use theme::{ActiveTheme, SyntaxTheme};
+use util::ResultExt;
+
+use crate::{editor_settings, Editor, EditorMode, EditorSettings};
+
+/// Requests the semantic tokens of `buffer`, or of every buffer in the editor when it is `None`,
+/// and layers their styles over the syntax highlighting. When `debounce` is set, waits for the
+/// configured edit debounce first.
+pub(super) fn refresh_semantic_tokens(
+    editor: &mut Editor,
+    buffer: Option<Model<Buffer>>,
+    debounce: bool,
+    cx: &mut ViewContext<Editor>,
+) {
+    if editor.mode != EditorMode::Full {
+        return;
+    }
+    let Some(project) = editor.project.clone() else {
+        return;
+    };
+    let settings = EditorSettings::get_global(cx).semantic_tokens.clone();
+    if !settings.enabled {
+        editor.semantic_tokens_tasks.clear();
+        editor.display_map.update(cx, |display_map, _| {
+            display_map.clear_semantic_token_highlights()
+        });
+        cx.notify();
+        return;
+    }
+
+    clear_removed_semantic_tokens(editor, cx);
+    let debounce_duration = Duration::from_millis(settings.edit_debounce_ms);
+    let debounce = (debounce && !debounce_duration.is_zero()).then_some(debounce_duration);
+    let buffers = match buffer {
+        Some(buffer) => vec![buffer],
+        None => editor.buffer.read(cx).all_buffers().into_iter().collect(),
+    };
+    for buffer in buffers {
+        let buffer_id = buffer.read(cx).remote_id();
+        let project = project.clone();
+        let settings = settings.clone();
+        let task = cx.spawn(|editor, mut cx| async move {
+            if let Some(debounce) = debounce {
+                cx.background_executor().timer(debounce).await;
+            }
+
+            let Some(tokens) = project
+                .update(&mut cx, |project, cx| {
+                    project.semantic_tokens(buffer.clone(), cx)
+                })
+                .log_err()
+            else {
+                return;
+            };
+            let Some(tokens) = tokens.await.log_err() else {
+                return;
+            };
+
+            editor
+                .update(&mut cx, |editor, cx| {
+                    let syntax_theme = cx.theme().syntax().clone();
+                    let multi_buffer = editor.buffer.read(cx);
+                    let multi_buffer_snapshot = multi_buffer.snapshot(cx);
+                    let styles = token_styles(&tokens, &settings, &syntax_theme);
+                    let buffer_snapshot = buffer.read(cx).snapshot();
+                    let mut highlights = Vec::new();
+                    for (excerpt_id, excerpt_range) in multi_buffer.excerpts_for_buffer(&buffer, cx)
+                    {
+                        let excerpt_range = excerpt_range.context;
+                        let start_ix = tokens.tokens.partition_point(|token| {
+                            token
+                                .range
+                                .end
+                                .cmp(&excerpt_range.start, &buffer_snapshot)
+                                .is_le()
+                        });
+                        for token in &tokens.tokens[start_ix..] {
+                            if token
+                                .range
+                                .start
+                                .cmp(&excerpt_range.end, &buffer_snapshot)
+                                .is_ge()
+                            {
+                                break;
+                            }
+                            let Some(Some(style)) =
+                                styles.get(&(token.token_type, token.token_modifiers))
+                            else {
+                                continue;
+                            };
+                            let start = multi_buffer_snapshot
+                                .anchor_in_excerpt(excerpt_id, token.range.start);
+                            let end = multi_buffer_snapshot
+                                .anchor_in_excerpt(excerpt_id, token.range.end);
+                            if let Some((start, end)) = start.zip(end) {
+                                highlights.push((start..end, *style));
+                            }
+                        }
+                    }
+
+                    editor.display_map.update(cx, |display_map, _| {
+                        display_map.set_semantic_token_highlights(buffer_id, highlights);
+                    });
+                    cx.notify();
+                })
+                .ok();
+        });
+        editor.semantic_tokens_tasks.insert(buffer_id, task);
+    }
+}
+
+/// Drops the semantic tokens of the buffers that are no longer in the editor.
+pub(super) fn clear_removed_semantic_tokens(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    let multi_buffer = editor.buffer.read(cx);
+    let removed_buffer_ids = editor
+        .display_map
+        .read(cx)
+        .semantic_token_buffers()
+        .filter(|buffer_id| multi_buffer.buffer(*buffer_id).is_none())
+        .collect::<Vec<_>>();
+    editor
+        .semantic_tokens_tasks
+        .retain(|buffer_id, _| multi_buffer.buffer(*buffer_id).is_some());
+    if removed_buffer_ids.is_empty() {
+        return;
+    }
+    editor.display_map.update(cx, |display_map, _| {
+        for buffer_id in removed_buffer_ids {
+            display_map.set_semantic_token_highlights(buffer_id, Vec::new());
+        }
+    });
+    cx.notify();
+}
+
+/// Resolves the style of every distinct token type and modifier combination in the response.
+/// Combinations that none of the configured styles apply to map to `None`.
+fn token_styles(
+    tokens: &SemanticTokens,
+    settings: &editor_settings::SemanticTokens,
+    syntax_theme: &SyntaxTheme,
+) -> HashMap<(u32, u32), Option<HighlightStyle>> {
+    let theme_style = |name: &String| {
+        let style = syntax_theme.get(name);
+        (style != HighlightStyle::default()).then_some(style)
+    };
+
+    let mut styles = HashMap::default();
+    for token in &tokens.tokens {
+        styles
+            .entry((token.token_type, token.token_modifiers))
+            .or_insert_with(|| {
+                let mut style = tokens
+                    .token_type(token)
+                    .and_then(|token_type| settings.token_types.get(token_type))
+                    .and_then(theme_style);
+                for modifier in tokens.token_modifiers(token) {
+                    let Some(modifier_style) =
+                        settings.token_modifiers.get(modifier).and_then(theme_style)
+                    else {
+                        continue;
+                    };
+                    style
+                        .get_or_insert_with(HighlightStyle::default)
+                        .highlight(modifier_style);
+                }
+                style
+            });
+    }
+    styles
+}
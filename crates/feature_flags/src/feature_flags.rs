@@ -69,6 +69,11 @@ impl FeatureFlag for GitUiFeatureFlag {
     const NAME: &'static str = "git-ui";
 }
 
+pub struct DebuggerFeatureFlag;
+impl FeatureFlag for DebuggerFeatureFlag {
+    const NAME: &'static str = "debugger";
+}
+
 pub struct Remoting {}
 impl FeatureFlag for Remoting {
     const NAME: &'static str = "remoting";
@@ -43,7 +43,7 @@ use util::ResultExt;
 #[cfg(any(test, feature = "test-support"))]
 use collections::{btree_map, BTreeMap};
 #[cfg(any(test, feature = "test-support"))]
-use git::repository::{FakeGitRepositoryState, GitFileStatus};
+use git::repository::{FakeGitRepositoryState, GitFileStatus, RepoPath};
 #[cfg(any(test, feature = "test-support"))]
 use parking_lot::Mutex;
 #[cfg(any(test, feature = "test-support"))]
@@ -1307,6 +1307,44 @@ impl FakeFs {
         });
     }
 
+    pub fn set_head_for_repo(&self, dot_git: &Path, head_state: &[(&Path, String)]) {
+        self.with_git_state(dot_git, true, |state| {
+            state.head_contents.clear();
+            state.head_contents.extend(
+                head_state
+                    .iter()
+                    .map(|(path, content)| (path.to_path_buf(), content.clone())),
+            );
+        });
+    }
+
+    pub fn set_commits_for_repo(
+        &self,
+        dot_git: &Path,
+        commits: Vec<(git::commit::CommitSummary, Vec<git::commit::CommitFileDiff>)>,
+    ) {
+        self.with_git_state(dot_git, true, |state| {
+            state.commits.clear();
+            state.commit_diffs.clear();
+            for (commit, files) in commits {
+                state.commit_diffs.insert(commit.sha, files);
+                state.commits.push(commit);
+            }
+        });
+    }
+
+    pub fn set_revision_for_repo(&self, dot_git: &Path, revision: &str, files: &[(&Path, String)]) {
+        self.with_git_state(dot_git, true, |state| {
+            state.revision_contents.insert(
+                revision.to_string(),
+                files
+                    .iter()
+                    .map(|(path, content)| (RepoPath::from(*path), content.clone()))
+                    .collect(),
+            );
+        });
+    }
+
     pub fn set_blame_for_repo(&self, dot_git: &Path, blames: Vec<(&Path, git::blame::Blame)>) {
         self.with_git_state(dot_git, true, |state| {
             state.blames.clear();
@@ -1955,6 +1993,14 @@ impl Fs for FakeFs {
         let state = self.state.lock();
         let entry = state.read_path(abs_dot_git).unwrap();
         let mut entry = entry.lock();
+        // Linked worktrees have a `.git` file pointing to their git directory instead.
+        if let FakeFsEntry::File { content, .. } = &*entry {
+            let git_dir =
+                git::worktree::parse_gitdir_file(&String::from_utf8_lossy(content), abs_dot_git)?;
+            drop(entry);
+            drop(state);
+            return self.open_repo(&normalize_path(&git_dir));
+        }
         if let FakeFsEntry::Dir { git_repo_state, .. } = &mut *entry {
             let state = git_repo_state
                 .get_or_insert_with(|| {
@@ -1,4 +1,4 @@
-use crate::Oid;
+use crate::{repository::RepoPath, Oid};
 use anyhow::{anyhow, Result};
 use collections::HashMap;
 use std::path::Path;
@@ -36,3 +36,119 @@ pub fn get_messages(working_directory: &Path, shas: &[Oid]) -> Result<HashMap<Oi
         )
         .collect::<HashMap<Oid, String>>())
 }
+
+/// A commit as listed by [`crate::repository::GitRepository::log`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitSummary {
+    pub sha: Oid,
+    pub parents: Vec<Oid>,
+    pub author_name: String,
+    pub author_email: String,
+    /// Author timestamp, normalized to Unix Epoch format.
+    pub timestamp: i64,
+    pub subject: String,
+    /// The branches and tags pointing at this commit, e.g. `HEAD -> main` or `tag: v1.0`.
+    pub refs: Vec<String>,
+}
+
+/// Restricts and pages the commits returned by [`crate::repository::GitRepository::log`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LogOptions {
+    /// Only list commits touching this path.
+    pub path: Option<RepoPath>,
+    /// Only list commits whose author name or email matches this pattern.
+    pub author: Option<String>,
+    /// Only list commits whose message matches this pattern.
+    pub message: Option<String>,
+    /// The number of commits to skip, to load further pages.
+    pub skip: usize,
+    /// The maximum number of commits to return, or zero for no limit.
+    pub limit: usize,
+}
+
+/// The changes a commit made to a single file, relative to its first parent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CommitFileDiff {
+    pub path: RepoPath,
+    /// The contents before the commit, or `None` if the file was added.
+    pub old_text: Option<String>,
+    /// The contents after the commit, or `None` if the file was deleted.
+    pub new_text: Option<String>,
+}
+
+const LOG_FIELD_SEPARATOR: char = '\x1f';
+const LOG_RECORD_SEPARATOR: char = '\x1e';
+
+/// The `--format` argument whose output [`parse_log`] understands.
+pub(crate) const LOG_FORMAT: &str = "--format=%H%x1f%P%x1f%an%x1f%ae%x1f%at%x1f%D%x1f%s%x1e";
+
+pub(crate) fn parse_log(output: &str) -> Result<Vec<CommitSummary>> {
+    output
+        .split(LOG_RECORD_SEPARATOR)
+        .map(str::trim_start)
+        .filter(|record| !record.is_empty())
+        .map(|record| {
+            let mut fields = record.split(LOG_FIELD_SEPARATOR);
+            let mut next_field = || {
+                fields
+                    .next()
+                    .ok_or_else(|| anyhow!("malformed git log record: {record:?}"))
+            };
+            let sha = next_field()?.parse()?;
+            let parents = next_field()?
+                .split_whitespace()
+                .map(str::parse)
+                .collect::<Result<Vec<Oid>>>()?;
+            let author_name = next_field()?.to_string();
+            let author_email = next_field()?.to_string();
+            let timestamp = next_field()?.parse()?;
+            let refs = next_field()?
+                .split(", ")
+                .filter(|name| !name.is_empty())
+                .map(ToString::to_string)
+                .collect();
+            let subject = next_field()?.to_string();
+            Ok(CommitSummary {
+                sha,
+                parents,
+                author_name,
+                author_email,
+                timestamp,
+                subject,
+                refs,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log() {
+        let output = concat!(
+            "1111111111111111111111111111111111111111\x1f",
+            "2222222222222222222222222222222222222222 3333333333333333333333333333333333333333\x1f",
+            "Jane Doe\x1fjane@example.com\x1f1700000000\x1f",
+            "HEAD -> main, tag: v1.0\x1fMerge branch 'feature'\x1e\n",
+            "2222222222222222222222222222222222222222\x1f\x1fJohn Doe\x1fjohn@example.com\x1f",
+            "1600000000\x1f\x1fInitial commit\x1e\n",
+        );
+
+        let commits = parse_log(output).unwrap();
+        assert_eq!(commits.len(), 2);
+        assert_eq!(
+            commits[0].sha.to_string(),
+            "1111111111111111111111111111111111111111"
+        );
+        assert_eq!(commits[0].parents.len(), 2);
+        assert_eq!(commits[0].author_name, "Jane Doe");
+        assert_eq!(commits[0].timestamp, 1700000000);
+        assert_eq!(commits[0].refs, vec!["HEAD -> main", "tag: v1.0"]);
+        assert_eq!(commits[0].subject, "Merge branch 'feature'");
+        assert!(commits[1].parents.is_empty());
+        assert!(commits[1].refs.is_empty());
+        assert_eq!(commits[1].subject, "Initial commit");
+    }
+}
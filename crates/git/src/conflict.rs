@@ -0,0 +1,227 @@
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+const OURS_MARKER: &str = "<<<<<<<";
+const BASE_MARKER: &str = "|||||||";
+const SEPARATOR_MARKER: &str = "=======";
+const THEIRS_MARKER: &str = ">>>>>>>";
+
+/// A region of a file where a merge left conflicting changes, delimited by conflict markers.
+///
+/// All ranges are byte offsets into the parsed text. The ranges of the sides cover whole lines,
+/// including their trailing newlines, and exclude the marker lines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConflictRegion {
+    /// The whole region, from the start of the `<<<<<<<` line to the end of the `>>>>>>>` line.
+    pub range: Range<usize>,
+    /// The lines between the `<<<<<<<` marker and the base or `=======` marker.
+    pub ours: Range<usize>,
+    /// The lines between the `|||||||` and `=======` markers, present with the `diff3` and
+    /// `zdiff3` conflict styles.
+    pub base: Option<Range<usize>>,
+    /// The lines between the `=======` and `>>>>>>>` markers.
+    pub theirs: Range<usize>,
+}
+
+/// How to resolve a [`ConflictRegion`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    Ours,
+    Theirs,
+    /// Keeps our lines followed by their lines.
+    Both,
+}
+
+impl ConflictRegion {
+    /// Returns the text replacing this region in `text` once it is resolved.
+    pub fn resolved_text(&self, text: &str, resolution: ConflictResolution) -> String {
+        match resolution {
+            ConflictResolution::Ours => text[self.ours.clone()].to_string(),
+            ConflictResolution::Theirs => text[self.theirs.clone()].to_string(),
+            ConflictResolution::Both => {
+                let mut resolved = text[self.ours.clone()].to_string();
+                resolved.push_str(&text[self.theirs.clone()]);
+                resolved
+            }
+        }
+    }
+}
+
+/// Finds the conflict regions of `text`, in order. Markers that don't form a complete region are
+/// ignored, as they may be part of the file's content.
+pub fn parse_conflicts(text: &str) -> Vec<ConflictRegion> {
+    enum State {
+        Ours {
+            start: usize,
+            ours_start: usize,
+        },
+        Base {
+            start: usize,
+            ours: Range<usize>,
+            base_start: usize,
+        },
+        Theirs {
+            start: usize,
+            ours: Range<usize>,
+            base: Option<Range<usize>>,
+            theirs_start: usize,
+        },
+    }
+
+    let mut conflicts = Vec::new();
+    let mut state = None;
+    let mut line_start = 0;
+    for line in text.split_inclusive('\n') {
+        let line_end = line_start + line.len();
+        let content = line.trim_end_matches(['\n', '\r']);
+
+        if is_marker(content, OURS_MARKER) {
+            state = Some(State::Ours {
+                start: line_start,
+                ours_start: line_end,
+            });
+        } else {
+            state = match (state, content) {
+                (Some(State::Ours { start, ours_start }), content)
+                    if is_marker(content, BASE_MARKER) =>
+                {
+                    Some(State::Base {
+                        start,
+                        ours: ours_start..line_start,
+                        base_start: line_end,
+                    })
+                }
+                (Some(State::Ours { start, ours_start }), SEPARATOR_MARKER) => {
+                    Some(State::Theirs {
+                        start,
+                        ours: ours_start..line_start,
+                        base: None,
+                        theirs_start: line_end,
+                    })
+                }
+                (
+                    Some(State::Base {
+                        start,
+                        ours,
+                        base_start,
+                    }),
+                    SEPARATOR_MARKER,
+                ) => Some(State::Theirs {
+                    start,
+                    ours,
+                    base: Some(base_start..line_start),
+                    theirs_start: line_end,
+                }),
+                (
+                    Some(State::Theirs {
+                        start,
+                        ours,
+                        base,
+                        theirs_start,
+                    }),
+                    content,
+                ) if is_marker(content, THEIRS_MARKER) => {
+                    conflicts.push(ConflictRegion {
+                        range: start..line_end,
+                        ours,
+                        base,
+                        theirs: theirs_start..line_start,
+                    });
+                    None
+                }
+                (state, _) => state,
+            };
+        }
+
+        line_start = line_end;
+    }
+    conflicts
+}
+
+/// Whether `line` is the given marker, optionally followed by a space and a label.
+fn is_marker(line: &str, marker: &str) -> bool {
+    line.strip_prefix(marker)
+        .map_or(false, |rest| rest.is_empty() || rest.starts_with(' '))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_conflicts() {
+        let text = "\
+one
+<<<<<<< HEAD
+two
+=======
+deux
+>>>>>>> french
+three
+<<<<<<< HEAD
+||||||| base
+four
+=======
+quatre
+vier
+>>>>>>> french
+";
+        let conflicts = parse_conflicts(text);
+        assert_eq!(conflicts.len(), 2);
+
+        assert_eq!(
+            &text[conflicts[0].range.clone()],
+            "<<<<<<< HEAD\ntwo\n=======\ndeux\n>>>>>>> french\n"
+        );
+        assert_eq!(&text[conflicts[0].ours.clone()], "two\n");
+        assert_eq!(conflicts[0].base, None);
+        assert_eq!(&text[conflicts[0].theirs.clone()], "deux\n");
+
+        assert_eq!(&text[conflicts[1].ours.clone()], "");
+        assert_eq!(
+            conflicts[1].base.clone().map(|base| &text[base]),
+            Some("four\n")
+        );
+        assert_eq!(&text[conflicts[1].theirs.clone()], "quatre\nvier\n");
+
+        assert_eq!(
+            conflicts[0].resolved_text(text, ConflictResolution::Ours),
+            "two\n"
+        );
+        assert_eq!(
+            conflicts[1].resolved_text(text, ConflictResolution::Theirs),
+            "quatre\nvier\n"
+        );
+        assert_eq!(
+            conflicts[0].resolved_text(text, ConflictResolution::Both),
+            "two\ndeux\n"
+        );
+    }
+
+    #[test]
+    fn test_parse_incomplete_conflicts() {
+        // A separator outside of a region, an unterminated region and a region restarted by a
+        // new `<<<<<<<` marker.
+        let text = "\
+=======
+<<<<<<< HEAD
+one
+<<<<<<< HEAD\r
+two\r
+=======\r
+zwei\r
+>>>>>>> german
+<<<<<<<< not a marker
+=======
+";
+        let conflicts = parse_conflicts(text);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(&text[conflicts[0].ours.clone()], "two\r\n");
+        assert_eq!(&text[conflicts[0].theirs.clone()], "zwei\r\n");
+        assert_eq!(
+            &text[conflicts[0].range.clone()],
+            "<<<<<<< HEAD\r\ntwo\r\n=======\r\nzwei\r\n>>>>>>> german\n"
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use rope::Rope;
 use std::{iter, ops::Range};
 use sum_tree::SumTree;
-use text::{Anchor, BufferSnapshot, OffsetRangeExt, Point};
+use text::{Anchor, Bias, BufferSnapshot, OffsetRangeExt, Point};
 
 pub use git2 as libgit;
 use libgit::{DiffLineType as GitDiffLineType, DiffOptions as GitOptions, Patch as GitPatch};
@@ -81,9 +81,16 @@ impl BufferDiff {
         let patch = Self::diff(diff_base, &buffer_text);
 
         if let Some(patch) = patch {
+            let diff_base = Rope::from(diff_base);
             let mut divergence = 0;
             for hunk_index in 0..patch.num_hunks() {
-                let hunk = Self::process_patch_hunk(&patch, hunk_index, buffer, &mut divergence);
+                let hunk = Self::process_patch_hunk(
+                    &patch,
+                    hunk_index,
+                    &diff_base,
+                    buffer,
+                    &mut divergence,
+                );
                 tree.push(hunk, buffer);
             }
         }
@@ -181,6 +188,30 @@ impl BufferDiff {
         })
     }
 
+    /// Returns the hunks of this diff intersecting the given range that don't overlap any hunk of
+    /// `other`, a diff of the same buffer against another base.
+    pub fn hunks_outside_of<'a>(
+        &'a self,
+        other: &'a BufferDiff,
+        range: Range<Anchor>,
+        buffer: &'a BufferSnapshot,
+    ) -> impl 'a + Iterator<Item = DiffHunk> {
+        self.hunks_intersecting_range(range, buffer)
+            .filter(move |hunk| {
+                !other
+                    .hunks_intersecting_range(hunk.buffer_range.clone(), buffer)
+                    .any(|other_hunk| {
+                        let (rows, other_rows) = (&hunk.row_range, &other_hunk.row_range);
+                        // Removals have empty row ranges, so they overlap the rows they touch.
+                        if rows.is_empty() || other_rows.is_empty() {
+                            other_rows.start <= rows.end && rows.start <= other_rows.end
+                        } else {
+                            other_rows.start < rows.end && rows.start < other_rows.end
+                        }
+                    })
+            })
+    }
+
     #[cfg(test)]
     fn clear(&mut self, buffer: &text::BufferSnapshot) {
         self.tree = SumTree::new(buffer);
@@ -222,6 +253,7 @@ impl BufferDiff {
     fn process_patch_hunk(
         patch: &GitPatch<'_>,
         hunk_index: usize,
+        diff_base: &Rope,
         buffer: &text::BufferSnapshot,
         buffer_row_divergence: &mut i64,
     ) -> InternalDiffHunk {
@@ -273,8 +305,15 @@ impl BufferDiff {
             row..row
         });
 
-        //unwrap_or addition without deletion
-        let diff_base_byte_range = diff_base_byte_range.unwrap_or(0..0);
+        //unwrap_or addition without deletion, which is inserted after the hunk's old start line
+        let diff_base_byte_range = diff_base_byte_range.unwrap_or_else(|| {
+            let old_start = patch
+                .hunk(hunk_index)
+                .map_or(0, |(hunk, _)| hunk.old_start());
+            let offset = diff_base
+                .point_to_offset(diff_base.clip_point(Point::new(old_start, 0), Bias::Left));
+            offset..offset
+        });
 
         let start = Point::new(buffer_row_range.start, 0);
         let end = Point::new(buffer_row_range.end, 0);
@@ -421,4 +460,35 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_buffer_diff_addition_base_offsets() {
+        let diff_base = "
+            one
+            two
+            three
+        "
+        .unindent();
+        let diff_base_rope = Rope::from(diff_base.clone());
+
+        let buffer_text = "
+            zero
+            one
+            two
+            two and a half
+            three
+            four
+        "
+        .unindent();
+
+        let buffer = Buffer::new(0, BufferId::new(1).unwrap(), buffer_text);
+        let mut diff = BufferDiff::new(&buffer);
+        smol::block_on(diff.update(&diff_base_rope, &buffer));
+        assert_eq!(
+            diff.hunks(&buffer)
+                .map(|hunk| (hunk.row_range, hunk.diff_base_byte_range))
+                .collect::<Vec<_>>(),
+            [(0..1, 0..0), (3..4, 8..8), (5..6, 14..14)]
+        );
+    }
 }
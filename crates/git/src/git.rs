@@ -1,10 +1,15 @@
 pub mod blame;
 pub mod commit;
+pub mod conflict;
 pub mod diff;
+pub mod graph;
 mod hosting_provider;
+pub mod rebase;
 mod remote;
 pub mod repository;
+pub mod stash;
 pub mod status;
+pub mod worktree;
 
 use anyhow::{anyhow, Context, Result};
 use serde::{Deserialize, Serialize};
@@ -0,0 +1,153 @@
+use crate::Oid;
+
+/// How a single commit of the branch graph is drawn, in terms of lanes (columns) of the graph.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GraphRow {
+    /// The lane of the commit's node.
+    pub lane: usize,
+    /// Lanes running past the commit, from the top to the bottom of the row.
+    pub passing: Vec<usize>,
+    /// Lanes ending at the commit's node, coming from the top of the row.
+    pub incoming: Vec<usize>,
+    /// Lanes leaving the commit's node towards its parents, at the bottom of the row.
+    pub outgoing: Vec<usize>,
+}
+
+impl GraphRow {
+    /// Returns the number of lanes needed to draw this row.
+    pub fn width(&self) -> usize {
+        self.passing
+            .iter()
+            .chain(&self.incoming)
+            .chain(&self.outgoing)
+            .copied()
+            .chain([self.lane])
+            .max()
+            .map_or(0, |lane| lane + 1)
+    }
+}
+
+/// Assigns lanes to commits listed newest first, like `git log --topo-order` does.
+///
+/// Each lane waits for a specific commit: the parent of the last commit drawn in it. Commits are
+/// fed one page at a time, so the graph keeps the pending lanes between pages.
+#[derive(Clone, Debug, Default)]
+pub struct CommitGraph {
+    lanes: Vec<Option<Oid>>,
+}
+
+impl CommitGraph {
+    pub fn push(&mut self, sha: Oid, parents: &[Oid]) -> GraphRow {
+        let incoming = self
+            .lanes
+            .iter()
+            .enumerate()
+            .filter(|(_, expected)| **expected == Some(sha))
+            .map(|(ix, _)| ix)
+            .collect::<Vec<_>>();
+        let lane = match incoming.first() {
+            Some(lane) => *lane,
+            None => self.free_lane(),
+        };
+        for ix in &incoming {
+            self.lanes[*ix] = None;
+        }
+        let passing = self
+            .lanes
+            .iter()
+            .enumerate()
+            .filter(|(_, expected)| expected.is_some())
+            .map(|(ix, _)| ix)
+            .collect::<Vec<_>>();
+
+        let mut outgoing = Vec::with_capacity(parents.len());
+        for (ix, parent) in parents.iter().enumerate() {
+            let parent_lane = if let Some(existing) = self.lane_expecting(*parent) {
+                existing
+            } else {
+                let parent_lane = if ix == 0 { lane } else { self.free_lane() };
+                self.lanes[parent_lane] = Some(*parent);
+                parent_lane
+            };
+            if !outgoing.contains(&parent_lane) {
+                outgoing.push(parent_lane);
+            }
+        }
+
+        while self.lanes.last() == Some(&None) {
+            self.lanes.pop();
+        }
+
+        GraphRow {
+            lane,
+            passing,
+            incoming,
+            outgoing,
+        }
+    }
+
+    fn lane_expecting(&self, sha: Oid) -> Option<usize> {
+        self.lanes
+            .iter()
+            .position(|expected| *expected == Some(sha))
+    }
+
+    fn free_lane(&mut self) -> usize {
+        if let Some(ix) = self.lanes.iter().position(Option::is_none) {
+            ix
+        } else {
+            self.lanes.push(None);
+            self.lanes.len() - 1
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oid(n: u8) -> Oid {
+        Oid::from_bytes(&[n; 20]).unwrap()
+    }
+
+    fn row(lane: usize, passing: &[usize], incoming: &[usize], outgoing: &[usize]) -> GraphRow {
+        GraphRow {
+            lane,
+            passing: passing.to_vec(),
+            incoming: incoming.to_vec(),
+            outgoing: outgoing.to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_linear_history() {
+        let mut graph = CommitGraph::default();
+        assert_eq!(graph.push(oid(3), &[oid(2)]), row(0, &[], &[], &[0]));
+        assert_eq!(graph.push(oid(2), &[oid(1)]), row(0, &[], &[0], &[0]));
+        assert_eq!(graph.push(oid(1), &[]), row(0, &[], &[0], &[]));
+    }
+
+    #[test]
+    fn test_branch_and_merge() {
+        // 4 merges 3 into 2, which both branched off 1.
+        let mut graph = CommitGraph::default();
+        assert_eq!(
+            graph.push(oid(4), &[oid(2), oid(3)]),
+            row(0, &[], &[], &[0, 1])
+        );
+        assert_eq!(graph.push(oid(3), &[oid(1)]), row(1, &[0], &[1], &[1]));
+        assert_eq!(graph.push(oid(2), &[oid(1)]), row(0, &[1], &[0], &[1]));
+        assert_eq!(graph.push(oid(1), &[]), row(1, &[], &[1], &[]));
+    }
+
+    #[test]
+    fn test_branches_without_merge() {
+        // 3 and 2 are the tips of two branches forked from 1.
+        let mut graph = CommitGraph::default();
+        assert_eq!(graph.push(oid(3), &[oid(1)]), row(0, &[], &[], &[0]));
+        let row_2 = graph.push(oid(2), &[oid(1)]);
+        assert_eq!(row_2, row(1, &[0], &[], &[0]));
+        assert_eq!(row_2.width(), 2);
+        assert_eq!(graph.push(oid(1), &[]), row(0, &[], &[0], &[]));
+    }
+}
@@ -0,0 +1,254 @@
+use anyhow::{anyhow, Result};
+use std::fmt::Write as _;
+
+/// What an interactive rebase does with a commit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RebaseAction {
+    /// Keeps the commit as is.
+    Pick,
+    /// Keeps the commit with a new message.
+    Reword,
+    /// Melds the commit into the previous one, combining their messages.
+    Squash,
+    /// Melds the commit into the previous one, keeping the message of the previous one.
+    Fixup,
+    /// Removes the commit.
+    Drop,
+    /// A line that isn't about a single commit, such as `exec`, `label` or `merge`, or one using
+    /// options that aren't modeled, such as `fixup -C`. It is kept as written, in
+    /// [`RebaseTodoItem::subject`].
+    Command,
+}
+
+impl RebaseAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RebaseAction::Pick => "pick",
+            RebaseAction::Reword => "reword",
+            RebaseAction::Squash => "squash",
+            RebaseAction::Fixup => "fixup",
+            RebaseAction::Drop => "drop",
+            RebaseAction::Command => "command",
+        }
+    }
+
+    /// Parses a command of a todo list, in its long or abbreviated form.
+    fn from_command(command: &str) -> Option<Self> {
+        match command {
+            "pick" | "p" => Some(RebaseAction::Pick),
+            "reword" | "r" => Some(RebaseAction::Reword),
+            "squash" | "s" => Some(RebaseAction::Squash),
+            "fixup" | "f" => Some(RebaseAction::Fixup),
+            "drop" | "d" => Some(RebaseAction::Drop),
+            _ => None,
+        }
+    }
+
+    /// Whether the commit is melded into the one before it.
+    pub fn melds(&self) -> bool {
+        matches!(self, RebaseAction::Squash | RebaseAction::Fixup)
+    }
+}
+
+/// A line of the todo list of an interactive rebase.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RebaseTodoItem {
+    pub action: RebaseAction,
+    /// The SHA of the commit, which is abbreviated in the todo lists written by git.
+    pub sha: String,
+    pub subject: String,
+    /// The new message of a reworded commit. When unset, git asks for it with its editor.
+    pub message: Option<String>,
+}
+
+impl RebaseTodoItem {
+    pub fn pick(sha: String, subject: String) -> Self {
+        Self {
+            action: RebaseAction::Pick,
+            sha,
+            subject,
+            message: None,
+        }
+    }
+
+    pub fn command(line: String) -> Self {
+        Self {
+            action: RebaseAction::Command,
+            sha: String::new(),
+            subject: line,
+            message: None,
+        }
+    }
+}
+
+/// How an interactive rebase ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RebaseOutcome {
+    Completed,
+    /// The rebase stopped, typically because a commit conflicted. It can be continued once the
+    /// conflicts are resolved, or aborted.
+    Stopped {
+        message: String,
+    },
+}
+
+/// Parses a todo list as written by `git rebase --interactive`, skipping comments. The lines that
+/// aren't supported by the other [`RebaseAction`]s, such as `exec` or `merge`, are kept as
+/// [`RebaseAction::Command`]s.
+pub fn parse_todo(text: &str) -> Result<Vec<RebaseTodoItem>> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            let mut parts = line.splitn(3, char::is_whitespace);
+            let command = parts.next().unwrap_or_default();
+            let Some(action) = RebaseAction::from_command(command) else {
+                return Ok(RebaseTodoItem::command(line.to_string()));
+            };
+            let sha = parts
+                .next()
+                .ok_or_else(|| anyhow!("malformed rebase todo line: {line:?}"))?;
+            if sha.starts_with('-') {
+                return Ok(RebaseTodoItem::command(line.to_string()));
+            }
+            Ok(RebaseTodoItem {
+                action,
+                sha: sha.to_string(),
+                subject: parts.next().unwrap_or_default().trim().to_string(),
+                message: None,
+            })
+        })
+        .collect()
+}
+
+/// Writes a todo list for `git rebase --interactive`. The new messages of reworded commits are
+/// set by `exec` commands amending them, so that git doesn't need an editor. Line breaks in the
+/// subjects are replaced, so that each item stays on its own line.
+pub fn serialize_todo(items: &[RebaseTodoItem]) -> Result<String> {
+    let mut todo = String::new();
+    for item in items {
+        let subject = item.subject.replace(['\r', '\n'], " ");
+        if item.action == RebaseAction::Command {
+            writeln!(todo, "{subject}")?;
+            continue;
+        }
+        if item.sha.is_empty() || !item.sha.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(anyhow!("invalid commit SHA {:?}", item.sha));
+        }
+        let action = match (item.action, &item.message) {
+            (RebaseAction::Reword, Some(_)) => RebaseAction::Pick,
+            (action, _) => action,
+        };
+        writeln!(todo, "{} {} {subject}", action.as_str(), item.sha)?;
+        if let (RebaseAction::Reword, Some(message)) = (item.action, &item.message) {
+            let lines = message.lines().map(shell_quote).collect::<Vec<_>>();
+            writeln!(
+                todo,
+                "exec printf '%s\\n' {} | git commit --amend --only --quiet --cleanup=strip -F -",
+                lines.join(" ")
+            )?;
+        }
+    }
+    Ok(todo)
+}
+
+/// Quotes a string for `sh`, which runs the `exec` commands of a rebase.
+fn shell_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_todo() {
+        let todo = concat!(
+            "pick 1a2b3c4 First commit\n",
+            "s 5d6e7f8 Second commit\n",
+            "\n",
+            "fixup 9a8b7c6\n",
+            "# Rebase 0f0f0f0..9a8b7c6 onto 0f0f0f0 (3 commands)\n",
+            "#\n",
+            "# Commands:\n",
+            "# p, pick <commit> = use commit\n",
+        );
+        assert_eq!(
+            parse_todo(todo).unwrap(),
+            [
+                RebaseTodoItem::pick("1a2b3c4".into(), "First commit".into()),
+                RebaseTodoItem {
+                    action: RebaseAction::Squash,
+                    ..RebaseTodoItem::pick("5d6e7f8".into(), "Second commit".into())
+                },
+                RebaseTodoItem {
+                    action: RebaseAction::Fixup,
+                    ..RebaseTodoItem::pick("9a8b7c6".into(), "".into())
+                },
+            ]
+        );
+
+        assert_eq!(parse_todo("# Nothing to do\n").unwrap(), []);
+        assert_eq!(
+            parse_todo("exec make test\nfixup -C 1a2b3c4 Subject\nbreak\n").unwrap(),
+            [
+                RebaseTodoItem::command("exec make test".into()),
+                RebaseTodoItem::command("fixup -C 1a2b3c4 Subject".into()),
+                RebaseTodoItem::command("break".into()),
+            ]
+        );
+        assert!(parse_todo("pick\n").is_err());
+    }
+
+    #[test]
+    fn test_serialize_todo() {
+        let items = [
+            RebaseTodoItem::pick("1a2b3c4".into(), "First".into()),
+            RebaseTodoItem {
+                action: RebaseAction::Reword,
+                message: Some("Don't panic\n\nDetails".into()),
+                ..RebaseTodoItem::pick("5d6e7f8".into(), "Second".into())
+            },
+            RebaseTodoItem {
+                action: RebaseAction::Reword,
+                ..RebaseTodoItem::pick("9a8b7c6".into(), "Third".into())
+            },
+            RebaseTodoItem {
+                action: RebaseAction::Drop,
+                ..RebaseTodoItem::pick("0d0d0d0".into(), "Fourth".into())
+            },
+            RebaseTodoItem::command("exec make test".into()),
+        ];
+        assert_eq!(
+            serialize_todo(&items).unwrap(),
+            concat!(
+                "pick 1a2b3c4 First\n",
+                "pick 5d6e7f8 Second\n",
+                r"exec printf '%s\n' 'Don'\''t panic' '' 'Details'",
+                " | git commit --amend --only --quiet --cleanup=strip -F -\n",
+                "reword 9a8b7c6 Third\n",
+                "drop 0d0d0d0 Fourth\n",
+                "exec make test\n",
+            )
+        );
+        assert_eq!(
+            serialize_todo(&[RebaseTodoItem::pick(
+                "1a2b3c4".into(),
+                "First\nexec rm -rf ~".into()
+            )])
+            .unwrap(),
+            "pick 1a2b3c4 First exec rm -rf ~\n"
+        );
+        assert!(serialize_todo(&[RebaseTodoItem::pick(
+            "1a2b3c4\nexec rm -rf ~".into(),
+            "First".into()
+        )])
+        .is_err());
+
+        // Serialized todo lists can be parsed back, as long as no commit is reworded.
+        assert_eq!(
+            parse_todo(&serialize_todo(&items[3..]).unwrap()).unwrap(),
+            &items[3..]
+        );
+    }
+}
@@ -1,6 +1,10 @@
-use crate::GitHostingProviderRegistry;
+use crate::commit::{parse_log, CommitFileDiff, CommitSummary, LogOptions, LOG_FORMAT};
+use crate::rebase::{serialize_todo, RebaseAction, RebaseOutcome, RebaseTodoItem};
+use crate::stash::{parse_stash_list, StashEntry, StashOptions, STASH_LIST_FORMAT};
+use crate::worktree::{parse_worktree_list, GitWorktree};
 use crate::{blame::Blame, status::GitStatus};
-use anyhow::{Context, Result};
+use crate::{GitHostingProviderRegistry, Oid};
+use anyhow::{anyhow, Context, Result};
 use collections::{HashMap, HashSet};
 use git2::BranchType;
 use gpui::SharedString;
@@ -9,7 +13,10 @@ use rope::Rope;
 use serde::{Deserialize, Serialize};
 use std::{
     cmp::Ordering,
+    ffi::OsStr,
+    io::Write as _,
     path::{Component, Path, PathBuf},
+    process::Stdio,
     sync::Arc,
 };
 use sum_tree::MapSeekTarget;
@@ -21,6 +28,43 @@ pub struct Branch {
     pub name: SharedString,
     /// Timestamp of most recent commit, normalized to Unix Epoch format.
     pub unix_timestamp: Option<i64>,
+    /// The remote-tracking branch this branch pulls from and pushes to, if any.
+    pub upstream: Option<UpstreamBranch>,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq)]
+pub struct UpstreamBranch {
+    /// The short name of the remote-tracking branch, such as `origin/main`.
+    pub name: SharedString,
+    /// The number of commits of the branch that its upstream doesn't have.
+    pub ahead: u32,
+    /// The number of commits of the upstream that the branch doesn't have.
+    pub behind: u32,
+}
+
+/// How [`GitRepository::pull`] integrates the upstream changes into the current branch.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PullStrategy {
+    Merge,
+    Rebase,
+}
+
+/// An operation exchanging commits with the remote of a repository.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RemoteOperation {
+    Fetch,
+    Pull(PullStrategy),
+    Push { force: bool },
+}
+
+impl RemoteOperation {
+    pub fn run(self, repo: &dyn GitRepository, askpass: Option<&Path>) -> Result<()> {
+        match self {
+            RemoteOperation::Fetch => repo.fetch(askpass),
+            RemoteOperation::Pull(strategy) => repo.pull(strategy, askpass),
+            RemoteOperation::Push { force } => repo.push(force, askpass),
+        }
+    }
 }
 
 pub trait GitRepository: Send + Sync {
@@ -30,6 +74,14 @@ pub trait GitRepository: Send + Sync {
     /// Note that for symlink entries, this will return the contents of the symlink, not the target.
     fn load_index_text(&self, relative_file_path: &Path) -> Option<String>;
 
+    /// Loads the contents of a file as of the `HEAD` commit.
+    /// Like [`GitRepository::load_index_text`], symlink entries are skipped.
+    fn load_committed_text(&self, relative_file_path: &Path) -> Option<String>;
+
+    /// Replaces the staged contents of a file, leaving the working directory untouched.
+    /// Passing `None` removes the file from the index.
+    fn set_index_text(&self, path: &RepoPath, content: Option<String>) -> Result<()>;
+
     /// Returns the URL of the remote with the given name.
     fn remote_url(&self, name: &str) -> Option<String>;
     fn branch_name(&self) -> Option<String>;
@@ -48,6 +100,97 @@ pub trait GitRepository: Send + Sync {
 
     /// Returns the path to the repository, typically the `.git` folder.
     fn dot_git_dir(&self) -> PathBuf;
+
+    /// Returns the paths whose changes are staged in the index, relative to the repository root.
+    fn staged_paths(&self) -> Result<Vec<RepoPath>>;
+
+    /// Updates the index to match the working directory at the given paths, including deletions.
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+
+    /// Resets the index to match `HEAD` at the given paths, keeping the working directory as is.
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()>;
+
+    /// Resets the index and the working directory to match `HEAD` at the given paths. Files that
+    /// `HEAD` doesn't have are removed from the index and deleted.
+    fn discard_paths(&self, paths: &[RepoPath]) -> Result<()>;
+
+    /// Creates a commit out of the staged changes.
+    fn commit(&self, message: &str) -> Result<()>;
+
+    /// Lists the commits reachable from `HEAD`, newest first, with parents before children.
+    fn log(&self, options: &LogOptions) -> Result<Vec<CommitSummary>>;
+
+    /// Returns the text files changed by a commit, relative to its first parent.
+    /// Binary files and symlinks are skipped.
+    fn commit_diff(&self, sha: Oid) -> Result<Vec<CommitFileDiff>>;
+
+    /// Returns the full message of the commit at the given revision, which can be anything
+    /// `git rev-parse` accepts, such as an abbreviated SHA.
+    fn commit_message(&self, revision: &str) -> Result<String>;
+
+    /// Loads the contents of a file at the given revision, which can be anything `git rev-parse`
+    /// accepts, such as a branch, a tag, a SHA or `HEAD~2`. Fails if the revision doesn't exist.
+    /// Like [`GitRepository::load_index_text`], symlink entries are skipped.
+    fn load_revision_text(&self, revision: &str, path: &RepoPath) -> Result<Option<String>>;
+
+    /// Returns the paths whose contents in the working directory differ from the given revision,
+    /// relative to the repository root. Untracked files are not included.
+    fn changed_paths_since(&self, revision: &str) -> Result<Vec<RepoPath>>;
+
+    /// Downloads the commits of the upstream remote of the current branch, or of `origin` when
+    /// the branch has no upstream. Credential prompts are answered by running the `askpass`
+    /// program, and fail when there is none.
+    fn fetch(&self, askpass: Option<&Path>) -> Result<()>;
+
+    /// Fetches the upstream of the current branch and integrates its changes into the branch.
+    fn pull(&self, strategy: PullStrategy, askpass: Option<&Path>) -> Result<()>;
+
+    /// Pushes the current branch to its upstream, which is set to a branch of the same name on
+    /// `origin` when there is none. A forced push only overwrites the remote commits that were
+    /// fetched, so that commits pushed by others in the meantime aren't lost.
+    fn push(&self, force: bool, askpass: Option<&Path>) -> Result<()>;
+
+    /// Lists the entries of the stash, most recent first.
+    fn stash_list(&self) -> Result<Vec<StashEntry>>;
+
+    /// Saves the local changes selected by `options` as a new stash entry and reverts them in the
+    /// working directory. Fails when there are no such changes.
+    fn stash_push(&self, message: Option<&str>, options: StashOptions) -> Result<()>;
+
+    /// Restores the changes of the stash entry at `index` in the working directory.
+    fn stash_apply(&self, index: usize) -> Result<()>;
+
+    /// Restores the changes of the stash entry at `index` and removes the entry. The entry is
+    /// kept when its changes conflict with the working directory.
+    fn stash_pop(&self, index: usize) -> Result<()>;
+
+    /// Removes the stash entry at `index`, discarding its changes.
+    fn stash_drop(&self, index: usize) -> Result<()>;
+
+    /// Lists the commits that an interactive rebase onto `base` would replay, oldest first,
+    /// all picked. Merge commits are left out, as rebasing drops them.
+    fn rebase_todo(&self, base: &str) -> Result<Vec<RebaseTodoItem>>;
+
+    /// Rebases the current branch onto `base`, replaying the commits as listed in `todo`.
+    fn rebase(&self, base: &str, todo: &[RebaseTodoItem]) -> Result<RebaseOutcome>;
+
+    /// Continues a stopped rebase, once its conflicts are resolved and staged.
+    fn rebase_continue(&self) -> Result<RebaseOutcome>;
+
+    /// Aborts a stopped rebase, restoring the branch as it was before the rebase.
+    fn rebase_abort(&self) -> Result<()>;
+
+    /// Lists the worktrees of the repository, the main one first.
+    fn worktrees(&self) -> Result<Vec<GitWorktree>>;
+
+    /// Creates a linked worktree at `path` checking out `revision`. When `new_branch` is set, a
+    /// branch with that name is created at the revision. Otherwise, a local branch given as the
+    /// revision is checked out, and any other revision is checked out as a detached `HEAD`.
+    fn create_worktree(&self, path: &Path, revision: &str, new_branch: Option<&str>) -> Result<()>;
+
+    /// Removes the linked worktree at `path`. Unless `force` is set, fails when the worktree
+    /// has local changes or untracked files.
+    fn remove_worktree(&self, path: &Path, force: bool) -> Result<()>;
 }
 
 impl std::fmt::Debug for dyn GitRepository {
@@ -74,6 +217,92 @@ impl RealGitRepository {
             hosting_provider_registry,
         }
     }
+
+    fn working_directory(&self) -> Result<PathBuf> {
+        self.repository
+            .lock()
+            .workdir()
+            .context("failed to read git work directory")
+            .map(Path::to_path_buf)
+    }
+
+    fn run_git_command<I, S>(&self, args: I) -> Result<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<std::ffi::OsStr>,
+    {
+        let working_directory = self.working_directory()?;
+        let mut command = util::command::new_std_command(&self.git_binary_path);
+        command
+            .current_dir(&working_directory)
+            .args(args)
+            .stdin(Stdio::null());
+        command_output(&mut command)
+    }
+
+    /// Runs a git command that connects to a remote. Git and ssh are told to prompt for
+    /// credentials with the `askpass` program, as there is no terminal to prompt on.
+    fn run_git_remote_command(&self, args: &[&str], askpass: Option<&Path>) -> Result<String> {
+        let working_directory = self.working_directory()?;
+        let mut command = util::command::new_std_command(&self.git_binary_path);
+        command
+            .current_dir(&working_directory)
+            .args(args)
+            .stdin(Stdio::null())
+            .env("GIT_TERMINAL_PROMPT", "0");
+        if let Some(askpass) = askpass {
+            command
+                .env("GIT_ASKPASS", askpass)
+                .env("SSH_ASKPASS", askpass)
+                .env("SSH_ASKPASS_REQUIRE", "force");
+        }
+        command_output(&mut command)
+    }
+
+    /// Runs a rebase command. Git replaces the todo list with the one at `todo_path` instead of
+    /// opening an editor, and keeps the combined messages of squashed commits as they are.
+    fn run_git_rebase_command(
+        &self,
+        args: &[&str],
+        todo_path: Option<&Path>,
+    ) -> Result<RebaseOutcome> {
+        let working_directory = self.working_directory()?;
+        let mut command = util::command::new_std_command(&self.git_binary_path);
+        command
+            .current_dir(&working_directory)
+            .args(args)
+            .stdin(Stdio::null())
+            .env("GIT_EDITOR", "true");
+        if let Some(todo_path) = todo_path {
+            let todo_path = todo_path.to_string_lossy().replace('\'', r"'\''");
+            command.env("GIT_SEQUENCE_EDITOR", format!("cp '{todo_path}'"));
+        }
+        match command_output(&mut command) {
+            Ok(_) => Ok(RebaseOutcome::Completed),
+            Err(error) => {
+                let state = self.repository.lock().state();
+                if matches!(
+                    state,
+                    git2::RepositoryState::RebaseInteractive | git2::RepositoryState::RebaseMerge
+                ) {
+                    Ok(RebaseOutcome::Stopped {
+                        message: error.to_string(),
+                    })
+                } else {
+                    Err(error)
+                }
+            }
+        }
+    }
+}
+
+fn command_output(command: &mut std::process::Command) -> Result<String> {
+    let output = command.output().context("failed to start git process")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("git process failed: {}", stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
 
 // https://git-scm.com/book/en/v2/Git-Internals-Git-Objects
@@ -115,6 +344,81 @@ impl GitRepository for RealGitRepository {
         None
     }
 
+    fn load_committed_text(&self, relative_file_path: &Path) -> Option<String> {
+        fn logic(repo: &git2::Repository, relative_file_path: &Path) -> Result<Option<String>> {
+            check_path_to_repo_path_errors(relative_file_path)?;
+
+            let tree = match repo.head() {
+                Ok(head) => head.peel_to_tree()?,
+                Err(err) if err.code() == git2::ErrorCode::UnbornBranch => return Ok(None),
+                Err(err) => return Err(err.into()),
+            };
+            let entry = match tree.get_path(relative_file_path) {
+                Ok(entry) => entry,
+                Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+                Err(err) => return Err(err.into()),
+            };
+            if entry.filemode() as u32 == GIT_MODE_SYMLINK {
+                return Ok(None);
+            }
+
+            let content = repo.find_blob(entry.id())?.content().to_owned();
+            Ok(Some(String::from_utf8(content)?))
+        }
+
+        match logic(&self.repository.lock(), relative_file_path) {
+            Ok(value) => return value,
+            Err(err) => log::error!("Error loading committed text: {:?}", err),
+        }
+        None
+    }
+
+    fn set_index_text(&self, path: &RepoPath, content: Option<String>) -> Result<()> {
+        let Some(content) = content else {
+            self.run_git_command(
+                ["update-index", "--force-remove", "--"]
+                    .iter()
+                    .map(std::ffi::OsStr::new)
+                    .chain([path.as_os_str()]),
+            )?;
+            return Ok(());
+        };
+
+        let working_directory = self.working_directory()?;
+        let mut child = util::command::new_std_command(&self.git_binary_path)
+            .current_dir(&working_directory)
+            .args(["hash-object", "-w", "--stdin"])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("failed to start git process")?;
+        child
+            .stdin
+            .take()
+            .context("failed to open git process stdin")?
+            .write_all(content.as_bytes())?;
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow!("git process failed: {}", stderr.trim()));
+        }
+        let sha = String::from_utf8(output.stdout)?;
+
+        // Keep the mode of an existing entry, so that executable files stay executable.
+        const GIT_MODE_BLOB: u32 = 0o100644;
+        let mode = self
+            .repository
+            .lock()
+            .index()
+            .ok()
+            .and_then(|index| index.get_path(path, 0))
+            .map_or(GIT_MODE_BLOB, |entry| entry.mode);
+        let cache_info = format!("{:o},{},{}", mode, sha.trim(), path.to_string_lossy());
+        self.run_git_command(["update-index", "--add", "--cacheinfo", &cache_info])?;
+        Ok(())
+    }
+
     fn remote_url(&self, name: &str) -> Option<String> {
         let repo = self.repository.lock();
         let remote = repo.find_remote(name).ok()?;
@@ -173,10 +477,22 @@ impl GitRepository for RealGitRepository {
                         time::UtcOffset::from_whole_seconds(timezone_offset * 60).ok()?;
                     let unix_timestamp =
                         time::OffsetDateTime::from_unix_timestamp(unix_timestamp).ok()?;
+                    let upstream = branch.upstream().ok().and_then(|upstream| {
+                        let name = upstream.name().ok().flatten()?.to_string().into();
+                        let (ahead, behind) = repo
+                            .graph_ahead_behind(branch.get().target()?, upstream.get().target()?)
+                            .ok()?;
+                        Some(UpstreamBranch {
+                            name,
+                            ahead: ahead as u32,
+                            behind: behind as u32,
+                        })
+                    });
                     Some(Branch {
                         is_head,
                         name,
                         unix_timestamp: Some(unix_timestamp.to_offset(utc_offset).unix_timestamp()),
+                        upstream,
                     })
                 })
             })
@@ -225,6 +541,365 @@ impl GitRepository for RealGitRepository {
             self.hosting_provider_registry.clone(),
         )
     }
+
+    fn staged_paths(&self) -> Result<Vec<RepoPath>> {
+        let output =
+            self.run_git_command(["diff", "--cached", "--name-only", "--no-renames", "-z"])?;
+        Ok(output
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(|path| RepoPath::new(PathBuf::from(path)))
+            .collect())
+    }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        self.run_git_command(
+            ["update-index", "--add", "--remove", "--"]
+                .iter()
+                .map(std::ffi::OsStr::new)
+                .chain(paths.iter().map(|path| path.as_os_str())),
+        )?;
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        self.run_git_command(
+            ["reset", "--quiet", "--"]
+                .iter()
+                .map(std::ffi::OsStr::new)
+                .chain(paths.iter().map(|path| path.as_os_str())),
+        )?;
+        Ok(())
+    }
+
+    fn discard_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        if paths.is_empty() {
+            return Ok(());
+        }
+        let (tracked, untracked): (Vec<_>, Vec<_>) = {
+            let repo = self.repository.lock();
+            let head_tree = repo.head().and_then(|head| head.peel_to_tree()).ok();
+            paths.iter().partition(|path| {
+                head_tree
+                    .as_ref()
+                    .map_or(false, |tree| tree.get_path(path).is_ok())
+            })
+        };
+
+        if !tracked.is_empty() {
+            self.run_git_command(
+                ["checkout", "--quiet", "HEAD", "--"]
+                    .iter()
+                    .map(std::ffi::OsStr::new)
+                    .chain(tracked.iter().map(|path| path.as_os_str())),
+            )?;
+        }
+        if !untracked.is_empty() {
+            let working_directory = self.working_directory()?;
+            {
+                let repo = self.repository.lock();
+                let mut index = repo.index()?;
+                for path in &untracked {
+                    index.remove_path(path)?;
+                }
+                index.write()?;
+            }
+            for path in untracked {
+                match std::fs::remove_file(working_directory.join(path)) {
+                    Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+                        return Err(error).with_context(|| format!("deleting {path:?}"));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn commit(&self, message: &str) -> Result<()> {
+        self.run_git_command(["commit", "--quiet", "--cleanup=strip", "-m", message])?;
+        Ok(())
+    }
+
+    fn log(&self, options: &LogOptions) -> Result<Vec<CommitSummary>> {
+        if self.head_sha().is_none() {
+            return Ok(Vec::new());
+        }
+
+        let mut args = vec![
+            "log".to_string(),
+            "--topo-order".to_string(),
+            LOG_FORMAT.to_string(),
+            format!("--skip={}", options.skip),
+        ];
+        if options.limit > 0 {
+            args.push(format!("--max-count={}", options.limit));
+        }
+        if options.author.is_some() || options.message.is_some() {
+            args.push("--fixed-strings".to_string());
+            args.push("--regexp-ignore-case".to_string());
+        }
+        if let Some(author) = &options.author {
+            args.push(format!("--author={author}"));
+        }
+        if let Some(message) = &options.message {
+            args.push(format!("--grep={message}"));
+        }
+        let path = options
+            .path
+            .as_ref()
+            .filter(|path| !path.as_os_str().is_empty());
+        if path.is_some() {
+            // Rewrite the parents of the listed commits to skip the ones not touching the path,
+            // so that the listed commits form a graph on their own.
+            args.push("--parents".to_string());
+        }
+        args.push("--".to_string());
+        if let Some(path) = path {
+            args.push(path.to_string_lossy().into_owned());
+        }
+
+        parse_log(&self.run_git_command(&args)?)
+    }
+
+    fn commit_diff(&self, sha: Oid) -> Result<Vec<CommitFileDiff>> {
+        fn load_text(repo: &git2::Repository, file: &git2::DiffFile) -> Result<Option<String>> {
+            if file.id().is_zero() {
+                return Ok(None);
+            }
+            let blob = repo.find_blob(file.id())?;
+            if blob.is_binary() {
+                return Err(anyhow!("binary file"));
+            }
+            Ok(Some(String::from_utf8(blob.content().to_vec())?))
+        }
+
+        let repo = self.repository.lock();
+        let commit = repo.find_commit(sha.0)?;
+        let tree = commit.tree()?;
+        let parent_tree = if commit.parent_count() > 0 {
+            Some(commit.parent(0)?.tree()?)
+        } else {
+            None
+        };
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), None)?;
+
+        let mut files = Vec::new();
+        for delta in diff.deltas() {
+            let (old_file, new_file) = (delta.old_file(), delta.new_file());
+            if old_file.mode() == git2::FileMode::Link || new_file.mode() == git2::FileMode::Link {
+                continue;
+            }
+            let Some(path) = new_file.path().or(old_file.path()) else {
+                continue;
+            };
+            let (Ok(old_text), Ok(new_text)) =
+                (load_text(&repo, &old_file), load_text(&repo, &new_file))
+            else {
+                continue;
+            };
+            files.push(CommitFileDiff {
+                path: RepoPath::from(path),
+                old_text,
+                new_text,
+            });
+        }
+        Ok(files)
+    }
+
+    fn commit_message(&self, revision: &str) -> Result<String> {
+        let repo = self.repository.lock();
+        let commit = repo
+            .revparse_single(revision)
+            .and_then(|object| object.peel_to_commit())
+            .with_context(|| format!("unknown revision {revision:?}"))?;
+        let message = commit
+            .message()
+            .with_context(|| format!("the message of commit {revision} isn't valid UTF-8"))?;
+        Ok(message.to_string())
+    }
+
+    fn load_revision_text(&self, revision: &str, path: &RepoPath) -> Result<Option<String>> {
+        check_path_to_repo_path_errors(path)?;
+
+        let repo = self.repository.lock();
+        let tree = repo
+            .revparse_single(revision)
+            .and_then(|object| object.peel_to_tree())
+            .with_context(|| format!("unknown revision {revision:?}"))?;
+        let entry = match tree.get_path(path) {
+            Ok(entry) => entry,
+            Err(err) if err.code() == git2::ErrorCode::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+        if entry.filemode() as u32 == GIT_MODE_SYMLINK {
+            return Ok(None);
+        }
+
+        let content = repo.find_blob(entry.id())?.content().to_owned();
+        Ok(Some(String::from_utf8(content)?))
+    }
+
+    fn changed_paths_since(&self, revision: &str) -> Result<Vec<RepoPath>> {
+        // Keep the revision from being interpreted as an option.
+        if revision.starts_with('-') {
+            return Err(anyhow!("invalid revision {revision:?}"));
+        }
+        let output =
+            self.run_git_command(["diff", "--name-only", "--no-renames", "-z", revision, "--"])?;
+        Ok(output
+            .split('\0')
+            .filter(|path| !path.is_empty())
+            .map(|path| RepoPath::new(PathBuf::from(path)))
+            .collect())
+    }
+
+    fn fetch(&self, askpass: Option<&Path>) -> Result<()> {
+        self.run_git_remote_command(&["fetch", "--quiet"], askpass)?;
+        Ok(())
+    }
+
+    fn pull(&self, strategy: PullStrategy, askpass: Option<&Path>) -> Result<()> {
+        let strategy = match strategy {
+            PullStrategy::Merge => "--no-rebase",
+            PullStrategy::Rebase => "--rebase",
+        };
+        self.run_git_remote_command(&["pull", "--quiet", "--no-edit", strategy], askpass)?;
+        Ok(())
+    }
+
+    fn push(&self, force: bool, askpass: Option<&Path>) -> Result<()> {
+        const REMOTE_NAME: &str = "origin";
+
+        let has_upstream = {
+            let repo = self.repository.lock();
+            let head = repo.head()?;
+            anyhow::ensure!(head.is_branch(), "HEAD is not on a branch");
+            git2::Branch::wrap(head).upstream().is_ok()
+        };
+        let mut args = vec!["push", "--quiet"];
+        if force {
+            args.push("--force-with-lease");
+        }
+        if !has_upstream {
+            args.extend(["--set-upstream", REMOTE_NAME, "HEAD"]);
+        }
+        self.run_git_remote_command(&args, askpass)?;
+        Ok(())
+    }
+
+    fn stash_list(&self) -> Result<Vec<StashEntry>> {
+        let output = self.run_git_command(["stash", "list", STASH_LIST_FORMAT])?;
+        parse_stash_list(&output)
+    }
+
+    fn stash_push(&self, message: Option<&str>, options: StashOptions) -> Result<()> {
+        const STASH_REF: &str = "refs/stash";
+
+        let stash_id = || self.repository.lock().refname_to_id(STASH_REF).ok();
+        let previous_stash_id = stash_id();
+        let mut args = vec!["stash", "push", "--quiet"];
+        if options.include_untracked {
+            args.push("--include-untracked");
+        }
+        if options.staged_only {
+            args.push("--staged");
+        }
+        if let Some(message) = message {
+            args.extend(["--message", message]);
+        }
+        self.run_git_command(&args)?;
+        // Git succeeds without creating an entry when there is nothing to stash.
+        if stash_id() == previous_stash_id {
+            anyhow::bail!("No local changes to save");
+        }
+        Ok(())
+    }
+
+    fn stash_apply(&self, index: usize) -> Result<()> {
+        self.run_git_command(["stash", "apply", "--quiet", &format!("stash@{{{index}}}")])?;
+        Ok(())
+    }
+
+    fn stash_pop(&self, index: usize) -> Result<()> {
+        self.run_git_command(["stash", "pop", "--quiet", &format!("stash@{{{index}}}")])?;
+        Ok(())
+    }
+
+    fn stash_drop(&self, index: usize) -> Result<()> {
+        self.run_git_command(["stash", "drop", "--quiet", &format!("stash@{{{index}}}")])?;
+        Ok(())
+    }
+
+    fn rebase_todo(&self, base: &str) -> Result<Vec<RebaseTodoItem>> {
+        // Keep the revision from being interpreted as an option.
+        if base.starts_with('-') {
+            return Err(anyhow!("invalid revision {base:?}"));
+        }
+        let range = format!("{base}..HEAD");
+        let output =
+            self.run_git_command(["log", "--reverse", "--no-merges", LOG_FORMAT, &range, "--"])?;
+        Ok(parse_log(&output)?
+            .into_iter()
+            .map(|commit| RebaseTodoItem::pick(commit.sha.to_string(), commit.subject))
+            .collect())
+    }
+
+    fn rebase(&self, base: &str, todo: &[RebaseTodoItem]) -> Result<RebaseOutcome> {
+        if base.starts_with('-') {
+            return Err(anyhow!("invalid revision {base:?}"));
+        }
+        let mut todo_file = tempfile::NamedTempFile::new()?;
+        todo_file.write_all(serialize_todo(todo)?.as_bytes())?;
+        self.run_git_rebase_command(
+            &["rebase", "--interactive", "--quiet", base],
+            Some(todo_file.path()),
+        )
+    }
+
+    fn rebase_continue(&self) -> Result<RebaseOutcome> {
+        self.run_git_rebase_command(&["rebase", "--continue"], None)
+    }
+
+    fn rebase_abort(&self) -> Result<()> {
+        self.run_git_command(["rebase", "--abort"])?;
+        Ok(())
+    }
+
+    fn worktrees(&self) -> Result<Vec<GitWorktree>> {
+        let output = self.run_git_command(["worktree", "list", "--porcelain"])?;
+        parse_worktree_list(&output)
+    }
+
+    fn create_worktree(&self, path: &Path, revision: &str, new_branch: Option<&str>) -> Result<()> {
+        let mut args = vec![
+            OsStr::new("worktree"),
+            OsStr::new("add"),
+            OsStr::new("--quiet"),
+        ];
+        if let Some(new_branch) = new_branch {
+            args.extend([OsStr::new("-b"), OsStr::new(new_branch)]);
+        }
+        args.extend([OsStr::new("--"), path.as_os_str(), OsStr::new(revision)]);
+        self.run_git_command(args)?;
+        Ok(())
+    }
+
+    fn remove_worktree(&self, path: &Path, force: bool) -> Result<()> {
+        let mut args = vec![OsStr::new("worktree"), OsStr::new("remove")];
+        if force {
+            args.push(OsStr::new("--force"));
+        }
+        args.extend([OsStr::new("--"), path.as_os_str()]);
+        self.run_git_command(args)?;
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -237,10 +912,45 @@ pub struct FakeGitRepositoryState {
     pub dot_git_dir: PathBuf,
     pub event_emitter: smol::channel::Sender<PathBuf>,
     pub index_contents: HashMap<PathBuf, String>,
+    pub head_contents: HashMap<PathBuf, String>,
     pub blames: HashMap<PathBuf, Blame>,
     pub worktree_statuses: HashMap<RepoPath, GitFileStatus>,
     pub current_branch_name: Option<String>,
     pub branches: HashSet<String>,
+    pub staged_paths: HashSet<RepoPath>,
+    pub commit_messages: Vec<String>,
+    /// The commits listed by [`GitRepository::log`], newest first.
+    pub commits: Vec<CommitSummary>,
+    pub commit_diffs: HashMap<Oid, Vec<CommitFileDiff>>,
+    /// The file contents at each revision. Every file listed for a revision is reported by
+    /// [`GitRepository::changed_paths_since`] as changed since that revision.
+    pub revision_contents: HashMap<String, HashMap<RepoPath, String>>,
+    /// The upstream of each branch, updated as if the remote never changed.
+    pub upstreams: HashMap<String, UpstreamBranch>,
+    /// The entries listed by [`GitRepository::stash_list`], most recent first.
+    pub stashes: Vec<StashEntry>,
+    /// The worktree statuses saved by each stash entry, restored when it is applied.
+    pub stashed_statuses: HashMap<Oid, HashMap<RepoPath, GitFileStatus>>,
+    /// The files that conflict when a rebase replays the commit with the given SHA. The
+    /// conflicts only happen once, as if they were resolved when the rebase continues.
+    pub rebase_conflicts: HashMap<String, Vec<RepoPath>>,
+    /// The rebase that stopped on a conflict, if any.
+    pub rebase: Option<FakeRebase>,
+    /// The linked worktrees listed by [`GitRepository::worktrees`] after the main one.
+    pub linked_worktrees: Vec<GitWorktree>,
+}
+
+/// A rebase of a [`FakeGitRepository`] that stopped, rewriting the commits of the fake log.
+#[derive(Debug, Clone)]
+pub struct FakeRebase {
+    /// The commits before the rebase, restored when it is aborted.
+    original_commits: Vec<CommitSummary>,
+    /// The commits the rebased ones are replayed onto, newest first.
+    onto: Vec<CommitSummary>,
+    /// The commits replayed so far, oldest first.
+    done: Vec<CommitSummary>,
+    /// The todo items left to replay.
+    remaining: std::collections::VecDeque<RebaseTodoItem>,
 }
 
 impl FakeGitRepository {
@@ -255,10 +965,22 @@ impl FakeGitRepositoryState {
             dot_git_dir,
             event_emitter,
             index_contents: Default::default(),
+            head_contents: Default::default(),
             blames: Default::default(),
             worktree_statuses: Default::default(),
             current_branch_name: Default::default(),
             branches: Default::default(),
+            staged_paths: Default::default(),
+            commit_messages: Default::default(),
+            commits: Default::default(),
+            commit_diffs: Default::default(),
+            revision_contents: Default::default(),
+            upstreams: Default::default(),
+            stashes: Default::default(),
+            stashed_statuses: Default::default(),
+            rebase_conflicts: Default::default(),
+            rebase: None,
+            linked_worktrees: Default::default(),
         }
     }
 }
@@ -271,6 +993,25 @@ impl GitRepository for FakeGitRepository {
         state.index_contents.get(path).cloned()
     }
 
+    fn load_committed_text(&self, path: &Path) -> Option<String> {
+        let state = self.state.lock();
+        state.head_contents.get(path).cloned()
+    }
+
+    fn set_index_text(&self, path: &RepoPath, content: Option<String>) -> Result<()> {
+        let mut state = self.state.lock();
+        if let Some(content) = content {
+            state.index_contents.insert(path.to_path_buf(), content);
+        } else {
+            state.index_contents.remove(path.as_ref());
+        }
+        state
+            .event_emitter
+            .try_send(state.dot_git_dir.clone())
+            .expect("Dropped repo change event");
+        Ok(())
+    }
+
     fn remote_url(&self, _name: &str) -> Option<String> {
         None
     }
@@ -321,6 +1062,7 @@ impl GitRepository for FakeGitRepository {
                 is_head: Some(branch_name) == current_branch.as_ref(),
                 name: branch_name.into(),
                 unix_timestamp: None,
+                upstream: state.upstreams.get(branch_name).cloned(),
             })
             .collect())
     }
@@ -358,6 +1100,510 @@ impl GitRepository for FakeGitRepository {
             .with_context(|| format!("failed to get blame for {:?}", path))
             .cloned()
     }
+
+    fn staged_paths(&self) -> Result<Vec<RepoPath>> {
+        let state = self.state.lock();
+        let mut paths = state.staged_paths.iter().cloned().collect::<Vec<_>>();
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn stage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        state.staged_paths.extend(paths.iter().cloned());
+        state
+            .event_emitter
+            .try_send(state.dot_git_dir.clone())
+            .expect("Dropped repo change event");
+        Ok(())
+    }
+
+    fn unstage_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        for path in paths {
+            state.staged_paths.remove(path);
+        }
+        state
+            .event_emitter
+            .try_send(state.dot_git_dir.clone())
+            .expect("Dropped repo change event");
+        Ok(())
+    }
+
+    fn discard_paths(&self, paths: &[RepoPath]) -> Result<()> {
+        let mut state = self.state.lock();
+        for path in paths {
+            state.staged_paths.remove(path);
+            state.worktree_statuses.remove(path);
+            match state.head_contents.get(&path.0).cloned() {
+                Some(contents) => {
+                    state.index_contents.insert(path.0.clone(), contents);
+                }
+                None => {
+                    state.index_contents.remove(&path.0);
+                }
+            }
+        }
+        state
+            .event_emitter
+            .try_send(state.dot_git_dir.clone())
+            .expect("Dropped repo change event");
+        Ok(())
+    }
+
+    fn commit(&self, message: &str) -> Result<()> {
+        let mut state = self.state.lock();
+        if state.staged_paths.is_empty() {
+            anyhow::bail!("nothing to commit");
+        }
+        state.staged_paths.clear();
+        state.commit_messages.push(message.to_owned());
+        state
+            .event_emitter
+            .try_send(state.dot_git_dir.clone())
+            .expect("Dropped repo change event");
+        Ok(())
+    }
+
+    fn log(&self, options: &LogOptions) -> Result<Vec<CommitSummary>> {
+        let state = self.state.lock();
+        let matches = |pattern: &Option<String>, texts: &[&str]| {
+            pattern.as_ref().map_or(true, |pattern| {
+                let pattern = pattern.to_lowercase();
+                texts
+                    .iter()
+                    .any(|text| text.to_lowercase().contains(&pattern))
+            })
+        };
+        let commits = state
+            .commits
+            .iter()
+            .filter(|commit| {
+                matches(
+                    &options.author,
+                    &[&commit.author_name, &commit.author_email],
+                ) && matches(&options.message, &[&commit.subject])
+                    && options.path.as_ref().map_or(true, |path| {
+                        state.commit_diffs.get(&commit.sha).map_or(false, |files| {
+                            files.iter().any(|file| file.path.starts_with(path))
+                        })
+                    })
+            })
+            .skip(options.skip);
+        Ok(if options.limit > 0 {
+            commits.take(options.limit).cloned().collect()
+        } else {
+            commits.cloned().collect()
+        })
+    }
+
+    fn commit_diff(&self, sha: Oid) -> Result<Vec<CommitFileDiff>> {
+        let state = self.state.lock();
+        state
+            .commit_diffs
+            .get(&sha)
+            .cloned()
+            .with_context(|| format!("commit {sha} not found"))
+    }
+
+    fn commit_message(&self, revision: &str) -> Result<String> {
+        // Fake commits only have a subject.
+        let state = self.state.lock();
+        let ix = fake_commit_index(&state.commits, revision)?;
+        Ok(state.commits[ix].subject.clone())
+    }
+
+    fn load_revision_text(&self, revision: &str, path: &RepoPath) -> Result<Option<String>> {
+        let state = self.state.lock();
+        let contents = state
+            .revision_contents
+            .get(revision)
+            .with_context(|| format!("unknown revision {revision:?}"))?;
+        Ok(contents.get(path).cloned())
+    }
+
+    fn changed_paths_since(&self, revision: &str) -> Result<Vec<RepoPath>> {
+        let state = self.state.lock();
+        let mut paths = state
+            .revision_contents
+            .get(revision)
+            .with_context(|| format!("unknown revision {revision:?}"))?
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        paths.sort();
+        Ok(paths)
+    }
+
+    fn fetch(&self, _askpass: Option<&Path>) -> Result<()> {
+        Ok(())
+    }
+
+    fn pull(&self, strategy: PullStrategy, _askpass: Option<&Path>) -> Result<()> {
+        let mut state = self.state.lock();
+        let branch = state
+            .current_branch_name
+            .clone()
+            .context("HEAD is not on a branch")?;
+        let upstream = state
+            .upstreams
+            .get_mut(&branch)
+            .with_context(|| format!("branch {branch:?} has no upstream"))?;
+        if strategy == PullStrategy::Merge && upstream.ahead > 0 && upstream.behind > 0 {
+            // The merge commit.
+            upstream.ahead += 1;
+        }
+        upstream.behind = 0;
+        state
+            .event_emitter
+            .try_send(state.dot_git_dir.clone())
+            .expect("Dropped repo change event");
+        Ok(())
+    }
+
+    fn push(&self, force: bool, _askpass: Option<&Path>) -> Result<()> {
+        let mut state = self.state.lock();
+        let branch = state
+            .current_branch_name
+            .clone()
+            .context("HEAD is not on a branch")?;
+        let upstream = state
+            .upstreams
+            .entry(branch.clone())
+            .or_insert_with(|| UpstreamBranch {
+                name: format!("origin/{branch}").into(),
+                ahead: 0,
+                behind: 0,
+            });
+        if upstream.behind > 0 && !force {
+            anyhow::bail!(
+                "updates were rejected because the remote contains commits you don't have"
+            );
+        }
+        upstream.ahead = 0;
+        upstream.behind = 0;
+        state
+            .event_emitter
+            .try_send(state.dot_git_dir.clone())
+            .expect("Dropped repo change event");
+        Ok(())
+    }
+
+    fn stash_list(&self) -> Result<Vec<StashEntry>> {
+        let state = self.state.lock();
+        Ok(state.stashes.clone())
+    }
+
+    fn stash_push(&self, message: Option<&str>, options: StashOptions) -> Result<()> {
+        let mut state = self.state.lock();
+        let stashed_statuses = if options.staged_only {
+            let staged_paths = std::mem::take(&mut state.staged_paths);
+            staged_paths
+                .into_iter()
+                .filter_map(|path| {
+                    let status = state.worktree_statuses.remove(&path)?;
+                    Some((path, status))
+                })
+                .collect::<HashMap<_, _>>()
+        } else {
+            state.staged_paths.clear();
+            let include_untracked = options.include_untracked;
+            let (stashed, kept): (HashMap<_, _>, HashMap<_, _>) =
+                std::mem::take(&mut state.worktree_statuses)
+                    .into_iter()
+                    .partition(|(_, status)| include_untracked || *status != GitFileStatus::Added);
+            state.worktree_statuses = kept;
+            stashed
+        };
+        if stashed_statuses.is_empty() {
+            anyhow::bail!("No local changes to save");
+        }
+
+        let branch = state
+            .current_branch_name
+            .as_deref()
+            .unwrap_or("(no branch)");
+        let message = match message {
+            Some(message) => format!("On {branch}: {message}"),
+            None => format!("WIP on {branch}"),
+        };
+        let sha = Oid(git2::Oid::hash_object(
+            git2::ObjectType::Commit,
+            format!("{}\n{message}", state.stashed_statuses.len()).as_bytes(),
+        )?);
+        state.stashed_statuses.insert(sha, stashed_statuses);
+        state.stashes.insert(
+            0,
+            StashEntry {
+                index: 0,
+                sha,
+                message,
+                timestamp: 0,
+            },
+        );
+        for (index, entry) in state.stashes.iter_mut().enumerate() {
+            entry.index = index;
+        }
+        state
+            .event_emitter
+            .try_send(state.dot_git_dir.clone())
+            .expect("Dropped repo change event");
+        Ok(())
+    }
+
+    fn stash_apply(&self, index: usize) -> Result<()> {
+        let mut state = self.state.lock();
+        let entry = state
+            .stashes
+            .get(index)
+            .with_context(|| format!("stash@{{{index}}} is not a valid reference"))?;
+        let statuses = state
+            .stashed_statuses
+            .get(&entry.sha)
+            .cloned()
+            .unwrap_or_default();
+        if statuses
+            .keys()
+            .any(|path| state.worktree_statuses.contains_key(path))
+        {
+            anyhow::bail!("your local changes would be overwritten by the stash");
+        }
+        state.worktree_statuses.extend(statuses);
+        state
+            .event_emitter
+            .try_send(state.dot_git_dir.clone())
+            .expect("Dropped repo change event");
+        Ok(())
+    }
+
+    fn stash_pop(&self, index: usize) -> Result<()> {
+        self.stash_apply(index)?;
+        self.stash_drop(index)
+    }
+
+    fn stash_drop(&self, index: usize) -> Result<()> {
+        let mut state = self.state.lock();
+        if index >= state.stashes.len() {
+            anyhow::bail!("stash@{{{index}}} is not a valid reference");
+        }
+        let entry = state.stashes.remove(index);
+        state.stashed_statuses.remove(&entry.sha);
+        for (index, entry) in state.stashes.iter_mut().enumerate() {
+            entry.index = index;
+        }
+        state
+            .event_emitter
+            .try_send(state.dot_git_dir.clone())
+            .expect("Dropped repo change event");
+        Ok(())
+    }
+
+    fn rebase_todo(&self, base: &str) -> Result<Vec<RebaseTodoItem>> {
+        let state = self.state.lock();
+        let base_ix = fake_commit_index(&state.commits, base)?;
+        Ok(state.commits[..base_ix]
+            .iter()
+            .rev()
+            .filter(|commit| commit.parents.len() < 2)
+            .map(|commit| RebaseTodoItem::pick(commit.sha.to_string(), commit.subject.clone()))
+            .collect())
+    }
+
+    fn rebase(&self, base: &str, todo: &[RebaseTodoItem]) -> Result<RebaseOutcome> {
+        let mut state = self.state.lock();
+        if state.rebase.is_some() {
+            anyhow::bail!("a rebase is already in progress");
+        }
+        let base_ix = fake_commit_index(&state.commits, base)?;
+        state.rebase = Some(FakeRebase {
+            original_commits: state.commits.clone(),
+            onto: state.commits[base_ix..].to_vec(),
+            done: Vec::new(),
+            remaining: todo.iter().cloned().collect(),
+        });
+        replay_fake_rebase(&mut state)
+    }
+
+    fn rebase_continue(&self) -> Result<RebaseOutcome> {
+        let mut state = self.state.lock();
+        if state.rebase.is_none() {
+            anyhow::bail!("no rebase in progress");
+        }
+        if state
+            .worktree_statuses
+            .values()
+            .any(|status| *status == GitFileStatus::Conflict)
+        {
+            return Ok(RebaseOutcome::Stopped {
+                message: "you must resolve all conflicts before continuing".into(),
+            });
+        }
+        replay_fake_rebase(&mut state)
+    }
+
+    fn rebase_abort(&self) -> Result<()> {
+        let mut state = self.state.lock();
+        let rebase = state.rebase.take().context("no rebase in progress")?;
+        state.commits = rebase.original_commits;
+        state
+            .worktree_statuses
+            .retain(|_, status| *status != GitFileStatus::Conflict);
+        state
+            .event_emitter
+            .try_send(state.dot_git_dir.clone())
+            .expect("Dropped repo change event");
+        Ok(())
+    }
+
+    fn worktrees(&self) -> Result<Vec<GitWorktree>> {
+        let state = self.state.lock();
+        let main_worktree = GitWorktree {
+            path: state
+                .dot_git_dir
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+            head: None,
+            branch: state.current_branch_name.clone(),
+            is_main: true,
+            is_bare: false,
+            is_locked: false,
+            is_prunable: false,
+        };
+        Ok(std::iter::once(main_worktree)
+            .chain(state.linked_worktrees.iter().cloned())
+            .collect())
+    }
+
+    fn create_worktree(&self, path: &Path, revision: &str, new_branch: Option<&str>) -> Result<()> {
+        let mut state = self.state.lock();
+        if state
+            .linked_worktrees
+            .iter()
+            .any(|worktree| worktree.path == path)
+        {
+            anyhow::bail!("'{}' already exists", path.display());
+        }
+        let branch = match new_branch {
+            Some(new_branch) => {
+                if !state.branches.insert(new_branch.to_string()) {
+                    anyhow::bail!("a branch named '{new_branch}' already exists");
+                }
+                Some(new_branch.to_string())
+            }
+            None if state.branches.contains(revision) => {
+                let is_checked_out = state.current_branch_name.as_deref() == Some(revision)
+                    || state
+                        .linked_worktrees
+                        .iter()
+                        .any(|worktree| worktree.branch.as_deref() == Some(revision));
+                if is_checked_out {
+                    anyhow::bail!("'{revision}' is already checked out");
+                }
+                Some(revision.to_string())
+            }
+            None => None,
+        };
+        state.linked_worktrees.push(GitWorktree {
+            path: path.to_path_buf(),
+            head: None,
+            branch,
+            is_main: false,
+            is_bare: false,
+            is_locked: false,
+            is_prunable: false,
+        });
+        state
+            .event_emitter
+            .try_send(state.dot_git_dir.clone())
+            .expect("Dropped repo change event");
+        Ok(())
+    }
+
+    fn remove_worktree(&self, path: &Path, _force: bool) -> Result<()> {
+        let mut state = self.state.lock();
+        let ix = state
+            .linked_worktrees
+            .iter()
+            .position(|worktree| worktree.path == path)
+            .with_context(|| format!("'{}' is not a working tree", path.display()))?;
+        state.linked_worktrees.remove(ix);
+        state
+            .event_emitter
+            .try_send(state.dot_git_dir.clone())
+            .expect("Dropped repo change event");
+        Ok(())
+    }
+}
+
+/// Returns the index of a commit in a fake log, looked up by its SHA or by a prefix of it.
+fn fake_commit_index(commits: &[CommitSummary], revision: &str) -> Result<usize> {
+    commits
+        .iter()
+        .position(|commit| commit.sha.to_string().starts_with(revision))
+        .with_context(|| format!("unknown revision {revision:?}"))
+}
+
+/// Replays the remaining items of the rebase in progress, until it completes or a commit
+/// conflicts. Completing the rebase rewrites the fake log, keeping the SHAs of the commits.
+fn replay_fake_rebase(state: &mut FakeGitRepositoryState) -> Result<RebaseOutcome> {
+    let mut rebase = state.rebase.take().context("no rebase in progress")?;
+    while let Some(item) = rebase.remaining.pop_front() {
+        // Commands such as `exec` aren't run by the fake repository.
+        if item.action == RebaseAction::Command {
+            continue;
+        }
+        let ix = fake_commit_index(&rebase.original_commits, &item.sha)?;
+        let mut commit = rebase.original_commits[ix].clone();
+        match item.action {
+            RebaseAction::Pick => rebase.done.push(commit),
+            RebaseAction::Reword => {
+                if let Some(message) = &item.message {
+                    commit.subject = message.lines().next().unwrap_or_default().to_string();
+                }
+                rebase.done.push(commit);
+            }
+            RebaseAction::Squash | RebaseAction::Fixup => {
+                if rebase.done.is_empty() {
+                    state.rebase = Some(rebase);
+                    return Err(anyhow!(
+                        "cannot '{}' without a previous commit",
+                        item.action.as_str()
+                    ));
+                }
+            }
+            RebaseAction::Drop | RebaseAction::Command => continue,
+        }
+
+        if let Some(paths) = state.rebase_conflicts.remove(&commit.sha.to_string()) {
+            for path in paths {
+                state
+                    .worktree_statuses
+                    .insert(path, GitFileStatus::Conflict);
+            }
+            state.rebase = Some(rebase);
+            state
+                .event_emitter
+                .try_send(state.dot_git_dir.clone())
+                .expect("Dropped repo change event");
+            return Ok(RebaseOutcome::Stopped {
+                message: format!("could not apply {}", commit.sha.display_short()),
+            });
+        }
+    }
+
+    let mut parent = rebase.onto.first().map(|commit| commit.sha);
+    for commit in &mut rebase.done {
+        commit.parents = parent.into_iter().collect();
+        commit.refs.clear();
+        parent = Some(commit.sha);
+    }
+    state.commits = rebase.done.into_iter().rev().chain(rebase.onto).collect();
+    state
+        .event_emitter
+        .try_send(state.dot_git_dir.clone())
+        .expect("Dropped repo change event");
+    Ok(RebaseOutcome::Completed)
 }
 
 fn check_path_to_repo_path_errors(relative_file_path: &Path) -> Result<()> {
@@ -476,3 +1722,329 @@ impl<'a> MapSeekTarget<RepoPath> for RepoPathDescendants<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use util::test::temp_tree;
+
+    fn git(working_directory: &Path, args: &[&str]) {
+        let output = std::process::Command::new("git")
+            .current_dir(working_directory)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {args:?} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+
+    fn clone(root: &Path, name: &str) -> PathBuf {
+        git(root, &["clone", "--quiet", "remote.git", name]);
+        let path = root.join(name);
+        git(&path, &["config", "user.name", "Test"]);
+        git(&path, &["config", "user.email", "test@example.com"]);
+        git(&path, &["config", "commit.gpgsign", "false"]);
+        path
+    }
+
+    fn commit_file(path: &Path, file_name: &str, content: &str) {
+        std::fs::write(path.join(file_name), content).unwrap();
+        git(path, &["add", file_name]);
+        git(path, &["commit", "--quiet", "-m", file_name]);
+    }
+
+    fn upstream(repo: &RealGitRepository) -> Option<UpstreamBranch> {
+        repo.branches()
+            .unwrap()
+            .into_iter()
+            .find(|branch| branch.is_head)
+            .unwrap()
+            .upstream
+    }
+
+    #[test]
+    fn test_remote_operations() {
+        let root = temp_tree(json!({}));
+        let root = root.path();
+        git(root, &["init", "--quiet", "--bare", "remote.git"]);
+
+        let local_path = clone(root, "local");
+        commit_file(&local_path, "a.txt", "a");
+        let repo = RealGitRepository::new(
+            git2::Repository::open(&local_path).unwrap(),
+            None,
+            Arc::new(GitHostingProviderRegistry::new()),
+        );
+        assert_eq!(upstream(&repo), None);
+
+        // The first push sets the upstream of the branch.
+        repo.push(false, None).unwrap();
+        let branch_name = repo.branch_name().unwrap();
+        assert_eq!(
+            upstream(&repo),
+            Some(UpstreamBranch {
+                name: format!("origin/{branch_name}").into(),
+                ahead: 0,
+                behind: 0,
+            })
+        );
+
+        // Commits pushed from another clone only count once fetched.
+        let other_path = clone(root, "other");
+        commit_file(&other_path, "b.txt", "b");
+        git(&other_path, &["push", "--quiet"]);
+        commit_file(&local_path, "c.txt", "c");
+        assert_eq!(upstream(&repo).map(|u| (u.ahead, u.behind)), Some((1, 0)));
+        repo.fetch(None).unwrap();
+        assert_eq!(upstream(&repo).map(|u| (u.ahead, u.behind)), Some((1, 1)));
+
+        repo.pull(PullStrategy::Rebase, None).unwrap();
+        assert!(local_path.join("b.txt").exists());
+        assert_eq!(upstream(&repo).map(|u| (u.ahead, u.behind)), Some((1, 0)));
+        repo.push(false, None).unwrap();
+        assert_eq!(upstream(&repo).map(|u| (u.ahead, u.behind)), Some((0, 0)));
+
+        // Rewriting a pushed commit requires a forced push.
+        git(
+            &local_path,
+            &["commit", "--quiet", "--amend", "-m", "amended"],
+        );
+        assert_eq!(upstream(&repo).map(|u| (u.ahead, u.behind)), Some((1, 1)));
+        assert!(repo.push(false, None).is_err());
+        repo.push(true, None).unwrap();
+        assert_eq!(upstream(&repo).map(|u| (u.ahead, u.behind)), Some((0, 0)));
+
+        // Merging diverged branches creates a merge commit.
+        git(&other_path, &["pull", "--quiet", "--rebase"]);
+        commit_file(&other_path, "d.txt", "d");
+        git(&other_path, &["push", "--quiet"]);
+        commit_file(&local_path, "e.txt", "e");
+        repo.pull(PullStrategy::Merge, None).unwrap();
+        assert!(local_path.join("d.txt").exists());
+        assert_eq!(upstream(&repo).map(|u| (u.ahead, u.behind)), Some((2, 0)));
+    }
+
+    #[test]
+    fn test_stash() {
+        let root = temp_tree(json!({}));
+        let root = root.path();
+        git(root, &["init", "--quiet", "--bare", "remote.git"]);
+        let path = clone(root, "local");
+        commit_file(&path, "a.txt", "a");
+        commit_file(&path, "b.txt", "b");
+        let repo = RealGitRepository::new(
+            git2::Repository::open(&path).unwrap(),
+            None,
+            Arc::new(GitHostingProviderRegistry::new()),
+        );
+        assert_eq!(repo.stash_list().unwrap(), []);
+        assert!(repo.stash_push(None, StashOptions::default()).is_err());
+
+        // Untracked files are only stashed when asked to.
+        std::fs::write(path.join("a.txt"), "a2").unwrap();
+        std::fs::write(path.join("c.txt"), "c").unwrap();
+        repo.stash_push(Some("first"), StashOptions::default())
+            .unwrap();
+        assert_eq!(std::fs::read_to_string(path.join("a.txt")).unwrap(), "a");
+        assert!(path.join("c.txt").exists());
+        repo.stash_push(
+            None,
+            StashOptions {
+                include_untracked: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert!(!path.join("c.txt").exists());
+
+        let entries = repo.stash_list().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].index, 0);
+        assert!(entries[0].message.starts_with("WIP on "));
+        assert_eq!(entries[1].index, 1);
+        assert!(entries[1].message.ends_with(": first"));
+
+        // Only the staged changes are stashed when asked to.
+        std::fs::write(path.join("a.txt"), "a3").unwrap();
+        std::fs::write(path.join("b.txt"), "b3").unwrap();
+        git(&path, &["add", "b.txt"]);
+        repo.stash_push(
+            Some("staged"),
+            StashOptions {
+                staged_only: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(std::fs::read_to_string(path.join("a.txt")).unwrap(), "a3");
+        assert_eq!(std::fs::read_to_string(path.join("b.txt")).unwrap(), "b");
+        assert_eq!(repo.stash_list().unwrap().len(), 3);
+        git(&path, &["checkout", "--quiet", "--", "a.txt"]);
+
+        // Applying keeps the entry, popping removes it.
+        repo.stash_apply(0).unwrap();
+        assert_eq!(std::fs::read_to_string(path.join("b.txt")).unwrap(), "b3");
+        assert_eq!(repo.stash_list().unwrap().len(), 3);
+        git(&path, &["reset", "--quiet", "--hard"]);
+        repo.stash_drop(0).unwrap();
+        repo.stash_pop(1).unwrap();
+        assert_eq!(std::fs::read_to_string(path.join("a.txt")).unwrap(), "a2");
+        let entries = repo.stash_list().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].index, 0);
+        assert!(entries[0].message.starts_with("WIP on "));
+        assert!(repo.stash_drop(1).is_err());
+    }
+
+    #[test]
+    fn test_interactive_rebase() {
+        let root = temp_tree(json!({}));
+        let root = root.path();
+        git(root, &["init", "--quiet", "--bare", "remote.git"]);
+        let path = clone(root, "local");
+        for name in ["a", "b", "c", "d"] {
+            commit_file(&path, &format!("{name}.txt"), name);
+        }
+        let repo = RealGitRepository::new(
+            git2::Repository::open(&path).unwrap(),
+            None,
+            Arc::new(GitHostingProviderRegistry::new()),
+        );
+        let subjects = |repo: &RealGitRepository| {
+            repo.log(&LogOptions::default())
+                .unwrap()
+                .into_iter()
+                .map(|commit| commit.subject)
+                .collect::<Vec<_>>()
+        };
+
+        let todo = repo.rebase_todo("HEAD~3").unwrap();
+        assert_eq!(
+            todo.iter()
+                .map(|item| (item.action, item.subject.as_str()))
+                .collect::<Vec<_>>(),
+            [
+                (RebaseAction::Pick, "b.txt"),
+                (RebaseAction::Pick, "c.txt"),
+                (RebaseAction::Pick, "d.txt"),
+            ]
+        );
+
+        // Reorder, reword and squash.
+        let [b, c, d] = [&todo[0], &todo[1], &todo[2]];
+        let todo = [
+            d.clone(),
+            RebaseTodoItem {
+                action: RebaseAction::Reword,
+                message: Some("Don't panic\n\nDetails".into()),
+                ..b.clone()
+            },
+            RebaseTodoItem {
+                action: RebaseAction::Squash,
+                ..c.clone()
+            },
+        ];
+        assert_eq!(
+            repo.rebase("HEAD~3", &todo).unwrap(),
+            RebaseOutcome::Completed
+        );
+        assert_eq!(subjects(&repo), ["Don't panic", "d.txt", "a.txt"]);
+
+        // Dropping the commit that adds a file conflicts with the commit that changes it.
+        std::fs::write(path.join("d.txt"), "d2").unwrap();
+        git(&path, &["commit", "--quiet", "--all", "-m", "d2"]);
+        let todo = repo.rebase_todo("HEAD~3").unwrap();
+        let todo = [
+            todo[1].clone(),
+            RebaseTodoItem {
+                action: RebaseAction::Drop,
+                ..todo[0].clone()
+            },
+            todo[2].clone(),
+        ];
+        let RebaseOutcome::Stopped { .. } = repo.rebase("HEAD~3", &todo).unwrap() else {
+            panic!("the rebase should stop on the conflict");
+        };
+        // The rebase can't continue until the conflict is resolved.
+        let RebaseOutcome::Stopped { .. } = repo.rebase_continue().unwrap() else {
+            panic!("the conflict should be resolved first");
+        };
+        repo.rebase_abort().unwrap();
+        assert_eq!(subjects(&repo), ["d2", "Don't panic", "d.txt", "a.txt"]);
+
+        let RebaseOutcome::Stopped { .. } = repo.rebase("HEAD~3", &todo).unwrap() else {
+            panic!("the rebase should stop on the conflict");
+        };
+        git(&path, &["add", "d.txt"]);
+        assert_eq!(repo.rebase_continue().unwrap(), RebaseOutcome::Completed);
+        assert_eq!(subjects(&repo), ["d2", "Don't panic", "a.txt"]);
+        assert_eq!(std::fs::read_to_string(path.join("d.txt")).unwrap(), "d2");
+    }
+
+    #[test]
+    fn test_worktrees() {
+        let root = temp_tree(json!({}));
+        let root = root.path().canonicalize().unwrap();
+        git(&root, &["init", "--quiet", "--bare", "remote.git"]);
+        let path = clone(&root, "local");
+        commit_file(&path, "a.txt", "a");
+        git(&path, &["branch", "--move", "main"]);
+        git(&path, &["branch", "feature"]);
+        let repo = RealGitRepository::new(
+            git2::Repository::open(&path).unwrap(),
+            None,
+            Arc::new(GitHostingProviderRegistry::new()),
+        );
+        let checkouts = |repo: &RealGitRepository| {
+            let mut checkouts = repo
+                .worktrees()
+                .unwrap()
+                .into_iter()
+                .map(|worktree| (worktree.path, worktree.branch, worktree.is_main))
+                .collect::<Vec<_>>();
+            // Git lists the linked worktrees in no particular order.
+            checkouts[1..].sort();
+            checkouts
+        };
+        assert_eq!(
+            checkouts(&repo),
+            [(path.clone(), Some("main".to_string()), true)]
+        );
+
+        let review_path = root.join("local-review");
+        let detached_path = root.join("local-detached");
+        repo.create_worktree(&review_path, "feature", None).unwrap();
+        repo.create_worktree(&detached_path, "HEAD", None).unwrap();
+        // A branch can't be checked out by two worktrees.
+        assert!(repo
+            .create_worktree(&root.join("local-other"), "feature", None)
+            .is_err());
+        repo.create_worktree(&root.join("local-new"), "HEAD", Some("new"))
+            .unwrap();
+        assert_eq!(
+            checkouts(&repo),
+            [
+                (path.clone(), Some("main".to_string()), true),
+                (detached_path.clone(), None, false),
+                (root.join("local-new"), Some("new".to_string()), false),
+                (review_path.clone(), Some("feature".to_string()), false),
+            ]
+        );
+        assert_eq!(
+            std::fs::read_to_string(review_path.join("a.txt")).unwrap(),
+            "a"
+        );
+
+        // Worktrees with untracked files are only removed when forced to.
+        std::fs::write(review_path.join("b.txt"), "b").unwrap();
+        assert!(repo.remove_worktree(&review_path, false).is_err());
+        repo.remove_worktree(&review_path, true).unwrap();
+        repo.remove_worktree(&detached_path, false).unwrap();
+        assert!(!review_path.exists());
+        assert_eq!(checkouts(&repo).len(), 2);
+    }
+}
@@ -0,0 +1,106 @@
+use crate::{repository::GitRepository, Oid};
+use anyhow::{anyhow, Result};
+
+/// An entry of the stash, as listed by [`crate::repository::GitRepository::stash_list`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StashEntry {
+    /// The position of the entry in the stash, the most recent being `0` as in `stash@{0}`.
+    pub index: usize,
+    pub sha: Oid,
+    /// Describes the entry, e.g. `WIP on main: 1234abc Subject` or `On main: custom message`.
+    pub message: String,
+    /// When the entry was created, normalized to Unix Epoch format.
+    pub timestamp: i64,
+}
+
+impl StashEntry {
+    /// The name git refers to the entry by, e.g. `stash@{0}`.
+    pub fn name(&self) -> String {
+        format!("stash@{{{}}}", self.index)
+    }
+}
+
+/// Selects the changes saved by [`crate::repository::GitRepository::stash_push`]. By default,
+/// the staged and unstaged changes to tracked files are saved.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StashOptions {
+    /// Also save and remove untracked files.
+    pub include_untracked: bool,
+    /// Only save the staged changes, leaving the unstaged ones in the working directory.
+    pub staged_only: bool,
+}
+
+/// What to do with an existing stash entry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StashAction {
+    /// Restores the changes of the entry, keeping it in the stash.
+    Apply,
+    /// Restores the changes of the entry and removes it from the stash, unless they conflict.
+    Pop,
+    /// Removes the entry from the stash without restoring its changes.
+    Drop,
+}
+
+impl StashAction {
+    pub fn run(self, repo: &dyn GitRepository, index: usize) -> Result<()> {
+        match self {
+            StashAction::Apply => repo.stash_apply(index),
+            StashAction::Pop => repo.stash_pop(index),
+            StashAction::Drop => repo.stash_drop(index),
+        }
+    }
+}
+
+const STASH_FIELD_SEPARATOR: char = '\x1f';
+
+/// The `--format` argument whose output [`parse_stash_list`] understands.
+pub(crate) const STASH_LIST_FORMAT: &str = "--format=%H%x1f%ct%x1f%gs";
+
+pub(crate) fn parse_stash_list(output: &str) -> Result<Vec<StashEntry>> {
+    output
+        .lines()
+        .filter(|line| !line.is_empty())
+        .enumerate()
+        .map(|(index, line)| {
+            let mut fields = line.splitn(3, STASH_FIELD_SEPARATOR);
+            let mut next_field = || {
+                fields
+                    .next()
+                    .ok_or_else(|| anyhow!("malformed git stash entry: {line:?}"))
+            };
+            Ok(StashEntry {
+                index,
+                sha: next_field()?.parse()?,
+                timestamp: next_field()?.parse()?,
+                message: next_field()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stash_list() {
+        let output = concat!(
+            "d0f1e2c3b4a5968778695a4b3c2d1e0f1a2b3c4d\x1f1700000100\x1fOn main: halfway there\n",
+            "0123456789abcdef0123456789abcdef01234567\x1f1700000000\x1fWIP on main: 1234abc Subject\n",
+        );
+        let entries = parse_stash_list(output).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].name(), "stash@{0}");
+        assert_eq!(
+            entries[0].sha,
+            "d0f1e2c3b4a5968778695a4b3c2d1e0f1a2b3c4d".parse().unwrap()
+        );
+        assert_eq!(entries[0].timestamp, 1700000100);
+        assert_eq!(entries[0].message, "On main: halfway there");
+        assert_eq!(entries[1].index, 1);
+        assert_eq!(entries[1].message, "WIP on main: 1234abc Subject");
+
+        assert_eq!(parse_stash_list("").unwrap(), []);
+        assert!(parse_stash_list("not a stash entry\n").is_err());
+    }
+}
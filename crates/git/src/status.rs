@@ -59,6 +59,9 @@ impl GitStatus {
                         match status {
                             "A" | "??" => GitFileStatus::Added,
                             "M" => GitFileStatus::Modified,
+                            "DD" | "AU" | "UD" | "UA" | "DU" | "AA" | "UU" => {
+                                GitFileStatus::Conflict
+                            }
                             _ => return None,
                         },
                     ))
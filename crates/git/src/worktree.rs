@@ -0,0 +1,200 @@
+use crate::Oid;
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+
+/// A working tree of a repository, as listed by [`crate::repository::GitRepository::worktrees`].
+/// Besides the main one, a repository can have linked worktrees created with
+/// `git worktree add`, each checking out its own branch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GitWorktree {
+    pub path: PathBuf,
+    /// The commit checked out, unless the worktree is bare.
+    pub head: Option<Oid>,
+    /// The short name of the branch checked out, unless `HEAD` is detached.
+    pub branch: Option<String>,
+    /// Whether this is the main worktree, whose `.git` directory is shared with the linked ones.
+    pub is_main: bool,
+    pub is_bare: bool,
+    /// Whether the worktree is locked, which prevents it from being removed or pruned.
+    pub is_locked: bool,
+    /// Whether the directory of the worktree is missing, so that `git worktree prune` would
+    /// remove it.
+    pub is_prunable: bool,
+}
+
+impl GitWorktree {
+    /// Describes what the worktree checks out, e.g. `main` or `1a2b3c4 (detached)`.
+    pub fn checkout_label(&self) -> String {
+        match (&self.branch, &self.head) {
+            (Some(branch), _) => branch.clone(),
+            (None, Some(head)) => format!("{} (detached)", head.display_short()),
+            (None, None) if self.is_bare => "(bare)".to_string(),
+            (None, None) => String::new(),
+        }
+    }
+}
+
+/// Parses the output of `git worktree list --porcelain`, in which each worktree is a block of
+/// lines separated from the next by an empty line.
+pub(crate) fn parse_worktree_list(output: &str) -> Result<Vec<GitWorktree>> {
+    let mut worktrees = Vec::new();
+    for block in output.split("\n\n") {
+        let mut lines = block.lines().filter(|line| !line.is_empty());
+        let Some(first_line) = lines.next() else {
+            continue;
+        };
+        let path = first_line
+            .strip_prefix("worktree ")
+            .ok_or_else(|| anyhow!("malformed git worktree entry: {first_line:?}"))?;
+        let mut worktree = GitWorktree {
+            path: PathBuf::from(path),
+            head: None,
+            branch: None,
+            is_main: worktrees.is_empty(),
+            is_bare: false,
+            is_locked: false,
+            is_prunable: false,
+        };
+        for line in lines {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "HEAD" => worktree.head = Some(value.parse()?),
+                "branch" => {
+                    let branch = value.strip_prefix("refs/heads/").unwrap_or(value);
+                    worktree.branch = Some(branch.to_string());
+                }
+                "bare" => worktree.is_bare = true,
+                "locked" => worktree.is_locked = true,
+                "prunable" => worktree.is_prunable = true,
+                _ => {}
+            }
+        }
+        worktrees.push(worktree);
+    }
+    Ok(worktrees)
+}
+
+/// Returns the git directory a `.git` file points to. Linked worktrees and submodules have such
+/// a file instead of a `.git` directory, containing `gitdir: <path>` where the path may be
+/// relative to the directory of the file.
+pub fn parse_gitdir_file(contents: &str, dot_git_abs_path: &Path) -> Option<PathBuf> {
+    let git_dir = contents.lines().next()?.strip_prefix("gitdir:")?.trim();
+    if git_dir.is_empty() {
+        return None;
+    }
+    Some(dot_git_abs_path.parent()?.join(git_dir))
+}
+
+/// The path a new worktree checking out `revision` is created at by default: next to the main
+/// worktree, named after it and the revision, e.g. `../zed-feature-x` for `feature/x`.
+pub fn default_worktree_path(main_worktree: &Path, revision: &str) -> PathBuf {
+    let name = main_worktree
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let revision = revision
+        .chars()
+        .map(|ch| {
+            if ch.is_alphanumeric() || matches!(ch, '-' | '_' | '.') {
+                ch
+            } else {
+                '-'
+            }
+        })
+        .collect::<String>();
+    let revision = revision.trim_matches(['-', '.']);
+    main_worktree.with_file_name(format!("{name}-{revision}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_worktree_list() {
+        let output = concat!(
+            "worktree /code/zed\n",
+            "HEAD 1a2b3c4d5e6f7a8b9c0d1e2f3a4b5c6d7e8f9a0b\n",
+            "branch refs/heads/main\n",
+            "\n",
+            "worktree /code/zed-review\n",
+            "HEAD 0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f\n",
+            "detached\n",
+            "locked reviewing a pull request\n",
+            "\n",
+            "worktree /code/zed-gone\n",
+            "HEAD 0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f0f\n",
+            "branch refs/heads/gone\n",
+            "prunable gitdir file points to non-existent location\n",
+            "\n",
+        );
+        let worktrees = parse_worktree_list(output).unwrap();
+        assert_eq!(
+            worktrees
+                .iter()
+                .map(|worktree| (
+                    worktree.path.to_str().unwrap(),
+                    worktree.checkout_label(),
+                    worktree.is_main,
+                    worktree.is_locked,
+                    worktree.is_prunable
+                ))
+                .collect::<Vec<_>>(),
+            [
+                ("/code/zed", "main".to_string(), true, false, false),
+                (
+                    "/code/zed-review",
+                    "0f0f0f0 (detached)".to_string(),
+                    false,
+                    true,
+                    false
+                ),
+                ("/code/zed-gone", "gone".to_string(), false, false, true),
+            ]
+        );
+
+        let worktrees = parse_worktree_list("worktree /code/zed.git\nbare\n\n").unwrap();
+        assert!(worktrees[0].is_bare);
+        assert_eq!(worktrees[0].checkout_label(), "(bare)");
+        assert!(parse_worktree_list("HEAD 0f0f0f0\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_gitdir_file() {
+        assert_eq!(
+            parse_gitdir_file(
+                "gitdir: /code/zed/.git/worktrees/review\n",
+                Path::new("/code/review/.git")
+            ),
+            Some(PathBuf::from("/code/zed/.git/worktrees/review"))
+        );
+        assert_eq!(
+            parse_gitdir_file(
+                "gitdir: ../.git/modules/sub\n",
+                Path::new("/code/zed/sub/.git")
+            ),
+            Some(PathBuf::from("/code/zed/sub/../.git/modules/sub"))
+        );
+        assert_eq!(parse_gitdir_file("", Path::new("/code/zed/.git")), None);
+        assert_eq!(
+            parse_gitdir_file("ref: refs/heads/main\n", Path::new("/code/zed/.git")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_default_worktree_path() {
+        assert_eq!(
+            default_worktree_path(Path::new("/code/zed"), "feature/x"),
+            Path::new("/code/zed-feature-x")
+        );
+        assert_eq!(
+            default_worktree_path(Path::new("/code/zed"), "HEAD~2"),
+            Path::new("/code/zed-HEAD-2")
+        );
+        assert_eq!(
+            default_worktree_path(Path::new("/code/zed"), "refs/pull/12/head/"),
+            Path::new("/code/zed-refs-pull-12-head")
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use anyhow::{anyhow, Context as _, Result};
+use editor::{Editor, MultiBuffer, DEFAULT_MULTIBUFFER_CONTEXT};
+use futures::future::join_all;
+use git::Oid;
+use gpui::{Task, ViewContext};
+use language::{Anchor, Buffer, Capability};
+use project::{buffer_store::BufferChangeSet, ProjectPath};
+use workspace::{notifications::NotifyTaskExt, Workspace};
+use zed_actions::git::ViewCommit;
+
+pub(crate) fn deploy(
+    workspace: &mut Workspace,
+    action: &ViewCommit,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let repository = workspace
+        .project()
+        .read(cx)
+        .find_worktree(&action.path, cx)
+        .map(|(worktree, path)| ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: path.into(),
+        });
+    let sha = action.sha.parse::<Oid>();
+    let task = match (repository, sha) {
+        (Some(repository), Ok(sha)) => {
+            let title = format!("Commit {}", sha.display_short());
+            open_commit(workspace, repository, sha, title, cx)
+        }
+        (None, _) => Task::ready(Err(anyhow!("{:?} is not part of the project", action.path))),
+        (_, Err(error)) => Task::ready(Err(error)),
+    };
+    task.detach_and_notify_err(cx);
+}
+
+/// Opens a read-only multibuffer showing the changes `sha` made to each file, with the contents
+/// of its parent shown as expanded diff hunks. `repository` is any path within the repository.
+pub(crate) fn open_commit(
+    workspace: &mut Workspace,
+    repository: ProjectPath,
+    sha: Oid,
+    title: String,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<()>> {
+    let project = workspace.project().clone();
+    let files = project.read(cx).commit_diff(repository, sha, cx);
+    let languages = project.read(cx).languages().clone();
+
+    cx.spawn(|workspace, mut cx| async move {
+        let files = files.await.context("loading the commit diff")?;
+        let mut files_with_language = Vec::with_capacity(files.len());
+        for file in files {
+            let language = languages.language_for_file_path(&file.path).await.ok();
+            files_with_language.push((file, language));
+        }
+
+        let (buffers, change_sets, diffs_computed) = workspace.update(&mut cx, |_, cx| {
+            let mut buffers = Vec::new();
+            let mut change_sets = Vec::new();
+            let mut diffs_computed = Vec::new();
+            for (file, language) in files_with_language {
+                let buffer = cx.new_model(|cx| {
+                    let mut buffer = Buffer::local(file.new_text.unwrap_or_default(), cx);
+                    if let Some(language) = language {
+                        buffer.set_language(Some(language), cx);
+                    }
+                    buffer.set_capability(Capability::ReadOnly, cx);
+                    buffer
+                });
+                let change_set = cx.new_model(|cx| {
+                    let buffer_snapshot = buffer.read(cx).text_snapshot();
+                    let mut change_set = BufferChangeSet::new(&buffer_snapshot);
+                    diffs_computed.push(change_set.set_base_text(
+                        file.old_text.unwrap_or_default(),
+                        buffer_snapshot,
+                        cx,
+                    ));
+                    change_set
+                });
+                buffers.push(buffer);
+                change_sets.push(change_set);
+            }
+            (buffers, change_sets, diffs_computed)
+        })?;
+        join_all(diffs_computed).await;
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let multibuffer = cx.new_model(|cx| {
+                let mut multibuffer = MultiBuffer::new(Capability::ReadOnly).with_title(title);
+                for (buffer, change_set) in buffers.iter().zip(&change_sets) {
+                    let buffer_snapshot = buffer.read(cx).text_snapshot();
+                    let hunk_ranges = change_set
+                        .read(cx)
+                        .diff_hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &buffer_snapshot)
+                        .map(|hunk| hunk.buffer_range)
+                        .collect::<Vec<_>>();
+                    multibuffer.push_excerpts_with_context_lines(
+                        buffer.clone(),
+                        hunk_ranges,
+                        DEFAULT_MULTIBUFFER_CONTEXT,
+                        cx,
+                    );
+                }
+                multibuffer
+            });
+            let editor = cx.new_view(|cx| {
+                let mut editor =
+                    Editor::for_multibuffer(multibuffer, Some(project.clone()), true, cx);
+                editor.set_expand_all_diff_hunks();
+                for change_set in change_sets {
+                    editor.add_change_set(change_set, cx);
+                }
+                editor
+            });
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, cx);
+        })
+    })
+}
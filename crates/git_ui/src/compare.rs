@@ -0,0 +1,349 @@
+use std::{any::Any, path::Path};
+
+use anyhow::{anyhow, Context as _, Result};
+use editor::{Addon, Editor, ExcerptRange, MultiBuffer, DEFAULT_MULTIBUFFER_CONTEXT};
+use futures::future::{join_all, try_join_all};
+use gpui::{AppContext, Model, Subscription, Task, ViewContext};
+use language::{Anchor, Buffer, BufferEvent, Capability};
+use project::{buffer_store::BufferChangeSet, ProjectPath};
+use util::ResultExt;
+use workspace::{notifications::NotifyTaskExt, Workspace};
+use zed_actions::git::{CompareFiles, CompareProjectWithRevision, CompareWithRevision};
+
+use crate::{
+    revision_picker::{RevisionPicker, RevisionTarget},
+    CompareSelections,
+};
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+            workspace.register_action(compare_with_revision);
+            workspace.register_action(compare_project_with_revision);
+            workspace.register_action(compare_files);
+            workspace.register_action(compare_selections);
+        },
+    )
+    .detach();
+}
+
+fn compare_with_revision(
+    workspace: &mut Workspace,
+    action: &CompareWithRevision,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    match &action.revision {
+        Some(revision) => editor
+            .update(cx, |editor, cx| {
+                editor.set_diff_base_revision(Some(revision.clone()), cx)
+            })
+            .detach_and_notify_err(cx),
+        None => RevisionPicker::toggle(workspace, RevisionTarget::Editor(editor.downgrade()), cx),
+    }
+}
+
+fn compare_project_with_revision(
+    workspace: &mut Workspace,
+    action: &CompareProjectWithRevision,
+    cx: &mut ViewContext<Workspace>,
+) {
+    match &action.revision {
+        Some(revision) => {
+            open_revision_diff(workspace, revision.clone(), cx).detach_and_notify_err(cx)
+        }
+        None => RevisionPicker::toggle(workspace, RevisionTarget::Project, cx),
+    }
+}
+
+/// Opens a multibuffer showing how the files of the project changed since `revision`, with their
+/// contents at the revision shown as expanded diff hunks. Unlike in the commit view, the files
+/// stay editable and their diffs are updated as they are edited.
+pub(crate) fn open_revision_diff(
+    workspace: &mut Workspace,
+    revision: String,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<()>> {
+    let project = workspace.project().clone();
+    let changed_paths = project
+        .read(cx)
+        .visible_worktrees(cx)
+        .map(|worktree| {
+            let repository = ProjectPath::root_path(worktree.read(cx).id());
+            project
+                .read(cx)
+                .changed_paths_since(repository, revision.clone(), cx)
+        })
+        .collect::<Vec<_>>();
+
+    cx.spawn(|workspace, mut cx| async move {
+        // Worktrees outside of a repository fail to list their changes, which only matters when
+        // no worktree could list them.
+        let mut paths = Vec::new();
+        let mut error = None;
+        for result in join_all(changed_paths).await {
+            match result {
+                Ok(changed_paths) => paths.extend(changed_paths),
+                Err(err) => error = error.or(Some(err)),
+            }
+        }
+        if paths.is_empty() {
+            return Err(error.unwrap_or_else(|| anyhow!("No files changed since {revision}")));
+        }
+
+        let open_buffers = project.update(&mut cx, |project, cx| {
+            paths
+                .into_iter()
+                .map(|path| project.open_buffer(path, cx))
+                .collect::<Vec<_>>()
+        })?;
+        let buffers = join_all(open_buffers)
+            .await
+            .into_iter()
+            .filter_map(|buffer| buffer.log_err())
+            .collect::<Vec<_>>();
+        let change_sets = project.update(&mut cx, |project, cx| {
+            buffers
+                .iter()
+                .map(|buffer| {
+                    project.open_changes_since_revision(buffer.clone(), revision.clone(), cx)
+                })
+                .collect::<Vec<_>>()
+        })?;
+        let change_sets = try_join_all(change_sets)
+            .await
+            .context("comparing the files with the revision")?;
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let capability = project.read(cx).capability();
+            let multibuffer = cx.new_model(|cx| {
+                let mut multibuffer =
+                    MultiBuffer::new(capability).with_title(format!("Changes since {revision}"));
+                for (buffer, change_set) in buffers.iter().zip(&change_sets) {
+                    let buffer_snapshot = buffer.read(cx).text_snapshot();
+                    let hunk_ranges = change_set
+                        .read(cx)
+                        .diff_hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &buffer_snapshot)
+                        .map(|hunk| hunk.buffer_range)
+                        .collect::<Vec<_>>();
+                    multibuffer.push_excerpts_with_context_lines(
+                        buffer.clone(),
+                        hunk_ranges,
+                        DEFAULT_MULTIBUFFER_CONTEXT,
+                        cx,
+                    );
+                }
+                multibuffer
+            });
+            let editor = cx.new_view(|cx| {
+                let mut editor =
+                    Editor::for_multibuffer(multibuffer, Some(project.clone()), true, cx);
+                editor.set_expand_all_diff_hunks();
+                for change_set in change_sets {
+                    editor.add_change_set(change_set, cx);
+                }
+                editor
+                    .set_diff_base_revision(Some(revision), cx)
+                    .detach_and_log_err(cx);
+                editor
+            });
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, cx);
+        })
+    })
+}
+
+fn compare_files(
+    workspace: &mut Workspace,
+    action: &CompareFiles,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let mut open_buffer = |abs_path: &Path| {
+        let Some((worktree, path)) = project.read(cx).find_worktree(abs_path, cx) else {
+            return Task::ready(Err(anyhow!("{abs_path:?} is not part of the project")));
+        };
+        let project_path = ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: path.into(),
+        };
+        project.update(cx, |project, cx| project.open_buffer(project_path, cx))
+    };
+    let old_buffer = open_buffer(action.old_path.as_path());
+    let new_buffer = open_buffer(action.new_path.as_path());
+    let file_name = |path: &Path| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+    let title = format!(
+        "{} ↔ {}",
+        file_name(&action.old_path),
+        file_name(&action.new_path)
+    );
+
+    cx.spawn(|workspace, mut cx| async move {
+        let (old_buffer, new_buffer) = futures::try_join!(old_buffer, new_buffer)?;
+        workspace
+            .update(&mut cx, |workspace, cx| {
+                open_buffer_diff(workspace, old_buffer, new_buffer, title, cx)
+            })?
+            .await
+    })
+    .detach_and_notify_err(cx);
+}
+
+/// Compares the text of the first two selections of the active editor.
+fn compare_selections(
+    workspace: &mut Workspace,
+    _: &CompareSelections,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(editor) = workspace.active_item_as::<Editor>(cx) else {
+        return;
+    };
+    let selections = editor.update(cx, |editor, cx| {
+        let snapshot = editor.buffer().read(cx).snapshot(cx);
+        editor
+            .selections
+            .all::<usize>(cx)
+            .into_iter()
+            .filter(|selection| !selection.is_empty())
+            .map(|selection| {
+                let text = snapshot
+                    .text_for_range(selection.range())
+                    .collect::<String>();
+                (text, snapshot.language_at(selection.start).cloned())
+            })
+            .collect::<Vec<_>>()
+    });
+    let [(old_text, old_language), (new_text, new_language), ..] = selections.as_slice() else {
+        Task::ready(Err(anyhow!("Select the two ranges of text to compare")))
+            .detach_and_notify_err(cx);
+        return;
+    };
+
+    let [old_buffer, new_buffer] =
+        [(old_text, old_language), (new_text, new_language)].map(|(text, language)| {
+            cx.new_model(|cx| {
+                let mut buffer = Buffer::local(text.clone(), cx);
+                buffer.set_language(language.clone(), cx);
+                buffer
+            })
+        });
+    old_buffer.update(cx, |buffer, cx| {
+        buffer.set_capability(Capability::ReadOnly, cx)
+    });
+    open_buffer_diff(
+        workspace,
+        old_buffer,
+        new_buffer,
+        "Selection Comparison".to_string(),
+        cx,
+    )
+    .detach_and_notify_err(cx);
+}
+
+/// Opens an editor showing `new_buffer` as a whole, with the changes from `old_buffer` shown as
+/// expanded diff hunks. The diff is kept up to date as either buffer is edited.
+pub(crate) fn open_buffer_diff(
+    workspace: &mut Workspace,
+    old_buffer: Model<Buffer>,
+    new_buffer: Model<Buffer>,
+    title: String,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<()>> {
+    let project = workspace.project().clone();
+    let mut diff_computed = None;
+    let change_set = cx.new_model(|cx| {
+        let buffer_snapshot = new_buffer.read(cx).text_snapshot();
+        let mut change_set = BufferChangeSet::new(&buffer_snapshot);
+        diff_computed =
+            Some(change_set.set_base_text(old_buffer.read(cx).text(), buffer_snapshot, cx));
+        change_set
+    });
+
+    cx.spawn(|workspace, mut cx| async move {
+        if let Some(diff_computed) = diff_computed {
+            diff_computed.await.ok();
+        }
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let capability = new_buffer.read(cx).capability();
+            let multibuffer = cx.new_model(|cx| {
+                let mut multibuffer = MultiBuffer::new(capability).with_title(title);
+                multibuffer.push_excerpts(
+                    new_buffer.clone(),
+                    [ExcerptRange {
+                        context: Anchor::MIN..Anchor::MAX,
+                        primary: None,
+                    }],
+                    cx,
+                );
+                multibuffer
+            });
+            let editor = cx.new_view(|cx| {
+                let mut editor =
+                    Editor::for_multibuffer(multibuffer, Some(project.clone()), true, cx);
+                editor.set_expand_all_diff_hunks();
+                editor.add_change_set(change_set.clone(), cx);
+                let addon = BufferDiffAddon::new(old_buffer, new_buffer, change_set, cx);
+                editor.register_addon(addon);
+                editor
+            });
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, cx);
+        })
+    })
+}
+
+/// Recalculates the diff of an [`open_buffer_diff`] editor when either of its buffers changes.
+/// Unlike the change sets of project files, this one isn't tracked by the project.
+struct BufferDiffAddon {
+    _subscriptions: Vec<Subscription>,
+}
+
+impl BufferDiffAddon {
+    fn new(
+        old_buffer: Model<Buffer>,
+        new_buffer: Model<Buffer>,
+        change_set: Model<BufferChangeSet>,
+        cx: &mut ViewContext<Editor>,
+    ) -> Self {
+        let subscriptions = vec![
+            cx.subscribe(&old_buffer, {
+                let new_buffer = new_buffer.clone();
+                let change_set = change_set.clone();
+                move |_, old_buffer, event: &BufferEvent, cx| {
+                    if let BufferEvent::Edited = event {
+                        let base_text = old_buffer.read(cx).text();
+                        let buffer_snapshot = new_buffer.read(cx).text_snapshot();
+                        change_set.update(cx, |change_set, cx| {
+                            let _ = change_set.set_base_text(base_text, buffer_snapshot, cx);
+                        });
+                    }
+                }
+            }),
+            cx.subscribe(
+                &new_buffer,
+                move |_, new_buffer, event: &BufferEvent, cx| {
+                    if let BufferEvent::Edited = event {
+                        let buffer_snapshot = new_buffer.read(cx).text_snapshot();
+                        change_set.update(cx, |change_set, cx| {
+                            let _ = change_set.recalculate_diff(buffer_snapshot, cx);
+                        });
+                    }
+                },
+            ),
+        ];
+        Self {
+            _subscriptions: subscriptions,
+        }
+    }
+}
+
+impl Addon for BufferDiffAddon {
+    fn to_any(&self) -> &dyn Any {
+        self
+    }
+}
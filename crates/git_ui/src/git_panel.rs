@@ -14,14 +14,16 @@ use git::repository::GitFileStatus;
 use util::{ResultExt, TryFutureExt};
 
 use db::kvp::KEY_VALUE_STORE;
+use editor::Editor;
 use gpui::*;
-use project::{Entry, EntryKind, Fs, Project, ProjectEntryId, WorktreeId};
+use project::{Entry, EntryKind, Fs, Project, ProjectEntryId, ProjectPath, WorktreeId};
 use serde::{Deserialize, Serialize};
 use settings::Settings as _;
 use ui::{
     prelude::*, Checkbox, Divider, DividerColor, ElevationIndex, Scrollbar, ScrollbarState, Tooltip,
 };
 use workspace::dock::{DockPosition, Panel, PanelEvent};
+use workspace::notifications::DetachAndPromptErr;
 use workspace::Workspace;
 
 use crate::{git_status_icon, settings::GitPanelSettings};
@@ -51,6 +53,7 @@ pub struct GitStatusEntry {}
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct EntryDetails {
+    worktree_id: WorktreeId,
     filename: String,
     display_name: String,
     path: Arc<Path>,
@@ -58,6 +61,7 @@ struct EntryDetails {
     depth: usize,
     is_expanded: bool,
     status: Option<GitFileStatus>,
+    is_staged: bool,
 }
 
 impl EntryDetails {
@@ -73,6 +77,7 @@ struct SerializedGitPanel {
 
 pub struct GitPanel {
     _workspace: WeakView<Workspace>,
+    commit_editor: View<Editor>,
     current_modifiers: Modifiers,
     focus_handle: FocusHandle,
     fs: Arc<dyn Fs>,
@@ -84,6 +89,10 @@ pub struct GitPanel {
     selected_item: Option<usize>,
     show_scrollbar: bool,
     expanded_dir_ids: HashMap<WorktreeId, Vec<ProjectEntryId>>,
+    /// The paths whose changes are staged in the index, by worktree.
+    staged_paths: HashMap<WorktreeId, HashSet<Arc<Path>>>,
+    reload_staged_paths_task: Task<()>,
+    is_committing: bool,
 
     // The entries that are currently shown in the panel, aka
     // not hidden by folding or such
@@ -125,16 +134,27 @@ impl GitPanel {
                 | project::Event::WorktreeAdded(_)
                 | project::Event::WorktreeOrderChanged => {
                     this.update_visible_entries(None, cx);
+                    this.reload_staged_paths(cx);
                     cx.notify();
                 }
+                project::Event::WorktreeUpdatedGitRepositories(_) => {
+                    this.reload_staged_paths(cx);
+                }
                 _ => {}
             })
             .detach();
 
             let scroll_handle = UniformListScrollHandle::new();
+            let commit_editor = cx.new_view(|cx| {
+                let mut editor = Editor::auto_height(6, cx);
+                editor.set_placeholder_text("Enter commit message", cx);
+                editor
+            });
+            cx.observe(&commit_editor, |_, _, cx| cx.notify()).detach();
 
             let mut this = Self {
                 _workspace: weak_workspace,
+                commit_editor,
                 focus_handle: cx.focus_handle(),
                 fs,
                 pending_serialization: Task::ready(None),
@@ -142,6 +162,9 @@ impl GitPanel {
                 visible_entries: Vec::new(),
                 current_modifiers: cx.modifiers(),
                 expanded_dir_ids: Default::default(),
+                staged_paths: Default::default(),
+                reload_staged_paths_task: Task::ready(()),
+                is_committing: false,
 
                 width: Some(px(360.)),
                 scrollbar_state: ScrollbarState::new(scroll_handle.clone()).parent_view(cx.view()),
@@ -151,6 +174,7 @@ impl GitPanel {
                 hide_scrollbar_task: None,
             };
             this.update_visible_entries(None, cx);
+            this.reload_staged_paths(cx);
             this
         });
 
@@ -254,36 +278,287 @@ impl GitPanel {
 }
 
 impl GitPanel {
-    fn stage_all(&mut self, _: &StageAll, _cx: &mut ViewContext<Self>) {
-        // TODO: Implement stage all
-        println!("Stage all triggered");
+    fn stage_all(&mut self, _: &StageAll, cx: &mut ViewContext<Self>) {
+        for (worktree_id, paths) in self.changed_paths() {
+            self.stage_paths(worktree_id, paths, cx);
+        }
     }
 
-    fn unstage_all(&mut self, _: &UnstageAll, _cx: &mut ViewContext<Self>) {
-        // TODO: Implement unstage all
-        println!("Unstage all triggered");
+    fn unstage_all(&mut self, _: &UnstageAll, cx: &mut ViewContext<Self>) {
+        for (worktree_id, paths) in self.changed_paths() {
+            self.unstage_paths(worktree_id, paths, cx);
+        }
     }
 
-    fn discard_all(&mut self, _: &DiscardAll, _cx: &mut ViewContext<Self>) {
-        // TODO: Implement discard all
-        println!("Discard all triggered");
+    /// Discards the changes of every changed file once the user confirms it.
+    fn discard_all(&mut self, _: &DiscardAll, cx: &mut ViewContext<Self>) {
+        let changed_paths = self.changed_paths();
+        let file_count = changed_paths
+            .iter()
+            .map(|(_, paths)| paths.len())
+            .sum::<usize>();
+        if file_count == 0 {
+            return;
+        }
+
+        let answer = cx.prompt(
+            PromptLevel::Warning,
+            &format!(
+                "Discard the changes of {file_count} {}?",
+                if file_count == 1 { "file" } else { "files" }
+            ),
+            Some("Both staged and unstaged changes are lost, and new files are deleted."),
+            &["Discard", "Cancel"],
+        );
+        let project = self.project.clone();
+        cx.spawn(|this, mut cx| async move {
+            if answer.await != Ok(0) {
+                return Ok(());
+            }
+
+            let discards = project.update(&mut cx, |project, cx| {
+                changed_paths
+                    .into_iter()
+                    .map(|(worktree_id, paths)| project.discard_paths(worktree_id, paths, cx))
+                    .collect::<Vec<_>>()
+            })?;
+            let result = async {
+                for discard in discards {
+                    discard.await?;
+                }
+                anyhow::Ok(())
+            }
+            .await;
+
+            this.update(&mut cx, |this, cx| this.reload_staged_paths(cx))?;
+            result
+        })
+        .detach_and_prompt_err("Failed to discard changes", cx, |_, _| None);
     }
 
     /// Commit all staged changes
-    fn commit_staged_changes(&mut self, _: &CommitStagedChanges, _cx: &mut ViewContext<Self>) {
-        // TODO: Implement commit all staged
-        println!("Commit staged changes triggered");
+    fn commit_staged_changes(&mut self, _: &CommitStagedChanges, cx: &mut ViewContext<Self>) {
+        self.commit(false, cx);
     }
 
     /// Commit all changes, regardless of whether they are staged or not
-    fn commit_all_changes(&mut self, _: &CommitAllChanges, _cx: &mut ViewContext<Self>) {
-        // TODO: Implement commit all changes
-        println!("Commit all changes triggered");
+    fn commit_all_changes(&mut self, _: &CommitAllChanges, cx: &mut ViewContext<Self>) {
+        self.commit(true, cx);
     }
 
-    fn all_staged(&self) -> bool {
-        // TODO: Implement all_staged
-        true
+    fn stage_paths(
+        &mut self,
+        worktree_id: WorktreeId,
+        paths: Vec<Arc<Path>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        // Show the new state right away, the reload once the index has changed confirms it.
+        self.staged_paths
+            .entry(worktree_id)
+            .or_default()
+            .extend(paths.iter().cloned());
+        cx.notify();
+
+        let stage = self.project.read(cx).stage_paths(worktree_id, paths, cx);
+        cx.spawn(|this, mut cx| async move {
+            let result = stage.await;
+            this.update(&mut cx, |this, cx| this.reload_staged_paths(cx))?;
+            result
+        })
+        .detach_and_prompt_err("Failed to stage changes", cx, |_, _| None);
+    }
+
+    fn unstage_paths(
+        &mut self,
+        worktree_id: WorktreeId,
+        paths: Vec<Arc<Path>>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if let Some(staged_paths) = self.staged_paths.get_mut(&worktree_id) {
+            for path in &paths {
+                staged_paths.remove(path);
+            }
+        }
+        cx.notify();
+
+        let unstage = self.project.read(cx).unstage_paths(worktree_id, paths, cx);
+        cx.spawn(|this, mut cx| async move {
+            let result = unstage.await;
+            this.update(&mut cx, |this, cx| this.reload_staged_paths(cx))?;
+            result
+        })
+        .detach_and_prompt_err("Failed to unstage changes", cx, |_, _| None);
+    }
+
+    fn toggle_staged(
+        &mut self,
+        worktree_id: WorktreeId,
+        path: Arc<Path>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        if self.is_staged(worktree_id, &path) {
+            self.unstage_paths(worktree_id, vec![path], cx);
+        } else {
+            self.stage_paths(worktree_id, vec![path], cx);
+        }
+    }
+
+    /// Commits the staged changes of every repository with the message in the commit editor,
+    /// staging all changes first when `include_unstaged` is set.
+    fn commit(&mut self, include_unstaged: bool, cx: &mut ViewContext<Self>) {
+        let message = self.commit_editor.read(cx).text(cx);
+        if self.is_committing || message.trim().is_empty() {
+            return;
+        }
+
+        let project = self.project.clone();
+        let stage = if include_unstaged {
+            self.changed_paths()
+                .into_iter()
+                .map(|(worktree_id, paths)| project.read(cx).stage_paths(worktree_id, paths, cx))
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let worktree_ids = self
+            .visible_entries
+            .iter()
+            .map(|(worktree_id, _, _)| *worktree_id)
+            .collect::<Vec<_>>();
+
+        self.is_committing = true;
+        cx.notify();
+        cx.spawn(|this, mut cx| async move {
+            let result = async {
+                for stage in stage {
+                    stage.await?;
+                }
+
+                let mut repositories = Vec::new();
+                for worktree_id in worktree_ids {
+                    let staged_paths = project
+                        .update(&mut cx, |project, cx| project.staged_paths(worktree_id, cx))?
+                        .await?;
+                    project.update(&mut cx, |project, cx| {
+                        let Some(worktree) = project.worktree_for_id(worktree_id, cx) else {
+                            return;
+                        };
+                        let snapshot = worktree.read(cx).snapshot();
+                        for path in &staged_paths {
+                            let Some(work_directory) = snapshot
+                                .repository_for_path(path)
+                                .and_then(|repository| repository.work_directory(&snapshot))
+                            else {
+                                continue;
+                            };
+                            let repository = ProjectPath {
+                                worktree_id,
+                                path: work_directory.as_ref().into(),
+                            };
+                            if !repositories.contains(&repository) {
+                                repositories.push(repository);
+                            }
+                        }
+                    })?;
+                }
+                if repositories.is_empty() {
+                    anyhow::bail!("There are no staged changes to commit");
+                }
+
+                for repository in repositories {
+                    project
+                        .update(&mut cx, |project, cx| {
+                            project.commit(repository, message.clone(), cx)
+                        })?
+                        .await?;
+                }
+                anyhow::Ok(())
+            }
+            .await;
+
+            this.update(&mut cx, |this, cx| {
+                this.is_committing = false;
+                if result.is_ok() {
+                    this.commit_editor
+                        .update(cx, |commit_editor, cx| commit_editor.clear(cx));
+                }
+                this.reload_staged_paths(cx);
+            })?;
+            result
+        })
+        .detach_and_prompt_err("Failed to commit", cx, |_, _| None);
+    }
+
+    fn reload_staged_paths(&mut self, cx: &mut ViewContext<Self>) {
+        let project = self.project.read(cx);
+        let reloads = project
+            .visible_worktrees(cx)
+            .map(|worktree| {
+                let worktree_id = worktree.read(cx).id();
+                (worktree_id, project.staged_paths(worktree_id, cx))
+            })
+            .collect::<Vec<_>>();
+
+        self.reload_staged_paths_task = cx.spawn(|this, mut cx| async move {
+            let mut staged_paths = HashMap::default();
+            for (worktree_id, reload) in reloads {
+                if let Some(paths) = reload.await.log_err() {
+                    staged_paths.insert(worktree_id, paths.into_iter().collect());
+                }
+            }
+            this.update(&mut cx, |this, cx| {
+                this.staged_paths = staged_paths;
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    /// Returns the changed files shown in the panel, by worktree.
+    fn changed_paths(&self) -> Vec<(WorktreeId, Vec<Arc<Path>>)> {
+        self.visible_entries
+            .iter()
+            .map(|(worktree_id, entries, _)| {
+                let paths = entries
+                    .iter()
+                    .filter(|entry| entry.is_file())
+                    .map(|entry| entry.path.clone())
+                    .collect();
+                (*worktree_id, paths)
+            })
+            .collect()
+    }
+
+    fn is_staged(&self, worktree_id: WorktreeId, path: &Path) -> bool {
+        self.staged_paths
+            .get(&worktree_id)
+            .is_some_and(|staged_paths| staged_paths.contains(path))
+    }
+
+    fn staging_state(&self) -> ToggleState {
+        let mut changed_count = 0;
+        let mut staged_count = 0;
+        for (worktree_id, paths) in self.changed_paths() {
+            changed_count += paths.len();
+            staged_count += paths
+                .iter()
+                .filter(|path| self.is_staged(worktree_id, path))
+                .count();
+        }
+
+        if staged_count == 0 {
+            ToggleState::Unselected
+        } else if staged_count == changed_count {
+            ToggleState::Selected
+        } else {
+            ToggleState::Indeterminate
+        }
+    }
+
+    fn has_staged_changes(&self) -> bool {
+        self.staged_paths
+            .values()
+            .any(|staged_paths| !staged_paths.is_empty())
     }
 
     fn no_entries(&self) -> bool {
@@ -363,6 +638,8 @@ impl GitPanel {
                     let display_name = entry.path.to_string_lossy().into_owned();
 
                     let details = EntryDetails {
+                        worktree_id: *worktree_id,
+                        is_staged: self.is_staged(*worktree_id, &entry.path),
                         filename,
                         display_name,
                         kind: entry.kind,
@@ -455,6 +732,7 @@ impl GitPanel {
         let focus_handle = self.focus_handle(cx).clone();
 
         let changes_string = format!("{} changes", self.entry_count());
+        let staging_state = self.staging_state();
 
         h_flex()
             .h(px(32.))
@@ -464,7 +742,17 @@ impl GitPanel {
             .child(
                 h_flex()
                     .gap_2()
-                    .child(Checkbox::new("all-changes", true.into()).disabled(true))
+                    .child(
+                        Checkbox::new("all-changes", staging_state)
+                            .disabled(self.no_entries())
+                            .on_click(cx.listener(move |this, _, cx| {
+                                if staging_state == ToggleState::Selected {
+                                    this.unstage_all(&UnstageAll, cx)
+                                } else {
+                                    this.stage_all(&StageAll, cx)
+                                }
+                            })),
+                    )
                     .child(div().text_buffer(cx).text_ui_sm(cx).child(changes_string)),
             )
             .child(div().flex_grow())
@@ -484,11 +772,14 @@ impl GitPanel {
                                 )
                             })
                             .icon_size(IconSize::Small)
-                            .disabled(true),
+                            .disabled(self.no_entries())
+                            .on_click(cx.listener(move |_, _, cx| {
+                                cx.dispatch_action(Box::new(DiscardAll))
+                            })),
                     )
-                    .child(if self.all_staged() {
+                    .child(if staging_state == ToggleState::Selected {
                         self.panel_button("unstage-all", "Unstage All").on_click(
-                            cx.listener(move |_, _, cx| cx.dispatch_action(Box::new(DiscardAll))),
+                            cx.listener(move |_, _, cx| cx.dispatch_action(Box::new(UnstageAll))),
                         )
                     } else {
                         self.panel_button("stage-all", "Stage All").on_click(
@@ -501,6 +792,8 @@ impl GitPanel {
     pub fn render_commit_editor(&self, cx: &ViewContext<Self>) -> impl IntoElement {
         let focus_handle_1 = self.focus_handle(cx).clone();
         let focus_handle_2 = self.focus_handle(cx).clone();
+        let can_commit =
+            !self.is_committing && !self.commit_editor.read(cx).text(cx).trim().is_empty();
 
         let commit_staged_button = self
             .panel_button("commit-staged-changes", "Commit")
@@ -513,6 +806,7 @@ impl GitPanel {
                     cx,
                 )
             })
+            .disabled(!can_commit || !self.has_staged_changes())
             .on_click(cx.listener(|this, _: &ClickEvent, cx| {
                 this.commit_staged_changes(&CommitStagedChanges, cx)
             }));
@@ -528,6 +822,7 @@ impl GitPanel {
                     cx,
                 )
             })
+            .disabled(!can_commit || self.no_entries())
             .on_click(cx.listener(|this, _: &ClickEvent, cx| {
                 this.commit_all_changes(&CommitAllChanges, cx)
             }));
@@ -540,19 +835,15 @@ impl GitPanel {
                 .bg(cx.theme().colors().editor_background)
                 .font_buffer(cx)
                 .text_ui_sm(cx)
-                .text_color(cx.theme().colors().text_muted)
-                .child("Add a message")
                 .gap_1()
-                .child(div().flex_grow())
+                .child(div().flex_grow().child(self.commit_editor.clone()))
                 .child(h_flex().child(div().gap_1().flex_grow()).child(
                     if self.current_modifiers.alt {
                         commit_all_button
                     } else {
                         commit_staged_button
                     },
-                ))
-                .cursor(CursorStyle::OperationNotAllowed)
-                .opacity(0.5),
+                )),
         )
     }
 
@@ -658,7 +949,8 @@ impl GitPanel {
     ) -> impl IntoElement {
         let id = id.to_proto() as usize;
         let checkbox_id = ElementId::Name(format!("checkbox_{}", id).into());
-        let is_staged = ToggleState::Selected;
+        let worktree_id = details.worktree_id;
+        let path = details.path.clone();
 
         h_flex()
             .id(id)
@@ -671,7 +963,11 @@ impl GitPanel {
             .font_buffer(cx)
             .text_ui_sm(cx)
             .when(!details.is_dir(), |this| {
-                this.child(Checkbox::new(checkbox_id, is_staged))
+                this.child(
+                    Checkbox::new(checkbox_id, details.is_staged.into()).on_click(cx.listener(
+                        move |this, _, cx| this.toggle_staged(worktree_id, path.clone(), cx),
+                    )),
+                )
             })
             .when_some(details.status, |this, status| {
                 this.child(git_status_icon(status))
@@ -4,8 +4,16 @@ use gpui::{actions, AppContext, Hsla};
 use settings::GitPanelSettings;
 use ui::{Color, Icon, IconName, IntoElement};
 
+mod commit_view;
+mod compare;
 pub mod git_panel;
+pub mod history_view;
+mod rebase_editor;
+mod remote_operations;
+mod revision_picker;
 mod settings;
+mod stash_picker;
+mod worktree_picker;
 
 actions!(
     git_ui,
@@ -14,12 +22,19 @@ actions!(
         UnstageAll,
         DiscardAll,
         CommitStagedChanges,
-        CommitAllChanges
+        CommitAllChanges,
+        CompareSelections
     ]
 );
 
 pub fn init(cx: &mut AppContext) {
     GitPanelSettings::register(cx);
+    history_view::init(cx);
+    compare::init(cx);
+    remote_operations::init(cx);
+    stash_picker::init(cx);
+    rebase_editor::init(cx);
+    worktree_picker::init(cx);
 }
 
 const ADDED_COLOR: Hsla = Hsla {
@@ -0,0 +1,611 @@
+use std::{path::Path, time::Duration};
+
+use editor::{Editor, EditorEvent};
+use git::{
+    commit::{CommitSummary, LogOptions},
+    graph::{CommitGraph, GraphRow},
+};
+use gpui::{
+    canvas, fill, point, uniform_list, AnyElement, AppContext, Bounds, EventEmitter, FocusHandle,
+    FocusableView, Hsla, Model, Pixels, Point, ScrollStrategy, Subscription, Task,
+    UniformListScrollHandle, View, WeakView,
+};
+use menu::{Confirm, SelectNext, SelectPrev};
+use project::{Project, ProjectPath};
+use time::{OffsetDateTime, UtcOffset};
+use time_format::TimestampFormat;
+use ui::{prelude::*, Tooltip};
+use workspace::{
+    item::{Item, ItemEvent},
+    notifications::NotifyTaskExt,
+    Workspace,
+};
+use zed_actions::git::ViewHistory;
+
+use crate::commit_view;
+
+const PAGE_SIZE: usize = 200;
+const FILTER_DEBOUNCE: Duration = Duration::from_millis(300);
+const ROW_HEIGHT: Pixels = px(28.);
+const LANE_WIDTH: Pixels = px(12.);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+            workspace.register_action(HistoryView::deploy);
+            workspace.register_action(commit_view::deploy);
+        },
+    )
+    .detach();
+}
+
+/// Lists the commits of a repository, or of a path within it, next to their branch graph.
+pub struct HistoryView {
+    project: Model<Project>,
+    workspace: WeakView<Workspace>,
+    /// Locates the repository, and is the only path whose commits are listed when
+    /// `restrict_to_path` is set.
+    path: ProjectPath,
+    restrict_to_path: bool,
+    title: SharedString,
+    author_editor: View<Editor>,
+    message_editor: View<Editor>,
+    commits: Vec<CommitSummary>,
+    /// The graph is only drawn when no filter is set, as filtering breaks the parent links.
+    graph: Option<CommitGraph>,
+    graph_rows: Vec<GraphRow>,
+    graph_width: usize,
+    has_more: bool,
+    loading: bool,
+    error: Option<SharedString>,
+    selected_index: Option<usize>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    load_task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl HistoryView {
+    fn deploy(workspace: &mut Workspace, action: &ViewHistory, cx: &mut ViewContext<Workspace>) {
+        let project = workspace.project().clone();
+        let target = if let Some(abs_path) = &action.path {
+            project
+                .read(cx)
+                .find_worktree(abs_path, cx)
+                .map(|(worktree, path)| {
+                    let path = ProjectPath {
+                        worktree_id: worktree.read(cx).id(),
+                        path: path.into(),
+                    };
+                    (path, true)
+                })
+        } else {
+            workspace
+                .active_item(cx)
+                .and_then(|item| item.project_path(cx))
+                .or_else(|| {
+                    let worktree = project.read(cx).visible_worktrees(cx).next()?;
+                    Some(ProjectPath {
+                        worktree_id: worktree.read(cx).id(),
+                        path: Path::new("").into(),
+                    })
+                })
+                .map(|path| (path, false))
+        };
+        let Some((path, restrict_to_path)) = target else {
+            return;
+        };
+
+        let existing = workspace.items_of_type::<Self>(cx).find(|view| {
+            let view = view.read(cx);
+            view.restrict_to_path == restrict_to_path
+                && if restrict_to_path {
+                    view.path == path
+                } else {
+                    view.path.worktree_id == path.worktree_id
+                }
+        });
+        if let Some(existing) = existing {
+            workspace.activate_item(&existing, true, true, cx);
+            return;
+        }
+
+        let workspace_handle = cx.view().downgrade();
+        let view =
+            cx.new_view(|cx| Self::new(project, workspace_handle, path, restrict_to_path, cx));
+        workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+    }
+
+    fn new(
+        project: Model<Project>,
+        workspace: WeakView<Workspace>,
+        path: ProjectPath,
+        restrict_to_path: bool,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let author_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Filter by author", cx);
+            editor
+        });
+        let message_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("Filter by message", cx);
+            editor
+        });
+
+        let mut subscriptions = [&author_editor, &message_editor]
+            .into_iter()
+            .map(|editor| {
+                cx.subscribe(editor, |this, _, event: &EditorEvent, cx| {
+                    if let EditorEvent::BufferEdited = event {
+                        this.load_commits(true, cx);
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        subscriptions.push(cx.subscribe(&project, |this, _, event, cx| {
+            if let project::Event::WorktreeUpdatedGitRepositories(worktree_id) = event {
+                if *worktree_id == this.path.worktree_id {
+                    this.load_commits(true, cx);
+                }
+            }
+        }));
+
+        let title = if restrict_to_path {
+            path.path
+                .file_name()
+                .map(|file_name| format!("History: {}", file_name.to_string_lossy()))
+        } else {
+            project
+                .read(cx)
+                .worktree_for_id(path.worktree_id, cx)
+                .map(|worktree| format!("History: {}", worktree.read(cx).root_name()))
+        }
+        .unwrap_or_else(|| "History".to_string());
+
+        let mut this = Self {
+            project,
+            workspace,
+            path,
+            restrict_to_path,
+            title: title.into(),
+            author_editor,
+            message_editor,
+            commits: Vec::new(),
+            graph: Some(CommitGraph::default()),
+            graph_rows: Vec::new(),
+            graph_width: 0,
+            has_more: false,
+            loading: false,
+            error: None,
+            selected_index: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            load_task: Task::ready(()),
+            _subscriptions: subscriptions,
+        };
+        this.load_commits(false, cx);
+        this
+    }
+
+    /// Loads the next page of commits. When `reset` is set, the filters changed, so the commits
+    /// are listed again from the start once the user stops typing.
+    fn load_commits(&mut self, reset: bool, cx: &mut ViewContext<Self>) {
+        self.loading = true;
+        self.load_task = cx.spawn(|this, mut cx| async move {
+            if reset {
+                cx.background_executor().timer(FILTER_DEBOUNCE).await;
+            }
+
+            let Ok((log, filtered)) = this.update(&mut cx, |this, cx| {
+                let options = LogOptions {
+                    path: None,
+                    author: filter_text(&this.author_editor, cx),
+                    message: filter_text(&this.message_editor, cx),
+                    skip: if reset { 0 } else { this.commits.len() },
+                    limit: PAGE_SIZE,
+                };
+                let filtered = options.author.is_some() || options.message.is_some();
+                let log = this.project.read(cx).git_log(
+                    this.path.clone(),
+                    this.restrict_to_path,
+                    options,
+                    cx,
+                );
+                (log, filtered)
+            }) else {
+                return;
+            };
+            let result = log.await;
+
+            this.update(&mut cx, |this, cx| {
+                this.loading = false;
+                if reset {
+                    this.commits.clear();
+                    this.graph_rows.clear();
+                    this.graph_width = 0;
+                    this.graph = (!filtered).then(CommitGraph::default);
+                    this.selected_index = None;
+                }
+                match result {
+                    Ok(commits) => {
+                        this.error = None;
+                        this.has_more = commits.len() == PAGE_SIZE;
+                        if let Some(graph) = this.graph.as_mut() {
+                            for commit in &commits {
+                                let row = graph.push(commit.sha, &commit.parents);
+                                this.graph_width = this.graph_width.max(row.width());
+                                this.graph_rows.push(row);
+                            }
+                        }
+                        this.commits.extend(commits);
+                    }
+                    Err(error) => {
+                        this.has_more = false;
+                        this.error = Some(format!("{error:#}").into());
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        if self.commits.is_empty() {
+            return;
+        }
+        let ix = self
+            .selected_index
+            .map_or(0, |ix| (ix + 1).min(self.commits.len() - 1));
+        self.select(ix, cx);
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        if self.commits.is_empty() {
+            return;
+        }
+        let ix = self.selected_index.map_or(0, |ix| ix.saturating_sub(1));
+        self.select(ix, cx);
+    }
+
+    fn select(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.selected_index = Some(ix);
+        self.scroll_handle
+            .scroll_to_item(ix, ScrollStrategy::Center);
+        if ix + 1 == self.commits.len() && self.has_more && !self.loading {
+            self.load_commits(false, cx);
+        }
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.selected_index {
+            self.open_commit(ix, cx);
+        }
+    }
+
+    fn open_commit(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(commit) = self.commits.get(ix) else {
+            return;
+        };
+        self.selected_index = Some(ix);
+        cx.notify();
+
+        let title = format!("{} {}", commit.sha.display_short(), commit.subject);
+        let repository = self.path.clone();
+        let sha = commit.sha;
+        self.workspace
+            .update(cx, |workspace, cx| {
+                commit_view::open_commit(workspace, repository, sha, title, cx)
+                    .detach_and_notify_err(cx);
+            })
+            .ok();
+    }
+
+    fn render_filters(&self, cx: &ViewContext<Self>) -> impl IntoElement {
+        let filter_input = |editor: &View<Editor>| {
+            div()
+                .w(rems(16.))
+                .px_2()
+                .py_1()
+                .rounded_md()
+                .border_1()
+                .border_color(cx.theme().colors().border)
+                .child(editor.clone())
+        };
+
+        h_flex()
+            .w_full()
+            .p_2()
+            .gap_2()
+            .border_b_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(Label::new(self.title.clone()))
+            .child(div().flex_1())
+            .child(filter_input(&self.author_editor))
+            .child(filter_input(&self.message_editor))
+    }
+
+    fn render_commit(
+        &self,
+        ix: usize,
+        now: OffsetDateTime,
+        timezone: UtcOffset,
+        cx: &ViewContext<Self>,
+    ) -> AnyElement {
+        let commit = &self.commits[ix];
+        let colors = cx.theme().colors();
+        let date = OffsetDateTime::from_unix_timestamp(commit.timestamp)
+            .map(|timestamp| {
+                time_format::format_localized_timestamp(
+                    timestamp,
+                    now,
+                    timezone,
+                    TimestampFormat::Relative,
+                )
+            })
+            .unwrap_or_default();
+        let author_tooltip: SharedString =
+            format!("{} <{}>", commit.author_name, commit.author_email).into();
+
+        h_flex()
+            .id(ix)
+            .h(ROW_HEIGHT)
+            .w_full()
+            .px_2()
+            .gap_2()
+            .cursor_pointer()
+            .when(self.selected_index == Some(ix), |this| {
+                this.bg(colors.element_selected)
+            })
+            .hover(|style| style.bg(colors.element_hover))
+            .when_some(self.graph_rows.get(ix), |this, row| {
+                this.child(render_graph(row.clone(), self.graph_width, cx))
+            })
+            .child(
+                h_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .gap_1()
+                    .children(commit.refs.iter().map(|name| render_ref(name, cx)))
+                    .child(Label::new(commit.subject.clone()).single_line()),
+            )
+            .child(
+                div()
+                    .id(("author", ix))
+                    .child(
+                        Label::new(commit.author_name.clone())
+                            .size(LabelSize::Small)
+                            .color(Color::Muted)
+                            .single_line(),
+                    )
+                    .tooltip(move |cx| Tooltip::text(author_tooltip.clone(), cx)),
+            )
+            .child(
+                Label::new(date)
+                    .size(LabelSize::Small)
+                    .color(Color::Muted)
+                    .single_line(),
+            )
+            .child(
+                div().font_buffer(cx).child(
+                    Label::new(commit.sha.display_short())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+            )
+            .on_click(cx.listener(move |this, _, cx| this.open_commit(ix, cx)))
+            .into_any_element()
+    }
+
+    fn render_load_more(&self, cx: &ViewContext<Self>) -> AnyElement {
+        h_flex()
+            .h(ROW_HEIGHT)
+            .w_full()
+            .justify_center()
+            .child(
+                Button::new(
+                    "load-more",
+                    if self.loading {
+                        "Loading…"
+                    } else {
+                        "Load More Commits"
+                    },
+                )
+                .disabled(self.loading)
+                .on_click(cx.listener(|this, _, cx| this.load_commits(false, cx))),
+            )
+            .into_any_element()
+    }
+
+    fn render_message(&self, message: SharedString, color: Color) -> impl IntoElement {
+        h_flex()
+            .size_full()
+            .justify_center()
+            .child(Label::new(message).color(color))
+    }
+}
+
+fn filter_text(editor: &View<Editor>, cx: &AppContext) -> Option<String> {
+    let text = editor.read(cx).text(cx);
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+fn render_ref(name: &str, cx: &WindowContext) -> impl IntoElement {
+    let (name, color) = match name.strip_prefix("HEAD -> ") {
+        Some(branch) => (branch, Color::Accent),
+        None => (name, Color::Default),
+    };
+    div()
+        .flex_none()
+        .px_1()
+        .rounded_md()
+        .border_1()
+        .border_color(cx.theme().colors().border)
+        .bg(cx.theme().colors().element_background)
+        .child(
+            Label::new(name.to_string())
+                .size(LabelSize::XSmall)
+                .color(color)
+                .single_line(),
+        )
+}
+
+/// Draws the lanes of a commit row: vertical lines for each lane, joined to the commit's node
+/// by horizontal lines for branches and merges.
+fn render_graph(row: GraphRow, width: usize, cx: &WindowContext) -> impl IntoElement {
+    let accents = cx.theme().accents().clone();
+    let node_size = px(8.);
+
+    canvas(
+        |_, _| {},
+        move |bounds, _, cx| {
+            let color = |lane: usize| accents.color_for_index(lane as u32);
+            let lane_x = |lane: usize| bounds.left() + LANE_WIDTH * (lane as f32 + 0.5);
+            let center_y = bounds.center().y;
+            let node = point(lane_x(row.lane), center_y);
+
+            for lane in &row.passing {
+                let x = lane_x(*lane);
+                paint_line(
+                    point(x, bounds.top()),
+                    point(x, bounds.bottom()),
+                    color(*lane),
+                    cx,
+                );
+            }
+            for lane in &row.incoming {
+                let x = lane_x(*lane);
+                paint_line(point(x, bounds.top()), point(x, center_y), color(*lane), cx);
+                paint_line(point(x, center_y), node, color(*lane), cx);
+            }
+            for lane in &row.outgoing {
+                let x = lane_x(*lane);
+                paint_line(node, point(x, center_y), color(*lane), cx);
+                paint_line(
+                    point(x, center_y),
+                    point(x, bounds.bottom()),
+                    color(*lane),
+                    cx,
+                );
+            }
+
+            cx.paint_quad(
+                fill(
+                    Bounds::centered_at(node, gpui::size(node_size, node_size)),
+                    color(row.lane),
+                )
+                .corner_radii(node_size / 2.),
+            );
+        },
+    )
+    .flex_none()
+    .w(LANE_WIDTH * width as f32)
+    .h_full()
+}
+
+/// Paints a horizontal or vertical line between two points.
+fn paint_line(start: Point<Pixels>, end: Point<Pixels>, color: Hsla, cx: &mut WindowContext) {
+    let thickness = px(1.5);
+    let half = thickness / 2.;
+    let min = point(start.x.min(end.x) - half, start.y.min(end.y) - half);
+    let max = point(start.x.max(end.x) + half, start.y.max(end.y) + half);
+    cx.paint_quad(fill(Bounds::from_corners(min, max), color));
+}
+
+impl Render for HistoryView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let body = if self.commits.is_empty() {
+            if let Some(error) = self.error.clone() {
+                self.render_message(error, Color::Error).into_any_element()
+            } else if self.loading {
+                self.render_message("Loading commits…".into(), Color::Muted)
+                    .into_any_element()
+            } else {
+                self.render_message("No commits found".into(), Color::Muted)
+                    .into_any_element()
+            }
+        } else {
+            let item_count = self.commits.len() + self.has_more as usize;
+            uniform_list(
+                cx.view().clone(),
+                "commits",
+                item_count,
+                |this, range, cx| {
+                    let now = OffsetDateTime::now_utc();
+                    let timezone = UtcOffset::from_whole_seconds(
+                        chrono::Local::now().offset().local_minus_utc(),
+                    )
+                    .unwrap_or(UtcOffset::UTC);
+                    range
+                        .map(|ix| {
+                            if ix < this.commits.len() {
+                                this.render_commit(ix, now, timezone, cx)
+                            } else {
+                                this.render_load_more(cx)
+                            }
+                        })
+                        .collect()
+                },
+            )
+            .size_full()
+            .track_scroll(self.scroll_handle.clone())
+            .into_any_element()
+        };
+
+        v_flex()
+            .key_context("GitHistory")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(self.render_filters(cx))
+            .child(body)
+    }
+}
+
+impl EventEmitter<ItemEvent> for HistoryView {}
+
+impl FocusableView for HistoryView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for HistoryView {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content_text(&self, _: &WindowContext) -> Option<SharedString> {
+        Some(self.title.clone())
+    }
+
+    fn tab_icon(&self, _: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch))
+    }
+
+    fn tab_tooltip_text(&self, cx: &AppContext) -> Option<SharedString> {
+        let path = self.path.path.to_string_lossy();
+        if self.restrict_to_path && !path.is_empty() {
+            Some(format!("Commits touching {path}").into())
+        } else {
+            let worktree = self
+                .project
+                .read(cx)
+                .worktree_for_id(self.path.worktree_id, cx)?;
+            Some(format!("Commits of {}", worktree.read(cx).root_name()).into())
+        }
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("git history opened")
+    }
+}
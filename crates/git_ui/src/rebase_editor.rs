@@ -0,0 +1,737 @@
+use std::ffi::OsStr;
+
+use anyhow::{anyhow, Result};
+use collections::HashMap;
+use editor::{project_conflicts, Editor, EditorEvent};
+use git::rebase::{parse_todo, serialize_todo, RebaseAction, RebaseOutcome, RebaseTodoItem};
+use gpui::{
+    actions, uniform_list, AnyElement, AppContext, EntityId, EventEmitter, FocusHandle,
+    FocusableView, Model, ScrollStrategy, Subscription, Task, UniformListScrollHandle, View,
+};
+use language::Buffer;
+use menu::{SelectNext, SelectPrev};
+use project::{Project, ProjectEntryId, ProjectPath};
+use ui::{prelude::*, KeyBinding};
+use workspace::{
+    item::{Item, ItemEvent},
+    notifications::NotifyTaskExt,
+    ProjectItem, Workspace,
+};
+use zed_actions::git::InteractiveRebase;
+
+use crate::revision_picker::{RevisionPicker, RevisionTarget};
+
+actions!(
+    rebase_editor,
+    [
+        MoveUp,
+        MoveDown,
+        Pick,
+        Reword,
+        Squash,
+        Fixup,
+        DropCommit,
+        FocusTodo,
+        StartRebase,
+        ContinueRebase,
+        AbortRebase
+    ]
+);
+
+/// The name of the todo list git asks its sequence editor to edit.
+const TODO_FILE_NAME: &str = "git-rebase-todo";
+
+pub fn init(cx: &mut AppContext) {
+    workspace::register_project_item::<RebaseEditor>(cx);
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+            workspace.register_action(RebaseEditor::deploy);
+        },
+    )
+    .detach();
+}
+
+/// Opens a [`RebaseEditor`] listing the commits of the current branch of the first worktree's
+/// repository that aren't in `base`.
+pub(crate) fn open_rebase_editor(
+    workspace: &mut Workspace,
+    base: String,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<()>> {
+    let project = workspace.project().clone();
+    let Some(worktree) = project.read(cx).visible_worktrees(cx).next() else {
+        return Task::ready(Err(anyhow!("There is no repository to rebase")));
+    };
+    let repository = ProjectPath::root_path(worktree.read(cx).id());
+    let todo = project
+        .read(cx)
+        .rebase_todo(repository.clone(), base.clone(), cx);
+
+    cx.spawn(|workspace, mut cx| async move {
+        let items = todo.await?;
+        if items.is_empty() {
+            anyhow::bail!("There are no commits to rebase onto {base}");
+        }
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let target = RebaseTarget::Repository { repository, base };
+            let editor = cx.new_view(|cx| RebaseEditor::new(project, target, items, cx));
+            workspace.add_item_to_active_pane(Box::new(editor), None, true, cx);
+        })
+    })
+}
+
+/// The todo list of a rebase started by git, which opens it when Zed is its sequence editor, as
+/// in `GIT_SEQUENCE_EDITOR="zed --wait" git rebase -i`.
+pub struct RebaseTodoFile {
+    buffer: Model<Buffer>,
+    items: Vec<RebaseTodoItem>,
+}
+
+impl project::ProjectItem for RebaseTodoFile {
+    fn try_open(
+        project: &Model<Project>,
+        path: &ProjectPath,
+        cx: &mut AppContext,
+    ) -> Option<Task<Result<Model<Self>>>> {
+        // Files opened from the command line outside of the project are single-file worktrees.
+        let worktree_abs_path = project
+            .read(cx)
+            .worktree_for_id(path.worktree_id, cx)?
+            .read(cx)
+            .abs_path();
+        let file_name = path
+            .path
+            .file_name()
+            .or_else(|| worktree_abs_path.file_name());
+        if file_name != Some(OsStr::new(TODO_FILE_NAME)) {
+            return None;
+        }
+
+        let open_buffer = project.update(cx, |project, cx| project.open_buffer(path.clone(), cx));
+        Some(cx.spawn(|mut cx| async move {
+            let buffer = open_buffer.await?;
+            let items = buffer.read_with(&cx, |buffer, _| parse_todo(&buffer.text()))??;
+            cx.new_model(|_| Self { buffer, items })
+        }))
+    }
+
+    fn entry_id(&self, cx: &AppContext) -> Option<ProjectEntryId> {
+        project::ProjectItem::entry_id(self.buffer.read(cx), cx)
+    }
+
+    fn project_path(&self, cx: &AppContext) -> Option<ProjectPath> {
+        project::ProjectItem::project_path(self.buffer.read(cx), cx)
+    }
+
+    fn is_dirty(&self) -> bool {
+        false
+    }
+}
+
+enum RebaseTarget {
+    /// Runs the rebase of a repository onto `base` itself.
+    Repository {
+        repository: ProjectPath,
+        base: String,
+    },
+    /// Writes the edited todo list back for git to run the rebase once the editor is closed.
+    TodoFile(Model<RebaseTodoFile>),
+}
+
+enum RebaseState {
+    Editing,
+    Running,
+    /// The rebase stopped on a conflict, which is resolved in the project conflicts view before
+    /// continuing.
+    Stopped {
+        message: SharedString,
+    },
+}
+
+/// Edits the todo list of an interactive rebase: the commits can be reordered, squashed, fixed
+/// up, reworded or dropped before running the rebase.
+pub struct RebaseEditor {
+    project: Model<Project>,
+    target: RebaseTarget,
+    items: Vec<RebaseTodoItem>,
+    selected_index: usize,
+    /// Edits the new message of the selected commit, when it is reworded.
+    message_editor: View<Editor>,
+    /// The full messages of the reworded commits, by SHA, as loaded from the repository.
+    original_messages: HashMap<String, String>,
+    state: RebaseState,
+    error: Option<SharedString>,
+    scroll_handle: UniformListScrollHandle,
+    focus_handle: FocusHandle,
+    task: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl RebaseEditor {
+    fn deploy(
+        workspace: &mut Workspace,
+        action: &InteractiveRebase,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        match &action.base {
+            Some(base) => open_rebase_editor(workspace, base.clone(), cx).detach_and_notify_err(cx),
+            None => RevisionPicker::toggle(workspace, RevisionTarget::Rebase, cx),
+        }
+    }
+
+    fn new(
+        project: Model<Project>,
+        target: RebaseTarget,
+        items: Vec<RebaseTodoItem>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let message_editor = cx.new_view(|cx| {
+            let mut editor = Editor::auto_height(8, cx);
+            editor.set_placeholder_text("Commit message", cx);
+            editor
+        });
+        let subscriptions =
+            vec![
+                cx.subscribe(&message_editor, |this, editor, event: &EditorEvent, cx| {
+                    if let EditorEvent::BufferEdited = event {
+                        this.message_edited(editor, cx);
+                    }
+                }),
+            ];
+
+        Self {
+            project,
+            target,
+            items,
+            selected_index: 0,
+            message_editor,
+            original_messages: HashMap::default(),
+            state: RebaseState::Editing,
+            error: None,
+            scroll_handle: UniformListScrollHandle::new(),
+            focus_handle: cx.focus_handle(),
+            task: Task::ready(()),
+            _subscriptions: subscriptions,
+        }
+    }
+
+    fn is_editing(&self) -> bool {
+        matches!(self.state, RebaseState::Editing)
+    }
+
+    fn select(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        self.selected_index = ix;
+        self.scroll_handle
+            .scroll_to_item(ix, ScrollStrategy::Center);
+        if let Some(item) = self.items.get(ix) {
+            if item.action == RebaseAction::Reword {
+                let message = item
+                    .message
+                    .clone()
+                    .or_else(|| self.original_messages.get(&item.sha).cloned());
+                let sha = item.sha.clone();
+                let loaded = message.is_some();
+                if !loaded {
+                    self.load_message(sha, cx);
+                }
+                // The message stays read-only until the current one of the commit is loaded, so
+                // that editing its subject keeps its body.
+                self.message_editor.update(cx, |editor, cx| {
+                    editor.set_read_only(!loaded);
+                    editor.set_text(message.unwrap_or_default(), cx);
+                });
+            }
+        }
+        cx.notify();
+    }
+
+    /// Records the new message of the selected commit. It stays unset while it matches the
+    /// current message of the commit, which git then keeps as is.
+    fn message_edited(&mut self, editor: View<Editor>, cx: &mut ViewContext<Self>) {
+        let Some(item) = self.items.get_mut(self.selected_index) else {
+            return;
+        };
+        let Some(original_message) = self.original_messages.get(&item.sha) else {
+            return;
+        };
+        if item.action == RebaseAction::Reword {
+            let message = editor.read(cx).text(cx);
+            item.message = (message.trim_end() != original_message.as_str()).then_some(message);
+        }
+    }
+
+    fn load_message(&mut self, sha: String, cx: &mut ViewContext<Self>) {
+        let repository = match &self.target {
+            RebaseTarget::Repository { repository, .. } => Some(repository.clone()),
+            // Git edits the todo list of the repository it runs in, which is usually the one
+            // opened in the workspace.
+            RebaseTarget::TodoFile(_) => self
+                .project
+                .read(cx)
+                .visible_worktrees(cx)
+                .next()
+                .map(|worktree| ProjectPath::root_path(worktree.read(cx).id())),
+        };
+        let Some(repository) = repository else {
+            self.error = Some("The message of the commit can't be loaded".into());
+            return;
+        };
+
+        let message = self
+            .project
+            .read(cx)
+            .commit_message(repository, sha.clone(), cx);
+        cx.spawn(|this, mut cx| async move {
+            let message = message.await;
+            this.update(&mut cx, |this, cx| {
+                let message = match message {
+                    Ok(message) => message.trim_end().to_string(),
+                    Err(error) => {
+                        this.error = Some(format!("{error:#}").into());
+                        cx.notify();
+                        return;
+                    }
+                };
+                this.original_messages.insert(sha.clone(), message);
+                let selected = this.items.get(this.selected_index);
+                if selected.map_or(false, |item| {
+                    item.sha == sha && item.action == RebaseAction::Reword
+                }) {
+                    this.select(this.selected_index, cx);
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    fn select_next(&mut self, _: &SelectNext, cx: &mut ViewContext<Self>) {
+        if self.selected_index + 1 < self.items.len() {
+            self.select(self.selected_index + 1, cx);
+        }
+    }
+
+    fn select_prev(&mut self, _: &SelectPrev, cx: &mut ViewContext<Self>) {
+        if self.selected_index > 0 {
+            self.select(self.selected_index - 1, cx);
+        }
+    }
+
+    fn move_up(&mut self, _: &MoveUp, cx: &mut ViewContext<Self>) {
+        if self.is_editing() && self.selected_index > 0 {
+            self.items
+                .swap(self.selected_index - 1, self.selected_index);
+            self.select(self.selected_index - 1, cx);
+        }
+    }
+
+    fn move_down(&mut self, _: &MoveDown, cx: &mut ViewContext<Self>) {
+        if self.is_editing() && self.selected_index + 1 < self.items.len() {
+            self.items
+                .swap(self.selected_index, self.selected_index + 1);
+            self.select(self.selected_index + 1, cx);
+        }
+    }
+
+    fn set_action(&mut self, action: RebaseAction, cx: &mut ViewContext<Self>) {
+        if !self.is_editing() {
+            return;
+        }
+        let Some(item) = self
+            .items
+            .get_mut(self.selected_index)
+            .filter(|item| item.action != RebaseAction::Command)
+        else {
+            return;
+        };
+        item.action = action;
+        if action != RebaseAction::Reword {
+            item.message = None;
+        }
+        self.error = None;
+        if action == RebaseAction::Reword {
+            self.select(self.selected_index, cx);
+            self.message_editor.focus_handle(cx).focus(cx);
+        }
+        cx.notify();
+    }
+
+    fn focus_todo(&mut self, _: &FocusTodo, cx: &mut ViewContext<Self>) {
+        self.focus_handle.focus(cx);
+    }
+
+    fn start_rebase(&mut self, _: &StartRebase, cx: &mut ViewContext<Self>) {
+        if !self.is_editing() {
+            return;
+        }
+        if self.items.first().map_or(false, |item| item.action.melds()) {
+            self.error = Some("The first commit has no previous commit to meld into".into());
+            cx.notify();
+            return;
+        }
+
+        match &self.target {
+            RebaseTarget::Repository { repository, base } => {
+                let rebase = self.project.read(cx).rebase(
+                    repository.clone(),
+                    base.clone(),
+                    self.items.clone(),
+                    cx,
+                );
+                self.run(rebase, cx);
+            }
+            RebaseTarget::TodoFile(file) => match serialize_todo(&self.items) {
+                Ok(todo) => {
+                    let buffer = file.read(cx).buffer.clone();
+                    self.write_todo_file(buffer, todo, cx);
+                }
+                Err(error) => {
+                    self.error = Some(format!("{error:#}").into());
+                    cx.notify();
+                }
+            },
+        }
+    }
+
+    fn continue_rebase(&mut self, _: &ContinueRebase, cx: &mut ViewContext<Self>) {
+        let RebaseTarget::Repository { repository, .. } = &self.target else {
+            return;
+        };
+        if let RebaseState::Stopped { .. } = self.state {
+            let task = self
+                .project
+                .read(cx)
+                .continue_rebase(repository.clone(), cx);
+            self.run(task, cx);
+        }
+    }
+
+    fn abort_rebase(&mut self, _: &AbortRebase, cx: &mut ViewContext<Self>) {
+        match (&self.target, &self.state) {
+            (RebaseTarget::Repository { repository, .. }, RebaseState::Stopped { .. }) => {
+                let abort = self.project.read(cx).abort_rebase(repository.clone(), cx);
+                let task = cx.background_executor().spawn(async move {
+                    abort.await?;
+                    Ok(RebaseOutcome::Completed)
+                });
+                self.run(task, cx);
+            }
+            (RebaseTarget::Repository { .. }, RebaseState::Editing) => {
+                cx.emit(ItemEvent::CloseItem);
+            }
+            // Git aborts the rebase when the todo list is empty.
+            (RebaseTarget::TodoFile(file), RebaseState::Editing) => {
+                let buffer = file.read(cx).buffer.clone();
+                self.write_todo_file(buffer, String::new(), cx);
+            }
+            _ => {}
+        }
+    }
+
+    fn run(&mut self, task: Task<Result<RebaseOutcome>>, cx: &mut ViewContext<Self>) {
+        let was_editing = self.is_editing();
+        self.state = RebaseState::Running;
+        self.error = None;
+        cx.notify();
+
+        self.task = cx.spawn(|this, mut cx| async move {
+            let result = task.await;
+            this.update(&mut cx, |this, cx| {
+                match result {
+                    Ok(RebaseOutcome::Completed) => cx.emit(ItemEvent::CloseItem),
+                    Ok(RebaseOutcome::Stopped { message }) => {
+                        this.state = RebaseState::Stopped {
+                            message: message.into(),
+                        };
+                        cx.dispatch_action(Box::new(project_conflicts::Deploy));
+                    }
+                    Err(error) => {
+                        this.error = Some(format!("{error:#}").into());
+                        if was_editing {
+                            this.state = RebaseState::Editing;
+                        } else {
+                            this.state = RebaseState::Stopped {
+                                message: "The rebase is stopped".into(),
+                            };
+                        }
+                    }
+                }
+                cx.notify();
+            })
+            .ok();
+        });
+    }
+
+    /// Saves the todo list for git and closes the editor, which lets `zed --wait` exit.
+    fn write_todo_file(&mut self, buffer: Model<Buffer>, todo: String, cx: &mut ViewContext<Self>) {
+        self.state = RebaseState::Running;
+        cx.notify();
+
+        buffer.update(cx, |buffer, cx| {
+            buffer.set_text(todo, cx);
+        });
+        let save = self
+            .project
+            .update(cx, |project, cx| project.save_buffer(buffer, cx));
+        self.task = cx.spawn(|this, mut cx| async move {
+            let result = save.await;
+            this.update(&mut cx, |this, cx| match result {
+                Ok(()) => cx.emit(ItemEvent::CloseItem),
+                Err(error) => {
+                    this.state = RebaseState::Editing;
+                    this.error = Some(format!("{error:#}").into());
+                    cx.notify();
+                }
+            })
+            .ok();
+        });
+    }
+
+    fn render_item(&self, ix: usize, cx: &ViewContext<Self>) -> AnyElement {
+        let item = &self.items[ix];
+        let colors = cx.theme().colors();
+        let action_color = match item.action {
+            RebaseAction::Pick => Color::Default,
+            RebaseAction::Reword => Color::Accent,
+            RebaseAction::Squash | RebaseAction::Fixup => Color::Warning,
+            RebaseAction::Drop => Color::Error,
+            RebaseAction::Command => Color::Muted,
+        };
+        let (action, subject) = match (&item.action, &item.message) {
+            (RebaseAction::Reword, Some(message)) => (
+                item.action.as_str(),
+                message.lines().next().unwrap_or_default(),
+            ),
+            // Commands are shown as written, with the command in place of the action.
+            (RebaseAction::Command, _) => item
+                .subject
+                .split_once(char::is_whitespace)
+                .unwrap_or((item.subject.as_str(), "")),
+            _ => (item.action.as_str(), item.subject.as_str()),
+        };
+        let sha = item.sha.get(..7).unwrap_or(&item.sha).to_string();
+
+        h_flex()
+            .id(ix)
+            .h(px(28.))
+            .w_full()
+            .px_2()
+            .gap_2()
+            .cursor_pointer()
+            .when(self.selected_index == ix, |this| {
+                this.bg(colors.element_selected)
+            })
+            .hover(|style| style.bg(colors.element_hover))
+            .child(
+                div().w(rems(4.)).flex_none().child(
+                    Label::new(action.to_string())
+                        .size(LabelSize::Small)
+                        .color(action_color),
+                ),
+            )
+            .child(
+                div()
+                    .font_buffer(cx)
+                    .flex_none()
+                    .child(Label::new(sha).size(LabelSize::Small).color(Color::Muted)),
+            )
+            .child(
+                Label::new(subject.to_string())
+                    .single_line()
+                    .strikethrough(item.action == RebaseAction::Drop)
+                    .when(item.action == RebaseAction::Fixup, |label| {
+                        label.color(Color::Muted)
+                    }),
+            )
+            .on_click(cx.listener(move |this, _, cx| {
+                this.select(ix, cx);
+                this.focus_handle.focus(cx);
+            }))
+            .into_any_element()
+    }
+
+    fn render_footer(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let status = match (&self.state, &self.error) {
+            (_, Some(error)) => Label::new(error.clone()).color(Color::Error),
+            (RebaseState::Editing, None) => {
+                let commits = match self.items.len() {
+                    1 => "1 commit".to_string(),
+                    count => format!("{count} commits"),
+                };
+                match &self.target {
+                    RebaseTarget::Repository { base, .. } => {
+                        Label::new(format!("Rebasing {commits} onto {base}"))
+                    }
+                    RebaseTarget::TodoFile(_) => Label::new(format!("Rebasing {commits}")),
+                }
+                .color(Color::Muted)
+            }
+            (RebaseState::Running, None) => Label::new("Rebasing…").color(Color::Muted),
+            (RebaseState::Stopped { message }, None) => {
+                Label::new(message.clone()).color(Color::Warning)
+            }
+        };
+
+        let buttons = match self.state {
+            RebaseState::Editing => vec![
+                Button::new("cancel", "Cancel")
+                    .key_binding(KeyBinding::for_action_in(
+                        &AbortRebase,
+                        &self.focus_handle,
+                        cx,
+                    ))
+                    .on_click(cx.listener(|this, _, cx| this.abort_rebase(&AbortRebase, cx))),
+                Button::new("start", "Rebase")
+                    .key_binding(KeyBinding::for_action_in(
+                        &StartRebase,
+                        &self.focus_handle,
+                        cx,
+                    ))
+                    .on_click(cx.listener(|this, _, cx| this.start_rebase(&StartRebase, cx))),
+            ],
+            RebaseState::Running => Vec::new(),
+            RebaseState::Stopped { .. } => vec![
+                Button::new("abort", "Abort")
+                    .on_click(cx.listener(|this, _, cx| this.abort_rebase(&AbortRebase, cx))),
+                Button::new("continue", "Continue")
+                    .on_click(cx.listener(|this, _, cx| this.continue_rebase(&ContinueRebase, cx))),
+            ],
+        };
+
+        h_flex()
+            .w_full()
+            .p_2()
+            .gap_2()
+            .border_t_1()
+            .border_color(cx.theme().colors().border_variant)
+            .child(div().flex_1().min_w_0().child(status.single_line()))
+            .children(buttons)
+    }
+}
+
+impl Render for RebaseEditor {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let show_message_editor = self.is_editing()
+            && self
+                .items
+                .get(self.selected_index)
+                .map_or(false, |item| item.action == RebaseAction::Reword);
+
+        v_flex()
+            .key_context("RebaseEditor")
+            .on_action(cx.listener(Self::focus_todo))
+            .on_action(cx.listener(Self::start_rebase))
+            .on_action(cx.listener(Self::continue_rebase))
+            .on_action(cx.listener(Self::abort_rebase))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                // The todo list has its own context, so that its single-key bindings don't apply
+                // while the message of a reworded commit is edited.
+                div()
+                    .key_context("RebaseTodo")
+                    .track_focus(&self.focus_handle)
+                    .on_action(cx.listener(Self::select_next))
+                    .on_action(cx.listener(Self::select_prev))
+                    .on_action(cx.listener(Self::move_up))
+                    .on_action(cx.listener(Self::move_down))
+                    .on_action(
+                        cx.listener(|this, _: &Pick, cx| this.set_action(RebaseAction::Pick, cx)),
+                    )
+                    .on_action(
+                        cx.listener(|this, _: &Reword, cx| {
+                            this.set_action(RebaseAction::Reword, cx)
+                        }),
+                    )
+                    .on_action(
+                        cx.listener(|this, _: &Squash, cx| {
+                            this.set_action(RebaseAction::Squash, cx)
+                        }),
+                    )
+                    .on_action(
+                        cx.listener(|this, _: &Fixup, cx| this.set_action(RebaseAction::Fixup, cx)),
+                    )
+                    .on_action(cx.listener(|this, _: &DropCommit, cx| {
+                        this.set_action(RebaseAction::Drop, cx)
+                    }))
+                    .flex_1()
+                    .w_full()
+                    .child(
+                        uniform_list(
+                            cx.view().clone(),
+                            "rebase-todo",
+                            self.items.len(),
+                            |this, range, cx| range.map(|ix| this.render_item(ix, cx)).collect(),
+                        )
+                        .size_full()
+                        .track_scroll(self.scroll_handle.clone()),
+                    ),
+            )
+            .when(show_message_editor, |this| {
+                this.child(
+                    div()
+                        .w_full()
+                        .p_2()
+                        .border_t_1()
+                        .border_color(cx.theme().colors().border_variant)
+                        .child(self.message_editor.clone()),
+                )
+            })
+            .child(self.render_footer(cx))
+    }
+}
+
+impl EventEmitter<ItemEvent> for RebaseEditor {}
+
+impl FocusableView for RebaseEditor {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Item for RebaseEditor {
+    type Event = ItemEvent;
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+
+    fn tab_content_text(&self, _: &WindowContext) -> Option<SharedString> {
+        Some(match &self.target {
+            RebaseTarget::Repository { base, .. } => format!("Rebase onto {base}").into(),
+            RebaseTarget::TodoFile(_) => "Interactive Rebase".into(),
+        })
+    }
+
+    fn tab_icon(&self, _: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::GitBranch))
+    }
+
+    fn for_each_project_item(
+        &self,
+        cx: &AppContext,
+        f: &mut dyn FnMut(EntityId, &dyn project::ProjectItem),
+    ) {
+        if let RebaseTarget::TodoFile(file) = &self.target {
+            f(file.entity_id(), file.read(cx));
+        }
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("git rebase editor opened")
+    }
+}
+
+impl ProjectItem for RebaseEditor {
+    type Item = RebaseTodoFile;
+
+    fn for_project_item(
+        project: Model<Project>,
+        item: Model<Self::Item>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let items = item.read(cx).items.clone();
+        Self::new(project, RebaseTarget::TodoFile(item), items, cx)
+    }
+}
@@ -0,0 +1,176 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use editor::Editor;
+use futures::channel::oneshot;
+use git::repository::{PullStrategy, RemoteOperation};
+use gpui::{
+    AnyWindowHandle, AppContext, AsyncAppContext, DismissEvent, EventEmitter, FocusHandle,
+    FocusableView, Render, View, WeakView,
+};
+use project::ProjectPath;
+use remote::AskPassDelegate;
+use ui::prelude::*;
+use workspace::{
+    notifications::{NotificationId, NotifyTaskExt},
+    ModalView, Toast, Workspace,
+};
+use zed_actions::git::{Fetch, ForcePush, Pull, PullRebase, Push};
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+            workspace.register_action(|workspace, _: &Fetch, cx| {
+                run_remote_operation(workspace, RemoteOperation::Fetch, cx)
+            });
+            workspace.register_action(|workspace, _: &Pull, cx| {
+                run_remote_operation(workspace, RemoteOperation::Pull(PullStrategy::Merge), cx)
+            });
+            workspace.register_action(|workspace, _: &PullRebase, cx| {
+                run_remote_operation(workspace, RemoteOperation::Pull(PullStrategy::Rebase), cx)
+            });
+            workspace.register_action(|workspace, _: &Push, cx| {
+                run_remote_operation(workspace, RemoteOperation::Push { force: false }, cx)
+            });
+            workspace.register_action(|workspace, _: &ForcePush, cx| {
+                run_remote_operation(workspace, RemoteOperation::Push { force: true }, cx)
+            });
+        },
+    )
+    .detach();
+}
+
+struct RemoteOperationToast;
+
+/// Runs a remote operation on the repository of the first worktree, like the branch menu of the
+/// title bar does.
+fn run_remote_operation(
+    workspace: &mut Workspace,
+    operation: RemoteOperation,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let project = workspace.project().clone();
+    let Some(worktree) = project.read(cx).visible_worktrees(cx).next() else {
+        return;
+    };
+    let repository = ProjectPath::root_path(worktree.read(cx).id());
+    let askpass = Arc::new(AskPassPrompt {
+        window: cx.window_handle(),
+        workspace: cx.view().downgrade(),
+    });
+    let task = project.update(cx, |project, cx| {
+        project.git_remote_operation(repository, operation, askpass, cx)
+    });
+    let message = match operation {
+        RemoteOperation::Fetch => "Fetched from the remote",
+        RemoteOperation::Pull(_) => "Pulled from the remote",
+        RemoteOperation::Push { .. } => "Pushed to the remote",
+    };
+
+    cx.spawn(|workspace, mut cx| async move {
+        task.await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<RemoteOperationToast>(), message).autohide(),
+                cx,
+            );
+        })
+    })
+    .detach_and_notify_err(cx);
+}
+
+/// Asks for the credentials requested by git or ssh in a modal of the workspace.
+struct AskPassPrompt {
+    window: AnyWindowHandle,
+    workspace: WeakView<Workspace>,
+}
+
+impl AskPassDelegate for AskPassPrompt {
+    fn ask_password(
+        &self,
+        prompt: String,
+        cx: &mut AsyncAppContext,
+    ) -> oneshot::Receiver<Result<String>> {
+        let (tx, rx) = oneshot::channel();
+        self.window
+            .update(cx, |_, cx| {
+                self.workspace.update(cx, |workspace, cx| {
+                    workspace.toggle_modal(cx, |cx| AskPassModal::new(prompt, tx, cx));
+                })
+            })
+            .ok();
+        rx
+    }
+}
+
+/// Shows a credential prompt. Dismissing the modal cancels the prompt.
+struct AskPassModal {
+    prompt: SharedString,
+    editor: View<Editor>,
+    tx: Option<oneshot::Sender<Result<String>>>,
+}
+
+impl AskPassModal {
+    fn new(
+        prompt: String,
+        tx: oneshot::Sender<Result<String>>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            // Questions such as whether to trust a host key are answered in clear text.
+            editor.set_masked(!prompt.contains("yes/no"), cx);
+            editor
+        });
+        Self {
+            prompt: prompt.trim().to_string().into(),
+            editor,
+            tx: Some(tx),
+        }
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(tx) = self.tx.take() {
+            tx.send(Ok(self.editor.read(cx).text(cx))).ok();
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn cancel(&mut self, _: &menu::Cancel, cx: &mut ViewContext<Self>) {
+        cx.emit(DismissEvent);
+    }
+}
+
+impl ModalView for AskPassModal {}
+impl EventEmitter<DismissEvent> for AskPassModal {}
+
+impl FocusableView for AskPassModal {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.editor.focus_handle(cx)
+    }
+}
+
+impl Render for AskPassModal {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex()
+            .w(rems(34.))
+            .elevation_2(cx)
+            .key_context("AskPassModal")
+            .on_action(cx.listener(Self::cancel))
+            .on_action(cx.listener(Self::confirm))
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .child(Label::new(self.prompt.clone()).color(Color::Muted)),
+            )
+            .child(
+                div()
+                    .border_t_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .px_2()
+                    .py_1()
+                    .child(self.editor.clone()),
+            )
+    }
+}
@@ -0,0 +1,305 @@
+use std::sync::Arc;
+
+use editor::Editor;
+use fuzzy::{StringMatch, StringMatchCandidate};
+use git::commit::LogOptions;
+use gpui::{
+    AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render, Subscription, Task,
+    View, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use project::ProjectPath;
+use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{notifications::NotifyTaskExt, ModalView, Workspace};
+
+use crate::{
+    compare::open_revision_diff, rebase_editor::open_rebase_editor,
+    worktree_picker::create_worktree,
+};
+
+const RECENT_COMMITS_COUNT: usize = 50;
+
+/// What the revision chosen in a [`RevisionPicker`] is used for.
+#[derive(Clone)]
+pub(crate) enum RevisionTarget {
+    /// The files of an editor, whose diff base is replaced by the revision.
+    Editor(WeakView<Editor>),
+    /// The whole project, whose changes since the revision are shown in a new multibuffer.
+    Project,
+    /// An interactive rebase of the current branch onto the revision.
+    Rebase,
+    /// A new worktree checking out the revision, on a new branch when one is given.
+    Worktree(Option<String>),
+}
+
+/// Picks a revision among the branches and the recent commits of the repository. Any revision
+/// understood by git, such as `HEAD~2` or a tag, can also be typed in.
+pub(crate) struct RevisionPicker {
+    picker: View<Picker<RevisionPickerDelegate>>,
+    _subscription: Subscription,
+}
+
+impl RevisionPicker {
+    pub(crate) fn toggle(
+        workspace: &mut Workspace,
+        target: RevisionTarget,
+        cx: &mut ViewContext<Workspace>,
+    ) {
+        let project = workspace.project().read(cx);
+        let Some(worktree) = project.visible_worktrees(cx).next() else {
+            return;
+        };
+        let repository = ProjectPath::root_path(worktree.read(cx).id());
+        let branches = project.branches(repository.clone(), cx);
+        let commits = project.git_log(
+            repository,
+            false,
+            LogOptions {
+                limit: RECENT_COMMITS_COUNT,
+                ..Default::default()
+            },
+            cx,
+        );
+
+        cx.spawn(|workspace, mut cx| async move {
+            let branches = branches.await.log_err().unwrap_or_default();
+            let commits = commits.await?;
+
+            let mut candidates = Vec::new();
+            if let RevisionTarget::Editor(_) = target {
+                candidates.push(RevisionCandidate {
+                    revision: None,
+                    label: "Index".into(),
+                });
+            }
+            candidates.push(RevisionCandidate {
+                revision: Some("HEAD".into()),
+                label: "HEAD".into(),
+            });
+            candidates.extend(branches.into_iter().map(|branch| RevisionCandidate {
+                revision: Some(branch.name.to_string()),
+                label: branch.name.to_string(),
+            }));
+            candidates.extend(commits.into_iter().map(|commit| {
+                let sha = commit.sha.display_short();
+                RevisionCandidate {
+                    label: format!("{sha} {}", commit.subject),
+                    revision: Some(sha),
+                }
+            }));
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let delegate = RevisionPickerDelegate {
+                    workspace: cx.view().downgrade(),
+                    target,
+                    candidates,
+                    matches: Vec::new(),
+                    selected_index: 0,
+                };
+                workspace.toggle_modal(cx, |cx| RevisionPicker::new(delegate, cx));
+            })
+        })
+        .detach_and_notify_err(cx);
+    }
+
+    fn new(delegate: RevisionPickerDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for RevisionPicker {}
+impl EventEmitter<DismissEvent> for RevisionPicker {}
+
+impl FocusableView for RevisionPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for RevisionPicker {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+struct RevisionCandidate {
+    /// The revision to compare with, or `None` for the index.
+    revision: Option<String>,
+    label: String,
+}
+
+enum RevisionMatch {
+    Candidate(StringMatch),
+    /// A revision typed in the query.
+    Typed(String),
+}
+
+pub(crate) struct RevisionPickerDelegate {
+    workspace: WeakView<Workspace>,
+    target: RevisionTarget,
+    candidates: Vec<RevisionCandidate>,
+    matches: Vec<RevisionMatch>,
+    selected_index: usize,
+}
+
+impl PickerDelegate for RevisionPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _: &mut WindowContext) -> Arc<str> {
+        match self.target {
+            RevisionTarget::Editor(_) | RevisionTarget::Project => {
+                "Compare with branch, commit or revision...".into()
+            }
+            RevisionTarget::Rebase => "Rebase onto branch, commit or revision...".into(),
+            RevisionTarget::Worktree(_) => {
+                "Check out branch, commit or revision in a new worktree...".into()
+            }
+        }
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .candidates
+            .iter()
+            .enumerate()
+            .map(|(ix, candidate)| StringMatchCandidate::new(ix, &candidate.label))
+            .collect::<Vec<_>>();
+        cx.spawn(move |picker, mut cx| async move {
+            let query = query.trim().to_string();
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+
+            picker
+                .update(&mut cx, |picker, _| {
+                    let delegate = &mut picker.delegate;
+                    let is_listed = delegate
+                        .candidates
+                        .iter()
+                        .any(|candidate| candidate.revision.as_deref() == Some(query.as_str()));
+                    delegate.matches.clear();
+                    if !query.is_empty() && !is_listed {
+                        delegate.matches.push(RevisionMatch::Typed(query));
+                    }
+                    delegate
+                        .matches
+                        .extend(matches.into_iter().map(RevisionMatch::Candidate));
+                    delegate.selected_index = 0;
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, _: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let revision = match self.matches.get(self.selected_index) {
+            Some(RevisionMatch::Candidate(candidate)) => {
+                self.candidates[candidate.candidate_id].revision.clone()
+            }
+            Some(RevisionMatch::Typed(revision)) => Some(revision.clone()),
+            None => return,
+        };
+
+        match &self.target {
+            RevisionTarget::Editor(editor) => {
+                if let Some(editor) = editor.upgrade() {
+                    editor
+                        .update(cx, |editor, cx| editor.set_diff_base_revision(revision, cx))
+                        .detach_and_notify_err(cx);
+                }
+            }
+            RevisionTarget::Project => {
+                if let Some((workspace, revision)) = self.workspace.upgrade().zip(revision) {
+                    workspace
+                        .update(cx, |workspace, cx| {
+                            open_revision_diff(workspace, revision, cx)
+                        })
+                        .detach_and_notify_err(cx);
+                }
+            }
+            RevisionTarget::Rebase => {
+                if let Some((workspace, revision)) = self.workspace.upgrade().zip(revision) {
+                    workspace
+                        .update(cx, |workspace, cx| {
+                            open_rebase_editor(workspace, revision, cx)
+                        })
+                        .detach_and_notify_err(cx);
+                }
+            }
+            RevisionTarget::Worktree(new_branch) => {
+                if let Some((workspace, revision)) = self.workspace.upgrade().zip(revision) {
+                    workspace
+                        .update(cx, |workspace, cx| {
+                            create_worktree(workspace, revision, new_branch.clone(), cx)
+                        })
+                        .detach_and_notify_err(cx);
+                }
+            }
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let item = ListItem::new(ix)
+            .inset(true)
+            .spacing(ListItemSpacing::Sparse)
+            .toggle_state(selected);
+        Some(match &self.matches[ix] {
+            RevisionMatch::Candidate(candidate) => item.child(HighlightedLabel::new(
+                candidate.string.clone(),
+                candidate.positions.clone(),
+            )),
+            RevisionMatch::Typed(revision) => item.child(Label::new(match self.target {
+                RevisionTarget::Editor(_) | RevisionTarget::Project => {
+                    format!("Compare with \"{revision}\"")
+                }
+                RevisionTarget::Rebase => format!("Rebase onto \"{revision}\""),
+                RevisionTarget::Worktree(_) => format!("Create worktree for \"{revision}\""),
+            })),
+        })
+    }
+}
@@ -0,0 +1,366 @@
+use std::sync::Arc;
+
+use fuzzy::{StringMatch, StringMatchCandidate};
+use git::stash::{StashAction, StashEntry, StashOptions};
+use gpui::{
+    AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, Render,
+    Subscription, Task, View, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use project::ProjectPath;
+use time::{OffsetDateTime, UtcOffset};
+use time_format::TimestampFormat;
+use ui::{prelude::*, HighlightedLabel, KeyBinding, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{
+    notifications::{NotificationId, NotifyTaskExt},
+    ModalView, Toast, Workspace,
+};
+use zed_actions::git::{Stash, StashIncludingUntracked, StashPop, StashStaged, ViewStashes};
+
+use crate::commit_view::open_commit;
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+            workspace.register_action(|workspace, _: &Stash, cx| {
+                stash_changes(workspace, StashOptions::default(), cx)
+            });
+            workspace.register_action(|workspace, _: &StashIncludingUntracked, cx| {
+                let options = StashOptions {
+                    include_untracked: true,
+                    ..Default::default()
+                };
+                stash_changes(workspace, options, cx)
+            });
+            workspace.register_action(|workspace, _: &StashStaged, cx| {
+                let options = StashOptions {
+                    staged_only: true,
+                    ..Default::default()
+                };
+                stash_changes(workspace, options, cx)
+            });
+            workspace.register_action(|workspace, _: &StashPop, cx| {
+                run_stash_action(workspace, StashAction::Pop, 0, cx)
+            });
+            workspace.register_action(StashPicker::toggle);
+        },
+    )
+    .detach();
+}
+
+struct StashToast;
+
+/// The repository of the first worktree, which is the one the stash actions apply to, like the
+/// remote operations.
+fn first_repository(workspace: &Workspace, cx: &AppContext) -> Option<ProjectPath> {
+    let worktree = workspace.project().read(cx).visible_worktrees(cx).next()?;
+    Some(ProjectPath::root_path(worktree.read(cx).id()))
+}
+
+fn stash_changes(
+    workspace: &mut Workspace,
+    options: StashOptions,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(repository) = first_repository(workspace, cx) else {
+        return;
+    };
+    let task = workspace
+        .project()
+        .read(cx)
+        .stash_push(repository, None, options, cx);
+    show_toast_on_success(task, "Stashed the local changes", cx);
+}
+
+fn run_stash_action(
+    workspace: &mut Workspace,
+    action: StashAction,
+    index: usize,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let Some(repository) = first_repository(workspace, cx) else {
+        return;
+    };
+    let task = workspace
+        .project()
+        .read(cx)
+        .stash_action(repository, action, index, cx);
+    let message = match action {
+        StashAction::Apply => format!("Applied stash@{{{index}}}"),
+        StashAction::Pop => format!("Popped stash@{{{index}}}"),
+        StashAction::Drop => format!("Dropped stash@{{{index}}}"),
+    };
+    show_toast_on_success(task, message, cx);
+}
+
+fn show_toast_on_success(
+    task: Task<anyhow::Result<()>>,
+    message: impl Into<SharedString>,
+    cx: &mut ViewContext<Workspace>,
+) {
+    let message = message.into();
+    cx.spawn(|workspace, mut cx| async move {
+        task.await?;
+        workspace.update(&mut cx, |workspace, cx| {
+            workspace.show_toast(
+                Toast::new(NotificationId::unique::<StashToast>(), message).autohide(),
+                cx,
+            );
+        })
+    })
+    .detach_and_notify_err(cx);
+}
+
+/// Lists the stash entries of the repository. Confirming applies the selected entry, and a
+/// secondary confirm pops it.
+pub(crate) struct StashPicker {
+    picker: View<Picker<StashPickerDelegate>>,
+    _subscription: Subscription,
+}
+
+impl StashPicker {
+    fn toggle(workspace: &mut Workspace, _: &ViewStashes, cx: &mut ViewContext<Workspace>) {
+        let Some(repository) = first_repository(workspace, cx) else {
+            return;
+        };
+        let entries = workspace
+            .project()
+            .read(cx)
+            .stash_list(repository.clone(), cx);
+
+        cx.spawn(|workspace, mut cx| async move {
+            let entries = entries.await?;
+            if entries.is_empty() {
+                anyhow::bail!("There are no stashed changes");
+            }
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let delegate = StashPickerDelegate {
+                    workspace: cx.view().downgrade(),
+                    repository,
+                    entries,
+                    matches: Vec::new(),
+                    selected_index: 0,
+                };
+                workspace.toggle_modal(cx, |cx| StashPicker::new(delegate, cx));
+            })
+        })
+        .detach_and_notify_err(cx);
+    }
+
+    fn new(delegate: StashPickerDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for StashPicker {}
+impl EventEmitter<DismissEvent> for StashPicker {}
+
+impl FocusableView for StashPicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for StashPicker {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+pub(crate) struct StashPickerDelegate {
+    workspace: WeakView<Workspace>,
+    repository: ProjectPath,
+    entries: Vec<StashEntry>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl StashPickerDelegate {
+    fn selected_entry(&self) -> Option<&StashEntry> {
+        let candidate = self.matches.get(self.selected_index)?;
+        self.entries.get(candidate.candidate_id)
+    }
+
+    fn run_action(&mut self, action: StashAction, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(index) = self.selected_entry().map(|entry| entry.index) else {
+            return;
+        };
+        if let Some(workspace) = self.workspace.upgrade() {
+            workspace.update(cx, |workspace, cx| {
+                run_stash_action(workspace, action, index, cx)
+            });
+        }
+        cx.emit(DismissEvent);
+    }
+
+    /// Opens the changes of the selected entry to tracked files in a multibuffer.
+    fn preview(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let title = format!("{}: {}", entry.name(), entry.message);
+        let sha = entry.sha;
+        if let Some(workspace) = self.workspace.upgrade() {
+            workspace
+                .update(cx, |workspace, cx| {
+                    open_commit(workspace, self.repository.clone(), sha, title, cx)
+                })
+                .detach_and_notify_err(cx);
+        }
+        cx.emit(DismissEvent);
+    }
+}
+
+impl PickerDelegate for StashPickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _: &mut WindowContext) -> Arc<str> {
+        "Select a stash entry...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(ix, entry)| StringMatchCandidate::new(ix, &entry.message))
+            .collect::<Vec<_>>();
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+
+            picker
+                .update(&mut cx, |picker, _| {
+                    picker.delegate.matches = matches;
+                    picker.delegate.selected_index = 0;
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let action = if secondary {
+            StashAction::Pop
+        } else {
+            StashAction::Apply
+        };
+        self.run_action(action, cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let candidate = &self.matches[ix];
+        let entry = &self.entries[candidate.candidate_id];
+        let timezone =
+            UtcOffset::from_whole_seconds(chrono::Local::now().offset().local_minus_utc())
+                .unwrap_or(UtcOffset::UTC);
+        let date = OffsetDateTime::from_unix_timestamp(entry.timestamp)
+            .map(|timestamp| {
+                time_format::format_localized_timestamp(
+                    timestamp,
+                    OffsetDateTime::now_utc(),
+                    timezone,
+                    TimestampFormat::Relative,
+                )
+            })
+            .unwrap_or_default();
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .start_slot(
+                    Label::new(entry.name())
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(HighlightedLabel::new(
+                    candidate.string.clone(),
+                    candidate.positions.clone(),
+                ))
+                .end_slot(Label::new(date).size(LabelSize::Small).color(Color::Muted)),
+        )
+    }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        self.selected_entry()?;
+        Some(
+            h_flex()
+                .w_full()
+                .p_2()
+                .gap_2()
+                .justify_end()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("preview", "Preview")
+                        .on_click(cx.listener(|picker, _, cx| picker.delegate.preview(cx))),
+                )
+                .child(Button::new("drop", "Drop").on_click(
+                    cx.listener(|picker, _, cx| picker.delegate.run_action(StashAction::Drop, cx)),
+                ))
+                .child(
+                    Button::new("pop", "Pop")
+                        .key_binding(KeyBinding::for_action(&menu::SecondaryConfirm, cx))
+                        .on_click(cx.listener(|picker, _, cx| {
+                            picker.delegate.run_action(StashAction::Pop, cx)
+                        })),
+                )
+                .child(
+                    Button::new("apply", "Apply")
+                        .key_binding(KeyBinding::for_action(&menu::Confirm, cx))
+                        .on_click(cx.listener(|picker, _, cx| {
+                            picker.delegate.run_action(StashAction::Apply, cx)
+                        })),
+                )
+                .into_any(),
+        )
+    }
+}
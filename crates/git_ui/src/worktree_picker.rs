@@ -0,0 +1,384 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::{anyhow, Context as _, Result};
+use fuzzy::{StringMatch, StringMatchCandidate};
+use git::worktree::{default_worktree_path, GitWorktree};
+use gpui::{
+    AnyElement, AppContext, DismissEvent, EventEmitter, FocusHandle, FocusableView, PromptLevel,
+    Render, Subscription, Task, View, WeakView,
+};
+use picker::{Picker, PickerDelegate};
+use project::ProjectPath;
+use ui::{prelude::*, HighlightedLabel, KeyBinding, ListItem, ListItemSpacing};
+use util::ResultExt;
+use workspace::{
+    notifications::{NotificationId, NotifyTaskExt},
+    AppState, ModalView, OpenOptions, Toast, Workspace,
+};
+use zed_actions::git::{CreateWorktree, ViewWorktrees};
+
+use crate::revision_picker::{RevisionPicker, RevisionTarget};
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(
+        |workspace: &mut Workspace, _cx: &mut ViewContext<Workspace>| {
+            workspace.register_action(|workspace, action: &CreateWorktree, cx| {
+                let new_branch = action.new_branch.clone();
+                match &action.revision {
+                    Some(revision) => create_worktree(workspace, revision.clone(), new_branch, cx)
+                        .detach_and_notify_err(cx),
+                    None => {
+                        RevisionPicker::toggle(workspace, RevisionTarget::Worktree(new_branch), cx)
+                    }
+                }
+            });
+            workspace.register_action(WorktreePicker::toggle);
+        },
+    )
+    .detach();
+}
+
+struct WorktreeToast;
+
+/// The repository of the first worktree, which is the one the git worktree actions apply to,
+/// like the stash actions.
+fn first_repository(workspace: &Workspace, cx: &AppContext) -> Option<ProjectPath> {
+    let worktree = workspace.project().read(cx).visible_worktrees(cx).next()?;
+    Some(ProjectPath::root_path(worktree.read(cx).id()))
+}
+
+/// Creates a worktree checking out `revision` next to the main worktree of the repository, and
+/// offers to open it in a new window.
+pub(crate) fn create_worktree(
+    workspace: &mut Workspace,
+    revision: String,
+    new_branch: Option<String>,
+    cx: &mut ViewContext<Workspace>,
+) -> Task<Result<()>> {
+    let Some(repository) = first_repository(workspace, cx) else {
+        return Task::ready(Err(anyhow!(
+            "There is no repository to create a worktree for"
+        )));
+    };
+    let project = workspace.project().clone();
+    let worktrees = project.read(cx).git_worktrees(repository.clone(), cx);
+
+    cx.spawn(|workspace, mut cx| async move {
+        let worktrees = worktrees.await?;
+        let main_worktree = worktrees
+            .iter()
+            .find(|worktree| worktree.is_main)
+            .context("The repository has no main worktree")?;
+        let path = default_worktree_path(
+            &main_worktree.path,
+            new_branch.as_deref().unwrap_or(&revision),
+        );
+        project
+            .update(&mut cx, |project, cx| {
+                project.create_git_worktree(repository, path.clone(), revision, new_branch, cx)
+            })?
+            .await?;
+
+        workspace.update(&mut cx, |workspace, cx| {
+            let message = format!("Created a worktree at {}", path.display());
+            let mut toast = Toast::new(NotificationId::unique::<WorktreeToast>(), message);
+            if workspace.project().read(cx).is_local() {
+                let app_state = workspace.app_state().clone();
+                toast = toast.on_click("Open in New Window", move |cx| {
+                    open_worktree(path.clone(), app_state.clone(), None, cx)
+                });
+            }
+            workspace.show_toast(toast, cx);
+        })
+    })
+}
+
+/// Opens a worktree in a new window, or in place of the project of `replace_window`.
+fn open_worktree(
+    path: PathBuf,
+    app_state: Arc<AppState>,
+    replace_window: Option<gpui::WindowHandle<Workspace>>,
+    cx: &mut AppContext,
+) {
+    let options = OpenOptions {
+        open_new_workspace: Some(true),
+        replace_window,
+        ..Default::default()
+    };
+    workspace::open_paths(&[path], app_state, options, cx).detach_and_log_err(cx);
+}
+
+/// Lists the worktrees of the repository. Confirming opens the selected worktree in a new
+/// window, and a secondary confirm opens it in place of the current project.
+pub(crate) struct WorktreePicker {
+    picker: View<Picker<WorktreePickerDelegate>>,
+    _subscription: Subscription,
+}
+
+impl WorktreePicker {
+    fn toggle(workspace: &mut Workspace, _: &ViewWorktrees, cx: &mut ViewContext<Workspace>) {
+        let Some(repository) = first_repository(workspace, cx) else {
+            return;
+        };
+        let worktrees = workspace
+            .project()
+            .read(cx)
+            .git_worktrees(repository.clone(), cx);
+
+        cx.spawn(|workspace, mut cx| async move {
+            let worktrees = worktrees.await?;
+            workspace.update(&mut cx, |workspace, cx| {
+                let delegate = WorktreePickerDelegate {
+                    workspace: cx.view().downgrade(),
+                    repository,
+                    worktrees,
+                    matches: Vec::new(),
+                    selected_index: 0,
+                };
+                workspace.toggle_modal(cx, |cx| WorktreePicker::new(delegate, cx));
+            })
+        })
+        .detach_and_notify_err(cx);
+    }
+
+    fn new(delegate: WorktreePickerDelegate, cx: &mut ViewContext<Self>) -> Self {
+        let picker = cx.new_view(|cx| Picker::uniform_list(delegate, cx));
+        let _subscription = cx.subscribe(&picker, |_, _, _, cx| cx.emit(DismissEvent));
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+impl ModalView for WorktreePicker {}
+impl EventEmitter<DismissEvent> for WorktreePicker {}
+
+impl FocusableView for WorktreePicker {
+    fn focus_handle(&self, cx: &AppContext) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl Render for WorktreePicker {
+    fn render(&mut self, _: &mut ViewContext<Self>) -> impl IntoElement {
+        v_flex().w(rems(34.)).child(self.picker.clone())
+    }
+}
+
+pub(crate) struct WorktreePickerDelegate {
+    workspace: WeakView<Workspace>,
+    repository: ProjectPath,
+    worktrees: Vec<GitWorktree>,
+    matches: Vec<StringMatch>,
+    selected_index: usize,
+}
+
+impl WorktreePickerDelegate {
+    fn selected_worktree(&self) -> Option<&GitWorktree> {
+        let candidate = self.matches.get(self.selected_index)?;
+        self.worktrees.get(candidate.candidate_id)
+    }
+
+    fn open(&mut self, in_place: bool, cx: &mut ViewContext<Picker<Self>>) {
+        let Some((worktree, workspace)) = self.selected_worktree().zip(self.workspace.upgrade())
+        else {
+            return;
+        };
+        let path = worktree.path.clone();
+        let replace_window = if in_place {
+            cx.window_handle().downcast::<Workspace>()
+        } else {
+            None
+        };
+        if workspace.read(cx).project().read(cx).is_local() {
+            let app_state = workspace.read(cx).app_state().clone();
+            open_worktree(path, app_state, replace_window, cx);
+        } else {
+            workspace.update(cx, |workspace, cx| {
+                workspace.show_error(&"Worktrees can only be opened in local projects", cx)
+            });
+        }
+        cx.emit(DismissEvent);
+    }
+
+    /// Removes the selected worktree, asking for confirmation first when it has local changes.
+    fn remove(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        let Some((worktree, workspace)) = self.selected_worktree().zip(self.workspace.upgrade())
+        else {
+            return;
+        };
+        if worktree.is_main {
+            return;
+        }
+        let path = worktree.path.clone();
+        let repository = self.repository.clone();
+        let project = workspace.read(cx).project().clone();
+
+        cx.spawn(|_, mut cx| async move {
+            let remove = |force, cx: &mut gpui::AsyncWindowContext| {
+                project.update(cx, |project, cx| {
+                    project.remove_git_worktree(repository.clone(), path.clone(), force, cx)
+                })
+            };
+            if let Err(error) = remove(false, &mut cx)?.await {
+                let answer = cx.update(|cx| {
+                    cx.prompt(
+                        PromptLevel::Warning,
+                        &format!("Remove the worktree at {}?", path.display()),
+                        Some(&error.to_string()),
+                        &["Remove Anyway", "Cancel"],
+                    )
+                })?;
+                if answer.await != Ok(0) {
+                    return Ok(());
+                }
+                remove(true, &mut cx)?.await?;
+            }
+
+            workspace.update(&mut cx, |workspace, cx| {
+                let message = format!("Removed the worktree at {}", path.display());
+                workspace.show_toast(
+                    Toast::new(NotificationId::unique::<WorktreeToast>(), message).autohide(),
+                    cx,
+                );
+            })
+        })
+        .detach_and_notify_err(cx);
+        cx.emit(DismissEvent);
+    }
+}
+
+impl PickerDelegate for WorktreePickerDelegate {
+    type ListItem = ListItem;
+
+    fn placeholder_text(&self, _: &mut WindowContext) -> Arc<str> {
+        "Select a worktree...".into()
+    }
+
+    fn match_count(&self) -> usize {
+        self.matches.len()
+    }
+
+    fn selected_index(&self) -> usize {
+        self.selected_index
+    }
+
+    fn set_selected_index(&mut self, ix: usize, _: &mut ViewContext<Picker<Self>>) {
+        self.selected_index = ix;
+    }
+
+    fn update_matches(&mut self, query: String, cx: &mut ViewContext<Picker<Self>>) -> Task<()> {
+        let candidates = self
+            .worktrees
+            .iter()
+            .enumerate()
+            .map(|(ix, worktree)| StringMatchCandidate::new(ix, &worktree.path.to_string_lossy()))
+            .collect::<Vec<_>>();
+        cx.spawn(move |picker, mut cx| async move {
+            let matches = if query.is_empty() {
+                candidates
+                    .into_iter()
+                    .map(|candidate| StringMatch {
+                        candidate_id: candidate.id,
+                        string: candidate.string,
+                        positions: Vec::new(),
+                        score: 0.0,
+                    })
+                    .collect()
+            } else {
+                fuzzy::match_strings(
+                    &candidates,
+                    &query,
+                    true,
+                    10000,
+                    &Default::default(),
+                    cx.background_executor().clone(),
+                )
+                .await
+            };
+
+            picker
+                .update(&mut cx, |picker, _| {
+                    picker.delegate.matches = matches;
+                    picker.delegate.selected_index = 0;
+                })
+                .log_err();
+        })
+    }
+
+    fn confirm(&mut self, secondary: bool, cx: &mut ViewContext<Picker<Self>>) {
+        self.open(secondary, cx);
+    }
+
+    fn dismissed(&mut self, cx: &mut ViewContext<Picker<Self>>) {
+        cx.emit(DismissEvent);
+    }
+
+    fn render_match(
+        &self,
+        ix: usize,
+        selected: bool,
+        _: &mut ViewContext<Picker<Self>>,
+    ) -> Option<Self::ListItem> {
+        let candidate = &self.matches[ix];
+        let worktree = &self.worktrees[candidate.candidate_id];
+        let mut checkout_label = worktree.checkout_label();
+        if worktree.is_locked {
+            checkout_label.push_str(" (locked)");
+        }
+        if worktree.is_prunable {
+            checkout_label.push_str(" (missing)");
+        }
+
+        Some(
+            ListItem::new(ix)
+                .inset(true)
+                .spacing(ListItemSpacing::Sparse)
+                .toggle_state(selected)
+                .child(HighlightedLabel::new(
+                    candidate.string.clone(),
+                    candidate.positions.clone(),
+                ))
+                .end_slot(
+                    Label::new(checkout_label)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                ),
+        )
+    }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        let worktree = self.selected_worktree()?;
+        Some(
+            h_flex()
+                .w_full()
+                .p_2()
+                .gap_2()
+                .justify_end()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Button::new("new", "New Worktree…")
+                        .on_click(|_, cx| cx.dispatch_action(Box::new(CreateWorktree::default()))),
+                )
+                .when(!worktree.is_main, |footer| {
+                    footer.child(
+                        Button::new("remove", "Remove")
+                            .on_click(cx.listener(|picker, _, cx| picker.delegate.remove(cx))),
+                    )
+                })
+                .child(
+                    Button::new("open-here", "Open Here")
+                        .key_binding(KeyBinding::for_action(&menu::SecondaryConfirm, cx))
+                        .on_click(cx.listener(|picker, _, cx| picker.delegate.open(true, cx))),
+                )
+                .child(
+                    Button::new("open", "Open in New Window")
+                        .key_binding(KeyBinding::for_action(&menu::Confirm, cx))
+                        .on_click(cx.listener(|picker, _, cx| picker.delegate.open(false, cx))),
+                )
+                .into_any(),
+        )
+    }
+}
@@ -3256,6 +3256,115 @@ impl BufferSnapshot {
             .filter_map(|(range, obj)| (obj == TextObject::InsideFunction).then_some(range))
     }
 
+    /// Returns the foldable ranges of the buffer described by the `folds.scm` queries of its
+    /// languages, sorted by their start. Each range starts at the end of the first line of the
+    /// folded item, so that line stays visible when folded.
+    ///
+    /// Returns `None` when none of the buffer's languages have a folds query.
+    pub fn syntax_fold_ranges(&self) -> Option<Vec<Range<Point>>> {
+        let mut matches = self.syntax.matches(0..self.len(), &self.text, |grammar| {
+            grammar.folds_config.as_ref().map(|c| &c.query)
+        });
+        if matches.grammars().is_empty() {
+            return None;
+        }
+        let configs = matches
+            .grammars()
+            .iter()
+            .map(|grammar| grammar.folds_config.as_ref().unwrap())
+            .collect::<Vec<_>>();
+
+        let mut ranges = Vec::new();
+        let mut consecutive_nodes = Vec::new();
+        let mut region_comments = Vec::new();
+        while let Some(mat) = matches.peek() {
+            let config = &configs[mat.grammar_index];
+            for capture in mat.captures {
+                let node = capture.node;
+                if capture.index == config.fold_capture_ix {
+                    ranges.extend(self.fold_range_for_node(node));
+                } else if Some(capture.index) == config.consecutive_capture_ix {
+                    consecutive_nodes.push((node.kind_id(), self.node_point_range(node)));
+                } else if Some(capture.index) == config.region_capture_ix {
+                    region_comments.push(node.byte_range());
+                }
+            }
+            matches.advance();
+        }
+
+        // Items of the same kind on adjacent lines, like imports, fold as a group.
+        consecutive_nodes.sort_by_key(|(_, range)| range.start);
+        consecutive_nodes.dedup();
+        let mut consecutive_nodes = consecutive_nodes.into_iter().peekable();
+        while let Some((kind, first)) = consecutive_nodes.next() {
+            let mut end = first.end;
+            while let Some((_, next)) = consecutive_nodes
+                .next_if(|(next_kind, next)| *next_kind == kind && next.start.row == end.row + 1)
+            {
+                end = next.end;
+            }
+            if end.row > first.start.row {
+                ranges.push(Point::new(first.start.row, self.line_len(first.start.row))..end);
+            }
+        }
+
+        // Comments such as `// #region` and `// #endregion` delimit arbitrary regions.
+        region_comments.sort_by_key(|range| range.start);
+        let mut region_starts = Vec::new();
+        for comment in region_comments {
+            let text = self.text_for_range(comment.clone()).collect::<String>();
+            let marker = text.trim_start_matches(|c: char| !c.is_alphanumeric() && c != '#');
+            let row = self.offset_to_point(comment.start).row;
+            if marker.starts_with("#region") {
+                region_starts.push(row);
+            } else if marker.starts_with("#endregion") {
+                if let Some(start_row) = region_starts.pop() {
+                    let end = self.offset_to_point(comment.end);
+                    ranges.push(Point::new(start_row, self.line_len(start_row))..end);
+                }
+            }
+        }
+
+        ranges.sort_unstable_by(|a, b| a.start.cmp(&b.start).then_with(|| b.end.cmp(&a.end)));
+        ranges.dedup_by(|a, b| a.start.row == b.start.row);
+        Some(ranges)
+    }
+
+    /// Returns the range to fold for a node captured as `@fold`, which ends before its last line
+    /// when that line only contains the node's closing delimiter.
+    fn fold_range_for_node(&self, node: tree_sitter::Node) -> Option<Range<Point>> {
+        let Range {
+            start: node_start,
+            end: node_end,
+        } = self.node_point_range(node);
+        let start = Point::new(node_start.row, self.line_len(node_start.row));
+
+        let mut end = node_end;
+        if let Some(last_child) = node.child(node.child_count().saturating_sub(1)) {
+            let last_child_start = Point::from_ts_point(last_child.start_position());
+            if node.child_count() > 1
+                && last_child_start.row == node_end.row
+                && last_child_start.column == self.indent_size_for_line(node_end.row).len
+                && node_end.row > node_start.row + 1
+            {
+                end = Point::new(node_end.row - 1, self.line_len(node_end.row - 1));
+            }
+        }
+
+        (end.row > start.row).then_some(start..end)
+    }
+
+    /// Returns the range of the node, excluding the trailing newline that some grammars include in
+    /// nodes like line comments.
+    fn node_point_range(&self, node: tree_sitter::Node) -> Range<Point> {
+        let start = Point::from_ts_point(node.start_position());
+        let mut end = Point::from_ts_point(node.end_position());
+        if end.column == 0 && end.row > start.row {
+            end = Point::new(end.row - 1, self.line_len(end.row - 1));
+        }
+        start..end
+    }
+
     /// For each grammar in the language, runs the provided
     /// [`tree_sitter::Query`] against the given range.
     pub fn matches(
@@ -948,6 +948,51 @@ fn test_text_objects(cx: &mut AppContext) {
     )
 }
 
+#[gpui::test]
+fn test_syntax_fold_ranges(cx: &mut AppContext) {
+    let text = indoc! {r#"
+        use std::fmt;
+        use std::sync::Arc;
+
+        // #region helpers
+        fn one() -> u8 {
+            1
+        }
+        // #endregion
+
+        fn call(
+            a: u8,
+        ) {}
+    "#};
+    let language = rust_lang()
+        .with_folds_query(
+            r#"
+            (block) @fold
+            (parameters) @fold
+            (use_declaration) @fold.consecutive
+            (line_comment) @fold.region
+            "#,
+        )
+        .unwrap();
+
+    let buffer = cx.new_model(|cx| Buffer::local(text, cx).with_language(Arc::new(language), cx));
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    assert_eq!(
+        snapshot.syntax_fold_ranges(),
+        Some(vec![
+            Point::new(0, 13)..Point::new(1, 19),
+            Point::new(3, 18)..Point::new(7, 13),
+            Point::new(4, 16)..Point::new(5, 5),
+            Point::new(9, 8)..Point::new(10, 10),
+        ])
+    );
+
+    let buffer =
+        cx.new_model(|cx| Buffer::local(text, cx).with_language(Arc::new(rust_lang()), cx));
+    let snapshot = buffer.update(cx, |buffer, _| buffer.snapshot());
+    assert_eq!(snapshot.syntax_fold_ranges(), None);
+}
+
 #[gpui::test]
 fn test_enclosing_bracket_ranges(cx: &mut AppContext) {
     let mut assert = |selection_text, range_markers| {
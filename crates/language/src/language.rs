@@ -849,6 +849,7 @@ pub struct Grammar {
     pub(crate) indents_config: Option<IndentConfig>,
     pub outline_config: Option<OutlineConfig>,
     pub text_object_config: Option<TextObjectConfig>,
+    pub(crate) folds_config: Option<FoldConfig>,
     pub embedding_config: Option<EmbeddingConfig>,
     pub(crate) injection_config: Option<InjectionConfig>,
     pub(crate) override_config: Option<OverrideConfig>,
@@ -912,6 +913,13 @@ pub struct TextObjectConfig {
     pub text_objects_by_capture_ix: Vec<(u32, TextObject)>,
 }
 
+struct FoldConfig {
+    query: Query,
+    fold_capture_ix: u32,
+    consecutive_capture_ix: Option<u32>,
+    region_capture_ix: Option<u32>,
+}
+
 #[derive(Debug)]
 pub struct EmbeddingConfig {
     pub query: Query,
@@ -990,6 +998,7 @@ impl Language {
                     brackets_config: None,
                     outline_config: None,
                     text_object_config: None,
+                    folds_config: None,
                     embedding_config: None,
                     indents_config: None,
                     injection_config: None,
@@ -1067,6 +1076,11 @@ impl Language {
                 .with_text_object_query(query.as_ref())
                 .context("Error loading textobject query")?;
         }
+        if let Some(query) = queries.folds {
+            self = self
+                .with_folds_query(query.as_ref())
+                .context("Error loading folds query")?;
+        }
         Ok(self)
     }
 
@@ -1162,6 +1176,33 @@ impl Language {
         Ok(self)
     }
 
+    pub fn with_folds_query(mut self, source: &str) -> Result<Self> {
+        let grammar = self
+            .grammar_mut()
+            .ok_or_else(|| anyhow!("cannot mutate grammar"))?;
+        let query = Query::new(&grammar.ts_language, source)?;
+        let mut fold_capture_ix = None;
+        let mut consecutive_capture_ix = None;
+        let mut region_capture_ix = None;
+        get_capture_indices(
+            &query,
+            &mut [
+                ("fold", &mut fold_capture_ix),
+                ("fold.consecutive", &mut consecutive_capture_ix),
+                ("fold.region", &mut region_capture_ix),
+            ],
+        );
+        if let Some(fold_capture_ix) = fold_capture_ix {
+            grammar.folds_config = Some(FoldConfig {
+                query,
+                fold_capture_ix,
+                consecutive_capture_ix,
+                region_capture_ix,
+            });
+        }
+        Ok(self)
+    }
+
     pub fn with_embedding_query(mut self, source: &str) -> Result<Self> {
         let grammar = self
             .grammar_mut()
@@ -182,6 +182,7 @@ pub const QUERY_FILENAME_PREFIXES: &[(
     ("redactions", |q| &mut q.redactions),
     ("runnables", |q| &mut q.runnables),
     ("textobjects", |q| &mut q.text_objects),
+    ("folds", |q| &mut q.folds),
 ];
 
 /// Tree-sitter language queries for a given language.
@@ -197,6 +198,7 @@ pub struct LanguageQueries {
     pub redactions: Option<Cow<'static, str>>,
     pub runnables: Option<Cow<'static, str>>,
     pub text_objects: Option<Cow<'static, str>>,
+    pub folds: Option<Cow<'static, str>>,
 }
 
 #[derive(Clone, Default)]
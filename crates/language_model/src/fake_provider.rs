@@ -84,7 +84,12 @@ pub struct ToolUseRequest {
 
 #[derive(Default)]
 pub struct FakeLanguageModel {
-    current_completion_txs: Mutex<Vec<(LanguageModelRequest, mpsc::UnboundedSender<String>)>>,
+    current_completion_txs: Mutex<
+        Vec<(
+            LanguageModelRequest,
+            mpsc::UnboundedSender<LanguageModelCompletionEvent>,
+        )>,
+    >,
     current_tool_use_txs: Mutex<Vec<(ToolUseRequest, mpsc::UnboundedSender<String>)>>,
 }
 
@@ -102,13 +107,21 @@ impl FakeLanguageModel {
     }
 
     pub fn stream_completion_response(&self, request: &LanguageModelRequest, chunk: String) {
+        self.send_completion_event(request, LanguageModelCompletionEvent::Text(chunk));
+    }
+
+    pub fn send_completion_event(
+        &self,
+        request: &LanguageModelRequest,
+        event: LanguageModelCompletionEvent,
+    ) {
         let current_completion_txs = self.current_completion_txs.lock();
         let tx = current_completion_txs
             .iter()
             .find(|(req, _)| req == request)
             .map(|(_, tx)| tx)
             .unwrap();
-        tx.unbounded_send(chunk).unwrap();
+        tx.unbounded_send(event).unwrap();
     }
 
     pub fn end_completion_stream(&self, request: &LanguageModelRequest) {
@@ -121,6 +134,10 @@ impl FakeLanguageModel {
         self.stream_completion_response(self.pending_completions().last().unwrap(), chunk);
     }
 
+    pub fn send_last_completion_event(&self, event: LanguageModelCompletionEvent) {
+        self.send_completion_event(self.pending_completions().last().unwrap(), event);
+    }
+
     pub fn end_last_completion_stream(&self) {
         self.end_completion_stream(self.pending_completions().last().unwrap());
     }
@@ -173,12 +190,7 @@ impl LanguageModel for FakeLanguageModel {
     ) -> BoxFuture<'static, Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>> {
         let (tx, rx) = mpsc::unbounded();
         self.current_completion_txs.lock().push((request, tx));
-        async move {
-            Ok(rx
-                .map(|text| Ok(LanguageModelCompletionEvent::Text(text)))
-                .boxed())
-        }
-        .boxed()
+        async move { Ok(rx.map(Ok).boxed()) }.boxed()
     }
 
     fn use_any_tool(
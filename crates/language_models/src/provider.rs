@@ -4,3 +4,4 @@ pub mod copilot_chat;
 pub mod google;
 pub mod ollama;
 pub mod open_ai;
+pub mod open_ai_compatible;
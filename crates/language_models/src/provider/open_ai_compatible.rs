@@ -0,0 +1,975 @@
+use anyhow::{anyhow, Result};
+use collections::{BTreeMap, BTreeSet, HashMap};
+use editor::{Editor, EditorElement, EditorStyle};
+use futures::{future::BoxFuture, stream::BoxStream, FutureExt, StreamExt};
+use gpui::{
+    AnyView, AppContext, AsyncAppContext, FontStyle, ModelContext, Subscription, Task, TextStyle,
+    View, WhiteSpace,
+};
+use http_client::HttpClient;
+use language_model::{
+    LanguageModel, LanguageModelCompletionEvent, LanguageModelId, LanguageModelName,
+    LanguageModelProvider, LanguageModelProviderId, LanguageModelProviderName,
+    LanguageModelProviderState, LanguageModelRegistry, LanguageModelRequest, LanguageModelToolUse,
+    MessageContent, RateLimiter, Role, StopReason,
+};
+use open_ai::{
+    stream_completion_with_headers, FunctionContent, FunctionDefinition, ImageUrl, MessagePart,
+    RequestMessage, ResponseStreamEvent, ToolCall, ToolCallContent, ToolChoice, ToolDefinition,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use settings::{Settings, SettingsStore};
+use std::sync::Arc;
+use theme::ThemeSettings;
+use ui::{prelude::*, Icon, IconName};
+use util::ResultExt;
+
+use crate::provider::open_ai::count_open_ai_tokens;
+use crate::AllLanguageModelSettings;
+
+const PROVIDER_ID_PREFIX: &str = "openai_compatible/";
+
+/// The context window assumed for discovered models whose endpoint doesn't report it.
+const DEFAULT_MAX_TOKENS: usize = 8192;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OpenAiCompatibleSettings {
+    pub api_url: String,
+    pub headers: BTreeMap<String, String>,
+    pub available_models: Vec<AvailableModel>,
+    pub discover_models: bool,
+    pub requires_api_key: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AvailableModel {
+    /// The model's name in the API, e.g. "meta-llama/Llama-3.1-8B-Instruct".
+    pub name: String,
+    /// The model's name in Zed's UI, such as in the model selector dropdown menu in the assistant panel.
+    pub display_name: Option<String>,
+    /// The model's context window size.
+    pub max_tokens: usize,
+    /// The maximum number of tokens the model may generate in a response.
+    pub max_output_tokens: Option<u32>,
+    /// Whether the model supports tool calling. Tools are only sent to models that do.
+    #[serde(default)]
+    pub supports_tools: bool,
+    /// Whether the model accepts images. Images are left out of requests to models that don't.
+    #[serde(default)]
+    pub supports_images: bool,
+}
+
+/// Registers a provider for each OpenAI-compatible endpoint in the settings, and keeps the
+/// registered providers in sync with the settings as they change.
+pub fn register_providers(
+    registry: &mut LanguageModelRegistry,
+    http_client: Arc<dyn HttpClient>,
+    cx: &mut ModelContext<LanguageModelRegistry>,
+) {
+    let mut registered_endpoints = BTreeSet::default();
+    sync_providers(registry, &mut registered_endpoints, &http_client, cx);
+    cx.observe_global::<SettingsStore>(move |registry, cx| {
+        sync_providers(registry, &mut registered_endpoints, &http_client, cx);
+    })
+    .detach();
+}
+
+fn sync_providers(
+    registry: &mut LanguageModelRegistry,
+    registered_endpoints: &mut BTreeSet<Arc<str>>,
+    http_client: &Arc<dyn HttpClient>,
+    cx: &mut ModelContext<LanguageModelRegistry>,
+) {
+    let endpoints = AllLanguageModelSettings::get_global(cx)
+        .openai_compatible
+        .keys()
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    if &endpoints == registered_endpoints {
+        return;
+    }
+
+    for name in registered_endpoints.difference(&endpoints) {
+        registry.unregister_provider(provider_id(name), cx);
+    }
+    for name in endpoints.difference(registered_endpoints) {
+        registry.register_provider(
+            OpenAiCompatibleLanguageModelProvider::new(name.clone(), http_client.clone(), cx),
+            cx,
+        );
+    }
+    *registered_endpoints = endpoints;
+}
+
+fn provider_id(name: &str) -> LanguageModelProviderId {
+    LanguageModelProviderId(format!("{PROVIDER_ID_PREFIX}{name}").into())
+}
+
+pub struct OpenAiCompatibleLanguageModelProvider {
+    name: Arc<str>,
+    http_client: Arc<dyn HttpClient>,
+    state: gpui::Model<State>,
+}
+
+pub struct State {
+    name: Arc<str>,
+    http_client: Arc<dyn HttpClient>,
+    api_key: Option<String>,
+    discovered_models: Vec<AvailableModel>,
+    fetch_models_task: Option<Task<Result<()>>>,
+    _subscription: Subscription,
+}
+
+impl State {
+    fn settings<'a>(&self, cx: &'a AppContext) -> Option<&'a OpenAiCompatibleSettings> {
+        AllLanguageModelSettings::get_global(cx)
+            .openai_compatible
+            .get(&self.name)
+    }
+
+    fn requires_api_key(&self, cx: &AppContext) -> bool {
+        self.settings(cx)
+            .map_or(true, |settings| settings.requires_api_key)
+    }
+
+    fn is_authenticated(&self, cx: &AppContext) -> bool {
+        self.api_key.is_some() || !self.requires_api_key(cx)
+    }
+
+    fn reset_api_key(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let Some(settings) = self.settings(cx) else {
+            return Task::ready(Err(anyhow!("Endpoint {} is not configured", self.name)));
+        };
+        let delete_credentials = cx.delete_credentials(&settings.api_url);
+        cx.spawn(|this, mut cx| async move {
+            delete_credentials.await.log_err();
+            this.update(&mut cx, |this, cx| {
+                this.api_key = None;
+                this.discovered_models.clear();
+                cx.notify();
+            })
+        })
+    }
+
+    fn set_api_key(&mut self, api_key: String, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let Some(settings) = self.settings(cx) else {
+            return Task::ready(Err(anyhow!("Endpoint {} is not configured", self.name)));
+        };
+        let write_credentials =
+            cx.write_credentials(&settings.api_url, "Bearer", api_key.as_bytes());
+
+        cx.spawn(|this, mut cx| async move {
+            write_credentials.await?;
+            this.update(&mut cx, |this, cx| {
+                this.api_key = Some(api_key);
+                this.restart_fetch_models_task(cx);
+                cx.notify();
+            })
+        })
+    }
+
+    fn authenticate(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        if self.api_key.is_some() {
+            return Task::ready(Ok(()));
+        }
+        let Some(settings) = self.settings(cx) else {
+            return Task::ready(Err(anyhow!("Endpoint {} is not configured", self.name)));
+        };
+        if !settings.requires_api_key {
+            if self.fetch_models_task.is_none() {
+                self.restart_fetch_models_task(cx);
+            }
+            return Task::ready(Ok(()));
+        }
+
+        let read_credentials = cx.read_credentials(&settings.api_url);
+        cx.spawn(|this, mut cx| async move {
+            let (_, api_key) = read_credentials
+                .await?
+                .ok_or_else(|| anyhow!("credentials not found"))?;
+            let api_key = String::from_utf8(api_key)?;
+            this.update(&mut cx, |this, cx| {
+                this.api_key = Some(api_key);
+                this.restart_fetch_models_task(cx);
+                cx.notify();
+            })
+        })
+    }
+
+    /// Fetches the models served by the endpoint from its `/models` route, unless model discovery
+    /// is turned off for it.
+    fn fetch_models(&mut self, cx: &mut ModelContext<Self>) -> Task<Result<()>> {
+        let Some(settings) = self.settings(cx) else {
+            return Task::ready(Ok(()));
+        };
+        let api_key = self.api_key.clone();
+        if api_key.is_none() && settings.requires_api_key {
+            return Task::ready(Ok(()));
+        }
+        if !settings.discover_models {
+            self.discovered_models.clear();
+            return Task::ready(Ok(()));
+        }
+
+        let http_client = self.http_client.clone();
+        let api_url = settings.api_url.clone();
+        let headers = request_headers(settings);
+        cx.spawn(|this, mut cx| async move {
+            let models =
+                open_ai::list_models(http_client.as_ref(), &api_url, api_key.as_deref(), &headers)
+                    .await?;
+            let models = models
+                .into_iter()
+                .map(|model| AvailableModel {
+                    name: model.id,
+                    display_name: None,
+                    max_tokens: model.max_model_len.unwrap_or(DEFAULT_MAX_TOKENS),
+                    max_output_tokens: None,
+                    supports_tools: false,
+                    supports_images: false,
+                })
+                .collect();
+
+            this.update(&mut cx, |this, cx| {
+                this.discovered_models = models;
+                cx.notify();
+            })
+        })
+    }
+
+    fn restart_fetch_models_task(&mut self, cx: &mut ModelContext<Self>) {
+        let task = self.fetch_models(cx);
+        self.fetch_models_task.replace(task);
+    }
+}
+
+fn request_headers(settings: &OpenAiCompatibleSettings) -> Vec<(String, String)> {
+    settings
+        .headers
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect()
+}
+
+impl OpenAiCompatibleLanguageModelProvider {
+    pub fn new(name: Arc<str>, http_client: Arc<dyn HttpClient>, cx: &mut AppContext) -> Self {
+        let state = cx.new_model(|cx| {
+            let subscription = cx.observe_global::<SettingsStore>({
+                let mut settings = AllLanguageModelSettings::get_global(cx)
+                    .openai_compatible
+                    .get(&name)
+                    .cloned();
+                move |this: &mut State, cx| {
+                    let new_settings = this.settings(cx).cloned();
+                    if settings == new_settings {
+                        return;
+                    }
+
+                    // The credentials are stored per URL, so they have to be loaded again when the
+                    // URL changes.
+                    let url_changed = settings.as_ref().map(|settings| &settings.api_url)
+                        != new_settings.as_ref().map(|settings| &settings.api_url);
+                    settings = new_settings;
+                    if url_changed {
+                        this.api_key = None;
+                        this.discovered_models.clear();
+                        this.authenticate(cx).detach();
+                    } else {
+                        this.restart_fetch_models_task(cx);
+                    }
+                    cx.notify();
+                }
+            });
+
+            State {
+                name: name.clone(),
+                http_client: http_client.clone(),
+                api_key: None,
+                discovered_models: Vec::new(),
+                fetch_models_task: None,
+                _subscription: subscription,
+            }
+        });
+
+        Self {
+            name,
+            http_client,
+            state,
+        }
+    }
+}
+
+impl LanguageModelProviderState for OpenAiCompatibleLanguageModelProvider {
+    type ObservableEntity = State;
+
+    fn observable_entity(&self) -> Option<gpui::Model<Self::ObservableEntity>> {
+        Some(self.state.clone())
+    }
+}
+
+impl LanguageModelProvider for OpenAiCompatibleLanguageModelProvider {
+    fn id(&self) -> LanguageModelProviderId {
+        provider_id(&self.name)
+    }
+
+    fn name(&self) -> LanguageModelProviderName {
+        LanguageModelProviderName(self.name.to_string().into())
+    }
+
+    fn icon(&self) -> IconName {
+        IconName::AiOpenAi
+    }
+
+    fn provided_models(&self, cx: &AppContext) -> Vec<Arc<dyn LanguageModel>> {
+        let state = self.state.read(cx);
+        let mut models = BTreeMap::default();
+
+        // Add the models discovered from the endpoint
+        for model in &state.discovered_models {
+            models.insert(model.name.clone(), model.clone());
+        }
+
+        // Override with available models from settings
+        if let Some(settings) = state.settings(cx) {
+            for model in &settings.available_models {
+                models.insert(model.name.clone(), model.clone());
+            }
+        }
+
+        models
+            .into_values()
+            .map(|model| {
+                Arc::new(OpenAiCompatibleLanguageModel {
+                    id: LanguageModelId::from(model.name.clone()),
+                    provider_id: self.id(),
+                    provider_name: self.name(),
+                    model,
+                    state: self.state.clone(),
+                    http_client: self.http_client.clone(),
+                    request_limiter: RateLimiter::new(4),
+                }) as Arc<dyn LanguageModel>
+            })
+            .collect()
+    }
+
+    fn is_authenticated(&self, cx: &AppContext) -> bool {
+        self.state.read(cx).is_authenticated(cx)
+    }
+
+    fn authenticate(&self, cx: &mut AppContext) -> Task<Result<()>> {
+        self.state.update(cx, |state, cx| state.authenticate(cx))
+    }
+
+    fn configuration_view(&self, cx: &mut WindowContext) -> AnyView {
+        cx.new_view(|cx| ConfigurationView::new(self.state.clone(), cx))
+            .into()
+    }
+
+    fn reset_credentials(&self, cx: &mut AppContext) -> Task<Result<()>> {
+        self.state.update(cx, |state, cx| state.reset_api_key(cx))
+    }
+}
+
+pub struct OpenAiCompatibleLanguageModel {
+    id: LanguageModelId,
+    provider_id: LanguageModelProviderId,
+    provider_name: LanguageModelProviderName,
+    model: AvailableModel,
+    state: gpui::Model<State>,
+    http_client: Arc<dyn HttpClient>,
+    request_limiter: RateLimiter,
+}
+
+impl OpenAiCompatibleLanguageModel {
+    fn stream_completion(
+        &self,
+        request: open_ai::Request,
+        cx: &AsyncAppContext,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<ResponseStreamEvent>>>> {
+        let http_client = self.http_client.clone();
+        let Ok(endpoint) = cx.read_model(&self.state, |state, cx| {
+            state.settings(cx).map(|settings| {
+                (
+                    state.api_key.clone(),
+                    settings.requires_api_key,
+                    settings.api_url.clone(),
+                    request_headers(settings),
+                )
+            })
+        }) else {
+            return futures::future::ready(Err(anyhow!("App state dropped"))).boxed();
+        };
+        let provider_name = self.provider_name.0.clone();
+
+        let future = self.request_limiter.stream(async move {
+            let (api_key, requires_api_key, api_url, headers) =
+                endpoint.ok_or_else(|| anyhow!("Endpoint {provider_name} is not configured"))?;
+            if api_key.is_none() && requires_api_key {
+                return Err(anyhow!("Missing {provider_name} API Key"));
+            }
+            let request = stream_completion_with_headers(
+                http_client.as_ref(),
+                &api_url,
+                api_key.as_deref(),
+                &headers,
+                request,
+            );
+            let response = request.await?;
+            Ok(response)
+        });
+
+        async move { Ok(future.await?.boxed()) }.boxed()
+    }
+
+    /// Converts the request to the OpenAI format, only including tools and images when the model
+    /// supports them.
+    fn to_open_ai_request(&self, request: LanguageModelRequest) -> open_ai::Request {
+        let mut messages = Vec::new();
+        for message in request.messages {
+            match message.role {
+                Role::User if self.model.supports_tools || self.model.supports_images => {
+                    let mut parts = Vec::new();
+                    for content in message.content {
+                        match content {
+                            MessageContent::Text(text) => parts.push(MessagePart::Text { text }),
+                            MessageContent::Image(image) if self.model.supports_images => parts
+                                .push(MessagePart::ImageUrl {
+                                    image_url: ImageUrl {
+                                        url: format!("data:image/png;base64,{}", image.source),
+                                    },
+                                }),
+                            MessageContent::ToolResult(tool_result)
+                                if self.model.supports_tools =>
+                            {
+                                messages.push(RequestMessage::Tool {
+                                    content: tool_result.content,
+                                    tool_call_id: tool_result.tool_use_id,
+                                })
+                            }
+                            MessageContent::ToolResult(tool_result) => {
+                                parts.push(MessagePart::Text {
+                                    text: tool_result.content,
+                                })
+                            }
+                            MessageContent::Image(_) | MessageContent::ToolUse(_) => {}
+                        }
+                    }
+                    if !parts.is_empty() {
+                        let content = if self.model.supports_images {
+                            open_ai::MessageContent::Multipart(parts)
+                        } else {
+                            open_ai::MessageContent::Multipart(parts).text().into()
+                        };
+                        messages.push(RequestMessage::User { content });
+                    }
+                }
+                Role::User => messages.push(RequestMessage::User {
+                    content: message.string_contents().into(),
+                }),
+                Role::Assistant => {
+                    let tool_calls = if self.model.supports_tools {
+                        message
+                            .content
+                            .iter()
+                            .filter_map(|content| match content {
+                                MessageContent::ToolUse(tool_use) => Some(ToolCall {
+                                    id: tool_use.id.to_string(),
+                                    content: ToolCallContent::Function {
+                                        function: FunctionContent {
+                                            name: tool_use.name.clone(),
+                                            arguments: tool_use.input.to_string(),
+                                        },
+                                    },
+                                }),
+                                _ => None,
+                            })
+                            .collect()
+                    } else {
+                        Vec::new()
+                    };
+                    let text = message.string_contents();
+                    messages.push(RequestMessage::Assistant {
+                        content: (!text.is_empty() || tool_calls.is_empty()).then_some(text),
+                        tool_calls,
+                    });
+                }
+                Role::System => messages.push(RequestMessage::System {
+                    content: message.string_contents(),
+                }),
+            }
+        }
+
+        let tools = if self.model.supports_tools {
+            request
+                .tools
+                .into_iter()
+                .map(|tool| ToolDefinition::Function {
+                    function: FunctionDefinition {
+                        name: tool.name,
+                        description: Some(tool.description),
+                        parameters: Some(tool.input_schema),
+                    },
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        open_ai::Request {
+            model: self.model.name.clone(),
+            messages,
+            stream: true,
+            max_tokens: self.model.max_output_tokens,
+            stop: request.stop,
+            temperature: request.temperature.unwrap_or(1.0),
+            tool_choice: None,
+            tools,
+        }
+    }
+}
+
+impl LanguageModel for OpenAiCompatibleLanguageModel {
+    fn id(&self) -> LanguageModelId {
+        self.id.clone()
+    }
+
+    fn name(&self) -> LanguageModelName {
+        LanguageModelName::from(
+            self.model
+                .display_name
+                .clone()
+                .unwrap_or_else(|| self.model.name.clone()),
+        )
+    }
+
+    fn provider_id(&self) -> LanguageModelProviderId {
+        self.provider_id.clone()
+    }
+
+    fn provider_name(&self) -> LanguageModelProviderName {
+        self.provider_name.clone()
+    }
+
+    fn telemetry_id(&self) -> String {
+        format!("openai_compatible/{}", self.model.name)
+    }
+
+    fn max_token_count(&self) -> usize {
+        self.model.max_tokens
+    }
+
+    fn max_output_tokens(&self) -> Option<u32> {
+        self.model.max_output_tokens
+    }
+
+    fn count_tokens(
+        &self,
+        request: LanguageModelRequest,
+        cx: &AppContext,
+    ) -> BoxFuture<'static, Result<usize>> {
+        count_open_ai_tokens(
+            request,
+            open_ai::Model::Custom {
+                name: self.model.name.clone(),
+                display_name: self.model.display_name.clone(),
+                max_tokens: self.model.max_tokens,
+                max_output_tokens: self.model.max_output_tokens,
+                max_completion_tokens: None,
+            },
+            cx,
+        )
+    }
+
+    fn stream_completion(
+        &self,
+        request: LanguageModelRequest,
+        cx: &AsyncAppContext,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<LanguageModelCompletionEvent>>>> {
+        let request = self.to_open_ai_request(request);
+        let completions = self.stream_completion(request, cx);
+        async move { Ok(map_to_completion_events(completions.await?).boxed()) }.boxed()
+    }
+
+    fn use_any_tool(
+        &self,
+        request: LanguageModelRequest,
+        tool_name: String,
+        tool_description: String,
+        schema: serde_json::Value,
+        cx: &AsyncAppContext,
+    ) -> BoxFuture<'static, Result<BoxStream<'static, Result<String>>>> {
+        if !self.model.supports_tools {
+            return futures::future::ready(Err(anyhow!(
+                "{} does not support tool calling",
+                self.model.name
+            )))
+            .boxed();
+        }
+
+        let mut request = self.to_open_ai_request(request);
+        request.tool_choice = Some(ToolChoice::Other(ToolDefinition::Function {
+            function: FunctionDefinition {
+                name: tool_name.clone(),
+                description: None,
+                parameters: None,
+            },
+        }));
+        request.tools = vec![ToolDefinition::Function {
+            function: FunctionDefinition {
+                name: tool_name.clone(),
+                description: Some(tool_description),
+                parameters: Some(schema),
+            },
+        }];
+
+        let response = self.stream_completion(request, cx);
+        self.request_limiter
+            .run(async move {
+                let response = response.await?;
+                Ok(
+                    open_ai::extract_tool_args_from_events(tool_name, Box::pin(response))
+                        .await?
+                        .boxed(),
+                )
+            })
+            .boxed()
+    }
+}
+
+#[derive(Default)]
+struct RawToolCall {
+    id: String,
+    name: String,
+    arguments: String,
+}
+
+/// Maps the events streamed by an OpenAI-compatible API to completion events, accumulating the
+/// chunks of each tool call until the model finishes its response.
+fn map_to_completion_events(
+    events: impl futures::Stream<Item = Result<ResponseStreamEvent>>,
+) -> impl futures::Stream<Item = Result<LanguageModelCompletionEvent>> {
+    let mut tool_calls_by_index = HashMap::<usize, RawToolCall>::default();
+    events.flat_map(move |event| {
+        let mut completion_events = Vec::new();
+        match event {
+            Ok(event) => {
+                for choice in event.choices {
+                    if let Some(text) = choice.delta.content.filter(|text| !text.is_empty()) {
+                        completion_events.push(Ok(LanguageModelCompletionEvent::Text(text)));
+                    }
+
+                    for chunk in choice.delta.tool_calls.unwrap_or_default() {
+                        let tool_call = tool_calls_by_index.entry(chunk.index).or_default();
+                        if let Some(id) = chunk.id {
+                            tool_call.id = id;
+                        }
+                        if let Some(function) = chunk.function {
+                            if let Some(name) = function.name {
+                                tool_call.name = name;
+                            }
+                            if let Some(arguments) = function.arguments {
+                                tool_call.arguments.push_str(&arguments);
+                            }
+                        }
+                    }
+
+                    let Some(finish_reason) = choice.finish_reason else {
+                        continue;
+                    };
+                    let mut tool_calls = tool_calls_by_index.drain().collect::<Vec<_>>();
+                    tool_calls.sort_by_key(|(index, _)| *index);
+                    for (_, tool_call) in tool_calls {
+                        completion_events.push(parse_tool_call(tool_call));
+                    }
+                    let stop_reason = match finish_reason.as_str() {
+                        "tool_calls" => StopReason::ToolUse,
+                        "length" => StopReason::MaxTokens,
+                        _ => StopReason::EndTurn,
+                    };
+                    completion_events.push(Ok(LanguageModelCompletionEvent::Stop(stop_reason)));
+                }
+            }
+            Err(error) => completion_events.push(Err(error)),
+        }
+        futures::stream::iter(completion_events)
+    })
+}
+
+fn parse_tool_call(tool_call: RawToolCall) -> Result<LanguageModelCompletionEvent> {
+    let input = if tool_call.arguments.trim().is_empty() {
+        serde_json::Value::Object(Default::default())
+    } else {
+        serde_json::from_str(&tool_call.arguments).map_err(|error| {
+            anyhow!(
+                "Invalid arguments for tool {}: {error}: {}",
+                tool_call.name,
+                tool_call.arguments
+            )
+        })?
+    };
+    Ok(LanguageModelCompletionEvent::ToolUse(
+        LanguageModelToolUse {
+            id: tool_call.id.into(),
+            name: tool_call.name,
+            input,
+        },
+    ))
+}
+
+struct ConfigurationView {
+    api_key_editor: View<Editor>,
+    state: gpui::Model<State>,
+    load_credentials_task: Option<Task<()>>,
+}
+
+impl ConfigurationView {
+    fn new(state: gpui::Model<State>, cx: &mut ViewContext<Self>) -> Self {
+        let api_key_editor = cx.new_view(|cx| {
+            let mut editor = Editor::single_line(cx);
+            editor.set_placeholder_text("API key", cx);
+            editor
+        });
+
+        cx.observe(&state, |_, _, cx| {
+            cx.notify();
+        })
+        .detach();
+
+        let load_credentials_task = Some(cx.spawn({
+            let state = state.clone();
+            |this, mut cx| async move {
+                if let Some(task) = state
+                    .update(&mut cx, |state, cx| state.authenticate(cx))
+                    .log_err()
+                {
+                    // We don't log an error, because "not signed in" is also an error.
+                    let _ = task.await;
+                }
+
+                this.update(&mut cx, |this, cx| {
+                    this.load_credentials_task = None;
+                    cx.notify();
+                })
+                .log_err();
+            }
+        }));
+
+        Self {
+            api_key_editor,
+            state,
+            load_credentials_task,
+        }
+    }
+
+    fn save_api_key(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        let api_key = self.api_key_editor.read(cx).text(cx);
+        if api_key.is_empty() {
+            return;
+        }
+
+        let state = self.state.clone();
+        cx.spawn(|_, mut cx| async move {
+            state
+                .update(&mut cx, |state, cx| state.set_api_key(api_key, cx))?
+                .await
+        })
+        .detach_and_log_err(cx);
+
+        cx.notify();
+    }
+
+    fn reset_api_key(&mut self, cx: &mut ViewContext<Self>) {
+        self.api_key_editor
+            .update(cx, |editor, cx| editor.set_text("", cx));
+
+        let state = self.state.clone();
+        cx.spawn(|_, mut cx| async move {
+            state
+                .update(&mut cx, |state, cx| state.reset_api_key(cx))?
+                .await
+        })
+        .detach_and_log_err(cx);
+
+        cx.notify();
+    }
+
+    fn render_api_key_editor(&self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let settings = ThemeSettings::get_global(cx);
+        let text_style = TextStyle {
+            color: cx.theme().colors().text,
+            font_family: settings.ui_font.family.clone(),
+            font_features: settings.ui_font.features.clone(),
+            font_fallbacks: settings.ui_font.fallbacks.clone(),
+            font_size: rems(0.875).into(),
+            font_weight: settings.ui_font.weight,
+            font_style: FontStyle::Normal,
+            line_height: relative(1.3),
+            background_color: None,
+            underline: None,
+            strikethrough: None,
+            white_space: WhiteSpace::Normal,
+            truncate: None,
+        };
+        EditorElement::new(
+            &self.api_key_editor,
+            EditorStyle {
+                background: cx.theme().colors().editor_background,
+                local_player: cx.theme().players().local(),
+                text: text_style,
+                ..Default::default()
+            },
+        )
+    }
+}
+
+impl Render for ConfigurationView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let state = self.state.read(cx);
+        let is_authenticated = state.is_authenticated(cx);
+        let requires_api_key = state.requires_api_key(cx);
+        let api_url = state
+            .settings(cx)
+            .map(|settings| settings.api_url.clone())
+            .unwrap_or_default();
+
+        if self.load_credentials_task.is_some() {
+            div().child(Label::new("Loading credentials...")).into_any()
+        } else if !is_authenticated {
+            v_flex()
+                .size_full()
+                .on_action(cx.listener(Self::save_api_key))
+                .child(Label::new(format!(
+                    "To use the models served at {api_url}, paste its API key below and hit enter."
+                )))
+                .child(
+                    h_flex()
+                        .w_full()
+                        .my_2()
+                        .px_2()
+                        .py_1()
+                        .bg(cx.theme().colors().editor_background)
+                        .rounded_md()
+                        .child(self.render_api_key_editor(cx)),
+                )
+                .child(
+                    Label::new(
+                        "The key is stored in your keychain for this URL. If the endpoint doesn't check API keys, set `requires_api_key` to `false` in its settings.",
+                    )
+                    .size(LabelSize::Small),
+                )
+                .into_any()
+        } else if !requires_api_key {
+            h_flex()
+                .gap_1()
+                .child(Icon::new(IconName::Check).color(Color::Success))
+                .child(Label::new(format!(
+                    "The endpoint at {api_url} doesn't need an API key."
+                )))
+                .into_any()
+        } else {
+            h_flex()
+                .size_full()
+                .justify_between()
+                .child(
+                    h_flex()
+                        .gap_1()
+                        .child(Icon::new(IconName::Check).color(Color::Success))
+                        .child(Label::new(format!("API key configured for {api_url}."))),
+                )
+                .child(
+                    Button::new("reset-key", "Reset key")
+                        .icon(Some(IconName::Trash))
+                        .icon_size(IconSize::Small)
+                        .icon_position(IconPosition::Start)
+                        .on_click(cx.listener(|this, _, cx| this.reset_api_key(cx))),
+                )
+                .into_any()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use open_ai::{ChoiceDelta, FunctionChunk, ResponseMessageDelta, ToolCallChunk};
+
+    fn event(
+        content: Option<&str>,
+        tool_calls: Vec<ToolCallChunk>,
+        finish_reason: Option<&str>,
+    ) -> Result<ResponseStreamEvent> {
+        Ok(ResponseStreamEvent {
+            created: 0,
+            model: "model".into(),
+            choices: vec![ChoiceDelta {
+                index: 0,
+                delta: ResponseMessageDelta {
+                    role: None,
+                    content: content.map(Into::into),
+                    tool_calls: Some(tool_calls),
+                },
+                finish_reason: finish_reason.map(Into::into),
+            }],
+            usage: None,
+        })
+    }
+
+    fn tool_call_chunk(
+        index: usize,
+        id: Option<&str>,
+        name: Option<&str>,
+        arguments: &str,
+    ) -> ToolCallChunk {
+        ToolCallChunk {
+            index,
+            id: id.map(Into::into),
+            function: Some(FunctionChunk {
+                name: name.map(Into::into),
+                arguments: Some(arguments.into()),
+            }),
+        }
+    }
+
+    #[gpui::test]
+    async fn test_map_to_completion_events() {
+        let events = futures::stream::iter([
+            event(Some("Let me check."), Vec::new(), None),
+            event(
+                None,
+                vec![
+                    tool_call_chunk(0, Some("call-1"), Some("read_file"), "{\"path\":"),
+                    tool_call_chunk(1, Some("call-2"), Some("diagnostics"), ""),
+                ],
+                None,
+            ),
+            event(
+                None,
+                vec![tool_call_chunk(0, None, None, " \"src/main.rs\"}")],
+                Some("tool_calls"),
+            ),
+        ]);
+
+        let completion_events = map_to_completion_events(events)
+            .map(|event| event.unwrap())
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            completion_events,
+            [
+                LanguageModelCompletionEvent::Text("Let me check.".into()),
+                LanguageModelCompletionEvent::ToolUse(LanguageModelToolUse {
+                    id: "call-1".into(),
+                    name: "read_file".into(),
+                    input: serde_json::json!({ "path": "src/main.rs" }),
+                }),
+                LanguageModelCompletionEvent::ToolUse(LanguageModelToolUse {
+                    id: "call-2".into(),
+                    name: "diagnostics".into(),
+                    input: serde_json::json!({}),
+                }),
+                LanguageModelCompletionEvent::Stop(StopReason::ToolUse),
+            ]
+        );
+    }
+}
@@ -1,6 +1,7 @@
 use std::sync::Arc;
 
 use anyhow::Result;
+use collections::BTreeMap;
 use gpui::AppContext;
 use language_model::LanguageModelCacheConfiguration;
 use project::Fs;
@@ -16,6 +17,7 @@ use crate::provider::{
     google::GoogleSettings,
     ollama::OllamaSettings,
     open_ai::OpenAiSettings,
+    open_ai_compatible::OpenAiCompatibleSettings,
 };
 
 /// Initializes the language model settings.
@@ -56,6 +58,7 @@ pub struct AllLanguageModelSettings {
     pub anthropic: AnthropicSettings,
     pub ollama: OllamaSettings,
     pub openai: OpenAiSettings,
+    pub openai_compatible: BTreeMap<Arc<str>, OpenAiCompatibleSettings>,
     pub zed_dot_dev: ZedDotDevSettings,
     pub google: GoogleSettings,
     pub copilot_chat: CopilotChatSettings,
@@ -66,6 +69,8 @@ pub struct AllLanguageModelSettingsContent {
     pub anthropic: Option<AnthropicSettingsContent>,
     pub ollama: Option<OllamaSettingsContent>,
     pub openai: Option<OpenAiSettingsContent>,
+    /// OpenAI-compatible endpoints, by the name they are listed under in Zed.
+    pub openai_compatible: Option<BTreeMap<String, OpenAiCompatibleSettingsContent>>,
     #[serde(rename = "zed.dev")]
     pub zed_dot_dev: Option<ZedDotDevSettingsContent>,
     pub google: Option<GoogleSettingsContent>,
@@ -214,6 +219,25 @@ pub struct OpenAiSettingsContentV1 {
     pub available_models: Option<Vec<provider::open_ai::AvailableModel>>,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
+pub struct OpenAiCompatibleSettingsContent {
+    /// The URL of the API, including its version, e.g. "http://localhost:8000/v1".
+    pub api_url: String,
+    /// Headers to send along with each request, e.g. to route it through a gateway.
+    pub headers: Option<BTreeMap<String, String>>,
+    /// Models to offer in addition to the discovered ones, or to override their settings.
+    pub available_models: Option<Vec<provider::open_ai_compatible::AvailableModel>>,
+    /// Whether to list the models served by the endpoint from its `/models` route.
+    ///
+    /// Default: true
+    pub discover_models: Option<bool>,
+    /// Whether the endpoint needs an API key. Requests to endpoints that don't are sent without an
+    /// `Authorization` header.
+    ///
+    /// Default: true
+    pub requires_api_key: Option<bool>,
+}
+
 #[derive(Default, Clone, Debug, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct GoogleSettingsContent {
     pub api_url: Option<String>,
@@ -294,6 +318,19 @@ impl settings::Settings for AllLanguageModelSettings {
                 &mut settings.openai.available_models,
                 openai.as_ref().and_then(|s| s.available_models.clone()),
             );
+            for (name, endpoint) in value.openai_compatible.iter().flatten() {
+                settings.openai_compatible.insert(
+                    name.as_str().into(),
+                    OpenAiCompatibleSettings {
+                        api_url: endpoint.api_url.clone(),
+                        headers: endpoint.headers.clone().unwrap_or_default(),
+                        available_models: endpoint.available_models.clone().unwrap_or_default(),
+                        discover_models: endpoint.discover_models.unwrap_or(true),
+                        requires_api_key: endpoint.requires_api_key.unwrap_or(true),
+                    },
+                );
+            }
+
             merge(
                 &mut settings.zed_dot_dev.available_models,
                 value
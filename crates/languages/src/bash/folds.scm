@@ -0,0 +1,9 @@
+(compound_statement) @fold
+(do_group) @fold
+(if_statement) @fold
+(case_statement) @fold
+(heredoc_body) @fold
+
+(comment) @fold.consecutive
+
+(comment) @fold.region
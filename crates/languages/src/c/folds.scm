@@ -0,0 +1,12 @@
+(compound_statement) @fold
+(field_declaration_list) @fold
+(enumerator_list) @fold
+(initializer_list) @fold
+(argument_list) @fold
+(parameter_list) @fold
+(comment) @fold
+
+(preproc_include) @fold.consecutive
+(comment) @fold.consecutive
+
+(comment) @fold.region
@@ -0,0 +1,13 @@
+(compound_statement) @fold
+(declaration_list) @fold
+(field_declaration_list) @fold
+(enumerator_list) @fold
+(initializer_list) @fold
+(argument_list) @fold
+(parameter_list) @fold
+(comment) @fold
+
+(preproc_include) @fold.consecutive
+(comment) @fold.consecutive
+
+(comment) @fold.region
@@ -0,0 +1,7 @@
+(block) @fold
+(keyframe_block_list) @fold
+(comment) @fold
+
+(import_statement) @fold.consecutive
+
+(comment) @fold.region
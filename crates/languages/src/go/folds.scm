@@ -0,0 +1,14 @@
+(block) @fold
+(field_declaration_list) @fold
+(interface_type) @fold
+(literal_value) @fold
+(argument_list) @fold
+(parameter_list) @fold
+(import_spec_list) @fold
+(const_declaration) @fold
+(var_declaration) @fold
+(comment) @fold
+
+(comment) @fold.consecutive
+
+(comment) @fold.region
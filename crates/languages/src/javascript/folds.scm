@@ -0,0 +1,16 @@
+(statement_block) @fold
+(class_body) @fold
+(switch_body) @fold
+(object) @fold
+(array) @fold
+(arguments) @fold
+(formal_parameters) @fold
+(named_imports) @fold
+(template_string) @fold
+(comment) @fold
+(jsx_element) @fold
+
+(import_statement) @fold.consecutive
+(comment) @fold.consecutive
+
+(comment) @fold.region
@@ -0,0 +1,2 @@
+(object) @fold
+(array) @fold
@@ -0,0 +1,4 @@
+(object) @fold
+(array) @fold
+
+(comment) @fold.region
@@ -0,0 +1,19 @@
+(function_definition) @fold
+(class_definition) @fold
+(if_statement) @fold
+(for_statement) @fold
+(while_statement) @fold
+(with_statement) @fold
+(try_statement) @fold
+(dictionary) @fold
+(list) @fold
+(tuple) @fold
+(argument_list) @fold
+(parameters) @fold
+(string) @fold
+
+(import_statement) @fold.consecutive
+(import_from_statement) @fold.consecutive
+(comment) @fold.consecutive
+
+(comment) @fold.region
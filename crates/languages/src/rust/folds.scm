@@ -0,0 +1,17 @@
+(block) @fold
+(declaration_list) @fold
+(field_declaration_list) @fold
+(enum_variant_list) @fold
+(field_initializer_list) @fold
+(match_block) @fold
+(arguments) @fold
+(parameters) @fold
+(array_expression) @fold
+(token_tree) @fold
+(use_list) @fold
+(block_comment) @fold
+
+(use_declaration) @fold.consecutive
+(line_comment) @fold.consecutive
+
+(line_comment) @fold.region
@@ -219,7 +219,7 @@ pub enum RequestMessage {
         tool_calls: Vec<ToolCall>,
     },
     User {
-        content: String,
+        content: MessageContent,
     },
     System {
         content: String,
@@ -230,6 +230,50 @@ pub enum RequestMessage {
     },
 }
 
+/// The content of a user message, which is either plain text or, for models with vision, a list
+/// of text and image parts.
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum MessageContent {
+    Plain(String),
+    Multipart(Vec<MessagePart>),
+}
+
+impl MessageContent {
+    /// Returns the text of the content, leaving out any images.
+    pub fn text(&self) -> String {
+        match self {
+            MessageContent::Plain(text) => text.clone(),
+            MessageContent::Multipart(parts) => parts
+                .iter()
+                .filter_map(|part| match part {
+                    MessagePart::Text { text } => Some(text.as_str()),
+                    MessagePart::ImageUrl { .. } => None,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<String> for MessageContent {
+    fn from(text: String) -> Self {
+        MessageContent::Plain(text)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MessagePart {
+    Text { text: String },
+    ImageUrl { image_url: ImageUrl },
+}
+
+#[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
+pub struct ImageUrl {
+    /// The URL of the image, or its contents as a `data:` URL.
+    pub url: String,
+}
+
 #[derive(Serialize, Deserialize, Debug, Eq, PartialEq)]
 pub struct ToolCall {
     pub id: String,
@@ -456,7 +500,7 @@ fn adapt_response_to_stream(response: Response) -> ResponseStreamEvent {
                     }),
                     content: match choice.message {
                         RequestMessage::Assistant { content, .. } => content,
-                        RequestMessage::User { content } => Some(content),
+                        RequestMessage::User { content } => Some(content.text()),
                         RequestMessage::System { content } => Some(content),
                         RequestMessage::Tool { content, .. } => Some(content),
                     },
@@ -481,12 +525,29 @@ pub async fn stream_completion(
         return Ok(stream::once(future::ready(response_stream_event)).boxed());
     }
 
+    stream_completion_with_headers(client, api_url, Some(api_key), &[], request).await
+}
+
+/// Streams a chat completion from an OpenAI-compatible API, sending the given headers along with
+/// the request. No `Authorization` header is sent without an API key.
+pub async fn stream_completion_with_headers(
+    client: &dyn HttpClient,
+    api_url: &str,
+    api_key: Option<&str>,
+    headers: &[(String, String)],
+    request: Request,
+) -> Result<BoxStream<'static, Result<ResponseStreamEvent>>> {
     let uri = format!("{api_url}/chat/completions");
-    let request_builder = HttpRequest::builder()
+    let mut request_builder = HttpRequest::builder()
         .method(Method::POST)
         .uri(uri)
-        .header("Content-Type", "application/json")
-        .header("Authorization", format!("Bearer {}", api_key));
+        .header("Content-Type", "application/json");
+    if let Some(api_key) = api_key {
+        request_builder = request_builder.header("Authorization", format!("Bearer {}", api_key));
+    }
+    for (name, value) in headers {
+        request_builder = request_builder.header(name.as_str(), value.as_str());
+    }
 
     let request = request_builder.body(AsyncBody::from(serde_json::to_string(&request)?))?;
     let mut response = client.send(request).await?;
@@ -543,6 +604,58 @@ pub async fn stream_completion(
     }
 }
 
+#[derive(Deserialize)]
+struct ListModelsResponse {
+    data: Vec<ListedModel>,
+}
+
+/// A model returned by the `/models` endpoint of an OpenAI-compatible API.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ListedModel {
+    pub id: String,
+    /// The context length of the model, which vLLM reports but OpenAI does not.
+    #[serde(default)]
+    pub max_model_len: Option<usize>,
+}
+
+/// Lists the models served by an OpenAI-compatible API. No `Authorization` header is sent without
+/// an API key.
+pub async fn list_models(
+    client: &dyn HttpClient,
+    api_url: &str,
+    api_key: Option<&str>,
+    headers: &[(String, String)],
+) -> Result<Vec<ListedModel>> {
+    let uri = format!("{api_url}/models");
+    let mut request_builder = HttpRequest::builder()
+        .method(Method::GET)
+        .uri(uri)
+        .header("Accept", "application/json");
+    if let Some(api_key) = api_key {
+        request_builder = request_builder.header("Authorization", format!("Bearer {}", api_key));
+    }
+    for (name, value) in headers {
+        request_builder = request_builder.header(name.as_str(), value.as_str());
+    }
+
+    let request = request_builder.body(AsyncBody::default())?;
+    let mut response = client.send(request).await?;
+    let mut body = String::new();
+    response.body_mut().read_to_string(&mut body).await?;
+
+    if response.status().is_success() {
+        let response: ListModelsResponse =
+            serde_json::from_str(&body).context("failed to parse the list of models")?;
+        Ok(response.data)
+    } else {
+        Err(anyhow!(
+            "Failed to list models: {} {}",
+            response.status(),
+            body,
+        ))
+    }
+}
+
 #[derive(Copy, Clone, Serialize, Deserialize)]
 pub enum OpenAiEmbeddingModel {
     #[serde(rename = "text-embedding-3-small")]
@@ -203,6 +203,14 @@ pub fn contexts_dir() -> &'static PathBuf {
     })
 }
 
+/// Returns the path to the threads directory.
+///
+/// This is where the threads of the Assistant Panel 2 are stored, one JSON file per thread.
+pub fn threads_dir() -> &'static PathBuf {
+    static THREADS_DIR: OnceLock<PathBuf> = OnceLock::new();
+    THREADS_DIR.get_or_init(|| support_dir().join("threads"))
+}
+
 /// Returns the path to the contexts directory.
 ///
 /// This is where the prompts for use with the Assistant are stored.
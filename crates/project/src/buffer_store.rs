@@ -56,9 +56,14 @@ pub struct BufferChangeSet {
     pub buffer_id: BufferId,
     pub base_text: Option<Model<Buffer>>,
     pub diff_to_buffer: git::diff::BufferDiff,
+    /// The contents of the file at `HEAD`, used to tell staged changes apart.
+    pub committed_text: Option<Arc<String>>,
+    pub diff_to_committed: git::diff::BufferDiff,
     pub recalculate_diff_task: Option<Task<Result<()>>>,
     pub diff_updated_futures: Vec<oneshot::Sender<()>>,
     pub base_text_version: usize,
+    /// The git revision the base text was loaded from, or `None` when it is the index text.
+    pub base_revision: Option<String>,
 }
 
 enum BufferStoreState {
@@ -87,6 +92,8 @@ enum OpenBuffer {
     Complete {
         buffer: WeakModel<Buffer>,
         unstaged_changes: Option<WeakModel<BufferChangeSet>>,
+        /// Change sets comparing the buffer with a git revision, kept up to date as it is edited.
+        revision_changes: Vec<WeakModel<BufferChangeSet>>,
     },
     Operations(Vec<Operation>),
 }
@@ -100,27 +107,32 @@ pub enum BufferStoreEvent {
     },
 }
 
+/// The texts a buffer is diffed against: its contents in the index and at `HEAD`.
+struct DiffBases {
+    staged_text: Option<String>,
+    committed_text: Option<String>,
+}
+
 #[derive(Default, Debug)]
 pub struct ProjectTransaction(pub HashMap<Model<Buffer>, language::Transaction>);
 
 impl EventEmitter<BufferStoreEvent> for BufferStore {}
 
 impl RemoteBufferStore {
-    fn load_staged_text(
-        &self,
-        buffer_id: BufferId,
-        cx: &AppContext,
-    ) -> Task<Result<Option<String>>> {
+    fn load_diff_bases(&self, buffer_id: BufferId, cx: &AppContext) -> Task<Result<DiffBases>> {
         let project_id = self.project_id;
         let client = self.upstream_client.clone();
         cx.background_executor().spawn(async move {
-            Ok(client
+            let response = client
                 .request(proto::GetStagedText {
                     project_id,
                     buffer_id: buffer_id.to_proto(),
                 })
-                .await?
-                .staged_text)
+                .await?;
+            Ok(DiffBases {
+                staged_text: response.staged_text,
+                committed_text: response.committed_text,
+            })
         })
     }
     pub fn wait_for_remote_buffer(
@@ -390,11 +402,7 @@ impl RemoteBufferStore {
 }
 
 impl LocalBufferStore {
-    fn load_staged_text(
-        &self,
-        buffer: &Model<Buffer>,
-        cx: &AppContext,
-    ) -> Task<Result<Option<String>>> {
+    fn load_diff_bases(&self, buffer: &Model<Buffer>, cx: &AppContext) -> Task<Result<DiffBases>> {
         let Some(file) = buffer.read(cx).file() else {
             return Task::ready(Err(anyhow!("buffer has no file")));
         };
@@ -408,7 +416,14 @@ impl LocalBufferStore {
             return Task::ready(Err(anyhow!("no such worktree")));
         };
 
-        worktree.read(cx).load_staged_file(path.as_ref(), cx)
+        let staged_text = worktree.read(cx).load_staged_file(path.as_ref(), cx);
+        let committed_text = worktree.read(cx).load_committed_file(path.as_ref(), cx);
+        cx.background_executor().spawn(async move {
+            Ok(DiffBases {
+                staged_text: staged_text.await?,
+                committed_text: committed_text.await.log_err().flatten(),
+            })
+        })
     }
 
     fn save_local_buffer(
@@ -563,16 +578,25 @@ impl LocalBufferStore {
                         .filter_map(|(change_set, buffer_snapshot, path)| {
                             let (repo_entry, local_repo_entry) = snapshot.repo_for_path(&path)?;
                             let relative_path = repo_entry.relativize(&snapshot, &path).ok()?;
-                            let base_text = local_repo_entry.repo().load_index_text(&relative_path);
-                            Some((change_set, buffer_snapshot, base_text))
+                            let repo = local_repo_entry.repo();
+                            let diff_bases = DiffBases {
+                                staged_text: repo.load_index_text(&relative_path),
+                                committed_text: repo.load_committed_text(&relative_path),
+                            };
+                            Some((change_set, buffer_snapshot, diff_bases))
                         })
                         .collect::<Vec<_>>()
                 })
                 .await;
 
             this.update(&mut cx, |this, cx| {
-                for (change_set, buffer_snapshot, staged_text) in diff_bases_by_buffer {
+                for (change_set, buffer_snapshot, diff_bases) in diff_bases_by_buffer {
+                    let DiffBases {
+                        staged_text,
+                        committed_text,
+                    } = diff_bases;
                     change_set.update(cx, |change_set, cx| {
+                        change_set.set_committed_text(committed_text.clone(), &buffer_snapshot, cx);
                         if let Some(staged_text) = staged_text.clone() {
                             let _ =
                                 change_set.set_base_text(staged_text, buffer_snapshot.clone(), cx);
@@ -587,6 +611,7 @@ impl LocalBufferStore {
                                 project_id: *project_id,
                                 buffer_id: buffer_snapshot.remote_id().to_proto(),
                                 staged_text,
+                                committed_text,
                             })
                             .log_err();
                     }
@@ -896,6 +921,7 @@ impl BufferStore {
         client.add_model_request_handler(Self::handle_reload_buffers);
         client.add_model_request_handler(Self::handle_get_permalink_to_line);
         client.add_model_request_handler(Self::handle_get_staged_text);
+        client.add_model_request_handler(Self::handle_set_index_text);
         client.add_model_message_handler(Self::handle_update_diff_base);
     }
 
@@ -1027,8 +1053,8 @@ impl BufferStore {
             hash_map::Entry::Occupied(e) => e.get().clone(),
             hash_map::Entry::Vacant(entry) => {
                 let load = match &self.state {
-                    BufferStoreState::Local(this) => this.load_staged_text(&buffer, cx),
-                    BufferStoreState::Remote(this) => this.load_staged_text(buffer_id, cx),
+                    BufferStoreState::Local(this) => this.load_diff_bases(&buffer, cx),
+                    BufferStoreState::Remote(this) => this.load_diff_bases(buffer_id, cx),
                 };
 
                 entry
@@ -1048,19 +1074,66 @@ impl BufferStore {
             .spawn(async move { task.await.map_err(|e| anyhow!("{e}")) })
     }
 
+    /// Opens a change set comparing the buffer with the contents of its file at the given git
+    /// revision. Unlike the unstaged changes, a new change set is created on each call, and its
+    /// base text is not reloaded when the repository changes.
+    pub fn open_changes_since_revision(
+        &mut self,
+        buffer: Model<Buffer>,
+        revision: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Model<BufferChangeSet>>> {
+        let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
+            return Task::ready(Err(anyhow!("buffer has no file")));
+        };
+        let project_path = ProjectPath {
+            worktree_id: file.worktree_id(cx),
+            path: file.path.clone(),
+        };
+        let load =
+            self.worktree_store
+                .read(cx)
+                .load_revision_text(project_path, revision.clone(), cx);
+
+        cx.spawn(|this, mut cx| async move {
+            // A file that doesn't exist at the revision is shown as entirely added.
+            let text = load.await?.unwrap_or_default();
+            let change_set = buffer.update(&mut cx, |buffer, cx| {
+                cx.new_model(|_| BufferChangeSet::new(buffer))
+            })?;
+            change_set
+                .update(&mut cx, |change_set, cx| {
+                    change_set.base_revision = Some(revision);
+                    change_set.set_base_text(text, buffer.read(cx).text_snapshot(), cx)
+                })?
+                .await
+                .ok();
+
+            this.update(&mut cx, |this, cx| {
+                if let Some(OpenBuffer::Complete {
+                    revision_changes, ..
+                }) = this.opened_buffers.get_mut(&buffer.read(cx).remote_id())
+                {
+                    revision_changes.push(change_set.downgrade());
+                }
+            })?;
+            Ok(change_set)
+        })
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn set_change_set(&mut self, buffer_id: BufferId, change_set: Model<BufferChangeSet>) {
         self.loading_change_sets
             .insert(buffer_id, Task::ready(Ok(change_set)).shared());
     }
 
-    pub async fn open_unstaged_changes_internal(
+    async fn open_unstaged_changes_internal(
         this: WeakModel<Self>,
-        text: Result<Option<String>>,
+        diff_bases: Result<DiffBases>,
         buffer: Model<Buffer>,
         mut cx: AsyncAppContext,
     ) -> Result<Model<BufferChangeSet>> {
-        let text = match text {
+        let diff_bases = match diff_bases {
             Err(e) => {
                 this.update(&mut cx, |this, cx| {
                     let buffer_id = buffer.read(cx).remote_id();
@@ -1068,17 +1141,18 @@ impl BufferStore {
                 })?;
                 return Err(e);
             }
-            Ok(text) => text,
+            Ok(diff_bases) => diff_bases,
         };
 
         let change_set = buffer.update(&mut cx, |buffer, cx| {
             cx.new_model(|_| BufferChangeSet::new(buffer))
         })?;
 
-        if let Some(text) = text {
+        if let Some(text) = diff_bases.staged_text {
             change_set
                 .update(&mut cx, |change_set, cx| {
                     let snapshot = buffer.read(cx).text_snapshot();
+                    change_set.set_committed_text(diff_bases.committed_text, &snapshot, cx);
                     change_set.set_base_text(text, snapshot, cx)
                 })?
                 .await
@@ -1200,6 +1274,68 @@ impl BufferStore {
         }
     }
 
+    /// Replaces the staged contents of the buffer's file, updating its unstaged changes right away
+    /// rather than waiting for the repository to be rescanned.
+    pub fn set_index_text(
+        &self,
+        buffer: &Model<Buffer>,
+        text: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let buffer_snapshot = buffer.read(cx).text_snapshot();
+        let Some(file) = File::from_dyn(buffer.read(cx).file()) else {
+            return Task::ready(Err(anyhow!("buffer has no file")));
+        };
+
+        let write = match file.worktree.read(cx) {
+            Worktree::Local(worktree) => {
+                let worktree = worktree.snapshot();
+                let Some((repo_entry, local_repo_entry)) = worktree.repo_for_path(&file.path)
+                else {
+                    return Task::ready(Err(anyhow!("buffer is not in a git repository")));
+                };
+                let relative_path = match repo_entry.relativize(&worktree, &file.path) {
+                    Ok(relative_path) => relative_path,
+                    Err(error) => return Task::ready(Err(error)),
+                };
+                let repo = local_repo_entry.repo().clone();
+                let text = text.clone();
+                cx.background_executor().spawn(async move {
+                    repo.set_index_text(&relative_path, text)
+                        .with_context(|| format!("Failed to stage {:?}", relative_path.0))
+                })
+            }
+            Worktree::Remote(worktree) => {
+                let request = worktree.client().request(proto::SetIndexText {
+                    project_id: worktree.project_id(),
+                    buffer_id: buffer_snapshot.remote_id().to_proto(),
+                    text: text.clone(),
+                });
+                cx.background_executor().spawn(async move {
+                    request.await?;
+                    Ok(())
+                })
+            }
+        };
+
+        cx.spawn(|this, mut cx| async move {
+            write.await?;
+            this.update(&mut cx, |this, cx| {
+                let Some(change_set) = this.get_unstaged_changes(buffer_snapshot.remote_id())
+                else {
+                    return;
+                };
+                change_set.update(cx, |change_set, cx| {
+                    if let Some(text) = text {
+                        let _ = change_set.set_base_text(text, buffer_snapshot, cx);
+                    } else {
+                        change_set.unset_base_text(buffer_snapshot, cx);
+                    }
+                });
+            })
+        })
+    }
+
     pub fn get_permalink_to_line(
         &self,
         buffer: &Model<Buffer>,
@@ -1278,6 +1414,7 @@ impl BufferStore {
         let open_buffer = OpenBuffer::Complete {
             buffer: buffer.downgrade(),
             unstaged_changes: None,
+            revision_changes: Vec::new(),
         };
 
         let handle = cx.handle().downgrade();
@@ -1492,7 +1629,9 @@ impl BufferStore {
         for buffer in buffers {
             let buffer = buffer.read(cx).text_snapshot();
             if let Some(OpenBuffer::Complete {
-                unstaged_changes, ..
+                unstaged_changes,
+                revision_changes,
+                ..
             }) = self.opened_buffers.get_mut(&buffer.remote_id())
             {
                 if let Some(unstaged_changes) = unstaged_changes
@@ -1505,6 +1644,16 @@ impl BufferStore {
                 } else {
                     unstaged_changes.take();
                 }
+
+                revision_changes.retain(|changes| {
+                    let Some(changes) = changes.upgrade() else {
+                        return false;
+                    };
+                    changes.update(cx, |changes, cx| {
+                        futures.push(changes.recalculate_diff(buffer.clone(), cx));
+                    });
+                    true
+                });
             }
         }
         async move {
@@ -1941,13 +2090,30 @@ impl BufferStore {
                 shared.unstaged_changes = Some(change_set.clone());
             }
         })?;
-        let staged_text = change_set.read_with(&cx, |change_set, cx| {
-            change_set
+        change_set.read_with(&cx, |change_set, cx| proto::GetStagedTextResponse {
+            staged_text: change_set
                 .base_text
                 .as_ref()
-                .map(|buffer| buffer.read(cx).text())
-        })?;
-        Ok(proto::GetStagedTextResponse { staged_text })
+                .map(|buffer| buffer.read(cx).text()),
+            committed_text: change_set
+                .committed_text
+                .as_ref()
+                .map(|text| text.to_string()),
+        })
+    }
+
+    pub async fn handle_set_index_text(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::SetIndexText>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        this.update(&mut cx, |this, cx| {
+            let buffer = this.get_existing(buffer_id)?;
+            anyhow::Ok(this.set_index_text(&buffer, envelope.payload.text, cx))
+        })??
+        .await?;
+        Ok(proto::Ack {})
     }
 
     pub async fn handle_update_diff_base(
@@ -1971,10 +2137,12 @@ impl BufferStore {
             return Ok(());
         };
         change_set.update(&mut cx, |change_set, cx| {
+            let buffer_snapshot = buffer.read(cx).text_snapshot();
+            change_set.set_committed_text(request.payload.committed_text, &buffer_snapshot, cx);
             if let Some(staged_text) = request.payload.staged_text {
-                let _ = change_set.set_base_text(staged_text, buffer.read(cx).text_snapshot(), cx);
+                let _ = change_set.set_base_text(staged_text, buffer_snapshot, cx);
             } else {
-                change_set.unset_base_text(buffer.read(cx).text_snapshot(), cx)
+                change_set.unset_base_text(buffer_snapshot, cx)
             }
         })?;
         Ok(())
@@ -2195,9 +2363,12 @@ impl BufferChangeSet {
             buffer_id: buffer.remote_id(),
             base_text: None,
             diff_to_buffer: git::diff::BufferDiff::new(buffer),
+            committed_text: None,
+            diff_to_committed: git::diff::BufferDiff::new(buffer),
             recalculate_diff_task: None,
             diff_updated_futures: Vec::new(),
             base_text_version: 0,
+            base_revision: None,
         }
     }
 
@@ -2230,6 +2401,17 @@ impl BufferChangeSet {
             .hunks_intersecting_range_rev(range, buffer_snapshot)
     }
 
+    /// Returns the hunks of the diff between `HEAD` and the buffer whose changes are entirely
+    /// staged, that is, that don't overlap any hunk of the diff between the index and the buffer.
+    pub fn staged_hunks_intersecting_range<'a>(
+        &'a self,
+        range: Range<text::Anchor>,
+        buffer_snapshot: &'a text::BufferSnapshot,
+    ) -> impl 'a + Iterator<Item = git::diff::DiffHunk> {
+        self.diff_to_committed
+            .hunks_outside_of(&self.diff_to_buffer, range, buffer_snapshot)
+    }
+
     #[cfg(any(test, feature = "test-support"))]
     pub fn base_text_string(&self, cx: &AppContext) -> Option<String> {
         self.base_text.as_ref().map(|buffer| buffer.read(cx).text())
@@ -2241,8 +2423,11 @@ impl BufferChangeSet {
         buffer_snapshot: text::BufferSnapshot,
         cx: &mut ModelContext<Self>,
     ) -> oneshot::Receiver<()> {
+        // Keep the line ending of the base text, for the hunks staged into it to be written back
+        // with it.
+        let line_ending = LineEnding::detect(&base_text);
         LineEnding::normalize(&mut base_text);
-        self.recalculate_diff_internal(base_text, buffer_snapshot, true, cx)
+        self.recalculate_diff_internal(base_text, line_ending, buffer_snapshot, true, cx)
     }
 
     pub fn unset_base_text(
@@ -2253,19 +2438,45 @@ impl BufferChangeSet {
         if self.base_text.is_some() {
             self.base_text = None;
             self.diff_to_buffer = BufferDiff::new(&buffer_snapshot);
+            self.diff_to_committed = BufferDiff::new(&buffer_snapshot);
             self.recalculate_diff_task.take();
             self.base_text_version += 1;
             cx.notify();
         }
     }
 
+    /// Sets the contents of the file at `HEAD`. Like the index text, the diff against it is
+    /// computed at the next recalculation, so this must be called before [`Self::set_base_text`].
+    pub fn set_committed_text(
+        &mut self,
+        committed_text: Option<String>,
+        buffer_snapshot: &text::BufferSnapshot,
+        cx: &mut ModelContext<Self>,
+    ) {
+        self.committed_text = committed_text.map(|mut committed_text| {
+            LineEnding::normalize(&mut committed_text);
+            Arc::new(committed_text)
+        });
+        if self.committed_text.is_none() && !self.diff_to_committed.is_empty() {
+            self.diff_to_committed = BufferDiff::new(buffer_snapshot);
+            cx.notify();
+        }
+    }
+
     pub fn recalculate_diff(
         &mut self,
         buffer_snapshot: text::BufferSnapshot,
         cx: &mut ModelContext<Self>,
     ) -> oneshot::Receiver<()> {
         if let Some(base_text) = self.base_text.clone() {
-            self.recalculate_diff_internal(base_text.read(cx).text(), buffer_snapshot, false, cx)
+            let base_text = base_text.read(cx);
+            self.recalculate_diff_internal(
+                base_text.text(),
+                base_text.line_ending(),
+                buffer_snapshot,
+                false,
+                cx,
+            )
         } else {
             oneshot::channel().1
         }
@@ -2274,28 +2485,37 @@ impl BufferChangeSet {
     fn recalculate_diff_internal(
         &mut self,
         base_text: String,
+        line_ending: LineEnding,
         buffer_snapshot: text::BufferSnapshot,
         base_text_changed: bool,
         cx: &mut ModelContext<Self>,
     ) -> oneshot::Receiver<()> {
         let (tx, rx) = oneshot::channel();
         self.diff_updated_futures.push(tx);
+        let committed_text = self.committed_text.clone();
         self.recalculate_diff_task = Some(cx.spawn(|this, mut cx| async move {
-            let (base_text, diff) = cx
+            let (base_text, diff, committed_diff) = cx
                 .background_executor()
                 .spawn(async move {
                     let diff = BufferDiff::build(&base_text, &buffer_snapshot).await;
-                    (base_text, diff)
+                    let committed_diff = match committed_text {
+                        Some(committed_text) => {
+                            BufferDiff::build(&committed_text, &buffer_snapshot).await
+                        }
+                        None => BufferDiff::new(&buffer_snapshot),
+                    };
+                    (base_text, diff, committed_diff)
                 })
                 .await;
             this.update(&mut cx, |this, cx| {
                 if base_text_changed {
                     this.base_text_version += 1;
                     this.base_text = Some(cx.new_model(|cx| {
-                        Buffer::local_normalized(Rope::from(base_text), LineEnding::default(), cx)
+                        Buffer::local_normalized(Rope::from(base_text), line_ending, cx)
                     }));
                 }
                 this.diff_to_buffer = diff;
+                this.diff_to_committed = committed_diff;
                 this.recalculate_diff_task.take();
                 for tx in this.diff_updated_futures.drain(..) {
                     tx.send(()).ok();
@@ -0,0 +1,974 @@
+use crate::{
+    buffer_store::{BufferStore, BufferStoreEvent},
+    project_settings::ProjectSettings,
+    worktree_store::WorktreeStore,
+    ProjectPath,
+};
+use anyhow::{anyhow, Context as _, Result};
+use collections::{BTreeMap, HashMap};
+use dap::{
+    client::{DebugAdapterClient, DebugAdapterClientId},
+    events, requests, ContinueArguments, DebugAdapterBinary, DebugAdapterConfig, DebugAdapterName,
+    DebugRequestType, NextArguments, PauseArguments, SetBreakpointsArguments,
+    SetExceptionBreakpointsArguments, Source, SourceBreakpoint, StackFrame, StackTraceArguments,
+    StepInArguments, StepOutArguments, StoppedEventReason, Thread,
+};
+use futures::future;
+use gpui::{AppContext, EventEmitter, Model, ModelContext, Task};
+use language::{Buffer, BufferSnapshot};
+use settings::Settings as _;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use text::{BufferId, Point};
+use util::ResultExt as _;
+
+/// The number of lines of adapter output kept per debug session.
+const MAX_OUTPUT_LINES: usize = 1000;
+
+pub enum DapStoreEvent {
+    SessionStarted(DebugAdapterClientId),
+    SessionStopped(DebugAdapterClientId),
+    Stopped {
+        client_id: DebugAdapterClientId,
+        thread_id: Option<u64>,
+        reason: StoppedEventReason,
+    },
+    Continued(DebugAdapterClientId),
+    Output(DebugAdapterClientId),
+    ThreadsChanged(DebugAdapterClientId),
+    BreakpointsChanged(ProjectPath),
+    ActiveLocationChanged,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DebugSessionStatus {
+    Starting,
+    Running,
+    Stopped { thread_id: Option<u64> },
+    Exited,
+}
+
+/// A line of a file where execution is currently paused, as reported by a debug adapter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DebugLocation {
+    pub client_id: DebugAdapterClientId,
+    pub path: ProjectPath,
+    pub row: u32,
+}
+
+pub struct DebugSession {
+    client: Arc<DebugAdapterClient>,
+    config: DebugAdapterConfig,
+    status: DebugSessionStatus,
+    threads: Vec<Thread>,
+    output: Vec<String>,
+    _subscriptions: Vec<dap::client::Subscription>,
+}
+
+impl DebugSession {
+    pub fn client(&self) -> &Arc<DebugAdapterClient> {
+        &self.client
+    }
+
+    pub fn config(&self) -> &DebugAdapterConfig {
+        &self.config
+    }
+
+    pub fn status(&self) -> DebugSessionStatus {
+        self.status
+    }
+
+    pub fn threads(&self) -> &[Thread] {
+        &self.threads
+    }
+
+    pub fn output(&self) -> &[String] {
+        &self.output
+    }
+}
+
+/// A breakpoint set by the user in a file of the project.
+///
+/// Breakpoints are anchored in the buffer while it is open, so that they move along with edits,
+/// and fall back to the last known row when the buffer is closed.
+#[derive(Debug, Clone)]
+pub struct Breakpoint {
+    pub position: text::Anchor,
+    cached_row: u32,
+}
+
+impl Breakpoint {
+    pub fn row(&self, snapshot: Option<&BufferSnapshot>) -> u32 {
+        match snapshot {
+            Some(snapshot) if self.position.buffer_id == Some(snapshot.remote_id()) => {
+                self.position.to_point(snapshot).row
+            }
+            _ => self.cached_row,
+        }
+    }
+}
+
+enum DapStoreMode {
+    Local,
+    Remote,
+}
+
+/// Manages the debug adapters running for a project, along with the breakpoints set in its files.
+pub struct DapStore {
+    mode: DapStoreMode,
+    worktree_store: Model<WorktreeStore>,
+    buffer_store: Model<BufferStore>,
+    next_client_id: usize,
+    sessions: BTreeMap<DebugAdapterClientId, DebugSession>,
+    breakpoints: BTreeMap<ProjectPath, Vec<Breakpoint>>,
+    active_location: Option<DebugLocation>,
+    buffer_subscriptions: HashMap<BufferId, gpui::Subscription>,
+    #[cfg(any(test, feature = "test-support"))]
+    fake_adapters: HashMap<
+        DebugAdapterName,
+        (
+            dap::Capabilities,
+            futures::channel::mpsc::UnboundedSender<dap::client::FakeDebugAdapter>,
+        ),
+    >,
+    _subscriptions: Vec<gpui::Subscription>,
+}
+
+impl EventEmitter<DapStoreEvent> for DapStore {}
+
+impl DapStore {
+    pub fn new_local(
+        worktree_store: Model<WorktreeStore>,
+        buffer_store: Model<BufferStore>,
+        cx: &mut ModelContext<Self>,
+    ) -> Self {
+        Self::new(DapStoreMode::Local, worktree_store, buffer_store, cx)
+    }
+
+    pub fn new_remote(
+        worktree_store: Model<WorktreeStore>,
+        buffer_store: Model<BufferStore>,
+        cx: &mut ModelContext<Self>,
+    ) -> Self {
+        Self::new(DapStoreMode::Remote, worktree_store, buffer_store, cx)
+    }
+
+    fn new(
+        mode: DapStoreMode,
+        worktree_store: Model<WorktreeStore>,
+        buffer_store: Model<BufferStore>,
+        cx: &mut ModelContext<Self>,
+    ) -> Self {
+        Self {
+            mode,
+            _subscriptions: vec![
+                cx.subscribe(&buffer_store, Self::on_buffer_store_event),
+                cx.on_app_quit(Self::shutdown_sessions),
+            ],
+            worktree_store,
+            buffer_store,
+            next_client_id: 0,
+            sessions: BTreeMap::default(),
+            breakpoints: BTreeMap::default(),
+            active_location: None,
+            buffer_subscriptions: HashMap::default(),
+            #[cfg(any(test, feature = "test-support"))]
+            fake_adapters: HashMap::default(),
+        }
+    }
+
+    pub fn is_local(&self) -> bool {
+        matches!(self.mode, DapStoreMode::Local)
+    }
+
+    pub fn sessions(&self) -> impl Iterator<Item = (DebugAdapterClientId, &DebugSession)> {
+        self.sessions.iter().map(|(id, session)| (*id, session))
+    }
+
+    pub fn session(&self, client_id: DebugAdapterClientId) -> Option<&DebugSession> {
+        self.sessions.get(&client_id)
+    }
+
+    /// The location execution is currently paused at, if any.
+    pub fn active_location(&self) -> Option<&DebugLocation> {
+        self.active_location.as_ref()
+    }
+
+    /// Highlights the given stack frame as the current execution location, e.g. when the
+    /// user selects a frame in the debugger panel.
+    pub fn set_active_stack_frame(
+        &mut self,
+        client_id: DebugAdapterClientId,
+        stack_frame: &StackFrame,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let location = self.location_for_stack_frame(client_id, stack_frame, cx);
+        if self.active_location != location {
+            self.active_location = location;
+            cx.emit(DapStoreEvent::ActiveLocationChanged);
+            cx.notify();
+        }
+    }
+
+    /// Resolves the source of a stack frame to a path in the project.
+    pub fn project_path_for_source(&self, source: &Source, cx: &AppContext) -> Option<ProjectPath> {
+        let abs_path = Path::new(source.path.as_ref()?);
+        let (worktree, relative_path) = self.worktree_store.read(cx).find_worktree(abs_path, cx)?;
+        Some(ProjectPath {
+            worktree_id: worktree.read(cx).id(),
+            path: relative_path.into(),
+        })
+    }
+
+    fn location_for_stack_frame(
+        &self,
+        client_id: DebugAdapterClientId,
+        stack_frame: &StackFrame,
+        cx: &AppContext,
+    ) -> Option<DebugLocation> {
+        let path = self.project_path_for_source(stack_frame.source.as_ref()?, cx)?;
+        Some(DebugLocation {
+            client_id,
+            path,
+            row: stack_frame.line.saturating_sub(1) as u32,
+        })
+    }
+
+    pub fn breakpoints(&self) -> &BTreeMap<ProjectPath, Vec<Breakpoint>> {
+        &self.breakpoints
+    }
+
+    /// Returns the rows of the breakpoints set in the given buffer.
+    pub fn breakpoint_rows(&self, snapshot: &BufferSnapshot, cx: &AppContext) -> Vec<u32> {
+        let Some(file) = snapshot.file() else {
+            return Vec::new();
+        };
+        let project_path = ProjectPath::from((file.worktree_id(cx), file.path().clone()));
+        self.breakpoints
+            .get(&project_path)
+            .map(|breakpoints| {
+                breakpoints
+                    .iter()
+                    .map(|breakpoint| breakpoint.row(Some(snapshot)))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Adds a breakpoint on the row containing `position`, or removes the breakpoints on that row.
+    pub fn toggle_breakpoint(
+        &mut self,
+        buffer: &Model<Buffer>,
+        position: text::Anchor,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(project_path) = project_path_for_buffer(buffer.read(cx), cx) else {
+            return;
+        };
+        let snapshot = buffer.read(cx).snapshot();
+        let row = position.to_point(&snapshot).row;
+
+        let breakpoints = self.breakpoints.entry(project_path.clone()).or_default();
+        let breakpoint_count = breakpoints.len();
+        breakpoints.retain(|breakpoint| breakpoint.row(Some(&snapshot)) != row);
+        if breakpoints.len() == breakpoint_count {
+            breakpoints.push(Breakpoint {
+                position: snapshot.anchor_before(Point::new(row, 0)),
+                cached_row: row,
+            });
+        }
+        if breakpoints.is_empty() {
+            self.breakpoints.remove(&project_path);
+        } else {
+            self.watch_buffer(buffer, cx);
+        }
+
+        self.send_breakpoints(&project_path, cx)
+            .detach_and_log_err(cx);
+        cx.emit(DapStoreEvent::BreakpointsChanged(project_path));
+        cx.notify();
+    }
+
+    fn on_buffer_store_event(
+        &mut self,
+        _: Model<BufferStore>,
+        event: &BufferStoreEvent,
+        cx: &mut ModelContext<Self>,
+    ) {
+        match event {
+            BufferStoreEvent::BufferAdded(buffer) => {
+                let Some(project_path) = project_path_for_buffer(buffer.read(cx), cx) else {
+                    return;
+                };
+                if let Some(breakpoints) = self.breakpoints.get_mut(&project_path) {
+                    let snapshot = buffer.read(cx).snapshot();
+                    for breakpoint in breakpoints {
+                        let row = breakpoint.cached_row.min(snapshot.max_point().row);
+                        breakpoint.position = snapshot.anchor_before(Point::new(row, 0));
+                    }
+                    self.watch_buffer(buffer, cx);
+                }
+            }
+            BufferStoreEvent::BufferDropped(buffer_id) => {
+                self.buffer_subscriptions.remove(buffer_id);
+            }
+            BufferStoreEvent::BufferChangedFilePath { buffer, old_file } => {
+                let Some(old_file) = old_file else {
+                    return;
+                };
+                let old_path =
+                    ProjectPath::from((old_file.worktree_id(cx), old_file.path().clone()));
+                let Some(breakpoints) = self.breakpoints.remove(&old_path) else {
+                    return;
+                };
+                if let Some(new_path) = project_path_for_buffer(buffer.read(cx), cx) {
+                    self.breakpoints.insert(new_path.clone(), breakpoints);
+                    cx.emit(DapStoreEvent::BreakpointsChanged(new_path));
+                }
+                cx.emit(DapStoreEvent::BreakpointsChanged(old_path));
+            }
+        }
+    }
+
+    /// Keeps the cached rows of the buffer's breakpoints up to date, so they survive the buffer
+    /// being closed and reopened.
+    fn watch_buffer(&mut self, buffer: &Model<Buffer>, cx: &mut ModelContext<Self>) {
+        let buffer_id = buffer.read(cx).remote_id();
+        if self.buffer_subscriptions.contains_key(&buffer_id) {
+            return;
+        }
+        let subscription = cx.subscribe(buffer, |this, buffer, event, cx| {
+            if let language::BufferEvent::Edited = event {
+                let buffer = buffer.read(cx);
+                let Some(project_path) = project_path_for_buffer(buffer, cx) else {
+                    return;
+                };
+                if let Some(breakpoints) = this.breakpoints.get_mut(&project_path) {
+                    let snapshot = buffer.snapshot();
+                    for breakpoint in breakpoints {
+                        breakpoint.cached_row = breakpoint.row(Some(&snapshot));
+                    }
+                }
+            }
+        });
+        self.buffer_subscriptions.insert(buffer_id, subscription);
+    }
+
+    /// Starts a debug adapter for the given configuration and launches (or attaches to) the debuggee.
+    pub fn start_session(
+        &mut self,
+        config: DebugAdapterConfig,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<DebugAdapterClientId>> {
+        if !self.is_local() {
+            return Task::ready(Err(anyhow!(
+                "debugging is only supported for local projects"
+            )));
+        }
+
+        let client_id = DebugAdapterClientId(self.next_client_id);
+        self.next_client_id += 1;
+
+        let working_dir = config.cwd.clone().or_else(|| {
+            self.worktree_store
+                .read(cx)
+                .visible_worktrees(cx)
+                .next()
+                .map(|worktree| worktree.read(cx).abs_path().to_path_buf())
+        });
+        let client = match self.create_client(client_id, &config, working_dir, cx) {
+            Ok(client) => Arc::new(client),
+            Err(error) => return Task::ready(Err(error)),
+        };
+
+        let subscriptions = self.subscribe_to_client(&client, cx);
+        self.sessions.insert(
+            client_id,
+            DebugSession {
+                client: client.clone(),
+                config: config.clone(),
+                status: DebugSessionStatus::Starting,
+                threads: Vec::new(),
+                output: Vec::new(),
+                _subscriptions: subscriptions,
+            },
+        );
+        cx.emit(DapStoreEvent::SessionStarted(client_id));
+        cx.notify();
+
+        cx.spawn(|this, mut cx| async move {
+            let result = async {
+                client.initialize().await?;
+                // Breakpoints are sent once the adapter reports it's `initialized`, which may only
+                // happen after the launch request has been received.
+                match config.request {
+                    DebugRequestType::Launch => {
+                        client
+                            .request::<requests::Launch>(dap::LaunchRequestArguments {
+                                raw: config.arguments.clone(),
+                            })
+                            .await
+                    }
+                    DebugRequestType::Attach => {
+                        client
+                            .request::<requests::Attach>(dap::AttachRequestArguments {
+                                raw: config.arguments.clone(),
+                            })
+                            .await
+                    }
+                }
+            }
+            .await;
+
+            match result {
+                Ok(()) => {
+                    this.update(&mut cx, |this, cx| {
+                        if let Some(session) = this.sessions.get_mut(&client_id) {
+                            if session.status == DebugSessionStatus::Starting {
+                                session.status = DebugSessionStatus::Running;
+                            }
+                        }
+                        cx.notify();
+                    })?;
+                    Ok(client_id)
+                }
+                Err(error) => {
+                    this.update(&mut cx, |this, cx| this.stop_session(client_id, cx))?
+                        .await;
+                    Err(error.context(format!("failed to start debug session {:?}", config.label)))
+                }
+            }
+        })
+    }
+
+    fn create_client(
+        &mut self,
+        client_id: DebugAdapterClientId,
+        config: &DebugAdapterConfig,
+        working_dir: Option<PathBuf>,
+        cx: &mut ModelContext<Self>,
+    ) -> Result<DebugAdapterClient> {
+        #[cfg(any(test, feature = "test-support"))]
+        if let Some((capabilities, fake_tx)) = self.fake_adapters.get(&config.adapter) {
+            let (client, fake) = dap::client::FakeDebugAdapter::new(
+                client_id,
+                config.adapter.clone(),
+                capabilities.clone(),
+                cx.to_async(),
+            );
+            fake_tx.unbounded_send(fake).ok();
+            return Ok(client);
+        }
+
+        let binary: DebugAdapterBinary = ProjectSettings::get_global(cx)
+            .dap
+            .adapters
+            .get(&config.adapter)
+            .cloned()
+            .with_context(|| {
+                format!(
+                    "no debug adapter named {:?} is configured",
+                    config.adapter.0
+                )
+            })?;
+        let working_dir = working_dir.context("no working directory for debug adapter")?;
+        DebugAdapterClient::new(
+            client_id,
+            config.adapter.clone(),
+            binary,
+            &working_dir,
+            cx.to_async(),
+        )
+    }
+
+    fn subscribe_to_client(
+        &self,
+        client: &DebugAdapterClient,
+        cx: &mut ModelContext<Self>,
+    ) -> Vec<dap::client::Subscription> {
+        let client_id = client.id();
+        let this = cx.weak_model();
+        vec![
+            client.on_event::<events::Initialized, _>({
+                let this = this.clone();
+                move |_, mut cx| {
+                    this.update(&mut cx, |this, cx| {
+                        this.configure_session(client_id, cx).detach_and_log_err(cx)
+                    })
+                    .ok();
+                }
+            }),
+            client.on_event::<events::Stopped, _>({
+                let this = this.clone();
+                move |event, mut cx| {
+                    this.update(&mut cx, |this, cx| {
+                        this.handle_stopped(client_id, event, cx)
+                    })
+                    .ok();
+                }
+            }),
+            client.on_event::<events::Continued, _>({
+                let this = this.clone();
+                move |_, mut cx| {
+                    this.update(&mut cx, |this, cx| {
+                        this.set_running(client_id, cx);
+                    })
+                    .ok();
+                }
+            }),
+            client.on_event::<events::Thread, _>({
+                let this = this.clone();
+                move |_, mut cx| {
+                    this.update(&mut cx, |this, cx| {
+                        this.refresh_threads(client_id, cx).detach_and_log_err(cx)
+                    })
+                    .ok();
+                }
+            }),
+            client.on_event::<events::Output, _>({
+                let this = this.clone();
+                move |event, mut cx| {
+                    this.update(&mut cx, |this, cx| {
+                        if let Some(session) = this.sessions.get_mut(&client_id) {
+                            session
+                                .output
+                                .extend(event.output.lines().map(String::from));
+                            let excess = session.output.len().saturating_sub(MAX_OUTPUT_LINES);
+                            session.output.drain(..excess);
+                            cx.emit(DapStoreEvent::Output(client_id));
+                            cx.notify();
+                        }
+                    })
+                    .ok();
+                }
+            }),
+            client.on_event::<events::Exited, _>({
+                let this = this.clone();
+                move |event, mut cx| {
+                    log::info!("debuggee exited with code {}", event.exit_code);
+                    this.update(&mut cx, |this, cx| this.set_exited(client_id, cx))
+                        .ok();
+                }
+            }),
+            client.on_event::<events::Terminated, _>({
+                move |_, mut cx| {
+                    this.update(&mut cx, |this, cx| {
+                        this.set_exited(client_id, cx);
+                        this.stop_session(client_id, cx).detach();
+                    })
+                    .ok();
+                }
+            }),
+        ]
+    }
+
+    /// Sends the breakpoints and finishes the configuration sequence once the adapter is `initialized`.
+    ///
+    /// [DAP Specification](https://microsoft.github.io/debug-adapter-protocol/specification#Events_Initialized)
+    fn configure_session(
+        &mut self,
+        client_id: DebugAdapterClientId,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(session) = self.sessions.get(&client_id) else {
+            return Task::ready(Ok(()));
+        };
+        let client = session.client.clone();
+        let set_breakpoints = self
+            .breakpoints
+            .keys()
+            .filter_map(|project_path| self.set_breakpoints_arguments(project_path, cx))
+            .map(|arguments| client.request::<requests::SetBreakpoints>(arguments))
+            .collect::<Vec<_>>();
+
+        cx.background_executor().spawn(async move {
+            for result in future::join_all(set_breakpoints).await {
+                result.log_err();
+            }
+            let capabilities = client.capabilities();
+            if capabilities
+                .exception_breakpoint_filters
+                .map_or(false, |filters| !filters.is_empty())
+                || capabilities.supports_configuration_done_request != Some(true)
+            {
+                client
+                    .request::<requests::SetExceptionBreakpoints>(
+                        SetExceptionBreakpointsArguments {
+                            filters: Vec::new(),
+                            filter_options: None,
+                            exception_options: None,
+                        },
+                    )
+                    .await
+                    .log_err();
+            }
+            if capabilities.supports_configuration_done_request == Some(true) {
+                client
+                    .request::<requests::ConfigurationDone>(dap::ConfigurationDoneArguments)
+                    .await?;
+            }
+            Ok(())
+        })
+    }
+
+    fn set_breakpoints_arguments(
+        &self,
+        project_path: &ProjectPath,
+        cx: &AppContext,
+    ) -> Option<SetBreakpointsArguments> {
+        let worktree = self
+            .worktree_store
+            .read(cx)
+            .worktree_for_id(project_path.worktree_id, cx)?;
+        let abs_path = worktree.read(cx).absolutize(&project_path.path).log_err()?;
+        let snapshot = self
+            .buffer_store
+            .read(cx)
+            .get_by_path(project_path, cx)
+            .map(|buffer| buffer.read(cx).snapshot());
+        let mut rows = self
+            .breakpoints
+            .get(project_path)
+            .into_iter()
+            .flatten()
+            .map(|breakpoint| breakpoint.row(snapshot.as_ref()))
+            .collect::<Vec<_>>();
+        rows.sort_unstable();
+        rows.dedup();
+
+        Some(SetBreakpointsArguments {
+            source: Source {
+                name: abs_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned()),
+                path: Some(abs_path.to_string_lossy().into_owned()),
+                source_reference: None,
+                presentation_hint: None,
+                origin: None,
+                sources: None,
+                adapter_data: None,
+                checksums: None,
+            },
+            breakpoints: Some(
+                rows.into_iter()
+                    .map(|row| SourceBreakpoint {
+                        line: row as u64 + 1,
+                        column: None,
+                        condition: None,
+                        hit_condition: None,
+                        log_message: None,
+                        mode: None,
+                    })
+                    .collect(),
+            ),
+            lines: None,
+            source_modified: None,
+        })
+    }
+
+    /// Sends the breakpoints of the given file to all running debug adapters.
+    pub fn send_breakpoints(
+        &self,
+        project_path: &ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        let Some(arguments) = self.set_breakpoints_arguments(project_path, cx) else {
+            return Task::ready(Ok(()));
+        };
+        let requests = self
+            .sessions
+            .values()
+            .filter(|session| session.status != DebugSessionStatus::Exited)
+            .map(|session| {
+                session
+                    .client
+                    .request::<requests::SetBreakpoints>(arguments.clone())
+            })
+            .collect::<Vec<_>>();
+        cx.background_executor().spawn(async move {
+            for result in future::join_all(requests).await {
+                result?;
+            }
+            Ok(())
+        })
+    }
+
+    fn handle_stopped(
+        &mut self,
+        client_id: DebugAdapterClientId,
+        event: dap::StoppedEvent,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(session) = self.sessions.get_mut(&client_id) else {
+            return;
+        };
+        session.status = DebugSessionStatus::Stopped {
+            thread_id: event.thread_id,
+        };
+        cx.emit(DapStoreEvent::Stopped {
+            client_id,
+            thread_id: event.thread_id,
+            reason: event.reason,
+        });
+        cx.notify();
+
+        let client = session.client.clone();
+        let refresh_threads = self.refresh_threads(client_id, cx);
+        cx.spawn(|this, mut cx| async move {
+            refresh_threads.await.log_err();
+            let thread_id = match event.thread_id {
+                Some(thread_id) => thread_id,
+                None => {
+                    let thread_id = this.update(&mut cx, |this, _| {
+                        this.sessions
+                            .get(&client_id)
+                            .and_then(|session| session.threads.first())
+                            .map(|thread| thread.id)
+                    })?;
+                    let Some(thread_id) = thread_id else {
+                        return Ok(());
+                    };
+                    thread_id
+                }
+            };
+            let stack_trace = client
+                .request::<requests::StackTrace>(StackTraceArguments {
+                    thread_id,
+                    start_frame: Some(0),
+                    levels: Some(1),
+                    format: None,
+                })
+                .await?;
+            this.update(&mut cx, |this, cx| {
+                if let Some(frame) = stack_trace.stack_frames.first() {
+                    this.set_active_stack_frame(client_id, frame, cx);
+                }
+            })
+        })
+        .detach_and_log_err(cx);
+    }
+
+    fn refresh_threads(
+        &mut self,
+        client_id: DebugAdapterClientId,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(session) = self.sessions.get(&client_id) else {
+            return Task::ready(Ok(()));
+        };
+        let threads = session.client.request::<requests::Threads>(());
+        cx.spawn(|this, mut cx| async move {
+            let threads = threads.await?;
+            this.update(&mut cx, |this, cx| {
+                if let Some(session) = this.sessions.get_mut(&client_id) {
+                    session.threads = threads.threads;
+                    cx.emit(DapStoreEvent::ThreadsChanged(client_id));
+                    cx.notify();
+                }
+            })
+        })
+    }
+
+    fn set_running(&mut self, client_id: DebugAdapterClientId, cx: &mut ModelContext<Self>) {
+        if let Some(session) = self.sessions.get_mut(&client_id) {
+            session.status = DebugSessionStatus::Running;
+            self.clear_active_location(client_id, cx);
+            cx.emit(DapStoreEvent::Continued(client_id));
+            cx.notify();
+        }
+    }
+
+    fn set_exited(&mut self, client_id: DebugAdapterClientId, cx: &mut ModelContext<Self>) {
+        if let Some(session) = self.sessions.get_mut(&client_id) {
+            session.status = DebugSessionStatus::Exited;
+            self.clear_active_location(client_id, cx);
+            cx.notify();
+        }
+    }
+
+    fn clear_active_location(
+        &mut self,
+        client_id: DebugAdapterClientId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        if self
+            .active_location
+            .as_ref()
+            .map_or(false, |location| location.client_id == client_id)
+        {
+            self.active_location = None;
+            cx.emit(DapStoreEvent::ActiveLocationChanged);
+        }
+    }
+
+    /// Sends a request to the debug adapter of the given session.
+    pub fn request<R: dap::requests::Request>(
+        &self,
+        client_id: DebugAdapterClientId,
+        arguments: R::Arguments,
+        cx: &AppContext,
+    ) -> Task<Result<R::Response>>
+    where
+        R::Response: 'static + Send,
+    {
+        let Some(session) = self.sessions.get(&client_id) else {
+            return Task::ready(Err(anyhow!("no debug session with id {client_id}")));
+        };
+        let request = session.client.request::<R>(arguments);
+        cx.background_executor().spawn(request)
+    }
+
+    pub fn continue_thread(
+        &mut self,
+        client_id: DebugAdapterClientId,
+        thread_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let request = self.request::<requests::Continue>(
+            client_id,
+            ContinueArguments {
+                thread_id,
+                single_thread: None,
+            },
+            cx,
+        );
+        self.resume_after(client_id, request, cx)
+    }
+
+    pub fn step_over(
+        &mut self,
+        client_id: DebugAdapterClientId,
+        thread_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let request = self.request::<requests::Next>(
+            client_id,
+            NextArguments {
+                thread_id,
+                single_thread: None,
+                granularity: None,
+            },
+            cx,
+        );
+        self.resume_after(client_id, request, cx)
+    }
+
+    pub fn step_in(
+        &mut self,
+        client_id: DebugAdapterClientId,
+        thread_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let request = self.request::<requests::StepIn>(
+            client_id,
+            StepInArguments {
+                thread_id,
+                single_thread: None,
+                target_id: None,
+                granularity: None,
+            },
+            cx,
+        );
+        self.resume_after(client_id, request, cx)
+    }
+
+    pub fn step_out(
+        &mut self,
+        client_id: DebugAdapterClientId,
+        thread_id: u64,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let request = self.request::<requests::StepOut>(
+            client_id,
+            StepOutArguments {
+                thread_id,
+                single_thread: None,
+                granularity: None,
+            },
+            cx,
+        );
+        self.resume_after(client_id, request, cx)
+    }
+
+    pub fn pause(
+        &self,
+        client_id: DebugAdapterClientId,
+        thread_id: u64,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        self.request::<requests::Pause>(client_id, PauseArguments { thread_id }, cx)
+    }
+
+    /// Marks the session as running once a request resuming execution succeeds, since adapters
+    /// aren't required to send a `continued` event in response to a request.
+    fn resume_after<T: 'static>(
+        &mut self,
+        client_id: DebugAdapterClientId,
+        request: Task<Result<T>>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        cx.spawn(|this, mut cx| async move {
+            request.await?;
+            this.update(&mut cx, |this, cx| this.set_running(client_id, cx))
+        })
+    }
+
+    /// Disconnects from the debug adapter, terminating the debuggee.
+    pub fn stop_session(
+        &mut self,
+        client_id: DebugAdapterClientId,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<()> {
+        let Some(session) = self.sessions.remove(&client_id) else {
+            return Task::ready(());
+        };
+        self.clear_active_location(client_id, cx);
+        cx.emit(DapStoreEvent::SessionStopped(client_id));
+        cx.notify();
+
+        let shutdown = session.client.shutdown();
+        cx.background_executor().spawn(async move {
+            if let Some(shutdown) = shutdown {
+                shutdown.await;
+            }
+        })
+    }
+
+    fn shutdown_sessions(
+        &mut self,
+        cx: &mut ModelContext<Self>,
+    ) -> impl future::Future<Output = ()> {
+        let shutdowns = std::mem::take(&mut self.sessions)
+            .into_values()
+            .filter_map(|session| session.client.shutdown())
+            .collect::<Vec<_>>();
+        self.active_location = None;
+        cx.notify();
+        async move {
+            future::join_all(shutdowns).await;
+        }
+    }
+
+    /// Makes sessions for the given adapter talk to a [`dap::client::FakeDebugAdapter`]
+    /// instead of spawning a process.
+    #[cfg(any(test, feature = "test-support"))]
+    pub fn register_fake_adapter(
+        &mut self,
+        name: DebugAdapterName,
+        capabilities: dap::Capabilities,
+    ) -> futures::channel::mpsc::UnboundedReceiver<dap::client::FakeDebugAdapter> {
+        let (tx, rx) = futures::channel::mpsc::unbounded();
+        self.fake_adapters.insert(name, (capabilities, tx));
+        rx
+    }
+}
+
+fn project_path_for_buffer(buffer: &Buffer, cx: &AppContext) -> Option<ProjectPath> {
+    let file = buffer.file()?;
+    Some(ProjectPath::from((
+        file.worktree_id(cx),
+        file.path().clone(),
+    )))
+}
@@ -10,15 +10,16 @@ use crate::{
     toolchain_store::{EmptyToolchainStore, ToolchainStoreEvent},
     worktree_store::{WorktreeStore, WorktreeStoreEvent},
     yarn::YarnPathStore,
-    CodeAction, Completion, CoreCompletion, Hover, InlayHint, ProjectItem as _, ProjectPath,
-    ProjectTransaction, ResolveState, Symbol, ToolchainStore,
+    CodeAction, CodeLens, Completion, CoreCompletion, DocumentLink, Hover, InlayHint,
+    ProjectItem as _, ProjectPath, ProjectTransaction, ResolveState, SemanticToken, SemanticTokens,
+    Symbol, ToolchainStore,
 };
 use anyhow::{anyhow, Context as _, Result};
 use async_trait::async_trait;
 use client::{proto, TypedEnvelope};
 use collections::{btree_map, BTreeMap, HashMap, HashSet};
 use futures::{
-    future::{join_all, Shared},
+    future::{join_all, LocalBoxFuture, Shared},
     select,
     stream::FuturesUnordered,
     AsyncWriteExt, Future, FutureExt, StreamExt,
@@ -94,6 +95,7 @@ pub use worktree::{
 
 const SERVER_LAUNCHING_BEFORE_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
 pub const SERVER_PROGRESS_THROTTLE_TIMEOUT: Duration = Duration::from_millis(100);
+const PULL_DIAGNOSTICS_DEBOUNCE: Duration = Duration::from_millis(250);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FormatTrigger {
@@ -173,6 +175,31 @@ pub struct LocalLspStore {
     buffer_snapshots: HashMap<BufferId, HashMap<LanguageServerId, Vec<LspBufferSnapshot>>>, // buffer_id -> server_id -> vec of snapshots
     _subscription: gpui::Subscription,
     registered_buffers: HashMap<BufferId, usize>,
+    semantic_tokens: HashMap<BufferId, CachedSemanticTokens>,
+    /// The result ids of the last diagnostic reports pulled from each language server, sent back
+    /// with the next pull so that the server can answer that nothing changed.
+    diagnostic_result_ids: HashMap<LanguageServerId, HashMap<lsp::Url, String>>,
+    /// The diagnostics each language server published and reported when pulled, by document.
+    lsp_diagnostics: HashMap<LanguageServerId, HashMap<lsp::Url, LspDiagnostics>>,
+    document_diagnostics_tasks: HashMap<BufferId, Task<()>>,
+    workspace_diagnostics_tasks: HashMap<LanguageServerId, Task<()>>,
+}
+
+/// The diagnostics of a document from a single language server, kept apart by how they were
+/// received so that published diagnostics and pulled reports don't replace each other.
+#[derive(Default)]
+struct LspDiagnostics {
+    published: Vec<lsp::Diagnostic>,
+    pulled: Vec<lsp::Diagnostic>,
+}
+
+/// The last semantic tokens received for a buffer, kept to request deltas against.
+struct CachedSemanticTokens {
+    server_id: LanguageServerId,
+    version: clock::Global,
+    result_id: Option<String>,
+    data: Vec<lsp::SemanticToken>,
+    tokens: SemanticTokens,
 }
 
 impl LocalLspStore {
@@ -515,9 +542,10 @@ impl LocalLspStore {
                     if let Some(this) = this.upgrade() {
                         adapter.process_diagnostics(&mut params);
                         this.update(&mut cx, |this, cx| {
-                            this.update_diagnostics(
+                            this.merge_lsp_diagnostics(
                                 server_id,
                                 params,
+                                false,
                                 &adapter.disk_based_diagnostic_sources,
                                 cx,
                             )
@@ -819,6 +847,74 @@ impl LocalLspStore {
             })
             .detach();
 
+        language_server
+            .on_request::<lsp::request::CodeLensRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            cx.emit(LspStoreEvent::RefreshCodeLens);
+                            this.downstream_client.as_ref().map(|(client, project_id)| {
+                                client.send(proto::RefreshCodeLens {
+                                    project_id: *project_id,
+                                })
+                            })
+                        })?
+                        .transpose()?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::WorkspaceDiagnosticRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            this.refresh_pulled_diagnostics(server_id, cx);
+                        })?;
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
+        language_server
+            .on_request::<lsp::request::SemanticTokensRefresh, _, _>({
+                let this = this.clone();
+                move |(), mut cx| {
+                    let this = this.clone();
+                    async move {
+                        this.update(&mut cx, |this, cx| {
+                            if let Some(local) = this.as_local_mut() {
+                                // Keep the result ids around, so that the next requests can still be deltas.
+                                for cached in local.semantic_tokens.values_mut() {
+                                    if cached.server_id == server_id {
+                                        cached.version = clock::Global::new();
+                                    }
+                                }
+                            }
+                            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+                            // Failing to forward the refresh to the clients of this project must
+                            // not fail the language server's request.
+                            this.downstream_client.as_ref().map(|(client, project_id)| {
+                                client.send(proto::RefreshSemanticTokens {
+                                    project_id: *project_id,
+                                })
+                            })
+                        })?
+                        .transpose()
+                        .log_err();
+                        Ok(())
+                    }
+                }
+            })
+            .detach();
+
         language_server
             .on_request::<lsp::request::ShowMessageRequest, _, _>({
                 let this = this.clone();
@@ -1957,6 +2053,8 @@ impl LocalLspStore {
     ) {
         buffer.update(cx, |buffer, cx| {
             self.buffer_snapshots.remove(&buffer.remote_id());
+            self.semantic_tokens.remove(&buffer.remote_id());
+            self.document_diagnostics_tasks.remove(&buffer.remote_id());
             for (_, language_server) in self.language_servers_for_buffer(buffer, cx) {
                 language_server
                     .notify::<lsp::notification::DidCloseTextDocument>(
@@ -2772,6 +2870,16 @@ pub struct LspStore {
     _maintain_buffer_languages: Task<()>,
     diagnostic_summaries:
         HashMap<WorktreeId, HashMap<Arc<Path>, HashMap<LanguageServerId, DiagnosticSummary>>>,
+    document_links: HashMap<BufferId, CachedDocumentLinks>,
+}
+
+/// The document links requested for a version of a buffer, shared by everyone asking for the
+/// links of that version.
+struct CachedDocumentLinks {
+    version: clock::Global,
+    links: Shared<Task<Result<Vec<DocumentLink>, Arc<anyhow::Error>>>>,
+    /// The links resolved since they were requested, which replace the unresolved ones.
+    resolved_links: Vec<DocumentLink>,
 }
 
 pub enum LspStoreEvent {
@@ -2789,6 +2897,8 @@ pub enum LspStoreEvent {
     },
     Notification(String),
     RefreshInlayHints,
+    RefreshCodeLens,
+    RefreshSemanticTokens,
     DiagnosticsUpdated {
         language_server_id: LanguageServerId,
         path: ProjectPath,
@@ -2840,12 +2950,20 @@ impl LspStore {
         client.add_model_request_handler(Self::handle_inlay_hints);
         client.add_model_request_handler(Self::handle_get_project_symbols);
         client.add_model_request_handler(Self::handle_resolve_inlay_hint);
+        client.add_model_request_handler(Self::handle_get_semantic_tokens);
         client.add_model_request_handler(Self::handle_open_buffer_for_symbol);
         client.add_model_request_handler(Self::handle_refresh_inlay_hints);
+        client.add_model_request_handler(Self::handle_refresh_code_lens);
+        client.add_model_request_handler(Self::handle_refresh_semantic_tokens);
+        client.add_model_request_handler(Self::handle_resolve_code_lens);
+        client.add_model_request_handler(Self::handle_resolve_document_link);
         client.add_model_request_handler(Self::handle_on_type_formatting);
         client.add_model_request_handler(Self::handle_apply_additional_edits_for_completion);
         client.add_model_request_handler(Self::handle_register_buffer_with_language_servers);
         client.add_model_request_handler(Self::handle_lsp_command::<GetCodeActions>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetCodeLens>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetFoldingRanges>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetDocumentLinks>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetCompletions>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetHover>);
         client.add_model_request_handler(Self::handle_lsp_command::<GetDefinition>);
@@ -2857,6 +2975,12 @@ impl LspStore {
         client.add_model_request_handler(Self::handle_lsp_command::<PerformRename>);
         client.add_model_request_handler(Self::handle_lsp_command::<lsp_ext_command::ExpandMacro>);
         client.add_model_request_handler(Self::handle_lsp_command::<LinkedEditingRange>);
+        client.add_model_request_handler(Self::handle_lsp_command::<PrepareCallHierarchy>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetIncomingCalls>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetOutgoingCalls>);
+        client.add_model_request_handler(Self::handle_lsp_command::<PrepareTypeHierarchy>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSupertypes>);
+        client.add_model_request_handler(Self::handle_lsp_command::<GetSubtypes>);
     }
 
     pub fn as_remote(&self) -> Option<&RemoteLspStore> {
@@ -2967,6 +3091,11 @@ impl LspStore {
                     this.as_local_mut().unwrap().shutdown_language_servers(cx)
                 }),
                 registered_buffers: HashMap::default(),
+                semantic_tokens: HashMap::default(),
+                diagnostic_result_ids: HashMap::default(),
+                lsp_diagnostics: HashMap::default(),
+                document_diagnostics_tasks: HashMap::default(),
+                workspace_diagnostics_tasks: HashMap::default(),
             }),
             last_formatting_failure: None,
             downstream_client: None,
@@ -2977,6 +3106,7 @@ impl LspStore {
             language_server_statuses: Default::default(),
             nonce: StdRng::from_entropy().gen(),
             diagnostic_summaries: Default::default(),
+            document_links: Default::default(),
             active_entry: None,
 
             _maintain_workspace_config,
@@ -3032,6 +3162,7 @@ impl LspStore {
             language_server_statuses: Default::default(),
             nonce: StdRng::from_entropy().gen(),
             diagnostic_summaries: Default::default(),
+            document_links: Default::default(),
             active_entry: None,
             toolchain_store,
             _maintain_workspace_config,
@@ -3079,7 +3210,9 @@ impl LspStore {
                     }
                 }
             }
-            BufferStoreEvent::BufferDropped(_) => {}
+            BufferStoreEvent::BufferDropped(buffer_id) => {
+                self.document_links.remove(buffer_id);
+            }
         }
     }
 
@@ -3166,11 +3299,19 @@ impl LspStore {
     ) {
         match event {
             language::BufferEvent::Edited { .. } => {
-                self.on_buffer_edited(buffer, cx);
+                self.on_buffer_edited(buffer.clone(), cx);
+                self.pull_document_diagnostics(&buffer, true, cx);
             }
 
             language::BufferEvent::Saved => {
-                self.on_buffer_saved(buffer, cx);
+                self.on_buffer_saved(buffer.clone(), cx);
+                self.pull_document_diagnostics(&buffer, false, cx);
+                if let Some(local) = self.as_local() {
+                    let server_ids = local.language_server_ids_for_buffer(buffer.read(cx), cx);
+                    for server_id in server_ids {
+                        self.pull_workspace_diagnostics(server_id, cx);
+                    }
+                }
             }
 
             _ => {}
@@ -3219,6 +3360,7 @@ impl LspStore {
             *refcount += 1;
             if *refcount == 1 {
                 local.register_buffer_with_language_servers(buffer, cx);
+                self.pull_document_diagnostics(buffer, false, cx);
             }
 
             cx.observe_release(&handle, move |this, buffer, cx| {
@@ -3812,6 +3954,348 @@ impl LspStore {
         }
     }
 
+    pub fn resolve_code_lens(
+        &self,
+        lens: CodeLens,
+        buffer_handle: Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<anyhow::Result<CodeLens>> {
+        if lens.lsp_lens.command.is_some() {
+            return Task::ready(Ok(lens));
+        }
+
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = proto::ResolveCodeLens {
+                project_id,
+                buffer_id: buffer_handle.read(cx).remote_id().into(),
+                lens: Some(Self::serialize_code_lens(&lens)),
+            };
+            cx.spawn(move |_, _| async move {
+                let response = upstream_client
+                    .request(request)
+                    .await
+                    .context("code lens proto request")?;
+                match response.lens {
+                    Some(resolved_lens) => Self::deserialize_code_lens(resolved_lens)
+                        .context("code lens proto resolve response conversion"),
+                    None => Ok(lens),
+                }
+            })
+        } else {
+            let buffer = buffer_handle.read(cx);
+            let Some((_, lang_server)) =
+                self.language_server_for_local_buffer(buffer, lens.server_id, cx)
+            else {
+                return Task::ready(Ok(lens));
+            };
+            if !GetCodeLens::can_resolve_lens(&lang_server.capabilities()) {
+                return Task::ready(Ok(lens));
+            }
+
+            let lang_server = lang_server.clone();
+            cx.spawn(move |_, _| async move {
+                let mut lsp_lens = lang_server
+                    .request::<lsp::request::CodeLensResolve>(lens.lsp_lens.clone())
+                    .await
+                    .context("code lens resolve LSP request")?;
+                // Leave the lens unresolved if its command is not one the server can run.
+                if lsp_lens.command.as_ref().map_or(false, |command| {
+                    !GetCodeLens::can_execute_command(&lang_server.capabilities(), command)
+                }) {
+                    lsp_lens.command = None;
+                }
+                Ok(CodeLens { lsp_lens, ..lens })
+            })
+        }
+    }
+
+    /// Returns the document links of the buffer from its primary language server. The links are
+    /// requested once per version of the buffer.
+    pub fn document_links(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<anyhow::Result<Vec<DocumentLink>>> {
+        let buffer = buffer_handle.read(cx);
+        let buffer_id = buffer.remote_id();
+        let version = buffer.version();
+        let links = match self.document_links.get(&buffer_id) {
+            Some(cached) if cached.version == version => cached.links.clone(),
+            _ => {
+                let request = self.request_lsp(
+                    buffer_handle.clone(),
+                    LanguageServerToQuery::Primary,
+                    GetDocumentLinks,
+                    cx,
+                );
+                let links = cx
+                    .spawn(|_, _| async move { request.await.map_err(Arc::new) })
+                    .shared();
+                self.document_links.insert(
+                    buffer_id,
+                    CachedDocumentLinks {
+                        version: version.clone(),
+                        links: links.clone(),
+                        resolved_links: Vec::new(),
+                    },
+                );
+                links
+            }
+        };
+        cx.spawn(|this, mut cx| async move {
+            let mut links = links.await.map_err(|error| anyhow!("{error:#}"))?;
+            this.update(&mut cx, |this, _| {
+                let Some(cached) = this.document_links.get(&buffer_id) else {
+                    return;
+                };
+                if cached.version != version {
+                    return;
+                }
+                for link in &mut links {
+                    if let Some(resolved_link) = cached
+                        .resolved_links
+                        .iter()
+                        .find(|resolved_link| same_document_link(resolved_link, link))
+                    {
+                        *link = resolved_link.clone();
+                    }
+                }
+            })?;
+            Ok(links)
+        })
+    }
+
+    /// Stores a resolved link in the cached links of the buffer's version it was resolved for, so
+    /// that it isn't resolved again.
+    fn cache_resolved_document_link(
+        &mut self,
+        buffer_id: BufferId,
+        version: &clock::Global,
+        link: &DocumentLink,
+    ) {
+        let Some(cached) = self.document_links.get_mut(&buffer_id) else {
+            return;
+        };
+        if &cached.version != version {
+            return;
+        }
+        cached
+            .resolved_links
+            .retain(|resolved_link| !same_document_link(resolved_link, link));
+        cached.resolved_links.push(link.clone());
+    }
+
+    pub fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer_handle: Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<anyhow::Result<DocumentLink>> {
+        if link.lsp_link.target.is_some() {
+            return Task::ready(Ok(link));
+        }
+
+        let buffer_id = buffer_handle.read(cx).remote_id();
+        let version = buffer_handle.read(cx).version();
+        if let Some((upstream_client, project_id)) = self.upstream_client() {
+            let request = proto::ResolveDocumentLink {
+                project_id,
+                buffer_id: buffer_id.into(),
+                link: Some(Self::serialize_document_link(&link)),
+            };
+            cx.spawn(move |this, mut cx| async move {
+                let response = upstream_client
+                    .request(request)
+                    .await
+                    .context("document link proto request")?;
+                let Some(resolved_link) = response.link else {
+                    return Ok(link);
+                };
+                let resolved_link = Self::deserialize_document_link(resolved_link)
+                    .context("document link proto resolve response conversion")?;
+                this.update(&mut cx, |this, _| {
+                    this.cache_resolved_document_link(buffer_id, &version, &resolved_link)
+                })?;
+                Ok(resolved_link)
+            })
+        } else {
+            let buffer = buffer_handle.read(cx);
+            let Some((_, lang_server)) =
+                self.language_server_for_local_buffer(buffer, link.server_id, cx)
+            else {
+                return Task::ready(Ok(link));
+            };
+            if !GetDocumentLinks::can_resolve_link(&lang_server.capabilities()) {
+                return Task::ready(Ok(link));
+            }
+
+            let lang_server = lang_server.clone();
+            cx.spawn(move |this, mut cx| async move {
+                let lsp_link = lang_server
+                    .request::<lsp::request::DocumentLinkResolve>(link.lsp_link.clone())
+                    .await
+                    .context("document link resolve LSP request")?;
+                let resolved_link = DocumentLink { lsp_link, ..link };
+                this.update(&mut cx, |this, _| {
+                    this.cache_resolved_document_link(buffer_id, &version, &resolved_link)
+                })?;
+                Ok(resolved_link)
+            })
+        }
+    }
+
+    /// Fetches the semantic tokens of the buffer from its first language server that provides them,
+    /// requesting only the changes since the previous response when the server supports deltas.
+    pub fn semantic_tokens(
+        &mut self,
+        buffer_handle: Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<anyhow::Result<SemanticTokens>> {
+        let buffer = buffer_handle.read(cx);
+        let buffer_id = buffer.remote_id();
+
+        if let Some((client, project_id)) = self.upstream_client() {
+            let request = proto::GetSemanticTokens {
+                project_id,
+                buffer_id: buffer_id.into(),
+                version: serialize_version(&buffer.version()),
+            };
+            return cx.spawn(move |_, mut cx| async move {
+                let response = client
+                    .request(request)
+                    .await
+                    .context("semantic tokens proto request")?;
+                buffer_handle
+                    .update(&mut cx, |buffer, _| {
+                        buffer.wait_for_version(deserialize_version(&response.version))
+                    })?
+                    .await?;
+                Ok(semantic_tokens_from_proto(response))
+            });
+        }
+
+        let Some(local) = self.as_local() else {
+            return Task::ready(Ok(SemanticTokens::default()));
+        };
+        let Some(abs_path) = File::from_dyn(buffer.file())
+            .and_then(File::as_local)
+            .map(|file| file.abs_path(cx))
+        else {
+            return Task::ready(Ok(SemanticTokens::default()));
+        };
+        let Some((server, legend, supports_delta)) = local
+            .language_servers_for_buffer(buffer, cx)
+            .find_map(|(_, server)| {
+                let (legend, supports_delta) = semantic_tokens_legend(&server.capabilities())?;
+                Some((server.clone(), legend, supports_delta))
+            })
+        else {
+            return Task::ready(Ok(SemanticTokens::default()));
+        };
+        let uri = match lsp::Url::from_file_path(&abs_path) {
+            Ok(uri) => uri,
+            Err(()) => return Task::ready(Err(anyhow!("invalid file path {abs_path:?}"))),
+        };
+
+        let server_id = server.server_id();
+        let version = buffer.version();
+        let snapshot = buffer.text_snapshot();
+        let cached = local
+            .semantic_tokens
+            .get(&buffer_id)
+            .filter(|cached| cached.server_id == server_id);
+        if let Some(cached) = cached.filter(|cached| cached.version == version) {
+            return Task::ready(Ok(cached.tokens.clone()));
+        }
+
+        let text_document = lsp::TextDocumentIdentifier::new(uri);
+        let previous = cached
+            .filter(|_| supports_delta)
+            .and_then(|cached| Some((cached.result_id.clone()?, cached.data.clone())));
+        // Send the request right away, so that the response matches the snapshot taken above.
+        let response: LocalBoxFuture<Result<(Option<String>, Vec<lsp::SemanticToken>)>> =
+            match previous {
+                Some((previous_result_id, mut data)) => {
+                    let request = server.request::<lsp::request::SemanticTokensFullDeltaRequest>(
+                        lsp::SemanticTokensDeltaParams {
+                            text_document,
+                            previous_result_id,
+                            work_done_progress_params: Default::default(),
+                            partial_result_params: Default::default(),
+                        },
+                    );
+                    async move {
+                        let response =
+                            request.await.context("semantic tokens delta LSP request")?;
+                        Ok(match response {
+                            Some(lsp::SemanticTokensFullDeltaResult::Tokens(tokens)) => {
+                                (tokens.result_id, tokens.data)
+                            }
+                            Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(delta)) => {
+                                apply_semantic_token_edits(&mut data, delta.edits);
+                                (delta.result_id, data)
+                            }
+                            Some(lsp::SemanticTokensFullDeltaResult::PartialTokensDelta {
+                                edits,
+                            }) => {
+                                apply_semantic_token_edits(&mut data, edits);
+                                (None, data)
+                            }
+                            None => (None, Vec::new()),
+                        })
+                    }
+                    .boxed_local()
+                }
+                None => {
+                    let request = server.request::<lsp::request::SemanticTokensFullRequest>(
+                        lsp::SemanticTokensParams {
+                            text_document,
+                            work_done_progress_params: Default::default(),
+                            partial_result_params: Default::default(),
+                        },
+                    );
+                    async move {
+                        let response = request.await.context("semantic tokens LSP request")?;
+                        Ok(match response {
+                            Some(lsp::SemanticTokensResult::Tokens(tokens)) => {
+                                (tokens.result_id, tokens.data)
+                            }
+                            Some(lsp::SemanticTokensResult::Partial(partial)) => {
+                                (None, partial.data)
+                            }
+                            None => (None, Vec::new()),
+                        })
+                    }
+                    .boxed_local()
+                }
+            };
+
+        cx.spawn(move |this, mut cx| async move {
+            let (result_id, data) = response.await?;
+            let (token_types, token_modifiers) = legend;
+            let tokens = SemanticTokens {
+                token_types,
+                token_modifiers,
+                tokens: decode_semantic_tokens(&data, &snapshot),
+            };
+            this.update(&mut cx, |this, _| {
+                if let Some(local) = this.as_local_mut() {
+                    local.semantic_tokens.insert(
+                        buffer_id,
+                        CachedSemanticTokens {
+                            server_id,
+                            version,
+                            result_id,
+                            data,
+                            tokens: tokens.clone(),
+                        },
+                    );
+                }
+            })?;
+            Ok(tokens)
+        })
+    }
+
     pub(crate) fn linked_edit(
         &mut self,
         buffer: &Model<Buffer>,
@@ -5088,6 +5572,294 @@ impl LspStore {
         None
     }
 
+    /// Pulls the diagnostics of the buffer from its language servers that support the pull model.
+    /// When `debounce` is set, waits a little first, so that typing does not flood the servers
+    /// with requests.
+    fn pull_document_diagnostics(
+        &mut self,
+        buffer: &Model<Buffer>,
+        debounce: bool,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let buffer_id = buffer.read(cx).remote_id();
+        let buffer = buffer.downgrade();
+        let task = cx.spawn(move |this, mut cx| async move {
+            if debounce {
+                cx.background_executor()
+                    .timer(PULL_DIAGNOSTICS_DEBOUNCE)
+                    .await;
+            }
+
+            let Some(requests) = this
+                .update(&mut cx, |this, cx| {
+                    let local = this.as_local()?;
+                    let buffer = buffer.upgrade()?;
+                    let buffer = buffer.read(cx);
+                    let abs_path = File::from_dyn(buffer.file())?.as_local()?.abs_path(cx);
+                    let uri = lsp::Url::from_file_path(abs_path).log_err()?;
+                    let requests = local
+                        .language_servers_for_buffer(buffer, cx)
+                        .filter_map(|(adapter, server)| {
+                            let options = diagnostic_options(&server.capabilities())?;
+                            let server_id = server.server_id();
+                            // Diagnostics are reported against the version the server last saw.
+                            let version = local
+                                .buffer_snapshots
+                                .get(&buffer_id)?
+                                .get(&server_id)?
+                                .last()?
+                                .version;
+                            let params = lsp::DocumentDiagnosticParams {
+                                text_document: lsp::TextDocumentIdentifier::new(uri.clone()),
+                                identifier: options.identifier,
+                                previous_result_id: local
+                                    .diagnostic_result_ids
+                                    .get(&server_id)
+                                    .and_then(|result_ids| result_ids.get(&uri))
+                                    .cloned(),
+                                work_done_progress_params: Default::default(),
+                                partial_result_params: Default::default(),
+                            };
+                            let adapter = adapter.clone();
+                            let server = server.clone();
+                            let uri = uri.clone();
+                            Some(async move {
+                                let response = server
+                                    .request::<lsp::request::DocumentDiagnosticRequest>(params)
+                                    .await;
+                                (adapter, server_id, uri, version, response)
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    Some(requests)
+                })
+                .ok()
+                .flatten()
+            else {
+                return;
+            };
+
+            for (adapter, server_id, uri, version, response) in join_all(requests).await {
+                let Some(response) = response
+                    .context("document diagnostic LSP request")
+                    .log_err()
+                else {
+                    continue;
+                };
+                this.update(&mut cx, |this, cx| {
+                    this.apply_document_diagnostic_report(
+                        server_id,
+                        &adapter,
+                        uri,
+                        Some(version),
+                        response,
+                        cx,
+                    );
+                })
+                .ok();
+            }
+        });
+        local.document_diagnostics_tasks.insert(buffer_id, task);
+    }
+
+    /// Pulls the diagnostics of the whole workspace from the language server, if it supports
+    /// workspace diagnostics.
+    fn pull_workspace_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let Some(LanguageServerState::Running {
+            adapter, server, ..
+        }) = local.language_servers.get(&server_id)
+        else {
+            return;
+        };
+        let Some(options) = diagnostic_options(&server.capabilities()) else {
+            return;
+        };
+        if !options.workspace_diagnostics {
+            return;
+        }
+
+        let params = lsp::WorkspaceDiagnosticParams {
+            identifier: options.identifier,
+            previous_result_ids: local
+                .diagnostic_result_ids
+                .get(&server_id)
+                .into_iter()
+                .flatten()
+                .map(|(uri, result_id)| lsp::PreviousResultId {
+                    uri: uri.clone(),
+                    value: result_id.clone(),
+                })
+                .collect(),
+            work_done_progress_params: Default::default(),
+            partial_result_params: Default::default(),
+        };
+        let adapter = adapter.clone();
+        let server = server.clone();
+        // Servers may hold workspace diagnostic requests open until something changes, so cancel
+        // the previous request before starting a new one.
+        local.workspace_diagnostics_tasks.remove(&server_id);
+        let task = cx.spawn(move |this, mut cx| async move {
+            let Some(response) = server
+                .request::<lsp::request::WorkspaceDiagnosticRequest>(params)
+                .await
+                .context("workspace diagnostic LSP request")
+                .log_err()
+            else {
+                return;
+            };
+            let items = match response {
+                lsp::WorkspaceDiagnosticReportResult::Report(report) => report.items,
+                lsp::WorkspaceDiagnosticReportResult::Partial(partial) => partial.items,
+            };
+            this.update(&mut cx, |this, cx| {
+                for item in items {
+                    let (uri, version, report) = match item {
+                        lsp::WorkspaceDocumentDiagnosticReport::Full(report) => (
+                            report.uri,
+                            report.version,
+                            lsp::DocumentDiagnosticReportKind::Full(
+                                report.full_document_diagnostic_report,
+                            ),
+                        ),
+                        lsp::WorkspaceDocumentDiagnosticReport::Unchanged(report) => (
+                            report.uri,
+                            report.version,
+                            lsp::DocumentDiagnosticReportKind::Unchanged(
+                                report.unchanged_document_diagnostic_report,
+                            ),
+                        ),
+                    };
+                    let version = version.map(|version| version as i32);
+                    this.apply_diagnostic_report(server_id, &adapter, uri, version, report, cx);
+                }
+            })
+            .ok();
+        });
+        local.workspace_diagnostics_tasks.insert(server_id, task);
+    }
+
+    /// Pulls the diagnostics of every open buffer and of the workspace again, after the language
+    /// server asked for it with `workspace/diagnostic/refresh`.
+    fn refresh_pulled_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let Some(local) = self.as_local() else {
+            return;
+        };
+        let buffers = self
+            .buffer_store
+            .read(cx)
+            .buffers()
+            .filter(|buffer| {
+                let buffer = buffer.read(cx);
+                local.registered_buffers.contains_key(&buffer.remote_id())
+                    && local
+                        .language_server_ids_for_buffer(buffer, cx)
+                        .contains(&server_id)
+            })
+            .collect::<Vec<_>>();
+        for buffer in &buffers {
+            self.pull_document_diagnostics(buffer, false, cx);
+        }
+        self.pull_workspace_diagnostics(server_id, cx);
+    }
+
+    fn apply_document_diagnostic_report(
+        &mut self,
+        server_id: LanguageServerId,
+        adapter: &CachedLspAdapter,
+        uri: lsp::Url,
+        version: Option<i32>,
+        response: lsp::DocumentDiagnosticReportResult,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let (report, related_documents) = match response {
+            lsp::DocumentDiagnosticReportResult::Report(lsp::DocumentDiagnosticReport::Full(
+                report,
+            )) => (
+                Some(lsp::DocumentDiagnosticReportKind::Full(
+                    report.full_document_diagnostic_report,
+                )),
+                report.related_documents,
+            ),
+            lsp::DocumentDiagnosticReportResult::Report(
+                lsp::DocumentDiagnosticReport::Unchanged(report),
+            ) => (
+                Some(lsp::DocumentDiagnosticReportKind::Unchanged(
+                    report.unchanged_document_diagnostic_report,
+                )),
+                report.related_documents,
+            ),
+            lsp::DocumentDiagnosticReportResult::Partial(partial) => {
+                (None, partial.related_documents)
+            }
+        };
+        if let Some(report) = report {
+            self.apply_diagnostic_report(server_id, adapter, uri, version, report, cx);
+        }
+        for (uri, report) in related_documents.into_iter().flatten() {
+            self.apply_diagnostic_report(server_id, adapter, uri, None, report, cx);
+        }
+    }
+
+    /// Replaces the pulled diagnostics of the document with a full report, and remembers the
+    /// report's result id for the next pull.
+    fn apply_diagnostic_report(
+        &mut self,
+        server_id: LanguageServerId,
+        adapter: &CachedLspAdapter,
+        uri: lsp::Url,
+        version: Option<i32>,
+        report: lsp::DocumentDiagnosticReportKind,
+        cx: &mut ModelContext<Self>,
+    ) {
+        let result_id = match report {
+            lsp::DocumentDiagnosticReportKind::Full(report) => {
+                let mut params = lsp::PublishDiagnosticsParams {
+                    uri: uri.clone(),
+                    diagnostics: report.items,
+                    version,
+                };
+                adapter.process_diagnostics(&mut params);
+                self.merge_lsp_diagnostics(
+                    server_id,
+                    params,
+                    true,
+                    &adapter.disk_based_diagnostic_sources,
+                    cx,
+                )
+                .log_err();
+                report.result_id
+            }
+            lsp::DocumentDiagnosticReportKind::Unchanged(report) => Some(report.result_id),
+        };
+
+        let Some(local) = self.as_local_mut() else {
+            return;
+        };
+        let result_ids = local.diagnostic_result_ids.entry(server_id).or_default();
+        match result_id {
+            Some(result_id) => {
+                result_ids.insert(uri, result_id);
+            }
+            None => {
+                result_ids.remove(&uri);
+            }
+        }
+    }
+
     pub(crate) async fn refresh_workspace_configurations(
         this: &WeakModel<Self>,
         mut cx: AsyncAppContext,
@@ -5963,6 +6735,8 @@ impl LspStore {
                     progress_tokens: Default::default(),
                 },
             );
+            // Links fetched before the server started may be missing the ones it provides.
+            this.document_links.clear();
             cx.emit(LspStoreEvent::LanguageServerAdded(
                 server_id,
                 LanguageServerName(server.name.into()),
@@ -6606,6 +7380,28 @@ impl LspStore {
         Ok(proto::Ack {})
     }
 
+    async fn handle_refresh_code_lens(
+        this: Model<Self>,
+        _: TypedEnvelope<proto::RefreshCodeLens>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(LspStoreEvent::RefreshCodeLens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
+    async fn handle_refresh_semantic_tokens(
+        this: Model<Self>,
+        _: TypedEnvelope<proto::RefreshSemanticTokens>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        this.update(&mut cx, |_, cx| {
+            cx.emit(LspStoreEvent::RefreshSemanticTokens);
+        })?;
+        Ok(proto::Ack {})
+    }
+
     async fn handle_inlay_hints(
         this: Model<Self>,
         envelope: TypedEnvelope<proto::InlayHints>,
@@ -6682,6 +7478,90 @@ impl LspStore {
         })
     }
 
+    async fn handle_resolve_code_lens(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ResolveCodeLens>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ResolveCodeLensResponse> {
+        let lens = envelope
+            .payload
+            .lens
+            .context("missing code lens")
+            .and_then(Self::deserialize_code_lens)?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        let resolved_lens = this
+            .update(&mut cx, |this, cx| this.resolve_code_lens(lens, buffer, cx))?
+            .await
+            .context("code lens resolve")?;
+        Ok(proto::ResolveCodeLensResponse {
+            lens: Some(Self::serialize_code_lens(&resolved_lens)),
+        })
+    }
+
+    async fn handle_resolve_document_link(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ResolveDocumentLink>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ResolveDocumentLinkResponse> {
+        let link = envelope
+            .payload
+            .link
+            .context("missing document link")
+            .and_then(Self::deserialize_document_link)?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        let resolved_link = this
+            .update(&mut cx, |this, cx| {
+                this.resolve_document_link(link, buffer, cx)
+            })?
+            .await
+            .context("document link resolve")?;
+        Ok(proto::ResolveDocumentLinkResponse {
+            link: Some(Self::serialize_document_link(&resolved_link)),
+        })
+    }
+
+    async fn handle_get_semantic_tokens(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GetSemanticTokens>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GetSemanticTokensResponse> {
+        let buffer_id = BufferId::new(envelope.payload.buffer_id)?;
+        let buffer = this.update(&mut cx, |this, cx| {
+            this.buffer_store.read(cx).get_existing(buffer_id)
+        })??;
+        buffer
+            .update(&mut cx, |buffer, _| {
+                buffer.wait_for_version(deserialize_version(&envelope.payload.version))
+            })?
+            .await
+            .with_context(|| format!("waiting for version for buffer {}", buffer.entity_id()))?;
+        let tokens = this
+            .update(&mut cx, |this, cx| this.semantic_tokens(buffer.clone(), cx))?
+            .await
+            .context("semantic tokens fetch")?;
+        Ok(proto::GetSemanticTokensResponse {
+            token_types: tokens.token_types.to_vec(),
+            token_modifiers: tokens.token_modifiers.to_vec(),
+            tokens: tokens
+                .tokens
+                .into_iter()
+                .map(|token| proto::SemanticToken {
+                    start: Some(serialize_anchor(&token.range.start)),
+                    end: Some(serialize_anchor(&token.range.end)),
+                    token_type: token.token_type,
+                    token_modifiers: token.token_modifiers,
+                })
+                .collect(),
+            version: serialize_version(&buffer.update(&mut cx, |buffer, _| buffer.version())?),
+        })
+    }
+
     async fn handle_open_buffer_for_symbol(
         this: Model<Self>,
         envelope: TypedEnvelope<proto::OpenBufferForSymbol>,
@@ -7123,6 +8003,9 @@ impl LspStore {
 
         self.language_server_statuses.remove(&server_id);
         let local = self.as_local_mut().unwrap();
+        local.diagnostic_result_ids.remove(&server_id);
+        local.lsp_diagnostics.remove(&server_id);
+        local.workspace_diagnostics_tasks.remove(&server_id);
         for diagnostics in local.diagnostics.values_mut() {
             diagnostics.retain(|_, diagnostics_by_server_id| {
                 if let Ok(ix) = diagnostics_by_server_id.binary_search_by_key(&server_id, |e| e.0) {
@@ -7237,6 +8120,33 @@ impl LspStore {
         .detach();
     }
 
+    /// Updates the diagnostics of a document with the ones a language server published or
+    /// reported when pulled, keeping the ones it sent the other way.
+    fn merge_lsp_diagnostics(
+        &mut self,
+        server_id: LanguageServerId,
+        mut params: lsp::PublishDiagnosticsParams,
+        pulled: bool,
+        disk_based_sources: &[String],
+        cx: &mut ModelContext<Self>,
+    ) -> Result<()> {
+        if let Some(local) = self.as_local_mut() {
+            let documents = local.lsp_diagnostics.entry(server_id).or_default();
+            let diagnostics = documents.entry(params.uri.clone()).or_default();
+            let (received, other) = if pulled {
+                (&mut diagnostics.pulled, &diagnostics.published)
+            } else {
+                (&mut diagnostics.published, &diagnostics.pulled)
+            };
+            received.clone_from(&params.diagnostics);
+            params.diagnostics.extend(other.iter().cloned());
+            if diagnostics.published.is_empty() && diagnostics.pulled.is_empty() {
+                documents.remove(&params.uri);
+            }
+        }
+        self.update_diagnostics(server_id, params, disk_based_sources, cx)
+    }
+
     pub fn update_diagnostics(
         &mut self,
         language_server_id: LanguageServerId,
@@ -7424,6 +8334,8 @@ impl LspStore {
             },
         );
 
+        // Links fetched before the server started may be missing the ones it provides.
+        self.document_links.clear();
         cx.emit(LspStoreEvent::LanguageServerAdded(
             server_id,
             language_server.name(),
@@ -7445,6 +8357,7 @@ impl LspStore {
         }
 
         // Tell the language server about every open buffer in the worktree that matches the language.
+        let mut opened_buffers = Vec::new();
         self.buffer_store.clone().update(cx, |buffer_store, cx| {
             for buffer_handle in buffer_store.buffers() {
                 let buffer = buffer_handle.read(cx);
@@ -7503,6 +8416,7 @@ impl LspStore {
                             },
                         )
                         .log_err();
+                    opened_buffers.push(buffer_handle.clone());
                 }
 
                 buffer_handle.update(cx, |buffer, cx| {
@@ -7525,6 +8439,11 @@ impl LspStore {
             }
         });
 
+        for buffer in &opened_buffers {
+            self.pull_document_diagnostics(buffer, false, cx);
+        }
+        self.pull_workspace_diagnostics(server_id, cx);
+
         cx.notify();
     }
 
@@ -7868,6 +8787,58 @@ impl LspStore {
         })
     }
 
+    pub(crate) fn serialize_code_lens(lens: &CodeLens) -> proto::CodeLens {
+        proto::CodeLens {
+            server_id: lens.server_id.0 as u64,
+            start: Some(serialize_anchor(&lens.range.start)),
+            end: Some(serialize_anchor(&lens.range.end)),
+            lsp_lens: serde_json::to_vec(&lens.lsp_lens).unwrap(),
+        }
+    }
+
+    pub(crate) fn deserialize_code_lens(lens: proto::CodeLens) -> Result<CodeLens> {
+        let start = lens
+            .start
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid start"))?;
+        let end = lens
+            .end
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid end"))?;
+        let lsp_lens = serde_json::from_slice(&lens.lsp_lens)?;
+        Ok(CodeLens {
+            server_id: LanguageServerId(lens.server_id as usize),
+            range: start..end,
+            lsp_lens,
+        })
+    }
+
+    pub(crate) fn serialize_document_link(link: &DocumentLink) -> proto::DocumentLink {
+        proto::DocumentLink {
+            server_id: link.server_id.0 as u64,
+            start: Some(serialize_anchor(&link.range.start)),
+            end: Some(serialize_anchor(&link.range.end)),
+            lsp_link: serde_json::to_vec(&link.lsp_link).unwrap(),
+        }
+    }
+
+    pub(crate) fn deserialize_document_link(link: proto::DocumentLink) -> Result<DocumentLink> {
+        let start = link
+            .start
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid start"))?;
+        let end = link
+            .end
+            .and_then(deserialize_anchor)
+            .ok_or_else(|| anyhow!("invalid end"))?;
+        let lsp_link = serde_json::from_slice(&link.lsp_link)?;
+        Ok(DocumentLink {
+            server_id: LanguageServerId(link.server_id as usize),
+            range: start..end,
+            lsp_link,
+        })
+    }
+
     fn update_last_formatting_failure<T>(&mut self, formatting_result: &anyhow::Result<T>) {
         match &formatting_result {
             Ok(_) => self.last_formatting_failure = None,
@@ -8641,6 +9612,118 @@ async fn populate_labels_for_symbols(
     }
 }
 
+/// Returns the token type and modifier names the server uses, and whether it can send deltas.
+fn semantic_tokens_legend(
+    capabilities: &lsp::ServerCapabilities,
+) -> Option<((Arc<[String]>, Arc<[String]>), bool)> {
+    let options = match capabilities.semantic_tokens_provider.as_ref()? {
+        lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(options) => options,
+        lsp::SemanticTokensServerCapabilities::SemanticTokensRegistrationOptions(options) => {
+            &options.semantic_tokens_options
+        }
+    };
+    let supports_delta = match options.full.as_ref()? {
+        lsp::SemanticTokensFullOptions::Bool(supported) => {
+            if !supported {
+                return None;
+            }
+            false
+        }
+        lsp::SemanticTokensFullOptions::Delta { delta } => delta.unwrap_or(false),
+    };
+    let token_types = options
+        .legend
+        .token_types
+        .iter()
+        .map(|token_type| token_type.as_str().to_string())
+        .collect();
+    let token_modifiers = options
+        .legend
+        .token_modifiers
+        .iter()
+        .map(|modifier| modifier.as_str().to_string())
+        .collect();
+    Some(((token_types, token_modifiers), supports_delta))
+}
+
+/// Applies the edits of a semantic tokens delta response. Edit offsets and counts are expressed
+/// in integers of the encoded data, five per token.
+fn apply_semantic_token_edits(
+    data: &mut Vec<lsp::SemanticToken>,
+    mut edits: Vec<lsp::SemanticTokensEdit>,
+) {
+    edits.sort_by_key(|edit| edit.start);
+    for edit in edits.into_iter().rev() {
+        let start = (edit.start as usize / 5).min(data.len());
+        let end = (start + edit.delete_count as usize / 5).min(data.len());
+        data.splice(start..end, edit.data.unwrap_or_default());
+    }
+}
+
+fn decode_semantic_tokens(
+    data: &[lsp::SemanticToken],
+    snapshot: &TextBufferSnapshot,
+) -> Vec<SemanticToken> {
+    let mut tokens = Vec::with_capacity(data.len());
+    let mut row = 0;
+    let mut column = 0;
+    for token in data {
+        if token.delta_line == 0 {
+            column += token.delta_start;
+        } else {
+            row += token.delta_line;
+            column = token.delta_start;
+        }
+        let start = snapshot.clip_point_utf16(Unclipped(PointUtf16::new(row, column)), Bias::Left);
+        let end = snapshot.clip_point_utf16(
+            Unclipped(PointUtf16::new(row, column + token.length)),
+            Bias::Left,
+        );
+        if start < end {
+            tokens.push(SemanticToken {
+                range: snapshot.anchor_after(start)..snapshot.anchor_before(end),
+                token_type: token.token_type,
+                token_modifiers: token.token_modifiers_bitset,
+            });
+        }
+    }
+    tokens
+}
+
+fn semantic_tokens_from_proto(response: proto::GetSemanticTokensResponse) -> SemanticTokens {
+    SemanticTokens {
+        token_types: response.token_types.into(),
+        token_modifiers: response.token_modifiers.into(),
+        tokens: response
+            .tokens
+            .into_iter()
+            .filter_map(|token| {
+                Some(SemanticToken {
+                    range: deserialize_anchor(token.start?)?..deserialize_anchor(token.end?)?,
+                    token_type: token.token_type,
+                    token_modifiers: token.token_modifiers,
+                })
+            })
+            .collect(),
+    }
+}
+
+/// Whether two document links, possibly from different responses, are the same link of a buffer.
+fn same_document_link(a: &DocumentLink, b: &DocumentLink) -> bool {
+    a.server_id == b.server_id
+        && a.lsp_link.range == b.lsp_link.range
+        && a.lsp_link.data == b.lsp_link.data
+}
+
+fn diagnostic_options(capabilities: &lsp::ServerCapabilities) -> Option<lsp::DiagnosticOptions> {
+    match capabilities.diagnostic_provider.as_ref()? {
+        lsp::DiagnosticServerCapabilities::Options(options) => Some(options.clone()),
+        lsp::DiagnosticServerCapabilities::RegistrationOptions(options) => {
+            Some(options.diagnostic_options.clone())
+        }
+    }
+}
+
 fn include_text(server: &lsp::LanguageServer) -> Option<bool> {
     match server.capabilities().text_document_sync.as_ref()? {
         lsp::TextDocumentSyncCapability::Kind(kind) => match *kind {
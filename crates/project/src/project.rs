@@ -1,6 +1,7 @@
 pub mod buffer_store;
 mod color_extractor;
 pub mod connection_manager;
+pub mod dap_store;
 pub mod debounced_delay;
 pub mod image_store;
 pub mod lsp_command;
@@ -29,6 +30,7 @@ use buffer_store::{BufferChangeSet, BufferStore, BufferStoreEvent};
 use client::{proto, Client, Collaborator, PendingEntitySubscription, TypedEnvelope, UserStore};
 use clock::ReplicaId;
 use collections::{BTreeSet, HashMap, HashSet};
+use dap_store::DapStore;
 use debounced_delay::DebouncedDelay;
 pub use environment::ProjectEnvironment;
 use futures::{
@@ -39,7 +41,13 @@ use futures::{
 pub use image_store::{ImageItem, ImageStore};
 use image_store::{ImageItemEvent, ImageStoreEvent};
 
-use git::{blame::Blame, repository::GitRepository};
+use git::{
+    blame::Blame,
+    rebase::{RebaseOutcome, RebaseTodoItem},
+    repository::{GitRepository, RemoteOperation},
+    stash::{StashAction, StashEntry, StashOptions},
+    worktree::GitWorktree,
+};
 use gpui::{
     AnyModel, AppContext, AsyncAppContext, BorrowAppContext, Context as _, EventEmitter, Hsla,
     Model, ModelContext, SharedString, Task, WeakModel, WindowContext,
@@ -60,7 +68,7 @@ use node_runtime::NodeRuntime;
 use parking_lot::Mutex;
 pub use prettier_store::PrettierStore;
 use project_settings::{ProjectSettings, SettingsObserver, SettingsObserverEvent};
-use remote::{SshConnectionOptions, SshRemoteClient};
+use remote::{AskPassDelegate, SshConnectionOptions, SshRemoteClient};
 use rpc::{
     proto::{LanguageServerPromptResponse, SSH_PROJECT_ID},
     AnyProtoClient, ErrorCode,
@@ -153,6 +161,7 @@ pub struct Project {
     buffer_store: Model<BufferStore>,
     image_store: Model<ImageStore>,
     lsp_store: Model<LspStore>,
+    dap_store: Model<DapStore>,
     _subscriptions: Vec<gpui::Subscription>,
     buffers_needing_diff: HashSet<WeakModel<Buffer>>,
     git_diff_debouncer: DebouncedDelay<Self>,
@@ -270,6 +279,8 @@ pub enum Event {
     Reshared,
     Rejoined,
     RefreshInlayHints,
+    RefreshCodeLens,
+    RefreshSemanticTokens,
     RevealInProjectPanel(ProjectEntryId),
     SnippetEdit(BufferId, Vec<(lsp::Range, Snippet)>),
 }
@@ -394,6 +405,30 @@ pub struct CodeAction {
     pub lsp_action: lsp::CodeAction,
 }
 
+/// A code lens provided by a language server.
+#[derive(Clone, Debug)]
+pub struct CodeLens {
+    /// The id of the language server that produced this code lens.
+    pub server_id: LanguageServerId,
+    /// The range of the buffer this code lens is about.
+    pub range: Range<Anchor>,
+    /// The raw code lens provided by the language server. Its command is only
+    /// present once the lens is resolved.
+    pub lsp_lens: lsp::CodeLens,
+}
+
+/// A link to another resource provided by a language server, such as a dependency in a manifest.
+#[derive(Clone, Debug)]
+pub struct DocumentLink {
+    /// The id of the language server that produced this link.
+    pub server_id: LanguageServerId,
+    /// The range of the buffer the link is attached to.
+    pub range: Range<Anchor>,
+    /// The raw link provided by the language server. Its target may only be present once the
+    /// link is resolved.
+    pub lsp_link: lsp::DocumentLink,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ResolveState {
     Resolved,
@@ -453,6 +488,82 @@ pub struct DocumentHighlight {
     pub kind: DocumentHighlightKind,
 }
 
+/// A symbol that can be used as a node in a call hierarchy.
+#[derive(Debug, Clone)]
+pub struct CallHierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    pub buffer: Model<Buffer>,
+    /// The range enclosing the whole symbol, e.g. the function including its body.
+    pub range: Range<language::Anchor>,
+    /// The range of the symbol's name.
+    pub selection_range: Range<language::Anchor>,
+    /// Opaque data the language server attached to the item, sent back with follow-up requests.
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CallHierarchyCall {
+    /// The caller for incoming calls, or the callee for outgoing calls.
+    pub item: CallHierarchyItem,
+    /// The locations of the calls, which always lie inside of the calling item.
+    pub call_sites: Vec<Location>,
+}
+
+/// A type that can be used as a node in a type hierarchy.
+#[derive(Debug, Clone)]
+pub struct TypeHierarchyItem {
+    pub name: String,
+    pub kind: lsp::SymbolKind,
+    pub detail: Option<String>,
+    pub buffer: Model<Buffer>,
+    /// The range enclosing the whole type definition.
+    pub range: Range<language::Anchor>,
+    /// The range of the type's name.
+    pub selection_range: Range<language::Anchor>,
+    /// Opaque data the language server attached to the item, sent back with follow-up requests.
+    pub data: Option<serde_json::Value>,
+}
+
+/// Semantic tokens of a buffer, decoded from the primary language server's response.
+#[derive(Debug, Clone, Default)]
+pub struct SemanticTokens {
+    /// The token type names from the server's legend, indexed by [`SemanticToken::token_type`].
+    pub token_types: Arc<[String]>,
+    /// The token modifier names from the server's legend, indexed by the bits of
+    /// [`SemanticToken::token_modifiers`].
+    pub token_modifiers: Arc<[String]>,
+    /// Non-overlapping tokens, sorted by their position in the buffer.
+    pub tokens: Vec<SemanticToken>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub range: Range<language::Anchor>,
+    pub token_type: u32,
+    pub token_modifiers: u32,
+}
+
+impl SemanticTokens {
+    pub fn token_type(&self, token: &SemanticToken) -> Option<&str> {
+        self.token_types
+            .get(token.token_type as usize)
+            .map(String::as_str)
+    }
+
+    pub fn token_modifiers<'a>(
+        &'a self,
+        token: &'a SemanticToken,
+    ) -> impl Iterator<Item = &'a str> + 'a {
+        self.token_modifiers
+            .iter()
+            .enumerate()
+            .filter(|(ix, _)| *ix < 32 && token.token_modifiers & (1 << ix) != 0)
+            .map(|(_, modifier)| modifier.as_str())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct Symbol {
     pub language_server_name: LanguageServerName,
@@ -678,6 +789,10 @@ impl Project {
             });
             cx.subscribe(&lsp_store, Self::on_lsp_store_event).detach();
 
+            let dap_store = cx.new_model(|cx| {
+                DapStore::new_local(worktree_store.clone(), buffer_store.clone(), cx)
+            });
+
             Self {
                 buffer_ordered_messages_tx: tx,
                 collaborators: Default::default(),
@@ -685,6 +800,7 @@ impl Project {
                 buffer_store,
                 image_store,
                 lsp_store,
+                dap_store,
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
                 client_subscriptions: Vec::new(),
@@ -796,6 +912,10 @@ impl Project {
             cx.subscribe(&ssh, Self::on_ssh_event).detach();
             cx.observe(&ssh, |_, _, cx| cx.notify()).detach();
 
+            let dap_store = cx.new_model(|cx| {
+                DapStore::new_remote(worktree_store.clone(), buffer_store.clone(), cx)
+            });
+
             let this = Self {
                 buffer_ordered_messages_tx: tx,
                 collaborators: Default::default(),
@@ -803,6 +923,7 @@ impl Project {
                 buffer_store,
                 image_store,
                 lsp_store,
+                dap_store,
                 join_project_response_message_id: 0,
                 client_state: ProjectClientState::Local,
                 client_subscriptions: Vec::new(),
@@ -861,6 +982,7 @@ impl Project {
             ssh_proto.add_model_request_handler(Self::handle_language_server_prompt_request);
             ssh_proto.add_model_message_handler(Self::handle_hide_toast);
             ssh_proto.add_model_request_handler(Self::handle_update_buffer_from_ssh);
+            ssh_proto.add_model_request_handler(WorktreeStore::handle_git_ask_pass);
             BufferStore::init(&ssh_proto);
             LspStore::init(&ssh_proto);
             SettingsObserver::init(&ssh_proto);
@@ -986,6 +1108,10 @@ impl Project {
             SettingsObserver::new_remote(worktree_store.clone(), task_store.clone(), cx)
         })?;
 
+        let dap_store = cx.new_model(|cx| {
+            DapStore::new_remote(worktree_store.clone(), buffer_store.clone(), cx)
+        })?;
+
         let this = cx.new_model(|cx| {
             let replica_id = response.payload.replica_id as ReplicaId;
 
@@ -1017,6 +1143,7 @@ impl Project {
                 image_store,
                 worktree_store: worktree_store.clone(),
                 lsp_store: lsp_store.clone(),
+                dap_store,
                 active_entry: None,
                 collaborators: Default::default(),
                 join_project_response_message_id: response.message_id,
@@ -1343,6 +1470,10 @@ impl Project {
         &self.task_store
     }
 
+    pub fn dap_store(&self) -> &Model<DapStore> {
+        &self.dap_store
+    }
+
     pub fn snippets(&self) -> &Model<SnippetProvider> {
         &self.snippets
     }
@@ -1901,6 +2032,21 @@ impl Project {
         })
     }
 
+    pub fn open_changes_since_revision(
+        &mut self,
+        buffer: Model<Buffer>,
+        revision: String,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Model<BufferChangeSet>>> {
+        if self.is_disconnected(cx) {
+            return Task::ready(Err(anyhow!(ErrorCode::Disconnected)));
+        }
+
+        self.buffer_store.update(cx, |buffer_store, cx| {
+            buffer_store.open_changes_since_revision(buffer, revision, cx)
+        })
+    }
+
     pub fn open_buffer_by_id(
         &mut self,
         id: BufferId,
@@ -2185,6 +2331,8 @@ impl Project {
                 };
             }
             LspStoreEvent::RefreshInlayHints => cx.emit(Event::RefreshInlayHints),
+            LspStoreEvent::RefreshCodeLens => cx.emit(Event::RefreshCodeLens),
+            LspStoreEvent::RefreshSemanticTokens => cx.emit(Event::RefreshSemanticTokens),
             LspStoreEvent::LanguageServerPrompt(prompt) => {
                 cx.emit(Event::LanguageServerPrompt(prompt.clone()))
             }
@@ -2733,6 +2881,88 @@ impl Project {
         )
     }
 
+    pub fn prepare_call_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            PrepareCallHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn incoming_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetIncomingCalls { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn outgoing_calls(
+        &mut self,
+        item: &CallHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CallHierarchyCall>>> {
+        self.request_lsp(
+            item.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetOutgoingCalls { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn prepare_type_hierarchy<T: ToPointUtf16>(
+        &mut self,
+        buffer: &Model<Buffer>,
+        position: T,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        let position = position.to_point_utf16(buffer.read(cx));
+        self.request_lsp(
+            buffer.clone(),
+            LanguageServerToQuery::Primary,
+            PrepareTypeHierarchy { position },
+            cx,
+        )
+    }
+
+    pub fn supertypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSupertypes { item: item.clone() },
+            cx,
+        )
+    }
+
+    pub fn subtypes(
+        &mut self,
+        item: &TypeHierarchyItem,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<TypeHierarchyItem>>> {
+        self.request_lsp(
+            item.buffer.clone(),
+            LanguageServerToQuery::Primary,
+            GetSubtypes { item: item.clone() },
+            cx,
+        )
+    }
+
     fn document_highlights_impl(
         &mut self,
         buffer: &Model<Buffer>,
@@ -2890,6 +3120,98 @@ impl Project {
         })
     }
 
+    pub fn code_lens(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<CodeLens>>> {
+        self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::Primary,
+            GetCodeLens,
+            cx,
+        )
+    }
+
+    /// Returns the folding ranges of the buffer from its primary language server, or `None` when
+    /// that server does not provide them.
+    pub fn folding_ranges(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Option<Vec<Range<Anchor>>>>> {
+        self.request_lsp(
+            buffer_handle.clone(),
+            LanguageServerToQuery::Primary,
+            GetFoldingRanges,
+            cx,
+        )
+    }
+
+    pub fn document_links(
+        &mut self,
+        buffer_handle: &Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<Vec<DocumentLink>>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.document_links(buffer_handle, cx)
+        })
+    }
+
+    pub fn resolve_document_link(
+        &self,
+        link: DocumentLink,
+        buffer_handle: Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<DocumentLink>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.resolve_document_link(link, buffer_handle, cx)
+        })
+    }
+
+    pub fn resolve_code_lens(
+        &self,
+        lens: CodeLens,
+        buffer_handle: Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<CodeLens>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.resolve_code_lens(lens, buffer_handle, cx)
+        })
+    }
+
+    /// Runs the command of a resolved code lens through `workspace/executeCommand`, applying any
+    /// workspace edits the language server sends back while handling it. Fails if the server does
+    /// not list the command among the ones it executes.
+    pub fn run_code_lens(
+        &self,
+        buffer_handle: Model<Buffer>,
+        lens: CodeLens,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<ProjectTransaction>> {
+        let Some(command) = lens.lsp_lens.command else {
+            return Task::ready(Err(anyhow!("code lens has no command")));
+        };
+        if let Some(server) = self.language_server_for_id(lens.server_id, cx) {
+            if !GetCodeLens::can_execute_command(&server.capabilities(), &command) {
+                return Task::ready(Err(anyhow!(
+                    "language server does not handle the {:?} command",
+                    command.command
+                )));
+            }
+        }
+        let action = CodeAction {
+            server_id: lens.server_id,
+            range: lens.range,
+            lsp_action: lsp::CodeAction {
+                title: command.title.clone(),
+                command: Some(command),
+                ..Default::default()
+            },
+        };
+        self.apply_code_action(buffer_handle, action, true, cx)
+    }
+
     fn prepare_rename_impl(
         &mut self,
         buffer: Model<Buffer>,
@@ -2983,6 +3305,16 @@ impl Project {
         })
     }
 
+    pub fn semantic_tokens(
+        &mut self,
+        buffer_handle: Model<Buffer>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<anyhow::Result<SemanticTokens>> {
+        self.lsp_store.update(cx, |lsp_store, cx| {
+            lsp_store.semantic_tokens(buffer_handle, cx)
+        })
+    }
+
     pub fn search(
         &mut self,
         query: SearchQuery,
@@ -3552,6 +3884,224 @@ impl Project {
             .update_or_create_branch(repository, new_branch, cx)
     }
 
+    pub fn staged_paths(
+        &self,
+        worktree_id: WorktreeId,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<Arc<Path>>>> {
+        self.worktree_store().read(cx).staged_paths(worktree_id, cx)
+    }
+
+    pub fn stage_paths(
+        &self,
+        worktree_id: WorktreeId,
+        paths: Vec<Arc<Path>>,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        self.worktree_store()
+            .read(cx)
+            .stage_paths(worktree_id, paths, cx)
+    }
+
+    pub fn unstage_paths(
+        &self,
+        worktree_id: WorktreeId,
+        paths: Vec<Arc<Path>>,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        self.worktree_store()
+            .read(cx)
+            .unstage_paths(worktree_id, paths, cx)
+    }
+
+    pub fn discard_paths(
+        &self,
+        worktree_id: WorktreeId,
+        paths: Vec<Arc<Path>>,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        self.worktree_store()
+            .read(cx)
+            .discard_paths(worktree_id, paths, cx)
+    }
+
+    pub fn commit(
+        &self,
+        repository: ProjectPath,
+        message: String,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        self.worktree_store()
+            .read(cx)
+            .commit(repository, message, cx)
+    }
+
+    /// Fetches, pulls or pushes the repository at `repository`, prompting for credentials with
+    /// `askpass`.
+    pub fn git_remote_operation(
+        &mut self,
+        repository: ProjectPath,
+        operation: RemoteOperation,
+        askpass: Arc<dyn AskPassDelegate>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.worktree_store.update(cx, |worktree_store, cx| {
+            worktree_store.git_remote_operation(repository, operation, askpass, cx)
+        })
+    }
+
+    pub fn stash_list(
+        &self,
+        repository: ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<StashEntry>>> {
+        self.worktree_store().read(cx).stash_list(repository, cx)
+    }
+
+    pub fn stash_push(
+        &self,
+        repository: ProjectPath,
+        message: Option<String>,
+        options: StashOptions,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        self.worktree_store()
+            .read(cx)
+            .stash_push(repository, message, options, cx)
+    }
+
+    pub fn stash_action(
+        &self,
+        repository: ProjectPath,
+        action: StashAction,
+        index: usize,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        self.worktree_store()
+            .read(cx)
+            .stash_action(repository, action, index, cx)
+    }
+
+    pub fn rebase_todo(
+        &self,
+        repository: ProjectPath,
+        base: String,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<RebaseTodoItem>>> {
+        self.worktree_store()
+            .read(cx)
+            .rebase_todo(repository, base, cx)
+    }
+
+    pub fn rebase(
+        &self,
+        repository: ProjectPath,
+        base: String,
+        todo: Vec<RebaseTodoItem>,
+        cx: &AppContext,
+    ) -> Task<Result<RebaseOutcome>> {
+        self.worktree_store()
+            .read(cx)
+            .rebase(repository, base, todo, cx)
+    }
+
+    pub fn continue_rebase(
+        &self,
+        repository: ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<RebaseOutcome>> {
+        self.worktree_store()
+            .read(cx)
+            .continue_or_abort_rebase(repository, false, cx)
+    }
+
+    pub fn abort_rebase(&self, repository: ProjectPath, cx: &AppContext) -> Task<Result<()>> {
+        let task = self
+            .worktree_store()
+            .read(cx)
+            .continue_or_abort_rebase(repository, true, cx);
+        cx.background_executor().spawn(async move {
+            task.await?;
+            Ok(())
+        })
+    }
+
+    pub fn git_worktrees(
+        &self,
+        repository: ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<GitWorktree>>> {
+        self.worktree_store().read(cx).git_worktrees(repository, cx)
+    }
+
+    pub fn create_git_worktree(
+        &self,
+        repository: ProjectPath,
+        path: PathBuf,
+        revision: String,
+        new_branch: Option<String>,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        self.worktree_store()
+            .read(cx)
+            .create_git_worktree(repository, path, revision, new_branch, cx)
+    }
+
+    pub fn remove_git_worktree(
+        &self,
+        repository: ProjectPath,
+        path: PathBuf,
+        force: bool,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        self.worktree_store()
+            .read(cx)
+            .remove_git_worktree(repository, path, force, cx)
+    }
+
+    pub fn git_log(
+        &self,
+        path: ProjectPath,
+        restrict_to_path: bool,
+        options: git::commit::LogOptions,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<git::commit::CommitSummary>>> {
+        self.worktree_store()
+            .read(cx)
+            .git_log(path, restrict_to_path, options, cx)
+    }
+
+    pub fn commit_diff(
+        &self,
+        path: ProjectPath,
+        sha: git::Oid,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<git::commit::CommitFileDiff>>> {
+        self.worktree_store().read(cx).commit_diff(path, sha, cx)
+    }
+
+    pub fn commit_message(
+        &self,
+        path: ProjectPath,
+        revision: String,
+        cx: &AppContext,
+    ) -> Task<Result<String>> {
+        self.worktree_store()
+            .read(cx)
+            .commit_message(path, revision, cx)
+    }
+
+    pub fn changed_paths_since(
+        &self,
+        path: ProjectPath,
+        revision: String,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<ProjectPath>>> {
+        self.worktree_store()
+            .read(cx)
+            .changed_paths_since(path, revision, cx)
+    }
+
     pub fn blame_buffer(
         &self,
         buffer: &Model<Buffer>,
@@ -3561,6 +4111,17 @@ impl Project {
         self.buffer_store.read(cx).blame_buffer(buffer, version, cx)
     }
 
+    pub fn set_index_text(
+        &mut self,
+        buffer: &Model<Buffer>,
+        text: Option<String>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        self.buffer_store.update(cx, |buffer_store, cx| {
+            buffer_store.set_index_text(buffer, text, cx)
+        })
+    }
+
     pub fn get_permalink_to_line(
         &self,
         buffer: &Model<Buffer>,
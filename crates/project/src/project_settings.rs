@@ -1,5 +1,6 @@
 use anyhow::Context;
 use collections::HashMap;
+use dap::{DebugAdapterBinary, DebugAdapterConfig, DebugAdapterName};
 use fs::Fs;
 use gpui::{AppContext, AsyncAppContext, BorrowAppContext, EventEmitter, Model, ModelContext};
 use lsp::LanguageServerName;
@@ -56,6 +57,24 @@ pub struct ProjectSettings {
     /// Configuration for session-related features
     #[serde(default)]
     pub session: SessionSettings,
+
+    /// Configuration for debug adapters and debug sessions
+    #[serde(default)]
+    pub dap: DapSettings,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DapSettings {
+    /// The debug adapters that can be started, keyed by name.
+    ///
+    /// Default: {}
+    #[serde(default)]
+    pub adapters: HashMap<DebugAdapterName, DebugAdapterBinary>,
+    /// The debug configurations offered by the debugger panel.
+    ///
+    /// Default: []
+    #[serde(default)]
+    pub configurations: Vec<DebugAdapterConfig>,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
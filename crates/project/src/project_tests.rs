@@ -2632,6 +2632,794 @@ async fn test_definition(cx: &mut gpui::TestAppContext) {
     }
 }
 
+#[gpui::test]
+async fn test_call_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn a() {\n    b();\n}\nfn b() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                call_hierarchy_provider: Some(lsp::CallHierarchyServerCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp("/dir/a.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    let uri = lsp::Url::from_file_path("/dir/a.rs").unwrap();
+    let lsp_item = |name: &str, line: u32| lsp::CallHierarchyItem {
+        name: name.to_string(),
+        kind: lsp::SymbolKind::FUNCTION,
+        tags: None,
+        detail: None,
+        uri: uri.clone(),
+        range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line, 9)),
+        selection_range: lsp::Range::new(lsp::Position::new(line, 3), lsp::Position::new(line, 4)),
+        data: Some(json!({ "name": name })),
+    };
+    let item_a = lsp_item("a", 0);
+    let item_b = lsp_item("b", 3);
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::CallHierarchyPrepare, _, _>({
+        let item_b = item_b.clone();
+        move |params, _| {
+            let item_b = item_b.clone();
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(3, 3)
+                );
+                Ok(Some(vec![item_b]))
+            }
+        }
+    });
+    fake_server.handle_request::<lsp::request::CallHierarchyIncomingCalls, _, _>({
+        let item_a = item_a.clone();
+        move |params, _| {
+            let item_a = item_a.clone();
+            async move {
+                assert_eq!(params.item.name, "b");
+                assert_eq!(params.item.data, Some(json!({ "name": "b" })));
+                Ok(Some(vec![lsp::CallHierarchyIncomingCall {
+                    from: item_a,
+                    from_ranges: vec![lsp::Range::new(
+                        lsp::Position::new(1, 4),
+                        lsp::Position::new(1, 5),
+                    )],
+                }]))
+            }
+        }
+    });
+    fake_server.handle_request::<lsp::request::CallHierarchyOutgoingCalls, _, _>({
+        let item_b = item_b.clone();
+        move |params, _| {
+            let item_b = item_b.clone();
+            async move {
+                assert_eq!(params.item.name, "a");
+                Ok(Some(vec![lsp::CallHierarchyOutgoingCall {
+                    to: item_b,
+                    from_ranges: vec![lsp::Range::new(
+                        lsp::Position::new(1, 4),
+                        lsp::Position::new(1, 5),
+                    )],
+                }]))
+            }
+        }
+    });
+
+    let mut items = project
+        .update(cx, |project, cx| {
+            project.prepare_call_hierarchy(&buffer, Point::new(3, 3), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    let item = items.pop().unwrap();
+    assert_eq!(item.name, "b");
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(
+            item.selection_range.to_point(buffer),
+            Point::new(3, 3)..Point::new(3, 4)
+        );
+    });
+
+    let mut incoming_calls = project
+        .update(cx, |project, cx| project.incoming_calls(&item, cx))
+        .await
+        .unwrap();
+    assert_eq!(incoming_calls.len(), 1);
+    let incoming_call = incoming_calls.pop().unwrap();
+    assert_eq!(incoming_call.item.name, "a");
+    buffer.read_with(cx, |buffer, _| {
+        let call_sites = incoming_call
+            .call_sites
+            .iter()
+            .map(|location| location.range.to_point(buffer))
+            .collect::<Vec<_>>();
+        assert_eq!(call_sites, [Point::new(1, 4)..Point::new(1, 5)]);
+    });
+
+    let outgoing_calls = project
+        .update(cx, |project, cx| {
+            project.outgoing_calls(&incoming_call.item, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        outgoing_calls
+            .iter()
+            .map(|call| call.item.name.as_str())
+            .collect::<Vec<_>>(),
+        ["b"]
+    );
+}
+
+#[gpui::test]
+async fn test_type_hierarchy(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "trait A {}\nstruct B;\nimpl A for B {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: serde_json::from_value(json!({ "typeHierarchyProvider": true })).unwrap(),
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp("/dir/a.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    let uri = lsp::Url::from_file_path("/dir/a.rs").unwrap();
+    let lsp_item =
+        |name: &str, kind: lsp::SymbolKind, line: u32, column: u32| lsp::TypeHierarchyItem {
+            name: name.to_string(),
+            kind,
+            tags: None,
+            detail: None,
+            uri: uri.clone(),
+            range: lsp::Range::new(lsp::Position::new(line, 0), lsp::Position::new(line, 10)),
+            selection_range: lsp::Range::new(
+                lsp::Position::new(line, column),
+                lsp::Position::new(line, column + 1),
+            ),
+            data: None,
+        };
+    let item_a = lsp_item("A", lsp::SymbolKind::INTERFACE, 0, 6);
+    let item_b = lsp_item("B", lsp::SymbolKind::STRUCT, 1, 7);
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::TypeHierarchyPrepare, _, _>({
+        let item_b = item_b.clone();
+        move |params, _| {
+            let item_b = item_b.clone();
+            async move {
+                assert_eq!(
+                    params.text_document_position_params.position,
+                    lsp::Position::new(1, 7)
+                );
+                Ok(Some(vec![item_b]))
+            }
+        }
+    });
+    fake_server.handle_request::<lsp::request::TypeHierarchySupertypes, _, _>({
+        let item_a = item_a.clone();
+        move |params, _| {
+            let item_a = item_a.clone();
+            async move {
+                assert_eq!(params.item.name, "B");
+                Ok(Some(vec![item_a]))
+            }
+        }
+    });
+    fake_server.handle_request::<lsp::request::TypeHierarchySubtypes, _, _>({
+        let item_b = item_b.clone();
+        move |params, _| {
+            let item_b = item_b.clone();
+            async move {
+                assert_eq!(params.item.name, "A");
+                Ok(Some(vec![item_b]))
+            }
+        }
+    });
+
+    let mut items = project
+        .update(cx, |project, cx| {
+            project.prepare_type_hierarchy(&buffer, Point::new(1, 7), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 1);
+    let item = items.pop().unwrap();
+    assert_eq!(item.name, "B");
+
+    let mut supertypes = project
+        .update(cx, |project, cx| project.supertypes(&item, cx))
+        .await
+        .unwrap();
+    assert_eq!(supertypes.len(), 1);
+    let supertype = supertypes.pop().unwrap();
+    assert_eq!(supertype.name, "A");
+    assert_eq!(supertype.kind, lsp::SymbolKind::INTERFACE);
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(
+            supertype.selection_range.to_point(buffer),
+            Point::new(0, 6)..Point::new(0, 7)
+        );
+    });
+
+    let subtypes = project
+        .update(cx, |project, cx| project.subtypes(&supertype, cx))
+        .await
+        .unwrap();
+    assert_eq!(
+        subtypes
+            .iter()
+            .map(|item| item.name.as_str())
+            .collect::<Vec<_>>(),
+        ["B"]
+    );
+}
+
+#[gpui::test]
+async fn test_semantic_tokens(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn main() {\n    let mut x = 1;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                semantic_tokens_provider: Some(
+                    lsp::SemanticTokensServerCapabilities::SemanticTokensOptions(
+                        lsp::SemanticTokensOptions {
+                            legend: lsp::SemanticTokensLegend {
+                                token_types: vec![
+                                    lsp::SemanticTokenType::FUNCTION,
+                                    lsp::SemanticTokenType::VARIABLE,
+                                ],
+                                token_modifiers: vec![lsp::SemanticTokenModifier::new("mutable")],
+                            },
+                            full: Some(lsp::SemanticTokensFullOptions::Delta { delta: Some(true) }),
+                            ..Default::default()
+                        },
+                    ),
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp("/dir/a.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::SemanticTokensFullRequest, _, _>(
+        |_, _| async move {
+            Ok(Some(lsp::SemanticTokensResult::Tokens(
+                lsp::SemanticTokens {
+                    result_id: Some("1".to_string()),
+                    data: vec![
+                        lsp::SemanticToken {
+                            delta_line: 0,
+                            delta_start: 3,
+                            length: 4,
+                            token_type: 0,
+                            token_modifiers_bitset: 0,
+                        },
+                        lsp::SemanticToken {
+                            delta_line: 1,
+                            delta_start: 12,
+                            length: 1,
+                            token_type: 1,
+                            token_modifiers_bitset: 1,
+                        },
+                    ],
+                },
+            )))
+        },
+    );
+    fake_server.handle_request::<lsp::request::SemanticTokensFullDeltaRequest, _, _>(
+        |params, _| async move {
+            assert_eq!(params.previous_result_id, "1");
+            Ok(Some(lsp::SemanticTokensFullDeltaResult::TokensDelta(
+                lsp::SemanticTokensDelta {
+                    result_id: Some("2".to_string()),
+                    edits: vec![lsp::SemanticTokensEdit {
+                        start: 10,
+                        delete_count: 0,
+                        data: Some(vec![lsp::SemanticToken {
+                            delta_line: 1,
+                            delta_start: 4,
+                            length: 1,
+                            token_type: 1,
+                            token_modifiers_bitset: 1,
+                        }]),
+                    }],
+                },
+            )))
+        },
+    );
+
+    let describe_tokens = |tokens: SemanticTokens, cx: &mut gpui::TestAppContext| {
+        buffer.read_with(cx, |buffer, _| {
+            tokens
+                .tokens
+                .iter()
+                .map(|token| {
+                    (
+                        token.range.to_point(buffer),
+                        tokens.token_type(token).unwrap().to_string(),
+                        tokens.token_modifiers(token).collect::<Vec<_>>().join(","),
+                    )
+                })
+                .collect::<Vec<_>>()
+        })
+    };
+
+    let tokens = project
+        .update(cx, |project, cx| {
+            project.semantic_tokens(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        describe_tokens(tokens, cx),
+        [
+            (
+                Point::new(0, 3)..Point::new(0, 7),
+                "function".to_string(),
+                String::new()
+            ),
+            (
+                Point::new(1, 12)..Point::new(1, 13),
+                "variable".to_string(),
+                "mutable".to_string()
+            ),
+        ]
+    );
+
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit(
+            [(Point::new(2, 0)..Point::new(2, 0), "    x += 1;\n")],
+            None,
+            cx,
+        )
+    });
+    let tokens = project
+        .update(cx, |project, cx| {
+            project.semantic_tokens(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        describe_tokens(tokens, cx),
+        [
+            (
+                Point::new(0, 3)..Point::new(0, 7),
+                "function".to_string(),
+                String::new()
+            ),
+            (
+                Point::new(1, 12)..Point::new(1, 13),
+                "variable".to_string(),
+                "mutable".to_string()
+            ),
+            (
+                Point::new(2, 4)..Point::new(2, 5),
+                "variable".to_string(),
+                "mutable".to_string()
+            ),
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_code_lens(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn main() {}\n\n#[test]\nfn test_main() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                code_lens_provider: Some(lsp::CodeLensOptions {
+                    resolve_provider: Some(true),
+                }),
+                execute_command_provider: Some(lsp::ExecuteCommandOptions {
+                    commands: vec!["run".to_string()],
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp("/dir/a.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::CodeLensRequest, _, _>(|_, _| async move {
+        Ok(Some(vec![
+            lsp::CodeLens {
+                range: lsp::Range::new(lsp::Position::new(3, 3), lsp::Position::new(3, 12)),
+                command: None,
+                data: Some(json!({ "test": "test_main" })),
+            },
+            lsp::CodeLens {
+                range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 7)),
+                command: Some(lsp::Command {
+                    title: "Run".to_string(),
+                    command: "run".to_string(),
+                    arguments: Some(vec![json!("main")]),
+                }),
+                data: None,
+            },
+            lsp::CodeLens {
+                range: lsp::Range::new(lsp::Position::new(0, 3), lsp::Position::new(0, 7)),
+                command: Some(lsp::Command {
+                    title: "Debug".to_string(),
+                    command: "client.debug".to_string(),
+                    arguments: None,
+                }),
+                data: None,
+            },
+        ]))
+    });
+    fake_server.handle_request::<lsp::request::CodeLensResolve, _, _>(|lens, _| async move {
+        let test_name = lens.data.as_ref().unwrap()["test"].clone();
+        Ok(lsp::CodeLens {
+            command: Some(lsp::Command {
+                title: "Run Test".to_string(),
+                command: "run".to_string(),
+                arguments: Some(vec![test_name]),
+            }),
+            ..lens
+        })
+    });
+
+    let lenses = project
+        .update(cx, |project, cx| project.code_lens(&buffer, cx))
+        .await
+        .unwrap();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(
+            lenses
+                .iter()
+                .map(|lens| (lens.range.to_point(buffer), lens.lsp_lens.command.is_some()))
+                .collect::<Vec<_>>(),
+            [
+                (Point::new(0, 3)..Point::new(0, 7), true),
+                (Point::new(3, 3)..Point::new(3, 12), false),
+            ]
+        );
+    });
+
+    let lens = project
+        .update(cx, |project, cx| {
+            project.resolve_code_lens(lenses[1].clone(), buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(lens.lsp_lens.command.as_ref().unwrap().title, "Run Test");
+
+    // Commands that the server does not list can't be run through it.
+    let mut client_lens = lens.clone();
+    client_lens.lsp_lens.command.as_mut().unwrap().command = "client.debug".to_string();
+    project
+        .update(cx, |project, cx| {
+            project.run_code_lens(buffer.clone(), client_lens, cx)
+        })
+        .await
+        .unwrap_err();
+
+    let mut executed_commands =
+        fake_server.handle_request::<lsp::request::ExecuteCommand, _, _>(|params, _| async move {
+            assert_eq!(params.command, "run");
+            assert_eq!(params.arguments, [json!("test_main")]);
+            Ok(None)
+        });
+    project
+        .update(cx, |project, cx| {
+            project.run_code_lens(buffer.clone(), lens, cx)
+        })
+        .await
+        .unwrap();
+    executed_commands.next().await.unwrap();
+}
+
+#[gpui::test]
+async fn test_pulled_diagnostics(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn main() {}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                diagnostic_provider: Some(lsp::DiagnosticServerCapabilities::Options(
+                    lsp::DiagnosticOptions::default(),
+                )),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp("/dir/a.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    let previous_result_ids = Arc::new(Mutex::new(Vec::new()));
+    fake_server.handle_request::<lsp::request::DocumentDiagnosticRequest, _, _>({
+        let previous_result_ids = previous_result_ids.clone();
+        move |params, _| {
+            previous_result_ids
+                .lock()
+                .push(params.previous_result_id.clone());
+            async move {
+                let report = if params.previous_result_id.is_some() {
+                    lsp::DocumentDiagnosticReport::Unchanged(
+                        lsp::RelatedUnchangedDocumentDiagnosticReport {
+                            related_documents: None,
+                            unchanged_document_diagnostic_report:
+                                lsp::UnchangedDocumentDiagnosticReport {
+                                    result_id: "1".to_string(),
+                                },
+                        },
+                    )
+                } else {
+                    lsp::DocumentDiagnosticReport::Full(lsp::RelatedFullDocumentDiagnosticReport {
+                        related_documents: None,
+                        full_document_diagnostic_report: lsp::FullDocumentDiagnosticReport {
+                            result_id: Some("1".to_string()),
+                            items: vec![lsp::Diagnostic {
+                                range: lsp::Range::new(
+                                    lsp::Position::new(0, 3),
+                                    lsp::Position::new(0, 7),
+                                ),
+                                severity: Some(lsp::DiagnosticSeverity::WARNING),
+                                message: "unused function".to_string(),
+                                ..Default::default()
+                            }],
+                        },
+                    })
+                };
+                Ok(lsp::DocumentDiagnosticReportResult::Report(report))
+            }
+        }
+    });
+
+    let diagnostics = |cx: &mut gpui::TestAppContext| {
+        buffer.read_with(cx, |buffer, _| {
+            buffer
+                .snapshot()
+                .diagnostics_in_range::<_, Point>(0..buffer.len(), false)
+                .map(|entry| (entry.range, entry.diagnostic.message))
+                .collect::<Vec<_>>()
+        })
+    };
+
+    // Editing the buffer pulls its diagnostics once the edits settle.
+    buffer.update(cx, |buffer, cx| buffer.edit([(13..13, "\n")], None, cx));
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(
+        diagnostics(cx),
+        [(
+            Point::new(0, 3)..Point::new(0, 7),
+            "unused function".to_string()
+        )]
+    );
+
+    // The next pull sends back the result id, and an unchanged report keeps the diagnostics.
+    buffer.update(cx, |buffer, cx| buffer.edit([(14..14, "\n")], None, cx));
+    cx.executor().advance_clock(Duration::from_secs(1));
+    cx.executor().run_until_parked();
+    assert_eq!(
+        previous_result_ids.lock().last().cloned(),
+        Some(Some("1".to_string()))
+    );
+    assert_eq!(
+        diagnostics(cx),
+        [(
+            Point::new(0, 3)..Point::new(0, 7),
+            "unused function".to_string()
+        )]
+    );
+
+    // Published diagnostics don't replace the pulled ones.
+    fake_server.notify::<lsp::notification::PublishDiagnostics>(lsp::PublishDiagnosticsParams {
+        uri: lsp::Url::from_file_path("/dir/a.rs").unwrap(),
+        version: None,
+        diagnostics: vec![lsp::Diagnostic {
+            range: lsp::Range::new(lsp::Position::new(0, 0), lsp::Position::new(0, 2)),
+            severity: Some(lsp::DiagnosticSeverity::ERROR),
+            message: "published error".to_string(),
+            ..Default::default()
+        }],
+    });
+    cx.executor().run_until_parked();
+    assert_eq!(
+        diagnostics(cx),
+        [
+            (
+                Point::new(0, 0)..Point::new(0, 2),
+                "published error".to_string()
+            ),
+            (
+                Point::new(0, 3)..Point::new(0, 7),
+                "unused function".to_string()
+            )
+        ]
+    );
+}
+
+#[gpui::test]
+async fn test_folding_ranges(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "a.rs": "fn main() {\n    let x = [\n        1,\n    ];\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+
+    let language_registry = project.read_with(cx, |project, _| project.languages().clone());
+    language_registry.add(rust_lang());
+    let mut fake_servers = language_registry.register_fake_lsp(
+        "Rust",
+        FakeLspAdapter {
+            capabilities: lsp::ServerCapabilities {
+                folding_range_provider: Some(lsp::FoldingRangeProviderCapability::Simple(true)),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    );
+
+    let (buffer, _handle) = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer_with_lsp("/dir/a.rs", cx)
+        })
+        .await
+        .unwrap();
+
+    let fake_server = fake_servers.next().await.unwrap();
+    fake_server.handle_request::<lsp::request::FoldingRangeRequest, _, _>(|_, _| async move {
+        Ok(Some(vec![
+            lsp::FoldingRange {
+                start_line: 1,
+                end_line: 2,
+                ..Default::default()
+            },
+            lsp::FoldingRange {
+                start_line: 0,
+                start_character: Some(11),
+                end_line: 4,
+                end_character: Some(0),
+                ..Default::default()
+            },
+            lsp::FoldingRange {
+                start_line: 3,
+                end_line: 3,
+                ..Default::default()
+            },
+        ]))
+    });
+
+    let ranges = project
+        .update(cx, |project, cx| project.folding_ranges(&buffer, cx))
+        .await
+        .unwrap()
+        .unwrap();
+    buffer.read_with(cx, |buffer, _| {
+        assert_eq!(
+            ranges
+                .iter()
+                .map(|range| range.to_point(buffer))
+                .collect::<Vec<_>>(),
+            [
+                Point::new(0, 11)..Point::new(4, 0),
+                Point::new(1, 13)..Point::new(2, 10),
+            ]
+        );
+    });
+}
+
 #[gpui::test]
 async fn test_completions_without_edit_ranges(cx: &mut gpui::TestAppContext) {
     init_test(cx);
@@ -5652,6 +6440,318 @@ async fn test_unstaged_changes_for_buffer(cx: &mut gpui::TestAppContext) {
     });
 }
 
+#[gpui::test]
+async fn test_changes_since_revision(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            ".git": {},
+            "src": {
+                "main.rs": "one\ntwo\nthree\n",
+                "lib.rs": "",
+            }
+        }),
+    )
+    .await;
+    fs.set_index_for_repo(
+        Path::new("/dir/.git"),
+        &[(Path::new("src/main.rs"), "one\ntwo\nthree\n".into())],
+    );
+    fs.set_revision_for_repo(
+        Path::new("/dir/.git"),
+        "HEAD~2",
+        &[(Path::new("src/main.rs"), "one\nthree\n".into())],
+    );
+
+    let project = Project::test(fs.clone(), ["/dir".as_ref()], cx).await;
+    let worktree_id = project.update(cx, |project, cx| {
+        project.worktrees(cx).next().unwrap().read(cx).id()
+    });
+
+    let changed_paths = project
+        .update(cx, |project, cx| {
+            project.changed_paths_since(ProjectPath::root_path(worktree_id), "HEAD~2".into(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        changed_paths,
+        [ProjectPath {
+            worktree_id,
+            path: Path::new("src/main.rs").into(),
+        }]
+    );
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/src/main.rs", cx)
+        })
+        .await
+        .unwrap();
+    let revision_changes = project
+        .update(cx, |project, cx| {
+            project.open_changes_since_revision(buffer.clone(), "HEAD~2".into(), cx)
+        })
+        .await
+        .unwrap();
+    let unstaged_changes = project
+        .update(cx, |project, cx| {
+            project.open_unstaged_changes(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+
+    cx.run_until_parked();
+    revision_changes.update(cx, |revision_changes, cx| {
+        assert_eq!(revision_changes.base_revision.as_deref(), Some("HEAD~2"));
+        let snapshot = buffer.read(cx).snapshot();
+        assert_hunks(
+            revision_changes.diff_hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &snapshot),
+            &snapshot,
+            &revision_changes.base_text.as_ref().unwrap().read(cx).text(),
+            &[(1..2, "", "two\n")],
+        );
+    });
+
+    // Both change sets are updated as the buffer is edited.
+    buffer.update(cx, |buffer, cx| buffer.edit([(0..3, "ONE")], None, cx));
+    cx.run_until_parked();
+    revision_changes.update(cx, |revision_changes, cx| {
+        let snapshot = buffer.read(cx).snapshot();
+        assert_hunks(
+            revision_changes.diff_hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &snapshot),
+            &snapshot,
+            &revision_changes.base_text.as_ref().unwrap().read(cx).text(),
+            &[(0..2, "one\n", "ONE\ntwo\n")],
+        );
+    });
+    unstaged_changes.update(cx, |unstaged_changes, cx| {
+        assert_eq!(unstaged_changes.base_revision, None);
+        let snapshot = buffer.read(cx).snapshot();
+        assert_hunks(
+            unstaged_changes.diff_hunks_intersecting_range(Anchor::MIN..Anchor::MAX, &snapshot),
+            &snapshot,
+            &unstaged_changes.base_text.as_ref().unwrap().read(cx).text(),
+            &[(0..1, "one\n", "ONE\n")],
+        );
+    });
+
+    let unknown_revision = project
+        .update(cx, |project, cx| {
+            project.open_changes_since_revision(buffer.clone(), "v1.0".into(), cx)
+        })
+        .await;
+    assert!(unknown_revision.is_err());
+}
+
+#[gpui::test]
+async fn test_debug_session_breakpoints(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree(
+        "/dir",
+        json!({
+            "main.rs": "fn main() {\n    let a = 1;\n    let b = 2;\n}\n",
+        }),
+    )
+    .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let dap_store = project.read_with(cx, |project, _| project.dap_store().clone());
+    let mut fake_adapters = dap_store.update(cx, |dap_store, _| {
+        dap_store.register_fake_adapter(
+            "fake-adapter".into(),
+            dap::Capabilities {
+                supports_configuration_done_request: Some(true),
+                ..Default::default()
+            },
+        )
+    });
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_local_buffer("/dir/main.rs", cx)
+        })
+        .await
+        .unwrap();
+    dap_store.update(cx, |dap_store, cx| {
+        let position = buffer.read(cx).anchor_before(Point::new(1, 4));
+        dap_store.toggle_breakpoint(&buffer, position, cx);
+    });
+
+    let start_session = dap_store.update(cx, |dap_store, cx| {
+        dap_store.start_session(
+            dap::DebugAdapterConfig {
+                label: "main".into(),
+                adapter: "fake-adapter".into(),
+                request: dap::DebugRequestType::Launch,
+                arguments: json!({ "program": "main" }),
+                cwd: None,
+            },
+            cx,
+        )
+    });
+    let fake_adapter = fake_adapters.next().await.unwrap();
+
+    let (breakpoints_tx, mut breakpoints_rx) = futures::channel::mpsc::unbounded();
+    fake_adapter.handle_request::<dap::requests::SetBreakpoints, _, _>(move |arguments, _| {
+        let breakpoints_tx = breakpoints_tx.clone();
+        async move {
+            let lines = arguments
+                .breakpoints
+                .unwrap_or_default()
+                .into_iter()
+                .map(|breakpoint| breakpoint.line)
+                .collect::<Vec<_>>();
+            breakpoints_tx
+                .unbounded_send((arguments.source.path.unwrap(), lines))
+                .unwrap();
+            Ok(dap::SetBreakpointsResponse {
+                breakpoints: Vec::new(),
+            })
+        }
+    });
+    fake_adapter.handle_request::<dap::requests::Threads, _, _>(|_, _| async move {
+        Ok(dap::ThreadsResponse {
+            threads: vec![dap::Thread {
+                id: 1,
+                name: "main".into(),
+            }],
+        })
+    });
+    fake_adapter.handle_request::<dap::requests::StackTrace, _, _>(|arguments, _| async move {
+        assert_eq!(arguments.thread_id, 1);
+        Ok(dap::StackTraceResponse {
+            stack_frames: vec![dap::StackFrame {
+                id: 1,
+                name: "main".into(),
+                source: Some(dap::Source {
+                    name: Some("main.rs".into()),
+                    path: Some("/dir/main.rs".into()),
+                    source_reference: None,
+                    presentation_hint: None,
+                    origin: None,
+                    sources: None,
+                    adapter_data: None,
+                    checksums: None,
+                }),
+                line: 4,
+                column: 5,
+                end_line: None,
+                end_column: None,
+                can_restart: None,
+                instruction_pointer_reference: None,
+                module_id: None,
+                presentation_hint: None,
+            }],
+            total_frames: Some(1),
+        })
+    });
+
+    // Breakpoints are sent once the adapter is initialized.
+    let client_id = start_session.await.unwrap();
+    fake_adapter.send_event::<dap::events::Initialized>(());
+    assert_eq!(
+        breakpoints_rx.next().await.unwrap(),
+        ("/dir/main.rs".to_string(), vec![2])
+    );
+
+    // Breakpoints move with edits, and toggling one sends the updated set for the file.
+    buffer.update(cx, |buffer, cx| {
+        buffer.edit([(0..0, "use std;\n")], None, cx)
+    });
+    dap_store.update(cx, |dap_store, cx| {
+        let position = buffer.read(cx).anchor_before(Point::new(3, 0));
+        dap_store.toggle_breakpoint(&buffer, position, cx);
+    });
+    assert_eq!(
+        breakpoints_rx.next().await.unwrap(),
+        ("/dir/main.rs".to_string(), vec![3, 4])
+    );
+    dap_store.read_with(cx, |dap_store, cx| {
+        assert_eq!(
+            dap_store.breakpoint_rows(&buffer.read(cx).snapshot(), cx),
+            vec![2, 3]
+        );
+    });
+
+    // Stopping highlights the location of the top stack frame.
+    fake_adapter.send_event::<dap::events::Stopped>(dap::StoppedEvent {
+        reason: dap::StoppedEventReason::Breakpoint,
+        description: None,
+        thread_id: Some(1),
+        preserve_focus_hint: None,
+        text: None,
+        all_threads_stopped: Some(true),
+        hit_breakpoint_ids: None,
+    });
+    cx.run_until_parked();
+    dap_store.read_with(cx, |dap_store, _| {
+        let session = dap_store.session(client_id).unwrap();
+        assert_eq!(
+            session.status(),
+            dap_store::DebugSessionStatus::Stopped { thread_id: Some(1) }
+        );
+        assert_eq!(session.threads().len(), 1);
+        let location = dap_store.active_location().unwrap();
+        assert_eq!(location.path.path.as_ref(), Path::new("main.rs"));
+        assert_eq!(location.row, 3);
+    });
+
+    dap_store
+        .update(cx, |dap_store, cx| dap_store.stop_session(client_id, cx))
+        .await;
+    dap_store.read_with(cx, |dap_store, _| {
+        assert!(dap_store.sessions().next().is_none());
+        assert!(dap_store.active_location().is_none());
+    });
+}
+
+#[gpui::test]
+async fn test_debug_session_terminated(cx: &mut gpui::TestAppContext) {
+    init_test(cx);
+
+    let fs = FakeFs::new(cx.executor());
+    fs.insert_tree("/dir", json!({ "main.rs": "fn main() {}\n" }))
+        .await;
+
+    let project = Project::test(fs, ["/dir".as_ref()], cx).await;
+    let dap_store = project.read_with(cx, |project, _| project.dap_store().clone());
+    let mut fake_adapters = dap_store.update(cx, |dap_store, _| {
+        dap_store.register_fake_adapter("fake-adapter".into(), dap::Capabilities::default())
+    });
+
+    let start_session = dap_store.update(cx, |dap_store, cx| {
+        dap_store.start_session(
+            dap::DebugAdapterConfig {
+                label: "main".into(),
+                adapter: "fake-adapter".into(),
+                request: dap::DebugRequestType::Launch,
+                arguments: json!({ "program": "main" }),
+                cwd: None,
+            },
+            cx,
+        )
+    });
+    let fake_adapter = fake_adapters.next().await.unwrap();
+    let client_id = start_session.await.unwrap();
+    dap_store.read_with(cx, |dap_store, _| {
+        assert!(dap_store.session(client_id).is_some());
+    });
+
+    // The session is removed from within the handler of the event that terminates it.
+    fake_adapter.send_event::<dap::events::Terminated>(dap::TerminatedEvent { restart: None });
+    cx.run_until_parked();
+    dap_store.read_with(cx, |dap_store, _| {
+        assert!(dap_store.session(client_id).is_none());
+        assert!(dap_store.sessions().next().is_none());
+    });
+}
+
 async fn search(
     project: &Model<Project>,
     query: SearchQuery,
@@ -10,10 +10,19 @@ use futures::{
     future::{BoxFuture, Shared},
     FutureExt, SinkExt,
 };
+use git::{
+    commit::{CommitFileDiff, CommitSummary, LogOptions},
+    rebase::{RebaseAction, RebaseOutcome, RebaseTodoItem},
+    repository::{GitRepository, PullStrategy, RemoteOperation, RepoPath},
+    stash::{StashAction, StashEntry, StashOptions},
+    worktree::GitWorktree,
+    Oid,
+};
 use gpui::{
     AppContext, AsyncAppContext, EntityId, EventEmitter, Model, ModelContext, Task, WeakModel,
 };
 use postage::oneshot;
+use remote::AskPassDelegate;
 use rpc::{
     proto::{self, SSH_PROJECT_ID},
     AnyProtoClient, ErrorExt, TypedEnvelope,
@@ -34,6 +43,14 @@ struct MatchingEntry {
     respond: oneshot::Sender<ProjectPath>,
 }
 
+/// How [`WorktreeStore::update_index`] changes the given paths.
+#[derive(Clone, Copy)]
+enum IndexUpdate {
+    Stage,
+    Unstage,
+    Discard,
+}
+
 enum WorktreeStoreState {
     Local {
         fs: Arc<dyn Fs>,
@@ -54,6 +71,9 @@ pub struct WorktreeStore {
     loading_worktrees:
         HashMap<SanitizedPath, Shared<Task<Result<Model<Worktree>, Arc<anyhow::Error>>>>>,
     state: WorktreeStoreState,
+    next_askpass_id: u64,
+    /// The delegates answering the credential prompts of remote operations running on the host.
+    askpass_delegates: HashMap<u64, Arc<dyn AskPassDelegate>>,
 }
 
 pub enum WorktreeStoreEvent {
@@ -74,6 +94,26 @@ impl WorktreeStore {
         client.add_model_request_handler(Self::handle_expand_project_entry);
         client.add_model_request_handler(Self::handle_git_branches);
         client.add_model_request_handler(Self::handle_update_branch);
+        client.add_model_request_handler(Self::handle_git_staged_paths);
+        client.add_model_request_handler(Self::handle_git_stage);
+        client.add_model_request_handler(Self::handle_git_unstage);
+        client.add_model_request_handler(Self::handle_git_discard);
+        client.add_model_request_handler(Self::handle_git_commit);
+        client.add_model_request_handler(Self::handle_git_log);
+        client.add_model_request_handler(Self::handle_git_commit_diff);
+        client.add_model_request_handler(Self::handle_git_commit_message);
+        client.add_model_request_handler(Self::handle_git_revision_text);
+        client.add_model_request_handler(Self::handle_git_changed_paths);
+        client.add_model_request_handler(Self::handle_git_remote_operation);
+        client.add_model_request_handler(Self::handle_git_stash_list);
+        client.add_model_request_handler(Self::handle_git_stash_push);
+        client.add_model_request_handler(Self::handle_git_stash_action);
+        client.add_model_request_handler(Self::handle_git_rebase_todo);
+        client.add_model_request_handler(Self::handle_git_rebase);
+        client.add_model_request_handler(Self::handle_git_rebase_control);
+        client.add_model_request_handler(Self::handle_git_worktree_list);
+        client.add_model_request_handler(Self::handle_git_create_worktree);
+        client.add_model_request_handler(Self::handle_git_remove_worktree);
     }
 
     pub fn local(retain_worktrees: bool, fs: Arc<dyn Fs>) -> Self {
@@ -85,6 +125,8 @@ impl WorktreeStore {
             worktrees_reordered: false,
             retain_worktrees,
             state: WorktreeStoreState::Local { fs },
+            next_askpass_id: 0,
+            askpass_delegates: HashMap::default(),
         }
     }
 
@@ -104,6 +146,8 @@ impl WorktreeStore {
                 upstream_client,
                 upstream_project_id,
             },
+            next_askpass_id: 0,
+            askpass_delegates: HashMap::default(),
         }
     }
 
@@ -915,6 +959,13 @@ impl WorktreeStore {
                             unix_timestamp: proto_branch
                                 .unix_timestamp
                                 .map(|timestamp| timestamp as i64),
+                            upstream: proto_branch.upstream.map(|upstream| {
+                                git::repository::UpstreamBranch {
+                                    name: upstream.name.into(),
+                                    ahead: upstream.ahead,
+                                    behind: upstream.behind,
+                                }
+                            }),
                         })
                         .collect();
 
@@ -984,195 +1035,1911 @@ impl WorktreeStore {
         }
     }
 
-    async fn filter_paths(
-        fs: &Arc<dyn Fs>,
-        mut input: Receiver<MatchingEntry>,
-        query: &SearchQuery,
-    ) -> Result<()> {
-        while let Some(mut entry) = input.next().await {
-            let abs_path = entry.worktree_path.join(&entry.path.path);
-            let Some(file) = fs.open_sync(&abs_path).await.log_err() else {
-                continue;
-            };
-            if query.detect(file).unwrap_or(false) {
-                entry.respond.send(entry.path).await?
-            }
-        }
+    /// Returns the paths of the worktree whose changes are staged, across all of its repositories.
+    pub fn staged_paths(
+        &self,
+        worktree_id: WorktreeId,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<Arc<Path>>>> {
+        let Some(worktree) = self.worktree_for_id(worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for WorktreeId")));
+        };
 
-        Ok(())
-    }
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let snapshot = local_worktree.snapshot();
+                let repositories = snapshot
+                    .repositories()
+                    .filter_map(|(_, repository)| {
+                        let repo = local_worktree.get_local_repo(repository)?.repo().clone();
+                        Some((repository.clone(), repo))
+                    })
+                    .collect::<Vec<_>>();
+                let snapshot = worktree.read(cx).snapshot();
 
-    pub async fn handle_create_project_entry(
-        this: Model<Self>,
-        envelope: TypedEnvelope<proto::CreateProjectEntry>,
-        mut cx: AsyncAppContext,
-    ) -> Result<proto::ProjectEntryResponse> {
-        let worktree = this.update(&mut cx, |this, cx| {
-            let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
-            this.worktree_for_id(worktree_id, cx)
-                .ok_or_else(|| anyhow!("worktree not found"))
-        })??;
-        Worktree::handle_create_entry(worktree, envelope.payload, cx).await
-    }
+                cx.background_executor().spawn(async move {
+                    let mut paths = Vec::new();
+                    for (repository, repo) in repositories {
+                        paths.extend(
+                            repo.staged_paths()?.iter().filter_map(|repo_path| {
+                                repository.unrelativize(&snapshot, repo_path)
+                            }),
+                        );
+                    }
+                    Ok(paths)
+                })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let request = remote_worktree.client().request(proto::GitStagedPaths {
+                    project_id: remote_worktree.project_id(),
+                    worktree_id: worktree_id.to_proto(),
+                });
 
-    pub async fn handle_rename_project_entry(
-        this: Model<super::Project>,
-        envelope: TypedEnvelope<proto::RenameProjectEntry>,
-        mut cx: AsyncAppContext,
-    ) -> Result<proto::ProjectEntryResponse> {
-        let entry_id = ProjectEntryId::from_proto(envelope.payload.entry_id);
-        let (worktree_id, worktree, old_path, is_dir) = this
-            .update(&mut cx, |this, cx| {
-                this.worktree_store
-                    .read(cx)
-                    .worktree_and_entry_for_id(entry_id, cx)
-                    .map(|(worktree, entry)| {
-                        (
-                            worktree.read(cx).id(),
-                            worktree,
-                            entry.path.clone(),
-                            entry.is_dir(),
-                        )
-                    })
-            })?
-            .ok_or_else(|| anyhow!("worktree not found"))?;
-        let (old_abs_path, new_abs_path) = {
-            let root_path = worktree.update(&mut cx, |this, _| this.abs_path())?;
-            (
-                root_path.join(&old_path),
-                root_path.join(&envelope.payload.new_path),
-            )
-        };
-        let lsp_store = this
-            .update(&mut cx, |this, _| this.lsp_store())?
-            .downgrade();
-        LspStore::will_rename_entry(
-            lsp_store,
-            worktree_id,
-            &old_abs_path,
-            &new_abs_path,
-            is_dir,
-            cx.clone(),
-        )
-        .await;
-        let response = Worktree::handle_rename_entry(worktree, envelope.payload, cx.clone()).await;
-        this.update(&mut cx, |this, cx| {
-            this.lsp_store().read(cx).did_rename_entry(
-                worktree_id,
-                &old_abs_path,
-                &new_abs_path,
-                is_dir,
-            );
-        })
-        .ok();
-        response
+                cx.background_executor().spawn(async move {
+                    let response = request.await?;
+                    Ok(response
+                        .paths
+                        .into_iter()
+                        .map(|path| Path::new(&path).into())
+                        .collect())
+                })
+            }
+        }
     }
 
-    pub async fn handle_copy_project_entry(
-        this: Model<Self>,
-        envelope: TypedEnvelope<proto::CopyProjectEntry>,
-        mut cx: AsyncAppContext,
-    ) -> Result<proto::ProjectEntryResponse> {
-        let entry_id = ProjectEntryId::from_proto(envelope.payload.entry_id);
-        let worktree = this.update(&mut cx, |this, cx| {
-            this.worktree_for_entry(entry_id, cx)
-                .ok_or_else(|| anyhow!("worktree not found"))
-        })??;
-        Worktree::handle_copy_entry(worktree, envelope.payload, cx).await
+    pub fn stage_paths(
+        &self,
+        worktree_id: WorktreeId,
+        paths: Vec<Arc<Path>>,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        self.update_index(worktree_id, paths, IndexUpdate::Stage, cx)
     }
 
-    pub async fn handle_delete_project_entry(
-        this: Model<Self>,
-        envelope: TypedEnvelope<proto::DeleteProjectEntry>,
-        mut cx: AsyncAppContext,
-    ) -> Result<proto::ProjectEntryResponse> {
-        let entry_id = ProjectEntryId::from_proto(envelope.payload.entry_id);
-        let worktree = this.update(&mut cx, |this, cx| {
-            this.worktree_for_entry(entry_id, cx)
-                .ok_or_else(|| anyhow!("worktree not found"))
-        })??;
-        Worktree::handle_delete_entry(worktree, envelope.payload, cx).await
+    pub fn unstage_paths(
+        &self,
+        worktree_id: WorktreeId,
+        paths: Vec<Arc<Path>>,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        self.update_index(worktree_id, paths, IndexUpdate::Unstage, cx)
     }
 
-    pub async fn handle_expand_project_entry(
-        this: Model<Self>,
-        envelope: TypedEnvelope<proto::ExpandProjectEntry>,
-        mut cx: AsyncAppContext,
-    ) -> Result<proto::ExpandProjectEntryResponse> {
-        let entry_id = ProjectEntryId::from_proto(envelope.payload.entry_id);
-        let worktree = this
-            .update(&mut cx, |this, cx| this.worktree_for_entry(entry_id, cx))?
-            .ok_or_else(|| anyhow!("invalid request"))?;
-        Worktree::handle_expand_entry(worktree, envelope.payload, cx).await
+    pub fn discard_paths(
+        &self,
+        worktree_id: WorktreeId,
+        paths: Vec<Arc<Path>>,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        self.update_index(worktree_id, paths, IndexUpdate::Discard, cx)
     }
 
-    pub async fn handle_git_branches(
-        this: Model<Self>,
-        branches: TypedEnvelope<proto::GitBranches>,
-        cx: AsyncAppContext,
-    ) -> Result<proto::GitBranchesResponse> {
-        let project_path = branches
-            .payload
-            .repository
-            .clone()
-            .context("Invalid GitBranches call")?;
-        let project_path = ProjectPath {
-            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
-            path: Path::new(&project_path.path).into(),
+    fn update_index(
+        &self,
+        worktree_id: WorktreeId,
+        paths: Vec<Arc<Path>>,
+        update: IndexUpdate,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        let Some(worktree) = self.worktree_for_id(worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for WorktreeId")));
         };
 
-        let branches = this
-            .read_with(&cx, |this, cx| this.branches(project_path, cx))?
-            .await?;
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let snapshot = local_worktree.snapshot();
+                let paths_by_repository = util::maybe!({
+                    let mut paths_by_repository = HashMap::<
+                        ProjectEntryId,
+                        (Arc<dyn GitRepository>, Vec<RepoPath>),
+                    >::default();
+                    for path in &paths {
+                        let repository = snapshot
+                            .repository_for_path(path)
+                            .with_context(|| format!("No repository found for {path:?}"))?;
+                        let repo = local_worktree
+                            .get_local_repo(&repository)
+                            .with_context(|| format!("No repository found for {path:?}"))?
+                            .repo()
+                            .clone();
+                        let repo_path = repository.relativize(&snapshot, path)?;
+                        paths_by_repository
+                            .entry(repository.work_directory_id())
+                            .or_insert_with(|| (repo, Vec::new()))
+                            .1
+                            .push(repo_path);
+                    }
+                    anyhow::Ok(paths_by_repository)
+                });
+                let paths_by_repository = match paths_by_repository {
+                    Ok(paths_by_repository) => paths_by_repository,
+                    Err(error) => return Task::ready(Err(error)),
+                };
 
-        Ok(proto::GitBranchesResponse {
-            branches: branches
-                .into_iter()
-                .map(|branch| proto::Branch {
-                    is_head: branch.is_head,
-                    name: branch.name.to_string(),
-                    unix_timestamp: branch.unix_timestamp.map(|timestamp| timestamp as u64),
+                cx.background_executor().spawn(async move {
+                    for (repo, paths) in paths_by_repository.into_values() {
+                        match update {
+                            IndexUpdate::Stage => repo.stage_paths(&paths)?,
+                            IndexUpdate::Unstage => repo.unstage_paths(&paths)?,
+                            IndexUpdate::Discard => repo.discard_paths(&paths)?,
+                        }
+                    }
+                    Ok(())
                 })
-                .collect(),
-        })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let client = remote_worktree.client();
+                let project_id = remote_worktree.project_id();
+                let worktree_id = worktree_id.to_proto();
+                let paths = paths
+                    .iter()
+                    .map(|path| path.to_string_lossy().to_string())
+                    .collect();
+
+                cx.background_executor().spawn(async move {
+                    match update {
+                        IndexUpdate::Stage => {
+                            client
+                                .request(proto::GitStage {
+                                    project_id,
+                                    worktree_id,
+                                    paths,
+                                })
+                                .await?;
+                        }
+                        IndexUpdate::Unstage => {
+                            client
+                                .request(proto::GitUnstage {
+                                    project_id,
+                                    worktree_id,
+                                    paths,
+                                })
+                                .await?;
+                        }
+                        IndexUpdate::Discard => {
+                            client
+                                .request(proto::GitDiscard {
+                                    project_id,
+                                    worktree_id,
+                                    paths,
+                                })
+                                .await?;
+                        }
+                    }
+                    Ok(())
+                })
+            }
+        }
     }
 
-    pub async fn handle_update_branch(
-        this: Model<Self>,
-        update_branch: TypedEnvelope<proto::UpdateGitBranch>,
-        cx: AsyncAppContext,
-    ) -> Result<proto::Ack> {
-        let project_path = update_branch
-            .payload
-            .repository
-            .clone()
-            .context("Invalid GitBranches call")?;
-        let project_path = ProjectPath {
-            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
-            path: Path::new(&project_path.path).into(),
+    pub fn commit(
+        &self,
+        repository: ProjectPath,
+        message: String,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        let Some(worktree) = self.worktree_for_id(repository.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
         };
-        let new_branch = update_branch.payload.branch_name;
 
-        this.read_with(&cx, |this, cx| {
-            this.update_or_create_branch(project_path, new_branch, cx)
-        })?
-        .await?;
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let repo = util::maybe!({
+                    let worktree_error = |error| {
+                        format!(
+                            "{} for worktree {}",
+                            error,
+                            local_worktree.abs_path().to_string_lossy()
+                        )
+                    };
 
-        Ok(proto::Ack {})
-    }
-}
+                    let entry = local_worktree
+                        .git_entry(repository.path)
+                        .with_context(|| worktree_error("No git entry found"))?;
 
-#[derive(Clone, Debug)]
-enum WorktreeHandle {
-    Strong(Model<Worktree>),
-    Weak(WeakModel<Worktree>),
-}
+                    anyhow::Ok(
+                        local_worktree
+                            .get_local_repo(&entry)
+                            .with_context(|| worktree_error("No repository found"))?
+                            .repo()
+                            .clone(),
+                    )
+                });
+                let repo = match repo {
+                    Ok(repo) => repo,
+                    Err(error) => return Task::ready(Err(error)),
+                };
 
-impl WorktreeHandle {
-    fn upgrade(&self) -> Option<Model<Worktree>> {
-        match self {
-            WorktreeHandle::Strong(handle) => Some(handle.clone()),
-            WorktreeHandle::Weak(handle) => handle.upgrade(),
-        }
-    }
+                cx.background_executor()
+                    .spawn(async move { repo.commit(&message) })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let request = remote_worktree.client().request(proto::GitCommit {
+                    project_id: remote_worktree.project_id(),
+                    repository: Some(proto::ProjectPath {
+                        worktree_id: repository.worktree_id.to_proto(),
+                        path: repository.path.to_string_lossy().to_string(), // Root path
+                    }),
+                    message,
+                });
+
+                cx.background_executor().spawn(async move {
+                    request.await?;
+                    Ok(())
+                })
+            }
+        }
+    }
+
+    /// Lists the commits of the repository containing `path`. When `restrict_to_path` is set,
+    /// only the commits touching `path` are listed.
+    pub fn git_log(
+        &self,
+        path: ProjectPath,
+        restrict_to_path: bool,
+        mut options: LogOptions,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<CommitSummary>>> {
+        let Some(worktree) = self.worktree_for_id(path.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
+        };
+
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let repo = util::maybe!({
+                    let snapshot = local_worktree.snapshot();
+                    let repository = snapshot
+                        .repository_for_path(&path.path)
+                        .with_context(|| format!("No repository found for {:?}", path.path))?;
+                    if restrict_to_path {
+                        options.path = Some(repository.relativize(&snapshot, &path.path)?);
+                    }
+                    anyhow::Ok(
+                        local_worktree
+                            .get_local_repo(&repository)
+                            .with_context(|| format!("No repository found for {:?}", path.path))?
+                            .repo()
+                            .clone(),
+                    )
+                });
+                let repo = match repo {
+                    Ok(repo) => repo,
+                    Err(error) => return Task::ready(Err(error)),
+                };
+
+                cx.background_executor()
+                    .spawn(async move { repo.log(&options) })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let request = remote_worktree.client().request(proto::GitLog {
+                    project_id: remote_worktree.project_id(),
+                    path: Some(proto::ProjectPath {
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                    }),
+                    restrict_to_path,
+                    author: options.author,
+                    message: options.message,
+                    skip: options.skip as u64,
+                    limit: options.limit as u64,
+                });
+
+                cx.background_executor().spawn(async move {
+                    request
+                        .await?
+                        .commits
+                        .into_iter()
+                        .map(deserialize_commit_summary)
+                        .collect()
+                })
+            }
+        }
+    }
+
+    /// Returns the files changed by a commit of the repository containing `path`.
+    pub fn commit_diff(
+        &self,
+        path: ProjectPath,
+        sha: Oid,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<CommitFileDiff>>> {
+        let Some(worktree) = self.worktree_for_id(path.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
+        };
+
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let Some(repo) = local_worktree.local_git_repo(&path.path) else {
+                    return Task::ready(Err(anyhow!("No repository found for {:?}", path.path)));
+                };
+
+                cx.background_executor()
+                    .spawn(async move { repo.commit_diff(sha) })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let request = remote_worktree.client().request(proto::GitCommitDiff {
+                    project_id: remote_worktree.project_id(),
+                    repository: Some(proto::ProjectPath {
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                    }),
+                    sha: sha.to_string(),
+                });
+
+                cx.background_executor().spawn(async move {
+                    Ok(request
+                        .await?
+                        .files
+                        .into_iter()
+                        .map(|file| CommitFileDiff {
+                            path: RepoPath::new(PathBuf::from(file.path)),
+                            old_text: file.old_text,
+                            new_text: file.new_text,
+                        })
+                        .collect())
+                })
+            }
+        }
+    }
+
+    /// Returns the full message of a commit of the repository containing `path`.
+    pub fn commit_message(
+        &self,
+        path: ProjectPath,
+        revision: String,
+        cx: &AppContext,
+    ) -> Task<Result<String>> {
+        let Some(worktree) = self.worktree_for_id(path.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
+        };
+
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let Some(repo) = local_worktree.local_git_repo(&path.path) else {
+                    return Task::ready(Err(anyhow!("No repository found for {:?}", path.path)));
+                };
+
+                cx.background_executor()
+                    .spawn(async move { repo.commit_message(&revision) })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let request = remote_worktree.client().request(proto::GitCommitMessage {
+                    project_id: remote_worktree.project_id(),
+                    repository: Some(proto::ProjectPath {
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                    }),
+                    revision,
+                });
+
+                cx.background_executor()
+                    .spawn(async move { Ok(request.await?.message) })
+            }
+        }
+    }
+
+    /// Loads the contents of the file at `path` as of the given git revision.
+    pub fn load_revision_text(
+        &self,
+        path: ProjectPath,
+        revision: String,
+        cx: &AppContext,
+    ) -> Task<Result<Option<String>>> {
+        let Some(worktree) = self.worktree_for_id(path.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
+        };
+
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let repo = util::maybe!({
+                    let snapshot = local_worktree.snapshot();
+                    let repository = snapshot
+                        .repository_for_path(&path.path)
+                        .with_context(|| format!("No repository found for {:?}", path.path))?;
+                    let repo_path = repository.relativize(&snapshot, &path.path)?;
+                    let repo = local_worktree
+                        .get_local_repo(&repository)
+                        .with_context(|| format!("No repository found for {:?}", path.path))?
+                        .repo()
+                        .clone();
+                    anyhow::Ok((repo, repo_path))
+                });
+                let (repo, repo_path) = match repo {
+                    Ok(repo) => repo,
+                    Err(error) => return Task::ready(Err(error)),
+                };
+
+                cx.background_executor()
+                    .spawn(async move { repo.load_revision_text(&revision, &repo_path) })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let request = remote_worktree.client().request(proto::GitRevisionText {
+                    project_id: remote_worktree.project_id(),
+                    path: Some(proto::ProjectPath {
+                        worktree_id: path.worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                    }),
+                    revision,
+                });
+
+                cx.background_executor()
+                    .spawn(async move { Ok(request.await?.text) })
+            }
+        }
+    }
+
+    /// Lists the files of the repository containing `path` whose contents differ from the given
+    /// git revision. Files outside of the worktree are skipped.
+    pub fn changed_paths_since(
+        &self,
+        path: ProjectPath,
+        revision: String,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<ProjectPath>>> {
+        let Some(worktree) = self.worktree_for_id(path.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
+        };
+        let worktree_id = path.worktree_id;
+
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let snapshot = local_worktree.snapshot();
+                let Some(repository) = snapshot.repository_for_path(&path.path) else {
+                    return Task::ready(Err(anyhow!("No repository found for {:?}", path.path)));
+                };
+                let Some(repo) = local_worktree
+                    .get_local_repo(&repository)
+                    .map(|repo| repo.repo().clone())
+                else {
+                    return Task::ready(Err(anyhow!("No repository found for {:?}", path.path)));
+                };
+
+                cx.background_executor().spawn(async move {
+                    Ok(repo
+                        .changed_paths_since(&revision)?
+                        .iter()
+                        .filter_map(|repo_path| {
+                            Some(ProjectPath {
+                                worktree_id,
+                                path: repository.unrelativize(&snapshot, repo_path)?,
+                            })
+                        })
+                        .collect())
+                })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let request = remote_worktree.client().request(proto::GitChangedPaths {
+                    project_id: remote_worktree.project_id(),
+                    repository: Some(proto::ProjectPath {
+                        worktree_id: worktree_id.to_proto(),
+                        path: path.path.to_string_lossy().to_string(),
+                    }),
+                    revision,
+                });
+
+                cx.background_executor().spawn(async move {
+                    Ok(request
+                        .await?
+                        .paths
+                        .into_iter()
+                        .map(|path| ProjectPath {
+                            worktree_id,
+                            path: Path::new(&path).into(),
+                        })
+                        .collect())
+                })
+            }
+        }
+    }
+
+    /// Fetches, pulls or pushes the repository at `repository`. The credential prompts of git and
+    /// ssh are answered by `askpass`, including when the repository is on another host.
+    pub fn git_remote_operation(
+        &mut self,
+        repository: ProjectPath,
+        operation: RemoteOperation,
+        askpass: Arc<dyn AskPassDelegate>,
+        cx: &mut ModelContext<Self>,
+    ) -> Task<Result<()>> {
+        let Some(worktree) = self.worktree_for_id(repository.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
+        };
+
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let repo = util::maybe!({
+                    let worktree_error = |error| {
+                        format!(
+                            "{} for worktree {}",
+                            error,
+                            local_worktree.abs_path().to_string_lossy()
+                        )
+                    };
+
+                    let entry = local_worktree
+                        .git_entry(repository.path)
+                        .with_context(|| worktree_error("No git entry found"))?;
+
+                    anyhow::Ok(
+                        local_worktree
+                            .get_local_repo(&entry)
+                            .with_context(|| worktree_error("No repository found"))?
+                            .repo()
+                            .clone(),
+                    )
+                });
+                let repo = match repo {
+                    Ok(repo) => repo,
+                    Err(error) => return Task::ready(Err(error)),
+                };
+
+                cx.spawn(|_, mut cx| async move {
+                    run_remote_operation(repo, operation, askpass, &mut cx).await
+                })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let askpass_id = self.next_askpass_id;
+                self.next_askpass_id += 1;
+                self.askpass_delegates.insert(askpass_id, askpass);
+
+                use proto::git_remote_operation::Operation;
+                let operation = match operation {
+                    RemoteOperation::Fetch => Operation::Fetch,
+                    RemoteOperation::Pull(PullStrategy::Merge) => Operation::PullMerge,
+                    RemoteOperation::Pull(PullStrategy::Rebase) => Operation::PullRebase,
+                    RemoteOperation::Push { force: false } => Operation::Push,
+                    RemoteOperation::Push { force: true } => Operation::ForcePush,
+                };
+                let request = remote_worktree.client().request(proto::GitRemoteOperation {
+                    project_id: remote_worktree.project_id(),
+                    repository: Some(proto::ProjectPath {
+                        worktree_id: repository.worktree_id.to_proto(),
+                        path: repository.path.to_string_lossy().to_string(), // Root path
+                    }),
+                    operation: operation as i32,
+                    askpass_id,
+                });
+
+                cx.spawn(|this, mut cx| async move {
+                    let result = request.await;
+                    this.update(&mut cx, |this, _| {
+                        this.askpass_delegates.remove(&askpass_id);
+                    })?;
+                    result?;
+                    Ok(())
+                })
+            }
+        }
+    }
+
+    /// Lists the stash of the repository at `repository`, most recent entry first.
+    pub fn stash_list(
+        &self,
+        repository: ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<StashEntry>>> {
+        let Some(worktree) = self.worktree_for_id(repository.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
+        };
+
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let Some(repo) = local_worktree.local_git_repo(&repository.path) else {
+                    return Task::ready(Err(anyhow!(
+                        "No repository found for {:?}",
+                        repository.path
+                    )));
+                };
+
+                cx.background_executor()
+                    .spawn(async move { repo.stash_list() })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let request = remote_worktree.client().request(proto::GitStashList {
+                    project_id: remote_worktree.project_id(),
+                    repository: Some(proto::ProjectPath {
+                        worktree_id: repository.worktree_id.to_proto(),
+                        path: repository.path.to_string_lossy().to_string(), // Root path
+                    }),
+                });
+
+                cx.background_executor().spawn(async move {
+                    request
+                        .await?
+                        .entries
+                        .into_iter()
+                        .map(|entry| {
+                            Ok(StashEntry {
+                                index: entry.index as usize,
+                                sha: entry.sha.parse()?,
+                                message: entry.message,
+                                timestamp: entry.timestamp,
+                            })
+                        })
+                        .collect()
+                })
+            }
+        }
+    }
+
+    /// Saves the local changes of the repository at `repository` selected by `options` as a new
+    /// stash entry.
+    pub fn stash_push(
+        &self,
+        repository: ProjectPath,
+        message: Option<String>,
+        options: StashOptions,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        let Some(worktree) = self.worktree_for_id(repository.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
+        };
+
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let Some(repo) = local_worktree.local_git_repo(&repository.path) else {
+                    return Task::ready(Err(anyhow!(
+                        "No repository found for {:?}",
+                        repository.path
+                    )));
+                };
+
+                cx.background_executor()
+                    .spawn(async move { repo.stash_push(message.as_deref(), options) })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let request = remote_worktree.client().request(proto::GitStashPush {
+                    project_id: remote_worktree.project_id(),
+                    repository: Some(proto::ProjectPath {
+                        worktree_id: repository.worktree_id.to_proto(),
+                        path: repository.path.to_string_lossy().to_string(), // Root path
+                    }),
+                    message,
+                    include_untracked: options.include_untracked,
+                    staged_only: options.staged_only,
+                });
+
+                cx.background_executor().spawn(async move {
+                    request.await?;
+                    Ok(())
+                })
+            }
+        }
+    }
+
+    /// Applies, pops or drops the stash entry at `index` of the repository at `repository`.
+    pub fn stash_action(
+        &self,
+        repository: ProjectPath,
+        action: StashAction,
+        index: usize,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        let Some(worktree) = self.worktree_for_id(repository.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
+        };
+
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let Some(repo) = local_worktree.local_git_repo(&repository.path) else {
+                    return Task::ready(Err(anyhow!(
+                        "No repository found for {:?}",
+                        repository.path
+                    )));
+                };
+
+                cx.background_executor()
+                    .spawn(async move { action.run(repo.as_ref(), index) })
+            }
+            Worktree::Remote(remote_worktree) => {
+                use proto::git_stash_action::Action;
+                let action = match action {
+                    StashAction::Apply => Action::Apply,
+                    StashAction::Pop => Action::Pop,
+                    StashAction::Drop => Action::Drop,
+                };
+                let request = remote_worktree.client().request(proto::GitStashAction {
+                    project_id: remote_worktree.project_id(),
+                    repository: Some(proto::ProjectPath {
+                        worktree_id: repository.worktree_id.to_proto(),
+                        path: repository.path.to_string_lossy().to_string(), // Root path
+                    }),
+                    index: index as u64,
+                    action: action as i32,
+                });
+
+                cx.background_executor().spawn(async move {
+                    request.await?;
+                    Ok(())
+                })
+            }
+        }
+    }
+
+    /// Lists the commits of the repository at `repository` that an interactive rebase onto `base`
+    /// would replay, oldest first.
+    pub fn rebase_todo(
+        &self,
+        repository: ProjectPath,
+        base: String,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<RebaseTodoItem>>> {
+        let Some(worktree) = self.worktree_for_id(repository.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
+        };
+
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let Some(repo) = local_worktree.local_git_repo(&repository.path) else {
+                    return Task::ready(Err(anyhow!(
+                        "No repository found for {:?}",
+                        repository.path
+                    )));
+                };
+
+                cx.background_executor()
+                    .spawn(async move { repo.rebase_todo(&base) })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let request = remote_worktree.client().request(proto::GitRebaseTodo {
+                    project_id: remote_worktree.project_id(),
+                    repository: Some(proto::ProjectPath {
+                        worktree_id: repository.worktree_id.to_proto(),
+                        path: repository.path.to_string_lossy().to_string(), // Root path
+                    }),
+                    base,
+                });
+
+                cx.background_executor().spawn(async move {
+                    Ok(request
+                        .await?
+                        .items
+                        .into_iter()
+                        .map(deserialize_rebase_todo_item)
+                        .collect())
+                })
+            }
+        }
+    }
+
+    /// Rebases the current branch of the repository at `repository` onto `base`, replaying the
+    /// commits as listed in `todo`.
+    pub fn rebase(
+        &self,
+        repository: ProjectPath,
+        base: String,
+        todo: Vec<RebaseTodoItem>,
+        cx: &AppContext,
+    ) -> Task<Result<RebaseOutcome>> {
+        let Some(worktree) = self.worktree_for_id(repository.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
+        };
+
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let Some(repo) = local_worktree.local_git_repo(&repository.path) else {
+                    return Task::ready(Err(anyhow!(
+                        "No repository found for {:?}",
+                        repository.path
+                    )));
+                };
+
+                cx.background_executor()
+                    .spawn(async move { repo.rebase(&base, &todo) })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let request = remote_worktree.client().request(proto::GitRebase {
+                    project_id: remote_worktree.project_id(),
+                    repository: Some(proto::ProjectPath {
+                        worktree_id: repository.worktree_id.to_proto(),
+                        path: repository.path.to_string_lossy().to_string(), // Root path
+                    }),
+                    base,
+                    items: todo.into_iter().map(serialize_rebase_todo_item).collect(),
+                });
+
+                cx.background_executor()
+                    .spawn(async move { Ok(deserialize_rebase_outcome(request.await?)) })
+            }
+        }
+    }
+
+    /// Continues the stopped rebase of the repository at `repository`, or aborts it.
+    pub fn continue_or_abort_rebase(
+        &self,
+        repository: ProjectPath,
+        abort: bool,
+        cx: &AppContext,
+    ) -> Task<Result<RebaseOutcome>> {
+        let Some(worktree) = self.worktree_for_id(repository.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
+        };
+
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let Some(repo) = local_worktree.local_git_repo(&repository.path) else {
+                    return Task::ready(Err(anyhow!(
+                        "No repository found for {:?}",
+                        repository.path
+                    )));
+                };
+
+                cx.background_executor().spawn(async move {
+                    if abort {
+                        repo.rebase_abort()?;
+                        Ok(RebaseOutcome::Completed)
+                    } else {
+                        repo.rebase_continue()
+                    }
+                })
+            }
+            Worktree::Remote(remote_worktree) => {
+                use proto::git_rebase_control::Operation;
+                let operation = if abort {
+                    Operation::Abort
+                } else {
+                    Operation::Continue
+                };
+                let request = remote_worktree.client().request(proto::GitRebaseControl {
+                    project_id: remote_worktree.project_id(),
+                    repository: Some(proto::ProjectPath {
+                        worktree_id: repository.worktree_id.to_proto(),
+                        path: repository.path.to_string_lossy().to_string(), // Root path
+                    }),
+                    operation: operation as i32,
+                });
+
+                cx.background_executor()
+                    .spawn(async move { Ok(deserialize_rebase_outcome(request.await?)) })
+            }
+        }
+    }
+
+    /// Lists the git worktrees of the repository at `repository`, the main one first.
+    pub fn git_worktrees(
+        &self,
+        repository: ProjectPath,
+        cx: &AppContext,
+    ) -> Task<Result<Vec<GitWorktree>>> {
+        let Some(worktree) = self.worktree_for_id(repository.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
+        };
+
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let Some(repo) = local_worktree.local_git_repo(&repository.path) else {
+                    return Task::ready(Err(anyhow!(
+                        "No repository found for {:?}",
+                        repository.path
+                    )));
+                };
+
+                cx.background_executor()
+                    .spawn(async move { repo.worktrees() })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let request = remote_worktree.client().request(proto::GitWorktreeList {
+                    project_id: remote_worktree.project_id(),
+                    repository: Some(proto::ProjectPath {
+                        worktree_id: repository.worktree_id.to_proto(),
+                        path: repository.path.to_string_lossy().to_string(), // Root path
+                    }),
+                });
+
+                cx.background_executor().spawn(async move {
+                    request
+                        .await?
+                        .worktrees
+                        .into_iter()
+                        .map(deserialize_git_worktree)
+                        .collect()
+                })
+            }
+        }
+    }
+
+    /// Creates a git worktree at the absolute `path` for the repository at `repository`,
+    /// checking out `revision`, optionally on a new branch.
+    pub fn create_git_worktree(
+        &self,
+        repository: ProjectPath,
+        path: PathBuf,
+        revision: String,
+        new_branch: Option<String>,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        let Some(worktree) = self.worktree_for_id(repository.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
+        };
+
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let Some(repo) = local_worktree.local_git_repo(&repository.path) else {
+                    return Task::ready(Err(anyhow!(
+                        "No repository found for {:?}",
+                        repository.path
+                    )));
+                };
+
+                cx.background_executor().spawn(async move {
+                    repo.create_worktree(&path, &revision, new_branch.as_deref())
+                })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let request = remote_worktree.client().request(proto::GitCreateWorktree {
+                    project_id: remote_worktree.project_id(),
+                    repository: Some(proto::ProjectPath {
+                        worktree_id: repository.worktree_id.to_proto(),
+                        path: repository.path.to_string_lossy().to_string(), // Root path
+                    }),
+                    path: path.to_string_lossy().to_string(),
+                    revision,
+                    new_branch,
+                });
+
+                cx.background_executor().spawn(async move {
+                    request.await?;
+                    Ok(())
+                })
+            }
+        }
+    }
+
+    /// Removes the linked git worktree at the absolute `path` from the repository at
+    /// `repository`.
+    pub fn remove_git_worktree(
+        &self,
+        repository: ProjectPath,
+        path: PathBuf,
+        force: bool,
+        cx: &AppContext,
+    ) -> Task<Result<()>> {
+        let Some(worktree) = self.worktree_for_id(repository.worktree_id, cx) else {
+            return Task::ready(Err(anyhow!("No worktree found for ProjectPath")));
+        };
+
+        match worktree.read(cx) {
+            Worktree::Local(local_worktree) => {
+                let Some(repo) = local_worktree.local_git_repo(&repository.path) else {
+                    return Task::ready(Err(anyhow!(
+                        "No repository found for {:?}",
+                        repository.path
+                    )));
+                };
+
+                cx.background_executor()
+                    .spawn(async move { repo.remove_worktree(&path, force) })
+            }
+            Worktree::Remote(remote_worktree) => {
+                let request = remote_worktree.client().request(proto::GitRemoveWorktree {
+                    project_id: remote_worktree.project_id(),
+                    repository: Some(proto::ProjectPath {
+                        worktree_id: repository.worktree_id.to_proto(),
+                        path: repository.path.to_string_lossy().to_string(), // Root path
+                    }),
+                    path: path.to_string_lossy().to_string(),
+                    force,
+                });
+
+                cx.background_executor().spawn(async move {
+                    request.await?;
+                    Ok(())
+                })
+            }
+        }
+    }
+
+    async fn filter_paths(
+        fs: &Arc<dyn Fs>,
+        mut input: Receiver<MatchingEntry>,
+        query: &SearchQuery,
+    ) -> Result<()> {
+        while let Some(mut entry) = input.next().await {
+            let abs_path = entry.worktree_path.join(&entry.path.path);
+            let Some(file) = fs.open_sync(&abs_path).await.log_err() else {
+                continue;
+            };
+            if query.detect(file).unwrap_or(false) {
+                entry.respond.send(entry.path).await?
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn handle_create_project_entry(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::CreateProjectEntry>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ProjectEntryResponse> {
+        let worktree = this.update(&mut cx, |this, cx| {
+            let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+            this.worktree_for_id(worktree_id, cx)
+                .ok_or_else(|| anyhow!("worktree not found"))
+        })??;
+        Worktree::handle_create_entry(worktree, envelope.payload, cx).await
+    }
+
+    pub async fn handle_rename_project_entry(
+        this: Model<super::Project>,
+        envelope: TypedEnvelope<proto::RenameProjectEntry>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ProjectEntryResponse> {
+        let entry_id = ProjectEntryId::from_proto(envelope.payload.entry_id);
+        let (worktree_id, worktree, old_path, is_dir) = this
+            .update(&mut cx, |this, cx| {
+                this.worktree_store
+                    .read(cx)
+                    .worktree_and_entry_for_id(entry_id, cx)
+                    .map(|(worktree, entry)| {
+                        (
+                            worktree.read(cx).id(),
+                            worktree,
+                            entry.path.clone(),
+                            entry.is_dir(),
+                        )
+                    })
+            })?
+            .ok_or_else(|| anyhow!("worktree not found"))?;
+        let (old_abs_path, new_abs_path) = {
+            let root_path = worktree.update(&mut cx, |this, _| this.abs_path())?;
+            (
+                root_path.join(&old_path),
+                root_path.join(&envelope.payload.new_path),
+            )
+        };
+        let lsp_store = this
+            .update(&mut cx, |this, _| this.lsp_store())?
+            .downgrade();
+        LspStore::will_rename_entry(
+            lsp_store,
+            worktree_id,
+            &old_abs_path,
+            &new_abs_path,
+            is_dir,
+            cx.clone(),
+        )
+        .await;
+        let response = Worktree::handle_rename_entry(worktree, envelope.payload, cx.clone()).await;
+        this.update(&mut cx, |this, cx| {
+            this.lsp_store().read(cx).did_rename_entry(
+                worktree_id,
+                &old_abs_path,
+                &new_abs_path,
+                is_dir,
+            );
+        })
+        .ok();
+        response
+    }
+
+    pub async fn handle_copy_project_entry(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::CopyProjectEntry>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ProjectEntryResponse> {
+        let entry_id = ProjectEntryId::from_proto(envelope.payload.entry_id);
+        let worktree = this.update(&mut cx, |this, cx| {
+            this.worktree_for_entry(entry_id, cx)
+                .ok_or_else(|| anyhow!("worktree not found"))
+        })??;
+        Worktree::handle_copy_entry(worktree, envelope.payload, cx).await
+    }
+
+    pub async fn handle_delete_project_entry(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::DeleteProjectEntry>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ProjectEntryResponse> {
+        let entry_id = ProjectEntryId::from_proto(envelope.payload.entry_id);
+        let worktree = this.update(&mut cx, |this, cx| {
+            this.worktree_for_entry(entry_id, cx)
+                .ok_or_else(|| anyhow!("worktree not found"))
+        })??;
+        Worktree::handle_delete_entry(worktree, envelope.payload, cx).await
+    }
+
+    pub async fn handle_expand_project_entry(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::ExpandProjectEntry>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::ExpandProjectEntryResponse> {
+        let entry_id = ProjectEntryId::from_proto(envelope.payload.entry_id);
+        let worktree = this
+            .update(&mut cx, |this, cx| this.worktree_for_entry(entry_id, cx))?
+            .ok_or_else(|| anyhow!("invalid request"))?;
+        Worktree::handle_expand_entry(worktree, envelope.payload, cx).await
+    }
+
+    pub async fn handle_git_branches(
+        this: Model<Self>,
+        branches: TypedEnvelope<proto::GitBranches>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::GitBranchesResponse> {
+        let project_path = branches
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitBranches call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+
+        let branches = this
+            .read_with(&cx, |this, cx| this.branches(project_path, cx))?
+            .await?;
+
+        Ok(proto::GitBranchesResponse {
+            branches: branches
+                .into_iter()
+                .map(|branch| proto::Branch {
+                    is_head: branch.is_head,
+                    name: branch.name.to_string(),
+                    unix_timestamp: branch.unix_timestamp.map(|timestamp| timestamp as u64),
+                    upstream: branch.upstream.map(|upstream| proto::UpstreamBranch {
+                        name: upstream.name.to_string(),
+                        ahead: upstream.ahead,
+                        behind: upstream.behind,
+                    }),
+                })
+                .collect(),
+        })
+    }
+
+    pub async fn handle_update_branch(
+        this: Model<Self>,
+        update_branch: TypedEnvelope<proto::UpdateGitBranch>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let project_path = update_branch
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitBranches call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+        let new_branch = update_branch.payload.branch_name;
+
+        this.read_with(&cx, |this, cx| {
+            this.update_or_create_branch(project_path, new_branch, cx)
+        })?
+        .await?;
+
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_git_staged_paths(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitStagedPaths>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::GitStagedPathsResponse> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let paths = this
+            .read_with(&cx, |this, cx| this.staged_paths(worktree_id, cx))?
+            .await?;
+
+        Ok(proto::GitStagedPathsResponse {
+            paths: paths
+                .iter()
+                .map(|path| path.to_string_lossy().to_string())
+                .collect(),
+        })
+    }
+
+    pub async fn handle_git_stage(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitStage>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|path| Path::new(path).into())
+            .collect();
+
+        this.read_with(&cx, |this, cx| this.stage_paths(worktree_id, paths, cx))?
+            .await?;
+
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_git_unstage(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitUnstage>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|path| Path::new(path).into())
+            .collect();
+
+        this.read_with(&cx, |this, cx| this.unstage_paths(worktree_id, paths, cx))?
+            .await?;
+
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_git_discard(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitDiscard>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let worktree_id = WorktreeId::from_proto(envelope.payload.worktree_id);
+        let paths = envelope
+            .payload
+            .paths
+            .iter()
+            .map(|path| Path::new(path).into())
+            .collect();
+
+        this.read_with(&cx, |this, cx| this.discard_paths(worktree_id, paths, cx))?
+            .await?;
+
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_git_commit(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitCommit>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let project_path = envelope
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitCommit call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+        let message = envelope.payload.message;
+
+        this.read_with(&cx, |this, cx| this.commit(project_path, message, cx))?
+            .await?;
+
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_git_log(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitLog>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::GitLogResponse> {
+        let project_path = envelope
+            .payload
+            .path
+            .clone()
+            .context("Invalid GitLog call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+        let options = LogOptions {
+            path: None,
+            author: envelope.payload.author,
+            message: envelope.payload.message,
+            skip: envelope.payload.skip as usize,
+            limit: envelope.payload.limit as usize,
+        };
+
+        let commits = this
+            .read_with(&cx, |this, cx| {
+                this.git_log(project_path, envelope.payload.restrict_to_path, options, cx)
+            })?
+            .await?;
+
+        Ok(proto::GitLogResponse {
+            commits: commits.into_iter().map(serialize_commit_summary).collect(),
+        })
+    }
+
+    pub async fn handle_git_commit_diff(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitCommitDiff>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::GitCommitDiffResponse> {
+        let project_path = envelope
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitCommitDiff call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+        let sha = envelope.payload.sha.parse()?;
+
+        let files = this
+            .read_with(&cx, |this, cx| this.commit_diff(project_path, sha, cx))?
+            .await?;
+
+        Ok(proto::GitCommitDiffResponse {
+            files: files
+                .into_iter()
+                .map(|file| proto::GitCommitFileDiff {
+                    path: file.path.to_string_lossy().to_string(),
+                    old_text: file.old_text,
+                    new_text: file.new_text,
+                })
+                .collect(),
+        })
+    }
+
+    pub async fn handle_git_commit_message(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitCommitMessage>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::GitCommitMessageResponse> {
+        let project_path = envelope
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitCommitMessage call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+
+        let message = this
+            .read_with(&cx, |this, cx| {
+                this.commit_message(project_path, envelope.payload.revision, cx)
+            })?
+            .await?;
+
+        Ok(proto::GitCommitMessageResponse { message })
+    }
+
+    pub async fn handle_git_revision_text(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitRevisionText>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::GitRevisionTextResponse> {
+        let project_path = envelope
+            .payload
+            .path
+            .clone()
+            .context("Invalid GitRevisionText call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+
+        let text = this
+            .read_with(&cx, |this, cx| {
+                this.load_revision_text(project_path, envelope.payload.revision, cx)
+            })?
+            .await?;
+
+        Ok(proto::GitRevisionTextResponse { text })
+    }
+
+    pub async fn handle_git_changed_paths(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitChangedPaths>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::GitChangedPathsResponse> {
+        let project_path = envelope
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitChangedPaths call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+
+        let paths = this
+            .read_with(&cx, |this, cx| {
+                this.changed_paths_since(project_path, envelope.payload.revision, cx)
+            })?
+            .await?;
+
+        Ok(proto::GitChangedPathsResponse {
+            paths: paths
+                .into_iter()
+                .map(|path| path.path.to_string_lossy().to_string())
+                .collect(),
+        })
+    }
+
+    pub async fn handle_git_remote_operation(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitRemoteOperation>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let project_path = envelope
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitRemoteOperation call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+        use proto::git_remote_operation::Operation;
+        let operation = match envelope.payload.operation() {
+            Operation::Fetch => RemoteOperation::Fetch,
+            Operation::PullMerge => RemoteOperation::Pull(PullStrategy::Merge),
+            Operation::PullRebase => RemoteOperation::Pull(PullStrategy::Rebase),
+            Operation::Push => RemoteOperation::Push { force: false },
+            Operation::ForcePush => RemoteOperation::Push { force: true },
+        };
+        let askpass_id = envelope.payload.askpass_id;
+
+        this.update(&mut cx, |this, cx| {
+            let askpass = Arc::new(DownstreamAskPassDelegate {
+                downstream_client: this.downstream_client.clone(),
+                askpass_id,
+            });
+            this.git_remote_operation(project_path, operation, askpass, cx)
+        })?
+        .await?;
+
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_git_stash_list(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitStashList>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::GitStashListResponse> {
+        let project_path = envelope
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitStashList call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+
+        let entries = this
+            .read_with(&cx, |this, cx| this.stash_list(project_path, cx))?
+            .await?;
+
+        Ok(proto::GitStashListResponse {
+            entries: entries
+                .into_iter()
+                .map(|entry| proto::GitStashEntry {
+                    index: entry.index as u64,
+                    sha: entry.sha.to_string(),
+                    message: entry.message,
+                    timestamp: entry.timestamp,
+                })
+                .collect(),
+        })
+    }
+
+    pub async fn handle_git_stash_push(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitStashPush>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let project_path = envelope
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitStashPush call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+        let options = StashOptions {
+            include_untracked: envelope.payload.include_untracked,
+            staged_only: envelope.payload.staged_only,
+        };
+
+        this.read_with(&cx, |this, cx| {
+            this.stash_push(project_path, envelope.payload.message, options, cx)
+        })?
+        .await?;
+
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_git_stash_action(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitStashAction>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let project_path = envelope
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitStashAction call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+        use proto::git_stash_action::Action;
+        let action = match envelope.payload.action() {
+            Action::Apply => StashAction::Apply,
+            Action::Pop => StashAction::Pop,
+            Action::Drop => StashAction::Drop,
+        };
+        let index = envelope.payload.index as usize;
+
+        this.read_with(&cx, |this, cx| {
+            this.stash_action(project_path, action, index, cx)
+        })?
+        .await?;
+
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_git_rebase_todo(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseTodo>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::GitRebaseTodoResponse> {
+        let project_path = envelope
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitRebaseTodo call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+
+        let items = this
+            .read_with(&cx, |this, cx| {
+                this.rebase_todo(project_path, envelope.payload.base, cx)
+            })?
+            .await?;
+
+        Ok(proto::GitRebaseTodoResponse {
+            items: items.into_iter().map(serialize_rebase_todo_item).collect(),
+        })
+    }
+
+    pub async fn handle_git_rebase(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitRebase>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::GitRebaseResponse> {
+        let project_path = envelope
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitRebase call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+        let todo = envelope
+            .payload
+            .items
+            .into_iter()
+            .map(deserialize_rebase_todo_item)
+            .collect::<Vec<_>>();
+        // Commands run arbitrary shell commands, and are only kept in the todo lists git writes.
+        if todo.iter().any(|item| item.action == RebaseAction::Command) {
+            return Err(anyhow!("rebase commands can't be sent remotely"));
+        }
+
+        let outcome = this
+            .read_with(&cx, |this, cx| {
+                this.rebase(project_path, envelope.payload.base, todo, cx)
+            })?
+            .await?;
+
+        Ok(serialize_rebase_outcome(outcome))
+    }
+
+    pub async fn handle_git_rebase_control(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitRebaseControl>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::GitRebaseResponse> {
+        let project_path = envelope
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitRebaseControl call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+        let abort = envelope.payload.operation() == proto::git_rebase_control::Operation::Abort;
+
+        let outcome = this
+            .read_with(&cx, |this, cx| {
+                this.continue_or_abort_rebase(project_path, abort, cx)
+            })?
+            .await?;
+
+        Ok(serialize_rebase_outcome(outcome))
+    }
+
+    pub async fn handle_git_worktree_list(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitWorktreeList>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::GitWorktreeListResponse> {
+        let project_path = envelope
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitWorktreeList call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+
+        let worktrees = this
+            .read_with(&cx, |this, cx| this.git_worktrees(project_path, cx))?
+            .await?;
+
+        Ok(proto::GitWorktreeListResponse {
+            worktrees: worktrees.into_iter().map(serialize_git_worktree).collect(),
+        })
+    }
+
+    pub async fn handle_git_create_worktree(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitCreateWorktree>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let project_path = envelope
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitCreateWorktree call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+        let payload = envelope.payload;
+
+        this.read_with(&cx, |this, cx| {
+            this.create_git_worktree(
+                project_path,
+                PathBuf::from(payload.path),
+                payload.revision,
+                payload.new_branch,
+                cx,
+            )
+        })?
+        .await?;
+
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_git_remove_worktree(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitRemoveWorktree>,
+        cx: AsyncAppContext,
+    ) -> Result<proto::Ack> {
+        let project_path = envelope
+            .payload
+            .repository
+            .clone()
+            .context("Invalid GitRemoveWorktree call")?;
+        let project_path = ProjectPath {
+            worktree_id: WorktreeId::from_proto(project_path.worktree_id),
+            path: Path::new(&project_path.path).into(),
+        };
+        let path = PathBuf::from(envelope.payload.path);
+        let force = envelope.payload.force;
+
+        this.read_with(&cx, |this, cx| {
+            this.remove_git_worktree(project_path, path, force, cx)
+        })?
+        .await?;
+
+        Ok(proto::Ack {})
+    }
+
+    pub async fn handle_git_ask_pass(
+        this: Model<Self>,
+        envelope: TypedEnvelope<proto::GitAskPass>,
+        mut cx: AsyncAppContext,
+    ) -> Result<proto::GitAskPassResponse> {
+        let askpass_id = envelope.payload.askpass_id;
+        let delegate = this
+            .read_with(&cx, |this, _| {
+                this.askpass_delegates.get(&askpass_id).cloned()
+            })?
+            .context("Credentials were requested for an operation that already finished")?;
+        let response = delegate
+            .ask_password(envelope.payload.prompt, &mut cx)
+            .await??;
+
+        Ok(proto::GitAskPassResponse { response })
+    }
+}
+
+/// Runs a remote operation, with an askpass program forwarding the credential prompts of git and
+/// ssh to `askpass`.
+#[cfg(unix)]
+async fn run_remote_operation(
+    repo: Arc<dyn GitRepository>,
+    operation: RemoteOperation,
+    askpass: Arc<dyn AskPassDelegate>,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    let askpass = remote::askpass::AskPassSession::new(askpass, cx).await?;
+    let askpass_path = askpass.script_path().to_path_buf();
+    cx.background_executor()
+        .spawn(async move { operation.run(repo.as_ref(), Some(&askpass_path)) })
+        .await
+}
+
+/// Runs a remote operation. Askpass programs aren't supported on this platform, so credential
+/// prompts fail.
+#[cfg(not(unix))]
+async fn run_remote_operation(
+    repo: Arc<dyn GitRepository>,
+    operation: RemoteOperation,
+    _askpass: Arc<dyn AskPassDelegate>,
+    cx: &mut AsyncAppContext,
+) -> Result<()> {
+    cx.background_executor()
+        .spawn(async move { operation.run(repo.as_ref(), None) })
+        .await
+}
+
+/// Forwards the credential prompts of a remote operation to the downstream client that requested
+/// it. Collaborators can't enter the host's credentials.
+struct DownstreamAskPassDelegate {
+    downstream_client: Option<(AnyProtoClient, u64)>,
+    askpass_id: u64,
+}
+
+impl AskPassDelegate for DownstreamAskPassDelegate {
+    fn ask_password(
+        &self,
+        prompt: String,
+        cx: &mut AsyncAppContext,
+    ) -> futures::channel::oneshot::Receiver<Result<String>> {
+        let (tx, rx) = futures::channel::oneshot::channel();
+        match &self.downstream_client {
+            Some((client, project_id)) if !client.is_via_collab() => {
+                let request = client.request(proto::GitAskPass {
+                    project_id: *project_id,
+                    askpass_id: self.askpass_id,
+                    prompt,
+                });
+                cx.background_executor()
+                    .spawn(async move {
+                        tx.send(request.await.map(|response| response.response))
+                            .ok();
+                    })
+                    .detach();
+            }
+            _ => {
+                tx.send(Err(anyhow!("Credentials can only be entered on the host")))
+                    .ok();
+            }
+        }
+        rx
+    }
+}
+
+#[derive(Clone, Debug)]
+enum WorktreeHandle {
+    Strong(Model<Worktree>),
+    Weak(WeakModel<Worktree>),
+}
+
+impl WorktreeHandle {
+    fn upgrade(&self) -> Option<Model<Worktree>> {
+        match self {
+            WorktreeHandle::Strong(handle) => Some(handle.clone()),
+            WorktreeHandle::Weak(handle) => handle.upgrade(),
+        }
+    }
+}
+
+fn serialize_commit_summary(commit: CommitSummary) -> proto::GitCommitSummary {
+    proto::GitCommitSummary {
+        sha: commit.sha.to_string(),
+        parents: commit.parents.iter().map(ToString::to_string).collect(),
+        author_name: commit.author_name,
+        author_email: commit.author_email,
+        timestamp: commit.timestamp,
+        subject: commit.subject,
+        refs: commit.refs,
+    }
+}
+
+fn deserialize_commit_summary(commit: proto::GitCommitSummary) -> Result<CommitSummary> {
+    Ok(CommitSummary {
+        sha: commit.sha.parse()?,
+        parents: commit
+            .parents
+            .iter()
+            .map(|parent| parent.parse())
+            .collect::<Result<_>>()?,
+        author_name: commit.author_name,
+        author_email: commit.author_email,
+        timestamp: commit.timestamp,
+        subject: commit.subject,
+        refs: commit.refs,
+    })
+}
+
+fn serialize_rebase_todo_item(item: RebaseTodoItem) -> proto::GitRebaseTodoItem {
+    use proto::git_rebase_todo_item::Action;
+    let action = match item.action {
+        RebaseAction::Pick => Action::Pick,
+        RebaseAction::Reword => Action::Reword,
+        RebaseAction::Squash => Action::Squash,
+        RebaseAction::Fixup => Action::Fixup,
+        RebaseAction::Drop => Action::Drop,
+        RebaseAction::Command => Action::Command,
+    };
+    proto::GitRebaseTodoItem {
+        action: action as i32,
+        sha: item.sha,
+        subject: item.subject,
+        message: item.message,
+    }
+}
+
+fn deserialize_rebase_todo_item(item: proto::GitRebaseTodoItem) -> RebaseTodoItem {
+    use proto::git_rebase_todo_item::Action;
+    let action = match item.action() {
+        Action::Pick => RebaseAction::Pick,
+        Action::Reword => RebaseAction::Reword,
+        Action::Squash => RebaseAction::Squash,
+        Action::Fixup => RebaseAction::Fixup,
+        Action::Drop => RebaseAction::Drop,
+        Action::Command => RebaseAction::Command,
+    };
+    RebaseTodoItem {
+        action,
+        sha: item.sha,
+        subject: item.subject,
+        message: item.message,
+    }
+}
+
+fn serialize_rebase_outcome(outcome: RebaseOutcome) -> proto::GitRebaseResponse {
+    proto::GitRebaseResponse {
+        stopped_message: match outcome {
+            RebaseOutcome::Completed => None,
+            RebaseOutcome::Stopped { message } => Some(message),
+        },
+    }
+}
+
+fn deserialize_rebase_outcome(response: proto::GitRebaseResponse) -> RebaseOutcome {
+    match response.stopped_message {
+        Some(message) => RebaseOutcome::Stopped { message },
+        None => RebaseOutcome::Completed,
+    }
+}
+
+fn serialize_git_worktree(worktree: GitWorktree) -> proto::GitWorktree {
+    proto::GitWorktree {
+        path: worktree.path.to_string_lossy().to_string(),
+        head: worktree.head.map(|head| head.to_string()),
+        branch: worktree.branch,
+        is_main: worktree.is_main,
+        is_bare: worktree.is_bare,
+        is_locked: worktree.is_locked,
+        is_prunable: worktree.is_prunable,
+    }
+}
+
+fn deserialize_git_worktree(worktree: proto::GitWorktree) -> Result<GitWorktree> {
+    Ok(GitWorktree {
+        path: PathBuf::from(worktree.path),
+        head: worktree.head.map(|head| head.parse()).transpose()?,
+        branch: worktree.branch,
+        is_main: worktree.is_main,
+        is_bare: worktree.is_bare,
+        is_locked: worktree.is_locked,
+        is_prunable: worktree.is_prunable,
+    })
 }
@@ -631,6 +631,10 @@ impl ProjectPanel {
             let is_read_only = project.is_read_only(cx);
             let is_remote = project.is_via_collab();
             let is_local = project.is_local();
+            let history_path = worktree
+                .repository_for_path(&entry.path)
+                .map(|_| worktree.abs_path().join(&entry.path));
+            let compare_files = self.compare_marked_files(cx);
 
             let context_menu = ContextMenu::build(cx, |menu, _| {
                 menu.context(self.focus_handle.clone()).map(|menu| {
@@ -677,6 +681,16 @@ impl ProjectPanel {
                             .separator()
                             .action("Copy Path", Box::new(CopyPath))
                             .action("Copy Relative Path", Box::new(CopyRelativePath))
+                            .when_some(compare_files, |menu, action| {
+                                menu.separator()
+                                    .action("Compare Selected Files", Box::new(action))
+                            })
+                            .when_some(history_path, |menu, path| {
+                                menu.separator().action(
+                                    "View Git History",
+                                    Box::new(zed_actions::git::ViewHistory { path: Some(path) }),
+                                )
+                            })
                             .separator()
                             .action("Rename", Box::new(Rename))
                             .when(!is_root & !is_remote, |menu| {
@@ -2172,6 +2186,38 @@ impl ProjectPanel {
         sanitized_entries
     }
 
+    /// Returns the action comparing the two marked files, if exactly two files are marked. The
+    /// selected file is compared with the other one.
+    fn compare_marked_files(&self, cx: &AppContext) -> Option<zed_actions::git::CompareFiles> {
+        let selection = self.selection?;
+        let marked_entries = self.marked_entries();
+        if marked_entries.len() != 2 {
+            return None;
+        }
+
+        let project = self.project.read(cx);
+        let mut old_path = None;
+        let mut new_path = None;
+        for marked_entry in marked_entries {
+            let worktree = project.worktree_for_id(marked_entry.worktree_id, cx)?;
+            let worktree = worktree.read(cx);
+            let entry = worktree.entry_for_id(marked_entry.entry_id)?;
+            if !entry.is_file() {
+                return None;
+            }
+            let abs_path = worktree.abs_path().join(&entry.path);
+            if marked_entry.entry_id == self.resolve_entry(selection.entry_id) {
+                new_path = Some(abs_path);
+            } else {
+                old_path = Some(abs_path);
+            }
+        }
+        Some(zed_actions::git::CompareFiles {
+            old_path: old_path?,
+            new_path: new_path?,
+        })
+    }
+
     // Returns the union of the currently selected entry and all marked entries.
     fn marked_entries(&self) -> BTreeSet<SelectedEntry> {
         let mut entries = self
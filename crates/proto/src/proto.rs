@@ -188,7 +188,13 @@ messages!(
     (GetChannelMessagesResponse, Background),
     (GetCodeActions, Background),
     (GetCodeActionsResponse, Background),
+    (GetCodeLens, Background),
+    (GetCodeLensResponse, Background),
     (GetCompletions, Background),
+    (GetDocumentLinks, Background),
+    (GetDocumentLinksResponse, Background),
+    (GetFoldingRanges, Background),
+    (GetFoldingRangesResponse, Background),
     (GetCompletionsResponse, Background),
     (GetDefinition, Background),
     (GetDefinitionResponse, Background),
@@ -198,16 +204,26 @@ messages!(
     (GetDocumentHighlightsResponse, Background),
     (GetHover, Background),
     (GetHoverResponse, Background),
+    (GetIncomingCalls, Background),
+    (GetIncomingCallsResponse, Background),
     (GetNotifications, Foreground),
     (GetNotificationsResponse, Foreground),
+    (GetOutgoingCalls, Background),
+    (GetOutgoingCallsResponse, Background),
     (GetPrivateUserInfo, Foreground),
     (GetPrivateUserInfoResponse, Foreground),
     (GetProjectSymbols, Background),
     (GetProjectSymbolsResponse, Background),
     (GetReferences, Background),
     (GetReferencesResponse, Background),
+    (GetSemanticTokens, Background),
+    (GetSemanticTokensResponse, Background),
     (GetSignatureHelp, Background),
     (GetSignatureHelpResponse, Background),
+    (GetSubtypes, Background),
+    (GetSubtypesResponse, Background),
+    (GetSupertypes, Background),
+    (GetSupertypesResponse, Background),
     (GetSupermavenApiKey, Background),
     (GetSupermavenApiKeyResponse, Background),
     (GetTypeDefinition, Background),
@@ -249,13 +265,19 @@ messages!(
     (PerformRename, Background),
     (PerformRenameResponse, Background),
     (Ping, Foreground),
+    (PrepareCallHierarchy, Background),
+    (PrepareCallHierarchyResponse, Background),
     (PrepareRename, Background),
     (PrepareRenameResponse, Background),
+    (PrepareTypeHierarchy, Background),
+    (PrepareTypeHierarchyResponse, Background),
     (ProjectEntryResponse, Foreground),
     (CountLanguageModelTokens, Background),
     (CountLanguageModelTokensResponse, Background),
     (RefreshLlmToken, Background),
+    (RefreshCodeLens, Foreground),
     (RefreshInlayHints, Foreground),
+    (RefreshSemanticTokens, Foreground),
     (RejoinChannelBuffers, Foreground),
     (RejoinChannelBuffersResponse, Foreground),
     (RejoinRoom, Foreground),
@@ -273,6 +295,10 @@ messages!(
     (RequestContact, Foreground),
     (ResolveCompletionDocumentation, Background),
     (ResolveCompletionDocumentationResponse, Background),
+    (ResolveCodeLens, Background),
+    (ResolveCodeLensResponse, Background),
+    (ResolveDocumentLink, Background),
+    (ResolveDocumentLinkResponse, Background),
     (ResolveInlayHint, Background),
     (ResolveInlayHintResponse, Background),
     (RespondToChannelInvite, Foreground),
@@ -360,6 +386,39 @@ messages!(
     (GitBranches, Background),
     (GitBranchesResponse, Background),
     (UpdateGitBranch, Background),
+    (GitStagedPaths, Background),
+    (GitStagedPathsResponse, Background),
+    (GitStage, Background),
+    (GitUnstage, Background),
+    (GitDiscard, Background),
+    (GitCommit, Background),
+    (SetIndexText, Background),
+    (GitLog, Background),
+    (GitLogResponse, Background),
+    (GitCommitDiff, Background),
+    (GitCommitDiffResponse, Background),
+    (GitCommitMessage, Background),
+    (GitCommitMessageResponse, Background),
+    (GitRevisionText, Background),
+    (GitRevisionTextResponse, Background),
+    (GitChangedPaths, Background),
+    (GitChangedPathsResponse, Background),
+    (GitRemoteOperation, Background),
+    (GitAskPass, Background),
+    (GitAskPassResponse, Background),
+    (GitStashList, Background),
+    (GitStashListResponse, Background),
+    (GitStashPush, Background),
+    (GitStashAction, Background),
+    (GitRebaseTodo, Background),
+    (GitRebaseTodoResponse, Background),
+    (GitRebase, Background),
+    (GitRebaseResponse, Background),
+    (GitRebaseControl, Background),
+    (GitWorktreeList, Background),
+    (GitWorktreeListResponse, Background),
+    (GitCreateWorktree, Background),
+    (GitRemoveWorktree, Background),
     (ListToolchains, Foreground),
     (ListToolchainsResponse, Foreground),
     (ActivateToolchain, Foreground),
@@ -402,19 +461,27 @@ request_messages!(
     (GetChannelMessages, GetChannelMessagesResponse),
     (GetChannelMessagesById, GetChannelMessagesResponse),
     (GetCodeActions, GetCodeActionsResponse),
+    (GetCodeLens, GetCodeLensResponse),
     (GetCompletions, GetCompletionsResponse),
+    (GetDocumentLinks, GetDocumentLinksResponse),
+    (GetFoldingRanges, GetFoldingRangesResponse),
     (GetDefinition, GetDefinitionResponse),
     (GetDeclaration, GetDeclarationResponse),
     (GetImplementation, GetImplementationResponse),
     (GetDocumentHighlights, GetDocumentHighlightsResponse),
     (GetHover, GetHoverResponse),
+    (GetIncomingCalls, GetIncomingCallsResponse),
     (GetLlmToken, GetLlmTokenResponse),
     (GetNotifications, GetNotificationsResponse),
+    (GetOutgoingCalls, GetOutgoingCallsResponse),
     (GetPrivateUserInfo, GetPrivateUserInfoResponse),
     (GetProjectSymbols, GetProjectSymbolsResponse),
     (GetReferences, GetReferencesResponse),
+    (GetSemanticTokens, GetSemanticTokensResponse),
     (GetSignatureHelp, GetSignatureHelpResponse),
     (GetStagedText, GetStagedTextResponse),
+    (GetSubtypes, GetSubtypesResponse),
+    (GetSupertypes, GetSupertypesResponse),
     (GetSupermavenApiKey, GetSupermavenApiKeyResponse),
     (GetTypeDefinition, GetTypeDefinitionResponse),
     (LinkedEditingRange, LinkedEditingRangeResponse),
@@ -439,9 +506,13 @@ request_messages!(
     (OpenNewBuffer, OpenBufferResponse),
     (PerformRename, PerformRenameResponse),
     (Ping, Ack),
+    (PrepareCallHierarchy, PrepareCallHierarchyResponse),
     (PrepareRename, PrepareRenameResponse),
+    (PrepareTypeHierarchy, PrepareTypeHierarchyResponse),
     (CountLanguageModelTokens, CountLanguageModelTokensResponse),
+    (RefreshCodeLens, Ack),
     (RefreshInlayHints, Ack),
+    (RefreshSemanticTokens, Ack),
     (RejoinChannelBuffers, RejoinChannelBuffersResponse),
     (RejoinRoom, RejoinRoomResponse),
     (ReloadBuffers, ReloadBuffersResponse),
@@ -456,6 +527,8 @@ request_messages!(
         ResolveCompletionDocumentation,
         ResolveCompletionDocumentationResponse
     ),
+    (ResolveCodeLens, ResolveCodeLensResponse),
+    (ResolveDocumentLink, ResolveDocumentLinkResponse),
     (ResolveInlayHint, ResolveInlayHintResponse),
     (RespondToChannelInvite, Ack),
     (RespondToContactRequest, Ack),
@@ -492,6 +565,28 @@ request_messages!(
     (LanguageServerPromptRequest, LanguageServerPromptResponse),
     (GitBranches, GitBranchesResponse),
     (UpdateGitBranch, Ack),
+    (GitStagedPaths, GitStagedPathsResponse),
+    (GitStage, Ack),
+    (GitUnstage, Ack),
+    (GitDiscard, Ack),
+    (GitCommit, Ack),
+    (SetIndexText, Ack),
+    (GitLog, GitLogResponse),
+    (GitCommitDiff, GitCommitDiffResponse),
+    (GitCommitMessage, GitCommitMessageResponse),
+    (GitRevisionText, GitRevisionTextResponse),
+    (GitChangedPaths, GitChangedPathsResponse),
+    (GitRemoteOperation, Ack),
+    (GitAskPass, GitAskPassResponse),
+    (GitStashList, GitStashListResponse),
+    (GitStashPush, Ack),
+    (GitStashAction, Ack),
+    (GitRebaseTodo, GitRebaseTodoResponse),
+    (GitRebase, GitRebaseResponse),
+    (GitRebaseControl, GitRebaseResponse),
+    (GitWorktreeList, GitWorktreeListResponse),
+    (GitCreateWorktree, Ack),
+    (GitRemoveWorktree, Ack),
     (ListToolchains, ListToolchainsResponse),
     (ActivateToolchain, Ack),
     (ActiveToolchain, ActiveToolchainResponse),
@@ -521,16 +616,24 @@ entity_messages!(
     FindSearchCandidates,
     FormatBuffers,
     GetCodeActions,
+    GetCodeLens,
     GetCompletions,
+    GetDocumentLinks,
+    GetFoldingRanges,
     GetDefinition,
     GetDeclaration,
     GetImplementation,
     GetDocumentHighlights,
     GetHover,
+    GetIncomingCalls,
+    GetOutgoingCalls,
     GetProjectSymbols,
     GetReferences,
+    GetSemanticTokens,
     GetSignatureHelp,
     GetStagedText,
+    GetSubtypes,
+    GetSupertypes,
     GetTypeDefinition,
     InlayHints,
     JoinProject,
@@ -544,12 +647,18 @@ entity_messages!(
     OpenBufferByPath,
     OpenBufferForSymbol,
     PerformRename,
+    PrepareCallHierarchy,
     PrepareRename,
+    PrepareTypeHierarchy,
+    RefreshCodeLens,
     RefreshInlayHints,
+    RefreshSemanticTokens,
     ReloadBuffers,
     RemoveProjectCollaborator,
     RenameProjectEntry,
+    ResolveCodeLens,
     ResolveCompletionDocumentation,
+    ResolveDocumentLink,
     ResolveInlayHint,
     SaveBuffer,
     StartLanguageServer,
@@ -581,6 +690,28 @@ entity_messages!(
     LanguageServerPromptRequest,
     GitBranches,
     UpdateGitBranch,
+    GitStagedPaths,
+    GitStage,
+    GitUnstage,
+    GitDiscard,
+    GitCommit,
+    SetIndexText,
+    GitLog,
+    GitCommitDiff,
+    GitCommitMessage,
+    GitRevisionText,
+    GitChangedPaths,
+    GitRemoteOperation,
+    GitAskPass,
+    GitStashList,
+    GitStashPush,
+    GitStashAction,
+    GitRebaseTodo,
+    GitRebase,
+    GitRebaseControl,
+    GitWorktreeList,
+    GitCreateWorktree,
+    GitRemoveWorktree,
     ListToolchains,
     ActivateToolchain,
     ActiveToolchain,
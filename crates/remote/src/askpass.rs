@@ -0,0 +1,132 @@
+use anyhow::Result;
+use futures::channel::oneshot;
+use gpui::AsyncAppContext;
+#[cfg(unix)]
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// Answers the credential prompts of programs such as `ssh` and `git`, typically by asking the
+/// user.
+pub trait AskPassDelegate: Send + Sync {
+    fn ask_password(
+        &self,
+        prompt: String,
+        cx: &mut AsyncAppContext,
+    ) -> oneshot::Receiver<Result<String>>;
+}
+
+/// An askpass program forwarding the prompts it receives to an [`AskPassDelegate`].
+///
+/// The program is a script that sends its arguments over a unix domain socket and prints the
+/// reply, which makes it suitable for `SSH_ASKPASS` and `GIT_ASKPASS`. The script and the socket
+/// are removed when the session is dropped.
+#[cfg(unix)]
+pub struct AskPassSession {
+    script_path: PathBuf,
+    opened_rx: Option<oneshot::Receiver<()>>,
+    canceled_rx: Option<oneshot::Receiver<smol::net::unix::UnixStream>>,
+    _task: gpui::Task<()>,
+    _temp_dir: tempfile::TempDir,
+}
+
+#[cfg(unix)]
+impl AskPassSession {
+    pub async fn new(delegate: Arc<dyn AskPassDelegate>, cx: &mut AsyncAppContext) -> Result<Self> {
+        use anyhow::Context as _;
+        use futures::{io::BufReader, AsyncBufReadExt as _, AsyncWriteExt as _};
+        use smol::{fs::unix::PermissionsExt as _, net::unix::UnixListener};
+        use util::ResultExt as _;
+
+        anyhow::ensure!(
+            which::which("nc").is_ok(),
+            "Cannot find nc, which is required to prompt for credentials."
+        );
+
+        let temp_dir = tempfile::Builder::new().prefix("zed-askpass").tempdir()?;
+
+        // Create a domain socket listener to handle requests from the askpass program.
+        let askpass_socket = temp_dir.path().join("askpass.sock");
+        let (opened_tx, opened_rx) = oneshot::channel::<()>();
+        let listener =
+            UnixListener::bind(&askpass_socket).context("failed to create askpass socket")?;
+
+        let (canceled_tx, canceled_rx) = oneshot::channel();
+        let mut canceled_tx = Some(canceled_tx);
+
+        let task = cx.spawn(|mut cx| async move {
+            let mut opened_tx = Some(opened_tx);
+
+            while let Ok((mut stream, _)) = listener.accept().await {
+                if let Some(opened_tx) = opened_tx.take() {
+                    opened_tx.send(()).ok();
+                }
+                let mut buffer = Vec::new();
+                let mut reader = BufReader::new(&mut stream);
+                if reader.read_until(b'\0', &mut buffer).await.is_err() {
+                    buffer.clear();
+                }
+                let password_prompt = String::from_utf8_lossy(&buffer);
+                if let Some(password) = delegate
+                    .ask_password(password_prompt.to_string(), &mut cx)
+                    .await
+                    .context("failed to get password")
+                    .and_then(|p| p)
+                    .log_err()
+                {
+                    stream.write_all(password.as_bytes()).await.log_err();
+                } else {
+                    if let Some(canceled_tx) = canceled_tx.take() {
+                        canceled_tx.send(stream).ok();
+                    }
+                    break;
+                }
+            }
+        });
+
+        // Create an askpass script that communicates back to this process.
+        let askpass_script = format!(
+            "{shebang}\n{print_args} | {nc} -U {askpass_socket} 2> /dev/null \n",
+            // on macOS `brew install netcat` provides the GNU netcat implementation
+            // which does not support -U.
+            nc = if cfg!(target_os = "macos") {
+                "/usr/bin/nc"
+            } else {
+                "nc"
+            },
+            askpass_socket = askpass_socket.display(),
+            print_args = "printf '%s\\0' \"$@\"",
+            shebang = "#!/bin/sh",
+        );
+        let script_path = temp_dir.path().join("askpass.sh");
+        smol::fs::write(&script_path, askpass_script).await?;
+        smol::fs::set_permissions(&script_path, std::fs::Permissions::from_mode(0o755)).await?;
+
+        Ok(Self {
+            script_path,
+            opened_rx: Some(opened_rx),
+            canceled_rx: Some(canceled_rx),
+            _task: task,
+            _temp_dir: temp_dir,
+        })
+    }
+
+    /// The path of the askpass program.
+    pub fn script_path(&self) -> &Path {
+        &self.script_path
+    }
+
+    /// Returns a receiver notified the first time the askpass program is run, which means that
+    /// the user is being prompted. Can only be taken once.
+    pub fn take_opened_rx(&mut self) -> Option<oneshot::Receiver<()>> {
+        self.opened_rx.take()
+    }
+
+    /// Returns a receiver notified when a prompt is canceled, with the connection of the
+    /// askpass program that is still waiting for a reply. Subsequent prompts are rejected.
+    /// Can only be taken once.
+    pub fn take_canceled_rx(&mut self) -> Option<oneshot::Receiver<smol::net::unix::UnixStream>> {
+        self.canceled_rx.take()
+    }
+}
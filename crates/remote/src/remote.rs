@@ -1,8 +1,10 @@
+pub mod askpass;
 pub mod json_log;
 pub mod protocol;
 pub mod proxy;
 pub mod ssh_session;
 
+pub use askpass::AskPassDelegate;
 pub use ssh_session::{
     ConnectionState, SshClientDelegate, SshConnectionOptions, SshPlatform, SshRemoteClient,
     SshRemoteEvent,
@@ -1,3 +1,5 @@
+#[cfg(unix)]
+use crate::askpass::{AskPassDelegate, AskPassSession};
 use crate::{
     json_log::LogRecord,
     protocol::{
@@ -249,6 +251,21 @@ pub trait SshClientDelegate: Send + Sync {
     fn set_status(&self, status: Option<&str>, cx: &mut AsyncAppContext);
 }
 
+/// Forwards the credential prompts of `ssh` to an [`SshClientDelegate`].
+#[cfg(unix)]
+struct SshAskPassDelegate(Arc<dyn SshClientDelegate>);
+
+#[cfg(unix)]
+impl AskPassDelegate for SshAskPassDelegate {
+    fn ask_password(
+        &self,
+        prompt: String,
+        cx: &mut AsyncAppContext,
+    ) -> oneshot::Receiver<Result<String>> {
+        self.0.ask_password(prompt, cx)
+    }
+}
+
 impl SshSocket {
     // :WARNING: ssh unquotes arguments when executing on the remote :WARNING:
     // e.g. $ ssh host sh -c 'ls -l' is equivalent to $ ssh host sh -c ls -l
@@ -1374,12 +1391,6 @@ impl SshRemoteConnection {
         delegate: Arc<dyn SshClientDelegate>,
         cx: &mut AsyncAppContext,
     ) -> Result<Self> {
-        use futures::AsyncWriteExt as _;
-        use futures::{io::BufReader, AsyncBufReadExt as _};
-        use smol::net::unix::UnixStream;
-        use smol::{fs::unix::PermissionsExt as _, net::unix::UnixListener};
-        use util::ResultExt as _;
-
         delegate.set_status(Some("Connecting"), cx);
 
         let url = connection_options.ssh_url();
@@ -1387,70 +1398,12 @@ impl SshRemoteConnection {
             .prefix("zed-ssh-session")
             .tempdir()?;
 
-        // Create a domain socket listener to handle requests from the askpass program.
-        let askpass_socket = temp_dir.path().join("askpass.sock");
-        let (askpass_opened_tx, askpass_opened_rx) = oneshot::channel::<()>();
-        let listener =
-            UnixListener::bind(&askpass_socket).context("failed to create askpass socket")?;
-
-        let (askpass_kill_master_tx, askpass_kill_master_rx) = oneshot::channel::<UnixStream>();
-        let mut kill_tx = Some(askpass_kill_master_tx);
-
-        let askpass_task = cx.spawn({
-            let delegate = delegate.clone();
-            |mut cx| async move {
-                let mut askpass_opened_tx = Some(askpass_opened_tx);
-
-                while let Ok((mut stream, _)) = listener.accept().await {
-                    if let Some(askpass_opened_tx) = askpass_opened_tx.take() {
-                        askpass_opened_tx.send(()).ok();
-                    }
-                    let mut buffer = Vec::new();
-                    let mut reader = BufReader::new(&mut stream);
-                    if reader.read_until(b'\0', &mut buffer).await.is_err() {
-                        buffer.clear();
-                    }
-                    let password_prompt = String::from_utf8_lossy(&buffer);
-                    if let Some(password) = delegate
-                        .ask_password(password_prompt.to_string(), &mut cx)
-                        .await
-                        .context("failed to get ssh password")
-                        .and_then(|p| p)
-                        .log_err()
-                    {
-                        stream.write_all(password.as_bytes()).await.log_err();
-                    } else {
-                        if let Some(kill_tx) = kill_tx.take() {
-                            kill_tx.send(stream).log_err();
-                            break;
-                        }
-                    }
-                }
-            }
-        });
-
-        anyhow::ensure!(
-            which::which("nc").is_ok(),
-            "Cannot find nc, which is required to connect over ssh."
-        );
-
-        // Create an askpass script that communicates back to this process.
-        let askpass_script = format!(
-            "{shebang}\n{print_args} | {nc} -U {askpass_socket} 2> /dev/null \n",
-            // on macOS `brew install netcat` provides the GNU netcat implementation
-            // which does not support -U.
-            nc = if cfg!(target_os = "macos") {
-                "/usr/bin/nc"
-            } else {
-                "nc"
-            },
-            askpass_socket = askpass_socket.display(),
-            print_args = "printf '%s\\0' \"$@\"",
-            shebang = "#!/bin/sh",
-        );
-        let askpass_script_path = temp_dir.path().join("askpass.sh");
-        fs::write(&askpass_script_path, askpass_script).await?;
-        fs::set_permissions(&askpass_script_path, std::fs::Permissions::from_mode(0o755)).await?;
+        let mut askpass =
+            AskPassSession::new(Arc::new(SshAskPassDelegate(delegate.clone())), cx).await?;
+        let askpass_opened_rx = askpass.take_opened_rx().context("askpass already opened")?;
+        let askpass_kill_master_rx = askpass
+            .take_canceled_rx()
+            .context("askpass already canceled")?;
 
         // Start the master SSH process, which does not do anything except for establish
         // the connection and keep it open, allowing other ssh commands to reuse it
@@ -1462,7 +1415,7 @@ impl SshRemoteConnection {
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
             .env("SSH_ASKPASS_REQUIRE", "force")
-            .env("SSH_ASKPASS", &askpass_script_path)
+            .env("SSH_ASKPASS", askpass.script_path())
             .args(connection_options.additional_args().unwrap_or(&Vec::new()))
             .args([
                 "-N",
@@ -1512,7 +1465,7 @@ impl SshRemoteConnection {
             return Err(e.context("Failed to connect to host"));
         }
 
-        drop(askpass_task);
+        drop(askpass);
 
         if master_process.try_status()?.is_some() {
             output.clear();
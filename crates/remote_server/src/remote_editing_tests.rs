@@ -3,6 +3,11 @@ use client::{Client, UserStore};
 use clock::FakeSystemClock;
 use extension::ExtensionHostProxy;
 use fs::{FakeFs, Fs};
+use git::{
+    rebase::{RebaseAction, RebaseOutcome, RebaseTodoItem},
+    repository::{GitFileStatus, RemoteOperation, UpstreamBranch},
+    stash::{StashAction, StashOptions},
+};
 use gpui::{Context, Model, SemanticVersion, TestAppContext};
 use http_client::{BlockedHttpClient, FakeHttpClient};
 use language::{
@@ -15,7 +20,7 @@ use project::{
     search::{SearchQuery, SearchResult},
     Project, ProjectPath,
 };
-use remote::SshRemoteClient;
+use remote::{AskPassDelegate, SshRemoteClient};
 use serde_json::json;
 use settings::{initial_server_settings_content, Settings, SettingsLocation, SettingsStore};
 use smol::stream::StreamExt;
@@ -1226,6 +1231,757 @@ async fn test_remote_git_branches(cx: &mut TestAppContext, server_cx: &mut TestA
     assert_eq!(server_branch.as_ref(), "totally-new-branch");
 }
 
+#[gpui::test]
+async fn test_remote_git_staging_and_commit(
+    cx: &mut TestAppContext,
+    server_cx: &mut TestAppContext,
+) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                ".git": {},
+                "README.md": "# project 1",
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }",
+                },
+            },
+        }),
+    )
+    .await;
+
+    let (project, _headless_project) = init_test(&fs, cx, server_cx).await;
+    let dot_git = Path::new("/code/project1/.git");
+
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    let worktree_id = cx.update(|cx| worktree.read(cx).id());
+    cx.run_until_parked();
+
+    let readme: Arc<Path> = Path::new("README.md").into();
+    let lib: Arc<Path> = Path::new("src/lib.rs").into();
+    project
+        .update(cx, |project, cx| {
+            project.stage_paths(worktree_id, vec![readme.clone(), lib.clone()], cx)
+        })
+        .await
+        .unwrap();
+    project
+        .update(cx, |project, cx| {
+            project.unstage_paths(worktree_id, vec![readme.clone()], cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    let staged_paths = project
+        .update(cx, |project, cx| project.staged_paths(worktree_id, cx))
+        .await
+        .unwrap();
+    assert_eq!(staged_paths, [lib.clone()]);
+
+    // Discarding the changes of a path unstages it too.
+    project
+        .update(cx, |project, cx| {
+            project.stage_paths(worktree_id, vec![readme.clone()], cx)
+        })
+        .await
+        .unwrap();
+    project
+        .update(cx, |project, cx| {
+            project.discard_paths(worktree_id, vec![readme.clone()], cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    let staged_paths = project
+        .update(cx, |project, cx| project.staged_paths(worktree_id, cx))
+        .await
+        .unwrap();
+    assert_eq!(staged_paths, [lib.clone()]);
+
+    project
+        .update(cx, |project, cx| {
+            project.commit(
+                ProjectPath::root_path(worktree_id),
+                "Add lib".to_string(),
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    let mut commit_messages = Vec::new();
+    let mut staged_paths = Vec::new();
+    fs.with_git_state(dot_git, false, |state| {
+        commit_messages = state.commit_messages.clone();
+        staged_paths = state.staged_paths.iter().cloned().collect();
+    });
+    assert_eq!(commit_messages, ["Add lib"]);
+    assert!(staged_paths.is_empty());
+
+    // Committing without staged changes fails.
+    let result = project
+        .update(cx, |project, cx| {
+            project.commit(ProjectPath::root_path(worktree_id), "Empty".to_string(), cx)
+        })
+        .await;
+    assert!(result.is_err());
+}
+
+#[gpui::test]
+async fn test_remote_set_index_text(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                ".git": {},
+                "src": {
+                    "lib.rs": "fn one() -> usize { 1 }\nfn two() -> usize { 2 }\n",
+                },
+            },
+        }),
+    )
+    .await;
+    let dot_git = Path::new("/code/project1/.git");
+    let committed_text = "fn one() -> usize { 0 }\n".to_string();
+    fs.set_head_for_repo(
+        dot_git,
+        &[(Path::new("src/lib.rs"), committed_text.clone())],
+    );
+    fs.set_index_for_repo(
+        dot_git,
+        &[(Path::new("src/lib.rs"), committed_text.clone())],
+    );
+
+    let (project, _headless_project) = init_test(&fs, cx, server_cx).await;
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    let worktree_id = cx.update(|cx| worktree.read(cx).id());
+    cx.run_until_parked();
+
+    let buffer = project
+        .update(cx, |project, cx| {
+            project.open_buffer((worktree_id, Path::new("src/lib.rs")), cx)
+        })
+        .await
+        .unwrap();
+    let change_set = project
+        .update(cx, |project, cx| {
+            project.open_unstaged_changes(buffer.clone(), cx)
+        })
+        .await
+        .unwrap();
+    change_set.read_with(cx, |change_set, _| {
+        assert_eq!(change_set.committed_text.as_deref(), Some(&committed_text));
+    });
+
+    let staged_text = "fn one() -> usize { 1 }\n".to_string();
+    project
+        .update(cx, |project, cx| {
+            project.set_index_text(&buffer, Some(staged_text.clone()), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+
+    let mut index_text = None;
+    fs.with_git_state(dot_git, false, |state| {
+        index_text = state.index_contents.get(Path::new("src/lib.rs")).cloned();
+    });
+    assert_eq!(index_text.as_ref(), Some(&staged_text));
+    change_set.update(cx, |change_set, cx| {
+        assert_eq!(change_set.base_text_string(cx), Some(staged_text));
+    });
+}
+
+#[gpui::test]
+async fn test_remote_git_log(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                ".git": {},
+                "src": {
+                    "lib.rs": "fn one() -> usize { 2 }\n",
+                    "main.rs": "fn main() {}\n",
+                },
+            },
+        }),
+    )
+    .await;
+    let first_sha = git::Oid::from_bytes(&[1; 20]).unwrap();
+    let second_sha = git::Oid::from_bytes(&[2; 20]).unwrap();
+    let commit = |sha, parents, subject: &str| git::commit::CommitSummary {
+        sha,
+        parents,
+        author_name: "Zed".into(),
+        author_email: "hi@zed.dev".into(),
+        timestamp: 0,
+        subject: subject.into(),
+        refs: Vec::new(),
+    };
+    let file_diff =
+        |path: &str, old_text: Option<&str>, new_text: &str| git::commit::CommitFileDiff {
+            path: Path::new(path).into(),
+            old_text: old_text.map(Into::into),
+            new_text: Some(new_text.into()),
+        };
+    fs.set_commits_for_repo(
+        Path::new("/code/project1/.git"),
+        vec![
+            (
+                commit(second_sha, vec![first_sha], "Return two"),
+                vec![file_diff(
+                    "src/lib.rs",
+                    Some("fn one() -> usize { 1 }\n"),
+                    "fn one() -> usize { 2 }\n",
+                )],
+            ),
+            (
+                commit(first_sha, Vec::new(), "Initial commit"),
+                vec![
+                    file_diff("src/lib.rs", None, "fn one() -> usize { 1 }\n"),
+                    file_diff("src/main.rs", None, "fn main() {}\n"),
+                ],
+            ),
+        ],
+    );
+
+    let (project, _headless_project) = init_test(&fs, cx, server_cx).await;
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    let worktree_id = cx.update(|cx| worktree.read(cx).id());
+    cx.run_until_parked();
+
+    let main_path = ProjectPath {
+        worktree_id,
+        path: Path::new("src/main.rs").into(),
+    };
+    let commits = project
+        .update(cx, |project, cx| {
+            project.git_log(main_path.clone(), false, Default::default(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        commits
+            .iter()
+            .map(|commit| (commit.sha, commit.subject.as_str()))
+            .collect::<Vec<_>>(),
+        [(second_sha, "Return two"), (first_sha, "Initial commit")]
+    );
+    assert_eq!(commits[0].parents, [first_sha]);
+
+    let commits = project
+        .update(cx, |project, cx| {
+            project.git_log(main_path.clone(), true, Default::default(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        commits.iter().map(|commit| commit.sha).collect::<Vec<_>>(),
+        [first_sha]
+    );
+
+    let message = project
+        .update(cx, |project, cx| {
+            project.commit_message(main_path.clone(), second_sha.to_string()[..7].into(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(message, "Return two");
+
+    let files = project
+        .update(cx, |project, cx| {
+            project.commit_diff(main_path, second_sha, cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        files,
+        [file_diff(
+            "src/lib.rs",
+            Some("fn one() -> usize { 1 }\n"),
+            "fn one() -> usize { 2 }\n",
+        )]
+    );
+}
+
+#[gpui::test]
+async fn test_remote_changes_since_revision(
+    cx: &mut TestAppContext,
+    server_cx: &mut TestAppContext,
+) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                ".git": {},
+                "src": {
+                    "lib.rs": "fn one() -> usize { 2 }\n",
+                },
+            },
+        }),
+    )
+    .await;
+    fs.set_revision_for_repo(
+        Path::new("/code/project1/.git"),
+        "main",
+        &[(Path::new("src/lib.rs"), "fn one() -> usize { 1 }\n".into())],
+    );
+
+    let (project, _headless_project) = init_test(&fs, cx, server_cx).await;
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    let worktree_id = cx.update(|cx| worktree.read(cx).id());
+    cx.run_until_parked();
+
+    let lib_path = ProjectPath {
+        worktree_id,
+        path: Path::new("src/lib.rs").into(),
+    };
+    let changed_paths = project
+        .update(cx, |project, cx| {
+            project.changed_paths_since(ProjectPath::root_path(worktree_id), "main".into(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(changed_paths, [lib_path.clone()]);
+
+    let buffer = project
+        .update(cx, |project, cx| project.open_buffer(lib_path, cx))
+        .await
+        .unwrap();
+    let change_set = project
+        .update(cx, |project, cx| {
+            project.open_changes_since_revision(buffer.clone(), "main".into(), cx)
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    change_set.read_with(cx, |change_set, cx| {
+        assert_eq!(
+            change_set.base_text_string(cx).as_deref(),
+            Some("fn one() -> usize { 1 }\n")
+        );
+    });
+
+    let unknown_revision = project
+        .update(cx, |project, cx| {
+            project.open_changes_since_revision(buffer.clone(), "v1.0".into(), cx)
+        })
+        .await;
+    assert!(unknown_revision.is_err());
+}
+
+#[gpui::test]
+async fn test_remote_git_push(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                ".git": {},
+                "README.md": "# project 1",
+            },
+        }),
+    )
+    .await;
+    fs.insert_branches(Path::new("/code/project1/.git"), &["main"]);
+    fs.with_git_state(Path::new("/code/project1/.git"), true, |state| {
+        state.upstreams.insert(
+            "main".into(),
+            UpstreamBranch {
+                name: "origin/main".into(),
+                ahead: 2,
+                behind: 0,
+            },
+        );
+    });
+
+    let (project, _headless_project) = init_test(&fs, cx, server_cx).await;
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    let root_path = ProjectPath::root_path(cx.update(|cx| worktree.read(cx).id()));
+    cx.run_until_parked();
+
+    let upstream = |cx: &mut TestAppContext| {
+        let branches = project.update(cx, |project, cx| project.branches(root_path.clone(), cx));
+        async move { branches.await.unwrap().remove(0).upstream.unwrap() }
+    };
+    let main_upstream = upstream(cx).await;
+    assert_eq!(main_upstream.name.as_ref(), "origin/main");
+    assert_eq!((main_upstream.ahead, main_upstream.behind), (2, 0));
+
+    struct NoCredentials;
+    impl AskPassDelegate for NoCredentials {
+        fn ask_password(
+            &self,
+            _: String,
+            _: &mut gpui::AsyncAppContext,
+        ) -> futures::channel::oneshot::Receiver<anyhow::Result<String>> {
+            panic!("no credentials should be requested");
+        }
+    }
+
+    project
+        .update(cx, |project, cx| {
+            project.git_remote_operation(
+                root_path.clone(),
+                RemoteOperation::Push { force: false },
+                Arc::new(NoCredentials),
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    cx.run_until_parked();
+    let main_upstream = upstream(cx).await;
+    assert_eq!((main_upstream.ahead, main_upstream.behind), (0, 0));
+}
+
+#[gpui::test]
+async fn test_remote_git_stash(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                ".git": {},
+                "README.md": "# project 1",
+            },
+        }),
+    )
+    .await;
+    let dot_git = Path::new("/code/project1/.git");
+    fs.insert_branches(dot_git, &["main"]);
+    fs.set_status_for_repo_via_git_operation(
+        dot_git,
+        &[(Path::new("README.md"), GitFileStatus::Modified)],
+    );
+
+    let (project, _headless_project) = init_test(&fs, cx, server_cx).await;
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    let root_path = ProjectPath::root_path(cx.update(|cx| worktree.read(cx).id()));
+    cx.run_until_parked();
+    let readme_status = |cx: &mut TestAppContext| {
+        worktree.read_with(cx, |worktree, _| {
+            worktree.snapshot().status_for_file("README.md")
+        })
+    };
+    assert_eq!(readme_status(cx), Some(GitFileStatus::Modified));
+
+    project
+        .update(cx, |project, cx| {
+            project.stash_push(
+                root_path.clone(),
+                Some("halfway there".into()),
+                StashOptions::default(),
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    let entries = project
+        .update(cx, |project, cx| project.stash_list(root_path.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0].index, 0);
+    assert_eq!(entries[0].message, "On main: halfway there");
+    cx.run_until_parked();
+    assert_eq!(readme_status(cx), None);
+
+    // There is nothing left to stash.
+    assert!(project
+        .update(cx, |project, cx| {
+            project.stash_push(root_path.clone(), None, StashOptions::default(), cx)
+        })
+        .await
+        .is_err());
+
+    project
+        .update(cx, |project, cx| {
+            project.stash_action(root_path.clone(), StashAction::Pop, 0, cx)
+        })
+        .await
+        .unwrap();
+    let entries = project
+        .update(cx, |project, cx| project.stash_list(root_path.clone(), cx))
+        .await
+        .unwrap();
+    assert_eq!(entries, []);
+    cx.run_until_parked();
+    assert_eq!(readme_status(cx), Some(GitFileStatus::Modified));
+}
+
+#[gpui::test]
+async fn test_remote_git_rebase(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                ".git": {},
+                "README.md": "# project 1",
+            },
+        }),
+    )
+    .await;
+    let dot_git = Path::new("/code/project1/.git");
+    let [base_sha, a_sha, b_sha, c_sha] =
+        [1, 2, 3, 4].map(|byte| git::Oid::from_bytes(&[byte; 20]).unwrap());
+    let commit = |sha, parent, subject: &str| {
+        let commit = git::commit::CommitSummary {
+            sha,
+            parents: parent.into_iter().collect(),
+            author_name: "Zed".into(),
+            author_email: "hi@zed.dev".into(),
+            timestamp: 0,
+            subject: subject.into(),
+            refs: Vec::new(),
+        };
+        (commit, Vec::new())
+    };
+    fs.insert_branches(dot_git, &["main"]);
+    fs.set_commits_for_repo(
+        dot_git,
+        vec![
+            commit(c_sha, Some(b_sha), "Add c"),
+            commit(b_sha, Some(a_sha), "Fix a"),
+            commit(a_sha, Some(base_sha), "Add a"),
+            commit(base_sha, None, "Initial commit"),
+        ],
+    );
+    fs.with_git_state(dot_git, false, |state| {
+        state
+            .rebase_conflicts
+            .insert(a_sha.to_string(), vec![Path::new("README.md").into()]);
+    });
+
+    let (project, _headless_project) = init_test(&fs, cx, server_cx).await;
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    let root_path = ProjectPath::root_path(cx.update(|cx| worktree.read(cx).id()));
+    cx.run_until_parked();
+
+    let mut todo = project
+        .update(cx, |project, cx| {
+            project.rebase_todo(root_path.clone(), base_sha.to_string(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        todo,
+        [
+            RebaseTodoItem::pick(a_sha.to_string(), "Add a".into()),
+            RebaseTodoItem::pick(b_sha.to_string(), "Fix a".into()),
+            RebaseTodoItem::pick(c_sha.to_string(), "Add c".into()),
+        ]
+    );
+
+    // Move the last commit first and reword it, then fix the first commit up.
+    todo.rotate_right(1);
+    todo[0].action = RebaseAction::Reword;
+    todo[0].message = Some("Add c first".into());
+    todo[2].action = RebaseAction::Fixup;
+    let outcome = project
+        .update(cx, |project, cx| {
+            project.rebase(root_path.clone(), base_sha.to_string(), todo, cx)
+        })
+        .await
+        .unwrap();
+    assert!(matches!(outcome, RebaseOutcome::Stopped { .. }));
+    cx.run_until_parked();
+    let readme_status = |cx: &mut TestAppContext| {
+        worktree.read_with(cx, |worktree, _| {
+            worktree.snapshot().status_for_file("README.md")
+        })
+    };
+    assert_eq!(readme_status(cx), Some(GitFileStatus::Conflict));
+
+    // The rebase can't continue until the conflict is resolved.
+    let outcome = project
+        .update(cx, |project, cx| {
+            project.continue_rebase(root_path.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert!(matches!(outcome, RebaseOutcome::Stopped { .. }));
+
+    fs.set_status_for_repo_via_git_operation(dot_git, &[]);
+    let outcome = project
+        .update(cx, |project, cx| {
+            project.continue_rebase(root_path.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(outcome, RebaseOutcome::Completed);
+
+    let commits = project
+        .update(cx, |project, cx| {
+            project.git_log(root_path.clone(), false, Default::default(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        commits
+            .iter()
+            .map(|commit| commit.subject.as_str())
+            .collect::<Vec<_>>(),
+        ["Add a", "Add c first", "Initial commit"]
+    );
+    assert_eq!(commits[1].parents, [base_sha]);
+}
+
+#[gpui::test]
+async fn test_remote_git_worktrees(cx: &mut TestAppContext, server_cx: &mut TestAppContext) {
+    let fs = FakeFs::new(server_cx.executor());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "project1": {
+                ".git": {},
+                "README.md": "# project 1",
+            },
+        }),
+    )
+    .await;
+    fs.insert_branches(Path::new("/code/project1/.git"), &["main", "feature"]);
+
+    let (project, _headless_project) = init_test(&fs, cx, server_cx).await;
+    let (worktree, _) = project
+        .update(cx, |project, cx| {
+            project.find_or_create_worktree("/code/project1", true, cx)
+        })
+        .await
+        .unwrap();
+    let root_path = ProjectPath::root_path(cx.update(|cx| worktree.read(cx).id()));
+    cx.run_until_parked();
+
+    project
+        .update(cx, |project, cx| {
+            project.create_git_worktree(
+                root_path.clone(),
+                "/code/project1-feature".into(),
+                "feature".into(),
+                None,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    project
+        .update(cx, |project, cx| {
+            project.create_git_worktree(
+                root_path.clone(),
+                "/code/project1-review".into(),
+                "main".into(),
+                Some("review".into()),
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+
+    // A branch can only be checked out in one worktree.
+    assert!(project
+        .update(cx, |project, cx| {
+            project.create_git_worktree(
+                root_path.clone(),
+                "/code/project1-feature-2".into(),
+                "feature".into(),
+                None,
+                cx,
+            )
+        })
+        .await
+        .is_err());
+
+    let worktrees = project
+        .update(cx, |project, cx| {
+            project.git_worktrees(root_path.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        worktrees
+            .iter()
+            .map(|worktree| (
+                worktree.path.to_str().unwrap(),
+                worktree.branch.as_deref(),
+                worktree.is_main
+            ))
+            .collect::<Vec<_>>(),
+        [
+            ("/code/project1", Some("main"), true),
+            ("/code/project1-feature", Some("feature"), false),
+            ("/code/project1-review", Some("review"), false),
+        ]
+    );
+
+    project
+        .update(cx, |project, cx| {
+            project.remove_git_worktree(
+                root_path.clone(),
+                "/code/project1-feature".into(),
+                false,
+                cx,
+            )
+        })
+        .await
+        .unwrap();
+    let worktrees = project
+        .update(cx, |project, cx| {
+            project.git_worktrees(root_path.clone(), cx)
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        worktrees
+            .iter()
+            .map(|worktree| worktree.path.to_str().unwrap())
+            .collect::<Vec<_>>(),
+        ["/code/project1", "/code/project1-review"]
+    );
+}
+
 pub async fn init_test(
     server_fs: &Arc<FakeFs>,
     cx: &mut TestAppContext,
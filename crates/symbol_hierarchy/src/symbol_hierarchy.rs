@@ -0,0 +1,567 @@
+use std::ops::Range;
+
+use anyhow::Result;
+use editor::{
+    actions::{ShowIncomingCalls, ShowOutgoingCalls, ShowSubtypes, ShowSupertypes},
+    scroll::Autoscroll,
+    Editor, EditorMode,
+};
+use gpui::{
+    actions, Action, AppContext, EventEmitter, FocusHandle, FocusableView, Model, Task, View,
+    ViewContext, WeakView,
+};
+use language::{Buffer, OffsetRangeExt as _};
+use project::{CallHierarchyItem, Location, Project, TypeHierarchyItem};
+use ui::{prelude::*, ListItem, ListItemSpacing};
+use util::ResultExt as _;
+use workspace::{
+    item::{Item, ItemEvent},
+    Workspace,
+};
+
+actions!(
+    symbol_hierarchy,
+    [ToggleDirection, ExpandSelectedEntry, CollapseSelectedEntry]
+);
+
+pub fn init(cx: &mut AppContext) {
+    cx.observe_new_views(register).detach();
+}
+
+fn register(editor: &mut Editor, cx: &mut ViewContext<Editor>) {
+    if editor.mode() != EditorMode::Full {
+        return;
+    }
+    register_kind::<ShowIncomingCalls>(editor, HierarchyKind::IncomingCalls, cx);
+    register_kind::<ShowOutgoingCalls>(editor, HierarchyKind::OutgoingCalls, cx);
+    register_kind::<ShowSupertypes>(editor, HierarchyKind::Supertypes, cx);
+    register_kind::<ShowSubtypes>(editor, HierarchyKind::Subtypes, cx);
+}
+
+fn register_kind<A: Action>(
+    editor: &mut Editor,
+    kind: HierarchyKind,
+    cx: &mut ViewContext<Editor>,
+) {
+    let handle = cx.view().downgrade();
+    editor
+        .register_action(move |_: &A, cx| {
+            if let Some(editor) = handle.upgrade() {
+                deploy(editor, kind, cx);
+            }
+        })
+        .detach();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HierarchyKind {
+    /// Show the functions calling the symbol.
+    IncomingCalls,
+    /// Show the functions called by the symbol.
+    OutgoingCalls,
+    /// Show the types the symbol inherits from or implements.
+    Supertypes,
+    /// Show the types inheriting from or implementing the symbol.
+    Subtypes,
+}
+
+impl HierarchyKind {
+    fn label(&self) -> &'static str {
+        match self {
+            Self::IncomingCalls => "Incoming Calls",
+            Self::OutgoingCalls => "Outgoing Calls",
+            Self::Supertypes => "Supertypes",
+            Self::Subtypes => "Subtypes",
+        }
+    }
+
+    fn toggled(&self) -> Self {
+        match self {
+            Self::IncomingCalls => Self::OutgoingCalls,
+            Self::OutgoingCalls => Self::IncomingCalls,
+            Self::Supertypes => Self::Subtypes,
+            Self::Subtypes => Self::Supertypes,
+        }
+    }
+}
+
+/// A node of either a call or a type hierarchy.
+#[derive(Clone, Debug)]
+enum HierarchyItem {
+    Call(CallHierarchyItem),
+    Type(TypeHierarchyItem),
+}
+
+impl HierarchyItem {
+    fn name(&self) -> &str {
+        match self {
+            Self::Call(item) => &item.name,
+            Self::Type(item) => &item.name,
+        }
+    }
+
+    fn detail(&self) -> Option<&str> {
+        match self {
+            Self::Call(item) => item.detail.as_deref(),
+            Self::Type(item) => item.detail.as_deref(),
+        }
+    }
+
+    fn buffer(&self) -> &Model<Buffer> {
+        match self {
+            Self::Call(item) => &item.buffer,
+            Self::Type(item) => &item.buffer,
+        }
+    }
+
+    fn selection_range(&self) -> &Range<language::Anchor> {
+        match self {
+            Self::Call(item) => &item.selection_range,
+            Self::Type(item) => &item.selection_range,
+        }
+    }
+}
+
+/// Opens the hierarchy of the symbol under the newest cursor of the given editor.
+pub fn deploy(editor: View<Editor>, kind: HierarchyKind, cx: &mut WindowContext) {
+    let Some(workspace) = editor.read(cx).workspace() else {
+        return;
+    };
+    let Some((buffer, position)) = ({
+        let editor = editor.read(cx);
+        let head = editor.selections.newest_anchor().head();
+        editor.buffer().read(cx).text_anchor_for_position(head, cx)
+    }) else {
+        return;
+    };
+    let project = workspace.read(cx).project().clone();
+    let roots: Task<Result<Vec<HierarchyItem>>> = project.update(cx, |project, cx| match kind {
+        HierarchyKind::IncomingCalls | HierarchyKind::OutgoingCalls => {
+            let items = project.prepare_call_hierarchy(&buffer, position, cx);
+            cx.spawn(|_, _| async move {
+                Ok(items.await?.into_iter().map(HierarchyItem::Call).collect())
+            })
+        }
+        HierarchyKind::Supertypes | HierarchyKind::Subtypes => {
+            let items = project.prepare_type_hierarchy(&buffer, position, cx);
+            cx.spawn(|_, _| async move {
+                Ok(items.await?.into_iter().map(HierarchyItem::Type).collect())
+            })
+        }
+    });
+    let workspace = workspace.downgrade();
+    cx.spawn(|mut cx| async move {
+        let roots = roots.await?;
+        if roots.is_empty() {
+            return Ok(());
+        }
+        workspace.update(&mut cx, |workspace, cx| {
+            let weak_workspace = workspace.weak_handle();
+            let view = cx
+                .new_view(|cx| SymbolHierarchyView::new(roots, kind, weak_workspace, project, cx));
+            workspace.add_item_to_active_pane(Box::new(view), None, true, cx);
+        })
+    })
+    .detach_and_log_err(cx);
+}
+
+enum Children {
+    Unloaded,
+    Loading(Task<()>),
+    Loaded(Vec<usize>),
+}
+
+struct HierarchyEntry {
+    item: HierarchyItem,
+    /// For call hierarchies, where the calls between this entry and its parent happen.
+    call_sites: Vec<Location>,
+    depth: usize,
+    expanded: bool,
+    children: Children,
+}
+
+/// A tree of the callers, callees, supertypes or subtypes of a symbol, expanded lazily one
+/// level at a time.
+pub struct SymbolHierarchyView {
+    workspace: WeakView<Workspace>,
+    project: Model<Project>,
+    kind: HierarchyKind,
+    roots: Vec<HierarchyItem>,
+    /// All entries loaded so far. The first `roots.len()` entries are the roots of the tree.
+    entries: Vec<HierarchyEntry>,
+    selected_entry: Option<usize>,
+    focus_handle: FocusHandle,
+}
+
+impl SymbolHierarchyView {
+    fn new(
+        roots: Vec<HierarchyItem>,
+        kind: HierarchyKind,
+        workspace: WeakView<Workspace>,
+        project: Model<Project>,
+        cx: &mut ViewContext<Self>,
+    ) -> Self {
+        let mut this = Self {
+            workspace,
+            project,
+            kind,
+            roots,
+            entries: Vec::new(),
+            selected_entry: None,
+            focus_handle: cx.focus_handle(),
+        };
+        this.reset_entries(cx);
+        this
+    }
+
+    fn reset_entries(&mut self, cx: &mut ViewContext<Self>) {
+        self.entries = self
+            .roots
+            .iter()
+            .map(|item| HierarchyEntry {
+                item: item.clone(),
+                call_sites: Vec::new(),
+                depth: 0,
+                expanded: false,
+                children: Children::Unloaded,
+            })
+            .collect();
+        self.selected_entry = (!self.entries.is_empty()).then_some(0);
+        for ix in 0..self.entries.len() {
+            self.toggle_expanded(ix, cx);
+        }
+    }
+
+    fn toggle_direction(&mut self, _: &ToggleDirection, cx: &mut ViewContext<Self>) {
+        self.kind = self.kind.toggled();
+        self.reset_entries(cx);
+        cx.emit(ItemEvent::UpdateTab);
+        cx.notify();
+    }
+
+    fn toggle_expanded(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(entry) = self.entries.get_mut(ix) else {
+            return;
+        };
+        entry.expanded = !entry.expanded;
+        if entry.expanded && matches!(entry.children, Children::Unloaded) {
+            let children = self.load_children(entry.item.clone(), cx);
+            self.entries[ix].children = Children::Loading(cx.spawn(|this, mut cx| async move {
+                let children = children.await.log_err().unwrap_or_default();
+                this.update(&mut cx, |this, cx| {
+                    this.insert_children(ix, children);
+                    cx.notify();
+                })
+                .ok();
+            }));
+        }
+        cx.notify();
+    }
+
+    fn load_children(
+        &self,
+        item: HierarchyItem,
+        cx: &mut ViewContext<Self>,
+    ) -> Task<Result<Vec<(HierarchyItem, Vec<Location>)>>> {
+        let kind = self.kind;
+        self.project.update(cx, |project, cx| match (kind, item) {
+            (HierarchyKind::IncomingCalls, HierarchyItem::Call(item)) => {
+                let calls = project.incoming_calls(&item, cx);
+                cx.spawn(|_, _| async move {
+                    Ok(calls
+                        .await?
+                        .into_iter()
+                        .map(|call| (HierarchyItem::Call(call.item), call.call_sites))
+                        .collect())
+                })
+            }
+            (HierarchyKind::OutgoingCalls, HierarchyItem::Call(item)) => {
+                let calls = project.outgoing_calls(&item, cx);
+                cx.spawn(|_, _| async move {
+                    Ok(calls
+                        .await?
+                        .into_iter()
+                        .map(|call| (HierarchyItem::Call(call.item), call.call_sites))
+                        .collect())
+                })
+            }
+            (HierarchyKind::Supertypes, HierarchyItem::Type(item)) => {
+                let items = project.supertypes(&item, cx);
+                cx.spawn(|_, _| async move {
+                    Ok(items
+                        .await?
+                        .into_iter()
+                        .map(|item| (HierarchyItem::Type(item), Vec::new()))
+                        .collect())
+                })
+            }
+            (HierarchyKind::Subtypes, HierarchyItem::Type(item)) => {
+                let items = project.subtypes(&item, cx);
+                cx.spawn(|_, _| async move {
+                    Ok(items
+                        .await?
+                        .into_iter()
+                        .map(|item| (HierarchyItem::Type(item), Vec::new()))
+                        .collect())
+                })
+            }
+            // Toggling the direction keeps the kind of hierarchy, so its items always match.
+            _ => Task::ready(Ok(Vec::new())),
+        })
+    }
+
+    fn insert_children(&mut self, parent_ix: usize, items: Vec<(HierarchyItem, Vec<Location>)>) {
+        let depth = self.entries[parent_ix].depth + 1;
+        let mut children = Vec::with_capacity(items.len());
+        for (item, call_sites) in items {
+            children.push(self.entries.len());
+            self.entries.push(HierarchyEntry {
+                item,
+                call_sites,
+                depth,
+                expanded: false,
+                children: Children::Unloaded,
+            });
+        }
+        self.entries[parent_ix].children = Children::Loaded(children);
+    }
+
+    /// Returns the indices of the entries that are currently visible, in display order.
+    fn visible_entries(&self) -> Vec<usize> {
+        let mut visible = Vec::new();
+        let mut stack = (0..self.roots.len().min(self.entries.len()))
+            .rev()
+            .collect::<Vec<_>>();
+        while let Some(ix) = stack.pop() {
+            visible.push(ix);
+            let entry = &self.entries[ix];
+            if let (true, Children::Loaded(children)) = (entry.expanded, &entry.children) {
+                stack.extend(children.iter().rev());
+            }
+        }
+        visible
+    }
+
+    fn select_next(&mut self, _: &menu::SelectNext, cx: &mut ViewContext<Self>) {
+        let visible = self.visible_entries();
+        let next = match self
+            .selected_entry
+            .and_then(|selected| visible.iter().position(|ix| *ix == selected))
+        {
+            Some(position) => visible.get(position + 1).or(visible.last()),
+            None => visible.first(),
+        };
+        self.selected_entry = next.copied();
+        cx.notify();
+    }
+
+    fn select_prev(&mut self, _: &menu::SelectPrev, cx: &mut ViewContext<Self>) {
+        let visible = self.visible_entries();
+        let prev = match self
+            .selected_entry
+            .and_then(|selected| visible.iter().position(|ix| *ix == selected))
+        {
+            Some(position) => visible.get(position.saturating_sub(1)),
+            None => visible.last(),
+        };
+        self.selected_entry = prev.copied();
+        cx.notify();
+    }
+
+    fn confirm(&mut self, _: &menu::Confirm, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.selected_entry {
+            self.open_entry(ix, cx);
+        }
+    }
+
+    fn expand_selected_entry(&mut self, _: &ExpandSelectedEntry, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.selected_entry {
+            if !self.entries[ix].expanded {
+                self.toggle_expanded(ix, cx);
+            }
+        }
+    }
+
+    fn collapse_selected_entry(&mut self, _: &CollapseSelectedEntry, cx: &mut ViewContext<Self>) {
+        if let Some(ix) = self.selected_entry {
+            if self.entries[ix].expanded {
+                self.toggle_expanded(ix, cx);
+            }
+        }
+    }
+
+    fn open_entry(&mut self, ix: usize, cx: &mut ViewContext<Self>) {
+        let Some(entry) = self.entries.get(ix) else {
+            return;
+        };
+        // Prefer jumping to where the call happens, falling back to the symbol itself.
+        let (buffer, range) = match entry.call_sites.first() {
+            Some(call_site) => (call_site.buffer.clone(), call_site.range.clone()),
+            None => (
+                entry.item.buffer().clone(),
+                entry.item.selection_range().clone(),
+            ),
+        };
+        self.selected_entry = Some(ix);
+        self.open_location(buffer, range, cx);
+        cx.notify();
+    }
+
+    fn open_location(
+        &self,
+        buffer: Model<Buffer>,
+        range: Range<language::Anchor>,
+        cx: &mut ViewContext<Self>,
+    ) {
+        let range = range.to_point(buffer.read(cx));
+        self.workspace
+            .update(cx, |workspace, cx| {
+                let pane = workspace.adjacent_pane(cx);
+                let editor = workspace.open_project_item::<Editor>(pane, buffer, true, true, cx);
+                editor.update(cx, |editor, cx| {
+                    editor.change_selections(Some(Autoscroll::center()), cx, |selections| {
+                        selections.select_ranges([range.start..range.start])
+                    });
+                });
+            })
+            .log_err();
+    }
+
+    fn render_entry(&self, ix: usize, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let entry = &self.entries[ix];
+        let buffer = entry.item.buffer().read(cx);
+        let row = entry.item.selection_range().to_point(buffer).start.row;
+        let location = buffer
+            .file()
+            .map(|file| format!("{}:{}", file.path().display(), row + 1));
+        let is_loading = matches!(entry.children, Children::Loading(_));
+        let has_children = match &entry.children {
+            Children::Loaded(children) => !children.is_empty(),
+            Children::Unloaded | Children::Loading(_) => true,
+        };
+
+        ListItem::new(("symbol-hierarchy-entry", ix))
+            .spacing(ListItemSpacing::Dense)
+            .indent_level(entry.depth)
+            .indent_step_size(px(12.))
+            .toggle(has_children.then_some(entry.expanded))
+            .toggle_state(self.selected_entry == Some(ix))
+            .on_toggle(cx.listener(move |this, _, cx| this.toggle_expanded(ix, cx)))
+            .on_click(cx.listener(move |this, _, cx| this.open_entry(ix, cx)))
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(Label::new(entry.item.name().to_string()))
+                    .when_some(entry.item.detail(), |this, detail| {
+                        this.child(
+                            Label::new(detail.to_string())
+                                .size(LabelSize::Small)
+                                .color(Color::Muted)
+                                .single_line(),
+                        )
+                    })
+                    .when_some(location, |this, location| {
+                        this.child(
+                            Label::new(location)
+                                .size(LabelSize::Small)
+                                .color(Color::Placeholder),
+                        )
+                    })
+                    .when(entry.call_sites.len() > 1, |this| {
+                        this.child(
+                            Label::new(format!("{} calls", entry.call_sites.len()))
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    })
+                    .when(is_loading, |this| {
+                        this.child(
+                            Label::new("Loading…")
+                                .size(LabelSize::Small)
+                                .color(Color::Muted),
+                        )
+                    }),
+            )
+    }
+}
+
+impl Render for SymbolHierarchyView {
+    fn render(&mut self, cx: &mut ViewContext<Self>) -> impl IntoElement {
+        let visible_entries = self.visible_entries();
+        v_flex()
+            .key_context("SymbolHierarchy")
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::toggle_direction))
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_prev))
+            .on_action(cx.listener(Self::confirm))
+            .on_action(cx.listener(Self::expand_selected_entry))
+            .on_action(cx.listener(Self::collapse_selected_entry))
+            .size_full()
+            .bg(cx.theme().colors().editor_background)
+            .child(
+                h_flex()
+                    .px_2()
+                    .py_1()
+                    .justify_between()
+                    .border_b_1()
+                    .border_color(cx.theme().colors().border_variant)
+                    .child(Label::new(self.kind.label()).color(Color::Muted))
+                    .child(
+                        Button::new(
+                            "toggle-symbol-hierarchy-direction",
+                            format!("Show {}", self.kind.toggled().label()),
+                        )
+                        .label_size(LabelSize::Small)
+                        .on_click(
+                            cx.listener(|this, _, cx| this.toggle_direction(&ToggleDirection, cx)),
+                        ),
+                    ),
+            )
+            .child(
+                v_flex()
+                    .id("symbol-hierarchy-entries")
+                    .flex_1()
+                    .p_1()
+                    .overflow_y_scroll()
+                    .children(
+                        visible_entries
+                            .into_iter()
+                            .map(|ix| self.render_entry(ix, cx)),
+                    ),
+            )
+    }
+}
+
+impl FocusableView for SymbolHierarchyView {
+    fn focus_handle(&self, _: &AppContext) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl EventEmitter<ItemEvent> for SymbolHierarchyView {}
+
+impl Item for SymbolHierarchyView {
+    type Event = ItemEvent;
+
+    fn tab_content_text(&self, _: &WindowContext) -> Option<SharedString> {
+        let name = self
+            .roots
+            .first()
+            .map(|item| item.name())
+            .unwrap_or_default();
+        Some(format!("{}: {name}", self.kind.label()).into())
+    }
+
+    fn tab_icon(&self, _: &WindowContext) -> Option<Icon> {
+        Some(Icon::new(IconName::ListTree))
+    }
+
+    fn telemetry_event_text(&self) -> Option<&'static str> {
+        Some("symbol hierarchy")
+    }
+
+    fn to_item_events(event: &Self::Event, mut f: impl FnMut(ItemEvent)) {
+        f(*event)
+    }
+}
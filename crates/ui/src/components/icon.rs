@@ -162,6 +162,12 @@ pub enum IconName {
     CursorIBeam,
     Dash,
     DatabaseZap,
+    DebugBreakpoint,
+    DebugContinue,
+    DebugPause,
+    DebugStepInto,
+    DebugStepOut,
+    DebugStepOver,
     Delete,
     Diff,
     Disconnected,
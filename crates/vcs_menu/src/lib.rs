@@ -1,19 +1,26 @@
 use anyhow::{anyhow, Context, Result};
 use fuzzy::{StringMatch, StringMatchCandidate};
-use git::repository::Branch;
+use git::{
+    repository::{Branch, UpstreamBranch},
+    stash::StashOptions,
+};
 use gpui::{
-    rems, AnyElement, AppContext, AsyncAppContext, DismissEvent, EventEmitter, FocusHandle,
-    FocusableView, InteractiveElement, IntoElement, ParentElement, Render, SharedString, Styled,
-    Subscription, Task, View, ViewContext, VisualContext, WeakView, WindowContext,
+    rems, Action, AnyElement, AppContext, AsyncAppContext, DismissEvent, EventEmitter, FocusHandle,
+    FocusableView, InteractiveElement, IntoElement, ParentElement, PromptLevel, Render,
+    SharedString, Styled, Subscription, Task, View, ViewContext, VisualContext, WeakView,
+    WindowContext,
 };
 use picker::{Picker, PickerDelegate};
 use project::ProjectPath;
 use std::{ops::Not, sync::Arc};
-use ui::{prelude::*, HighlightedLabel, ListItem, ListItemSpacing};
+use ui::{prelude::*, HighlightedLabel, KeyBinding, ListItem, ListItemSpacing};
 use util::ResultExt;
 use workspace::notifications::DetachAndPromptErr;
 use workspace::{ModalView, Workspace};
-use zed_actions::branches::OpenRecent;
+use zed_actions::{
+    branches::OpenRecent,
+    git::{Fetch, Pull, Push},
+};
 
 pub fn init(cx: &mut AppContext) {
     cx.observe_new_views(|workspace: &mut Workspace, _| {
@@ -130,6 +137,27 @@ impl BranchListDelegate {
     }
 }
 
+impl BranchListDelegate {
+    fn upstream(&self, branch_name: &str) -> Option<&UpstreamBranch> {
+        self.all_branches
+            .iter()
+            .find(|branch| branch.name.as_ref() == branch_name)?
+            .upstream
+            .as_ref()
+    }
+}
+
+/// Describes how far a branch is from its upstream, e.g. `↑2 ↓1` for a branch with 2 commits to
+/// push and 1 to pull.
+fn upstream_status(upstream: &UpstreamBranch) -> Option<String> {
+    match (upstream.ahead, upstream.behind) {
+        (0, 0) => None,
+        (ahead, 0) => Some(format!("↑{ahead}")),
+        (0, behind) => Some(format!("↓{behind}")),
+        (ahead, behind) => Some(format!("↑{ahead} ↓{behind}")),
+    }
+}
+
 impl PickerDelegate for BranchListDelegate {
     type ListItem = ListItem;
 
@@ -229,34 +257,86 @@ impl PickerDelegate for BranchListDelegate {
         cx.spawn({
             let branch = branch.clone();
             |picker, mut cx| async move {
-                let branch_change_task = picker.update(&mut cx, |this, cx| {
-                    let workspace = this
-                        .delegate
-                        .workspace
-                        .upgrade()
-                        .ok_or_else(|| anyhow!("workspace was dropped"))?;
-
-                    let project = workspace.read(cx).project().read(cx);
-                    let branch_to_checkout = match branch {
-                        BranchEntry::Branch(branch) => branch.string,
-                        BranchEntry::NewBranch { name: branch_name } => branch_name,
-                    };
-                    let worktree = project
-                        .visible_worktrees(cx)
-                        .next()
-                        .context("worktree disappeared")?;
-                    let repository = ProjectPath::root_path(worktree.read(cx).id());
-
-                    anyhow::Ok(project.update_or_create_branch(repository, branch_to_checkout, cx))
-                })??;
-
-                branch_change_task.await?;
-
-                picker.update(&mut cx, |_, cx| {
-                    cx.emit(DismissEvent);
-
-                    Ok::<(), anyhow::Error>(())
-                })
+                let (project, repository, branch_to_checkout) =
+                    picker.update(&mut cx, |this, cx| {
+                        let workspace = this
+                            .delegate
+                            .workspace
+                            .upgrade()
+                            .ok_or_else(|| anyhow!("workspace was dropped"))?;
+
+                        let project = workspace.read(cx).project().clone();
+                        let branch_to_checkout = match branch {
+                            BranchEntry::Branch(branch) => branch.string,
+                            BranchEntry::NewBranch { name: branch_name } => branch_name,
+                        };
+                        let worktree = project
+                            .read(cx)
+                            .visible_worktrees(cx)
+                            .next()
+                            .context("worktree disappeared")?;
+                        let repository = ProjectPath::root_path(worktree.read(cx).id());
+
+                        anyhow::Ok((project, repository, branch_to_checkout))
+                    })??;
+
+                let branch_change_task = project.update(&mut cx, |project, cx| {
+                    project.update_or_create_branch(
+                        repository.clone(),
+                        branch_to_checkout.clone(),
+                        cx,
+                    )
+                })?;
+                if let Err(error) = branch_change_task.await {
+                    // Checking out another branch fails when it would overwrite local changes,
+                    // which can be stashed to carry on.
+                    let has_local_changes = project.update(&mut cx, |project, cx| {
+                        project.worktree_for_id(repository.worktree_id, cx).map_or(
+                            false,
+                            |worktree| {
+                                worktree
+                                    .read(cx)
+                                    .entries(false, 0)
+                                    .any(|entry| entry.git_status.is_some())
+                            },
+                        )
+                    })?;
+                    if !has_local_changes {
+                        return Err(error);
+                    }
+
+                    let answer = picker.update(&mut cx, |_, cx| {
+                        cx.prompt(
+                            PromptLevel::Info,
+                            &format!("Switching to {branch_to_checkout} failed: {error}"),
+                            Some("Stash your local changes and try again?"),
+                            &["Stash and Switch", "Cancel"],
+                        )
+                    })?;
+                    if answer.await? != 0 {
+                        return Ok(());
+                    }
+
+                    let stash_message = format!("Switching to {branch_to_checkout}");
+                    project
+                        .update(&mut cx, |project, cx| {
+                            project.stash_push(
+                                repository.clone(),
+                                Some(stash_message),
+                                StashOptions::default(),
+                                cx,
+                            )
+                        })?
+                        .await?;
+                    project
+                        .update(&mut cx, |project, cx| {
+                            project.update_or_create_branch(repository, branch_to_checkout, cx)
+                        })?
+                        .await?;
+                }
+
+                picker.update(&mut cx, |_, cx| cx.emit(DismissEvent))?;
+                Ok(())
             }
         })
         .detach_and_prompt_err("Failed to change branch", cx, |_, _| None);
@@ -290,7 +370,17 @@ impl PickerDelegate for BranchListDelegate {
                             .copied()
                             .collect();
 
-                        parent.child(HighlightedLabel::new(shortened_branch_name, highlights))
+                        parent
+                            .child(HighlightedLabel::new(shortened_branch_name, highlights))
+                            .end_slot::<Label>(
+                                self.upstream(&branch.string).and_then(upstream_status).map(
+                                    |status| {
+                                        Label::new(status)
+                                            .size(LabelSize::Small)
+                                            .color(Color::Muted)
+                                    },
+                                ),
+                            )
                     }
                     BranchEntry::NewBranch { name } => {
                         parent.child(Label::new(format!("Create branch '{name}'")))
@@ -322,4 +412,58 @@ impl PickerDelegate for BranchListDelegate {
         };
         Some(v_flex().mt_1().child(label).into_any_element())
     }
+
+    fn render_footer(&self, cx: &mut ViewContext<Picker<Self>>) -> Option<AnyElement> {
+        let head = self.all_branches.iter().find(|branch| branch.is_head)?;
+        let upstream = head.upstream.as_ref();
+        let upstream_label = match upstream {
+            Some(upstream) => format!("Tracking {}", upstream.name),
+            None => "No upstream branch".to_string(),
+        };
+        let count_suffix = |count: Option<u32>, arrow: &str| match count {
+            Some(count) if count > 0 => format!(" {arrow}{count}"),
+            _ => String::new(),
+        };
+        let actions: [(&'static str, String, Box<dyn Action>); 3] = [
+            ("fetch", "Fetch".to_string(), Box::new(Fetch)),
+            (
+                "pull",
+                format!("Pull{}", count_suffix(upstream.map(|u| u.behind), "↓")),
+                Box::new(Pull),
+            ),
+            (
+                "push",
+                format!("Push{}", count_suffix(upstream.map(|u| u.ahead), "↑")),
+                Box::new(Push),
+            ),
+        ];
+        let buttons = actions
+            .into_iter()
+            .map(|(id, label, action)| {
+                Button::new(id, label)
+                    .key_binding(KeyBinding::for_action(action.as_ref(), cx))
+                    .on_click(cx.listener(move |picker, _, cx| {
+                        cx.dispatch_action(action.boxed_clone());
+                        picker.delegate.dismissed(cx);
+                    }))
+            })
+            .collect::<Vec<_>>();
+
+        Some(
+            h_flex()
+                .w_full()
+                .p_2()
+                .gap_2()
+                .justify_between()
+                .border_t_1()
+                .border_color(cx.theme().colors().border_variant)
+                .child(
+                    Label::new(upstream_label)
+                        .size(LabelSize::Small)
+                        .color(Color::Muted),
+                )
+                .child(h_flex().gap_2().children(buttons))
+                .into_any(),
+        )
+    }
 }
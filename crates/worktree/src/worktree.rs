@@ -22,6 +22,7 @@ use git::GitHostingProviderRegistry;
 use git::{
     repository::{GitFileStatus, GitRepository, RepoPath},
     status::GitStatus,
+    worktree::parse_gitdir_file,
     COOKIES, DOT_GIT, FSMONITOR_DAEMON, GITIGNORE,
 };
 use gpui::{
@@ -238,6 +239,18 @@ impl RepositoryEntry {
             relativize_path(path)
         }
     }
+
+    /// unrelativize is the inverse of relativize, returning the worktree path of the given
+    /// repository path, or `None` if the path lies outside of the worktree.
+    pub fn unrelativize(&self, worktree: &Snapshot, path: &RepoPath) -> Option<Arc<Path>> {
+        let entry = worktree.entry_for_id(self.work_directory.0)?;
+        let path = if let Some(location_in_repo) = &self.location_in_repo {
+            path.strip_prefix(location_in_repo).ok()?
+        } else {
+            path.as_path()
+        };
+        Some(entry.path.join(path).into())
+    }
 }
 
 impl From<&RepositoryEntry> for proto::RepositoryEntry {
@@ -733,6 +746,34 @@ impl Worktree {
         }
     }
 
+    pub fn load_committed_file(
+        &self,
+        path: &Path,
+        cx: &AppContext,
+    ) -> Task<Result<Option<String>>> {
+        match self {
+            Worktree::Local(this) => {
+                let path = Arc::from(path);
+                let snapshot = this.snapshot();
+                cx.background_executor().spawn(async move {
+                    if let Some(repo) = snapshot.repository_for_path(&path) {
+                        if let Some(repo_path) = repo.relativize(&snapshot, &path).log_err() {
+                            if let Some(git_repo) =
+                                snapshot.git_repositories.get(&*repo.work_directory)
+                            {
+                                return Ok(git_repo.repo_ptr.load_committed_text(&repo_path));
+                            }
+                        }
+                    }
+                    Ok(None)
+                })
+            }
+            Worktree::Remote(_) => Task::ready(Err(anyhow!(
+                "remote worktrees can't yet load committed files"
+            ))),
+        }
+    }
+
     pub fn load_binary_file(
         &self,
         path: &Path,
@@ -3120,7 +3161,11 @@ impl BackgroundScannerState {
 
         let actual_repo_path = repository.dot_git_dir();
 
-        let actual_dot_git_dir_abs_path = smol::block_on(find_git_dir(&actual_repo_path, fs))?;
+        let actual_dot_git_dir_abs_path =
+            match smol::block_on(resolve_dot_git_file(&dot_git_abs_path, fs)) {
+                Some(common_dir) => common_dir,
+                None => smol::block_on(find_git_dir(&actual_repo_path, fs))?,
+            };
         watcher.add(&actual_repo_path).log_err()?;
 
         let dot_git_worktree_abs_path = if actual_dot_git_dir_abs_path.as_ref() == dot_git_abs_path
@@ -3191,6 +3236,25 @@ async fn find_git_dir(path: &Path, fs: &dyn Fs) -> Option<Arc<Path>> {
     None
 }
 
+/// Resolves the git directory of a linked worktree or a submodule, whose `.git` entry is a file
+/// pointing to its own git directory. Linked worktrees resolve to the git directory they share
+/// with the main worktree, named by their `commondir` file, which the file system events within
+/// their own git directory are attributed to.
+async fn resolve_dot_git_file(dot_git_abs_path: &Path, fs: &dyn Fs) -> Option<Arc<Path>> {
+    let metadata = fs.metadata(dot_git_abs_path).await.ok()??;
+    if metadata.is_dir {
+        return None;
+    }
+    let contents = fs.load(dot_git_abs_path).await.log_err()?;
+    let git_dir = parse_gitdir_file(&contents, dot_git_abs_path)?;
+    let common_dir = match fs.load(&git_dir.join("commondir")).await {
+        Ok(common_dir) => git_dir.join(common_dir.trim()),
+        Err(_) => git_dir,
+    };
+    let common_dir = fs.canonicalize(&common_dir).await.log_err()?;
+    Some(common_dir.into())
+}
+
 async fn build_gitignore(abs_path: &Path, fs: &dyn Fs) -> Result<Gitignore> {
     let contents = fs.load(abs_path).await?;
     let parent = abs_path.parent().unwrap_or_else(|| Path::new("/"));
@@ -2712,6 +2712,74 @@ async fn test_propagate_git_statuses(cx: &mut TestAppContext) {
     );
 }
 
+#[gpui::test]
+async fn test_linked_git_worktree(cx: &mut TestAppContext) {
+    init_test(cx);
+    let fs = FakeFs::new(cx.background_executor.clone());
+    fs.insert_tree(
+        "/code",
+        json!({
+            "main": {
+                ".git": {
+                    "worktrees": {
+                        "review": {
+                            "commondir": "../..\n",
+                        },
+                    },
+                },
+                "a.txt": "",
+            },
+            "review": {
+                ".git": "gitdir: ../main/.git/worktrees/review\n",
+                "a.txt": "",
+                "b.txt": "",
+            },
+        }),
+    )
+    .await;
+    fs.set_status_for_repo_via_git_operation(
+        Path::new("/code/main/.git/worktrees/review"),
+        &[(Path::new("b.txt"), GitFileStatus::Added)],
+    );
+
+    let tree = Worktree::local(
+        Path::new("/code/review"),
+        true,
+        fs.clone(),
+        Default::default(),
+        &mut cx.to_async(),
+    )
+    .await
+    .unwrap();
+    cx.read(|cx| tree.read(cx).as_local().unwrap().scan_complete())
+        .await;
+    cx.executor().run_until_parked();
+
+    // The git directory of the worktree is resolved to the one it shares with the main worktree.
+    tree.read_with(cx, |tree, _| {
+        let tree = tree.as_local().unwrap();
+        let (_, repo) = tree.repo_for_path(Path::new("a.txt")).unwrap();
+        assert_eq!(
+            repo.dot_git_dir_abs_path.as_ref(),
+            Path::new("/code/main/.git")
+        );
+        assert_eq!(
+            repo.dot_git_worktree_abs_path.as_deref(),
+            Some(Path::new("/code/review/.git"))
+        );
+    });
+
+    // The statuses are read from the git directory of the worktree itself.
+    tree.read_with(cx, |tree, _| {
+        let snapshot = tree.snapshot();
+        assert_eq!(
+            snapshot.status_for_file("b.txt"),
+            Some(GitFileStatus::Added)
+        );
+        assert_eq!(snapshot.status_for_file("a.txt"), None);
+    });
+}
+
 #[gpui::test]
 async fn test_private_single_file_worktree(cx: &mut TestAppContext) {
     init_test(cx);
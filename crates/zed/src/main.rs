@@ -441,9 +441,11 @@ fn main() {
         file_finder::init(cx);
         tab_switcher::init(cx);
         outline::init(cx);
+        symbol_hierarchy::init(cx);
         project_symbols::init(cx);
         project_panel::init(Assets, cx);
         git_ui::git_panel::init(cx);
+        debugger_ui::init(cx);
         outline_panel::init(Assets, cx);
         tasks_ui::init(cx);
         snippets_ui::init(cx);
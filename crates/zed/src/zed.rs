@@ -303,6 +303,7 @@ fn initialize_panels(prompt_builder: Arc<PromptBuilder>, cx: &mut ViewContext<Wo
     let release_channel = ReleaseChannel::global(cx);
     let assistant2_feature_flag = cx.wait_for_flag::<feature_flags::Assistant2FeatureFlag>();
     let git_ui_feature_flag = cx.wait_for_flag::<feature_flags::GitUiFeatureFlag>();
+    let debugger_feature_flag = cx.wait_for_flag::<feature_flags::DebuggerFeatureFlag>();
 
     let prompt_builder = prompt_builder.clone();
 
@@ -361,6 +362,21 @@ fn initialize_panels(prompt_builder: Arc<PromptBuilder>, cx: &mut ViewContext<Wo
             }
         })?;
 
+        let debugger_enabled = debugger_feature_flag.await;
+        let debug_panel = if debugger_enabled {
+            Some(
+                debugger_ui::debugger_panel::DebugPanel::load(workspace_handle.clone(), cx.clone())
+                    .await?,
+            )
+        } else {
+            None
+        };
+        workspace_handle.update(&mut cx, |workspace, cx| {
+            if let Some(debug_panel) = debug_panel {
+                workspace.add_panel(debug_panel, cx);
+            }
+        })?;
+
         let is_assistant2_enabled = if cfg!(test) || release_channel != ReleaseChannel::Dev {
             false
         } else {
@@ -3534,6 +3550,7 @@ mod tests {
             editor::init(cx);
             collab_ui::init(&app_state, cx);
             git_ui::init(cx);
+            debugger_ui::init(cx);
             project_panel::init((), cx);
             outline_panel::init((), cx);
             terminal_view::init(cx);
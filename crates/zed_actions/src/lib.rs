@@ -51,6 +51,101 @@ pub mod branches {
     actions!(branches, [OpenRecent]);
 }
 
+pub mod git {
+    use std::path::PathBuf;
+
+    use gpui::{actions, impl_actions};
+    use serde::Deserialize;
+
+    actions!(
+        git,
+        [
+            Fetch,
+            Pull,
+            PullRebase,
+            Push,
+            ForcePush,
+            Stash,
+            StashIncludingUntracked,
+            StashStaged,
+            StashPop,
+            ViewStashes,
+            ViewWorktrees
+        ]
+    );
+
+    #[derive(PartialEq, Clone, Default, Debug, Deserialize)]
+    pub struct ViewHistory {
+        /// The absolute path of the file or directory whose commits to list.
+        /// When unset, lists all the commits of the repository containing the active item.
+        #[serde(default)]
+        pub path: Option<PathBuf>,
+    }
+
+    #[derive(PartialEq, Clone, Debug, Deserialize)]
+    pub struct ViewCommit {
+        pub sha: String,
+        /// The absolute path of any file within the repository containing the commit.
+        pub path: PathBuf,
+    }
+
+    #[derive(PartialEq, Clone, Default, Debug, Deserialize)]
+    pub struct CompareWithRevision {
+        /// The revision to compare the files of the active editor with, such as a branch, a tag,
+        /// a SHA or `HEAD~1`. When unset, a picker is shown to choose it.
+        #[serde(default)]
+        pub revision: Option<String>,
+    }
+
+    #[derive(PartialEq, Clone, Default, Debug, Deserialize)]
+    pub struct CompareProjectWithRevision {
+        /// The revision to compare the project with. When unset, a picker is shown to choose it.
+        #[serde(default)]
+        pub revision: Option<String>,
+    }
+
+    #[derive(PartialEq, Clone, Debug, Deserialize)]
+    pub struct CompareFiles {
+        /// The absolute path of the file shown as the base of the diff.
+        pub old_path: PathBuf,
+        /// The absolute path of the file compared with it.
+        pub new_path: PathBuf,
+    }
+
+    #[derive(PartialEq, Clone, Default, Debug, Deserialize)]
+    pub struct InteractiveRebase {
+        /// The revision to rebase the current branch onto, such as a branch, a tag, a SHA or
+        /// `HEAD~3`. When unset, a picker is shown to choose it.
+        #[serde(default)]
+        pub base: Option<String>,
+    }
+
+    #[derive(PartialEq, Clone, Default, Debug, Deserialize)]
+    pub struct CreateWorktree {
+        /// The revision to check out in the new worktree, such as a branch, a tag or a SHA.
+        /// When unset, a picker is shown to choose it.
+        #[serde(default)]
+        pub revision: Option<String>,
+        /// The name of a branch to create at the revision. When unset, a local branch given as
+        /// the revision is checked out, and any other revision as a detached `HEAD`.
+        #[serde(default)]
+        pub new_branch: Option<String>,
+    }
+
+    impl_actions!(
+        git,
+        [
+            ViewHistory,
+            ViewCommit,
+            CompareWithRevision,
+            CompareProjectWithRevision,
+            CompareFiles,
+            InteractiveRebase,
+            CreateWorktree
+        ]
+    );
+}
+
 pub mod command_palette {
     use gpui::actions;
 